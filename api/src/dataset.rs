@@ -7,18 +7,26 @@
 //! for different kinds of datasets,
 //! as well as a few implementations for them.
 
+use std::borrow::Borrow;
 use std::error::Error;
 
-use crate::graph::adapter::{DatasetGraph, PartialUnionGraph, UnionGraph};
-use crate::quad::{iter_spog, Quad};
+use crate::graph::adapter::{DatasetGraph, GraphSelection, PartialUnionGraph, UnionGraph};
+use crate::quad::{iter_spog, Quad, Spog};
 use crate::source::{IntoSource, QuadSource, StreamResult};
 use crate::term::matcher::{GraphNameMatcher, TermMatcher};
+use crate::term::skolem::Skolemizer;
 use crate::term::{GraphName, SimpleTerm, Term};
 
+use sophia_iri::Iri;
+
 use resiter::{filter::*, filter_map::*, flat_map::*, map::*};
 
 mod _foreign_impl;
 pub mod adapter;
+pub mod copy;
+pub mod declared;
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
 #[cfg(any(test, feature = "test_macro"))]
 #[macro_use]
 pub mod test;
@@ -295,6 +303,45 @@ pub trait Dataset {
             .filter_ok(Term::is_variable)
     }
 
+    /// Build a fallible iterator of the quads of this dataset,
+    /// where every blank node (including in graph names) is replaced by a [skolem IRI](Skolemizer) minted under `base`.
+    ///
+    /// The result is a [`QuadSource`], which can be collected into another dataset.
+    /// See also [`Dataset::deskolemize`].
+    fn skolemize<T: Borrow<str>>(
+        &self,
+        base: &Iri<T>,
+    ) -> impl Iterator<Item = DResult<Self, Spog<SimpleTerm<'static>>>> + '_ {
+        let skolemizer = Skolemizer::new(base);
+        self.quads().map_ok(move |q| {
+            let (spo, g) = q.to_spog();
+            (
+                spo.map(|t| skolemizer.skolemize(t)),
+                g.map(|t| skolemizer.skolemize(t)),
+            )
+        })
+    }
+
+    /// Build a fallible iterator of the quads of this dataset,
+    /// where every [skolem IRI](Skolemizer) minted under `base` (including in graph names)
+    /// is replaced by the corresponding blank node.
+    ///
+    /// The result is a [`QuadSource`], which can be collected into another dataset.
+    /// See also [`Dataset::skolemize`].
+    fn deskolemize<T: Borrow<str>>(
+        &self,
+        base: &Iri<T>,
+    ) -> impl Iterator<Item = DResult<Self, Spog<SimpleTerm<'static>>>> + '_ {
+        let skolemizer = Skolemizer::new(base);
+        self.quads().map_ok(move |q| {
+            let (spo, g) = q.to_spog();
+            (
+                spo.map(|t| skolemizer.deskolemize(t)),
+                g.map(|t| skolemizer.deskolemize(t)),
+            )
+        })
+    }
+
     /// Borrows one of the graphs of this dataset
     fn graph<T>(&self, graph_name: GraphName<T>) -> DatasetGraph<&Self, T>
     where
@@ -319,6 +366,17 @@ pub trait Dataset {
         PartialUnionGraph::new(self, selector)
     }
 
+    /// Borrows a graph that is the union of the graphs of this dataset with the given names.
+    ///
+    /// See [`GraphSelection`] for how it differs from [`partial_union_graph`](Dataset::partial_union_graph).
+    fn graph_selection<T, I>(&self, names: I) -> GraphSelection<&Self, T>
+    where
+        T: Term,
+        I: IntoIterator<Item = GraphName<T>>,
+    {
+        GraphSelection::new(self, names)
+    }
+
     /// Borrows a graph that is the union of all this dataset's graphs (default and named)
     fn union_graph(&self) -> UnionGraph<&Self> {
         UnionGraph::new(self)
@@ -0,0 +1,327 @@
+//! I provide [`copy_dataset`], a utility for migrating data from one [`Dataset`] implementation to another.
+//!
+//! The quads of the source dataset are read in a dedicated thread,
+//! and inserted in batches (see [`CopyConfig::batch_size`]) into the destination dataset,
+//! so that reading and writing overlap.
+//! A callback can be provided to report progress after each batch
+//! (see [`copy_dataset_with_progress`]).
+//!
+//! Long copies can be made resumable by setting a [checkpoint file](CopyConfig::with_checkpoint),
+//! where the number of quads already copied is recorded after each batch.
+//! If the copy is interrupted, calling [`copy_dataset`] again with the same checkpoint file
+//! skips the quads that were already copied.
+//! The checkpoint file is removed once the copy is complete.
+//!
+//! NB: resuming a copy assumes that the source dataset has not been modified in the meantime,
+//! and that it yields its quads in a deterministic order
+//! (which is the case of all the implementations provided by Sophia).
+//!
+//! # Example
+//! ```
+//! # use sophia_api::dataset::{Dataset, MutableDataset};
+//! # use sophia_api::dataset::copy::{copy_dataset, CopyConfig};
+//! # use sophia_api::ns::rdf;
+//! # use sophia_api::quad::Spog;
+//! # use sophia_api::term::SimpleTerm;
+//! # use std::collections::BTreeSet;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut src = BTreeSet::<Spog<SimpleTerm<'static>>>::new();
+//! MutableDataset::insert(&mut src, rdf::Alt, rdf::type_, rdf::Bag, Some(rdf::Seq))?;
+//! MutableDataset::insert(&mut src, rdf::Bag, rdf::type_, rdf::Seq, None as Option<SimpleTerm>)?;
+//!
+//! let mut dst = Vec::<Spog<SimpleTerm<'static>>>::new();
+//! let config = CopyConfig::new().with_batch_size(1);
+//! let summary = copy_dataset(&src, &mut dst, &config)?;
+//! assert_eq!(summary.copied, 2);
+//! assert_eq!(dst.quads().count(), 2);
+//! # Ok(()) }
+//! ```
+use std::convert::Infallible;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use super::*;
+
+/// The batch size used by [`CopyConfig::new`].
+const DEFAULT_BATCH_SIZE: usize = 10_000;
+
+/// [`copy_dataset`] configuration.
+#[derive(Clone, Debug)]
+pub struct CopyConfig {
+    batch_size: usize,
+    checkpoint: Option<PathBuf>,
+}
+
+impl CopyConfig {
+    /// The number of quads inserted at once into the destination dataset.
+    ///
+    /// Progress is reported, and the checkpoint file is updated, after each batch.
+    /// Default is 10,000.
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// The file where the number of quads already copied is recorded, if any.
+    ///
+    /// Default is `None`.
+    pub fn checkpoint(&self) -> Option<&Path> {
+        self.checkpoint.as_deref()
+    }
+
+    /// Build a new default [`CopyConfig`]
+    pub fn new() -> Self {
+        CopyConfig {
+            batch_size: DEFAULT_BATCH_SIZE,
+            checkpoint: None,
+        }
+    }
+
+    /// Change [`batch_size`](CopyConfig::batch_size) (at least 1)
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Change [`checkpoint`](CopyConfig::checkpoint)
+    pub fn with_checkpoint<P: Into<PathBuf>>(mut self, checkpoint: P) -> Self {
+        self.checkpoint = Some(checkpoint.into());
+        self
+    }
+}
+
+impl Default for CopyConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The progress of [`copy_dataset`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CopyProgress {
+    /// The number of quads that had already been copied by a previous run,
+    /// according to the checkpoint file
+    pub resumed_from: usize,
+    /// The total number of quads copied so far (including [`resumed_from`](CopyProgress::resumed_from))
+    pub copied: usize,
+}
+
+/// Error raised by [`copy_dataset`].
+#[derive(Debug, thiserror::Error)]
+pub enum CopyError {
+    /// Error while reading the source dataset
+    #[error("Error in source dataset: {0}")]
+    Source(Box<dyn Error + Send + Sync + 'static>),
+    /// Error while inserting into the destination dataset
+    #[error("Error in destination dataset: {0}")]
+    Destination(Box<dyn Error + Send + Sync + 'static>),
+    /// Error while reading or writing the checkpoint file
+    #[error("Error with checkpoint file: {0}")]
+    Checkpoint(#[from] io::Error),
+}
+
+/// Copy all the quads of `src` into `dst`.
+///
+/// Return the final [`CopyProgress`].
+/// If `config` has a [checkpoint file](CopyConfig::with_checkpoint) left by an interrupted copy,
+/// the quads recorded in it as already copied are skipped.
+pub fn copy_dataset<S, D>(
+    src: &S,
+    dst: &mut D,
+    config: &CopyConfig,
+) -> Result<CopyProgress, CopyError>
+where
+    S: Dataset + Sync,
+    D: MutableDataset,
+{
+    copy_dataset_with_progress(src, dst, config, |_| ())
+}
+
+/// Same as [`copy_dataset`],
+/// but calls `progress` each time a batch of quads has been inserted into `dst`.
+pub fn copy_dataset_with_progress<S, D, F>(
+    src: &S,
+    dst: &mut D,
+    config: &CopyConfig,
+    mut progress: F,
+) -> Result<CopyProgress, CopyError>
+where
+    S: Dataset + Sync,
+    D: MutableDataset,
+    F: FnMut(&CopyProgress),
+{
+    let resumed_from = match config.checkpoint() {
+        Some(path) => read_checkpoint(path)?,
+        None => 0,
+    };
+    let mut state = CopyProgress {
+        resumed_from,
+        copied: resumed_from,
+    };
+    let batch_size = config.batch_size;
+    std::thread::scope(|scope| -> Result<(), CopyError> {
+        // a small bound is enough to keep the reader busy while the writer inserts a batch
+        let (tx, rx) = mpsc::sync_channel(2);
+        scope.spawn(move || {
+            let mut batch = Vec::with_capacity(batch_size);
+            for res in src.quads().skip(resumed_from) {
+                match res {
+                    Ok(q) => {
+                        let (spo, g) = q.to_spog();
+                        batch.push((spo.map(Term::into_term), g.map(Term::into_term)));
+                        if batch.len() == batch_size {
+                            let full =
+                                std::mem::replace(&mut batch, Vec::with_capacity(batch_size));
+                            if tx.send(Ok(full)).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        let _ = tx.send(Err(err));
+                        return;
+                    }
+                }
+            }
+            if !batch.is_empty() {
+                let _ = tx.send(Ok(batch));
+            }
+        });
+        // NB: returning early drops rx, which stops the reader thread
+        for batch in rx {
+            let batch: Vec<Spog<SimpleTerm<'static>>> =
+                batch.map_err(|err| CopyError::Source(Box::new(err)))?;
+            let len = batch.len();
+            dst.insert_all(batch.into_iter().map(Ok::<_, Infallible>))
+                .map_err(|err| CopyError::Destination(Box::new(err.unwrap_sink_error())))?;
+            state.copied += len;
+            if let Some(path) = config.checkpoint() {
+                write_checkpoint(path, state.copied)?;
+            }
+            progress(&state);
+        }
+        Ok(())
+    })?;
+    if let Some(path) = config.checkpoint() {
+        if let Err(err) = fs::remove_file(path) {
+            if err.kind() != io::ErrorKind::NotFound {
+                return Err(err.into());
+            }
+        }
+    }
+    Ok(state)
+}
+
+/// Read the number of quads recorded in the checkpoint file (0 if it does not exist).
+fn read_checkpoint(path: &Path) -> Result<usize, CopyError> {
+    match fs::read_to_string(path) {
+        Ok(txt) => txt.trim().parse().map_err(|_| {
+            CopyError::Checkpoint(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid checkpoint file {}", path.display()),
+            ))
+        }),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(0),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Record `copied` in the checkpoint file.
+///
+/// The file is written atomically (by renaming a temporary file),
+/// so that an interruption never leaves it in an inconsistent state.
+fn write_checkpoint(path: &Path, copied: usize) -> io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, copied.to_string())?;
+    fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ns::rdf;
+    use std::collections::BTreeSet;
+
+    type MyDataset = BTreeSet<Spog<SimpleTerm<'static>>>;
+
+    fn source() -> MyDataset {
+        let mut d = MyDataset::new();
+        for s in [rdf::Alt, rdf::Bag, rdf::Seq, rdf::List, rdf::Property] {
+            let none = None as Option<SimpleTerm>;
+            MutableDataset::insert(&mut d, s, rdf::type_, rdf::Statement, none).unwrap();
+            MutableDataset::insert(&mut d, s, rdf::type_, rdf::Statement, Some(rdf::nil)).unwrap();
+        }
+        d
+    }
+
+    fn checkpoint_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("sophia_copy_{}_{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn copy_all() -> Result<(), CopyError> {
+        let src = source();
+        let mut dst = MyDataset::new();
+        let mut reported = vec![];
+        let config = CopyConfig::new().with_batch_size(3);
+        let summary =
+            copy_dataset_with_progress(&src, &mut dst, &config, |p| reported.push(p.copied))?;
+        assert_eq!(
+            summary,
+            CopyProgress {
+                resumed_from: 0,
+                copied: 10
+            }
+        );
+        assert_eq!(reported, vec![3, 6, 9, 10]);
+        assert_eq!(dst, src);
+        Ok(())
+    }
+
+    #[test]
+    fn copy_empty() -> Result<(), CopyError> {
+        let mut dst = MyDataset::new();
+        let summary = copy_dataset(&MyDataset::new(), &mut dst, &CopyConfig::new())?;
+        assert_eq!(summary.copied, 0);
+        assert!(dst.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn resume_from_checkpoint() -> Result<(), CopyError> {
+        let path = checkpoint_path("resume");
+        let src = source();
+        // simulate a previous run, interrupted after copying 4 quads
+        let mut dst: MyDataset = src.iter().take(4).cloned().collect();
+        fs::write(&path, "4")?;
+
+        let config = CopyConfig::new().with_batch_size(4).with_checkpoint(&path);
+        let mut reported = vec![];
+        let summary =
+            copy_dataset_with_progress(&src, &mut dst, &config, |p| reported.push(p.copied))?;
+        assert_eq!(
+            summary,
+            CopyProgress {
+                resumed_from: 4,
+                copied: 10
+            }
+        );
+        assert_eq!(reported, vec![8, 10]);
+        assert_eq!(dst, src);
+        assert!(!path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_checkpoint() -> Result<(), CopyError> {
+        let path = checkpoint_path("invalid");
+        fs::write(&path, "not a number")?;
+        let config = CopyConfig::new().with_checkpoint(&path);
+        let res = copy_dataset(&source(), &mut MyDataset::new(), &config);
+        fs::remove_file(&path)?;
+        assert!(matches!(res, Err(CopyError::Checkpoint(_))));
+        Ok(())
+    }
+}
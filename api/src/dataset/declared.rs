@@ -0,0 +1,222 @@
+//! I provide [`DeclaredGraphsDataset`], a [`Dataset`] wrapper keeping track of declared graph names.
+//!
+//! In the abstract syntax of RDF, a named graph exists only through its quads,
+//! so an empty named graph can not be represented.
+//! Some concrete syntaxes (such as TriG, with `GRAPH <g> {}`) can however declare empty graphs,
+//! and some applications give a meaning to such declarations.
+//! [`DeclaredGraphsDataset`] allows to keep them,
+//! so that they can be serialized back.
+//!
+//! # Example
+//! ```
+//! # use sophia_api::dataset::{Dataset, MutableDataset};
+//! # use sophia_api::dataset::declared::DeclaredGraphsDataset;
+//! # use sophia_api::ns::rdf;
+//! # use sophia_api::quad::Spog;
+//! # use sophia_api::term::SimpleTerm;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut d = DeclaredGraphsDataset::new(Vec::<Spog<SimpleTerm<'static>>>::new());
+//! d.declare_graph(rdf::Bag);
+//! d.declare_graph(rdf::Seq);
+//! d.insert(rdf::Alt, rdf::type_, rdf::Alt, Some(rdf::Seq))?;
+//! assert_eq!(d.declared_graphs().count(), 2);
+//! assert!(d.empty_graphs()? == vec![&rdf::Bag]);
+//! # Ok(()) }
+//! ```
+use super::*;
+use crate::source::{QuadSource, StreamResult};
+use crate::term::matcher::Any;
+use std::collections::BTreeSet;
+
+/// I wrap a [`Dataset`], keeping track of declared graph names,
+/// including those of empty graphs.
+///
+/// Quads are read from and written to the wrapped dataset unchanged;
+/// graph declarations are only affected by [`declare_graph`](Self::declare_graph)
+/// and [`undeclare_graph`](Self::undeclare_graph),
+/// so a graph stays declared after all its quads are removed.
+#[derive(Clone, Debug, Default)]
+pub struct DeclaredGraphsDataset<D> {
+    dataset: D,
+    declared: BTreeSet<SimpleTerm<'static>>,
+}
+
+impl<D: Dataset> DeclaredGraphsDataset<D> {
+    /// Wrap `dataset`, with no declared graph.
+    pub fn new(dataset: D) -> Self {
+        DeclaredGraphsDataset {
+            dataset,
+            declared: BTreeSet::new(),
+        }
+    }
+
+    /// The wrapped dataset.
+    pub fn dataset(&self) -> &D {
+        &self.dataset
+    }
+
+    /// Unwrap the inner dataset and the declared graph names.
+    pub fn into_inner(self) -> (D, BTreeSet<SimpleTerm<'static>>) {
+        (self.dataset, self.declared)
+    }
+
+    /// Declare a graph name.
+    ///
+    /// Return `true` if it was not already declared.
+    pub fn declare_graph<T: Term>(&mut self, graph_name: T) -> bool {
+        self.declared.insert(graph_name.into_term())
+    }
+
+    /// Remove the declaration of a graph name
+    /// (this does not remove the quads of that graph, if any).
+    ///
+    /// Return `true` if it was declared.
+    pub fn undeclare_graph<T: Term>(&mut self, graph_name: T) -> bool {
+        let declared: &BTreeSet<SimpleTerm<'_>> = &self.declared;
+        if !declared.contains(&graph_name.as_simple()) {
+            return false;
+        }
+        self.declared.remove(&graph_name.into_term::<SimpleTerm>())
+    }
+
+    /// Iter over the declared graph names, whether their graph is empty or not.
+    pub fn declared_graphs(&self) -> impl Iterator<Item = &SimpleTerm<'static>> + '_ {
+        self.declared.iter()
+    }
+
+    /// The declared graph names whose graph is empty in the wrapped dataset.
+    pub fn empty_graphs(&self) -> DResult<D, Vec<&SimpleTerm<'static>>> {
+        let mut ret = vec![];
+        for g in &self.declared {
+            if self
+                .dataset
+                .quads_matching(Any, Any, Any, [Some(g)])
+                .next()
+                .transpose()?
+                .is_none()
+            {
+                ret.push(g);
+            }
+        }
+        Ok(ret)
+    }
+}
+
+impl<D: Dataset> Dataset for DeclaredGraphsDataset<D> {
+    type Quad<'x> = D::Quad<'x> where Self: 'x;
+    type Error = D::Error;
+
+    fn quads(&self) -> impl Iterator<Item = DResult<Self, Self::Quad<'_>>> + '_ {
+        self.dataset.quads()
+    }
+
+    fn quads_matching<'s, S, P, O, G>(
+        &'s self,
+        sm: S,
+        pm: P,
+        om: O,
+        gm: G,
+    ) -> impl Iterator<Item = DResult<Self, Self::Quad<'s>>> + 's
+    where
+        S: TermMatcher + 's,
+        P: TermMatcher + 's,
+        O: TermMatcher + 's,
+        G: GraphNameMatcher + 's,
+    {
+        self.dataset.quads_matching(sm, pm, om, gm)
+    }
+
+    fn contains<TS, TP, TO, TG>(&self, s: TS, p: TP, o: TO, g: GraphName<TG>) -> DResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        self.dataset.contains(s, p, o, g)
+    }
+}
+
+impl<D: MutableDataset> MutableDataset for DeclaredGraphsDataset<D> {
+    type MutationError = D::MutationError;
+
+    fn insert<TS, TP, TO, TG>(
+        &mut self,
+        s: TS,
+        p: TP,
+        o: TO,
+        g: GraphName<TG>,
+    ) -> MdResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        self.dataset.insert(s, p, o, g)
+    }
+
+    fn remove<TS, TP, TO, TG>(
+        &mut self,
+        s: TS,
+        p: TP,
+        o: TO,
+        g: GraphName<TG>,
+    ) -> MdResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        self.dataset.remove(s, p, o, g)
+    }
+
+    fn insert_all<TS: QuadSource>(
+        &mut self,
+        src: TS,
+    ) -> StreamResult<usize, TS::Error, <Self as MutableDataset>::MutationError> {
+        self.dataset.insert_all(src)
+    }
+
+    fn remove_all<TS: QuadSource>(
+        &mut self,
+        src: TS,
+    ) -> StreamResult<usize, TS::Error, <Self as MutableDataset>::MutationError> {
+        self.dataset.remove_all(src)
+    }
+}
+
+impl<D: SetDataset> SetDataset for DeclaredGraphsDataset<D> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ns::rdf;
+    use crate::quad::Spog;
+
+    type MyDataset = Vec<Spog<SimpleTerm<'static>>>;
+
+    #[test]
+    fn declared_and_empty() -> Result<(), Box<dyn std::error::Error>> {
+        let mut d = DeclaredGraphsDataset::new(MyDataset::new());
+        assert!(d.declare_graph(rdf::Bag));
+        assert!(!d.declare_graph(rdf::Bag));
+        assert!(d.declare_graph(rdf::Seq));
+        assert_eq!(d.empty_graphs()?.len(), 2);
+
+        d.insert(rdf::Alt, rdf::type_, rdf::Alt, Some(rdf::Bag))?;
+        d.insert(rdf::Alt, rdf::type_, rdf::Alt, Some(rdf::Alt))?;
+        assert_eq!(d.quads().count(), 2);
+        assert_eq!(d.declared_graphs().count(), 2);
+        assert!(d.empty_graphs()? == vec![&rdf::Seq]);
+
+        assert!(d.undeclare_graph(rdf::Seq));
+        assert!(!d.undeclare_graph(rdf::Seq));
+        assert!(d.empty_graphs()?.is_empty());
+        let (inner, declared) = d.into_inner();
+        assert_eq!(inner.len(), 2);
+        assert_eq!(declared.len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,471 @@
+//! I provide an instrumentation wrapper reporting the operations performed on a [`Dataset`].
+//!
+//! [`ObservedDataset`] reports every mutation of the wrapped dataset
+//! as an [`OperationEvent`] (operation, graph name, quad count, duration)
+//! to an [`EventExporter`].
+//! Closures can be used as exporters;
+//! [`OtlpJsonExporter`] writes events as [OpenTelemetry log records][otlp]
+//! (one JSON object per line), ready to be ingested by observability pipelines.
+//!
+//! Only mutations are reported,
+//! as read operations are lazy and their duration depends on how much of the result is consumed.
+//!
+//! # Example
+//! ```
+//! # use sophia_api::dataset::{Dataset, MutableDataset};
+//! # use sophia_api::dataset::telemetry::{ObservedDataset, OperationEvent};
+//! # use sophia_api::ns::rdf;
+//! # use sophia_api::quad::Spog;
+//! # use sophia_api::term::{SimpleTerm, Term};
+//! # use std::collections::BTreeSet;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let events = std::cell::RefCell::new(vec![]);
+//! let mut d = ObservedDataset::new(
+//!     BTreeSet::<Spog<SimpleTerm<'static>>>::new(),
+//!     |e: &OperationEvent| events.borrow_mut().push(e.clone()),
+//! );
+//! d.insert(rdf::Alt, rdf::type_, rdf::Bag, Some(rdf::Seq))?;
+//! let events = events.take();
+//! assert_eq!(events.len(), 1);
+//! assert_eq!(events[0].graph_name.as_deref(), Some(rdf::Seq.iri().unwrap().as_str()));
+//! # Ok(()) }
+//! ```
+//!
+//! [otlp]: https://opentelemetry.io/docs/specs/otel/logs/data-model/
+use std::io;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use super::*;
+use crate::source::{QuadSource, StreamResult};
+use crate::term::matcher::{GraphNameMatcher, TermMatcher};
+use crate::term::{GraphName, Term, TermKind};
+
+/// The kind of operation reported by an [`OperationEvent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// [`MutableDataset::insert`]
+    Insert,
+    /// [`MutableDataset::remove`]
+    Remove,
+    /// [`MutableDataset::insert_all`]
+    InsertAll,
+    /// [`MutableDataset::remove_all`]
+    RemoveAll,
+    /// [`MutableDataset::remove_matching`]
+    RemoveMatching,
+}
+
+impl Operation {
+    /// The name of this operation, in snake case.
+    pub fn name(self) -> &'static str {
+        match self {
+            Operation::Insert => "insert",
+            Operation::Remove => "remove",
+            Operation::InsertAll => "insert_all",
+            Operation::RemoveAll => "remove_all",
+            Operation::RemoveMatching => "remove_matching",
+        }
+    }
+}
+
+/// An operation performed on an [`ObservedDataset`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OperationEvent {
+    /// The operation
+    pub operation: Operation,
+    /// The graph name concerned by the operation, if it concerns a single named graph
+    /// (as an IRI, or a blank node label prefixed with `_:`)
+    pub graph_name: Option<String>,
+    /// The number of quads actually inserted or removed
+    pub quads: usize,
+    /// When the operation started
+    pub start: SystemTime,
+    /// How long the operation took
+    pub duration: Duration,
+    /// Whether the operation failed
+    pub failed: bool,
+}
+
+/// A receiver of [`OperationEvent`]s.
+///
+/// This trait is implemented by closures accepting a `&OperationEvent`.
+pub trait EventExporter {
+    /// Export the given event.
+    fn export(&self, event: &OperationEvent);
+}
+
+impl<F: Fn(&OperationEvent)> EventExporter for F {
+    fn export(&self, event: &OperationEvent) {
+        self(event)
+    }
+}
+
+/// An [`EventExporter`] writing events as [OTLP/JSON log records][otlp], one per line.
+///
+/// Attributes follow the OpenTelemetry naming conventions
+/// (`db.operation.name`, `rdf.graph_name`, `rdf.quad_count`, `duration_ns`).
+/// IO errors are ignored, as telemetry must not disturb the observed operations.
+///
+/// [otlp]: https://opentelemetry.io/docs/specs/otlp/#json-protobuf-encoding
+#[derive(Debug)]
+pub struct OtlpJsonExporter<W> {
+    write: Mutex<W>,
+}
+
+impl<W: io::Write> OtlpJsonExporter<W> {
+    /// Build an exporter writing into `write`.
+    pub fn new(write: W) -> Self {
+        OtlpJsonExporter {
+            write: Mutex::new(write),
+        }
+    }
+
+    /// Unwrap the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.write
+            .into_inner()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<W: io::Write> EventExporter for OtlpJsonExporter<W> {
+    fn export(&self, event: &OperationEvent) {
+        let time = event
+            .start
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let (severity_number, severity_text) = if event.failed {
+            (17, "ERROR")
+        } else {
+            (9, "INFO")
+        };
+        let mut attributes = format!(
+            r#"{{"key":"db.operation.name","value":{{"stringValue":"{}"}}}}"#,
+            event.operation.name()
+        );
+        if let Some(g) = &event.graph_name {
+            attributes.push_str(r#",{"key":"rdf.graph_name","value":{"stringValue":""#);
+            json_escape(&mut attributes, g);
+            attributes.push_str(r#""}}"#);
+        }
+        let line = format!(
+            concat!(
+                r#"{{"timeUnixNano":"{}","severityNumber":{},"severityText":"{}","#,
+                r#""body":{{"stringValue":"sophia.dataset.{}"}},"attributes":[{},"#,
+                r#"{{"key":"rdf.quad_count","value":{{"intValue":"{}"}}}},"#,
+                r#"{{"key":"duration_ns","value":{{"intValue":"{}"}}}}]}}"#,
+            ),
+            time,
+            severity_number,
+            severity_text,
+            event.operation.name(),
+            attributes,
+            event.quads,
+            event.duration.as_nanos(),
+        );
+        let mut write = self.write.lock().unwrap_or_else(|err| err.into_inner());
+        let _ = writeln!(write, "{line}").and_then(|_| write.flush());
+    }
+}
+
+fn json_escape(buf: &mut String, txt: &str) {
+    for c in txt.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            c if c < ' ' => buf.push_str(&format!("\\u{:04x}", c as u32)),
+            c => buf.push(c),
+        }
+    }
+}
+
+/// I wrap a [`Dataset`], reporting all its mutations to an [`EventExporter`].
+///
+/// Each call to a method of [`MutableDataset`] produces exactly one [`OperationEvent`],
+/// including when it fails; read operations are passed through without being reported.
+#[derive(Clone, Debug)]
+pub struct ObservedDataset<D, E> {
+    dataset: D,
+    exporter: E,
+}
+
+impl<D, E> ObservedDataset<D, E>
+where
+    D: Dataset,
+    E: EventExporter,
+{
+    /// Wrap `dataset`, reporting its mutations to `exporter`.
+    pub fn new(dataset: D, exporter: E) -> Self {
+        ObservedDataset { dataset, exporter }
+    }
+
+    /// The wrapped dataset.
+    pub fn dataset(&self) -> &D {
+        &self.dataset
+    }
+
+    /// The exporter of this dataset.
+    pub fn exporter(&self) -> &E {
+        &self.exporter
+    }
+
+    /// Unwrap the inner dataset and exporter.
+    pub fn into_inner(self) -> (D, E) {
+        (self.dataset, self.exporter)
+    }
+
+    fn observe<T, Err, F, C>(
+        &mut self,
+        operation: Operation,
+        graph_name: Option<String>,
+        f: F,
+        count: C,
+    ) -> Result<T, Err>
+    where
+        F: FnOnce(&mut D) -> Result<T, Err>,
+        C: FnOnce(&T) -> usize,
+    {
+        let start = SystemTime::now();
+        let timer = Instant::now();
+        let res = f(&mut self.dataset);
+        let duration = timer.elapsed();
+        self.exporter.export(&OperationEvent {
+            operation,
+            graph_name,
+            quads: res.as_ref().map(count).unwrap_or(0),
+            start,
+            duration,
+            failed: res.is_err(),
+        });
+        res
+    }
+}
+
+fn graph_name_label<T: Term + ?Sized>(g: Option<&T>) -> Option<String> {
+    let g = g?;
+    match g.kind() {
+        TermKind::Iri => Some(g.iri().unwrap().as_str().to_string()),
+        TermKind::BlankNode => Some(format!("_:{}", g.bnode_id().unwrap().as_str())),
+        _ => None,
+    }
+}
+
+impl<D, E> Dataset for ObservedDataset<D, E>
+where
+    D: Dataset,
+{
+    type Quad<'x> = D::Quad<'x> where Self: 'x;
+    type Error = D::Error;
+
+    fn quads(&self) -> impl Iterator<Item = DResult<Self, Self::Quad<'_>>> + '_ {
+        self.dataset.quads()
+    }
+
+    fn quads_matching<'s, S, P, O, G>(
+        &'s self,
+        sm: S,
+        pm: P,
+        om: O,
+        gm: G,
+    ) -> impl Iterator<Item = DResult<Self, Self::Quad<'s>>> + 's
+    where
+        S: TermMatcher + 's,
+        P: TermMatcher + 's,
+        O: TermMatcher + 's,
+        G: GraphNameMatcher + 's,
+    {
+        self.dataset.quads_matching(sm, pm, om, gm)
+    }
+
+    fn contains<TS, TP, TO, TG>(&self, s: TS, p: TP, o: TO, g: GraphName<TG>) -> DResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        self.dataset.contains(s, p, o, g)
+    }
+}
+
+impl<D, E> MutableDataset for ObservedDataset<D, E>
+where
+    D: MutableDataset,
+    E: EventExporter,
+{
+    type MutationError = D::MutationError;
+
+    fn insert<TS, TP, TO, TG>(
+        &mut self,
+        s: TS,
+        p: TP,
+        o: TO,
+        g: GraphName<TG>,
+    ) -> MdResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        let label = graph_name_label(g.as_ref());
+        self.observe(
+            Operation::Insert,
+            label,
+            |d| d.insert(s, p, o, g),
+            |b| *b as usize,
+        )
+    }
+
+    fn remove<TS, TP, TO, TG>(
+        &mut self,
+        s: TS,
+        p: TP,
+        o: TO,
+        g: GraphName<TG>,
+    ) -> MdResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        let label = graph_name_label(g.as_ref());
+        self.observe(
+            Operation::Remove,
+            label,
+            |d| d.remove(s, p, o, g),
+            |b| *b as usize,
+        )
+    }
+
+    fn insert_all<TS: QuadSource>(
+        &mut self,
+        src: TS,
+    ) -> StreamResult<usize, TS::Error, <Self as MutableDataset>::MutationError> {
+        self.observe(Operation::InsertAll, None, |d| d.insert_all(src), |n| *n)
+    }
+
+    fn remove_all<TS: QuadSource>(
+        &mut self,
+        src: TS,
+    ) -> StreamResult<usize, TS::Error, <Self as MutableDataset>::MutationError> {
+        self.observe(Operation::RemoveAll, None, |d| d.remove_all(src), |n| *n)
+    }
+
+    fn remove_matching<S, P, O, G>(
+        &mut self,
+        ms: S,
+        mp: P,
+        mo: O,
+        mg: G,
+    ) -> Result<usize, Self::MutationError>
+    where
+        S: TermMatcher,
+        P: TermMatcher,
+        O: TermMatcher,
+        G: GraphNameMatcher,
+        Self::MutationError: From<Self::Error>,
+    {
+        let label = mg.constant().and_then(graph_name_label);
+        self.observe(
+            Operation::RemoveMatching,
+            label,
+            |d| d.remove_matching(ms, mp, mo, mg),
+            |n| *n,
+        )
+    }
+}
+
+impl<D, E> SetDataset for ObservedDataset<D, E>
+where
+    D: SetDataset,
+    E: EventExporter,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ns::{rdf, NsTerm};
+    use crate::quad::Spog;
+    use crate::source::IntoSource;
+    use crate::term::matcher::Any;
+    use crate::term::SimpleTerm;
+    use std::cell::RefCell;
+    use std::collections::BTreeSet;
+
+    type MyDataset = BTreeSet<Spog<SimpleTerm<'static>>>;
+
+    #[test]
+    fn events() -> Result<(), Box<dyn std::error::Error>> {
+        let events = RefCell::new(vec![]);
+        let mut d = ObservedDataset::new(MyDataset::new(), |e: &OperationEvent| {
+            events
+                .borrow_mut()
+                .push((e.operation, e.graph_name.clone(), e.quads))
+        });
+        d.insert(
+            rdf::Alt,
+            rdf::type_,
+            rdf::Bag,
+            None as GraphName<SimpleTerm>,
+        )?;
+        d.insert(
+            rdf::Alt,
+            rdf::type_,
+            rdf::Bag,
+            None as GraphName<SimpleTerm>,
+        )?;
+        d.insert(rdf::Alt, rdf::value, "foo", Some(rdf::Seq))?;
+        d.remove(rdf::Alt, rdf::value, "foo", Some(rdf::Seq))?;
+        let quads = vec![([rdf::Seq, rdf::type_, rdf::List], None::<NsTerm>)];
+        d.insert_all(quads.into_iter().into_source())?;
+        d.remove_matching([rdf::Alt, rdf::Seq], Any, Any, Any)?;
+        assert_eq!(d.quads().count(), 0);
+
+        let seq = Some(rdf::Seq.iri().unwrap().as_str().to_string());
+        assert_eq!(
+            events.take(),
+            vec![
+                (Operation::Insert, None, 1),
+                (Operation::Insert, None, 0),
+                (Operation::Insert, seq.clone(), 1),
+                (Operation::Remove, seq, 1),
+                (Operation::InsertAll, None, 1),
+                (Operation::RemoveMatching, None, 2),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn otlp_json() -> Result<(), Box<dyn std::error::Error>> {
+        let mut d = ObservedDataset::new(MyDataset::new(), OtlpJsonExporter::new(vec![]));
+        d.insert(rdf::Alt, rdf::type_, rdf::Bag, Some(rdf::Seq))?;
+        let (_, exporter) = d.into_inner();
+        let out = String::from_utf8(exporter.into_inner())?;
+        assert_eq!(out.lines().count(), 1);
+        assert!(out.starts_with(r#"{"timeUnixNano":""#));
+        assert!(
+            out.contains(r#""severityText":"INFO","body":{"stringValue":"sophia.dataset.insert"}"#)
+        );
+        assert!(out.contains(r#"{"key":"db.operation.name","value":{"stringValue":"insert"}}"#));
+        assert!(out.contains(&format!(
+            r#"{{"key":"rdf.graph_name","value":{{"stringValue":"{}"}}}}"#,
+            rdf::Seq.iri().unwrap().as_str()
+        )));
+        assert!(out.contains(r#"{"key":"rdf.quad_count","value":{"intValue":"1"}}"#));
+        assert!(out.ends_with("}]}\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn escape() {
+        let mut buf = String::new();
+        json_escape(&mut buf, "a\"b\\c\nd");
+        assert_eq!(buf, r#"a\"b\\c\u000ad"#);
+    }
+}
@@ -5,7 +5,6 @@ use std::fmt::Debug;
 use super::*;
 use crate::graph::test::*;
 use crate::ns::*;
-use crate::quad::*;
 use crate::source::*;
 use lazy_static::lazy_static;
 
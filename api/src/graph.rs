@@ -5,20 +5,30 @@
 //! for different kinds of graph,
 //! as well as a few implementations for them.
 
+use std::borrow::Borrow;
 use std::error::Error;
 
 use crate::dataset::adapter::GraphAsDataset;
 use crate::source::{IntoSource, StreamResult, TripleSource};
-use crate::term::{matcher::TermMatcher, SimpleTerm, Term};
+use crate::term::{matcher::TermMatcher, skolem::Skolemizer, SimpleTerm, Term};
 use crate::triple::Triple;
 
+use sophia_iri::Iri;
+
 use resiter::{filter::*, flat_map::*, map::*};
 
 mod _foreign_impl;
 pub mod adapter;
+pub mod algo;
+pub mod bloom;
+pub mod budget;
+pub mod counting;
+pub mod path;
+pub mod summary;
 #[cfg(any(test, feature = "test_macro"))]
 #[macro_use]
 pub mod test;
+pub mod walk;
 
 /// Type alias for results produced by a graph.
 pub type GResult<G, T> = Result<T, <G as Graph>::Error>;
@@ -271,6 +281,34 @@ pub trait Graph {
             .filter_ok(Term::is_variable)
     }
 
+    /// Build a fallible iterator of the triples of this graph,
+    /// where every blank node is replaced by a [skolem IRI](Skolemizer) minted under `base`.
+    ///
+    /// The result is a [`TripleSource`], which can be collected into another graph.
+    /// See also [`Graph::deskolemize`].
+    fn skolemize<T: Borrow<str>>(
+        &self,
+        base: &Iri<T>,
+    ) -> impl Iterator<Item = GResult<Self, [SimpleTerm<'static>; 3]>> + '_ {
+        let skolemizer = Skolemizer::new(base);
+        self.triples()
+            .map_ok(move |t| t.to_spo().map(|t| skolemizer.skolemize(t)))
+    }
+
+    /// Build a fallible iterator of the triples of this graph,
+    /// where every [skolem IRI](Skolemizer) minted under `base` is replaced by the corresponding blank node.
+    ///
+    /// The result is a [`TripleSource`], which can be collected into another graph.
+    /// See also [`Graph::skolemize`].
+    fn deskolemize<T: Borrow<str>>(
+        &self,
+        base: &Iri<T>,
+    ) -> impl Iterator<Item = GResult<Self, [SimpleTerm<'static>; 3]>> + '_ {
+        let skolemizer = Skolemizer::new(base);
+        self.triples()
+            .map_ok(move |t| t.to_spo().map(|t| skolemizer.deskolemize(t)))
+    }
+
     /// [`Dataset`](crate::dataset::Dataset) adapter borrowing this graph
     fn as_dataset(&self) -> GraphAsDataset<&Self> {
         GraphAsDataset::new(self)
@@ -3,9 +3,12 @@ use super::*;
 use crate::dataset::{DTerm, Dataset, MutableDataset, SetDataset};
 use crate::quad::Quad;
 use crate::term::{
+    graph_name_eq,
     matcher::{Any, GraphNameMatcher},
-    GraphName,
+    unicode::{UnicodePolicy, UnicodePolicyError},
+    GraphName, LanguageTag,
 };
+use std::rc::Rc;
 
 /// I wrap a [`Dataset`] as a [`Graph`]
 /// corresponding to the union of all graphs (default and named)
@@ -145,6 +148,116 @@ impl<D: Dataset, M: GraphNameMatcher + Copy> Graph for PartialUnionGraph<D, M> {
 
 //
 
+/// I wrap a [`Dataset`] as a [`Graph`]
+/// corresponding to the union of a set of its graphs,
+/// given by their names (e.g. the graphs visible to a given user, computed at run time).
+///
+/// Contrarily to [`PartialUnionGraph`],
+/// which passes its [`GraphNameMatcher`] to [`Dataset::quads_matching`] as a filter,
+/// this adapter queries each selected graph in turn with a constant graph name,
+/// so that datasets indexed by graph name (e.g. with a GSPO index)
+/// only visit the quads of the selected graphs.
+///
+/// NB: a triple present in several selected graphs is yielded once per graph.
+#[derive(Clone, Debug)]
+pub struct GraphSelection<D: Dataset, T: Term> {
+    d: D,
+    names: Vec<GraphName<T>>,
+}
+
+impl<D: Dataset, T: Term> GraphSelection<D, T> {
+    /// Wrap the given dataset as a single [`Graph`],
+    /// corresponding to the union of the graphs with the given names
+    /// (duplicate names are ignored).
+    pub fn new<I>(d: D, names: I) -> Self
+    where
+        I: IntoIterator<Item = GraphName<T>>,
+    {
+        let mut selected: Vec<GraphName<T>> = vec![];
+        for name in names {
+            let new = name.as_ref().map(Term::borrow_term);
+            if !selected
+                .iter()
+                .any(|old| graph_name_eq(old.as_ref().map(Term::borrow_term), new))
+            {
+                selected.push(name);
+            }
+        }
+        GraphSelection { d, names: selected }
+    }
+
+    /// The names of the selected graphs.
+    pub fn graph_names(&self) -> &[GraphName<T>] {
+        &self.names
+    }
+
+    /// Unwrap the inner [`Dataset`] and graph names.
+    pub fn unwrap(self) -> (D, Vec<GraphName<T>>) {
+        (self.d, self.names)
+    }
+}
+
+impl<D: Dataset, T: Term> Graph for GraphSelection<D, T> {
+    type Triple<'x> = [DTerm<'x, D>; 3] where Self: 'x;
+    type Error = D::Error;
+
+    fn triples(&self) -> impl Iterator<Item = GResult<Self, Self::Triple<'_>>> + '_ {
+        self.names.iter().flat_map(move |gn| {
+            self.d
+                .quads_matching(Any, Any, Any, [gn.as_ref().map(Term::borrow_term)])
+                // NB: for some reason, .map_ok(...) below does not compile since 1.66 nightly
+                .map(|r| r.map(Quad::into_triple))
+        })
+    }
+
+    fn triples_matching<'s, S, P, O>(
+        &'s self,
+        sm: S,
+        pm: P,
+        om: O,
+    ) -> impl Iterator<Item = GResult<Self, Self::Triple<'s>>> + 's
+    where
+        S: TermMatcher + 's,
+        P: TermMatcher + 's,
+        O: TermMatcher + 's,
+    {
+        // the matchers are shared by the iterators over each selected graph
+        let (sm, pm, om) = (Rc::new(sm), Rc::new(pm), Rc::new(om));
+        self.names.iter().flat_map(move |gn| {
+            self.d
+                .quads_matching(
+                    SharedMatcher(sm.clone()),
+                    SharedMatcher(pm.clone()),
+                    SharedMatcher(om.clone()),
+                    [gn.as_ref().map(Term::borrow_term)],
+                )
+                // NB: for some reason, .map_ok(...) below does not compile since 1.66 nightly
+                .map(|r| r.map(Quad::into_triple))
+        })
+    }
+}
+
+/// A [`TermMatcher`] shared by several iterators.
+struct SharedMatcher<M>(Rc<M>);
+
+impl<M: TermMatcher> TermMatcher for SharedMatcher<M> {
+    type Term = M::Term;
+
+    fn matches<T2: Term + ?Sized>(&self, term: &T2) -> bool {
+        self.0.matches(term)
+    }
+
+    fn constant(&self) -> Option<&Self::Term> {
+        self.0.constant()
+    }
+
+    fn language_tag(&self) -> Option<LanguageTag<&str>> {
+        self.0.language_tag()
+    }
+}
+
+//
+
 /// I wrap a [`Dataset`] as a [`Graph`]
 /// corresponding to a specific graph (default or named) of the wrapped dataset.
 ///
@@ -234,17 +347,120 @@ impl<D: MutableDataset, G: Term> MutableGraph for DatasetGraph<D, G> {
     }
 }
 
+/// I wrap a [`Graph`],
+/// applying a [`UnicodePolicy`] to the literals of the triples inserted into (or removed from) it.
+///
+/// NB: the policy is not applied to the terms passed to
+/// [`triples_matching`](Graph::triples_matching) or [`contains`](Graph::contains).
+///
+/// NB: [`remove_matching`](MutableGraph::remove_matching) and [`retain_matching`](MutableGraph::retain_matching)
+/// are only available if the underlying graph is infallible
+/// (i.e. its [`Error`](Graph::Error) type is [`Infallible`](std::convert::Infallible)).
+#[derive(Clone, Copy, Debug)]
+pub struct UnicodeGraph<G: Graph> {
+    graph: G,
+    policy: UnicodePolicy,
+}
+
+impl<G: Graph> UnicodeGraph<G> {
+    /// Wrap the given graph, applying the given policy to its mutations.
+    pub fn new(graph: G, policy: UnicodePolicy) -> Self {
+        UnicodeGraph { graph, policy }
+    }
+
+    /// The policy applied by this graph.
+    pub fn policy(&self) -> UnicodePolicy {
+        self.policy
+    }
+
+    /// Unwrap the inner [`Graph`].
+    pub fn unwrap(self) -> G {
+        self.graph
+    }
+}
+
+impl<G: Graph> Graph for UnicodeGraph<G> {
+    type Triple<'x> = G::Triple<'x> where Self: 'x;
+    type Error = G::Error;
+
+    fn triples(&self) -> impl Iterator<Item = GResult<Self, Self::Triple<'_>>> + '_ {
+        self.graph.triples()
+    }
+
+    fn triples_matching<'s, S, P, O>(
+        &'s self,
+        sm: S,
+        pm: P,
+        om: O,
+    ) -> impl Iterator<Item = GResult<Self, Self::Triple<'s>>> + 's
+    where
+        S: TermMatcher + 's,
+        P: TermMatcher + 's,
+        O: TermMatcher + 's,
+    {
+        self.graph.triples_matching(sm, pm, om)
+    }
+
+    fn contains<TS, TP, TO>(&self, s: TS, p: TP, o: TO) -> GResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        self.graph.contains(s, p, o)
+    }
+}
+
+impl<G: SetGraph> SetGraph for UnicodeGraph<G> {}
+
+impl<G: MutableGraph> MutableGraph for UnicodeGraph<G> {
+    type MutationError = UnicodePolicyError<G::MutationError>;
+
+    fn insert<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> MgResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let policy = self.policy;
+        self.graph
+            .insert(
+                policy.normalize_term(s)?,
+                policy.normalize_term(p)?,
+                policy.normalize_term(o)?,
+            )
+            .map_err(UnicodePolicyError::Inner)
+    }
+
+    fn remove<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> MgResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let policy = self.policy;
+        self.graph
+            .remove(
+                policy.normalize_term(s)?,
+                policy.normalize_term(p)?,
+                policy.normalize_term(o)?,
+            )
+            .map_err(UnicodePolicyError::Inner)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::quad::Spog;
+    use crate::source::StreamError;
     use crate::term::{graph_name_eq, FromTerm};
     use sophia_iri::Iri;
     use std::collections::BTreeSet;
 
-    static G1: Iri<&'static str> = Iri::new_unchecked_const("http://example.com/g1");
-    static G2: Iri<&'static str> = Iri::new_unchecked_const("http://example.com/g2");
-    static G3: Iri<&'static str> = Iri::new_unchecked_const("http://example.com/g3");
+    static G1: Iri<&'static str> = Iri::new_const("http://example.com/g1");
+    static G2: Iri<&'static str> = Iri::new_const("http://example.com/g2");
+    static G3: Iri<&'static str> = Iri::new_const("http://example.com/g3");
 
     type MyTerm = SimpleTerm<'static>;
     type MyQuad = Spog<MyTerm>;
@@ -318,6 +534,72 @@ mod test {
     }
     crate::test_graph_impl!(dataset_graph, MyDG, true, true, collect_dataset_graph);
 
+    type MyGS = GraphSelection<MyDS, MyTerm>;
+    fn collect_graph_selection<T: TripleSource>(mut ts: T) -> Result<MyGS, T::Error> {
+        let g1: GraphName<MyTerm> = Some(G1.into_term());
+        let g2: GraphName<MyTerm> = Some(G2.into_term());
+        let g3: GraphName<MyTerm> = Some(G3.into_term());
+        let mut ds = MyDS::new();
+        let mut b = true;
+        ts.for_each_triple(|t| {
+            let [s, p, o] = t.spo();
+            if b {
+                ds.insert_quad(([s, p, o].map(MyTerm::from_term), g1.clone()))
+                    .unwrap();
+                ds.insert_quad(([o, p, s].map(MyTerm::from_term), g3.clone()))
+                    .unwrap();
+            } else {
+                ds.insert_quad(([s, p, o].map(MyTerm::from_term), g2.clone()))
+                    .unwrap();
+            }
+            b = !b;
+        })?;
+        Ok(GraphSelection::new(ds, [g1.clone(), g2, g1]))
+    }
+    crate::test_immutable_graph_impl!(graph_selection, MyGS, true, true, collect_graph_selection);
+
+    #[test]
+    fn graph_selection_names() {
+        let g1: GraphName<MyTerm> = Some(G1.into_term());
+        let gs = GraphSelection::new(MyDS::new(), [None, g1.clone(), None, g1]);
+        assert_eq!(gs.graph_names().len(), 2);
+        assert_eq!(gs.triples().count(), 0);
+    }
+
+    type MyUnicodeGraph = UnicodeGraph<BTreeSet<[MyTerm; 3]>>;
+    fn collect_unicode_graph<T: TripleSource>(ts: T) -> Result<MyUnicodeGraph, T::Error> {
+        let mut g = UnicodeGraph::new(BTreeSet::new(), UnicodePolicy::new());
+        g.insert_all(ts).map_err(StreamError::unwrap_source_error)?;
+        Ok(g)
+    }
+    crate::test_graph_impl!(
+        unicode_graph,
+        MyUnicodeGraph,
+        true,
+        true,
+        collect_unicode_graph
+    );
+
+    #[test]
+    fn unicode_graph_normalizes() -> Result<(), Box<dyn std::error::Error>> {
+        let mut g = UnicodeGraph::new(BTreeSet::<[MyTerm; 3]>::new(), UnicodePolicy::new());
+        assert!(g.insert(G1, G1, "cafe\u{301}")?);
+        assert!(!g.insert(G1, G1, "caf\u{e9}")?);
+        assert!(g.contains(G1, G1, "caf\u{e9}")?);
+        assert!(g.remove(G1, G1, "cafe\u{301}")?);
+        assert_eq!(g.triples().count(), 0);
+
+        let mut g = UnicodeGraph::new(
+            g.unwrap(),
+            UnicodePolicy::new().with_reject_unassigned(true),
+        );
+        assert!(matches!(
+            g.insert(G1, G1, "ab\u{378}"),
+            Err(UnicodePolicyError::Unicode(_))
+        ));
+        Ok(())
+    }
+
     #[allow(dead_code)] // just check this compiles
     fn check_trait_impls() {
         let mut ds = MyDS::new();
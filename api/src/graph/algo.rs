@@ -0,0 +1,367 @@
+//! I provide algorithms on the directed graph formed by the triples of a [`Graph`]
+//! whose predicate matches a given [`TermMatcher`] (e.g. `[rdfs::subClassOf]`),
+//! each of these triples being an edge from its subject to its object.
+//!
+//! The nodes of that directed graph are enumerated once,
+//! but its edges are retrieved from the underlying [`Graph`] on demand,
+//! so the [`Graph`] is never copied into another structure.
+//!
+//! # Example
+//! ```
+//! # use sophia_api::graph::algo::{find_cycle, topological_sort};
+//! # use sophia_api::term::{IriRef, SimpleTerm};
+//! # use sophia_api::ns::rdfs;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let sub_class_of = IriRef::new_unchecked("http://www.w3.org/2000/01/rdf-schema#subClassOf");
+//! let mut graph = vec![
+//!     ["tag:Cat", "tag:Animal"],
+//!     ["tag:Animal", "tag:Thing"],
+//! ]
+//! .into_iter()
+//! .map(|[s, o]| [IriRef::new_unchecked(s), sub_class_of, IriRef::new_unchecked(o)])
+//! .collect::<Vec<_>>();
+//!
+//! let sorted = topological_sort(&graph, [rdfs::subClassOf])?;
+//! assert_eq!(sorted.len(), 3);
+//! assert!(sorted[0] == IriRef::new_unchecked("tag:Cat"));
+//! assert!(sorted[2] == IriRef::new_unchecked("tag:Thing"));
+//!
+//! let (thing, cat) = (IriRef::new_unchecked("tag:Thing"), IriRef::new_unchecked("tag:Cat"));
+//! graph.push([thing, sub_class_of, cat]);
+//! assert_eq!(find_cycle(&graph, [rdfs::subClassOf])?.unwrap().len(), 3);
+//! assert!(topological_sort(&graph, [rdfs::subClassOf]).is_err());
+//! # Ok(()) }
+//! ```
+use super::*;
+use crate::term::matcher::Any;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+
+/// Compute the strongly connected components of the directed graph
+/// formed by the triples of `graph` whose predicate matches `predicate`.
+///
+/// The components are returned in topological order,
+/// i.e. every component comes before all the components reachable from it.
+pub fn strongly_connected_components<G, P>(
+    graph: &G,
+    predicate: P,
+) -> Result<Vec<Vec<SimpleTerm<'static>>>, G::Error>
+where
+    G: Graph,
+    P: TermMatcher,
+{
+    let digraph = Digraph::new(graph, predicate)?;
+    let mut components = digraph.tarjan()?;
+    components.reverse();
+    Ok(components
+        .into_iter()
+        .map(|c| c.into_iter().map(|i| digraph.nodes[i].clone()).collect())
+        .collect())
+}
+
+/// Find a cycle in the directed graph
+/// formed by the triples of `graph` whose predicate matches `predicate`.
+///
+/// If one is found, its nodes are returned in order,
+/// each of them being linked to the next one, and the last one to the first one.
+pub fn find_cycle<G, P>(
+    graph: &G,
+    predicate: P,
+) -> Result<Option<Vec<SimpleTerm<'static>>>, G::Error>
+where
+    G: Graph,
+    P: TermMatcher,
+{
+    let digraph = Digraph::new(graph, predicate)?;
+    for component in digraph.tarjan()? {
+        if let Some(cycle) = digraph.cycle_in(&component)? {
+            return Ok(Some(
+                cycle
+                    .into_iter()
+                    .map(|i| digraph.nodes[i].clone())
+                    .collect(),
+            ));
+        }
+    }
+    Ok(None)
+}
+
+/// Sort topologically the nodes of the directed graph
+/// formed by the triples of `graph` whose predicate matches `predicate`.
+///
+/// In the returned vector, the subject of each triple comes before its object
+/// (e.g. with `rdfs:subClassOf`, subclasses come before their superclasses).
+/// Fail with [`TopologicalSortError::Cycle`] if the directed graph contains a cycle.
+pub fn topological_sort<G, P>(
+    graph: &G,
+    predicate: P,
+) -> Result<Vec<SimpleTerm<'static>>, TopologicalSortError<G::Error>>
+where
+    G: Graph,
+    P: TermMatcher,
+{
+    let digraph = Digraph::new(graph, predicate).map_err(TopologicalSortError::Graph)?;
+    let components = digraph.tarjan().map_err(TopologicalSortError::Graph)?;
+    let mut sorted = Vec::with_capacity(digraph.nodes.len());
+    for component in components.into_iter().rev() {
+        if let Some(cycle) = digraph
+            .cycle_in(&component)
+            .map_err(TopologicalSortError::Graph)?
+        {
+            return Err(TopologicalSortError::Cycle(
+                cycle
+                    .into_iter()
+                    .map(|i| digraph.nodes[i].clone())
+                    .collect(),
+            ));
+        }
+        sorted.push(digraph.nodes[component[0]].clone());
+    }
+    Ok(sorted)
+}
+
+/// Error raised by [`topological_sort`].
+#[derive(Debug, thiserror::Error)]
+pub enum TopologicalSortError<E: Error> {
+    /// Error in the underlying graph.
+    #[error("{0:?}")]
+    Graph(E),
+    /// The directed graph contains the given cycle (see [`find_cycle`]).
+    #[error("Cycle detected: {0:?}")]
+    Cycle(Vec<SimpleTerm<'static>>),
+}
+
+/// A view of a [`Graph`] as a directed graph, whose nodes are numbered.
+struct Digraph<'a, G, P> {
+    graph: &'a G,
+    predicate: P,
+    nodes: Vec<SimpleTerm<'static>>,
+    indices: BTreeMap<SimpleTerm<'static>, usize>,
+}
+
+const UNVISITED: usize = usize::MAX;
+
+/// Look `key` up in `indices`, shortening the lifetime of the stored keys to that of `key`.
+fn lookup<'x>(indices: &'x BTreeMap<SimpleTerm<'x>, usize>, key: &SimpleTerm<'x>) -> Option<usize> {
+    indices.get(key).copied()
+}
+
+impl<'a, G: Graph, P: TermMatcher> Digraph<'a, G, P> {
+    fn new(graph: &'a G, predicate: P) -> Result<Self, G::Error> {
+        let mut nodes = vec![];
+        let mut indices = BTreeMap::new();
+        for t in graph.triples_matching(Any, predicate.matcher_ref(), Any) {
+            let t = t?;
+            for node in [t.s().as_simple(), t.o().as_simple()] {
+                let indices_ref: &BTreeMap<SimpleTerm<'_>, usize> = &indices;
+                if !indices_ref.contains_key(&node) {
+                    let node: SimpleTerm<'static> = node.into_term();
+                    indices.insert(node.clone(), nodes.len());
+                    nodes.push(node);
+                }
+            }
+        }
+        Ok(Digraph {
+            graph,
+            predicate,
+            nodes,
+            indices,
+        })
+    }
+
+    /// The index of `node`, if it is a node of this digraph.
+    fn index_of(&self, node: SimpleTerm<'_>) -> Option<usize> {
+        lookup(&self.indices, &node)
+    }
+
+    /// The indices of the successors of node `i`.
+    fn successors(&self, i: usize) -> Result<Vec<usize>, G::Error> {
+        let mut successors = vec![];
+        for t in self
+            .graph
+            .triples_matching([&self.nodes[i]], self.predicate.matcher_ref(), Any)
+        {
+            let o = t?.to_o();
+            if let Some(j) = self.index_of(o.as_simple()) {
+                successors.push(j);
+            }
+        }
+        Ok(successors)
+    }
+
+    /// Tarjan's algorithm (iterative version),
+    /// returning the strongly connected components in reverse topological order.
+    fn tarjan(&self) -> Result<Vec<Vec<usize>>, G::Error> {
+        let n = self.nodes.len();
+        let mut index = vec![UNVISITED; n];
+        let mut lowlink = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = vec![];
+        let mut components = vec![];
+        let mut counter = 0;
+        for root in 0..n {
+            if index[root] != UNVISITED {
+                continue;
+            }
+            let mut calls = vec![];
+            let mut next = Some(root);
+            loop {
+                if let Some(v) = next.take() {
+                    index[v] = counter;
+                    lowlink[v] = counter;
+                    counter += 1;
+                    stack.push(v);
+                    on_stack[v] = true;
+                    calls.push((v, self.successors(v)?.into_iter()));
+                }
+                let Some((v, successors)) = calls.last_mut() else {
+                    break;
+                };
+                let v = *v;
+                if let Some(w) = successors.next() {
+                    if index[w] == UNVISITED {
+                        next = Some(w);
+                    } else if on_stack[w] {
+                        lowlink[v] = lowlink[v].min(index[w]);
+                    }
+                    continue;
+                }
+                calls.pop();
+                if let Some((u, _)) = calls.last() {
+                    lowlink[*u] = lowlink[*u].min(lowlink[v]);
+                }
+                if lowlink[v] == index[v] {
+                    let mut component = vec![];
+                    loop {
+                        let w = stack.pop().unwrap();
+                        on_stack[w] = false;
+                        component.push(w);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    component.reverse();
+                    components.push(component);
+                }
+            }
+        }
+        Ok(components)
+    }
+
+    /// Find a cycle in the given strongly connected component, if any.
+    fn cycle_in(&self, component: &[usize]) -> Result<Option<Vec<usize>>, G::Error> {
+        if component.len() == 1 {
+            let i = component[0];
+            return Ok(self.successors(i)?.contains(&i).then(|| vec![i]));
+        }
+        // every node of the component has a successor in the component,
+        // so following them eventually leads to an already visited node
+        let members: BTreeSet<usize> = component.iter().copied().collect();
+        let mut path = vec![component[0]];
+        loop {
+            let last = *path.last().unwrap();
+            let next = self
+                .successors(last)?
+                .into_iter()
+                .find(|j| members.contains(j))
+                .unwrap();
+            if let Some(pos) = path.iter().position(|i| *i == next) {
+                return Ok(Some(path.split_off(pos)));
+            }
+            path.push(next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::term::IriRef;
+
+    type MyGraph = Vec<[SimpleTerm<'static>; 3]>;
+
+    fn iri(txt: &str) -> SimpleTerm<'static> {
+        SimpleTerm::Iri(IriRef::new_unchecked(txt.to_string().into()))
+    }
+
+    fn make_graph(edges: &[(&str, &str, &str)]) -> MyGraph {
+        edges
+            .iter()
+            .map(|(s, p, o)| [iri(s), iri(p), iri(o)])
+            .collect()
+    }
+
+    fn names(nodes: &[SimpleTerm<'_>]) -> Vec<String> {
+        nodes
+            .iter()
+            .map(|t| t.iri().unwrap().as_str()[4..].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn scc() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph(&[
+            ("tag:a", "tag:p", "tag:b"),
+            ("tag:b", "tag:p", "tag:c"),
+            ("tag:c", "tag:p", "tag:a"),
+            ("tag:c", "tag:p", "tag:d"),
+            ("tag:d", "tag:p", "tag:e"),
+            ("tag:e", "tag:p", "tag:d"),
+            ("tag:e", "tag:q", "tag:f"),
+        ]);
+        let sccs = strongly_connected_components(&g, [iri("tag:p")])?;
+        let sccs: Vec<_> = sccs.iter().map(|c| names(c)).collect();
+        assert_eq!(sccs, [vec!["a", "b", "c"], vec!["d", "e"]]);
+
+        let sccs = strongly_connected_components(&g, Any)?;
+        let sccs: Vec<_> = sccs.iter().map(|c| names(c)).collect();
+        assert_eq!(sccs, [vec!["a", "b", "c"], vec!["d", "e"], vec!["f"]]);
+        Ok(())
+    }
+
+    #[test]
+    fn cycle() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph(&[
+            ("tag:a", "tag:p", "tag:b"),
+            ("tag:b", "tag:p", "tag:c"),
+            ("tag:c", "tag:q", "tag:a"),
+            ("tag:c", "tag:p", "tag:d"),
+        ]);
+        assert!(find_cycle(&g, [iri("tag:p")])?.is_none());
+        let cycle = find_cycle(&g, Any)?.unwrap();
+        assert_eq!(names(&cycle), ["a", "b", "c"]);
+
+        let g = make_graph(&[("tag:a", "tag:p", "tag:b"), ("tag:b", "tag:p", "tag:b")]);
+        let cycle = find_cycle(&g, [iri("tag:p")])?.unwrap();
+        assert_eq!(names(&cycle), ["b"]);
+        Ok(())
+    }
+
+    #[test]
+    fn toposort() -> Result<(), Box<dyn std::error::Error>> {
+        let mut g = make_graph(&[
+            ("tag:a", "tag:p", "tag:b"),
+            ("tag:a", "tag:p", "tag:c"),
+            ("tag:b", "tag:p", "tag:d"),
+            ("tag:c", "tag:p", "tag:d"),
+            ("tag:d", "tag:q", "tag:a"),
+        ]);
+        let sorted = names(&topological_sort(&g, [iri("tag:p")])?);
+        assert_eq!(sorted.len(), 4);
+        let pos = |n: &str| sorted.iter().position(|m| m == n).unwrap();
+        assert!(pos("a") < pos("b"));
+        assert!(pos("a") < pos("c"));
+        assert!(pos("b") < pos("d"));
+        assert!(pos("c") < pos("d"));
+
+        g.push([iri("tag:d"), iri("tag:p"), iri("tag:a")]);
+        match topological_sort(&g, [iri("tag:p")]) {
+            Err(TopologicalSortError::Cycle(cycle)) => {
+                assert_eq!(cycle.len(), 3);
+                assert_eq!(names(&cycle)[0], "a");
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+        Ok(())
+    }
+}
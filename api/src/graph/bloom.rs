@@ -0,0 +1,215 @@
+//! I provide [`BloomFilter`], an approximate membership structure for terms and triples.
+//!
+//! A Bloom filter answers membership queries with no false negative,
+//! but with a (configurable) rate of false positives,
+//! using a small fraction of the memory required by the full graph.
+//! It is therefore suited to cheaply reject lookups for absent resources
+//! (e.g. in front-line services), before hitting the full store.
+//!
+//! # Example
+//! ```
+//! # use sophia_api::graph::bloom::BloomFilter;
+//! # use sophia_api::term::IriRef;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let graph = vec![
+//!     ["tag:alice", "tag:knows", "tag:bob"],
+//!     ["tag:bob", "tag:knows", "tag:carol"],
+//! ]
+//! .into_iter()
+//! .map(|t| t.map(IriRef::new_unchecked))
+//! .collect::<Vec<_>>();
+//!
+//! let subjects = BloomFilter::from_subjects(&graph, 0.01)?;
+//! assert!(subjects.maybe_contains_term(IriRef::new_unchecked("tag:alice")));
+//! assert!(subjects.maybe_contains_term(IriRef::new_unchecked("tag:bob")));
+//! // a negative answer is always correct
+//! if !subjects.maybe_contains_term(IriRef::new_unchecked("tag:carol")) {
+//!     println!("no need to query the store about tag:carol");
+//! }
+//! # Ok(()) }
+//! ```
+use super::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+
+/// An approximate set of [terms](Term) and/or [triples](Triple),
+/// answering membership queries with no false negative, but with possible false positives.
+///
+/// Terms and triples are hashed in the same way regardless of their implementation,
+/// so a filter built from one graph can be queried with the terms of another.
+#[derive(Clone, Debug)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    nb_bits: u64,
+    nb_hashes: u32,
+}
+
+impl BloomFilter {
+    /// Build an empty [`BloomFilter`], sized to contain `expected_items` items
+    /// with a rate of false positives of `false_positive_rate`.
+    ///
+    /// # Panics
+    /// If `false_positive_rate` is not strictly between 0 and 1.
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        assert!(
+            false_positive_rate > 0.0 && false_positive_rate < 1.0,
+            "false_positive_rate must be in ]0, 1["
+        );
+        let n = expected_items.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let nb_bits = (-n * false_positive_rate.ln() / (ln2 * ln2))
+            .ceil()
+            .max(64.0) as u64;
+        let nb_hashes = ((nb_bits as f64 / n) * ln2).round().max(1.0) as u32;
+        BloomFilter {
+            bits: vec![0; nb_bits.div_ceil(64) as usize],
+            nb_bits,
+            nb_hashes,
+        }
+    }
+
+    /// Build a [`BloomFilter`] containing all the subjects of `graph`.
+    pub fn from_subjects<G: Graph>(graph: &G, false_positive_rate: f64) -> Result<Self, G::Error> {
+        let mut filter = Self::new(graph.triples().count(), false_positive_rate);
+        for t in graph.triples() {
+            filter.insert_term(t?.s());
+        }
+        Ok(filter)
+    }
+
+    /// Build a [`BloomFilter`] containing all the triples of `graph`.
+    pub fn from_triples<G: Graph>(graph: &G, false_positive_rate: f64) -> Result<Self, G::Error> {
+        let mut filter = Self::new(graph.triples().count(), false_positive_rate);
+        for t in graph.triples() {
+            filter.insert_triple(t?);
+        }
+        Ok(filter)
+    }
+
+    /// The number of bits of this filter.
+    pub fn nb_bits(&self) -> u64 {
+        self.nb_bits
+    }
+
+    /// The number of hash functions used by this filter.
+    pub fn nb_hashes(&self) -> u32 {
+        self.nb_hashes
+    }
+
+    /// Add `term` to this filter.
+    pub fn insert_term<T: Term>(&mut self, term: T) {
+        self.insert_hash(hash_term(term));
+    }
+
+    /// Add `triple` to this filter.
+    pub fn insert_triple<T: Triple>(&mut self, triple: T) {
+        self.insert_hash(hash_triple(triple));
+    }
+
+    /// Return false if `term` was certainly not added to this filter,
+    /// true if it was *probably* added.
+    pub fn maybe_contains_term<T: Term>(&self, term: T) -> bool {
+        self.contains_hash(hash_term(term))
+    }
+
+    /// Return false if `triple` was certainly not added to this filter,
+    /// true if it was *probably* added.
+    pub fn maybe_contains_triple<T: Triple>(&self, triple: T) -> bool {
+        self.contains_hash(hash_triple(triple))
+    }
+
+    fn insert_hash(&mut self, hash: u64) {
+        for i in self.positions(hash) {
+            self.bits[(i / 64) as usize] |= 1 << (i % 64);
+        }
+    }
+
+    fn contains_hash(&self, hash: u64) -> bool {
+        self.positions(hash)
+            .all(|i| self.bits[(i / 64) as usize] & (1 << (i % 64)) != 0)
+    }
+
+    /// The positions of the bits corresponding to `hash`,
+    /// using double hashing (Kirsch and Mitzenmacher, 2006).
+    fn positions(&self, hash: u64) -> impl Iterator<Item = u64> {
+        let nb_bits = self.nb_bits;
+        let h2 = hash.rotate_left(32) | 1;
+        (0..self.nb_hashes as u64).map(move |i| hash.wrapping_add(i.wrapping_mul(h2)) % nb_bits)
+    }
+}
+
+fn hash_term<T: Term>(term: T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    Term::hash(&term, &mut hasher);
+    hasher.finish()
+}
+
+fn hash_triple<T: Triple>(triple: T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for term in triple.spo() {
+        Term::hash(&term, &mut hasher);
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::term::IriRef;
+
+    type MyGraph = Vec<[SimpleTerm<'static>; 3]>;
+
+    fn iri(txt: String) -> SimpleTerm<'static> {
+        SimpleTerm::Iri(IriRef::new_unchecked(txt.into()))
+    }
+
+    fn make_graph() -> MyGraph {
+        (0..1000)
+            .map(|i| {
+                [
+                    iri(format!("tag:s{i}")),
+                    iri("tag:p".to_string()),
+                    iri(format!("tag:o{i}")),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sizing() {
+        let filter = BloomFilter::new(1000, 0.01);
+        assert_eq!(filter.nb_bits(), 9586);
+        assert_eq!(filter.nb_hashes(), 7);
+        let filter = BloomFilter::new(0, 0.5);
+        assert_eq!(filter.nb_bits(), 64);
+    }
+
+    #[test]
+    fn subjects() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph();
+        let filter = BloomFilter::from_subjects(&g, 0.01)?;
+        for t in &g {
+            assert!(filter.maybe_contains_term(&t[0]));
+        }
+        let false_positives = (0..1000)
+            .filter(|i| filter.maybe_contains_term(iri(format!("tag:o{i}"))))
+            .count();
+        assert!(false_positives < 50, "{false_positives}");
+        Ok(())
+    }
+
+    #[test]
+    fn triples() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph();
+        let filter = BloomFilter::from_triples(&g, 0.01)?;
+        for t in &g {
+            assert!(filter.maybe_contains_triple(t.spo()));
+        }
+        let false_positives = g
+            .iter()
+            .filter(|t| filter.maybe_contains_triple([&t[2], &t[1], &t[0]]))
+            .count();
+        assert!(false_positives < 50, "{false_positives}");
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+//! I provide [`Budget`], which limits the number of results and/or the time
+//! spent consuming an iterator, such as those returned by
+//! [`Graph::triples_matching`] or [`Dataset::quads_matching`](crate::dataset::Dataset::quads_matching).
+//!
+//! An iterator [with a budget](BudgetExt::with_budget) yields its items wrapped in `Ok`;
+//! when the budget is exhausted while results remain,
+//! it yields a single `Err(`[`Truncated`]`)` and stops.
+//! This allows interactive applications to return partial results predictably,
+//! and to tell their users that the results are partial.
+//!
+//! # Example
+//! ```
+//! # use sophia_api::graph::Graph;
+//! # use sophia_api::graph::budget::{Budget, BudgetExt, Truncated};
+//! # use sophia_api::ns::rdf;
+//! # use sophia_api::term::matcher::Any;
+//! # use std::time::Duration;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let graph = vec![
+//!     [rdf::Alt, rdf::type_, rdf::Property],
+//!     [rdf::Bag, rdf::type_, rdf::Property],
+//!     [rdf::Seq, rdf::type_, rdf::Property],
+//! ];
+//! let budget = Budget::new()
+//!     .with_max_results(2)
+//!     .with_timeout(Duration::from_secs(1));
+//! let results: Vec<_> = graph
+//!     .triples_matching(Any, [rdf::type_], Any)
+//!     .with_budget(&budget)
+//!     .collect();
+//! assert_eq!(results.len(), 3);
+//! assert!(results[..2].iter().all(Result::is_ok));
+//! assert!(matches!(results[2], Err(Truncated::MaxResults)));
+//! # Ok(()) }
+//! ```
+use std::time::{Duration, Instant};
+
+/// The limits applied to an iterator by [`BudgetExt::with_budget`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Budget {
+    max_results: Option<usize>,
+    timeout: Option<Duration>,
+}
+
+impl Budget {
+    /// Build a new default [`Budget`] (unlimited).
+    pub fn new() -> Self {
+        Budget {
+            max_results: None,
+            timeout: None,
+        }
+    }
+
+    /// The maximum number of results (defaults to `None`, i.e. unlimited).
+    pub fn max_results(&self) -> Option<usize> {
+        self.max_results
+    }
+
+    /// The maximum time spent consuming the iterator,
+    /// starting when the budget is [attached](BudgetExt::with_budget) to it
+    /// (defaults to `None`, i.e. unlimited).
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Change the maximum number of results.
+    pub fn with_max_results(mut self, max_results: usize) -> Self {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    /// Change the maximum time spent consuming the iterator.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The reason why an iterator [with a budget](BudgetExt::with_budget) was truncated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Truncated {
+    /// The [maximum number of results](Budget::with_max_results) was reached
+    MaxResults,
+    /// The [timeout](Budget::with_timeout) expired
+    Timeout,
+}
+
+impl std::fmt::Display for Truncated {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Truncated::MaxResults => write!(f, "maximum number of results reached"),
+            Truncated::Timeout => write!(f, "timeout expired"),
+        }
+    }
+}
+
+impl std::error::Error for Truncated {}
+
+/// Extension trait attaching a [`Budget`] to any iterator.
+pub trait BudgetExt: Iterator + Sized {
+    /// Limit this iterator according to `budget`.
+    ///
+    /// Items are yielded wrapped in `Ok`; once the budget is exhausted,
+    /// a single `Err(`[`Truncated`]`)` is yielded if more items remain.
+    /// The timeout starts now, not at the first call to `next`.
+    fn with_budget(self, budget: &Budget) -> Budgeted<Self> {
+        Budgeted {
+            inner: self,
+            remaining: budget.max_results,
+            deadline: budget.timeout.map(|t| Instant::now() + t),
+            done: false,
+        }
+    }
+}
+
+impl<I: Iterator> BudgetExt for I {}
+
+/// An iterator limited by a [`Budget`], returned by [`BudgetExt::with_budget`].
+///
+/// NB: the timeout is checked before each call to the underlying iterator,
+/// so a single slow call (e.g. skipping many non-matching triples) may exceed it.
+#[derive(Clone, Debug)]
+pub struct Budgeted<I> {
+    inner: I,
+    remaining: Option<usize>,
+    deadline: Option<Instant>,
+    done: bool,
+}
+
+impl<I: Iterator> Iterator for Budgeted<I> {
+    type Item = Result<I::Item, Truncated>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.deadline.is_some_and(|d| Instant::now() >= d) {
+            self.done = true;
+            // only signal truncation if results remain
+            return self.inner.next().map(|_| Err(Truncated::Timeout));
+        }
+        let item = self.inner.next();
+        match (item, self.remaining.as_mut()) {
+            (None, _) => {
+                self.done = true;
+                None
+            }
+            (Some(_), Some(0)) => {
+                self.done = true;
+                Some(Err(Truncated::MaxResults))
+            }
+            (Some(item), Some(remaining)) => {
+                *remaining -= 1;
+                Some(Ok(item))
+            }
+            (Some(item), None) => Some(Ok(item)),
+        }
+    }
+}
+
+impl<I: Iterator> std::iter::FusedIterator for Budgeted<I> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::Graph;
+    use crate::ns::rdf;
+    use crate::term::matcher::Any;
+    use crate::term::{SimpleTerm, Term};
+
+    type MyGraph = Vec<[SimpleTerm<'static>; 3]>;
+
+    fn make_graph() -> MyGraph {
+        [rdf::Alt, rdf::Bag, rdf::Seq]
+            .into_iter()
+            .map(|s| [s, rdf::type_, rdf::Property].map(Term::into_term))
+            .collect()
+    }
+
+    #[test]
+    fn unlimited() {
+        let g = make_graph();
+        let results: Vec<_> = g.triples().with_budget(&Budget::new()).collect();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(Result::is_ok));
+    }
+
+    #[test]
+    fn max_results() {
+        let g = make_graph();
+        let budget = Budget::new().with_max_results(1);
+        let mut it = g
+            .triples_matching(Any, [rdf::type_], Any)
+            .with_budget(&budget);
+        assert!(matches!(it.next(), Some(Ok(_))));
+        assert_eq!(it.next(), Some(Err(Truncated::MaxResults)));
+        assert_eq!(it.next(), None);
+
+        // no truncation is signaled if the budget is exactly sufficient
+        let budget = Budget::new().with_max_results(3);
+        let results: Vec<_> = g.triples().with_budget(&budget).collect();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(Result::is_ok));
+    }
+
+    #[test]
+    fn timeout() {
+        let g = make_graph();
+        let budget = Budget::new().with_timeout(Duration::ZERO);
+        let mut it = g.triples().with_budget(&budget);
+        assert_eq!(it.next(), Some(Err(Truncated::Timeout)));
+        assert_eq!(it.next(), None);
+
+        let empty = MyGraph::new();
+        assert_eq!(empty.triples().with_budget(&budget).next(), None);
+
+        let budget = Budget::new().with_timeout(Duration::from_secs(3600));
+        assert_eq!(g.triples().with_budget(&budget).count(), 3);
+    }
+}
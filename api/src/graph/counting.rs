@@ -0,0 +1,261 @@
+//! I provide [`CountingGraph`], a multigraph keeping track of the multiplicity of its triples.
+//!
+//! Inserting a triple that is already present increments its count,
+//! and removing a triple decrements it (the triple disappears when its count reaches zero).
+//! This is useful when aggregating streams of triples where multiplicity carries meaning
+//! (e.g. how many sources asserted a given triple).
+//!
+//! # Example
+//! ```
+//! # use sophia_api::graph::{Graph, MutableGraph};
+//! # use sophia_api::graph::counting::CountingGraph;
+//! # use sophia_api::term::{IriRef, SimpleTerm};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let [s, p, o] = ["tag:alice", "tag:knows", "tag:bob"].map(IriRef::new_unchecked);
+//! let mut graph = CountingGraph::<SimpleTerm>::new();
+//! graph.insert(s, p, o)?;
+//! graph.insert(s, p, o)?;
+//! assert_eq!(graph.count(s, p, o), 2);
+//! assert_eq!(graph.triples().count(), 2);
+//!
+//! graph.remove(s, p, o)?;
+//! assert_eq!(graph.count(s, p, o), 1);
+//! assert!(graph.contains(s, p, o)?);
+//! # Ok(()) }
+//! ```
+use super::*;
+use crate::source::SourceError;
+use crate::term::FromTerm;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+
+/// A [`Graph`] where each triple is associated with its number of occurrences.
+///
+/// [`Graph::triples`] yields each triple as many times as it was inserted;
+/// use [`CountingGraph::counts`] to iterate over distinct triples and their counts.
+///
+/// [`MutableGraph::remove`] removes a single occurrence,
+/// while [`CountingGraph::remove_all`] removes all of them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CountingGraph<T> {
+    counts: BTreeMap<[T; 3], usize>,
+    total: usize,
+}
+
+impl<T> CountingGraph<T>
+where
+    T: Term + FromTerm + Ord,
+{
+    /// Build a new empty [`CountingGraph`]
+    pub fn new() -> Self {
+        CountingGraph {
+            counts: BTreeMap::new(),
+            total: 0,
+        }
+    }
+
+    /// The number of occurrences of the given triple in this graph (0 if absent).
+    pub fn count<TS, TP, TO>(&self, s: TS, p: TP, o: TO) -> usize
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let key: [T; 3] = [s.into_term(), p.into_term(), o.into_term()];
+        self.counts.get(&key).copied().unwrap_or(0)
+    }
+
+    /// Iterate over the distinct triples of this graph, with their number of occurrences.
+    pub fn counts(&self) -> impl Iterator<Item = (&[T; 3], usize)> + '_ {
+        self.counts.iter().map(|(t, n)| (t, *n))
+    }
+
+    /// The number of distinct triples in this graph.
+    pub fn distinct_len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// The total number of occurrences of triples in this graph.
+    pub fn total_len(&self) -> usize {
+        self.total
+    }
+
+    /// Remove all occurrences of the given triple,
+    /// and return how many occurrences were removed.
+    pub fn remove_all<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> usize
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let key: [T; 3] = [s.into_term(), p.into_term(), o.into_term()];
+        let removed = self.counts.remove(&key).unwrap_or(0);
+        self.total -= removed;
+        removed
+    }
+
+    /// Increment the count of `triple`, and return `true` iff it was not present before.
+    fn add(&mut self, triple: [T; 3]) -> bool {
+        let n = self.counts.entry(triple).or_insert(0);
+        *n += 1;
+        self.total += 1;
+        *n == 1
+    }
+}
+
+impl<T> Default for CountingGraph<T>
+where
+    T: Term + FromTerm + Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Graph for CountingGraph<T>
+where
+    T: Term + FromTerm + Ord,
+{
+    type Error = Infallible;
+    type Triple<'x> = [T::BorrowTerm<'x>; 3] where Self: 'x;
+
+    fn triples(&self) -> impl Iterator<Item = GResult<Self, Self::Triple<'_>>> + '_ {
+        self.counts
+            .iter()
+            .flat_map(|(t, n)| (0..*n).map(move |_| Ok(t.spo())))
+    }
+
+    fn contains<TS, TP, TO>(&self, s: TS, p: TP, o: TO) -> GResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        Ok(self.count(s, p, o) > 0)
+    }
+}
+
+impl<T> CollectibleGraph for CountingGraph<T>
+where
+    T: Term + FromTerm + Ord,
+{
+    fn from_triple_source<TS: TripleSource>(
+        mut triples: TS,
+    ) -> StreamResult<Self, TS::Error, Self::Error> {
+        let mut g = CountingGraph::new();
+        triples
+            .for_each_triple(|t| {
+                g.add([t.s().into_term(), t.p().into_term(), t.o().into_term()]);
+            })
+            .map_err(SourceError)?;
+        Ok(g)
+    }
+}
+
+/// NB: [`MutableGraph::insert`] returns `true` iff the triple was not present before,
+/// and [`MutableGraph::remove`] returns `true` iff one occurrence of the triple was removed.
+impl<T> MutableGraph for CountingGraph<T>
+where
+    T: Term + FromTerm + Ord,
+{
+    type MutationError = Infallible;
+
+    fn insert<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> MgResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        Ok(self.add([s.into_term(), p.into_term(), o.into_term()]))
+    }
+
+    fn remove<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> MgResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let key: [T; 3] = [s.into_term(), p.into_term(), o.into_term()];
+        let Some(n) = self.counts.get_mut(&key) else {
+            return Ok(false);
+        };
+        *n -= 1;
+        if *n == 0 {
+            self.counts.remove(&key);
+        }
+        self.total -= 1;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ns::{rdf, rdfs};
+    use crate::term::matcher::Any;
+    use crate::term::SimpleTerm;
+
+    type MyGraph = CountingGraph<SimpleTerm<'static>>;
+
+    #[test]
+    fn insert_and_remove() -> Result<(), Infallible> {
+        let mut g = MyGraph::new();
+        assert!(g.insert(rdf::type_, rdf::type_, rdf::Property)?);
+        assert!(!g.insert(rdf::type_, rdf::type_, rdf::Property)?);
+        assert!(g.insert(rdfs::label, rdf::type_, rdf::Property)?);
+        assert_eq!(g.count(rdf::type_, rdf::type_, rdf::Property), 2);
+        assert_eq!(g.count(rdfs::label, rdf::type_, rdf::Property), 1);
+        assert_eq!(g.count(rdfs::Class, rdf::type_, rdfs::Class), 0);
+        assert_eq!(g.distinct_len(), 2);
+        assert_eq!(g.total_len(), 3);
+        assert_eq!(g.triples().count(), 3);
+
+        assert!(g.remove(rdf::type_, rdf::type_, rdf::Property)?);
+        assert!(g.contains(rdf::type_, rdf::type_, rdf::Property)?);
+        assert!(g.remove(rdf::type_, rdf::type_, rdf::Property)?);
+        assert!(!g.contains(rdf::type_, rdf::type_, rdf::Property)?);
+        assert!(!g.remove(rdf::type_, rdf::type_, rdf::Property)?);
+        assert_eq!(g.distinct_len(), 1);
+        assert_eq!(g.total_len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn remove_all() -> Result<(), Infallible> {
+        let mut g = MyGraph::new();
+        for _ in 0..3 {
+            g.insert(rdf::type_, rdf::type_, rdf::Property)?;
+        }
+        g.insert(rdfs::label, rdf::type_, rdf::Property)?;
+        assert_eq!(g.remove_all(rdf::type_, rdf::type_, rdf::Property), 3);
+        assert_eq!(g.remove_all(rdf::type_, rdf::type_, rdf::Property), 0);
+        assert_eq!(g.total_len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn collect_and_count() -> Result<(), Box<dyn std::error::Error>> {
+        let source: Vec<[SimpleTerm<'static>; 3]> = vec![
+            [
+                rdf::type_.into_term(),
+                rdf::type_.into_term(),
+                rdf::Property.into_term(),
+            ],
+            [
+                rdfs::label.into_term(),
+                rdf::type_.into_term(),
+                rdf::Property.into_term(),
+            ],
+            [
+                rdf::type_.into_term(),
+                rdf::type_.into_term(),
+                rdf::Property.into_term(),
+            ],
+        ];
+        let g: MyGraph = source.triples().collect_triples()?;
+        assert_eq!(g.counts().map(|(_, n)| n).sum::<usize>(), 3);
+        assert_eq!(g.count(rdf::type_, rdf::type_, rdf::Property), 2);
+        assert_eq!(g.triples_matching([rdf::type_], Any, Any).count(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,336 @@
+//! I provide [`shortest_path`] and [`is_reachable`],
+//! to find how two nodes of a [`Graph`] are connected.
+//!
+//! Both are configured with a [`WalkConfig`],
+//! restricting the direction and predicates of the triples to follow,
+//! and the maximum length of the path.
+//!
+//! # Example
+//! ```
+//! # use sophia_api::graph::path::shortest_path;
+//! # use sophia_api::graph::walk::WalkConfig;
+//! # use sophia_api::term::IriRef;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let knows = IriRef::new_unchecked("http://xmlns.com/foaf/0.1/knows");
+//! let graph = vec![
+//!     ["tag:alice", "tag:bob"],
+//!     ["tag:bob", "tag:carol"],
+//!     ["tag:carol", "tag:dave"],
+//! ]
+//! .into_iter()
+//! .map(|[s, o]| [IriRef::new_unchecked(s), knows, IriRef::new_unchecked(o)])
+//! .collect::<Vec<_>>();
+//!
+//! let alice = IriRef::new_unchecked("tag:alice");
+//! let dave = IriRef::new_unchecked("tag:dave");
+//! let path = shortest_path(&graph, alice, dave, &WalkConfig::new())?.unwrap();
+//! assert_eq!(path.len(), 3);
+//! assert!(path.nodes()[1] == IriRef::new_unchecked("tag:bob"));
+//!
+//! let config = WalkConfig::new().with_max_depth(2);
+//! assert!(shortest_path(&graph, alice, dave, &config)?.is_none());
+//! # Ok(()) }
+//! ```
+use super::walk::{edges, Direction, WalkConfig};
+use super::*;
+use std::collections::BTreeMap;
+
+/// A path between two nodes of a [`Graph`], as returned by [`shortest_path`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path {
+    nodes: Vec<SimpleTerm<'static>>,
+    triples: Vec<[SimpleTerm<'static>; 3]>,
+}
+
+impl Path {
+    /// The nodes of this path, from its source to its target.
+    ///
+    /// There is always one more node than [triples](Path::triples).
+    pub fn nodes(&self) -> &[SimpleTerm<'static>] {
+        &self.nodes
+    }
+
+    /// The triples connecting the [nodes](Path::nodes) of this path, in order.
+    ///
+    /// Depending on the [`Direction`] of the search,
+    /// each triple may have the previous node as its subject and the next one as its object,
+    /// or the other way around.
+    pub fn triples(&self) -> &[[SimpleTerm<'static>; 3]] {
+        &self.triples
+    }
+
+    /// The first node of this path.
+    pub fn source(&self) -> &SimpleTerm<'static> {
+        &self.nodes[0]
+    }
+
+    /// The last node of this path.
+    pub fn target(&self) -> &SimpleTerm<'static> {
+        &self.nodes[self.nodes.len() - 1]
+    }
+
+    /// The length of this path, i.e. its number of triples.
+    pub fn len(&self) -> usize {
+        self.triples.len()
+    }
+
+    /// Whether this path is empty, i.e. its source is also its target.
+    pub fn is_empty(&self) -> bool {
+        self.triples.is_empty()
+    }
+}
+
+/// Find a shortest path from `source` to `target` in `graph`.
+///
+/// Only the triples satisfying `config` are followed
+/// ([`direction`](WalkConfig::direction), [`predicates`](WalkConfig::predicates)),
+/// and no path longer than [`max_depth`](WalkConfig::max_depth) is considered.
+///
+/// The search is a bidirectional breadth-first search,
+/// alternately expanding the smallest frontier from each end.
+/// Return `Ok(None)` if no such path exists.
+pub fn shortest_path<G, T, U>(
+    graph: &G,
+    source: T,
+    target: U,
+    config: &WalkConfig,
+) -> Result<Option<Path>, G::Error>
+where
+    G: Graph,
+    T: Term,
+    U: Term,
+{
+    let source: SimpleTerm<'static> = source.into_term();
+    let target: SimpleTerm<'static> = target.into_term();
+    if source == target {
+        return Ok(Some(Path {
+            nodes: vec![source],
+            triples: vec![],
+        }));
+    }
+    let predicates = config.predicates();
+    let pm = |t: SimpleTerm<'_>| predicates.is_none_or(|ps| ps.iter().any(|p| Term::eq(p, &t)));
+    let max_depth = config.max_depth().unwrap_or(usize::MAX);
+    let backward_direction = match config.direction() {
+        Direction::Forward => Direction::Backward,
+        Direction::Backward => Direction::Forward,
+        Direction::Both => Direction::Both,
+    };
+    let mut forward = Search::new(source, config.direction());
+    let mut backward = Search::new(target, backward_direction);
+    while forward.depth + backward.depth < max_depth
+        && !forward.frontier.is_empty()
+        && !backward.frontier.is_empty()
+    {
+        let (this, other) = if forward.frontier.len() <= backward.frontier.len() {
+            (&mut forward, &backward)
+        } else {
+            (&mut backward, &forward)
+        };
+        if let Some(meeting) = this.expand(graph, &pm, other)? {
+            return Ok(Some(join(&forward, &backward, meeting)));
+        }
+    }
+    Ok(None)
+}
+
+/// Whether `target` can be reached from `source` in `graph`.
+///
+/// See [`shortest_path`] for the meaning of `config`.
+pub fn is_reachable<G, T, U>(
+    graph: &G,
+    source: T,
+    target: U,
+    config: &WalkConfig,
+) -> Result<bool, G::Error>
+where
+    G: Graph,
+    T: Term,
+    U: Term,
+{
+    Ok(shortest_path(graph, source, target, config)?.is_some())
+}
+
+/// One side of a bidirectional breadth-first search.
+struct Search {
+    direction: Direction,
+    depth: usize,
+    frontier: Vec<SimpleTerm<'static>>,
+    /// Maps every discovered node to the node it was discovered from, and the triple linking them.
+    parents: BTreeMap<SimpleTerm<'static>, Option<(SimpleTerm<'static>, [SimpleTerm<'static>; 3])>>,
+}
+
+impl Search {
+    fn new(start: SimpleTerm<'static>, direction: Direction) -> Self {
+        Search {
+            direction,
+            depth: 0,
+            frontier: vec![start.clone()],
+            parents: BTreeMap::from([(start, None)]),
+        }
+    }
+
+    /// The distance from the start to `node`, if it has been discovered.
+    fn distance(&self, node: &SimpleTerm<'static>) -> Option<usize> {
+        let mut parent = self.parents.get(node)?;
+        let mut distance = 0;
+        while let Some((node, _)) = parent {
+            distance += 1;
+            parent = &self.parents[node];
+        }
+        Some(distance)
+    }
+
+    /// Expand the frontier by one level.
+    ///
+    /// Return the newly discovered node closest to the start of `other`,
+    /// if any of them has already been discovered by `other`.
+    fn expand<G, P>(
+        &mut self,
+        graph: &G,
+        pm: &P,
+        other: &Search,
+    ) -> Result<Option<SimpleTerm<'static>>, G::Error>
+    where
+        G: Graph,
+        P: TermMatcher,
+    {
+        let mut next = vec![];
+        let mut meeting: Option<(usize, SimpleTerm<'static>)> = None;
+        for node in std::mem::take(&mut self.frontier) {
+            for (res, direction) in edges(graph, &node, self.direction, pm) {
+                let [s, p, o] = res?.to_spo();
+                let triple = [s.as_simple(), p.as_simple(), o.as_simple()];
+                let neighbour = if direction == Direction::Forward {
+                    &triple[2]
+                } else {
+                    &triple[0]
+                };
+                let parents: &BTreeMap<SimpleTerm<'_>, _> = &self.parents;
+                if parents.contains_key(neighbour) {
+                    continue;
+                }
+                let neighbour: SimpleTerm<'static> = neighbour.clone().into_term();
+                let triple: [SimpleTerm<'static>; 3] = triple.map(|t| t.into_term());
+                if let Some(distance) = other.distance(&neighbour) {
+                    if meeting.as_ref().is_none_or(|(d, _)| distance < *d) {
+                        meeting = Some((distance, neighbour.clone()));
+                    }
+                }
+                self.parents
+                    .insert(neighbour.clone(), Some((node.clone(), triple)));
+                next.push(neighbour);
+            }
+        }
+        self.frontier = next;
+        self.depth += 1;
+        Ok(meeting.map(|(_, node)| node))
+    }
+}
+
+/// Build the path going through `meeting`, discovered by both searches.
+fn join(forward: &Search, backward: &Search, meeting: SimpleTerm<'static>) -> Path {
+    let mut nodes = vec![meeting.clone()];
+    let mut triples = vec![];
+    let mut node = &meeting;
+    while let Some(Some((previous, triple))) = forward.parents.get(node) {
+        nodes.push(previous.clone());
+        triples.push(triple.clone());
+        node = previous;
+    }
+    nodes.reverse();
+    triples.reverse();
+    let mut node = &meeting;
+    while let Some(Some((next, triple))) = backward.parents.get(node) {
+        nodes.push(next.clone());
+        triples.push(triple.clone());
+        node = next;
+    }
+    Path { nodes, triples }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::term::IriRef;
+
+    type MyGraph = Vec<[SimpleTerm<'static>; 3]>;
+
+    fn iri(txt: &str) -> SimpleTerm<'static> {
+        SimpleTerm::Iri(IriRef::new_unchecked(txt.to_string().into()))
+    }
+
+    fn make_graph() -> MyGraph {
+        [
+            ("tag:a", "tag:p", "tag:b"),
+            ("tag:a", "tag:p", "tag:c"),
+            ("tag:b", "tag:p", "tag:d"),
+            ("tag:c", "tag:p", "tag:d"),
+            ("tag:d", "tag:q", "tag:e"),
+            ("tag:d", "tag:p", "tag:a"),
+        ]
+        .into_iter()
+        .map(|(s, p, o)| [iri(s), iri(p), iri(o)])
+        .collect()
+    }
+
+    fn names(nodes: &[SimpleTerm<'_>]) -> Vec<String> {
+        nodes
+            .iter()
+            .map(|t| t.iri().unwrap().as_str()[4..].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn forward() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph();
+        let path = shortest_path(&g, iri("tag:a"), iri("tag:e"), &WalkConfig::new())?.unwrap();
+        assert_eq!(names(path.nodes()), ["a", "b", "d", "e"]);
+        assert_eq!(path.len(), 3);
+        assert!(Term::eq(path.source(), iri("tag:a")));
+        assert!(Term::eq(path.target(), iri("tag:e")));
+        for (i, [s, _, o]) in path.triples().iter().enumerate() {
+            assert!(s == &path.nodes()[i]);
+            assert!(o == &path.nodes()[i + 1]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn empty() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph();
+        let path = shortest_path(&g, iri("tag:a"), iri("tag:a"), &WalkConfig::new())?.unwrap();
+        assert!(path.is_empty());
+        assert_eq!(names(path.nodes()), ["a"]);
+        Ok(())
+    }
+
+    #[test]
+    fn predicates_and_max_depth() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph();
+        let config = WalkConfig::new().with_predicates([iri("tag:p")]);
+        assert!(!is_reachable(&g, iri("tag:a"), iri("tag:e"), &config)?);
+        assert!(is_reachable(&g, iri("tag:e"), iri("tag:e"), &config)?);
+
+        let config = WalkConfig::new().with_max_depth(2);
+        assert!(shortest_path(&g, iri("tag:a"), iri("tag:e"), &config)?.is_none());
+        let path = shortest_path(&g, iri("tag:a"), iri("tag:d"), &config)?.unwrap();
+        assert_eq!(path.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn directions() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph();
+        let config = WalkConfig::new().with_direction(Direction::Backward);
+        let path = shortest_path(&g, iri("tag:e"), iri("tag:a"), &config)?.unwrap();
+        assert_eq!(names(path.nodes()), ["e", "d", "b", "a"]);
+        assert!(shortest_path(&g, iri("tag:a"), iri("tag:e"), &config)?.is_none());
+
+        let config = WalkConfig::new().with_direction(Direction::Both);
+        let path = shortest_path(&g, iri("tag:e"), iri("tag:a"), &config)?.unwrap();
+        assert_eq!(names(path.nodes()), ["e", "d", "a"]);
+        assert_eq!(names(&path.triples()[1]), ["d", "p", "a"],);
+        Ok(())
+    }
+}
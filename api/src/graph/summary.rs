@@ -0,0 +1,188 @@
+//! I provide [`summarize`], selecting the most informative triples about a resource,
+//! e.g. to build concise entity cards.
+//!
+//! Each triple having the resource as its subject is scored by combining
+//! * the popularity of its object (its number of occurrences as an object in the graph),
+//!   as popular objects are more likely to be meaningful to the reader, and
+//! * the specificity of its predicate (the inverse of its frequency in the graph),
+//!   as rare predicates are more likely to distinguish the resource from others.
+//!
+//! Triples are then selected by decreasing score,
+//! the score of a triple being halved for every already selected triple with the same predicate,
+//! so that the summary covers diverse predicates.
+//!
+//! # Example
+//! ```
+//! # use sophia_api::graph::summary::summarize;
+//! # use sophia_api::term::IriRef;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let graph = vec![
+//!     ["tag:alice", "tag:knows", "tag:bob"],
+//!     ["tag:alice", "tag:knows", "tag:carol"],
+//!     ["tag:alice", "tag:likes", "tag:tea"],
+//!     ["tag:bob", "tag:knows", "tag:carol"],
+//! ]
+//! .into_iter()
+//! .map(|t| t.map(IriRef::new_unchecked))
+//! .collect::<Vec<_>>();
+//!
+//! let summary = summarize(&graph, IriRef::new_unchecked("tag:alice"), 2)?;
+//! assert!(summary[0][2] == IriRef::new_unchecked("tag:tea"));
+//! assert!(summary[1][2] == IriRef::new_unchecked("tag:carol"));
+//! # Ok(()) }
+//! ```
+use super::*;
+use crate::term::matcher::Any;
+use std::collections::BTreeMap;
+
+/// Return (at most) the `k` most informative triples having `node` as their subject,
+/// by decreasing relevance (see the [module documentation](self)).
+pub fn summarize<G, T>(
+    graph: &G,
+    node: T,
+    k: usize,
+) -> Result<Vec<[SimpleTerm<'static>; 3]>, G::Error>
+where
+    G: Graph,
+    T: Term,
+{
+    let mut total = 0_usize;
+    for t in graph.triples() {
+        t?;
+        total += 1;
+    }
+    let mut candidates = vec![];
+    let mut predicate_counts = BTreeMap::<SimpleTerm<'static>, usize>::new();
+    for t in graph.triples_matching([node.borrow_term()], Any, Any) {
+        let triple: [SimpleTerm<'static>; 3] = t?.to_spo().map(|t| t.into_term());
+        predicate_counts.insert(triple[1].clone(), 0);
+        candidates.push(triple);
+    }
+    for (p, count) in predicate_counts.iter_mut() {
+        *count = count_matching(graph.triples_matching(Any, [p], Any))?;
+    }
+    let mut scored = Vec::with_capacity(candidates.len());
+    for triple in candidates {
+        let popularity = count_matching(graph.triples_matching(Any, Any, [&triple[2]]))?;
+        let frequency = predicate_counts[&triple[1]];
+        let score = (1.0 + popularity as f64).ln() + (total as f64 / frequency as f64).ln();
+        scored.push((score, triple));
+    }
+
+    let mut summary = Vec::with_capacity(k.min(scored.len()));
+    let mut selected = BTreeMap::<SimpleTerm<'static>, i32>::new();
+    while summary.len() < k && !scored.is_empty() {
+        let penalized = |(score, triple): &(f64, [SimpleTerm<'static>; 3])| {
+            score * 0.5_f64.powi(selected.get(&triple[1]).copied().unwrap_or(0))
+        };
+        let best = (1..scored.len()).fold(0, |best, i| {
+            if penalized(&scored[i]) > penalized(&scored[best]) {
+                i
+            } else {
+                best
+            }
+        });
+        let (_, triple) = scored.remove(best);
+        *selected.entry(triple[1].clone()).or_default() += 1;
+        summary.push(triple);
+    }
+    Ok(summary)
+}
+
+fn count_matching<T, E>(triples: impl Iterator<Item = Result<T, E>>) -> Result<usize, E> {
+    let mut count = 0;
+    for t in triples {
+        t?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::term::IriRef;
+
+    type MyGraph = Vec<[SimpleTerm<'static>; 3]>;
+
+    fn iri(txt: &str) -> SimpleTerm<'static> {
+        SimpleTerm::Iri(IriRef::new_unchecked(txt.to_string().into()))
+    }
+
+    fn make_graph() -> MyGraph {
+        let mut g: MyGraph = [
+            ("tag:alice", "tag:type", "tag:Person"),
+            ("tag:bob", "tag:type", "tag:Person"),
+            ("tag:carol", "tag:type", "tag:Person"),
+            ("tag:alice", "tag:knows", "tag:bob"),
+            ("tag:alice", "tag:knows", "tag:carol"),
+            ("tag:alice", "tag:knows", "tag:dave"),
+            ("tag:bob", "tag:knows", "tag:carol"),
+            ("tag:alice", "tag:color", "tag:blue"),
+        ]
+        .into_iter()
+        .map(|(s, p, o)| [iri(s), iri(p), iri(o)])
+        .collect();
+        g.push([iri("tag:alice"), iri("tag:name"), "Alice".into_term()]);
+        g
+    }
+
+    fn names(triples: &[[SimpleTerm<'static>; 3]]) -> Vec<String> {
+        triples
+            .iter()
+            .map(|[_, p, o]| {
+                let o = match o.iri() {
+                    Some(iri) => iri.as_str()[4..].to_string(),
+                    None => o.lexical_form().unwrap().to_string(),
+                };
+                format!("{} {o}", &p.iri().unwrap().as_str()[4..])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn summary() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph();
+        let summary = summarize(&g, iri("tag:alice"), 4)?;
+        assert_eq!(
+            names(&summary),
+            ["color blue", "name Alice", "type Person", "knows carol"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn diversity() -> Result<(), Box<dyn std::error::Error>> {
+        let mut g: MyGraph = vec![];
+        for s in ["alice", "b", "c", "d"] {
+            for o in ["x1", "x2"] {
+                g.push([
+                    iri(&format!("tag:{s}")),
+                    iri("tag:knows"),
+                    iri(&format!("tag:{o}")),
+                ]);
+            }
+        }
+        for (s, o) in [("alice", "blue"), ("e", "red"), ("f", "red"), ("g", "red")] {
+            g.push([
+                iri(&format!("tag:{s}")),
+                iri("tag:color"),
+                iri(&format!("tag:{o}")),
+            ]);
+        }
+        // both 'knows' triples score higher than the 'color' triple,
+        // but the second one is penalized for repeating the same predicate
+        let summary = summarize(&g, iri("tag:alice"), 3)?;
+        assert_eq!(names(&summary), ["knows x1", "color blue", "knows x2"]);
+        Ok(())
+    }
+
+    #[test]
+    fn small_and_empty() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph();
+        assert_eq!(summarize(&g, iri("tag:alice"), 100)?.len(), 6);
+        assert!(summarize(&g, iri("tag:alice"), 0)?.is_empty());
+        assert!(summarize(&g, iri("tag:dave"), 3)?.is_empty());
+        Ok(())
+    }
+}
@@ -13,7 +13,7 @@ pub type StaticTerm = SimpleTerm<'static>;
 #[allow(missing_docs)]
 mod ns {
     use super::*;
-    pub const NS: Namespace<&str> = Namespace::new_unchecked_const("http://example.org/");
+    pub const NS: Namespace<&str> = Namespace::new_const("http://example.org/");
     lazy_static! {
         pub static ref C1: NsTerm<'static> = NS.get("C1").unwrap();
         pub static ref C2: NsTerm<'static> = NS.get("C2").unwrap();
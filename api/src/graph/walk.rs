@@ -0,0 +1,401 @@
+//! I provide [`walk`], a depth-first traversal of a [`Graph`],
+//! reporting what it encounters to a [`Visitor`].
+//!
+//! # Example
+//! ```
+//! # use sophia_api::graph::walk::{walk, Control, Direction, Visitor, WalkConfig};
+//! # use sophia_api::term::{IriRef, SimpleTerm, Term};
+//! # use sophia_api::ns::rdfs;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! struct Printer(Vec<String>);
+//! impl Visitor for Printer {
+//!     fn enter(&mut self, node: &SimpleTerm<'_>, depth: usize) -> Control {
+//!         let iri = node.iri().unwrap();
+//!         self.0.push(format!("{}{}", "  ".repeat(depth), iri.as_str()));
+//!         Control::Continue
+//!     }
+//! }
+//!
+//! let sub_class_of = IriRef::new_unchecked("http://www.w3.org/2000/01/rdf-schema#subClassOf");
+//! let graph = vec![
+//!     ["tag:Cat", "tag:Animal"],
+//!     ["tag:Dog", "tag:Animal"],
+//!     ["tag:Animal", "tag:Thing"],
+//! ]
+//! .into_iter()
+//! .map(|[s, o]| [IriRef::new_unchecked(s), sub_class_of, IriRef::new_unchecked(o)])
+//! .collect::<Vec<_>>();
+//!
+//! // walk the class hierarchy downwards
+//! let config = WalkConfig::new()
+//!     .with_direction(Direction::Backward)
+//!     .with_predicates([rdfs::subClassOf]);
+//! let mut printer = Printer(vec![]);
+//! walk(&graph, IriRef::new_unchecked("tag:Thing"), &config, &mut printer)?;
+//! assert_eq!(printer.0, ["tag:Thing", "  tag:Animal", "    tag:Cat", "    tag:Dog"]);
+//! # Ok(()) }
+//! ```
+use super::*;
+use crate::term::matcher::Any;
+use std::collections::BTreeSet;
+
+/// The direction in which a [`walk`] follows the triples.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    /// From subject to object
+    #[default]
+    Forward,
+    /// From object to subject
+    Backward,
+    /// Both ways
+    Both,
+}
+
+/// Returned by the methods of [`Visitor`] to control the [`walk`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    /// Carry on
+    Continue,
+    /// Do not follow the edges of the node just entered (resp. the edge just reported),
+    /// but carry on with the rest of the walk
+    Skip,
+    /// Stop the walk immediately
+    Stop,
+}
+
+/// Receives the events of a [`walk`].
+///
+/// All methods have a default implementation,
+/// so implementers only need to override those they are interested in.
+pub trait Visitor {
+    /// Called when `node` is reached for the first time, at the given depth.
+    ///
+    /// Every node is entered at most once during a walk.
+    fn enter(&mut self, node: &SimpleTerm<'_>, depth: usize) -> Control {
+        let _ = (node, depth);
+        Control::Continue
+    }
+
+    /// Called for every triple linking the node last entered (and not left yet) to another node,
+    /// in the given `direction` (never [`Direction::Both`]).
+    ///
+    /// This is called even if the other node has already been entered,
+    /// in which case it will not be entered again.
+    fn edge(&mut self, triple: &[SimpleTerm<'_>; 3], direction: Direction) -> Control {
+        let _ = (triple, direction);
+        Control::Continue
+    }
+
+    /// Called when all the edges of `node` have been followed.
+    ///
+    /// NB: if the walk is [stopped](Control::Stop),
+    /// this is not called for the nodes that were entered but not left yet.
+    fn leave(&mut self, node: &SimpleTerm<'_>, depth: usize) {
+        let _ = (node, depth);
+    }
+}
+
+/// [`walk`] configuration.
+#[derive(Clone, Debug, Default)]
+pub struct WalkConfig {
+    direction: Direction,
+    predicates: Option<Vec<SimpleTerm<'static>>>,
+    max_depth: Option<usize>,
+}
+
+impl WalkConfig {
+    /// The direction in which triples are followed.
+    ///
+    /// Default: [`Direction::Forward`]
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// The predicates of the triples to follow.
+    ///
+    /// Default: `None` (all predicates)
+    pub fn predicates(&self) -> Option<&[SimpleTerm<'static>]> {
+        self.predicates.as_deref()
+    }
+
+    /// The maximum depth of the nodes to enter (the start node having depth 0).
+    ///
+    /// Default: `None` (unlimited)
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Build a default config (see individual methods for default values)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Transform a config with the given value for [`direction`](WalkConfig::direction)
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Transform a config with the given value for [`predicates`](WalkConfig::predicates)
+    pub fn with_predicates<I, T>(mut self, predicates: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Term,
+    {
+        self.predicates = Some(predicates.into_iter().map(Term::into_term).collect());
+        self
+    }
+
+    /// Transform a config with the given value for [`max_depth`](WalkConfig::max_depth)
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+}
+
+/// Walk `graph` depth-first from `start`, reporting to `visitor`.
+///
+/// Return `Ok(false)` if the walk was [stopped](Control::Stop) by the visitor,
+/// `Ok(true)` otherwise.
+pub fn walk<G, T, V>(
+    graph: &G,
+    start: T,
+    config: &WalkConfig,
+    visitor: &mut V,
+) -> Result<bool, G::Error>
+where
+    G: Graph,
+    T: Term,
+    V: Visitor + ?Sized,
+{
+    let predicates = config.predicates();
+    let pm = |t: SimpleTerm<'_>| predicates.is_none_or(|ps| ps.iter().any(|p| Term::eq(p, &t)));
+    let can_expand = |depth: usize| config.max_depth.is_none_or(|max| depth < max);
+    let mut visited = BTreeSet::<SimpleTerm<'static>>::new();
+    let mut stack: Vec<Frame<'_, G>> = vec![];
+
+    let start: SimpleTerm<'static> = start.into_term();
+    visited.insert(start.clone());
+    match visitor.enter(&start, 0) {
+        Control::Stop => return Ok(false),
+        control => stack.push(Frame {
+            edges: (control == Control::Continue && can_expand(0))
+                .then(|| edges(graph, &start, config.direction, &pm)),
+            node: start,
+            depth: 0,
+        }),
+    }
+    while let Some(frame) = stack.last_mut() {
+        let depth = frame.depth;
+        let next: Option<(GResult<G, G::Triple<'_>>, Direction)> =
+            frame.edges.as_mut().and_then(Iterator::next);
+        let Some((res, direction)) = next else {
+            let frame = stack.pop().unwrap();
+            visitor.leave(&frame.node, frame.depth);
+            continue;
+        };
+        let [s, p, o] = res?.to_spo();
+        let triple = [s.as_simple(), p.as_simple(), o.as_simple()];
+        match visitor.edge(&triple, direction) {
+            Control::Stop => return Ok(false),
+            Control::Skip => continue,
+            Control::Continue => {}
+        }
+        let [s, _, o] = triple;
+        let target = if direction == Direction::Forward {
+            o
+        } else {
+            s
+        };
+        let visited_ref: &BTreeSet<SimpleTerm<'_>> = &visited;
+        if visited_ref.contains(&target) {
+            continue;
+        }
+        let target: SimpleTerm<'static> = target.into_term();
+        visited.insert(target.clone());
+        match visitor.enter(&target, depth + 1) {
+            Control::Stop => return Ok(false),
+            control => stack.push(Frame {
+                edges: (control == Control::Continue && can_expand(depth + 1))
+                    .then(|| edges(graph, &target, config.direction, &pm)),
+                node: target,
+                depth: depth + 1,
+            }),
+        }
+    }
+    Ok(true)
+}
+
+pub(super) type Edges<'a, G> =
+    Box<dyn Iterator<Item = (GResult<G, <G as Graph>::Triple<'a>>, Direction)> + 'a>;
+
+struct Frame<'a, G: Graph + 'a> {
+    node: SimpleTerm<'static>,
+    depth: usize,
+    edges: Option<Edges<'a, G>>,
+}
+
+/// Iterate over the triples linking `node` to other nodes in the given `direction`.
+pub(super) fn edges<'a, G, P>(
+    graph: &'a G,
+    node: &SimpleTerm<'static>,
+    direction: Direction,
+    pm: &'a P,
+) -> Edges<'a, G>
+where
+    G: Graph,
+    P: TermMatcher,
+{
+    let forward = (direction != Direction::Backward).then(|| {
+        graph
+            .triples_matching([node.clone()], pm.matcher_ref(), Any)
+            .map(|res| (res, Direction::Forward))
+    });
+    let backward = (direction != Direction::Forward).then(|| {
+        graph
+            .triples_matching(Any, pm.matcher_ref(), [node.clone()])
+            .map(|res| (res, Direction::Backward))
+    });
+    Box::new(
+        forward
+            .into_iter()
+            .flatten()
+            .chain(backward.into_iter().flatten()),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::term::IriRef;
+
+    type MyGraph = Vec<[SimpleTerm<'static>; 3]>;
+
+    fn iri(txt: &str) -> SimpleTerm<'static> {
+        SimpleTerm::Iri(IriRef::new_unchecked(txt.to_string().into()))
+    }
+
+    fn make_graph() -> MyGraph {
+        [
+            ("tag:a", "tag:p", "tag:b"),
+            ("tag:a", "tag:p", "tag:c"),
+            ("tag:b", "tag:p", "tag:d"),
+            ("tag:c", "tag:p", "tag:d"),
+            ("tag:d", "tag:q", "tag:e"),
+            ("tag:d", "tag:p", "tag:a"),
+        ]
+        .into_iter()
+        .map(|(s, p, o)| [iri(s), iri(p), iri(o)])
+        .collect()
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+        skip: Option<&'static str>,
+        stop: Option<&'static str>,
+    }
+
+    fn name(t: &SimpleTerm<'_>) -> String {
+        t.iri().unwrap().as_str()[4..].to_string()
+    }
+
+    impl Visitor for Recorder {
+        fn enter(&mut self, node: &SimpleTerm<'_>, depth: usize) -> Control {
+            let name = name(node);
+            self.events.push(format!("+{name}{depth}"));
+            if self.stop == Some(name.as_str()) {
+                Control::Stop
+            } else if self.skip == Some(name.as_str()) {
+                Control::Skip
+            } else {
+                Control::Continue
+            }
+        }
+
+        fn edge(&mut self, triple: &[SimpleTerm<'_>; 3], direction: Direction) -> Control {
+            let arrow = if direction == Direction::Forward {
+                ">"
+            } else {
+                "<"
+            };
+            self.events
+                .push(format!("{}{arrow}{}", name(&triple[0]), name(&triple[2])));
+            Control::Continue
+        }
+
+        fn leave(&mut self, node: &SimpleTerm<'_>, depth: usize) {
+            self.events.push(format!("-{}{depth}", name(node)));
+        }
+    }
+
+    #[test]
+    fn walk_forward() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph();
+        let mut r = Recorder::default();
+        assert!(walk(&g, iri("tag:a"), &WalkConfig::new(), &mut r)?);
+        assert_eq!(
+            r.events,
+            [
+                "+a0", "a>b", "+b1", "b>d", "+d2", "d>e", "+e3", "-e3", "d>a", "-d2", "-b1", "a>c",
+                "+c1", "c>d", "-c1", "-a0"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn walk_backward_with_predicates() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph();
+        let mut r = Recorder::default();
+        let config = WalkConfig::new()
+            .with_direction(Direction::Backward)
+            .with_predicates([iri("tag:p")]);
+        assert!(walk(&g, iri("tag:d"), &config, &mut r)?);
+        assert_eq!(
+            r.events,
+            [
+                "+d0", "b<d", "+b1", "a<b", "+a2", "d<a", "-a2", "-b1", "c<d", "+c1", "a<c", "-c1",
+                "-d0"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn walk_both_max_depth() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph();
+        let mut r = Recorder::default();
+        let config = WalkConfig::new()
+            .with_direction(Direction::Both)
+            .with_max_depth(1);
+        assert!(walk(&g, iri("tag:e"), &config, &mut r)?);
+        assert_eq!(r.events, ["+e0", "d<e", "+d1", "-d1", "-e0"]);
+        Ok(())
+    }
+
+    #[test]
+    fn walk_skip_and_stop() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph();
+        let mut r = Recorder {
+            skip: Some("b"),
+            ..Recorder::default()
+        };
+        assert!(walk(&g, iri("tag:a"), &WalkConfig::new(), &mut r)?);
+        assert_eq!(
+            r.events,
+            [
+                "+a0", "a>b", "+b1", "-b1", "a>c", "+c1", "c>d", "+d2", "d>e", "+e3", "-e3", "d>a",
+                "-d2", "-c1", "-a0"
+            ]
+        );
+
+        let mut r = Recorder {
+            stop: Some("d"),
+            ..Recorder::default()
+        };
+        assert!(!walk(&g, iri("tag:a"), &WalkConfig::new(), &mut r)?);
+        assert_eq!(r.events, ["+a0", "a>b", "+b1", "b>d", "+d2"]);
+        Ok(())
+    }
+}
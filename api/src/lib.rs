@@ -45,6 +45,25 @@
 //!
 //! - **test_macros**: with this feature enabled,
 //!   this crate exposes macros that can help implementors of the API to test their implementation.
+//! - **serde** (enabled by default): with this feature enabled,
+//!   prefixes and [`SimpleTerm`](term::SimpleTerm)s (hence triples and quads made of them)
+//!   can be serialized and deserialized with [`serde`].
+//! - **json**: with this feature enabled,
+//!   `rdf:JSON` literals can be converted to and from [`serde_json`] values (see [`term::json`]).
+//! - **chrono**: with this feature enabled,
+//!   date/time literals can be converted to and from [`chrono`] values (see [`term::datetime`]).
+//! - **bigdecimal**: with this feature enabled,
+//!   `xsd:decimal` and `xsd:integer` literals can be converted to and from
+//!   arbitrary-precision [`bigdecimal`] values (see [`term::decimal`]).
+//! - **uuid**: with this feature enabled,
+//!   blank node identifiers can be generated from random UUIDs
+//!   (see `UuidBnodeIdGenerator` in [`term::bnode_id`]).
+//! - **testing**: with this feature enabled,
+//!   `proptest` strategies are provided to generate terms, triples and graphs
+//!   (see `testing`).
+//! - **telemetry**: with this feature enabled,
+//!   the mutations of a dataset can be reported to an exporter,
+//!   e.g. as OpenTelemetry log records (see `dataset::telemetry`).
 //!
 //! [SPARQL]: https://www.w3.org/TR/sparql11-query/
 //! [Notation3]: https://www.w3.org/TeamSubmission/n3/
@@ -61,7 +80,10 @@ pub mod serializer;
 pub mod source;
 pub mod sparql;
 pub mod term;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod triple;
+pub mod warning;
 
 /// Re-export MownStr to avoid dependency version mismatch.
 ///
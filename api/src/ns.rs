@@ -3,6 +3,8 @@
 //! This module provides:
 //! * the [`Namespace`](struct.Namespace.html) type for defining custom dynamic namespace;
 //! * the [`namespace`](crate::namespace) macro, for defning custom static namespaces;
+//! * the [`const_namespace`](crate::const_namespace) macro, for defining custom static namespaces
+//!   whose IRIs are checked at compile time;
 //! * modules corresponding to the most common namespaces
 //!   (generated via the [`namespace`](crate::namespace) macro).
 //!
@@ -199,12 +201,15 @@ pub mod owl {
         AllDifferent,
         AllDisjointClasses,
         AnnotationProperty,
+        AsymmetricProperty,
         Class,
         DatatypeProperty,
         FunctionalProperty,
         InverseFunctionalProperty,
         IrreflexiveProperty,
         ObjectProperty,
+        Ontology,
+        ReflexiveProperty,
         SymmetricProperty,
         TransitiveProperty,
         // Properties
@@ -216,6 +221,7 @@ pub mod owl {
         distinctMembers,
         equivalentClass,
         equivalentProperty,
+        imports,
         intersectionOf,
         inverseOf,
         maxCardinality,
@@ -231,7 +237,398 @@ pub mod owl {
         sourceIndividual,
         targetIndividual,
         targetValue,
-        unionOf
+        unionOf,
+        versionIRI
+    );
+}
+
+/// The standard `skos:` namespace
+pub mod skos {
+    namespace!(
+        "http://www.w3.org/2004/02/skos/core#",
+        // classes
+        Collection,
+        Concept,
+        ConceptScheme,
+        OrderedCollection,
+        // labels and notations
+        altLabel,
+        hiddenLabel,
+        notation,
+        prefLabel,
+        // documentation properties
+        changeNote,
+        definition,
+        editorialNote,
+        example,
+        historyNote,
+        note,
+        scopeNote,
+        // semantic relations
+        broader,
+        broaderTransitive,
+        narrower,
+        narrowerTransitive,
+        related,
+        semanticRelation,
+        // concept schemes
+        hasTopConcept,
+        inScheme,
+        topConceptOf,
+        // collections
+        member,
+        memberList,
+        // mapping properties
+        broadMatch,
+        closeMatch,
+        exactMatch,
+        mappingRelation,
+        narrowMatch,
+        relatedMatch
+    );
+}
+
+/// The standard `foaf:` namespace
+pub mod foaf {
+    namespace!(
+        "http://xmlns.com/foaf/0.1/",
+        // classes
+        Agent,
+        Document,
+        Group,
+        Image,
+        OnlineAccount,
+        Organization,
+        Person,
+        PersonalProfileDocument,
+        Project,
+        // properties
+        account,
+        accountName,
+        age,
+        based_near,
+        birthday,
+        depiction,
+        depicts,
+        familyName,
+        firstName,
+        gender,
+        givenName,
+        homepage,
+        img,
+        interest,
+        isPrimaryTopicOf,
+        knows,
+        lastName,
+        logo,
+        made,
+        maker,
+        mbox,
+        mbox_sha1sum,
+        member,
+        name,
+        nick,
+        openid,
+        page,
+        phone,
+        primaryTopic,
+        publications,
+        surname,
+        thumbnail,
+        title,
+        topic,
+        weblog,
+        workplaceHomepage
+    );
+}
+
+/// The standard `dcterms:` namespace (DCMI Metadata Terms)
+///
+/// NB: since `abstract` and `type` are reserved keywords in Rust,
+/// the terms `dcterms:abstract` and `dcterms:type` spell
+/// `dcterms::abstract_` and `dcterms::type_` (with a trailing underscore).
+pub mod dcterms {
+    namespace!(
+        "http://purl.org/dc/terms/",
+        // classes
+        Agent,
+        AgentClass,
+        BibliographicResource,
+        FileFormat,
+        Frequency,
+        Jurisdiction,
+        LicenseDocument,
+        LinguisticSystem,
+        Location,
+        LocationPeriodOrJurisdiction,
+        MediaType,
+        MediaTypeOrExtent,
+        MethodOfAccrual,
+        MethodOfInstruction,
+        PeriodOfTime,
+        PhysicalMedium,
+        PhysicalResource,
+        Policy,
+        ProvenanceStatement,
+        RightsStatement,
+        SizeOrDuration,
+        Standard,
+        // properties
+        accessRights,
+        accrualMethod,
+        accrualPeriodicity,
+        accrualPolicy,
+        alternative,
+        audience,
+        available,
+        bibliographicCitation,
+        conformsTo,
+        contributor,
+        coverage,
+        created,
+        creator,
+        date,
+        dateAccepted,
+        dateCopyrighted,
+        dateSubmitted,
+        description,
+        educationLevel,
+        extent,
+        format,
+        hasFormat,
+        hasPart,
+        hasVersion,
+        identifier,
+        instructionalMethod,
+        isFormatOf,
+        isPartOf,
+        isReferencedBy,
+        isReplacedBy,
+        isRequiredBy,
+        isVersionOf,
+        issued,
+        language,
+        license,
+        mediator,
+        medium,
+        modified,
+        provenance,
+        publisher,
+        references,
+        relation,
+        replaces,
+        requires,
+        rights,
+        rightsHolder,
+        source,
+        spatial,
+        subject,
+        tableOfContents,
+        temporal,
+        title,
+        valid;
+        // 'abstract' and 'type' are Rust keywords, so we use 'abstract_' and 'type_' instead
+        abstract_, "abstract",
+        type_, "type"
+    );
+}
+
+/// The standard `sh:` namespace (SHACL)
+///
+/// NB: since `in` is a reserved keyword in Rust,
+/// the term `sh:in` spells `sh::in_` (with a trailing underscore).
+pub mod sh {
+    namespace!(
+        "http://www.w3.org/ns/shacl#",
+        // shapes
+        NodeShape,
+        PropertyShape,
+        Shape,
+        // node kinds
+        BlankNode,
+        BlankNodeOrIRI,
+        BlankNodeOrLiteral,
+        IRI,
+        IRIOrLiteral,
+        Literal,
+        NodeKind,
+        // validation reports
+        Info,
+        Severity,
+        ValidationReport,
+        ValidationResult,
+        Violation,
+        Warning,
+        conforms,
+        focusNode,
+        result,
+        resultMessage,
+        resultPath,
+        resultSeverity,
+        sourceConstraintComponent,
+        sourceShape,
+        value,
+        // targets
+        targetClass,
+        targetNode,
+        targetObjectsOf,
+        targetSubjectsOf,
+        // property paths
+        alternativePath,
+        inversePath,
+        oneOrMorePath,
+        path,
+        zeroOrMorePath,
+        zeroOrOnePath,
+        // constraints
+        and,
+        class,
+        closed,
+        datatype,
+        deactivated,
+        disjoint,
+        equals,
+        hasValue,
+        ignoredProperties,
+        languageIn,
+        lessThan,
+        lessThanOrEquals,
+        maxCount,
+        maxExclusive,
+        maxInclusive,
+        maxLength,
+        minCount,
+        minExclusive,
+        minInclusive,
+        minLength,
+        node,
+        nodeKind,
+        not,
+        or,
+        pattern,
+        flags,
+        property,
+        qualifiedMaxCount,
+        qualifiedMinCount,
+        qualifiedValueShape,
+        uniqueLang,
+        xone,
+        // non-validating properties
+        defaultValue,
+        description,
+        group,
+        message,
+        name,
+        order,
+        severity,
+        // shapes graphs
+        shapesGraph,
+        suggestedShapesGraph;
+        // 'in' is a Rust keyword, so we use 'in_' instead
+        in_, "in"
+    );
+}
+
+/// The standard `prov:` namespace (PROV-O)
+pub mod prov {
+    namespace!(
+        "http://www.w3.org/ns/prov#",
+        // starting point classes
+        Activity,
+        Agent,
+        Entity,
+        // expanded classes
+        Bundle,
+        Collection,
+        EmptyCollection,
+        Location,
+        Organization,
+        Person,
+        Plan,
+        SoftwareAgent,
+        // qualified classes
+        Association,
+        Attribution,
+        Communication,
+        Delegation,
+        Derivation,
+        End,
+        Generation,
+        Invalidation,
+        Role,
+        Start,
+        Usage,
+        // starting point properties
+        actedOnBehalfOf,
+        endedAtTime,
+        startedAtTime,
+        used,
+        wasAssociatedWith,
+        wasAttributedTo,
+        wasDerivedFrom,
+        wasGeneratedBy,
+        wasInformedBy,
+        // expanded properties
+        atLocation,
+        generated,
+        generatedAtTime,
+        hadMember,
+        hadPrimarySource,
+        invalidated,
+        invalidatedAtTime,
+        value,
+        wasEndedBy,
+        wasInfluencedBy,
+        wasInvalidatedBy,
+        wasQuotedFrom,
+        wasRevisionOf,
+        wasStartedBy,
+        // qualified properties
+        activity,
+        agent,
+        atTime,
+        entity,
+        hadPlan,
+        hadRole,
+        qualifiedAssociation,
+        qualifiedAttribution,
+        qualifiedDerivation,
+        qualifiedGeneration,
+        qualifiedUsage
+    );
+}
+
+/// The standard `geo:` namespace (GeoSPARQL)
+pub mod geo {
+    namespace!(
+        "http://www.opengis.net/ont/geosparql#",
+        // classes
+        Feature,
+        Geometry,
+        SpatialObject,
+        // datatypes
+        gmlLiteral,
+        wktLiteral,
+        // properties
+        asGML,
+        asWKT,
+        coordinateDimension,
+        dimension,
+        hasDefaultGeometry,
+        hasGeometry,
+        hasSerialization,
+        isEmpty,
+        isSimple,
+        spatialDimension,
+        // topological relations (simple features)
+        sfContains,
+        sfCrosses,
+        sfDisjoint,
+        sfEquals,
+        sfIntersects,
+        sfOverlaps,
+        sfTouches,
+        sfWithin
     );
 }
 
@@ -241,6 +638,54 @@ mod test {
     use super::*;
     use std::rc::Rc;
 
+    mod ex {
+        const_namespace!(
+            "http://example.org/ns#",
+            Foo,
+            Bar;
+            type_, "type",
+            empty, ""
+        );
+    }
+
+    #[test]
+    fn test_const_namespace() {
+        use crate::term::Term;
+        assert_eq!(ex::PREFIX.as_str(), "http://example.org/ns#");
+        assert_eq!(ex::Foo.to_string(), "http://example.org/ns#Foo");
+        assert_eq!(ex::type_.to_string(), "http://example.org/ns#type");
+        assert!(Term::eq(&ex::empty, ex::PREFIX));
+        // terms are constants, so they can be used in other constants
+        const TERMS: [NsTerm; 2] = [ex::Foo, ex::Bar];
+        assert!(Term::eq(
+            &TERMS[1],
+            IriRef::new_unchecked("http://example.org/ns#Bar")
+        ));
+    }
+
+    #[test]
+    fn test_iter() {
+        use crate::term::Term;
+        assert_eq!(rdfs::TERMS.len(), rdfs::iter().count());
+        assert!(rdfs::iter().all(|t| t.ns() == rdfs::PREFIX));
+        assert!(rdf::iter().any(|t| Term::eq(&t, rdf::type_)));
+        let suffixes: Vec<_> = ex::iter().map(|t| t.suffix()).collect();
+        assert_eq!(suffixes, ["Foo", "Bar", "type", ""]);
+    }
+
+    #[test]
+    fn test_term_by_suffix() {
+        use crate::term::Term;
+        assert!(Term::eq(&rdf::term_by_suffix("type").unwrap(), rdf::type_));
+        assert!(Term::eq(
+            &xsd::term_by_suffix("integer").unwrap(),
+            xsd::integer
+        ));
+        assert!(Term::eq(&ex::term_by_suffix("").unwrap(), ex::empty));
+        assert!(rdf::term_by_suffix("type_").is_none());
+        assert!(rdf::term_by_suffix("unknown").is_none());
+    }
+
     #[test]
     fn test_same_term() {
         let ns1 = Namespace::new("http://schema.org/").unwrap();
@@ -23,17 +23,50 @@
 /// assert_eq!(ex::Foo, IriRef::new_unchecked("http://example.org/ns#Foo"));
 /// assert_eq!(ex::Bar, IriRef::new_unchecked("http://example.org/ns#Bar"));
 /// ```
+///
+/// # Iteration and reverse lookup
+/// The generated module also contains
+/// a `TERMS` slice and an `iter` function, listing all the terms of the namespace,
+/// and a `term_by_suffix` function, retrieving a term from its suffix.
+/// This allows to write generic tools (validators, documentation generators...) over namespace modules.
+/// ```
+/// # #[macro_use] extern crate sophia_api;
+/// # pub mod ex { namespace! { "http://example.org/ns#", Foo, Bar; type_, "type" } }
+/// assert_eq!(ex::TERMS.len(), 3);
+/// let suffixes: Vec<_> = ex::iter().map(|t| t.suffix()).collect();
+/// assert_eq!(suffixes, ["Foo", "Bar", "type"]);
+/// assert_eq!(ex::term_by_suffix("type").unwrap(), ex::type_);
+/// assert!(ex::term_by_suffix("type_").is_none());
+/// ```
 #[macro_export]
 macro_rules! namespace {
+    (@lookup $kind:tt, $($ident:ident,)*) => {
+        /// All the terms of this namespace, in the order of their declaration.
+        pub $kind TERMS: &[$crate::ns::NsTerm<'static>] = &[$($ident),*];
+
+        /// Iterate over all the terms of this namespace, in the order of their declaration.
+        pub fn iter() -> impl Iterator<Item = $crate::ns::NsTerm<'static>> {
+            TERMS.iter().copied()
+        }
+
+        /// Get the term of this namespace with the given suffix, if any.
+        ///
+        /// NB: the suffix is the one used in the IRI, which may differ from the name of the Rust item
+        /// (e.g. `"type"` for `type_`).
+        pub fn term_by_suffix(suffix: &str) -> Option<$crate::ns::NsTerm<'static>> {
+            TERMS.iter().find(|t| t.suffix() == suffix).copied()
+        }
+    };
     ($iri_prefix:expr, $($suffix:ident),*; $($r_id:ident, $r_sf:expr),*) => {
         /// Prefix used in this namespace.
-        pub static PREFIX: $crate::ns::IriRef<&'static str> = $crate::ns::IriRef::new_unchecked_const($iri_prefix);
+        pub static PREFIX: $crate::ns::IriRef<&'static str> = $crate::ns::IriRef::new_const($iri_prefix);
         $(
             $crate::ns_iri!(PREFIX, $suffix);
         )*
         $(
             $crate::ns_iri!(PREFIX, $r_id, $r_sf);
         )*
+        $crate::namespace!(@lookup static, $($suffix,)* $($r_id,)*);
 
         /// Test module for checking that IRIs are valid
         #[cfg(test)]
@@ -67,6 +100,73 @@ macro_rules! namespace {
     };
 }
 
+/// Create a "namespace module"
+/// defining a set of `const` terms within a given IRI space,
+/// all checked at compile time.
+///
+/// Contrarily to [`namespace!`](macro.namespace.html),
+/// which checks the generated IRIs in a test module,
+/// invalid IRIs are rejected by the compiler
+/// (see [`IriRef::new_const`](crate::ns::IriRef::new_const)).
+/// The downside is that the IRI prefix must be a string literal
+/// (not any constant expression).
+///
+/// # Example
+/// ```
+/// # #[macro_use] extern crate sophia_api;
+/// # use sophia_iri::IriRef;
+/// /// An example namespace module
+/// pub mod ex {
+///   const_namespace! {
+///     "http://example.org/ns#",
+///     Foo,
+///     Bar;
+///     type_, "type"
+///   }
+/// }
+///
+/// assert_eq!(ex::Foo, IriRef::new_unchecked("http://example.org/ns#Foo"));
+/// assert_eq!(ex::type_, IriRef::new_unchecked("http://example.org/ns#type"));
+/// ```
+///
+/// Invalid IRIs are rejected by the compiler:
+/// ```compile_fail
+/// # #[macro_use] extern crate sophia_api;
+/// pub mod ex {
+///   const_namespace! {
+///     "http://example.org/ns#",
+///     Foo;
+///     bad, "not valid"
+///   }
+/// }
+/// ```
+#[macro_export]
+macro_rules! const_namespace {
+    ($iri_prefix:literal, $($suffix:ident),*; $($r_id:ident, $r_sf:literal),*) => {
+        /// Prefix used in this namespace.
+        pub const PREFIX: $crate::ns::IriRef<&'static str> = $crate::ns::IriRef::new_const($iri_prefix);
+        $(
+            $crate::const_namespace!(@term $iri_prefix, $suffix, stringify!($suffix));
+        )*
+        $(
+            $crate::const_namespace!(@term $iri_prefix, $r_id, $r_sf);
+        )*
+        $crate::namespace!(@lookup const, $($suffix,)* $($r_id,)*);
+    };
+    ($iri_prefix:literal, $($suffix:ident),*) => {
+        $crate::const_namespace!($iri_prefix, $($suffix),*;);
+    };
+    (@term $iri_prefix:literal, $ident:ident, $($suffix:tt)+) => {
+        /// Generated term.
+        #[allow(non_upper_case_globals)]
+        pub const $ident: $crate::ns::NsTerm<'static> = {
+            // free constants are always evaluated, so this fails to compile if the IRI is invalid
+            let _ = $crate::ns::IriRef::new_const(concat!($iri_prefix, $($suffix)+));
+            $crate::ns::NsTerm::new_unchecked(PREFIX, $($suffix)+)
+        };
+    };
+}
+
 /// Create a term in a "namespace module".
 /// In general, you should use the [`namespace!`](macro.namespace.html) macro instead.
 ///
@@ -50,6 +50,16 @@ impl<T: Borrow<str>> Namespace<T> {
 }
 
 impl Namespace<&'static str> {
+    /// `const` constructor for [`Namespace`],
+    /// checking at compile time (when used in a `const` or `static` declaration)
+    /// that `iri` is a valid IRI reference (possibly relative).
+    ///
+    /// # Panics
+    /// If `iri` is not a valid IRI reference.
+    pub const fn new_const(iri: &'static str) -> Self {
+        Namespace(IriRef::new_const(iri))
+    }
+
     /// `const` constructor for [`Namespace`]
     ///
     ///  # Precondition
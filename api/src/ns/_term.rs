@@ -27,6 +27,16 @@ impl<'a> NsTerm<'a> {
         NsTerm { ns, suffix }
     }
 
+    /// The namespace of this term.
+    pub const fn ns(&self) -> IriRef<&'a str> {
+        self.ns
+    }
+
+    /// The suffix of this term, appended to its [namespace](NsTerm::ns) to form its IRI.
+    pub const fn suffix(&self) -> &'a str {
+        self.suffix
+    }
+
     /// Return an [`IriRef`] representing this term.
     pub fn iriref(&self) -> IriRef<MownStr> {
         IriRef::new_unchecked(if self.suffix.is_empty() {
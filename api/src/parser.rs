@@ -2,6 +2,8 @@
 
 use crate::source::{QuadSource, TripleSource};
 
+pub mod encoding;
+
 /// A parser takes some data of type `T`,
 /// and returns a [`TripleSource`].
 pub trait TripleParser<T> {
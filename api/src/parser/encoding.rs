@@ -0,0 +1,361 @@
+//! I provide [`EncodingFixer`], a [`BufRead`] adapter
+//! fixing common encoding problems of legacy RDF files on the fly.
+//!
+//! All the concrete syntaxes of RDF require UTF-8,
+//! but real-world dumps sometimes start with a byte-order mark (BOM),
+//! or are (partly) encoded in Latin-1 (ISO-8859-1).
+//! Parsers reject such input with an error that does not explain the actual problem.
+//! Wrapping the input in an [`EncodingFixer`] before passing it to a parser
+//! makes it acceptable, while reporting the fixes through a callback.
+//!
+//! # Example
+//! ```
+//! # use sophia_api::parser::encoding::{EncodingConfig, EncodingWarning, InputEncoding};
+//! # use std::io::Read;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // "café" with a BOM, followed by "thé" in Latin-1
+//! let input: &[u8] = b"\xEF\xBB\xBFcaf\xC3\xA9 th\xE9";
+//! let mut warnings = vec![];
+//! let mut fixed = EncodingConfig::new()
+//!     .with_encoding(InputEncoding::Utf8OrLatin1)
+//!     .wrap_with_callback(input, |w| warnings.push(w));
+//! let mut txt = String::new();
+//! fixed.read_to_string(&mut txt)?;
+//! drop(fixed);
+//! assert_eq!(txt, "café thé");
+//! assert_eq!(
+//!     warnings,
+//!     vec![
+//!         EncodingWarning::BomSkipped,
+//!         EncodingWarning::Latin1Byte { offset: 11, byte: 0xE9 },
+//!     ],
+//! );
+//! # Ok(()) }
+//! ```
+use std::io::{self, BufRead, Read};
+
+/// How the input of an [`EncodingFixer`] is expected to be encoded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InputEncoding {
+    /// The input is passed as is (invalid UTF-8 will be rejected by the parser).
+    #[default]
+    Utf8,
+    /// The input is entirely transcoded from Latin-1 to UTF-8.
+    Latin1,
+    /// Valid UTF-8 sequences are passed as is,
+    /// and any other byte is transcoded from Latin-1
+    /// (reporting an [`EncodingWarning::Latin1Byte`]).
+    Utf8OrLatin1,
+}
+
+/// A problem fixed by an [`EncodingFixer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EncodingWarning {
+    /// A UTF-8 byte-order mark was skipped at the start of the input.
+    BomSkipped,
+    /// A byte that was not part of a valid UTF-8 sequence was transcoded from Latin-1.
+    Latin1Byte {
+        /// The position of that byte in the input
+        offset: u64,
+        /// The value of that byte
+        byte: u8,
+    },
+}
+
+/// [`EncodingFixer`] configuration.
+#[derive(Clone, Debug)]
+pub struct EncodingConfig {
+    skip_bom: bool,
+    encoding: InputEncoding,
+}
+
+impl EncodingConfig {
+    /// Should a UTF-8 byte-order mark at the start of the input be skipped.
+    /// (defaults to true)
+    pub fn skip_bom(&self) -> bool {
+        self.skip_bom
+    }
+
+    /// How the input is expected to be encoded.
+    /// (defaults to [`InputEncoding::Utf8`])
+    pub fn encoding(&self) -> InputEncoding {
+        self.encoding
+    }
+
+    /// Build a new default [`EncodingConfig`].
+    pub fn new() -> Self {
+        EncodingConfig {
+            skip_bom: true,
+            encoding: InputEncoding::Utf8,
+        }
+    }
+
+    /// Transform an [`EncodingConfig`] by setting the [`skip_bom`][`EncodingConfig::skip_bom`] flag.
+    pub fn with_skip_bom(mut self, b: bool) -> Self {
+        self.skip_bom = b;
+        self
+    }
+
+    /// Transform an [`EncodingConfig`] by setting the [`encoding`][`EncodingConfig::encoding`].
+    pub fn with_encoding(mut self, encoding: InputEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Wrap `inner` in an [`EncodingFixer`] with this configuration,
+    /// ignoring warnings.
+    pub fn wrap<R: BufRead>(self, inner: R) -> EncodingFixer<R, fn(EncodingWarning)> {
+        self.wrap_with_callback(inner, ignore as fn(EncodingWarning))
+    }
+
+    /// Wrap `inner` in an [`EncodingFixer`] with this configuration,
+    /// calling `on_warning` on every fixed problem.
+    pub fn wrap_with_callback<R, F>(self, inner: R, on_warning: F) -> EncodingFixer<R, F>
+    where
+        R: BufRead,
+        F: FnMut(EncodingWarning),
+    {
+        EncodingFixer {
+            inner,
+            config: self,
+            on_warning,
+            out: Vec::new(),
+            pos: 0,
+            pending: Vec::new(),
+            offset: 0,
+            started: false,
+        }
+    }
+}
+
+fn ignore(_: EncodingWarning) {}
+
+impl Default for EncodingConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// I wrap a [`BufRead`], and fix its encoding on the fly,
+/// according to an [`EncodingConfig`].
+///
+/// It is built with [`EncodingConfig::wrap`] or [`EncodingConfig::wrap_with_callback`],
+/// and reports every fixed problem as an [`EncodingWarning`] to its callback.
+pub struct EncodingFixer<R, F> {
+    inner: R,
+    config: EncodingConfig,
+    on_warning: F,
+    /// Fixed bytes, not consumed yet
+    out: Vec<u8>,
+    /// Position of the first non-consumed byte in `out`
+    pos: usize,
+    /// Bytes read from `inner`, but not processed yet
+    /// (e.g. an incomplete UTF-8 sequence at the end of the previous chunk)
+    pending: Vec<u8>,
+    /// Offset (in `inner`) of the first byte of `pending`
+    offset: u64,
+    started: bool,
+}
+
+/// The UTF-8 byte-order mark.
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+impl<R, F> EncodingFixer<R, F>
+where
+    R: BufRead,
+    F: FnMut(EncodingWarning),
+{
+    /// Unwrap the inner reader.
+    ///
+    /// NB: any data read from it but not consumed yet is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Fill `self.out` with the next fixed bytes (leaving it empty only at the end of the input).
+    fn fill_out(&mut self) -> io::Result<()> {
+        self.out.clear();
+        self.pos = 0;
+        while self.out.is_empty() {
+            let chunk = self.inner.fill_buf()?;
+            let eof = chunk.is_empty();
+            self.pending.extend_from_slice(chunk);
+            let len = chunk.len();
+            self.inner.consume(len);
+            if !self.started {
+                if self.pending.len() < BOM.len() && !eof {
+                    continue;
+                }
+                self.started = true;
+                if self.config.skip_bom && self.pending.starts_with(BOM) {
+                    self.pending.drain(..BOM.len());
+                    self.offset += BOM.len() as u64;
+                    (self.on_warning)(EncodingWarning::BomSkipped);
+                }
+            }
+            self.process(eof);
+            if eof {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Move the bytes of `self.pending` to `self.out`, fixing them if required.
+    ///
+    /// Unless `eof` is true, an incomplete UTF-8 sequence at the end of `self.pending` is kept there.
+    fn process(&mut self, eof: bool) {
+        let mut done = 0;
+        match self.config.encoding {
+            InputEncoding::Utf8 => {
+                self.out.extend_from_slice(&self.pending);
+                done = self.pending.len();
+            }
+            InputEncoding::Latin1 => {
+                for b in &self.pending {
+                    push_latin1(&mut self.out, *b);
+                }
+                done = self.pending.len();
+            }
+            InputEncoding::Utf8OrLatin1 => {
+                while done < self.pending.len() {
+                    match std::str::from_utf8(&self.pending[done..]) {
+                        Ok(_) => {
+                            self.out.extend_from_slice(&self.pending[done..]);
+                            done = self.pending.len();
+                        }
+                        Err(e) => {
+                            let valid = done + e.valid_up_to();
+                            self.out.extend_from_slice(&self.pending[done..valid]);
+                            done = valid;
+                            if e.error_len().is_none() && !eof {
+                                break;
+                            }
+                            let byte = self.pending[done];
+                            push_latin1(&mut self.out, byte);
+                            (self.on_warning)(EncodingWarning::Latin1Byte {
+                                offset: self.offset + done as u64,
+                                byte,
+                            });
+                            done += 1;
+                        }
+                    }
+                }
+            }
+        }
+        self.pending.drain(..done);
+        self.offset += done as u64;
+    }
+}
+
+/// Push the UTF-8 encoding of the Latin-1 byte `b`.
+fn push_latin1(out: &mut Vec<u8>, b: u8) {
+    if b < 0x80 {
+        out.push(b);
+    } else {
+        out.push(0xC0 | (b >> 6));
+        out.push(0x80 | (b & 0x3F));
+    }
+}
+
+impl<R, F> Read for EncodingFixer<R, F>
+where
+    R: BufRead,
+    F: FnMut(EncodingWarning),
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl<R, F> BufRead for EncodingFixer<R, F>
+where
+    R: BufRead,
+    F: FnMut(EncodingWarning),
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos >= self.out.len() {
+            self.fill_out()?;
+        }
+        Ok(&self.out[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.out.len());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fix(input: &[u8], config: EncodingConfig) -> (String, Vec<EncodingWarning>) {
+        let mut warnings = vec![];
+        let mut txt = String::new();
+        // a tiny buffer, to check that sequences split across chunks are handled properly
+        let input = io::BufReader::with_capacity(1, input);
+        config
+            .wrap_with_callback(input, |w| warnings.push(w))
+            .read_to_string(&mut txt)
+            .unwrap();
+        (txt, warnings)
+    }
+
+    #[test]
+    fn bom() {
+        let input = b"\xEF\xBB\xBF<tag:s> <tag:p> \"\xC3\xA9\" .";
+        let (txt, warnings) = fix(input, EncodingConfig::new());
+        assert_eq!(txt, "<tag:s> <tag:p> \"é\" .");
+        assert_eq!(warnings, vec![EncodingWarning::BomSkipped]);
+
+        let (txt, warnings) = fix(input, EncodingConfig::new().with_skip_bom(false));
+        assert_eq!(txt, "\u{FEFF}<tag:s> <tag:p> \"é\" .");
+        assert!(warnings.is_empty());
+
+        for input in [&b""[..], &b"a"[..], &b"\xEF\xBB"[..]] {
+            let mut txt = vec![];
+            EncodingConfig::new()
+                .wrap(input)
+                .read_to_end(&mut txt)
+                .unwrap();
+            assert_eq!(&txt, input);
+        }
+    }
+
+    #[test]
+    fn latin1() {
+        let (txt, warnings) = fix(
+            b"caf\xE9 \xC3\xA9",
+            EncodingConfig::new().with_encoding(InputEncoding::Latin1),
+        );
+        assert_eq!(txt, "café Ã©");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn utf8_or_latin1() {
+        let (txt, warnings) = fix(
+            b"\xEF\xBB\xBFcaf\xC3\xA9 th\xE9 \xE2\x82\xAC \xC3",
+            EncodingConfig::new().with_encoding(InputEncoding::Utf8OrLatin1),
+        );
+        assert_eq!(txt, "café thé € Ã");
+        assert_eq!(
+            warnings,
+            vec![
+                EncodingWarning::BomSkipped,
+                EncodingWarning::Latin1Byte {
+                    offset: 11,
+                    byte: 0xE9
+                },
+                EncodingWarning::Latin1Byte {
+                    offset: 17,
+                    byte: 0xC3
+                },
+            ]
+        );
+    }
+}
@@ -3,6 +3,8 @@
 
 mod _error;
 pub use _error::*;
+mod _ordered_prefix_map;
+pub use _ordered_prefix_map::*;
 mod _prefix_map;
 pub use _prefix_map::*;
 mod _regex;
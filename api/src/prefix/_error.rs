@@ -4,3 +4,34 @@ use thiserror::Error;
 #[derive(Debug, Error)]
 #[error("The given prefix '{0}' does not match PN_PREFIX?")]
 pub struct InvalidPrefix(pub String);
+
+/// This error is raised by [`OrderedPrefixMap`](super::OrderedPrefixMap).
+#[derive(Debug, Error)]
+pub enum PrefixMapError {
+    /// A prefix was bound to a namespace while already bound to another one
+    #[error("Prefix '{prefix}:' is bound to <{existing}>, can not bind it to <{new}>")]
+    Conflict {
+        /// The conflicting prefix
+        prefix: String,
+        /// The namespace that the prefix is already bound to
+        existing: String,
+        /// The namespace that the prefix could not be bound to
+        new: String,
+    },
+    /// A prefixed name used an unknown prefix
+    #[error("Unknown prefix '{0}:'")]
+    UnknownPrefix(String),
+    /// A prefixed name was syntactically invalid
+    #[error("Invalid prefixed name '{0}'")]
+    InvalidPrefixedName(String),
+    /// A sequence of prefix declarations could not be parsed
+    #[error("Syntax error at line {line}, column {column}: {message}")]
+    Syntax {
+        /// The (1-based) number of the line where the error occurred
+        line: usize,
+        /// The (1-based) position in that line, in bytes, where the error occurred
+        column: usize,
+        /// A description of the error
+        message: String,
+    },
+}
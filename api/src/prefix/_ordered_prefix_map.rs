@@ -0,0 +1,484 @@
+//! I define [`OrderedPrefixMap`], an owned implementation of [`PrefixMap`].
+use super::*;
+use mownstr::MownStr;
+use sophia_iri::{Iri, IsIri};
+use std::fmt;
+use std::str::FromStr;
+
+/// An owned [`PrefixMap`], preserving the order in which prefixes were inserted
+/// (e.g. to serialize them in that order).
+///
+/// Each prefix is bound to at most one namespace:
+/// [`insert`](OrderedPrefixMap::insert) and [`merge`](OrderedPrefixMap::merge)
+/// fail rather than silently rebinding a prefix,
+/// while [`set`](OrderedPrefixMap::set) explicitly replaces the previous binding.
+///
+/// [`compact`](OrderedPrefixMap::compact) picks the longest matching namespace,
+/// and [`expand`](OrderedPrefixMap::expand) is its inverse.
+///
+/// An [`OrderedPrefixMap`] can be parsed from a sequence of Turtle or SPARQL prefix declarations,
+/// and is displayed as a sequence of Turtle prefix declarations.
+///
+/// # Example
+/// ```
+/// # use sophia_api::prefix::OrderedPrefixMap;
+/// # use sophia_api::term::Term;
+/// # use sophia_iri::Iri;
+/// let map: OrderedPrefixMap = r#"
+///     @prefix ex: <http://example.org/> .
+///     PREFIX exa: <http://example.org/a/>
+/// "#.parse()?;
+/// let iri = Iri::new_unchecked("http://example.org/a/b");
+/// assert_eq!(map.compact(iri).unwrap(), "exa:b");
+/// assert_eq!(map.expand("exa:b")?, iri);
+/// assert_eq!(iri.display_turtle(&map).to_string(), "exa:b");
+/// assert_eq!(
+///     map.to_string(),
+///     "@prefix ex: <http://example.org/> .\n@prefix exa: <http://example.org/a/> .\n",
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OrderedPrefixMap(Vec<PrefixMapPair>);
+
+impl OrderedPrefixMap {
+    /// Build an empty [`OrderedPrefixMap`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of prefixes in this map.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether this map contains no prefix.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The (prefix, namespace) pairs of this map, in the order of their insertion.
+    pub fn as_slice(&self) -> &[PrefixMapPair] {
+        &self.0
+    }
+
+    /// Bind `prefix` to `namespace`.
+    ///
+    /// Fails with [`PrefixMapError::Conflict`] if `prefix` is already bound to another namespace
+    /// (binding it again to the same namespace has no effect).
+    pub fn insert<P: IsPrefix, N: IsIri>(
+        &mut self,
+        prefix: P,
+        namespace: N,
+    ) -> Result<(), PrefixMapError> {
+        let (prefix, namespace) = (prefix.borrow(), namespace.borrow());
+        self.check(prefix, namespace)?;
+        if self.get_namespace(prefix).is_none() {
+            self.0.push((
+                Prefix::new_unchecked(prefix.into()),
+                Iri::new_unchecked(namespace.into()),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Bind `prefix` to `namespace`, returning the namespace it was previously bound to, if any.
+    ///
+    /// A rebound prefix keeps its original position in the map.
+    pub fn set<P: IsPrefix, N: IsIri>(&mut self, prefix: P, namespace: N) -> Option<Iri<Box<str>>> {
+        let (prefix, namespace) = (prefix.borrow(), namespace.borrow());
+        let namespace = Iri::new_unchecked(Box::from(namespace));
+        match self.0.iter_mut().find(|(p, _)| p.as_str() == prefix) {
+            Some((_, ns)) => Some(std::mem::replace(ns, namespace)),
+            None => {
+                self.0
+                    .push((Prefix::new_unchecked(prefix.into()), namespace));
+                None
+            }
+        }
+    }
+
+    /// Remove `prefix` from this map, returning the namespace it was bound to, if any.
+    pub fn remove(&mut self, prefix: &str) -> Option<Iri<Box<str>>> {
+        let i = self.0.iter().position(|(p, _)| p.as_str() == prefix)?;
+        Some(self.0.remove(i).1)
+    }
+
+    /// Insert all the prefixes of `other` into this map.
+    ///
+    /// Fails with [`PrefixMapError::Conflict`], leaving this map unchanged,
+    /// if any prefix of `other` is bound to another namespace in this map.
+    pub fn merge<P: PrefixMap + ?Sized>(&mut self, other: &P) -> Result<(), PrefixMapError> {
+        for (prefix, namespace) in other.iter() {
+            self.check(prefix.as_str(), namespace.as_str())?;
+        }
+        for (prefix, namespace) in other.iter() {
+            self.insert(prefix, namespace)?;
+        }
+        Ok(())
+    }
+
+    /// Abbreviate `iri` as a Turtle/SPARQL prefixed name, using the longest matching namespace.
+    ///
+    /// Return `None` if no namespace matches,
+    /// or if the remaining suffix is not a valid local name.
+    pub fn compact<T: IsIri>(&self, iri: T) -> Option<String> {
+        let iri = Iri::new_unchecked(iri.borrow());
+        self.get_checked_prefixed_pair(iri, |txt| txt.is_empty() || PN_LOCAL.is_match(txt))
+            .map(|(prefix, suffix)| format!("{}:{}", prefix.as_str(), suffix))
+    }
+
+    /// Expand the Turtle/SPARQL prefixed name `pname` into an IRI.
+    ///
+    /// The escape sequences allowed in local names (e.g. `\.`) are supported.
+    pub fn expand(&self, pname: &str) -> Result<Iri<String>, PrefixMapError> {
+        let invalid = || PrefixMapError::InvalidPrefixedName(pname.to_string());
+        let (prefix, local) = pname.split_once(':').ok_or_else(invalid)?;
+        let namespace = self
+            .get_namespace(prefix)
+            .ok_or_else(|| PrefixMapError::UnknownPrefix(prefix.to_string()))?;
+        // escape sequences are replaced by '_' for validation, and unescaped in the IRI
+        let mut checked = String::with_capacity(local.len());
+        let mut iri = String::with_capacity(namespace.as_str().len() + local.len());
+        iri.push_str(namespace.as_str());
+        let mut chars = local.chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                match chars.next() {
+                    Some(e) if "_~.-!$&'()*+,;=/?#@%".contains(e) => {
+                        checked.push('_');
+                        iri.push(e);
+                    }
+                    _ => return Err(invalid()),
+                }
+            } else {
+                checked.push(c);
+                iri.push(c);
+            }
+        }
+        if !checked.is_empty() && !PN_LOCAL.is_match(&checked) {
+            return Err(invalid());
+        }
+        Iri::new(iri).map_err(|_| invalid())
+    }
+
+    /// Check that binding `prefix` to `namespace` would not conflict with this map.
+    fn check(&self, prefix: &str, namespace: &str) -> Result<(), PrefixMapError> {
+        match self.get_namespace(prefix) {
+            Some(existing) if existing.as_str() != namespace => Err(PrefixMapError::Conflict {
+                prefix: prefix.to_string(),
+                existing: existing.as_str().to_string(),
+                new: namespace.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl PrefixMap for OrderedPrefixMap {
+    fn get_namespace<'s>(&'s self, prefix: &str) -> Option<Iri<&'s str>> {
+        PrefixMap::get_namespace(self.as_slice(), prefix)
+    }
+
+    fn get_checked_prefixed_pair<'s, T, F>(
+        &'s self,
+        iri: T,
+        suffix_check: F,
+    ) -> Option<(Prefix<&'s str>, MownStr<'s>)>
+    where
+        T: IsIri + 's,
+        F: Fn(&str) -> bool,
+    {
+        PrefixMap::get_checked_prefixed_pair(self.as_slice(), iri, suffix_check)
+    }
+
+    fn iter<'s>(&'s self) -> Box<dyn Iterator<Item = (Prefix<&'s str>, Iri<&'s str>)> + 's> {
+        PrefixMap::iter(self.as_slice())
+    }
+}
+
+impl From<OrderedPrefixMap> for Vec<PrefixMapPair> {
+    fn from(value: OrderedPrefixMap) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Display for OrderedPrefixMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (prefix, namespace) in &self.0 {
+            writeln!(f, "@prefix {}: <{}> .", prefix.as_str(), namespace.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for OrderedPrefixMap {
+    type Err = PrefixMapError;
+
+    /// Parse a sequence of Turtle (`@prefix p: <ns> .`) and/or SPARQL (`PREFIX p: <ns>`)
+    /// prefix declarations, possibly with comments.
+    ///
+    /// Namespaces must be absolute IRIs, without escape sequences,
+    /// and a prefix can not be declared twice with different namespaces.
+    fn from_str(txt: &str) -> Result<Self, Self::Err> {
+        let mut scanner = Scanner { txt, pos: 0 };
+        let mut map = OrderedPrefixMap::new();
+        loop {
+            scanner.skip_ws();
+            if scanner.pos == txt.len() {
+                return Ok(map);
+            }
+            let start = scanner.pos;
+            let sparql = if scanner.keyword("@prefix", false) {
+                false
+            } else if scanner.keyword("PREFIX", true) {
+                true
+            } else {
+                return Err(scanner.error(start, "expected a prefix declaration"));
+            };
+            scanner.skip_ws();
+            let prefix_start = scanner.pos;
+            let prefix = scanner.take_while(|c| c != ':' && !c.is_ascii_whitespace());
+            let prefix =
+                Prefix::new(prefix).map_err(|_| scanner.error(prefix_start, "invalid prefix"))?;
+            scanner.expect(':')?;
+            scanner.skip_ws();
+            scanner.expect('<')?;
+            let namespace_start = scanner.pos;
+            let namespace = scanner.take_while(|c| c != '>');
+            let namespace = Iri::new(namespace).map_err(|_| {
+                scanner.error(namespace_start, "invalid namespace (must be absolute)")
+            })?;
+            scanner.expect('>')?;
+            if !sparql {
+                scanner.skip_ws();
+                scanner.expect('.')?;
+            }
+            map.insert(prefix, namespace)
+                .map_err(|err| scanner.error(start, err.to_string()))?;
+        }
+    }
+}
+
+/// A cursor over a sequence of prefix declarations.
+struct Scanner<'a> {
+    txt: &'a str,
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn rest(&self) -> &'a str {
+        &self.txt[self.pos..]
+    }
+
+    /// Skip whitespaces and comments.
+    fn skip_ws(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start_matches([' ', '\t', '\r', '\n']);
+            self.pos += rest.len() - trimmed.len();
+            if !trimmed.starts_with('#') {
+                return;
+            }
+            self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+        }
+    }
+
+    /// Consume `kw` if it is followed by a whitespace.
+    fn keyword(&mut self, kw: &str, ignore_case: bool) -> bool {
+        let Some(candidate) = self.rest().get(..kw.len()) else {
+            return false;
+        };
+        let matched = if ignore_case {
+            candidate.eq_ignore_ascii_case(kw)
+        } else {
+            candidate == kw
+        };
+        let followed_by_ws = self.rest()[kw.len()..].starts_with([' ', '\t', '\r', '\n']);
+        if matched && followed_by_ws {
+            self.pos += kw.len();
+        }
+        matched && followed_by_ws
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, predicate: F) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), PrefixMapError> {
+        if self.rest().starts_with(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(self.pos, format!("expected '{c}'")))
+        }
+    }
+
+    fn error<M: Into<String>>(&self, pos: usize, message: M) -> PrefixMapError {
+        let before = &self.txt[..pos];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        PrefixMapError::Syntax {
+            line: before.matches('\n').count() + 1,
+            column: pos - line_start + 1,
+            message: message.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    fn make_map() -> OrderedPrefixMap {
+        let mut map = OrderedPrefixMap::new();
+        for (prefix, namespace) in [
+            ("s", "http://schema.org/"),
+            ("a", "http://example.org/a/"),
+            ("ab", "http://example.org/a/b#"),
+            ("", "http://example.org/"),
+        ] {
+            map.insert(Prefix::new_unchecked(prefix), Iri::new_unchecked(namespace))
+                .unwrap();
+        }
+        map
+    }
+
+    #[test]
+    fn insert_set_remove() {
+        let mut map = make_map();
+        let other = Iri::new_unchecked("http://other.org/");
+        map.insert(
+            Prefix::new_unchecked("s"),
+            Iri::new_unchecked("http://schema.org/"),
+        )
+        .unwrap();
+        assert_eq!(map.len(), 4);
+        assert!(matches!(
+            map.insert(Prefix::new_unchecked("s"), other),
+            Err(PrefixMapError::Conflict { .. })
+        ));
+        assert_eq!(
+            map.get_namespace("s").unwrap().as_str(),
+            "http://schema.org/"
+        );
+
+        let old = map.set(Prefix::new_unchecked("s"), other).unwrap();
+        assert_eq!(old.as_str(), "http://schema.org/");
+        assert_eq!(map.get_namespace("s").unwrap(), other);
+        assert_eq!(map.as_slice()[0].0.as_str(), "s");
+
+        assert_eq!(map.remove("a").unwrap().as_str(), "http://example.org/a/");
+        assert!(map.remove("a").is_none());
+        let prefixes: Vec<String> = map.iter().map(|(p, _)| p.as_str().to_string()).collect();
+        assert_eq!(prefixes, ["s", "ab", ""]);
+    }
+
+    #[test]
+    fn merge() {
+        let mut map = make_map();
+        let conflicting = [
+            (
+                Prefix::new_unchecked("x"),
+                Iri::new_unchecked("http://x.org/"),
+            ),
+            (
+                Prefix::new_unchecked("s"),
+                Iri::new_unchecked("http://other.org/"),
+            ),
+        ];
+        assert!(map.merge(&conflicting[..]).is_err());
+        assert_eq!(map, make_map());
+
+        let compatible = [
+            (
+                Prefix::new_unchecked("x"),
+                Iri::new_unchecked("http://x.org/"),
+            ),
+            (
+                Prefix::new_unchecked("s"),
+                Iri::new_unchecked("http://schema.org/"),
+            ),
+        ];
+        map.merge(&compatible[..]).unwrap();
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.get_namespace("x").unwrap().as_str(), "http://x.org/");
+    }
+
+    #[test_case("http://schema.org/Person", Some("s:Person"); "s:Person")]
+    #[test_case("http://example.org/", Some(":"); "single colon")]
+    #[test_case("http://example.org/a/c", Some("a:c"); "a:c")]
+    #[test_case("http://example.org/a/b#c", Some("ab:c"); "ab:c")]
+    #[test_case("http://example.org/a/b#c-d", Some("ab:c-d"); "ab:c-d")]
+    #[test_case("http://example.org/a/b#c/d", None; "slash")]
+    #[test_case("http://example.org/a#c", None; "invalid suffix")]
+    #[test_case("http://something.else.com/", None; "something else")]
+    fn compact(iri: &str, expected: Option<&str>) {
+        let map = make_map();
+        assert_eq!(map.compact(Iri::new_unchecked(iri)).as_deref(), expected);
+    }
+
+    #[test_case("s:Person", "http://schema.org/Person"; "s:Person")]
+    #[test_case(":", "http://example.org/"; "single colon")]
+    #[test_case("ab:c", "http://example.org/a/b#c"; "ab:c")]
+    #[test_case(r"a:c\.d\~", "http://example.org/a/c.d~"; "escapes")]
+    #[test_case("a:%C3%A9", "http://example.org/a/%C3%A9"; "percent")]
+    fn expand(pname: &str, expected: &str) {
+        let map = make_map();
+        assert_eq!(map.expand(pname).unwrap().as_str(), expected);
+    }
+
+    #[test_case("s"; "no colon")]
+    #[test_case("s:a."; "trailing dot")]
+    #[test_case(r"s:a\b"; "invalid escape")]
+    #[test_case("s:a b"; "space")]
+    fn expand_invalid(pname: &str) {
+        let map = make_map();
+        assert!(matches!(
+            map.expand(pname),
+            Err(PrefixMapError::InvalidPrefixedName(_))
+        ));
+    }
+
+    #[test]
+    fn expand_unknown() {
+        let map = make_map();
+        assert!(matches!(
+            map.expand("x:a"),
+            Err(PrefixMapError::UnknownPrefix(p)) if p == "x"
+        ));
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let txt = "# a comment\n\
+                   @prefix s: <http://schema.org/> .\n\
+                   PREFIX a:<http://example.org/a/>\n\
+                   prefix ab: <http://example.org/a/b#> # another comment\n\
+                   @prefix : <http://example.org/>.\n\
+                   @prefix s: <http://schema.org/> .\n";
+        let map: OrderedPrefixMap = txt.parse().unwrap();
+        assert_eq!(map, make_map());
+        assert_eq!(map.to_string().parse::<OrderedPrefixMap>().unwrap(), map);
+        assert_eq!("".parse::<OrderedPrefixMap>().unwrap().len(), 0);
+    }
+
+    #[test_case("@prefix s: <http://schema.org/>", 1, 32; "missing dot")]
+    #[test_case("@prefixs: <http://schema.org/> .", 1, 1; "missing space")]
+    #[test_case("\nPREFIX s <http://schema.org/>", 2, 9; "missing colon")]
+    #[test_case("PREFIX 1s: <http://schema.org/>", 1, 8; "invalid prefix")]
+    #[test_case("PREFIX s: <schema/>", 1, 12; "relative namespace")]
+    #[test_case("PREFIX s: <http://schema.org/", 1, 30; "unterminated namespace")]
+    #[test_case("PREFIX s: <tag:a>\nPREFIX s: <tag:b>", 2, 1; "conflict")]
+    fn parse_invalid(txt: &str, line: usize, column: usize) {
+        match txt.parse::<OrderedPrefixMap>() {
+            Err(PrefixMapError::Syntax {
+                line: l, column: c, ..
+            }) => assert_eq!((l, c), (line, column)),
+            other => panic!("{other:?}"),
+        }
+    }
+}
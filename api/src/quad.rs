@@ -352,10 +352,10 @@ mod test_quad {
     use crate::term::SimpleTerm;
     use sophia_iri::IriRef;
 
-    const S: IriRef<&str> = IriRef::new_unchecked_const("tag:s");
-    const P: IriRef<&str> = IriRef::new_unchecked_const("tag:o");
-    const O: IriRef<&str> = IriRef::new_unchecked_const("tag:p");
-    const G: GraphName<IriRef<&str>> = Some(IriRef::new_unchecked_const("tag:g"));
+    const S: IriRef<&str> = IriRef::new_const("tag:s");
+    const P: IriRef<&str> = IriRef::new_const("tag:o");
+    const O: IriRef<&str> = IriRef::new_const("tag:p");
+    const G: GraphName<IriRef<&str>> = Some(IriRef::new_const("tag:g"));
 
     #[test]
     fn quad_matched_by() {
@@ -60,6 +60,8 @@ pub mod convert;
 pub mod filter;
 pub mod filter_map;
 pub mod map;
+pub mod route;
+pub mod unicode;
 
 mod _quad;
 pub use _quad::*;
@@ -3,6 +3,7 @@ use std::error::Error;
 use super::*;
 use crate::dataset::{CollectibleDataset, Dataset, MutableDataset};
 use crate::quad::Quad;
+use crate::term::unicode::UnicodePolicy;
 
 /// A quad source is a [`Source`] producing [quads](Quad).
 ///
@@ -118,6 +119,20 @@ pub trait QuadSource: Source + IsQuadSource {
         self.map_items(move |i| map(Self::i2q(i)))
     }
 
+    /// Returns a source which applies `policy` to the literals of each quad.
+    ///
+    /// Quads rejected by the policy are reported as source errors.
+    #[inline]
+    fn normalize_quads(self, policy: UnicodePolicy) -> unicode::UnicodeQuadSource<Self>
+    where
+        Self: Sized,
+    {
+        unicode::UnicodeQuadSource {
+            source: self,
+            policy,
+        }
+    }
+
     /// Convert of quads in this source to triples (stripping the graph name).
     fn to_triples(self) -> convert::ToTriples<Self>
     where
@@ -2,6 +2,7 @@ use std::error::Error;
 
 use super::*;
 use crate::graph::{CollectibleGraph, Graph, MutableGraph};
+use crate::term::unicode::UnicodePolicy;
 use crate::triple::Triple;
 
 /// A triple source is a [`Source`] producing [triples](Triple).
@@ -125,6 +126,20 @@ pub trait TripleSource: Source + IsTripleSource {
         self.size_hint_items()
     }
 
+    /// Returns a source which applies `policy` to the literals of each triple.
+    ///
+    /// Triples rejected by the policy are reported as source errors.
+    #[inline]
+    fn normalize_triples(self, policy: UnicodePolicy) -> unicode::UnicodeTripleSource<Self>
+    where
+        Self: Sized,
+    {
+        unicode::UnicodeTripleSource {
+            source: self,
+            policy,
+        }
+    }
+
     /// Convert of triples in this source to quads (belonging to the default graph).
     #[inline]
     fn to_quads(self) -> convert::ToQuads<Self>
@@ -0,0 +1,225 @@
+//! I define [`RoutingTable`], which dispatches the quads of a [`QuadSource`]
+//! into several [datasets](MutableDataset),
+//! e.g. to split a dump by vocabulary, or by named graph.
+//!
+//! # Example
+//! ```
+//! # use sophia_api::source::{QuadSource, route::RoutingTable};
+//! # use sophia_api::dataset::Dataset;
+//! # use sophia_api::quad::Spog;
+//! # use sophia_api::term::{SimpleTerm, Term};
+//! # use sophia_api::ns::{rdf, xsd};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let dump: Vec<Spog<SimpleTerm<'static>>> = vec![
+//!     ([rdf::type_.into_term(), rdf::type_.into_term(), rdf::Property.into_term()], None),
+//!     ([xsd::string.into_term(), rdf::type_.into_term(), rdf::Statement.into_term()], None),
+//!     ([rdf::nil.into_term(), rdf::type_.into_term(), rdf::List.into_term()], Some(rdf::nil.into_term())),
+//! ];
+//! let table = RoutingTable::new()
+//!     .with_graph_name(Some(rdf::nil), 0)
+//!     .with_subject_namespace(rdf::PREFIX.as_str(), 1)
+//!     .with_default(2);
+//! let mut sinks: Vec<Vec<Spog<SimpleTerm<'static>>>> = vec![vec![]; 3];
+//! let counts = table.route_quads(dump.quads(), &mut sinks)?;
+//! assert_eq!(counts, vec![1, 1, 1]);
+//! # Ok(()) }
+//! ```
+use super::*;
+use crate::dataset::{MdResult, MutableDataset};
+use crate::quad::{Quad, Spog};
+use crate::term::{graph_name_eq, GraphName, SimpleTerm, Term};
+
+/// A user-provided predicate on quads, used by [`RoutingTable::with_predicate`].
+type QuadPredicate = Box<dyn for<'x> Fn(&Spog<SimpleTerm<'x>>) -> bool + Send + Sync>;
+
+/// A routing rule of a [`RoutingTable`].
+enum Rule {
+    SubjectNamespace(String),
+    GraphName(GraphName<SimpleTerm<'static>>),
+    Predicate(QuadPredicate),
+}
+
+impl Rule {
+    fn matches<Q: Quad>(&self, q: &Q) -> bool {
+        match self {
+            Rule::SubjectNamespace(ns) => q
+                .s()
+                .iri()
+                .map(|iri| iri.as_str().starts_with(ns.as_str()))
+                .unwrap_or(false),
+            Rule::GraphName(gn) => graph_name_eq(q.g(), gn.as_ref()),
+            Rule::Predicate(p) => {
+                let [s, p2, o] = [q.s(), q.p(), q.o()];
+                let g = q.g();
+                let ret = p(&(
+                    [s.as_simple(), p2.as_simple(), o.as_simple()],
+                    g.as_ref().map(Term::as_simple),
+                ));
+                ret
+            }
+        }
+    }
+}
+
+/// I dispatch quads into several sinks,
+/// identified by their index in a slice of [datasets](MutableDataset).
+///
+/// Rules are tried in the order in which they were added,
+/// and the first matching rule determines the sink of a quad.
+/// Quads matching no rule are sent to the [default](RoutingTable::with_default) sink if any,
+/// or dropped otherwise.
+#[derive(Default)]
+pub struct RoutingTable {
+    rules: Vec<(Rule, usize)>,
+    default: Option<usize>,
+}
+
+impl RoutingTable {
+    /// Build a new empty [`RoutingTable`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Route to sink `sink` all quads whose subject is an IRI starting with `ns`.
+    pub fn with_subject_namespace(mut self, ns: &str, sink: usize) -> Self {
+        self.rules
+            .push((Rule::SubjectNamespace(ns.to_string()), sink));
+        self
+    }
+
+    /// Route to sink `sink` all quads whose graph name is `graph_name`
+    /// (`None` for the default graph).
+    pub fn with_graph_name<T: Term>(mut self, graph_name: GraphName<T>, sink: usize) -> Self {
+        let graph_name = graph_name.map(Term::into_term);
+        self.rules.push((Rule::GraphName(graph_name), sink));
+        self
+    }
+
+    /// Route to sink `sink` all quads satisfying `predicate`.
+    pub fn with_predicate<F>(mut self, predicate: F, sink: usize) -> Self
+    where
+        F: for<'x> Fn(&Spog<SimpleTerm<'x>>) -> bool + Send + Sync + 'static,
+    {
+        self.rules
+            .push((Rule::Predicate(Box::new(predicate)), sink));
+        self
+    }
+
+    /// Route to sink `sink` all quads matching no other rule.
+    pub fn with_default(mut self, sink: usize) -> Self {
+        self.default = Some(sink);
+        self
+    }
+
+    /// The index of the sink where `quad` should be routed,
+    /// or `None` if it should be dropped.
+    pub fn route<Q: Quad>(&self, quad: &Q) -> Option<usize> {
+        self.rules
+            .iter()
+            .find(|(rule, _)| rule.matches(quad))
+            .map(|(_, sink)| *sink)
+            .or(self.default)
+    }
+
+    /// Insert every quad of `source` into the sink (in `sinks`) where it is routed.
+    ///
+    /// # Return value
+    /// The number of quads inserted in each sink.
+    /// These numbers are **not significant unless** the sinks also implement
+    /// [`SetDataset`](crate::dataset::SetDataset).
+    ///
+    /// # Panics
+    /// If this table routes a quad to a sink index not smaller than `sinks.len()`.
+    pub fn route_quads<S, D>(
+        &self,
+        mut source: S,
+        sinks: &mut [D],
+    ) -> StreamResult<Vec<usize>, S::Error, D::MutationError>
+    where
+        S: QuadSource,
+        D: MutableDataset,
+    {
+        let mut counts = vec![0; sinks.len()];
+        source
+            .try_for_each_quad(|q| -> MdResult<D, ()> {
+                if let Some(i) = self.route(&q) {
+                    if sinks[i].insert_quad(q.spog())? {
+                        counts[i] += 1;
+                    }
+                }
+                Ok(())
+            })
+            .and(Ok(counts))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dataset::Dataset;
+    use crate::ns::{rdf, xsd};
+
+    type MyDataset = Vec<Spog<SimpleTerm<'static>>>;
+
+    fn quad<T: Term>(s: T, g: Option<T>) -> Spog<SimpleTerm<'static>> {
+        (
+            [
+                s.into_term(),
+                rdf::type_.into_term(),
+                rdf::Statement.into_term(),
+            ],
+            g.map(Term::into_term),
+        )
+    }
+
+    fn dump() -> MyDataset {
+        vec![
+            quad(rdf::type_, None),
+            quad(xsd::string, None),
+            quad(rdf::nil, Some(rdf::nil)),
+            quad(xsd::integer, Some(xsd::integer)),
+        ]
+    }
+
+    #[test]
+    fn first_matching_rule_wins() -> Result<(), Box<dyn std::error::Error>> {
+        let table = RoutingTable::new()
+            .with_graph_name(Some(rdf::nil), 0)
+            .with_subject_namespace(rdf::PREFIX.as_str(), 1)
+            .with_subject_namespace(xsd::PREFIX.as_str(), 2);
+        let mut sinks: Vec<MyDataset> = vec![vec![]; 3];
+        let counts = table.route_quads(dump().quads(), &mut sinks)?;
+        assert_eq!(counts, vec![1, 1, 2]);
+        assert_eq!(sinks[0], vec![quad(rdf::nil, Some(rdf::nil))]);
+        assert_eq!(sinks[1], vec![quad(rdf::type_, None)]);
+        assert_eq!(
+            sinks[2],
+            vec![
+                quad(xsd::string, None),
+                quad(xsd::integer, Some(xsd::integer))
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn default_and_dropped() -> Result<(), Box<dyn std::error::Error>> {
+        let table = RoutingTable::new().with_graph_name(None::<SimpleTerm>, 0);
+        let mut sinks: Vec<MyDataset> = vec![vec![]];
+        let counts = table.route_quads(dump().quads(), &mut sinks)?;
+        assert_eq!(counts, vec![2]);
+
+        let table = table.with_default(1);
+        let mut sinks: Vec<MyDataset> = vec![vec![], vec![]];
+        let counts = table.route_quads(dump().quads(), &mut sinks)?;
+        assert_eq!(counts, vec![2, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn predicate() {
+        let table = RoutingTable::new().with_predicate(|q| q.1.is_some(), 3);
+        assert_eq!(table.route(&quad(rdf::nil, None)), None);
+        assert_eq!(table.route(&quad(rdf::nil, Some(rdf::nil))), Some(3));
+    }
+}
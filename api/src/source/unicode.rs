@@ -0,0 +1,117 @@
+//! I define [`UnicodeTripleSource`] and [`UnicodeQuadSource`],
+//! the result types of [`TripleSource::normalize_triples`] and [`QuadSource::normalize_quads`].
+use super::*;
+use crate::quad::Spog;
+use crate::term::unicode::{UnicodeError, UnicodePolicy, UnicodePolicyError};
+use crate::term::SimpleTerm;
+use std::error::Error;
+
+/// The result type of [`TripleSource::normalize_triples`].
+pub struct UnicodeTripleSource<S> {
+    pub(super) source: S,
+    pub(super) policy: UnicodePolicy,
+}
+
+impl<S: TripleSource> Source for UnicodeTripleSource<S> {
+    type Item<'x> = [SimpleTerm<'static>; 3];
+    type Error = UnicodePolicyError<S::Error>;
+
+    fn try_for_some_item<E, F>(&mut self, mut f: F) -> StreamResult<bool, Self::Error, E>
+    where
+        E: Error + Send + Sync + 'static,
+        F: FnMut(Self::Item<'_>) -> Result<(), E>,
+    {
+        let policy = &self.policy;
+        self.source
+            .try_for_some_triple(|t| {
+                let t = policy.normalize_triple(t).map_err(Interrupted::Policy)?;
+                f(t).map_err(Interrupted::Sink)
+            })
+            .map_err(resume)
+    }
+
+    fn size_hint_items(&self) -> (usize, Option<usize>) {
+        self.source.size_hint_items()
+    }
+}
+
+/// The result type of [`QuadSource::normalize_quads`].
+pub struct UnicodeQuadSource<S> {
+    pub(super) source: S,
+    pub(super) policy: UnicodePolicy,
+}
+
+impl<S: QuadSource> Source for UnicodeQuadSource<S> {
+    type Item<'x> = Spog<SimpleTerm<'static>>;
+    type Error = UnicodePolicyError<S::Error>;
+
+    fn try_for_some_item<E, F>(&mut self, mut f: F) -> StreamResult<bool, Self::Error, E>
+    where
+        E: Error + Send + Sync + 'static,
+        F: FnMut(Self::Item<'_>) -> Result<(), E>,
+    {
+        let policy = &self.policy;
+        self.source
+            .try_for_some_quad(|q| {
+                let q = policy.normalize_quad(q).map_err(Interrupted::Policy)?;
+                f(q).map_err(Interrupted::Sink)
+            })
+            .map_err(resume)
+    }
+
+    fn size_hint_items(&self) -> (usize, Option<usize>) {
+        self.source.size_hint_items()
+    }
+}
+
+/// Used to interrupt the underlying source when the policy rejects an item.
+#[derive(Debug, thiserror::Error)]
+enum Interrupted<E: Error> {
+    #[error(transparent)]
+    Policy(UnicodeError),
+    #[error(transparent)]
+    Sink(E),
+}
+
+fn resume<E1: Error, E2: Error>(
+    err: StreamError<E1, Interrupted<E2>>,
+) -> StreamError<UnicodePolicyError<E1>, E2> {
+    match err {
+        SourceError(e) => SourceError(UnicodePolicyError::Inner(e)),
+        SinkError(Interrupted::Policy(e)) => SourceError(UnicodePolicyError::Unicode(e)),
+        SinkError(Interrupted::Sink(e)) => SinkError(e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::term::Term;
+
+    #[test]
+    fn normalize_triples() {
+        let triples = vec![["tag:s", "tag:p", "cafe\u{301}"]];
+        let normalized: Vec<[SimpleTerm<'static>; 3]> = triples
+            .into_iter()
+            .into_source()
+            .normalize_triples(UnicodePolicy::new())
+            .collect_triples()
+            .unwrap();
+        assert!(Term::eq(&normalized[0][2], "caf\u{e9}"));
+    }
+
+    #[test]
+    fn normalize_quads_rejected() {
+        let quads = vec![(["tag:s", "tag:p", "ab\u{378}"], None::<&str>)];
+        let policy = UnicodePolicy::new().with_reject_unassigned(true);
+        let res: Result<Vec<Spog<SimpleTerm<'static>>>, _> = quads
+            .into_iter()
+            .into_source()
+            .normalize_quads(policy)
+            .collect_quads();
+        assert!(matches!(
+            res,
+            Err(SourceError(UnicodePolicyError::Unicode(_)))
+        ));
+    }
+}
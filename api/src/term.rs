@@ -13,17 +13,33 @@ use std::hash::Hash;
 
 mod _cmp;
 pub use _cmp::*;
+mod _display;
+pub use _display::*;
+mod _from_str;
+pub use _from_str::*;
 mod _graph_name;
 pub use _graph_name::*;
 mod _native_iri;
 mod _native_literal;
 mod _simple;
 pub use _simple::*;
+mod _sparql_cmp;
+pub use _sparql_cmp::SparqlOrd;
 
 pub mod bnode_id;
+#[cfg(feature = "chrono")]
+pub mod datetime;
+#[cfg(feature = "bigdecimal")]
+pub mod decimal;
+#[cfg(feature = "json")]
+pub mod json;
 pub mod language_tag;
+pub mod literal_value;
 pub mod matcher;
+pub mod skolem;
+pub mod unicode;
 pub mod var_name;
+pub mod wkt;
 
 /// This type is aliased from `sophia_iri` for convenience,
 /// as it is required to implement [`Term`].
@@ -31,6 +47,7 @@ pub type IriRef<T> = sophia_iri::IriRef<T>;
 // The following two types are also re-exported for the same reason.
 pub use bnode_id::BnodeId;
 pub use language_tag::LanguageTag;
+use literal_value::{LiteralValue, LiteralValueError};
 pub use var_name::VarName;
 
 lazy_static::lazy_static! {
@@ -492,6 +509,151 @@ pub trait Term: std::fmt::Debug {
         T::try_from_term(self)
     }
 
+    /// Extract the native value of this literal,
+    /// parsing its lexical form according to its datatype
+    /// (see [`literal_value`] for more details).
+    ///
+    /// Return an error if this term is not a literal,
+    /// if its datatype is not supported by `V`,
+    /// or if its lexical form is not valid for its datatype.
+    ///
+    /// ```
+    /// # use sophia_api::{ns::xsd, term::Term};
+    /// assert_eq!(("+42 " * xsd::integer).value::<i64>(), Ok(42));
+    /// assert!(("forty-two" * xsd::integer).value::<i64>().is_err());
+    /// ```
+    fn value<V: LiteralValue>(&self) -> Result<V, LiteralValueError> {
+        match (self.lexical_form(), self.datatype()) {
+            (Some(lex), Some(dt)) => V::from_lexical(&lex, dt.as_str()),
+            _ => Err(LiteralValueError::NotALiteral),
+        }
+    }
+
+    /// Check whether this term and `other` have the same value,
+    /// as opposed to [`Term::eq`] which checks whether they are the same term.
+    ///
+    /// See [`Term::value_cmp`] for more details.
+    ///
+    /// ```
+    /// # use sophia_api::{ns::xsd, term::Term};
+    /// assert!(("01" * xsd::integer).value_eq("1" * xsd::integer));
+    /// assert!(("1" * xsd::integer).value_eq("1.0" * xsd::decimal));
+    /// assert!(!("1" * xsd::integer).value_eq("1" * xsd::string));
+    /// ```
+    fn value_eq<T: Term>(&self, other: T) -> bool {
+        self.value_cmp(other) == Some(Ordering::Equal)
+    }
+
+    /// Compare the value of this term with the value of `other`,
+    /// in a way compatible with the [operators of SPARQL](https://www.w3.org/TR/sparql11-query/#OperatorMapping).
+    ///
+    /// * Numeric literals (`xsd:decimal`, `xsd:double`, `xsd:float`,
+    ///   and the integer datatypes derived from `xsd:decimal`) are compared by value,
+    ///   with numeric promotion (integers are compared exactly, other numbers as `f64`;
+    ///   with the `bigdecimal` feature, decimals are also compared exactly).
+    /// * `xsd:boolean` literals are compared by value (`false` < `true`).
+    /// * `xsd:string` literals are compared by lexical form,
+    ///   and so are language-tagged strings with the same language tag (case-insensitively).
+    ///
+    /// In any other case (including literals with an invalid lexical form),
+    /// return `Some(Equal)` if both terms are [the same](Term::eq), and `None` otherwise.
+    /// `None` is also returned when comparing `NaN` to any number.
+    fn value_cmp<T: Term>(&self, other: T) -> Option<Ordering> {
+        literal_value::value_cmp(self, &other)
+    }
+
+    /// Compare this term with `other` according to a total order
+    /// compatible with the [`ORDER BY` clause of SPARQL](https://www.w3.org/TR/sparql11-query/#modOrderBy),
+    /// suitable for sorting query solutions or producing canonical output:
+    /// * blank nodes < IRIs < literals < quoted triples < variables
+    /// * literals are grouped in the following order:
+    ///   numbers, booleans, `xsd:dateTime`s, `xsd:date`s, `xsd:string`s,
+    ///   language-tagged strings, and any other literal (including ill-formed ones)
+    /// * numbers, booleans, date-times and dates are ordered by value
+    ///   (`NaN` coming after every other number,
+    ///   and date-times without a timezone being considered as UTC),
+    ///   and language-tagged strings by lexical form
+    /// * quoted triples are ordered in lexicographical order
+    ///
+    /// In any other case, or when two literals have the same value,
+    /// terms are ordered as per [`Term::cmp`],
+    /// so that only [identical](Term::eq) terms are equal.
+    ///
+    /// See also [`SparqlOrd`].
+    ///
+    /// ```
+    /// # use sophia_api::{ns::xsd, term::Term};
+    /// # use std::cmp::Ordering;
+    /// assert_eq!(("2" * xsd::integer).sparql_cmp("10" * xsd::integer), Ordering::Less);
+    /// assert_eq!(("2" * xsd::integer).sparql_cmp("1.5" * xsd::double), Ordering::Greater);
+    /// ```
+    fn sparql_cmp<T: Term>(&self, other: T) -> Ordering {
+        _sparql_cmp::sparql_cmp(self, &other)
+    }
+
+    /// Copies this term into a [`SimpleTerm`],
+    /// replacing the lexical form of literals by their canonical form
+    /// (see [`literal_value::canonical_lexical_form`] for the supported datatypes).
+    ///
+    /// Literals with the same value and datatype therefore become [identical](Term::eq),
+    /// which is useful to produce canonical output, or to deduplicate terms by value.
+    ///
+    /// Other terms (including literals with an unsupported datatype or an invalid lexical form)
+    /// are copied unchanged.
+    ///
+    /// ```
+    /// # use sophia_api::{ns::xsd, term::Term};
+    /// let decimal = "+01.50" * xsd::decimal;
+    /// assert!(Term::eq(&decimal.canonicalized(), "1.5" * xsd::decimal));
+    /// let boolean = "0" * xsd::boolean;
+    /// assert!(Term::eq(&boolean.canonicalized(), "false" * xsd::boolean));
+    /// let invalid = "x" * xsd::integer;
+    /// assert!(Term::eq(&invalid.canonicalized(), "x" * xsd::integer));
+    /// ```
+    fn canonicalized(&self) -> SimpleTerm<'_> {
+        match self.as_simple() {
+            SimpleTerm::LiteralDatatype(lex, dt) => {
+                match literal_value::canonical_lexical_form(&lex, dt.as_str()) {
+                    Some(canonical) if canonical != *lex => {
+                        SimpleTerm::LiteralDatatype(canonical.into(), dt)
+                    }
+                    _ => SimpleTerm::LiteralDatatype(lex, dt),
+                }
+            }
+            simple => simple,
+        }
+    }
+
+    /// Display this term in the [Turtle](https://www.w3.org/TR/turtle/) syntax,
+    /// abbreviating IRIs as prefixed names with the given [prefix map](crate::prefix::PrefixMap).
+    ///
+    /// This is meant for human-readable output, such as logs or error messages.
+    /// Numbers and booleans in their Turtle syntax are displayed without quotes nor datatype,
+    /// variables as `?name`, and quoted triples as `<< s p o >>`.
+    ///
+    /// ```
+    /// # use sophia_api::{ns::{rdf, xsd}, prefix::{Prefix, PrefixMapPair}, term::{Term, LanguageTag}};
+    /// # use sophia_iri::Iri;
+    /// let prefixes = [(Prefix::new_unchecked("rdf"), Iri::new_unchecked(rdf::PREFIX.as_str()))];
+    /// assert_eq!(rdf::type_.display_turtle(&prefixes[..]).to_string(), "rdf:type");
+    /// assert_eq!(("42" * xsd::integer).display_turtle(&prefixes[..]).to_string(), "42");
+    /// let chat = "chat" * LanguageTag::new_unchecked("fr");
+    /// assert_eq!(chat.display_turtle(&prefixes[..]).to_string(), r#""chat"@fr"#);
+    ///
+    /// // with no prefix at all
+    /// let no_prefix: &[PrefixMapPair] = &[];
+    /// assert_eq!(
+    ///     rdf::type_.display_turtle(no_prefix).to_string(),
+    ///     "<http://www.w3.org/1999/02/22-rdf-syntax-ns#type>",
+    /// );
+    /// ```
+    fn display_turtle<'a, P>(&'a self, prefix_map: &'a P) -> DisplayTurtle<'a, Self, P>
+    where
+        P: crate::prefix::PrefixMap + ?Sized,
+    {
+        DisplayTurtle::new(self, prefix_map)
+    }
+
     /// Copies this term into a [`SimpleTerm`],
     /// borrowing as much as possible from `self`
     /// (calling [`SimpleTerm::from_term_ref`]).
@@ -0,0 +1,218 @@
+//! I define [`DisplayTurtle`],
+//! a human-readable representation of terms in the Turtle syntax.
+use super::*;
+use crate::ns::xsd;
+use crate::prefix::{PrefixMap, PN_LOCAL};
+use sophia_iri::Iri;
+use std::fmt;
+
+/// Display a [`Term`] in the [Turtle](https://www.w3.org/TR/turtle/) syntax
+/// (extended with SPARQL variables and RDF-star quoted triples),
+/// abbreviating IRIs with a [`PrefixMap`] whenever possible.
+///
+/// See [`Term::display_turtle`].
+#[derive(Clone, Copy, Debug)]
+pub struct DisplayTurtle<'a, T: ?Sized, P: ?Sized> {
+    term: &'a T,
+    prefix_map: &'a P,
+}
+
+impl<'a, T: ?Sized, P: ?Sized> DisplayTurtle<'a, T, P> {
+    /// Build a new [`DisplayTurtle`] for `term`, using the given prefix map.
+    pub fn new(term: &'a T, prefix_map: &'a P) -> Self {
+        DisplayTurtle { term, prefix_map }
+    }
+}
+
+impl<T: Term + ?Sized, P: PrefixMap + ?Sized> fmt::Display for DisplayTurtle<'_, T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use TermKind::*;
+        let t = self.term;
+        match t.kind() {
+            Iri => self.fmt_iri(f, t.iri().unwrap().as_str()),
+            BlankNode => write!(f, "_:{}", t.bnode_id().unwrap().as_str()),
+            Literal => {
+                let lex = t.lexical_form().unwrap();
+                if let Some(tag) = t.language_tag() {
+                    return write!(f, "\"{}\"@{}", Escaped(&lex), tag.as_str());
+                }
+                let dt = t.datatype().unwrap();
+                if xsd::integer == dt && is_integer(&lex)
+                    || xsd::decimal == dt && is_decimal(&lex)
+                    || xsd::double == dt && is_double(&lex)
+                    || xsd::boolean == dt && matches!(&lex[..], "true" | "false")
+                {
+                    f.write_str(&lex)
+                } else if xsd::string == dt {
+                    write!(f, "\"{}\"", Escaped(&lex))
+                } else {
+                    write!(f, "\"{}\"^^", Escaped(&lex))?;
+                    self.fmt_iri(f, dt.as_str())
+                }
+            }
+            Triple => {
+                let [s, p, o] = t.triple().unwrap();
+                write!(
+                    f,
+                    "<< {} {} {} >>",
+                    DisplayTurtle::new(&s, self.prefix_map),
+                    DisplayTurtle::new(&p, self.prefix_map),
+                    DisplayTurtle::new(&o, self.prefix_map),
+                )
+            }
+            Variable => write!(f, "?{}", t.variable().unwrap().as_str()),
+        }
+    }
+}
+
+impl<T: ?Sized, P: PrefixMap + ?Sized> DisplayTurtle<'_, T, P> {
+    /// Write `iri` as a prefixed name if possible, as an IRI reference otherwise.
+    fn fmt_iri(&self, f: &mut fmt::Formatter<'_>, iri: &str) -> fmt::Result {
+        if let Ok(iri) = Iri::new(iri) {
+            let pair = self
+                .prefix_map
+                .get_checked_prefixed_pair(iri, |txt| txt.is_empty() || PN_LOCAL.is_match(txt));
+            if let Some((prefix, suffix)) = pair {
+                return write!(f, "{}:{}", prefix.as_str(), suffix);
+            }
+        }
+        write!(f, "<{iri}>")
+    }
+}
+
+/// Display the content of a quoted string, escaping it as required.
+struct Escaped<'a>(&'a str);
+
+impl fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rest = self.0;
+        while let Some(i) = rest.find(['\n', '\r', '\\', '"']) {
+            f.write_str(&rest[..i])?;
+            f.write_str(match rest.as_bytes()[i] {
+                b'\n' => "\\n",
+                b'\r' => "\\r",
+                b'\\' => "\\\\",
+                _ => "\\\"",
+            })?;
+            rest = &rest[i + 1..];
+        }
+        f.write_str(rest)
+    }
+}
+
+/// Whether `txt` is a non-empty sequence of ASCII digits.
+fn is_digits(txt: &str) -> bool {
+    !txt.is_empty() && txt.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Whether `txt` matches the `INTEGER` rule of Turtle.
+fn is_integer(txt: &str) -> bool {
+    is_digits(txt.strip_prefix(['+', '-']).unwrap_or(txt))
+}
+
+/// Whether `txt` matches the `DECIMAL` rule of Turtle.
+fn is_decimal(txt: &str) -> bool {
+    match txt.strip_prefix(['+', '-']).unwrap_or(txt).split_once('.') {
+        Some((int, frac)) => (int.is_empty() || is_digits(int)) && is_digits(frac),
+        None => false,
+    }
+}
+
+/// Whether `txt` matches the `DOUBLE` rule of Turtle.
+fn is_double(txt: &str) -> bool {
+    let Some((mantissa, exponent)) = txt.split_once(['e', 'E']) else {
+        return false;
+    };
+    let mantissa = mantissa.strip_prefix(['+', '-']).unwrap_or(mantissa);
+    let mantissa_ok = match mantissa.split_once('.') {
+        Some((int, "")) => is_digits(int),
+        Some((int, frac)) => (int.is_empty() || is_digits(int)) && is_digits(frac),
+        None => is_digits(mantissa),
+    };
+    mantissa_ok && is_integer(exponent)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ns::rdf;
+    use crate::prefix::{Prefix, PrefixMapPair};
+    use test_case::test_case;
+
+    fn prefix_map() -> Vec<(Prefix<&'static str>, Iri<&'static str>)> {
+        vec![
+            (
+                Prefix::new_unchecked("ex"),
+                Iri::new_unchecked("http://example.org/"),
+            ),
+            (
+                Prefix::new_unchecked("xsd"),
+                Iri::new_unchecked("http://www.w3.org/2001/XMLSchema#"),
+            ),
+            (
+                Prefix::new_unchecked(""),
+                Iri::new_unchecked("http://example.org/default#"),
+            ),
+        ]
+    }
+
+    #[test_case("<http://example.org/foo>", "ex:foo")]
+    #[test_case("<http://example.org/>", "ex:")]
+    #[test_case("<http://example.org/default#bar>", ":bar")]
+    #[test_case("<http://example.org/a/b>", "<http://example.org/a/b>")]
+    #[test_case("<http://example.org/a.>", "<http://example.org/a.>")]
+    #[test_case("<http://example.org/%41.b>", "ex:%41.b")]
+    #[test_case("<http://other.org/>", "<http://other.org/>")]
+    #[test_case("_:b1", "_:b1")]
+    #[test_case(r#""chat"@fr"#, r#""chat"@fr"#)]
+    #[test_case(r#""foo""#, r#""foo""#)]
+    #[test_case(r#""a\"b\\c\nd""#, r#""a\"b\\c\nd""#)]
+    #[test_case(r#""42"^^<http://www.w3.org/2001/XMLSchema#integer>"#, "42")]
+    #[test_case(r#""-4.2"^^<http://www.w3.org/2001/XMLSchema#decimal>"#, "-4.2")]
+    #[test_case(r#""4.2e1"^^<http://www.w3.org/2001/XMLSchema#double>"#, "4.2e1")]
+    #[test_case(r#""true"^^<http://www.w3.org/2001/XMLSchema#boolean>"#, "true")]
+    #[test_case(
+        r#""4."^^<http://www.w3.org/2001/XMLSchema#decimal>"#,
+        r#""4."^^xsd:decimal"#
+    )]
+    #[test_case(
+        r#""1"^^<http://www.w3.org/2001/XMLSchema#boolean>"#,
+        r#""1"^^xsd:boolean"#
+    )]
+    #[test_case(r#""x"^^<http://example.org/dt>"#, r#""x"^^ex:dt"#)]
+    #[test_case(r#""x"^^<tag:dt>"#, r#""x"^^<tag:dt>"#)]
+    #[test_case("<< _:s <http://example.org/p> \"o\" >>", "<< _:s ex:p \"o\" >>")]
+    fn display_turtle(nt: &str, expected: &str) {
+        let term: SimpleTerm = nt.parse().unwrap();
+        let map = prefix_map();
+        assert_eq!(term.display_turtle(&map[..]).to_string(), expected);
+    }
+
+    #[test]
+    fn display_turtle_no_prefix() {
+        let no_prefix: &[PrefixMapPair] = &[];
+        assert_eq!(
+            rdf::type_.display_turtle(no_prefix).to_string(),
+            "<http://www.w3.org/1999/02/22-rdf-syntax-ns#type>"
+        );
+        let v = VarName::new_unchecked("x");
+        assert_eq!(v.display_turtle(no_prefix).to_string(), "?x");
+    }
+
+    #[test_case("1", true, false, false)]
+    #[test_case("-01", true, false, false)]
+    #[test_case("1.5", false, true, false)]
+    #[test_case(".5", false, true, false)]
+    #[test_case("1.", false, false, false)]
+    #[test_case("1e5", false, false, true)]
+    #[test_case("1.E-5", false, false, true)]
+    #[test_case("+.5e+5", false, false, true)]
+    #[test_case(".e5", false, false, false)]
+    #[test_case("1e", false, false, false)]
+    #[test_case("", false, false, false)]
+    fn numbers(txt: &str, integer: bool, decimal: bool, double: bool) {
+        assert_eq!(is_integer(txt), integer);
+        assert_eq!(is_decimal(txt), decimal);
+        assert_eq!(is_double(txt), double);
+    }
+}
@@ -0,0 +1,541 @@
+//! I implement [`FromStr`] for [`SimpleTerm`],
+//! parsing a single term in the [N-Triples] syntax
+//! (extended with RDF-star quoted triples),
+//! and [`parse_nt_statement`], parsing a whole [N-Triples] or [N-Quads] statement.
+//!
+//! Both rely on the same scanner,
+//! which is also used by the N-Triples validator and zero-copy parser of `sophia_turtle`,
+//! so that they all accept exactly the same syntax.
+//!
+//! [N-Triples]: https://www.w3.org/TR/n-triples/
+//! [N-Quads]: https://www.w3.org/TR/n-quads/
+use super::*;
+use crate::quad::Spog;
+use std::str::FromStr;
+use thiserror::Error;
+
+const XSD_STRING: IriRef<&str> = IriRef::new_const("http://www.w3.org/2001/XMLSchema#string");
+
+/// Parse a single term in the [N-Triples](https://www.w3.org/TR/n-triples/) syntax
+/// (extended with RDF-star quoted triples), ignoring surrounding whitespaces.
+///
+/// ```
+/// # use sophia_api::term::{SimpleTerm, Term, TermKind};
+/// let iri: SimpleTerm = "<http://example.org/a>".parse()?;
+/// assert_eq!(iri.iri().unwrap().as_str(), "http://example.org/a");
+/// let literal: SimpleTerm = r#""foo"@en"#.parse()?;
+/// assert_eq!(literal.language_tag().unwrap().as_str(), "en");
+/// let bnode: SimpleTerm = "_:b1".parse()?;
+/// assert_eq!(bnode.bnode_id().unwrap().as_str(), "b1");
+/// let triple: SimpleTerm = r#"<< _:b1 <http://example.org/p> "42" >>"#.parse()?;
+/// assert_eq!(triple.kind(), TermKind::Triple);
+/// // only the N-Triples syntax is supported
+/// assert!("42".parse::<SimpleTerm>().is_err());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+impl FromStr for SimpleTerm<'static> {
+    type Err = TermParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let txt = s.trim_start();
+        let offset = s.len() - txt.len();
+        let txt = txt.trim_end();
+        let mut scanner = Scanner::new(txt);
+        scanner
+            .term()
+            .and_then(|term| {
+                scanner.ws();
+                if scanner.pos == txt.len() {
+                    Ok(term.into_term())
+                } else {
+                    Err(scanner.error("unexpected trailing characters"))
+                }
+            })
+            .map_err(|(position, message)| TermParseError {
+                term: s.to_string(),
+                position: offset + position,
+                message,
+            })
+    }
+}
+
+/// Parse a single [N-Triples] statement (or [N-Quads] statement if `quads` is true),
+/// extended with RDF-star quoted triples.
+///
+/// `line` must not contain any line terminator (LF or CR).
+/// Return `None` if it is empty or only contains a comment.
+///
+/// The returned terms borrow their text from `line`, except for
+/// IRIs and literals containing escape sequences (which need to be unescaped).
+///
+/// ```
+/// # use sophia_api::term::{parse_nt_statement, Term};
+/// let ([s, _, o], g) = parse_nt_statement(r#"_:b <tag:p> "o"@en <tag:g> ."#, true)?.unwrap();
+/// assert_eq!(s.bnode_id().unwrap().as_str(), "b");
+/// assert_eq!(o.language_tag().unwrap().as_str(), "en");
+/// assert!(g.is_some());
+/// assert!(parse_nt_statement("# comment", false)?.is_none());
+/// assert!(parse_nt_statement("<tag:s> <tag:p> <tag:o>", false).is_err());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// [N-Triples]: https://www.w3.org/TR/n-triples/
+/// [N-Quads]: https://www.w3.org/TR/n-quads/
+pub fn parse_nt_statement(
+    line: &str,
+    quads: bool,
+) -> Result<Option<Spog<SimpleTerm<'_>>>, TermParseError> {
+    Scanner::new(line)
+        .statement(quads)
+        .map_err(|(position, message)| TermParseError {
+            term: line.to_string(),
+            position,
+            message,
+        })
+}
+
+/// Error raised when parsing a term from a string fails
+/// (see [`SimpleTerm::from_str`] and [`parse_nt_statement`]).
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[error("Invalid term {term:?}: {message} (at byte {position})")]
+pub struct TermParseError {
+    /// The text that could not be parsed
+    pub term: String,
+    /// The position, in bytes, where the error was detected
+    pub position: usize,
+    /// A description of the error
+    pub message: &'static str,
+}
+
+/// A syntax error, as its position in the text and its description.
+type SyntaxError = (usize, &'static str);
+
+/// A cursor over a single statement or term.
+struct Scanner<'a> {
+    txt: &'a str,
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(txt: &'a str) -> Self {
+        Scanner { txt, pos: 0 }
+    }
+
+    /// Parse the statement, or return `None` if it is empty (or a comment).
+    fn statement(&mut self, quads: bool) -> Result<Option<Spog<SimpleTerm<'a>>>, SyntaxError> {
+        self.ws();
+        if self.at_end_or_comment() {
+            return Ok(None);
+        }
+        let s = self.subject()?;
+        self.ws();
+        let p = SimpleTerm::Iri(self.iri()?);
+        self.ws();
+        let o = self.object()?;
+        self.ws();
+        let g = match self.peek() {
+            Some(b'<') if quads => Some(SimpleTerm::Iri(self.iri()?)),
+            Some(b'_') if quads => Some(self.bnode()?),
+            _ => None,
+        };
+        self.ws();
+        self.expect(b'.', "expected '.'")?;
+        self.ws();
+        if !self.at_end_or_comment() {
+            return Err(self.error("expected end of line"));
+        }
+        Ok(Some(([s, p, o], g)))
+    }
+
+    /// Any term, as in the object position.
+    fn term(&mut self) -> Result<SimpleTerm<'a>, SyntaxError> {
+        match self.peek() {
+            Some(b'<' | b'_' | b'"') => self.object(),
+            _ => Err(self.error("expected IRI, blank node, literal or quoted triple")),
+        }
+    }
+
+    fn subject(&mut self) -> Result<SimpleTerm<'a>, SyntaxError> {
+        match self.peek() {
+            Some(b'<') if self.peek_at(1) == Some(b'<') => self.quoted_triple(),
+            Some(b'<') => Ok(SimpleTerm::Iri(self.iri()?)),
+            Some(b'_') => self.bnode(),
+            _ => Err(self.error("expected IRI, blank node or quoted triple")),
+        }
+    }
+
+    fn object(&mut self) -> Result<SimpleTerm<'a>, SyntaxError> {
+        match self.peek() {
+            Some(b'"') => self.literal(),
+            _ => self.subject(),
+        }
+    }
+
+    fn quoted_triple(&mut self) -> Result<SimpleTerm<'a>, SyntaxError> {
+        self.pos += 2; // <<
+        self.ws();
+        let s = self.subject()?;
+        self.ws();
+        let p = SimpleTerm::Iri(self.iri()?);
+        self.ws();
+        let o = self.object()?;
+        self.ws();
+        if !self.txt[self.pos..].starts_with(">>") {
+            return Err(self.error("expected '>>'"));
+        }
+        self.pos += 2;
+        Ok(SimpleTerm::Triple(Box::new([s, p, o])))
+    }
+
+    /// `IRIREF`, which must furthermore be absolute.
+    fn iri(&mut self) -> Result<IriRef<MownStr<'a>>, SyntaxError> {
+        let start = self.pos;
+        self.expect(b'<', "expected IRI")?;
+        let mut escaped = false;
+        loop {
+            match self.peek() {
+                Some(b'>') => break,
+                Some(b'\\') => {
+                    self.pos += 1;
+                    self.uchar()?;
+                    escaped = true;
+                }
+                Some(0..=b' ' | b'<' | b'"' | b'{' | b'}' | b'|' | b'^' | b'`') => {
+                    return Err(self.error("invalid character in IRI"));
+                }
+                None => return Err((start, "unterminated IRI")),
+                Some(_) => self.pos += 1,
+            }
+        }
+        let raw = &self.txt[start + 1..self.pos];
+        self.pos += 1; // >
+        let txt = if escaped {
+            MownStr::from(unescape(raw).ok_or((start, "invalid code point in IRI"))?)
+        } else {
+            MownStr::from(raw)
+        };
+        let iri = IriRef::new(txt).map_err(|_| (start, "invalid IRI"))?;
+        if iri.scheme().is_none() {
+            return Err((start, "IRI must be absolute"));
+        }
+        Ok(iri)
+    }
+
+    /// `BLANK_NODE_LABEL`
+    fn bnode(&mut self) -> Result<SimpleTerm<'a>, SyntaxError> {
+        let start = self.pos;
+        if !(self.eat(b'_') && self.eat(b':')) {
+            return Err((start, "expected blank node"));
+        }
+        // NB: although N-Triples allows ':' in blank node labels, BnodeId does not
+        match self.peek_char() {
+            Some(c) if c != ':' && (is_pn_chars_u(c) || c.is_ascii_digit()) => {
+                self.pos += c.len_utf8()
+            }
+            _ => return Err((start, "invalid blank node label")),
+        }
+        while let Some(c) = self.peek_char() {
+            if c == ':' || !(is_pn_chars(c) || c == '.') {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+        // the label can not end with '.', which is then the end of the statement
+        while self.txt.as_bytes()[self.pos - 1] == b'.' {
+            self.pos -= 1;
+        }
+        let label = &self.txt[start + 2..self.pos];
+        Ok(SimpleTerm::BlankNode(BnodeId::new_unchecked(label.into())))
+    }
+
+    /// `literal`, i.e. `STRING_LITERAL_QUOTE ('^^' IRIREF | LANGTAG)?`
+    fn literal(&mut self) -> Result<SimpleTerm<'a>, SyntaxError> {
+        let start = self.pos;
+        self.pos += 1; // "
+        let mut escaped = false;
+        loop {
+            match self.peek() {
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    self.pos += 1;
+                    if !self.echar() {
+                        self.uchar()?;
+                    }
+                    escaped = true;
+                }
+                Some(b'\n' | b'\r') | None => return Err((start, "unterminated string")),
+                Some(_) => self.pos += 1,
+            }
+        }
+        let raw = &self.txt[start + 1..self.pos];
+        self.pos += 1; // "
+        let lex = if escaped {
+            MownStr::from(unescape(raw).ok_or((start, "invalid code point in string"))?)
+        } else {
+            MownStr::from(raw)
+        };
+        match self.peek() {
+            Some(b'^') => {
+                if !self.txt[self.pos..].starts_with("^^") {
+                    return Err(self.error("expected '^^'"));
+                }
+                self.pos += 2;
+                Ok(SimpleTerm::LiteralDatatype(lex, self.iri()?))
+            }
+            Some(b'@') => {
+                self.pos += 1;
+                let tag_start = self.pos;
+                if self.alphanumerics(false) == 0 {
+                    return Err(self.error("invalid language tag"));
+                }
+                while self.eat(b'-') {
+                    if self.alphanumerics(true) == 0 {
+                        return Err(self.error("invalid language tag"));
+                    }
+                }
+                let tag = LanguageTag::new_unchecked(self.txt[tag_start..self.pos].into());
+                Ok(SimpleTerm::LiteralLanguage(lex, tag))
+            }
+            _ => Ok(SimpleTerm::LiteralDatatype(
+                lex,
+                XSD_STRING.map_unchecked(MownStr::from),
+            )),
+        }
+    }
+
+    /// `ECHAR`, after the backslash
+    fn echar(&mut self) -> bool {
+        let ok = matches!(
+            self.peek(),
+            Some(b't' | b'b' | b'n' | b'r' | b'f' | b'"' | b'\'' | b'\\')
+        );
+        if ok {
+            self.pos += 1;
+        }
+        ok
+    }
+
+    /// `UCHAR`, after the backslash
+    ///
+    /// Errors are located at the backslash.
+    fn uchar(&mut self) -> Result<(), SyntaxError> {
+        let start = self.pos - 1;
+        let len = match self.peek() {
+            Some(b'u') => 4,
+            Some(b'U') => 8,
+            _ => return Err((start, "invalid escape sequence")),
+        };
+        self.pos += 1;
+        let ok = self
+            .txt
+            .as_bytes()
+            .get(self.pos..self.pos + len)
+            .is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit));
+        if !ok {
+            return Err((start, "invalid escape sequence"));
+        }
+        self.pos += len;
+        Ok(())
+    }
+
+    /// Consume ASCII letters (and digits if `digits` is true), and return how many.
+    fn alphanumerics(&mut self, digits: bool) -> usize {
+        let start = self.pos;
+        while let Some(b) = self.peek() {
+            if !(b.is_ascii_alphabetic() || (digits && b.is_ascii_digit())) {
+                break;
+            }
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+
+    fn ws(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t')) {
+            self.pos += 1;
+        }
+    }
+
+    fn at_end_or_comment(&self) -> bool {
+        matches!(self.peek(), None | Some(b'#'))
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        let ok = self.peek() == Some(b);
+        if ok {
+            self.pos += 1;
+        }
+        ok
+    }
+
+    fn expect(&mut self, b: u8, message: &'static str) -> Result<(), SyntaxError> {
+        if self.eat(b) {
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    fn error(&self, message: &'static str) -> SyntaxError {
+        (self.pos, message)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<u8> {
+        self.txt.as_bytes().get(self.pos + offset).copied()
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        self.txt[self.pos..].chars().next()
+    }
+}
+
+/// Replace the `ECHAR` and `UCHAR` escape sequences of `raw`
+/// (assumed to be well-formed) by the characters they represent.
+///
+/// Return `None` if a `UCHAR` does not represent a valid character.
+fn unescape(raw: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(i) = rest.find('\\') {
+        unescaped.push_str(&rest[..i]);
+        let (c, len) = match rest.as_bytes()[i + 1] {
+            b't' => ('\t', 2),
+            b'b' => ('\u{8}', 2),
+            b'n' => ('\n', 2),
+            b'r' => ('\r', 2),
+            b'f' => ('\u{C}', 2),
+            b'"' => ('"', 2),
+            b'\'' => ('\'', 2),
+            b'\\' => ('\\', 2),
+            b'u' => (hex_char(&rest[i + 2..i + 6])?, 6),
+            b'U' => (hex_char(&rest[i + 2..i + 10])?, 10),
+            _ => unreachable!("escape sequences are checked by the scanner"),
+        };
+        unescaped.push(c);
+        rest = &rest[i + len..];
+    }
+    unescaped.push_str(rest);
+    Some(unescaped)
+}
+
+/// `PN_CHARS_U` (which, in N-Triples, includes ':')
+fn is_pn_chars_u(c: char) -> bool {
+    matches!(c,
+        'A'..='Z'
+        | 'a'..='z'
+        | '_'
+        | ':'
+        | '\u{C0}'..='\u{D6}'
+        | '\u{D8}'..='\u{F6}'
+        | '\u{F8}'..='\u{2FF}'
+        | '\u{370}'..='\u{37D}'
+        | '\u{37F}'..='\u{1FFF}'
+        | '\u{200C}'..='\u{200D}'
+        | '\u{2070}'..='\u{218F}'
+        | '\u{2C00}'..='\u{2FEF}'
+        | '\u{3001}'..='\u{D7FF}'
+        | '\u{F900}'..='\u{FDCF}'
+        | '\u{FDF0}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{EFFFF}'
+    )
+}
+
+/// `PN_CHARS`
+fn is_pn_chars(c: char) -> bool {
+    is_pn_chars_u(c)
+        || matches!(c,
+            '-' | '0'..='9' | '\u{B7}' | '\u{300}'..='\u{36F}' | '\u{203F}'..='\u{2040}'
+        )
+}
+
+/// The character whose code point is given by the hexadecimal digits `hex`.
+fn hex_char(hex: &str) -> Option<char> {
+    char::from_u32(u32::from_str_radix(hex, 16).ok()?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ns::{rdf, xsd};
+    use test_case::test_case;
+
+    fn parse(txt: &str) -> SimpleTerm<'static> {
+        txt.parse().unwrap()
+    }
+
+    #[test]
+    fn iri() {
+        assert!(Term::eq(
+            &parse("<http://example.org/a>"),
+            IriRef::new_unchecked("http://example.org/a")
+        ));
+        assert!(Term::eq(
+            &parse("<http://www.w3.org/1999/02/22-rdf-syntax-ns#type>"),
+            rdf::type_
+        ));
+        assert_eq!(parse(" <tag:\\u00E9>\n").iri().unwrap().as_str(), "tag:é");
+    }
+
+    #[test]
+    fn bnode() {
+        assert_eq!(parse("_:b1").bnode_id().unwrap().as_str(), "b1");
+        assert_eq!(parse("_:a.b").bnode_id().unwrap().as_str(), "a.b");
+    }
+
+    #[test]
+    fn literals() {
+        assert!(Term::eq(&parse("\"foo\""), "foo"));
+        assert!(Term::eq(
+            &parse("\"42\"^^<http://www.w3.org/2001/XMLSchema#integer>"),
+            42
+        ));
+        assert!(Term::eq(
+            &parse("\"chat\"@en-GB"),
+            "chat" * LanguageTag::new_unchecked("en-GB")
+        ));
+        assert_eq!(
+            parse(r#""a\tb\"c\\ \u00e9\U0001F600""#)
+                .lexical_form()
+                .unwrap(),
+            "a\tb\"c\\ é😀"
+        );
+        assert!(Term::eq(&parse("\"\""), "" * xsd::string));
+    }
+
+    #[test]
+    fn quoted_triple() {
+        let t = parse("<< _:s <tag:p> <<_:s <tag:p> \"o\">> >>");
+        let [s, p, o] = t.triple().unwrap();
+        assert!(s.is_blank_node());
+        assert!(p.is_iri());
+        assert!(o.is_triple());
+    }
+
+    #[test_case("", 0; "empty")]
+    #[test_case("foo", 0)]
+    #[test_case("<tag:a> <tag:b>", 8)]
+    #[test_case("<tag:a b>", 6)]
+    #[test_case("<a>", 0)]
+    #[test_case("<tag:\\u00e>", 5)]
+    #[test_case("_:", 0; "empty bnode label")]
+    #[test_case("_:b.", 3)]
+    #[test_case("\"unterminated", 0)]
+    #[test_case("\"bad\\x\"", 4)]
+    #[test_case("\"\\uD800\"", 0)]
+    #[test_case("\"lit\"@", 6)]
+    #[test_case("\"lit\"@1en", 6)]
+    #[test_case("\"lit\"^<tag:d>", 5)]
+    #[test_case("<< \"s\" <tag:p> <tag:o> >>", 3)]
+    #[test_case("<< <tag:s> <tag:p> <tag:o> >", 27)]
+    #[test_case("<tag:a", 0)]
+    #[test_case("?x", 0)]
+    fn invalid(txt: &str, position: usize) {
+        let err = txt.parse::<SimpleTerm>().unwrap_err();
+        assert_eq!(err.position, position, "{txt:?}: {err}");
+        assert_eq!(err.term, txt);
+    }
+}
@@ -1,8 +1,13 @@
 use super::*;
-use crate::ns::rdf;
+use crate::ns::{rdf, xsd};
 
 lazy_static::lazy_static! {
     static ref RDF_LANG_STRING: Box<str> = rdf::langString.iri().unwrap().unwrap().into();
+    static ref XSD_BOOLEAN: Box<str> = xsd::boolean.iri().unwrap().unwrap().into();
+    static ref XSD_DOUBLE: Box<str> = xsd::double.iri().unwrap().unwrap().into();
+    static ref XSD_FLOAT: Box<str> = xsd::float.iri().unwrap().unwrap().into();
+    static ref XSD_INTEGER: Box<str> = xsd::integer.iri().unwrap().unwrap().into();
+    static ref XSD_STRING: Box<str> = xsd::string.iri().unwrap().unwrap().into();
 }
 
 /// A straightforward implementation of [`Term`] as an enum.
@@ -217,10 +222,208 @@ impl Ord for SimpleTerm<'_> {
     }
 }
 
+fn literal<'a, T: Into<MownStr<'a>>>(lex: T, datatype: &'static str) -> SimpleTerm<'a> {
+    LiteralDatatype(
+        lex.into(),
+        IriRef::new_unchecked(MownStr::from_str(datatype)),
+    )
+}
+
+macro_rules! from_integer {
+    ($($t: ty),*) => {
+        $(
+            /// Build an `xsd:integer` literal, with a canonical lexical form.
+            impl From<$t> for SimpleTerm<'static> {
+                fn from(value: $t) -> Self {
+                    literal(value.to_string(), &XSD_INTEGER)
+                }
+            }
+        )*
+    };
+}
+from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! from_float {
+    ($($t: ty => $datatype: ident, $name: literal),*) => {
+        $(
+            #[doc = concat!("Build an `xsd:", $name, "` literal, with a canonical lexical form")]
+            /// (e.g. `1.5E2`, `0.0E0`, `INF` or `NaN`).
+            impl From<$t> for SimpleTerm<'static> {
+                fn from(value: $t) -> Self {
+                    let lex = if value.is_nan() {
+                        "NaN".to_string()
+                    } else if value == <$t>::INFINITY {
+                        "INF".to_string()
+                    } else if value == <$t>::NEG_INFINITY {
+                        "-INF".to_string()
+                    } else {
+                        // the canonical mantissa always contains a decimal point
+                        let lex = format!("{value:E}");
+                        match lex.split_once('E') {
+                            Some((mantissa, exponent)) if !mantissa.contains('.') => {
+                                format!("{mantissa}.0E{exponent}")
+                            }
+                            _ => lex,
+                        }
+                    };
+                    literal(lex, &$datatype)
+                }
+            }
+        )*
+    };
+}
+from_float!(f32 => XSD_FLOAT, "float", f64 => XSD_DOUBLE, "double");
+
+/// Build an `xsd:boolean` literal.
+impl From<bool> for SimpleTerm<'static> {
+    fn from(value: bool) -> Self {
+        literal(if value { "true" } else { "false" }, &XSD_BOOLEAN)
+    }
+}
+
+/// Build an `xsd:string` literal, borrowing `value`.
+impl<'a> From<&'a str> for SimpleTerm<'a> {
+    fn from(value: &'a str) -> Self {
+        literal(value, &XSD_STRING)
+    }
+}
+
+/// Build an `xsd:string` literal.
+impl From<String> for SimpleTerm<'static> {
+    fn from(value: String) -> Self {
+        literal(value, &XSD_STRING)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod _serde {
+    use super::*;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// The serialized form of a [`SimpleTerm`], as an externally tagged enum
+    /// (e.g. `{"iri": "http://example.org/"}` or `{"literal": ["42", "http://www.w3.org/2001/XMLSchema#integer"]}` in JSON).
+    ///
+    /// It is generic over the representation of strings and quoted triples,
+    /// which are borrowed when serializing, and owned when deserializing.
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Repr<S, T> {
+        Iri(S),
+        Bnode(S),
+        Literal(S, S),
+        Lang(S, S),
+        Triple(T),
+        Var(S),
+    }
+
+    impl Serialize for SimpleTerm<'_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let repr: Repr<&str, &[SimpleTerm; 3]> = match self {
+                SimpleTerm::Iri(iri) => Repr::Iri(iri.as_str()),
+                SimpleTerm::BlankNode(bnid) => Repr::Bnode(bnid.as_str()),
+                SimpleTerm::LiteralDatatype(lex, dt) => Repr::Literal(&lex[..], dt.as_str()),
+                SimpleTerm::LiteralLanguage(lex, tag) => Repr::Lang(&lex[..], tag.as_str()),
+                SimpleTerm::Triple(tr) => Repr::Triple(tr),
+                SimpleTerm::Variable(vn) => Repr::Var(vn.as_str()),
+            };
+            repr.serialize(serializer)
+        }
+    }
+
+    /// NB: the validity of IRIs, blank node identifiers, language tags and variable names
+    /// is checked during deserialization.
+    /// Language tags are checked with [`LanguageTag::new_lenient`],
+    /// consistently with the parsers.
+    impl<'de> Deserialize<'de> for SimpleTerm<'static> {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let repr = Repr::<String, Box<[SimpleTerm<'static>; 3]>>::deserialize(deserializer)?;
+            Ok(match repr {
+                Repr::Iri(iri) => SimpleTerm::Iri(iri_ref(iri)?),
+                Repr::Bnode(bnid) => SimpleTerm::BlankNode(
+                    BnodeId::new(MownStr::from(bnid)).map_err(D::Error::custom)?,
+                ),
+                Repr::Literal(lex, dt) => SimpleTerm::LiteralDatatype(lex.into(), iri_ref(dt)?),
+                Repr::Lang(lex, tag) => SimpleTerm::LiteralLanguage(
+                    lex.into(),
+                    LanguageTag::new_lenient(MownStr::from(tag)).map_err(D::Error::custom)?,
+                ),
+                Repr::Triple(tr) => SimpleTerm::Triple(tr),
+                Repr::Var(vn) => {
+                    SimpleTerm::Variable(VarName::new(MownStr::from(vn)).map_err(D::Error::custom)?)
+                }
+            })
+        }
+    }
+
+    fn iri_ref<E: Error>(iri: String) -> Result<IriRef<MownStr<'static>>, E> {
+        IriRef::new(MownStr::from(iri)).map_err(E::custom)
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct MyTable {
+            terms: Vec<SimpleTerm<'static>>,
+        }
+
+        #[test]
+        fn round_trip() {
+            let en = LanguageTag::new_unchecked("en");
+            let data = MyTable {
+                terms: vec![
+                    rdf::type_.into_term(),
+                    BnodeId::new_unchecked("b1").into_term(),
+                    42.into_term(),
+                    ("hello" * en).into_term(),
+                    SimpleTerm::Triple(Box::new(
+                        [rdf::type_, rdf::type_, rdf::Property].map(Term::into_term),
+                    )),
+                    VarName::new_unchecked("x").into_term(),
+                ],
+            };
+            let toml_str = toml::to_string(&data).unwrap();
+            let data2 = toml::from_str::<MyTable>(&toml_str).unwrap();
+            assert_eq!(data, data2);
+        }
+
+        #[test]
+        fn round_trip_lenient_language_tag() {
+            let tag = LanguageTag::new_lenient("abc-de-fg-hi").unwrap();
+            let data = MyTable {
+                terms: vec![("x" * tag).into_term()],
+            };
+            let toml_str = toml::to_string(&data).unwrap();
+            let data2 = toml::from_str::<MyTable>(&toml_str).unwrap();
+            assert_eq!(data, data2);
+        }
+
+        #[test]
+        fn invalid_iri() {
+            let toml_str = r#"terms = [{ iri = "not an IRI" }]"#;
+            assert!(toml::from_str::<MyTable>(toml_str).is_err());
+        }
+
+        #[test]
+        fn invalid_language_tag() {
+            let toml_str = r#"terms = [{ lang = ["hello", "not a tag"] }]"#;
+            assert!(toml::from_str::<MyTable>(toml_str).is_err());
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::ns::xsd;
+    use test_case::test_case;
 
     #[test]
     fn iri_from_scratch() {
@@ -460,4 +663,31 @@ mod test {
         assert_eq!(t.variable().unwrap(), v);
         assert!(t.variable().unwrap().unwrap().is_borrowed());
     }
+
+    #[test_case(SimpleTerm::from(42i8), "42", xsd::integer)]
+    #[test_case(SimpleTerm::from(-42i32), "-42", xsd::integer)]
+    #[test_case(SimpleTerm::from(u64::MAX), "18446744073709551615", xsd::integer)]
+    #[test_case(SimpleTerm::from(1.25f64), "1.25E0", xsd::double)]
+    #[test_case(SimpleTerm::from(150f64), "1.5E2", xsd::double)]
+    #[test_case(SimpleTerm::from(-0.001f64), "-1.0E-3", xsd::double)]
+    #[test_case(SimpleTerm::from(0f64), "0.0E0", xsd::double)]
+    #[test_case(SimpleTerm::from(f64::NEG_INFINITY), "-INF", xsd::double)]
+    #[test_case(SimpleTerm::from(f32::NAN), "NaN", xsd::float)]
+    #[test_case(SimpleTerm::from(0.1f32), "1.0E-1", xsd::float)]
+    #[test_case(SimpleTerm::from(true), "true", xsd::boolean)]
+    #[test_case(SimpleTerm::from("hello"), "hello", xsd::string)]
+    #[test_case(SimpleTerm::from("hello".to_string()), "hello", xsd::string)]
+    fn from_native(t: SimpleTerm, lex: &str, dt: crate::ns::NsTerm) {
+        assert_consistent_term_impl(&t);
+        assert_eq!(t.kind(), TermKind::Literal);
+        assert_eq!(t.lexical_form().unwrap(), lex);
+        assert_eq!(t.datatype(), dt.iri());
+    }
+
+    #[test]
+    fn from_native_eq_native_term() {
+        assert_eq!(SimpleTerm::from(42i32), 42);
+        assert_eq!(SimpleTerm::from(true), true);
+        assert_eq!(SimpleTerm::from("hello"), "hello");
+    }
 }
@@ -0,0 +1,292 @@
+use super::literal_value::{collapse, xsd_local_name, Numeric};
+use super::*;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    /// The lexical space of `xsd:dateTime`
+    static ref DATE_TIME: Regex = Regex::new(
+        r"^(-?(?:[1-9][0-9]{3,}|0[0-9]{3}))-([0-9]{2})-([0-9]{2})T([0-9]{2}):([0-9]{2}):([0-9]{2})(?:\.([0-9]+))?(Z|[+-][0-9]{2}:[0-9]{2})?$"
+    ).unwrap();
+    /// The lexical space of `xsd:date`
+    static ref DATE: Regex = Regex::new(
+        r"^(-?(?:[1-9][0-9]{3,}|0[0-9]{3}))-([0-9]{2})-([0-9]{2})(Z|[+-][0-9]{2}:[0-9]{2})?$"
+    ).unwrap();
+}
+
+/// A wrapper for any term type, ordering terms with [`Term::sparql_cmp`].
+///
+/// This is convenient to sort terms (or solutions) with [`slice::sort_by_key`],
+/// or to store them in a [`BTreeSet`](std::collections::BTreeSet).
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug)]
+pub struct SparqlOrd<T>(pub T);
+
+impl<T1: Term, T2: Term> PartialEq<SparqlOrd<T2>> for SparqlOrd<T1> {
+    fn eq(&self, other: &SparqlOrd<T2>) -> bool {
+        sparql_cmp(&self.0, &other.0) == Ordering::Equal
+    }
+}
+
+impl<T: Term> Eq for SparqlOrd<T> {}
+
+impl<T1: Term, T2: Term> PartialOrd<SparqlOrd<T2>> for SparqlOrd<T1> {
+    fn partial_cmp(&self, other: &SparqlOrd<T2>) -> Option<Ordering> {
+        Some(sparql_cmp(&self.0, &other.0))
+    }
+}
+
+impl<T: Term> Ord for SparqlOrd<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        sparql_cmp(&self.0, &other.0)
+    }
+}
+
+/// Implementation of [`Term::sparql_cmp`].
+pub(crate) fn sparql_cmp<T1, T2>(t1: &T1, t2: &T2) -> Ordering
+where
+    T1: Term + ?Sized,
+    T2: Term,
+{
+    let k1 = t1.kind();
+    let k2 = t2.kind();
+    kind_rank(k1).cmp(&kind_rank(k2)).then_with(|| match k1 {
+        TermKind::Literal => {
+            let c1 = LiteralClass::of(t1);
+            let c2 = LiteralClass::of(t2);
+            c1.rank()
+                .cmp(&c2.rank())
+                .then_with(|| match (c1, c2) {
+                    (LiteralClass::Numeric(n1), LiteralClass::Numeric(n2)) => numeric_cmp(n1, n2),
+                    (LiteralClass::Boolean(b1), LiteralClass::Boolean(b2)) => Ord::cmp(&b1, &b2),
+                    (LiteralClass::Instant(i1), LiteralClass::Instant(i2)) => i1.cmp(&i2),
+                    (LiteralClass::Date(i1), LiteralClass::Date(i2)) => i1.cmp(&i2),
+                    (LiteralClass::LangString, LiteralClass::LangString) => {
+                        t1.lexical_form().unwrap().cmp(&t2.lexical_form().unwrap())
+                    }
+                    _ => Ordering::Equal,
+                })
+                // literals with the same value are ordered as terms
+                .then_with(|| Term::cmp(t1, t2.borrow_term()))
+        }
+        TermKind::Triple => {
+            let spo1 = t1.triple().unwrap();
+            let spo2 = t2.triple().unwrap();
+            sparql_cmp(&spo1[0], &spo2[0])
+                .then_with(|| sparql_cmp(&spo1[1], &spo2[1]))
+                .then_with(|| sparql_cmp(&spo1[2], &spo2[2]))
+        }
+        _ => Term::cmp(t1, t2.borrow_term()),
+    })
+}
+
+/// The rank of each kind of term in [`sparql_cmp`].
+fn kind_rank(kind: TermKind) -> u8 {
+    match kind {
+        TermKind::BlankNode => 0,
+        TermKind::Iri => 1,
+        TermKind::Literal => 2,
+        TermKind::Triple => 3,
+        TermKind::Variable => 4,
+    }
+}
+
+/// Numbers are ordered by value, `NaN` coming after every other number.
+fn numeric_cmp(n1: Numeric, n2: Numeric) -> Ordering {
+    n1.exact_cmp(&n2).unwrap_or_else(|| {
+        let (d1, d2) = (n1.as_f64(), n2.as_f64());
+        d1.partial_cmp(&d2)
+            .unwrap_or_else(|| Ord::cmp(&d1.is_nan(), &d2.is_nan()))
+    })
+}
+
+/// The different classes of literals, in the order used by [`sparql_cmp`],
+/// with the value used to compare literals of the same class (if any).
+enum LiteralClass {
+    Numeric(Numeric),
+    Boolean(bool),
+    /// `xsd:dateTime` and `xsd:dateTimeStamp`
+    Instant((i128, String)),
+    /// `xsd:date`
+    Date((i128, String)),
+    /// `xsd:string`
+    String,
+    LangString,
+    Other,
+}
+
+impl LiteralClass {
+    fn of<T: Term + ?Sized>(t: &T) -> Self {
+        if t.language_tag().is_some() {
+            return LiteralClass::LangString;
+        }
+        if let Some(n) = Numeric::of(t) {
+            return LiteralClass::Numeric(n);
+        }
+        let lex = t.lexical_form().unwrap();
+        let datatype = t.datatype().unwrap();
+        match xsd_local_name(datatype.as_str()) {
+            Some("boolean") => t.value().map_or(LiteralClass::Other, LiteralClass::Boolean),
+            Some("dateTime" | "dateTimeStamp") => {
+                instant(&DATE_TIME, &lex).map_or(LiteralClass::Other, LiteralClass::Instant)
+            }
+            Some("date") => instant(&DATE, &lex).map_or(LiteralClass::Other, LiteralClass::Date),
+            Some("string") => LiteralClass::String,
+            _ => LiteralClass::Other,
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            LiteralClass::Numeric(_) => 0,
+            LiteralClass::Boolean(_) => 1,
+            LiteralClass::Instant(_) => 2,
+            LiteralClass::Date(_) => 3,
+            LiteralClass::String => 4,
+            LiteralClass::LangString => 5,
+            LiteralClass::Other => 6,
+        }
+    }
+}
+
+/// Parse a lexical form matching [`DATE_TIME`] or [`DATE`] into
+/// the number of seconds since 1970-01-01T00:00:00Z and the significant fractional digits.
+///
+/// NB: values without a timezone are considered to be in UTC.
+fn instant(re: &Regex, lexical_form: &str) -> Option<(i128, String)> {
+    let caps = re.captures(collapse(lexical_form))?;
+    let year: i128 = caps[1].parse().ok()?;
+    let [month, day] = [2, 3].map(|i| caps[i].parse::<i128>().unwrap());
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+    let mut seconds = days_from_civil(year, month, day) * 86400;
+    let mut frac = "";
+    let mut tz_index = 4;
+    if caps.len() > 5 {
+        let [h, m, s] = [4, 5, 6].map(|i| caps[i].parse::<i128>().unwrap());
+        frac = caps.get(7).map_or("", |f| f.as_str()).trim_end_matches('0');
+        // 24:00:00 is the first instant of the following day
+        let midnight = h == 24 && m == 0 && s == 0 && frac.is_empty();
+        if (h > 23 && !midnight) || m > 59 || s > 59 {
+            return None;
+        }
+        seconds += h * 3600 + m * 60 + s;
+        tz_index = 8;
+    }
+    if let Some(tz) = caps.get(tz_index).map(|z| z.as_str()) {
+        if tz != "Z" {
+            let h: i128 = tz[1..3].parse().unwrap();
+            let m: i128 = tz[4..6].parse().unwrap();
+            if m > 59 || h * 60 + m > 14 * 60 {
+                return None;
+            }
+            let offset = h * 3600 + m * 60;
+            seconds += if tz.starts_with('-') { offset } else { -offset };
+        }
+    }
+    Some((seconds, frac.to_string()))
+}
+
+fn is_leap_year(year: i128) -> bool {
+    year.rem_euclid(4) == 0 && (year.rem_euclid(100) != 0 || year.rem_euclid(400) == 0)
+}
+
+fn days_in_month(year: i128, month: i128) -> i128 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The number of days between 1970-01-01 and the given date of the proleptic Gregorian calendar
+/// (see <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>).
+fn days_from_civil(year: i128, month: i128, day: i128) -> i128 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ns::{rdf, xsd};
+    use test_case::test_case;
+
+    #[test]
+    fn days() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        assert_eq!(days_from_civil(0, 3, 1), -719468);
+    }
+
+    #[test_case(BnodeId::new_unchecked("b").into_term(), rdf::type_.into_term())]
+    #[test_case(rdf::type_.into_term(), rdf::value.into_term())]
+    #[test_case(rdf::value.into_term(), "0" * xsd::integer)]
+    #[test_case("2" * xsd::integer, "10" * xsd::integer)]
+    #[test_case("1.5" * xsd::decimal, "2" * xsd::integer)]
+    #[test_case("1.0" * xsd::decimal, "1" * xsd::integer; "same value, ordered by datatype")]
+    #[test_case("INF" * xsd::double, "NaN" * xsd::double)]
+    #[test_case("NaN" * xsd::double, "false" * xsd::boolean)]
+    #[test_case("false" * xsd::boolean, "1" * xsd::boolean)]
+    #[test_case("true" * xsd::boolean, "2000-01-01T00:00:00Z" * xsd::dateTime)]
+    #[test_case("2000-01-01T01:00:00+02:00" * xsd::dateTime, "2000-01-01T00:00:00Z" * xsd::dateTime)]
+    #[test_case("2000-01-01T00:00:00.25Z" * xsd::dateTime, "2000-01-01T00:00:00.5Z" * xsd::dateTime)]
+    #[test_case("1999-12-31T24:00:00Z" * xsd::dateTime, "2000-01-01T00:00:00.1Z" * xsd::dateTime)]
+    #[test_case("-0001-01-01T00:00:00Z" * xsd::dateTime, "0001-01-01T00:00:00Z" * xsd::dateTime)]
+    #[test_case("2000-01-01T00:00:00Z" * xsd::dateTime, "1999-01-01" * xsd::date)]
+    #[test_case("1999-02-01" * xsd::date, "1999-10-01" * xsd::date)]
+    #[test_case("3000-01-01" * xsd::date, "a" * xsd::string)]
+    #[test_case("a" * xsd::string, "b" * xsd::string)]
+    #[test_case("b" * xsd::string, "a" * LanguageTag::new_unchecked("fr"))]
+    #[test_case("a" * LanguageTag::new_unchecked("fr"), "b" * LanguageTag::new_unchecked("en"))]
+    #[test_case("a" * LanguageTag::new_unchecked("en"), "a" * LanguageTag::new_unchecked("fr"))]
+    #[test_case("b" * LanguageTag::new_unchecked("en"), "a" * xsd::anyURI)]
+    #[test_case("x" * xsd::integer, "y" * xsd::integer; "invalid literals")]
+    #[test_case("3000-01-01" * xsd::date, "1999-02-30" * xsd::date; "invalid date")]
+    #[test_case("a" * xsd::anyURI, SimpleTerm::Triple(Box::new([rdf::type_.into_term(), rdf::type_.into_term(), rdf::type_.into_term()])))]
+    #[test_case(SimpleTerm::Triple(Box::new([rdf::type_.into_term(), rdf::type_.into_term(), "2" * xsd::integer])), SimpleTerm::Triple(Box::new([rdf::type_.into_term(), rdf::type_.into_term(), "10" * xsd::integer])))]
+    #[test_case(SimpleTerm::Triple(Box::new([rdf::type_.into_term(), rdf::type_.into_term(), rdf::type_.into_term()])), VarName::new_unchecked("x").into_term())]
+    fn less(t1: SimpleTerm, t2: SimpleTerm) {
+        assert_eq!(t1.sparql_cmp(&t2), Ordering::Less);
+        assert_eq!(t2.sparql_cmp(&t1), Ordering::Greater);
+    }
+
+    #[test_case("42" * xsd::integer)]
+    #[test_case("NaN" * xsd::double)]
+    #[test_case("2000-01-01T00:00:00Z" * xsd::dateTime)]
+    #[test_case("a" * LanguageTag::new_unchecked("en"))]
+    #[test_case("x" * xsd::integer)]
+    fn equal(t: SimpleTerm) {
+        assert_eq!(t.sparql_cmp(&t), Ordering::Equal);
+    }
+
+    #[test]
+    fn sort_by_key() {
+        let mut terms = [
+            "10" * xsd::integer,
+            "a" * xsd::string,
+            rdf::type_.into_term(),
+            "2.5" * xsd::double,
+            BnodeId::new_unchecked("b").into_term(),
+        ];
+        terms.sort_by_key(|t| SparqlOrd(t.clone()));
+        let expected = [
+            BnodeId::new_unchecked("b").into_term(),
+            rdf::type_.into_term(),
+            "2.5" * xsd::double,
+            "10" * xsd::integer,
+            "a" * xsd::string,
+        ];
+        assert!(SparqlOrd(&terms[0]) == SparqlOrd(&expected[0]));
+        for (t, e) in terms.iter().zip(expected.iter()) {
+            assert!(Term::eq(t, e));
+        }
+    }
+}
@@ -74,6 +74,99 @@ where
     }
 }
 
+/// A strategy for minting fresh [blank node identifiers](BnodeId),
+/// e.g. when a parser or a term factory needs a new blank node.
+///
+/// The following implementations are provided:
+/// * [`CounterBnodeIdGenerator`], deterministic, convenient for reproducible test fixtures;
+/// * [`PrefixedBnodeIdGenerator`], scoping the identifiers minted by another generator;
+/// * `UuidBnodeIdGenerator` (with the `uuid` feature),
+///   convenient when merging data produced by several independent workers.
+pub trait BnodeIdGenerator {
+    /// Mint a new blank node identifier,
+    /// distinct from all the identifiers previously minted by this generator.
+    fn next_bnode_id(&mut self) -> BnodeId<Box<str>>;
+}
+
+impl<G: BnodeIdGenerator + ?Sized> BnodeIdGenerator for &mut G {
+    fn next_bnode_id(&mut self) -> BnodeId<Box<str>> {
+        (**self).next_bnode_id()
+    }
+}
+
+impl<G: BnodeIdGenerator + ?Sized> BnodeIdGenerator for Box<G> {
+    fn next_bnode_id(&mut self) -> BnodeId<Box<str>> {
+        (**self).next_bnode_id()
+    }
+}
+
+/// A deterministic [`BnodeIdGenerator`], minting `b0`, `b1`, `b2`...
+#[derive(Clone, Debug, Default)]
+pub struct CounterBnodeIdGenerator {
+    next: u64,
+}
+
+impl CounterBnodeIdGenerator {
+    /// Build a new [`CounterBnodeIdGenerator`], starting at `b0`
+    pub fn new() -> Self {
+        Self::starting_at(0)
+    }
+
+    /// Build a new [`CounterBnodeIdGenerator`], starting at `b{start}`
+    pub fn starting_at(start: u64) -> Self {
+        CounterBnodeIdGenerator { next: start }
+    }
+}
+
+impl BnodeIdGenerator for CounterBnodeIdGenerator {
+    fn next_bnode_id(&mut self) -> BnodeId<Box<str>> {
+        let id = format!("b{}", self.next);
+        self.next += 1;
+        BnodeId::new_unchecked(id.into())
+    }
+}
+
+/// A [`BnodeIdGenerator`] prepending a fixed prefix to the identifiers minted by another generator,
+/// so that identifiers minted in different scopes (e.g. by different workers, or for different documents)
+/// never clash.
+#[derive(Clone, Debug)]
+pub struct PrefixedBnodeIdGenerator<G> {
+    prefix: Box<str>,
+    inner: G,
+}
+
+impl<G: BnodeIdGenerator> PrefixedBnodeIdGenerator<G> {
+    /// Build a new [`PrefixedBnodeIdGenerator`] prepending `prefix` to the identifiers minted by `inner`
+    pub fn new<T: Borrow<str>>(prefix: BnodeId<T>, inner: G) -> Self {
+        PrefixedBnodeIdGenerator {
+            prefix: prefix.as_str().into(),
+            inner,
+        }
+    }
+}
+
+impl<G: BnodeIdGenerator> BnodeIdGenerator for PrefixedBnodeIdGenerator<G> {
+    fn next_bnode_id(&mut self) -> BnodeId<Box<str>> {
+        let id = self.inner.next_bnode_id();
+        // the concatenation of two valid identifiers is a valid identifier
+        BnodeId::new_unchecked(format!("{}{}", self.prefix, id.as_str()).into())
+    }
+}
+
+/// A [`BnodeIdGenerator`] minting random (version 4) UUIDs,
+/// which are unique with overwhelming probability, even across processes.
+#[cfg(feature = "uuid")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UuidBnodeIdGenerator;
+
+#[cfg(feature = "uuid")]
+impl BnodeIdGenerator for UuidBnodeIdGenerator {
+    fn next_bnode_id(&mut self) -> BnodeId<Box<str>> {
+        let id = uuid::Uuid::new_v4().hyphenated().to_string();
+        BnodeId::new_unchecked(id.into())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -100,4 +193,37 @@ mod test {
     fn invalid(tag: &str) {
         assert!(BnodeId::new(tag).is_err());
     }
+
+    #[test]
+    fn counter_generator() {
+        let mut generator = CounterBnodeIdGenerator::new();
+        assert_eq!(generator.next_bnode_id().as_str(), "b0");
+        assert_eq!(generator.next_bnode_id().as_str(), "b1");
+        let mut generator = CounterBnodeIdGenerator::starting_at(42);
+        assert_eq!(generator.next_bnode_id().as_str(), "b42");
+    }
+
+    #[test]
+    fn prefixed_generator() {
+        let mut generator = PrefixedBnodeIdGenerator::new(
+            BnodeId::new_unchecked("w1_"),
+            CounterBnodeIdGenerator::new(),
+        );
+        assert_eq!(generator.next_bnode_id().as_str(), "w1_b0");
+        let mut boxed: Box<dyn BnodeIdGenerator + '_> = Box::new(&mut generator);
+        assert_eq!(boxed.next_bnode_id().as_str(), "w1_b1");
+        drop(boxed);
+        assert_eq!(generator.next_bnode_id().as_str(), "w1_b2");
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuid_generator() {
+        let mut generator = UuidBnodeIdGenerator;
+        let id1 = generator.next_bnode_id();
+        let id2 = generator.next_bnode_id();
+        assert!(BnodeId::new(id1.as_str()).is_ok());
+        assert_eq!(id1.as_str().len(), 36);
+        assert_ne!(id1.as_str(), id2.as_str());
+    }
 }
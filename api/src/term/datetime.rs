@@ -0,0 +1,519 @@
+//! I provide support for date/time literals, through the [`chrono`] crate.
+//!
+//! * The following types implement [`LiteralValue`],
+//!   so they can be extracted from literals with [`Term::value`]:
+//!   - [`DateTime<FixedOffset>`] and [`DateTime<Utc>`], from `xsd:dateTime` and `xsd:dateTimeStamp`
+//!     literals with a timezone;
+//!   - [`NaiveDateTime`], from `xsd:dateTime` literals without a timezone;
+//!   - [`NaiveDate`], from `xsd:date` literals without a timezone;
+//!   - [`TimeDelta`], from `xsd:duration` literals without years or months
+//!     (which do not have a fixed length), and from `xsd:dayTimeDuration` literals.
+//! * [`SimpleTerm`] implements [`From`] for all these types,
+//!   building `xsd:dateTime`, `xsd:date` and `xsd:duration` literals
+//!   with a canonical lexical form.
+//!
+//! Literals whose value can not be represented by the requested type
+//! (e.g. a literal without a timezone, extracted as a [`DateTime`])
+//! raise [`LiteralValueError::OutOfRange`].
+//! Fractional seconds beyond the nanosecond are ignored.
+//!
+//! # Example
+//! ```
+//! # use chrono::{DateTime, FixedOffset, TimeDelta};
+//! # use sophia_api::ns::xsd;
+//! # use sophia_api::term::{SimpleTerm, Term};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let start: DateTime<FixedOffset> = ("2024-02-29T23:30:00+01:00" * xsd::dateTime).value()?;
+//! let end = start + TimeDelta::minutes(90);
+//! let lit = SimpleTerm::from(end);
+//! assert_eq!(lit.lexical_form().unwrap(), "2024-03-01T01:00:00+01:00");
+//! assert_eq!(SimpleTerm::from(end - start).lexical_form().unwrap(), "PT1H30M");
+//! # Ok(()) }
+//! ```
+use super::literal_value::{collapse, xsd_local_name, LiteralValue, LiteralValueError};
+use super::{SimpleTerm, Term};
+use crate::ns::{xsd, NsTerm};
+use chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeDelta,
+    TimeZone, Timelike, Utc,
+};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::fmt::Write;
+
+lazy_static! {
+    /// The lexical space of `xsd:dateTime`
+    static ref DATE_TIME: Regex = Regex::new(
+        r"^(-?(?:[1-9][0-9]{3,}|0[0-9]{3}))-([0-9]{2})-([0-9]{2})T([0-9]{2}):([0-9]{2}):([0-9]{2})(?:\.([0-9]+))?(Z|[+-][0-9]{2}:[0-9]{2})?$"
+    ).unwrap();
+    /// The lexical space of `xsd:date`
+    static ref DATE: Regex = Regex::new(
+        r"^(-?(?:[1-9][0-9]{3,}|0[0-9]{3}))-([0-9]{2})-([0-9]{2})(Z|[+-][0-9]{2}:[0-9]{2})?$"
+    ).unwrap();
+    /// The lexical space of `xsd:duration`, except that it also matches "P", "-P" and "...T"
+    static ref DURATION: Regex = Regex::new(
+        r"^(-)?P(?:([0-9]+)Y)?(?:([0-9]+)M)?(?:([0-9]+)D)?(?:T(?:([0-9]+)H)?(?:([0-9]+)M)?(?:([0-9]+)(?:\.([0-9]+))?S)?)?$"
+    ).unwrap();
+}
+
+/// Extract values from `xsd:dateTime` and `xsd:dateTimeStamp` literals with a timezone.
+impl LiteralValue for DateTime<FixedOffset> {
+    fn from_lexical(lexical_form: &str, datatype: &str) -> Result<Self, LiteralValueError> {
+        if !matches!(xsd_local_name(datatype), Some("dateTime" | "dateTimeStamp")) {
+            return Err(LiteralValueError::UnsupportedDatatype(datatype.into()));
+        }
+        let (local, offset) = parse_date_time(lexical_form, datatype)?;
+        let offset =
+            offset.ok_or_else(|| LiteralValueError::out_of_range(lexical_form, datatype))?;
+        local
+            .checked_sub_offset(offset)
+            .map(|utc| DateTime::from_naive_utc_and_offset(utc, offset))
+            .ok_or_else(|| LiteralValueError::out_of_range(lexical_form, datatype))
+    }
+}
+
+/// Extract values from `xsd:dateTime` and `xsd:dateTimeStamp` literals with a timezone.
+impl LiteralValue for DateTime<Utc> {
+    fn from_lexical(lexical_form: &str, datatype: &str) -> Result<Self, LiteralValueError> {
+        DateTime::<FixedOffset>::from_lexical(lexical_form, datatype).map(|dt| dt.to_utc())
+    }
+}
+
+/// Extract values from `xsd:dateTime` literals without a timezone.
+impl LiteralValue for NaiveDateTime {
+    fn from_lexical(lexical_form: &str, datatype: &str) -> Result<Self, LiteralValueError> {
+        if xsd_local_name(datatype) != Some("dateTime") {
+            return Err(LiteralValueError::UnsupportedDatatype(datatype.into()));
+        }
+        match parse_date_time(lexical_form, datatype)? {
+            (local, None) => Ok(local),
+            _ => Err(LiteralValueError::out_of_range(lexical_form, datatype)),
+        }
+    }
+}
+
+/// Extract values from `xsd:date` literals without a timezone.
+impl LiteralValue for NaiveDate {
+    fn from_lexical(lexical_form: &str, datatype: &str) -> Result<Self, LiteralValueError> {
+        if xsd_local_name(datatype) != Some("date") {
+            return Err(LiteralValueError::UnsupportedDatatype(datatype.into()));
+        }
+        let invalid = || LiteralValueError::invalid(lexical_form, datatype);
+        let caps = DATE.captures(collapse(lexical_form)).ok_or_else(invalid)?;
+        let date = parse_date(&caps, lexical_form, datatype)?;
+        match caps.get(4) {
+            None => Ok(date),
+            Some(tz) => {
+                parse_timezone(tz.as_str()).ok_or_else(invalid)?;
+                Err(LiteralValueError::out_of_range(lexical_form, datatype))
+            }
+        }
+    }
+}
+
+/// Extract values from `xsd:duration` literals without years or months,
+/// and from `xsd:dayTimeDuration` literals.
+impl LiteralValue for TimeDelta {
+    fn from_lexical(lexical_form: &str, datatype: &str) -> Result<Self, LiteralValueError> {
+        let day_time_only = match xsd_local_name(datatype) {
+            Some("duration") => false,
+            Some("dayTimeDuration") => true,
+            _ => return Err(LiteralValueError::UnsupportedDatatype(datatype.into())),
+        };
+        let invalid = || LiteralValueError::invalid(lexical_form, datatype);
+        let out_of_range = || LiteralValueError::out_of_range(lexical_form, datatype);
+        let txt = collapse(lexical_form);
+        let caps = DURATION.captures(txt).ok_or_else(invalid)?;
+        if txt.ends_with(['P', 'T'])
+            || (day_time_only && (caps.get(2).is_some() || caps.get(3).is_some()))
+        {
+            return Err(invalid());
+        }
+        let field = |i: usize| -> Result<i64, LiteralValueError> {
+            caps.get(i)
+                .map_or(Ok(0), |m| m.as_str().parse())
+                .map_err(|_| out_of_range())
+        };
+        if field(2)? != 0 || field(3)? != 0 {
+            return Err(out_of_range());
+        }
+        let seconds = [(4, 86400), (5, 3600), (6, 60), (7, 1)]
+            .into_iter()
+            .try_fold(0_i64, |acc, (i, factor)| {
+                field(i)?
+                    .checked_mul(factor)
+                    .and_then(|secs| acc.checked_add(secs))
+                    .ok_or_else(out_of_range)
+            })?;
+        let nanos = caps.get(8).map_or(0, |m| parse_nanos(m.as_str()));
+        let delta = TimeDelta::try_seconds(seconds)
+            .and_then(|delta| delta.checked_add(&TimeDelta::nanoseconds(nanos.into())))
+            .ok_or_else(out_of_range)?;
+        Ok(if caps.get(1).is_some() { -delta } else { delta })
+    }
+}
+
+/// Build an `xsd:dateTime` literal, with a canonical lexical form.
+///
+/// NB: the seconds of the timezone offset, if any, are ignored.
+impl<Tz: TimeZone> From<DateTime<Tz>> for SimpleTerm<'static> {
+    fn from(value: DateTime<Tz>) -> Self {
+        let offset = value.offset().fix();
+        let mut lex = String::new();
+        write_date_time(&mut lex, value.naive_local());
+        if offset.local_minus_utc() == 0 {
+            lex.push('Z');
+        } else {
+            let minutes = offset.local_minus_utc() / 60;
+            let sign = if minutes < 0 { '-' } else { '+' };
+            let minutes = minutes.abs();
+            write!(lex, "{sign}{:02}:{:02}", minutes / 60, minutes % 60).unwrap();
+        }
+        literal(&lex, xsd::dateTime)
+    }
+}
+
+/// Build an `xsd:dateTime` literal without a timezone, with a canonical lexical form.
+impl From<NaiveDateTime> for SimpleTerm<'static> {
+    fn from(value: NaiveDateTime) -> Self {
+        let mut lex = String::new();
+        write_date_time(&mut lex, value);
+        literal(&lex, xsd::dateTime)
+    }
+}
+
+/// Build an `xsd:date` literal without a timezone, with a canonical lexical form.
+impl From<NaiveDate> for SimpleTerm<'static> {
+    fn from(value: NaiveDate) -> Self {
+        let mut lex = String::new();
+        write_date(&mut lex, value);
+        literal(&lex, xsd::date)
+    }
+}
+
+/// Build an `xsd:duration` literal, with a canonical lexical form.
+impl From<TimeDelta> for SimpleTerm<'static> {
+    fn from(value: TimeDelta) -> Self {
+        let mut lex = String::new();
+        if value < TimeDelta::zero() {
+            lex.push('-');
+        }
+        lex.push('P');
+        let value = value.abs();
+        let (seconds, nanos) = (value.num_seconds(), value.subsec_nanos());
+        let (days, hours, minutes, seconds) = (
+            seconds / 86400,
+            seconds / 3600 % 24,
+            seconds / 60 % 60,
+            seconds % 60,
+        );
+        if days > 0 {
+            write!(lex, "{days}D").unwrap();
+        }
+        if hours > 0 || minutes > 0 || seconds > 0 || nanos > 0 {
+            lex.push('T');
+            if hours > 0 {
+                write!(lex, "{hours}H").unwrap();
+            }
+            if minutes > 0 {
+                write!(lex, "{minutes}M").unwrap();
+            }
+            if seconds > 0 || nanos > 0 {
+                write!(lex, "{seconds}").unwrap();
+                write_nanos(&mut lex, nanos as u32);
+                lex.push('S');
+            }
+        } else if days == 0 {
+            lex.push_str("T0S");
+        }
+        literal(&lex, xsd::duration)
+    }
+}
+
+fn literal(lex: &str, datatype: NsTerm) -> SimpleTerm<'static> {
+    (lex * datatype).into_term()
+}
+
+/// Parse a `xsd:dateTime` into its local value and its timezone offset (if any).
+fn parse_date_time(
+    lexical_form: &str,
+    datatype: &str,
+) -> Result<(NaiveDateTime, Option<FixedOffset>), LiteralValueError> {
+    let invalid = || LiteralValueError::invalid(lexical_form, datatype);
+    let caps = DATE_TIME
+        .captures(collapse(lexical_form))
+        .ok_or_else(invalid)?;
+    let date = parse_date(&caps, lexical_form, datatype)?;
+    let [h, m, s] = [4, 5, 6].map(|i| caps[i].parse::<u32>().unwrap());
+    let frac = caps.get(7).map_or("", |m| m.as_str());
+    let local = if h == 24 {
+        // 24:00:00 is the first instant of the following day
+        if m != 0 || s != 0 || frac.bytes().any(|b| b != b'0') {
+            return Err(invalid());
+        }
+        date.succ_opt()
+            .ok_or_else(|| LiteralValueError::out_of_range(lexical_form, datatype))?
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    } else {
+        let time = NaiveTime::from_hms_nano_opt(h, m, s, parse_nanos(frac)).ok_or_else(invalid)?;
+        date.and_time(time)
+    };
+    let offset = match caps.get(8) {
+        None => None,
+        Some(tz) => Some(parse_timezone(tz.as_str()).ok_or_else(invalid)?),
+    };
+    Ok((local, offset))
+}
+
+/// Parse the year, month and day captured by [`DATE_TIME`] or [`DATE`].
+fn parse_date(
+    caps: &Captures,
+    lexical_form: &str,
+    datatype: &str,
+) -> Result<NaiveDate, LiteralValueError> {
+    let out_of_range = || LiteralValueError::out_of_range(lexical_form, datatype);
+    let year: i32 = caps[1].parse().map_err(|_| out_of_range())?;
+    let [month, day] = [2, 3].map(|i| caps[i].parse::<u32>().unwrap());
+    NaiveDate::from_ymd_opt(year, month, day).ok_or_else(|| {
+        if (NaiveDate::MIN.year()..=NaiveDate::MAX.year()).contains(&year) {
+            LiteralValueError::invalid(lexical_form, datatype)
+        } else {
+            out_of_range()
+        }
+    })
+}
+
+/// Parse a timezone (`Z` or `±hh:mm`, between -14:00 and +14:00).
+fn parse_timezone(tz: &str) -> Option<FixedOffset> {
+    if tz == "Z" {
+        return FixedOffset::east_opt(0);
+    }
+    let hours: i32 = tz[1..3].parse().ok()?;
+    let minutes: i32 = tz[4..6].parse().ok()?;
+    if minutes > 59 || hours * 60 + minutes > 14 * 60 {
+        return None;
+    }
+    let seconds = (hours * 60 + minutes) * 60;
+    FixedOffset::east_opt(if tz.starts_with('-') {
+        -seconds
+    } else {
+        seconds
+    })
+}
+
+/// Parse the fractional part of seconds into nanoseconds (ignoring digits beyond the 9th).
+fn parse_nanos(frac: &str) -> u32 {
+    frac.bytes()
+        .chain(std::iter::repeat(b'0'))
+        .take(9)
+        .fold(0, |acc, b| acc * 10 + u32::from(b - b'0'))
+}
+
+fn write_date(lex: &mut String, date: NaiveDate) {
+    let year = date.year();
+    if year < 0 {
+        lex.push('-');
+    }
+    write!(
+        lex,
+        "{:04}-{:02}-{:02}",
+        year.unsigned_abs(),
+        date.month(),
+        date.day()
+    )
+    .unwrap();
+}
+
+fn write_date_time(lex: &mut String, date_time: NaiveDateTime) {
+    write_date(lex, date_time.date());
+    let time = date_time.time();
+    write!(
+        lex,
+        "T{:02}:{:02}:{:02}",
+        time.hour(),
+        time.minute(),
+        time.second()
+    )
+    .unwrap();
+    // NB: leap seconds can not be represented in XML Schema
+    write_nanos(lex, time.nanosecond().min(999_999_999));
+}
+
+/// Write the fractional part of seconds, if not null, without trailing zeros.
+fn write_nanos(lex: &mut String, nanos: u32) {
+    if nanos > 0 {
+        let digits = format!("{nanos:09}");
+        write!(lex, ".{}", digits.trim_end_matches('0')).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("2024-02-29T12:34:56Z", "2024-02-29T12:34:56Z")]
+    #[test_case(" 2024-02-29T12:34:56.5-05:30\n", "2024-02-29T18:04:56.5Z")]
+    #[test_case("2023-12-31T24:00:00+01:00", "2023-12-31T23:00:00Z")]
+    #[test_case("-0044-03-15T12:00:00.1234567899Z", "-0044-03-15T12:00:00.123456789Z")]
+    fn date_time_ok(lex: &str, expected_utc: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let value: DateTime<Utc> = (lex * xsd::dateTime).value()?;
+        assert_eq!(
+            SimpleTerm::from(value).lexical_form().unwrap(),
+            expected_utc
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn date_time_preserves_offset() -> Result<(), Box<dyn std::error::Error>> {
+        let value: DateTime<FixedOffset> = ("2024-02-29T12:34:56+02:00" * xsd::dateTime).value()?;
+        assert_eq!(value.offset().local_minus_utc(), 7200);
+        assert_eq!(value.hour(), 12);
+        Ok(())
+    }
+
+    #[test_case("2024-02-30T12:00:00Z"; "invalid day")]
+    #[test_case("2024-02-29T12:60:00Z"; "invalid minute")]
+    #[test_case("2024-02-29T24:00:01Z"; "invalid 24h")]
+    #[test_case("2024-02-29T12:00:00+14:01"; "invalid timezone")]
+    #[test_case("2024-02-29 12:00:00Z"; "invalid separator")]
+    #[test_case("02024-02-29T12:00:00Z"; "invalid year")]
+    #[test_case("2024-02-29T12:00:00."; "invalid fraction")]
+    fn date_time_invalid(lex: &str) {
+        assert!(matches!(
+            (lex * xsd::dateTime).value::<DateTime<FixedOffset>>(),
+            Err(LiteralValueError::InvalidLexicalForm { .. }),
+        ));
+    }
+
+    #[test]
+    fn date_time_timezone() -> Result<(), Box<dyn std::error::Error>> {
+        let floating = "2024-02-29T12:00:00" * xsd::dateTime;
+        let zoned = "2024-02-29T12:00:00Z" * xsd::dateTime;
+        assert!(matches!(
+            floating.value::<DateTime<Utc>>(),
+            Err(LiteralValueError::OutOfRange { .. }),
+        ));
+        assert!(matches!(
+            zoned.value::<NaiveDateTime>(),
+            Err(LiteralValueError::OutOfRange { .. }),
+        ));
+        let naive: NaiveDateTime = floating.value()?;
+        assert_eq!(naive, zoned.value::<DateTime<Utc>>()?.naive_utc());
+        assert!(matches!(
+            ("2024-02-29" * xsd::date).value::<DateTime<Utc>>(),
+            Err(LiteralValueError::UnsupportedDatatype(_)),
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn date() -> Result<(), Box<dyn std::error::Error>> {
+        let value: NaiveDate = ("2024-02-29" * xsd::date).value()?;
+        assert_eq!(value, NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert!(matches!(
+            ("2023-02-29" * xsd::date).value::<NaiveDate>(),
+            Err(LiteralValueError::InvalidLexicalForm { .. }),
+        ));
+        assert!(matches!(
+            ("2024-02-29Z" * xsd::date).value::<NaiveDate>(),
+            Err(LiteralValueError::OutOfRange { .. }),
+        ));
+        assert!(matches!(
+            ("9999999-01-01" * xsd::date).value::<NaiveDate>(),
+            Err(LiteralValueError::OutOfRange { .. }),
+        ));
+        Ok(())
+    }
+
+    #[test_case("PT0S", 0, 0)]
+    #[test_case("P1D", 86400, 0)]
+    #[test_case("PT1H30M", 5400, 0)]
+    #[test_case("-P1DT2H3M4.5S", -93784, -500_000_000)]
+    #[test_case("PT0.000000001S", 0, 1)]
+    #[test_case("P0Y0M2D", 172800, 0)]
+    fn duration_ok(lex: &str, seconds: i64, nanos: i64) -> Result<(), Box<dyn std::error::Error>> {
+        let expected = TimeDelta::seconds(seconds) + TimeDelta::nanoseconds(nanos);
+        assert_eq!((lex * xsd::duration).value::<TimeDelta>()?, expected);
+        Ok(())
+    }
+
+    #[test_case("P"; "empty")]
+    #[test_case("P1DT"; "empty time")]
+    #[test_case("PT1.S"; "empty fraction")]
+    #[test_case("P1H"; "hours without T")]
+    #[test_case("PT1D"; "days after T")]
+    #[test_case("P-1D"; "inner sign")]
+    fn duration_invalid(lex: &str) {
+        assert!(matches!(
+            (lex * xsd::duration).value::<TimeDelta>(),
+            Err(LiteralValueError::InvalidLexicalForm { .. }),
+        ));
+    }
+
+    #[test]
+    fn duration_out_of_range() {
+        assert!(matches!(
+            ("P1M" * xsd::duration).value::<TimeDelta>(),
+            Err(LiteralValueError::OutOfRange { .. }),
+        ));
+        assert!(matches!(
+            ("PT99999999999999999999S" * xsd::duration).value::<TimeDelta>(),
+            Err(LiteralValueError::OutOfRange { .. }),
+        ));
+    }
+
+    #[test]
+    fn to_literal() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        let date_time = date.and_hms_milli_opt(1, 2, 3, 40).unwrap();
+        let utc = date_time.and_utc();
+        let offset = FixedOffset::west_opt(5 * 3600 + 1800).unwrap();
+        let cases = [
+            (SimpleTerm::from(date), "2024-02-29", xsd::date),
+            (
+                SimpleTerm::from(date_time),
+                "2024-02-29T01:02:03.04",
+                xsd::dateTime,
+            ),
+            (
+                SimpleTerm::from(utc),
+                "2024-02-29T01:02:03.04Z",
+                xsd::dateTime,
+            ),
+            (
+                SimpleTerm::from(utc.with_timezone(&offset)),
+                "2024-02-28T19:32:03.04-05:30",
+                xsd::dateTime,
+            ),
+            (
+                SimpleTerm::from(NaiveDate::from_ymd_opt(-44, 3, 15).unwrap()),
+                "-0044-03-15",
+                xsd::date,
+            ),
+            (SimpleTerm::from(TimeDelta::zero()), "PT0S", xsd::duration),
+            (SimpleTerm::from(TimeDelta::days(2)), "P2D", xsd::duration),
+            (
+                SimpleTerm::from(-TimeDelta::seconds(93784) - TimeDelta::milliseconds(500)),
+                "-P1DT2H3M4.5S",
+                xsd::duration,
+            ),
+        ];
+        for (term, lex, dt) in cases {
+            assert_eq!(term.lexical_form().unwrap(), lex);
+            assert_eq!(term.datatype(), dt.iri());
+        }
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let dt: DateTime<FixedOffset> =
+            ("2024-02-29T12:00:00.123-03:00" * xsd::dateTime).value()?;
+        assert_eq!(SimpleTerm::from(dt).value::<DateTime<FixedOffset>>()?, dt);
+        let delta: TimeDelta = ("-P3DT4.000005S" * xsd::duration).value()?;
+        assert_eq!(SimpleTerm::from(delta).value::<TimeDelta>()?, delta);
+        Ok(())
+    }
+}
@@ -0,0 +1,197 @@
+//! I provide support for arbitrary-precision numeric literals, through the [`bigdecimal`] crate.
+//!
+//! * The following types implement [`LiteralValue`],
+//!   so they can be extracted from literals with [`Term::value`]:
+//!   - [`BigInt`], from `xsd:integer` literals and the datatypes derived from it;
+//!   - [`BigDecimal`], from `xsd:decimal` literals and the integer datatypes derived from it.
+//! * [`SimpleTerm`] implements [`From`] for both types,
+//!   building `xsd:integer` and `xsd:decimal` literals
+//!   with a canonical lexical form.
+//!
+//! Unlike primitive numeric types, these types never lose precision
+//! nor raise [`LiteralValueError::OutOfRange`].
+//! With this feature enabled, [`Term::value_cmp`] and [`Term::sparql_cmp`]
+//! also compare decimals (and integers beyond the range of `i128`) exactly.
+//!
+//! # Example
+//! ```
+//! # use bigdecimal::{num_bigint::BigInt, BigDecimal};
+//! # use sophia_api::ns::xsd;
+//! # use sophia_api::term::{SimpleTerm, Term};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let big: BigInt = ("123456789012345678901234567890123456789012" * xsd::integer).value()?;
+//! let lit = SimpleTerm::from(big * 2);
+//! assert_eq!(lit.lexical_form().unwrap(), "246913578024691357802469135780246913578024");
+//!
+//! let price: BigDecimal = ("0.10" * xsd::decimal).value()?;
+//! let lit = SimpleTerm::from(price * BigDecimal::from(3));
+//! assert_eq!(lit.lexical_form().unwrap(), "0.3");
+//! # Ok(()) }
+//! ```
+use super::literal_value::{
+    canonical_decimal, collapse, is_decimal, xsd_local_name, LiteralValue, LiteralValueError,
+};
+use super::{SimpleTerm, Term};
+use crate::ns::{xsd, NsTerm};
+use bigdecimal::num_bigint::BigInt;
+use bigdecimal::BigDecimal;
+
+/// Extract values from literals whose datatype is `xsd:integer` or derived from it.
+impl LiteralValue for BigInt {
+    fn from_lexical(lexical_form: &str, datatype: &str) -> Result<Self, LiteralValueError> {
+        match i128::from_lexical(lexical_form, datatype) {
+            Ok(val) => Ok(val.into()),
+            // NB: OutOfRange means that the value does not fit in i128, but is still valid
+            Err(LiteralValueError::OutOfRange { .. }) => canonical_decimal(collapse(lexical_form))
+                .parse()
+                .map_err(|_| LiteralValueError::invalid(lexical_form, datatype)),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Extract values from literals whose datatype is `xsd:decimal`, or `xsd:integer` or derived from it.
+impl LiteralValue for BigDecimal {
+    fn from_lexical(lexical_form: &str, datatype: &str) -> Result<Self, LiteralValueError> {
+        if xsd_local_name(datatype) != Some("decimal") {
+            return BigInt::from_lexical(lexical_form, datatype).map(|i| BigDecimal::new(i, 0));
+        }
+        let txt = collapse(lexical_form);
+        if !is_decimal(txt) {
+            return Err(LiteralValueError::invalid(lexical_form, datatype));
+        }
+        canonical_decimal(txt)
+            .parse()
+            .map_err(|_| LiteralValueError::invalid(lexical_form, datatype))
+    }
+}
+
+/// Build an `xsd:integer` literal, with a canonical lexical form.
+impl From<BigInt> for SimpleTerm<'static> {
+    fn from(value: BigInt) -> Self {
+        literal(&value.to_string(), xsd::integer)
+    }
+}
+
+/// Build an `xsd:decimal` literal, with a canonical lexical form.
+impl From<BigDecimal> for SimpleTerm<'static> {
+    fn from(value: BigDecimal) -> Self {
+        literal(&plain_decimal(&value), xsd::decimal)
+    }
+}
+
+fn literal(lex: &str, datatype: NsTerm) -> SimpleTerm<'static> {
+    (lex * datatype).into_term()
+}
+
+/// The canonical lexical form of `value`
+/// (which, unlike its [`Display`](std::fmt::Display) representation, never uses an exponent).
+fn plain_decimal(value: &BigDecimal) -> String {
+    let (digits, scale) = value.as_bigint_and_exponent();
+    let digits = digits.to_string();
+    let (sign, digits) = match digits.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", digits.as_str()),
+    };
+    let scale_abs = scale.unsigned_abs() as usize;
+    let plain = if scale <= 0 {
+        format!("{sign}{digits}{}", "0".repeat(scale_abs))
+    } else if digits.len() > scale_abs {
+        let (int, frac) = digits.split_at(digits.len() - scale_abs);
+        format!("{sign}{int}.{frac}")
+    } else {
+        format!("{sign}0.{}{digits}", "0".repeat(scale_abs - digits.len()))
+    };
+    canonical_decimal(&plain)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cmp::Ordering;
+    use test_case::test_case;
+
+    const HUGE: &str = "123456789012345678901234567890123456789012";
+
+    #[test_case("42", xsd::integer, "42")]
+    #[test_case(" +0042 ", xsd::integer, "42")]
+    #[test_case("-0", xsd::nonPositiveInteger, "0")]
+    #[test_case(HUGE, xsd::integer, HUGE)]
+    #[test_case(
+        "-123456789012345678901234567890123456789012",
+        xsd::negativeInteger,
+        "-123456789012345678901234567890123456789012"
+    )]
+    fn big_int_ok(lex: &str, dt: NsTerm<'static>, expected: &str) {
+        let value = (lex * dt).value::<BigInt>().unwrap();
+        assert_eq!(value.to_string(), expected);
+    }
+
+    #[test_case("4.2", xsd::integer)]
+    #[test_case("+-1", xsd::integer)]
+    #[test_case("", xsd::integer)]
+    #[test_case(HUGE, xsd::long; "beyond long")]
+    #[test_case(HUGE, xsd::negativeInteger; "positive negativeInteger")]
+    fn big_int_invalid(lex: &str, dt: NsTerm<'static>) {
+        assert!(matches!(
+            (lex * dt).value::<BigInt>(),
+            Err(LiteralValueError::InvalidLexicalForm { .. })
+        ));
+    }
+
+    #[test_case("1.5", xsd::decimal, "1.5")]
+    #[test_case(" -00.50 ", xsd::decimal, "-0.5")]
+    #[test_case(".5", xsd::decimal, "0.5")]
+    #[test_case("7.", xsd::decimal, "7")]
+    #[test_case(
+        "0.1000000000000000000000000000000000000001",
+        xsd::decimal,
+        "0.1000000000000000000000000000000000000001"
+    )]
+    #[test_case(HUGE, xsd::decimal, HUGE)]
+    #[test_case("-12", xsd::short, "-12")]
+    #[test_case(HUGE, xsd::integer, HUGE; "integer beyond i128")]
+    fn big_decimal_ok(lex: &str, dt: NsTerm<'static>, expected: &str) {
+        let value = (lex * dt).value::<BigDecimal>().unwrap();
+        assert_eq!(plain_decimal(&value), expected);
+    }
+
+    #[test_case("1e5", xsd::decimal)]
+    #[test_case(".", xsd::decimal)]
+    #[test_case("1.5", xsd::integer)]
+    #[test_case("1.5", xsd::double; "unsupported")]
+    fn big_decimal_err(lex: &str, dt: NsTerm<'static>) {
+        assert!((lex * dt).value::<BigDecimal>().is_err());
+    }
+
+    #[test_case("0", 0, "0")]
+    #[test_case("15", 1, "1.5")]
+    #[test_case("-15", 3, "-0.015")]
+    #[test_case("150", 2, "1.5")]
+    #[test_case("15", -3, "15000")]
+    #[test_case("-0", -3, "0")]
+    fn to_literal(digits: &str, scale: i64, expected: &str) {
+        let value = BigDecimal::new(digits.parse().unwrap(), scale);
+        let lit = SimpleTerm::from(value);
+        assert!(Term::eq(&lit, expected * xsd::decimal));
+    }
+
+    #[test]
+    fn exact_comparison() {
+        let a = "0.1" * xsd::decimal;
+        let b = "0.10000000000000000001" * xsd::decimal;
+        assert_eq!(a.value_cmp(&b), Some(Ordering::Less));
+        assert_eq!(a.sparql_cmp(&b), Ordering::Less);
+        let huge = HUGE * xsd::integer;
+        let huge_plus = "123456789012345678901234567890123456789012.5" * xsd::decimal;
+        assert_eq!(huge.value_cmp(&huge_plus), Some(Ordering::Less));
+        assert_eq!(
+            ("1" * xsd::integer).value_cmp("1.0" * xsd::decimal),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            ("1.5" * xsd::decimal).value_cmp("1.5E0" * xsd::double),
+            Some(Ordering::Equal)
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! I provide support for the [`rdf:JSON`](crate::ns::rdf::JSON) datatype,
+//! through the [`serde_json`] crate.
+//!
+//! * [`serde_json::Value`] implements [`LiteralValue`],
+//!   so JSON literals can be parsed with [`Term::value`](crate::term::Term::value);
+//! * [`json_literal`] builds a JSON literal from a [`serde_json::Value`],
+//!   whose lexical form is canonicalized as required by [RDF 1.2],
+//!   i.e. per the [JSON Canonicalization Scheme] (RFC 8785).
+//!
+//! # Example
+//! ```
+//! # use sophia_api::ns::rdf;
+//! # use sophia_api::term::Term;
+//! # use sophia_api::term::json::json_literal;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let value: serde_json::Value = (r#"{ "b": [1, 2.50], "a": null }"# * rdf::JSON).value()?;
+//! assert_eq!(value["b"][1], 2.5);
+//! assert_eq!(json_literal(&value).lexical_form().unwrap(), r#"{"a":null,"b":[1,2.5]}"#);
+//! # Ok(()) }
+//! ```
+//!
+//! [RDF 1.2]: https://www.w3.org/TR/rdf12-concepts/#section-JSON
+//! [JSON Canonicalization Scheme]: https://www.rfc-editor.org/rfc/rfc8785
+use super::literal_value::{LiteralValue, LiteralValueError};
+use super::{SimpleTerm, Term};
+use crate::ns::rdf;
+use serde_json::{Number, Value};
+use std::fmt::Write;
+
+impl LiteralValue for Value {
+    fn from_lexical(lexical_form: &str, datatype: &str) -> Result<Self, LiteralValueError> {
+        if datatype != rdf::JSON.iri().unwrap().as_str() {
+            return Err(LiteralValueError::UnsupportedDatatype(datatype.into()));
+        }
+        serde_json::from_str(lexical_form)
+            .map_err(|_| LiteralValueError::invalid(lexical_form, datatype))
+    }
+}
+
+/// Build an `rdf:JSON` literal representing `value`,
+/// with a [canonical](canonical_json) lexical form.
+pub fn json_literal(value: &Value) -> SimpleTerm<'static> {
+    let lex = canonical_json(value);
+    (lex.as_str() * rdf::JSON).into_term()
+}
+
+/// Serialize `value` according to the [JSON Canonicalization Scheme] (RFC 8785):
+/// - no whitespace between tokens,
+/// - object members sorted by the UTF-16 code units of their names,
+/// - numbers serialized as in ECMAScript,
+/// - minimal escaping of strings.
+///
+/// [JSON Canonicalization Scheme]: https://www.rfc-editor.org/rfc/rfc8785
+pub fn canonical_json(value: &Value) -> String {
+    let mut buffer = String::new();
+    write_canonical(value, &mut buffer);
+    buffer
+}
+
+fn write_canonical(value: &Value, buffer: &mut String) {
+    match value {
+        Value::Null => buffer.push_str("null"),
+        Value::Bool(b) => buffer.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => write_number(n, buffer),
+        Value::String(s) => write_string(s, buffer),
+        Value::Array(items) => {
+            buffer.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    buffer.push(',');
+                }
+                write_canonical(item, buffer);
+            }
+            buffer.push(']');
+        }
+        Value::Object(members) => {
+            let mut members: Vec<_> = members.iter().collect();
+            members.sort_by(|(k1, _), (k2, _)| k1.encode_utf16().cmp(k2.encode_utf16()));
+            buffer.push('{');
+            for (i, (key, val)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    buffer.push(',');
+                }
+                write_string(key, buffer);
+                buffer.push(':');
+                write_canonical(val, buffer);
+            }
+            buffer.push('}');
+        }
+    }
+}
+
+fn write_string(s: &str, buffer: &mut String) {
+    buffer.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => buffer.push_str("\\\""),
+            '\\' => buffer.push_str("\\\\"),
+            '\u{8}' => buffer.push_str("\\b"),
+            '\t' => buffer.push_str("\\t"),
+            '\n' => buffer.push_str("\\n"),
+            '\u{c}' => buffer.push_str("\\f"),
+            '\r' => buffer.push_str("\\r"),
+            '\0'..='\u{1f}' => write!(buffer, "\\u{:04x}", c as u32).unwrap(),
+            _ => buffer.push(c),
+        }
+    }
+    buffer.push('"');
+}
+
+/// Numbers are serialized as IEEE 754 doubles,
+/// following the `Number.prototype.toString` algorithm of ECMAScript.
+fn write_number(n: &Number, buffer: &mut String) {
+    // integers within the safe range of doubles are serialized as is
+    const MAX_SAFE: u64 = (1 << 53) - 1;
+    if let Some(i) = n.as_u64().filter(|i| *i <= MAX_SAFE) {
+        write!(buffer, "{i}").unwrap();
+        return;
+    }
+    if let Some(i) = n.as_i64().filter(|i| i.unsigned_abs() <= MAX_SAFE) {
+        write!(buffer, "{i}").unwrap();
+        return;
+    }
+    // serde_json only accepts finite numbers
+    let f = n.as_f64().unwrap();
+    if f == 0.0 {
+        buffer.push('0');
+        return;
+    }
+    if f < 0.0 {
+        buffer.push('-');
+    }
+    // the shortest representation that round-trips, as d.ddde±n
+    let sci = format!("{:e}", f.abs());
+    let (mantissa, exponent) = sci.split_once('e').unwrap();
+    let digits = mantissa.replace('.', "");
+    let k = digits.len() as i32;
+    let n = exponent.parse::<i32>().unwrap() + 1;
+    if k <= n && n <= 21 {
+        buffer.push_str(&digits);
+        buffer.push_str(&"0".repeat((n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        let (int, frac) = digits.split_at(n as usize);
+        write!(buffer, "{int}.{frac}").unwrap();
+    } else if -6 < n && n <= 0 {
+        buffer.push_str("0.");
+        buffer.push_str(&"0".repeat(-n as usize));
+        buffer.push_str(&digits);
+    } else {
+        let (first, rest) = digits.split_at(1);
+        buffer.push_str(first);
+        if !rest.is_empty() {
+            write!(buffer, ".{rest}").unwrap();
+        }
+        let sign = if n > 0 { '+' } else { '-' };
+        write!(buffer, "e{sign}{}", (n - 1).abs()).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ns::xsd;
+    use serde_json::json;
+    use test_case::test_case;
+
+    #[test]
+    fn parse() -> Result<(), Box<dyn std::error::Error>> {
+        let value: Value = (r#" {"a": [true, null, "x"]} "# * rdf::JSON).value()?;
+        assert_eq!(value, json!({"a": [true, null, "x"]}));
+        assert!(matches!(
+            ("{" * rdf::JSON).value::<Value>(),
+            Err(LiteralValueError::InvalidLexicalForm { .. }),
+        ));
+        assert!(matches!(
+            ("{}" * xsd::string).value::<Value>(),
+            Err(LiteralValueError::UnsupportedDatatype(_)),
+        ));
+        Ok(())
+    }
+
+    #[test_case("0", "0")]
+    #[test_case("-0.0", "0")]
+    #[test_case("42", "42")]
+    #[test_case("-42", "-42")]
+    #[test_case("1.50", "1.5")]
+    #[test_case("1E3", "1000")]
+    #[test_case("1e21", "1e+21")]
+    #[test_case("123456789012345678901", "123456789012345680000")]
+    #[test_case("9007199254740993", "9007199254740992")]
+    #[test_case("0.000001", "0.000001")]
+    #[test_case("0.0000001", "1e-7")]
+    #[test_case("-1.25e-10", "-1.25e-10")]
+    #[test_case("333333333.33333329", "333333333.3333333")]
+    fn canonical_numbers(lex: &str, expected: &str) {
+        let value: Value = serde_json::from_str(lex).unwrap();
+        assert_eq!(canonical_json(&value), expected);
+    }
+
+    #[test]
+    fn canonical_strings() {
+        let value = json!("\"\\/\u{1}\u{8}\t\n\u{c}\r\u{7f}é€😀");
+        assert_eq!(
+            canonical_json(&value),
+            "\"\\\"\\\\/\\u0001\\b\\t\\n\\f\\r\u{7f}é€😀\""
+        );
+    }
+
+    #[test]
+    fn canonical_objects() {
+        // example from RFC 8785 section 3.2.3
+        let value = json!({
+            "\u{20ac}": "Euro Sign",
+            "\r": "Carriage Return",
+            "\u{fb33}": "Hebrew Letter Dalet With Dagesh",
+            "1": "One",
+            "\u{1f600}": "Emoji: Grinning Face",
+            "\u{80}": "Control",
+            "\u{f6}": "Latin Small Letter O With Diaeresis",
+        });
+        let keys: Vec<_> = canonical_json(&value)
+            .split(',')
+            .map(|member| member.split(':').next().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                "{\"\\r\"",
+                "\"1\"",
+                "\"\u{80}\"",
+                "\"\u{f6}\"",
+                "\"\u{20ac}\"",
+                "\"\u{1f600}\"",
+                "\"\u{fb33}\"",
+            ]
+        );
+        assert_eq!(
+            canonical_json(&json!({"b": [1, {"d": 2, "c": 3}], "a": {}})),
+            r#"{"a":{},"b":[1,{"c":3,"d":2}]}"#
+        );
+    }
+
+    #[test]
+    fn literal() -> Result<(), Box<dyn std::error::Error>> {
+        let value = json!({"b": 2, "a": [1e-7]});
+        let lit = json_literal(&value);
+        assert_eq!(lit.lexical_form().unwrap(), r#"{"a":[1e-7],"b":2}"#);
+        assert!(Term::eq(&lit.datatype().unwrap(), rdf::JSON));
+        assert_eq!(lit.value::<Value>()?, value);
+        Ok(())
+    }
+}
@@ -23,12 +23,37 @@ lazy_static! {
       (-[A-Za-z0-9]+)*
       $
     "#).unwrap();
+
+    /// Regular expression implementing the well-formedness grammar of
+    /// [BCP47 §2.1](https://www.rfc-editor.org/rfc/rfc5646#section-2.1).
+    ///
+    /// NB: regular grandfathered tags are not listed, as they match the `langtag` rule anyway.
+    static ref BCP47: Regex = Regex::new(r#"(?x)
+      ^(
+        # langtag
+        ( [A-Za-z]{2,3} (-[A-Za-z]{3}){0,3} | [A-Za-z]{4,8} )  # language (and extlang)
+        (-[A-Za-z]{4})?                                        # script
+        (-([A-Za-z]{2}|[0-9]{3}))?                             # region
+        (-([A-Za-z0-9]{5,8}|[0-9][A-Za-z0-9]{3}))*             # variant
+        (-[0-9A-WY-Za-wy-z](-[A-Za-z0-9]{2,8})+)*              # extension
+        (-[Xx](-[A-Za-z0-9]{1,8})+)?                           # privateuse
+      |
+        # privateuse
+        [Xx](-[A-Za-z0-9]{1,8})+
+      |
+        # irregular grandfathered
+        (?i: en-GB-oed | i-ami | i-bnn | i-default | i-enochian | i-hak | i-klingon
+           | i-lux | i-mingo | i-navajo | i-pwn | i-tao | i-tay | i-tsu
+           | sgn-BE-FR | sgn-BE-NL | sgn-CH-DE )
+      )$
+    "#).unwrap();
 }
 
 /// This wrapper guarantees that the underlying `str`
-/// is a valid [BCP47](https://tools.ietf.org/search/bcp47) language tag.
+/// is a well-formed [BCP47](https://tools.ietf.org/search/bcp47) language tag.
 ///
-/// NB: it is actually more permissive than BCP47.
+/// NB: language tags built with [`LanguageTag::new_lenient`] are only guaranteed to be
+/// sequences of alphanumeric subtags separated by hyphens.
 ///
 /// A [`LanguageTag`] can be combined to a `&str` with the `*` operator,
 /// to produce an RDF [language tagged string](https://www.w3.org/TR/rdf11-concepts/#dfn-language-tagged-string)
@@ -48,8 +73,27 @@ pub struct LanguageTag<T: Borrow<str>>(T);
 
 impl<T: Borrow<str>> LanguageTag<T> {
     /// Build a new [`LanguageTag`] from `tag`,
-    /// returning an error if it is not a valid BCP47 language tag.
+    /// returning an error if it is not a well-formed BCP47 language tag
+    /// (as defined in [BCP47 §2.1](https://www.rfc-editor.org/rfc/rfc5646#section-2.1)).
+    ///
+    /// NB: this does not check that the subtags are registered,
+    /// nor that `tag` is in [canonical case](LanguageTag::to_canonical_case).
     pub fn new(tag: T) -> Result<Self, InvalidLanguageTag> {
+        if BCP47.is_match(tag.borrow()) {
+            Ok(LanguageTag(tag))
+        } else {
+            Err(InvalidLanguageTag(tag.borrow().to_string()))
+        }
+    }
+
+    /// Build a new [`LanguageTag`] from `tag`,
+    /// returning an error only if it is not a sequence of alphanumeric subtags separated by hyphens,
+    /// the first one starting with a letter.
+    ///
+    /// This is more permissive than [`LanguageTag::new`],
+    /// and is meant for data using language tags that are not well-formed according to BCP47
+    /// (e.g. with overlong subtags).
+    pub fn new_lenient(tag: T) -> Result<Self, InvalidLanguageTag> {
         if LANG_TAG.is_match(tag.borrow()) {
             Ok(LanguageTag(tag))
         } else {
@@ -92,6 +136,69 @@ impl<T: Borrow<str>> LanguageTag<T> {
     {
         LanguageTag(f(self.0))
     }
+
+    /// Whether this language tag is in canonical case
+    /// (see [`LanguageTag::to_canonical_case`]).
+    pub fn is_canonical_case(&self) -> bool {
+        canonical_case_subtags(self.as_str()).all(|(subtag, case)| match case {
+            Case::Lower => !subtag.bytes().any(|b| b.is_ascii_uppercase()),
+            Case::Upper => !subtag.bytes().any(|b| b.is_ascii_lowercase()),
+            Case::Title => {
+                subtag.as_bytes()[0].is_ascii_uppercase()
+                    && !subtag[1..].bytes().any(|b| b.is_ascii_uppercase())
+            }
+        })
+    }
+
+    /// Return a copy of this language tag in canonical case, as recommended by
+    /// [BCP47 §2.1.1](https://www.rfc-editor.org/rfc/rfc5646#section-2.1.1):
+    /// - two-letter subtags (regions) are uppercase, e.g. `en-GB`;
+    /// - four-letter subtags (scripts) are titlecase, e.g. `sr-Latn`;
+    /// - all other subtags are lowercase,
+    ///   as well as all subtags following a singleton (e.g. `x-`),
+    ///   and the first subtag.
+    pub fn to_canonical_case(&self) -> LanguageTag<String> {
+        let mut out = String::with_capacity(self.as_str().len());
+        for (subtag, case) in canonical_case_subtags(self.as_str()) {
+            if !out.is_empty() {
+                out.push('-');
+            }
+            match case {
+                Case::Lower => out.push_str(&subtag.to_ascii_lowercase()),
+                Case::Upper => out.push_str(&subtag.to_ascii_uppercase()),
+                Case::Title => {
+                    out.push_str(&subtag[..1].to_ascii_uppercase());
+                    out.push_str(&subtag[1..].to_ascii_lowercase());
+                }
+            }
+        }
+        LanguageTag(out)
+    }
+}
+
+/// The canonical case of a subtag.
+enum Case {
+    Lower,
+    Upper,
+    Title,
+}
+
+/// Iter over the subtags of `tag`, with their canonical case.
+fn canonical_case_subtags(tag: &str) -> impl Iterator<Item = (&str, Case)> {
+    let mut after_singleton = false;
+    tag.split('-').enumerate().map(move |(i, subtag)| {
+        let case = if i == 0 || after_singleton {
+            Case::Lower
+        } else if subtag.len() == 2 {
+            Case::Upper
+        } else if subtag.len() == 4 && subtag.bytes().all(|b| b.is_ascii_alphabetic()) {
+            Case::Title
+        } else {
+            Case::Lower
+        };
+        after_singleton |= subtag.len() == 1;
+        (subtag, case)
+    })
 }
 
 impl LanguageTag<&'static str> {
@@ -207,10 +314,17 @@ mod test {
     #[test_case("fr-056")]
     #[test_case("ja-Hani")]
     #[test_case("ja-Hira")]
-    #[test_case("abc-de-fg-hi")]
     #[test_case("x-abc-de-fg-hi")]
+    #[test_case("zh-yue-HK")]
+    #[test_case("sl-rozaj-biske")]
+    #[test_case("de-CH-1901")]
+    #[test_case("en-US-u-islamcal")]
+    #[test_case("az-Arab-x-AZE-derbend")]
+    #[test_case("i-klingon")]
+    #[test_case("EN-gb-OED")]
     fn valid(tag: &str) {
         assert!(LanguageTag::new(tag).is_ok());
+        assert!(LanguageTag::new_lenient(tag).is_ok());
     }
 
     #[test_case(""; "empty")]
@@ -219,6 +333,35 @@ mod test {
     #[test_case("a.")]
     fn invalid(tag: &str) {
         assert!(LanguageTag::new(tag).is_err());
+        assert!(LanguageTag::new_lenient(tag).is_err());
+    }
+
+    #[test_case("abc-de-fg-hi")]
+    #[test_case("a-DE")]
+    #[test_case("en-US-a")]
+    #[test_case("de-419-DE")]
+    #[test_case("averylongtag")]
+    #[test_case("x-averylongtag")]
+    fn lenient_only(tag: &str) {
+        assert!(LanguageTag::new(tag).is_err());
+        assert!(LanguageTag::new_lenient(tag).is_ok());
+    }
+
+    #[test_case("en", "en")]
+    #[test_case("EN-gb", "en-GB")]
+    #[test_case("sr-latn-rs", "sr-Latn-RS")]
+    #[test_case("ZH-YUE-hk", "zh-yue-HK")]
+    #[test_case("de-ch-1901", "de-CH-1901")]
+    #[test_case("en-ca-x-CA", "en-CA-x-ca")]
+    #[test_case("az-ARAB-X-aze-DERB", "az-Arab-x-aze-derb")]
+    #[test_case("X-AB-ABCD", "x-ab-abcd")]
+    #[test_case("en-GB-OED", "en-GB-oed")]
+    fn canonical_case(tag: &str, expected: &str) {
+        let tag = LanguageTag::new(tag).unwrap();
+        assert_eq!(tag.is_canonical_case(), tag.as_str() == expected);
+        let canonical = tag.to_canonical_case();
+        assert_eq!(canonical.as_str(), expected);
+        assert!(canonical.is_canonical_case());
     }
 
     #[test_case("fr", "fr"; "all_lower")]
@@ -263,5 +406,8 @@ mod test {
         assert!(t3.is_literal());
         assert_eq!(t3.lexical_form().unwrap(), "cat");
         assert_eq!(t3.language_tag().unwrap(), en);
+        // the case of the language tag is preserved
+        let t4 = "chat" * LanguageTag::new("fr-fr").unwrap();
+        assert_eq!(t4.language_tag().unwrap().as_str(), "fr-fr");
     }
 }
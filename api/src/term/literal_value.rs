@@ -0,0 +1,644 @@
+//! I define the [`LiteralValue`] trait,
+//! for extracting native Rust values from typed literals
+//! (see [`Term::value`](super::Term::value)).
+//!
+//! Unlike a naive call to [`str::parse`] on the lexical form,
+//! the lexical forms are parsed according to the rules of [XML Schema datatypes]:
+//! - leading and trailing whitespaces are ignored (except for strings);
+//! - the lexical space of the datatype is enforced
+//!   (e.g. `"inf"^^xsd:double` or `"+ 1"^^xsd:integer` are rejected);
+//! - the value space of the datatype is enforced
+//!   (e.g. `"-1"^^xsd:nonNegativeInteger` is rejected).
+//!
+//! I also provide [`canonical_lexical_form`],
+//! which computes the canonical lexical form of (some) typed literals
+//! (see also [`Term::canonicalized`](super::Term::canonicalized)).
+//!
+//! # Example
+//! ```
+//! # use sophia_api::ns::xsd;
+//! # use sophia_api::term::Term;
+//! # use sophia_api::term::literal_value::LiteralValueError;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! assert_eq!(("  +42 " * xsd::int).value::<i64>()?, 42);
+//! assert_eq!(("42" * xsd::integer).value::<f64>()?, 42.0);
+//! assert_eq!(("-INF" * xsd::double).value::<f64>()?, f64::NEG_INFINITY);
+//! assert!(("1" * xsd::boolean).value::<bool>()?);
+//! assert!(matches!(
+//!     ("300" * xsd::integer).value::<u8>(),
+//!     Err(LiteralValueError::OutOfRange { .. }),
+//! ));
+//! # Ok(()) }
+//! ```
+//!
+//! [XML Schema datatypes]: https://www.w3.org/TR/xmlschema11-2/
+use super::Term;
+use crate::ns::{rdf, xsd};
+use std::cmp::Ordering;
+use thiserror::Error;
+
+/// A native Rust type whose values can be extracted from (some) typed literals.
+///
+/// Implementors must follow the rules of XML Schema for the datatypes they support,
+/// and return [`LiteralValueError::UnsupportedDatatype`] for the others
+/// (rather than parsing the lexical form anyway).
+pub trait LiteralValue: Sized {
+    /// Parse `lexical_form` according to `datatype`.
+    ///
+    /// # Precondition
+    /// `datatype` must be an absolute IRI.
+    fn from_lexical(lexical_form: &str, datatype: &str) -> Result<Self, LiteralValueError>;
+}
+
+/// An error raised when extracting a [`LiteralValue`] from a term.
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum LiteralValueError {
+    /// The term is not a literal
+    #[error("The term is not a literal")]
+    NotALiteral,
+    /// The datatype of the literal is not supported by the requested type
+    #[error("Datatype <{0}> is not supported by the requested type")]
+    UnsupportedDatatype(String),
+    /// The lexical form is not valid for the datatype of the literal
+    #[error("Invalid lexical form {lexical_form:?} for datatype <{datatype}>")]
+    InvalidLexicalForm {
+        /// The offending lexical form
+        lexical_form: String,
+        /// The datatype of the literal
+        datatype: String,
+    },
+    /// The value of the literal can not be represented by the requested type
+    #[error("The value of {lexical_form:?} (<{datatype}>) is out of range for the requested type")]
+    OutOfRange {
+        /// The offending lexical form
+        lexical_form: String,
+        /// The datatype of the literal
+        datatype: String,
+    },
+}
+
+impl LiteralValueError {
+    pub(crate) fn invalid(lexical_form: &str, datatype: &str) -> Self {
+        LiteralValueError::InvalidLexicalForm {
+            lexical_form: lexical_form.into(),
+            datatype: datatype.into(),
+        }
+    }
+
+    pub(crate) fn out_of_range(lexical_form: &str, datatype: &str) -> Self {
+        LiteralValueError::OutOfRange {
+            lexical_form: lexical_form.into(),
+            datatype: datatype.into(),
+        }
+    }
+}
+
+/// The bounds of the integer datatypes of XML Schema (`None` if unbounded).
+fn integer_bounds(local_name: &str) -> Option<(Option<i128>, Option<i128>)> {
+    Some(match local_name {
+        "integer" => (None, None),
+        "long" => (Some(i64::MIN.into()), Some(i64::MAX.into())),
+        "int" => (Some(i32::MIN.into()), Some(i32::MAX.into())),
+        "short" => (Some(i16::MIN.into()), Some(i16::MAX.into())),
+        "byte" => (Some(i8::MIN.into()), Some(i8::MAX.into())),
+        "nonNegativeInteger" => (Some(0), None),
+        "positiveInteger" => (Some(1), None),
+        "nonPositiveInteger" => (None, Some(0)),
+        "negativeInteger" => (None, Some(-1)),
+        "unsignedLong" => (Some(0), Some(u64::MAX.into())),
+        "unsignedInt" => (Some(0), Some(u32::MAX.into())),
+        "unsignedShort" => (Some(0), Some(u16::MAX.into())),
+        "unsignedByte" => (Some(0), Some(u8::MAX.into())),
+        _ => return None,
+    })
+}
+
+/// The local name of `datatype` in the `xsd:` namespace, if any.
+pub(crate) fn xsd_local_name(datatype: &str) -> Option<&str> {
+    datatype.strip_prefix(xsd::PREFIX.as_str())
+}
+
+/// Strip leading and trailing XML whitespaces (as per the `collapse` whitespace facet).
+pub(crate) fn collapse(lexical_form: &str) -> &str {
+    lexical_form.trim_matches([' ', '\t', '\n', '\r'])
+}
+
+/// Whether `txt` is a sequence of one or more ASCII digits.
+fn is_digits(txt: &str) -> bool {
+    !txt.is_empty() && txt.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Whether `txt` matches the lexical space of `xsd:decimal`.
+pub(crate) fn is_decimal(txt: &str) -> bool {
+    let txt = txt.strip_prefix(['+', '-']).unwrap_or(txt);
+    match txt.split_once('.') {
+        None => is_digits(txt),
+        Some((int, frac)) => {
+            (int.is_empty() || is_digits(int))
+                && (frac.is_empty() || is_digits(frac))
+                && !(int.is_empty() && frac.is_empty())
+        }
+    }
+}
+
+/// Whether `txt` matches the lexical space of `xsd:double` (and `xsd:float`).
+fn is_double(txt: &str) -> bool {
+    if matches!(txt, "INF" | "+INF" | "-INF" | "NaN") {
+        return true;
+    }
+    let (mantissa, exponent) = match txt.find(['e', 'E']) {
+        None => (txt, None),
+        Some(i) => (&txt[..i], Some(&txt[i + 1..])),
+    };
+    is_decimal(mantissa)
+        && exponent
+            .map(|e| is_digits(e.strip_prefix(['+', '-']).unwrap_or(e)))
+            .unwrap_or(true)
+}
+
+macro_rules! impl_integer_value {
+    ($($t: ty),*) => {
+        $(
+            /// Extract values from literals whose datatype is `xsd:integer` or derived from it.
+            impl LiteralValue for $t {
+                fn from_lexical(lexical_form: &str, datatype: &str) -> Result<Self, LiteralValueError> {
+                    let Some((min, max)) = xsd_local_name(datatype).and_then(integer_bounds) else {
+                        return Err(LiteralValueError::UnsupportedDatatype(datatype.into()));
+                    };
+                    let txt = collapse(lexical_form);
+                    let digits = txt.strip_prefix(['+', '-']).unwrap_or(txt);
+                    if !is_digits(digits) {
+                        return Err(LiteralValueError::invalid(lexical_form, datatype));
+                    }
+                    match txt.parse::<i128>() {
+                        Ok(val) if min.is_some_and(|min| val < min) || max.is_some_and(|max| val > max) => {
+                            return Err(LiteralValueError::invalid(lexical_form, datatype));
+                        }
+                        // NB: unsigned types do not parse "-0"
+                        Ok(0) => return Ok(0),
+                        Ok(_) => (),
+                        Err(_) => {
+                            // the value is beyond the range of i128
+                            let bound = if txt.starts_with('-') { min } else { max };
+                            if bound.is_some() {
+                                return Err(LiteralValueError::invalid(lexical_form, datatype));
+                            }
+                        }
+                    }
+                    txt.parse()
+                        .map_err(|_| LiteralValueError::out_of_range(lexical_form, datatype))
+                }
+            }
+        )*
+    };
+}
+impl_integer_value!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_float_value {
+    ($($t: ty),*) => {
+        $(
+            /// Extract values from literals whose datatype is `xsd:double`, `xsd:float`,
+            /// `xsd:decimal`, or derived from the latter.
+            impl LiteralValue for $t {
+                fn from_lexical(lexical_form: &str, datatype: &str) -> Result<Self, LiteralValueError> {
+                    let txt = collapse(lexical_form);
+                    let valid = match xsd_local_name(datatype) {
+                        Some("double" | "float") => is_double(txt),
+                        Some("decimal") => is_decimal(txt),
+                        Some(name) if integer_bounds(name).is_some() => {
+                            return i128::from_lexical(lexical_form, datatype)
+                                .map(|val| val as $t);
+                        }
+                        _ => return Err(LiteralValueError::UnsupportedDatatype(datatype.into())),
+                    };
+                    if !valid {
+                        return Err(LiteralValueError::invalid(lexical_form, datatype));
+                    }
+                    match txt {
+                        "INF" | "+INF" => Ok(<$t>::INFINITY),
+                        "-INF" => Ok(<$t>::NEG_INFINITY),
+                        "NaN" => Ok(<$t>::NAN),
+                        _ => txt
+                            .parse()
+                            .map_err(|_| LiteralValueError::invalid(lexical_form, datatype)),
+                    }
+                }
+            }
+        )*
+    };
+}
+impl_float_value!(f32, f64);
+
+/// Extract values from literals whose datatype is `xsd:boolean`.
+impl LiteralValue for bool {
+    fn from_lexical(lexical_form: &str, datatype: &str) -> Result<Self, LiteralValueError> {
+        if xsd_local_name(datatype) != Some("boolean") {
+            return Err(LiteralValueError::UnsupportedDatatype(datatype.into()));
+        }
+        match collapse(lexical_form) {
+            "true" | "1" => Ok(true),
+            "false" | "0" => Ok(false),
+            _ => Err(LiteralValueError::invalid(lexical_form, datatype)),
+        }
+    }
+}
+
+/// Extract values from literals whose datatype is `xsd:string` or `rdf:langString`
+/// (the lexical form is returned unchanged).
+impl LiteralValue for String {
+    fn from_lexical(lexical_form: &str, datatype: &str) -> Result<Self, LiteralValueError> {
+        if xsd_local_name(datatype) == Some("string")
+            || datatype == rdf::langString.iri().unwrap().as_str()
+        {
+            Ok(lexical_form.into())
+        } else {
+            Err(LiteralValueError::UnsupportedDatatype(datatype.into()))
+        }
+    }
+}
+
+/// Compute the [canonical lexical form] of `lexical_form` for `datatype`,
+/// as defined by XML Schema 1.1
+/// (see also [`Term::canonicalized`](super::Term::canonicalized)).
+///
+/// The following datatypes are supported:
+/// - `xsd:integer` and its derived datatypes (`"+007"` → `"7"`);
+/// - `xsd:decimal` (`"+01.50"` → `"1.5"`, `"2.0"` → `"2"`);
+/// - `xsd:double` and `xsd:float` (`"150"` → `"1.5E2"`, `"+INF"` → `"INF"`);
+/// - `xsd:boolean` (`"1"` → `"true"`, `"0"` → `"false"`).
+///
+/// Return `None` if `datatype` is not supported,
+/// or if `lexical_form` is not valid for `datatype`.
+///
+/// # Precondition
+/// `datatype` must be an absolute IRI.
+///
+/// [canonical lexical form]: https://www.w3.org/TR/xmlschema11-2/#dt-canonical-representation
+pub fn canonical_lexical_form(lexical_form: &str, datatype: &str) -> Option<String> {
+    let txt = collapse(lexical_form);
+    match xsd_local_name(datatype)? {
+        "boolean" => bool::from_lexical(lexical_form, datatype)
+            .ok()
+            .map(|b| b.to_string()),
+        "decimal" => is_decimal(txt).then(|| canonical_decimal(txt)),
+        "double" => f64::from_lexical(lexical_form, datatype)
+            .ok()
+            .map(|d| canonical_double(format!("{d:E}"))),
+        "float" => f32::from_lexical(lexical_form, datatype)
+            .ok()
+            .map(|f| canonical_double(format!("{f:E}"))),
+        name if integer_bounds(name).is_some() => {
+            match i128::from_lexical(lexical_form, datatype) {
+                // NB: OutOfRange means that the value does not fit in i128, but is still valid
+                Ok(_) | Err(LiteralValueError::OutOfRange { .. }) => Some(canonical_decimal(txt)),
+                Err(_) => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The canonical form of `txt`, assumed to match the lexical space of `xsd:decimal`:
+/// no plus sign, no superfluous zeros, and no decimal point for whole numbers.
+pub(crate) fn canonical_decimal(txt: &str) -> String {
+    let (negative, unsigned) = match txt.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, txt.strip_prefix('+').unwrap_or(txt)),
+    };
+    let (int, frac) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let int = int.trim_start_matches('0');
+    let frac = frac.trim_end_matches('0');
+    let mut canonical = String::with_capacity(txt.len() + 1);
+    if negative && !(int.is_empty() && frac.is_empty()) {
+        canonical.push('-');
+    }
+    canonical.push_str(if int.is_empty() { "0" } else { int });
+    if !frac.is_empty() {
+        canonical.push('.');
+        canonical.push_str(frac);
+    }
+    canonical
+}
+
+/// The canonical form of a floating point number,
+/// given its [`std::fmt::UpperExp`] representation (which is the shortest one).
+///
+/// The canonical form differs from that representation in the spelling of special values,
+/// and in that the mantissa always contains a decimal point (e.g. `1.0E2` instead of `1E2`).
+fn canonical_double(upper_exp: String) -> String {
+    match upper_exp.as_str() {
+        "inf" => "INF".into(),
+        "-inf" => "-INF".into(),
+        "NaN" => upper_exp,
+        _ => match upper_exp.split_once('E') {
+            Some((mantissa, exponent)) if !mantissa.contains('.') => {
+                format!("{mantissa}.0E{exponent}")
+            }
+            _ => upper_exp,
+        },
+    }
+}
+
+/// A numeric value, as far as [`value_cmp`] is concerned.
+pub(crate) enum Numeric {
+    Integer(i128),
+    #[cfg(feature = "bigdecimal")]
+    Decimal(bigdecimal::BigDecimal),
+    Double(f64),
+}
+
+impl Numeric {
+    pub(crate) fn of<T: Term + ?Sized>(t: &T) -> Option<Self> {
+        let datatype = t.datatype()?;
+        match xsd_local_name(datatype.as_str())? {
+            #[cfg(feature = "bigdecimal")]
+            "decimal" => t.value().ok().map(Numeric::Decimal),
+            #[cfg(not(feature = "bigdecimal"))]
+            "decimal" => t.value().ok().map(Numeric::Double),
+            "double" | "float" => t.value().ok().map(Numeric::Double),
+            name if integer_bounds(name).is_some() => match t.value() {
+                Ok(i) => Some(Numeric::Integer(i)),
+                #[cfg(feature = "bigdecimal")]
+                Err(LiteralValueError::OutOfRange { .. }) => t.value().ok().map(Numeric::Decimal),
+                Err(_) => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_f64(&self) -> f64 {
+        match self {
+            Numeric::Integer(i) => *i as f64,
+            #[cfg(feature = "bigdecimal")]
+            Numeric::Decimal(d) => bigdecimal::ToPrimitive::to_f64(d).unwrap_or(f64::NAN),
+            Numeric::Double(d) => *d,
+        }
+    }
+
+    /// Compare `self` and `other` exactly, if none of them is a floating point number.
+    pub(crate) fn exact_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Numeric::Integer(i1), Numeric::Integer(i2)) => Some(i1.cmp(i2)),
+            #[cfg(feature = "bigdecimal")]
+            (n1, n2) => Some(n1.as_big_decimal()?.cmp(&n2.as_big_decimal()?)),
+            #[cfg(not(feature = "bigdecimal"))]
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    fn as_big_decimal(&self) -> Option<std::borrow::Cow<'_, bigdecimal::BigDecimal>> {
+        use std::borrow::Cow;
+        match self {
+            Numeric::Integer(i) => Some(Cow::Owned(bigdecimal::BigDecimal::new((*i).into(), 0))),
+            Numeric::Decimal(d) => Some(Cow::Borrowed(d)),
+            Numeric::Double(_) => None,
+        }
+    }
+}
+
+/// Implementation of [`Term::value_cmp`].
+pub(crate) fn value_cmp<T1, T2>(t1: &T1, t2: &T2) -> Option<Ordering>
+where
+    T1: Term + ?Sized,
+    T2: Term,
+{
+    if let (Some(lex1), Some(lex2)) = (t1.lexical_form(), t2.lexical_form()) {
+        if let (Some(n1), Some(n2)) = (Numeric::of(t1), Numeric::of(t2)) {
+            return n1
+                .exact_cmp(&n2)
+                .or_else(|| n1.as_f64().partial_cmp(&n2.as_f64()));
+        }
+        if let (Ok(b1), Ok(b2)) = (t1.value::<bool>(), t2.value::<bool>()) {
+            return Some(Ord::cmp(&b1, &b2));
+        }
+        let (dt1, dt2) = (t1.datatype().unwrap(), t2.datatype().unwrap());
+        if xsd_local_name(dt1.as_str()) == Some("string")
+            && xsd_local_name(dt2.as_str()) == Some("string")
+        {
+            return Some(lex1.cmp(&lex2));
+        }
+        if let (Some(tag1), Some(tag2)) = (t1.language_tag(), t2.language_tag()) {
+            if tag1.as_str().eq_ignore_ascii_case(tag2.as_str()) {
+                return Some(lex1.cmp(&lex2));
+            }
+        }
+    }
+    // terms with no comparable value are only known to be equal to themselves
+    Term::eq(t1, t2.borrow_term()).then_some(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::term::{LanguageTag, SimpleTerm, Term};
+    use test_case::test_case;
+
+    #[test_case("42", xsd::integer, 42)]
+    #[test_case("+42", xsd::integer, 42)]
+    #[test_case(" \t-42\n", xsd::integer, -42)]
+    #[test_case("0042", xsd::long, 42)]
+    #[test_case("-0", xsd::nonNegativeInteger, 0)]
+    #[test_case("127", xsd::byte, 127)]
+    #[test_case("-9223372036854775808", xsd::long, i64::MIN)]
+    fn integer_ok(lex: &str, dt: crate::ns::NsTerm<'static>, expected: i64) {
+        assert_eq!((lex * dt).value::<i64>(), Ok(expected));
+    }
+
+    #[test_case("", xsd::integer)]
+    #[test_case("+", xsd::integer)]
+    #[test_case("4 2", xsd::integer)]
+    #[test_case("+-42", xsd::integer)]
+    #[test_case("4.2", xsd::integer)]
+    #[test_case("0x2A", xsd::integer)]
+    #[test_case("128", xsd::byte)]
+    #[test_case("-1", xsd::nonNegativeInteger)]
+    #[test_case("0", xsd::positiveInteger)]
+    #[test_case("1", xsd::nonPositiveInteger)]
+    #[test_case("9223372036854775808", xsd::long)]
+    #[test_case("-999999999999999999999999999999999999999999", xsd::unsignedLong)]
+    fn integer_invalid(lex: &str, dt: crate::ns::NsTerm<'static>) {
+        assert!(matches!(
+            (lex * dt).value::<i64>(),
+            Err(LiteralValueError::InvalidLexicalForm { .. })
+        ));
+    }
+
+    #[test_case("256", xsd::integer)]
+    #[test_case("-1", xsd::integer)]
+    #[test_case("999999999999999999999999999999999999999999", xsd::integer)]
+    #[test_case("-999999999999999999999999999999999999999999", xsd::integer)]
+    fn integer_out_of_range(lex: &str, dt: crate::ns::NsTerm<'static>) {
+        assert!(matches!(
+            (lex * dt).value::<u8>(),
+            Err(LiteralValueError::OutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn beyond_i128() {
+        let lex = "300000000000000000000000000000000000000";
+        assert_eq!(
+            (lex * xsd::integer).value::<u128>(),
+            Ok(300000000000000000000000000000000000000)
+        );
+    }
+
+    #[test_case("1.25", xsd::double, 1.25)]
+    #[test_case(" -1.5E2 ", xsd::double, -150.0)]
+    #[test_case(".5e-1", xsd::float, 0.05)]
+    #[test_case("1.", xsd::double, 1.0)]
+    #[test_case("+INF", xsd::double, f64::INFINITY)]
+    #[test_case("-INF", xsd::float, f64::NEG_INFINITY)]
+    #[test_case("+2.50", xsd::decimal, 2.5)]
+    #[test_case("-12", xsd::short, -12.0)]
+    fn float_ok(lex: &str, dt: crate::ns::NsTerm<'static>, expected: f64) {
+        assert_eq!((lex * dt).value::<f64>(), Ok(expected));
+    }
+
+    #[test_case("inf", xsd::double)]
+    #[test_case("infinity", xsd::double)]
+    #[test_case("nan", xsd::double)]
+    #[test_case("1e5", xsd::decimal)]
+    #[test_case("INF", xsd::decimal)]
+    #[test_case(".", xsd::decimal)]
+    #[test_case("1e", xsd::double)]
+    #[test_case("e5", xsd::double)]
+    #[test_case("1.5", xsd::integer)]
+    fn float_invalid(lex: &str, dt: crate::ns::NsTerm<'static>) {
+        assert!(matches!(
+            (lex * dt).value::<f64>(),
+            Err(LiteralValueError::InvalidLexicalForm { .. })
+        ));
+    }
+
+    #[test]
+    fn nan() {
+        assert!(("NaN" * xsd::double).value::<f32>().unwrap().is_nan());
+    }
+
+    #[test]
+    fn boolean() {
+        assert_eq!(("true" * xsd::boolean).value::<bool>(), Ok(true));
+        assert_eq!((" 0 " * xsd::boolean).value::<bool>(), Ok(false));
+        assert!(("True" * xsd::boolean).value::<bool>().is_err());
+        assert!(("yes" * xsd::boolean).value::<bool>().is_err());
+    }
+
+    #[test]
+    fn string() {
+        assert_eq!(" a ".value::<String>(), Ok(" a ".to_string()));
+        let en = LanguageTag::new_unchecked("en");
+        assert_eq!(("chat" * en).value::<String>(), Ok("chat".to_string()));
+    }
+
+    #[test]
+    fn unsupported() {
+        assert_eq!(
+            ("42" * xsd::string).value::<i32>(),
+            Err(LiteralValueError::UnsupportedDatatype(
+                xsd::string.iri().unwrap().to_string()
+            ))
+        );
+        assert_eq!(("true" * xsd::string).value::<bool>().ok(), None);
+        assert_eq!(
+            xsd::integer.value::<i32>(),
+            Err(LiteralValueError::NotALiteral)
+        );
+    }
+
+    #[test_case("01" * xsd::integer, "1" * xsd::integer, Some(Ordering::Equal))]
+    #[test_case("1" * xsd::byte, "1.0" * xsd::decimal, Some(Ordering::Equal))]
+    #[test_case("1" * xsd::integer, "1E0" * xsd::double, Some(Ordering::Equal))]
+    #[test_case("2" * xsd::integer, "1.5" * xsd::float, Some(Ordering::Greater))]
+    #[test_case("-2" * xsd::long, "1" * xsd::unsignedByte, Some(Ordering::Less))]
+    #[test_case("NaN" * xsd::double, "NaN" * xsd::double, None)]
+    #[test_case("INF" * xsd::double, "99" * xsd::integer, Some(Ordering::Greater))]
+    #[test_case("1" * xsd::boolean, "true" * xsd::boolean, Some(Ordering::Equal))]
+    #[test_case("false" * xsd::boolean, "true" * xsd::boolean, Some(Ordering::Less))]
+    #[test_case("a" * xsd::string, "b" * xsd::string, Some(Ordering::Less))]
+    #[test_case("1" * xsd::string, "1" * xsd::integer, None)]
+    #[test_case("1" * xsd::boolean, "1" * xsd::integer, None)]
+    #[test_case("x" * xsd::integer, "x" * xsd::integer, Some(Ordering::Equal))]
+    #[test_case("x" * xsd::integer, "y" * xsd::integer, None)]
+    #[test_case("x" * xsd::anyURI, "x" * xsd::anyURI, Some(Ordering::Equal))]
+    #[test_case("x" * xsd::anyURI, "y" * xsd::anyURI, None)]
+    #[test_case(xsd::integer.into_term(), xsd::integer.into_term(), Some(Ordering::Equal))]
+    #[test_case(xsd::integer.into_term(), xsd::decimal.into_term(), None)]
+    fn value_cmp(t1: SimpleTerm, t2: SimpleTerm, expected: Option<Ordering>) {
+        assert_eq!(t1.value_cmp(&t2), expected);
+        assert_eq!(t2.value_cmp(&t1), expected.map(Ordering::reverse));
+        assert_eq!(t1.value_eq(&t2), expected == Some(Ordering::Equal));
+    }
+
+    #[test_case("42", xsd::integer, "42")]
+    #[test_case(" +0042 ", xsd::integer, "42")]
+    #[test_case("-0", xsd::integer, "0")]
+    #[test_case("-000", xsd::nonPositiveInteger, "0")]
+    #[test_case("-12", xsd::byte, "-12")]
+    #[test_case(
+        "+00999999999999999999999999999999999999999999",
+        xsd::integer,
+        "999999999999999999999999999999999999999999"
+    )]
+    #[test_case("+01.50", xsd::decimal, "1.5")]
+    #[test_case("2.0", xsd::decimal, "2")]
+    #[test_case("-.5", xsd::decimal, "-0.5")]
+    #[test_case("-0.0", xsd::decimal, "0")]
+    #[test_case("007.", xsd::decimal, "7")]
+    #[test_case("150", xsd::double, "1.5E2")]
+    #[test_case("1", xsd::double, "1.0E0")]
+    #[test_case("0.1", xsd::double, "1.0E-1")]
+    #[test_case("-0", xsd::double, "-0.0E0")]
+    #[test_case("+INF", xsd::double, "INF")]
+    #[test_case("-INF", xsd::float, "-INF")]
+    #[test_case("NaN", xsd::float, "NaN")]
+    #[test_case("0.1", xsd::float, "1.0E-1")]
+    #[test_case("1", xsd::boolean, "true")]
+    #[test_case(" false", xsd::boolean, "false")]
+    fn canonical_ok(lex: &str, dt: crate::ns::NsTerm<'static>, expected: &str) {
+        let dt = dt.iri().unwrap();
+        assert_eq!(
+            canonical_lexical_form(lex, dt.as_str()).as_deref(),
+            Some(expected)
+        );
+    }
+
+    #[test_case("1.5", xsd::integer)]
+    #[test_case("256", xsd::unsignedByte)]
+    #[test_case("1e5", xsd::decimal)]
+    #[test_case("inf", xsd::double)]
+    #[test_case("yes", xsd::boolean)]
+    #[test_case("42", xsd::string)]
+    #[test_case("2024-01-01", xsd::date)]
+    fn canonical_none(lex: &str, dt: crate::ns::NsTerm<'static>) {
+        let dt = dt.iri().unwrap();
+        assert_eq!(canonical_lexical_form(lex, dt.as_str()), None);
+    }
+
+    #[test]
+    fn canonicalized() {
+        let t = "+01.50" * xsd::decimal;
+        assert!(Term::eq(&t.canonicalized(), "1.5" * xsd::decimal));
+        let t = "1" * xsd::integer;
+        assert!(Term::eq(&t.canonicalized(), &t));
+        let t = "a" * LanguageTag::new_unchecked("en");
+        assert!(Term::eq(&t.canonicalized(), &t));
+        assert!(Term::eq(&xsd::integer.canonicalized(), xsd::integer));
+    }
+
+    #[test]
+    fn value_cmp_language_strings() {
+        let en = "a" * LanguageTag::new_unchecked("en");
+        assert_eq!(
+            en.value_cmp("a" * LanguageTag::new_unchecked("EN")),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            en.value_cmp("b" * LanguageTag::new_unchecked("en")),
+            Some(Ordering::Less)
+        );
+        assert_eq!(en.value_cmp("a" * LanguageTag::new_unchecked("fr")), None);
+        assert_eq!(en.value_cmp("a" * xsd::string), None);
+    }
+}
@@ -13,36 +13,70 @@
 //! [`Dataset::quads_matching`](crate::dataset::Dataset::quads_matching),
 //! [`MutableDataset::remove_matching`](crate::dataset::MutableDataset::remove_matching),
 //! [`MutableDataset::retain_matching`](crate::dataset::MutableDataset::retain_matching).
+//!
+//! See also the [`tmatch!`](crate::tmatch) macro, for building matchers with a concise syntax.
+//!
+//! Besides exact terms and [`Any`], terms can be matched by their [kind](TermKind),
+//! by [regular expressions](IriRegexMatcher) over their IRI or [lexical form](LexicalRegexMatcher),
+//! by the [bounding box](BoundingBoxMatcher) of their WKT geometry,
+//! or by any closure accepting a [`SimpleTerm`]:
+//! ```
+//! # use sophia_api::{graph::Graph, ns::rdfs, term::{SimpleTerm, Term, matcher::*}};
+//! # use regex::Regex;
+//! # fn f<G: Graph>(g: &G) -> Result<(), G::Error> {
+//! let schema_org = IriRegexMatcher::new(Regex::new("^https?://schema.org/").unwrap());
+//! let mentions_rust = LexicalRegexMatcher::new(Regex::new("(?i)rust").unwrap());
+//! let short = |t: SimpleTerm| t.lexical_form().is_some_and(|lex| lex.len() < 20);
+//! for t in g.triples_matching(schema_org, [rdfs::label, rdfs::comment], Not(short)) {
+//!     // ...
+//! # let _ = t?;
+//! }
+//! for t in g.triples_matching(Any, Any, mentions_rust) {
+//!     // ...
+//! # let _ = t?;
+//! }
+//! # Ok(()) }
+//! ```
 
 use super::*;
 
 mod _any;
+mod _bounding_box_matcher;
 mod _datatype_matcher;
 mod _graph_name_matcher;
+mod _language_range_matcher;
 mod _language_tag_matcher;
 mod _matcher_ref;
 mod _not;
+mod _regex_matcher;
 mod _term_matcher_gn;
+mod _tmatch;
 mod _trait;
 
 pub use _any::Any;
+pub use _bounding_box_matcher::*;
 pub use _datatype_matcher::*;
 pub use _graph_name_matcher::*;
+pub use _language_range_matcher::*;
 pub use _language_tag_matcher::*;
 pub use _matcher_ref::*;
 pub use _not::Not;
+pub use _regex_matcher::*;
 pub use _term_matcher_gn::*;
 pub use _trait::*;
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::ns::xsd;
+    use crate::ns::{geo, xsd};
+    use crate::term::wkt::{BoundingBox, Point};
+    use regex::Regex;
     use sophia_iri::IriRef;
+    use test_case::test_case;
 
-    const T1: IriRef<&str> = IriRef::new_unchecked_const("tag:t1");
-    const T2: IriRef<&str> = IriRef::new_unchecked_const("tag:t2");
-    const T3: IriRef<&str> = IriRef::new_unchecked_const("tag:t3");
+    const T1: IriRef<&str> = IriRef::new_const("tag:t1");
+    const T2: IriRef<&str> = IriRef::new_const("tag:t2");
+    const T3: IriRef<&str> = IriRef::new_const("tag:t3");
 
     fn is_term_matcher<M: TermMatcher>(_: M) {}
 
@@ -57,6 +91,13 @@ mod test {
         is_term_matcher(([T1], [T2], [T3]));
         is_term_matcher([T1, T2].matcher_ref());
         is_term_matcher(Any * xsd::string);
+        is_term_matcher(IriRegexMatcher::new(Regex::new("^tag:").unwrap()));
+        is_term_matcher(LexicalRegexMatcher::new(Regex::new("^a").unwrap()));
+        is_term_matcher(LanguageRangeMatcher::basic("en").unwrap());
+        is_term_matcher(BoundingBoxMatcher::within(BoundingBox::new(
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 1.0, y: 1.0 },
+        )));
     }
 
     fn is_graph_name_matcher<M: GraphNameMatcher>(_: M) {}
@@ -199,6 +240,45 @@ mod test {
         assert!(TermMatcher::matches(&m1, "hello"));
     }
 
+    #[test]
+    fn iri_regex_matcher() {
+        let m = IriRegexMatcher::new(Regex::new("^tag:t[12]$").unwrap());
+        assert!(m.matches(&T1));
+        assert!(m.matches(&T2));
+        assert!(!m.matches(&T3));
+        assert!(!m.matches("tag:t1"));
+        assert!(!m.matches(&BnodeId::new_unchecked("t1")));
+        assert!(m.constant().is_none());
+        assert!(Not(m).matches(&T3));
+    }
+
+    #[test]
+    fn lexical_regex_matcher() {
+        let m = LexicalRegexMatcher::new(Regex::new("^4").unwrap());
+        assert!(m.matches(&42));
+        assert!(m.matches("4 walls"));
+        assert!(m.matches(&("4" * LanguageTag::new_unchecked("en"))));
+        assert!(!m.matches("24"));
+        assert!(!m.matches(&IriRef::new_unchecked("4")));
+        assert!(m.constant().is_none());
+    }
+
+    #[test]
+    fn triples_matching_regex_and_closure() {
+        use crate::graph::Graph;
+        let g = vec![[T1, T2, T1], [T2, T2, T3], [T3, T1, T1]];
+        let m = IriRegexMatcher::new(Regex::new("[12]$").unwrap());
+        let starts_with_t3 = |t: SimpleTerm| Term::eq(&t, T3);
+        let found: Vec<_> = g
+            .triples_matching(m.matcher_ref(), [T2], Not(starts_with_t3))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0][0].iri().unwrap().as_str(), "tag:t1");
+        let found = g.triples_matching(Any, Any, (Any, Any, m)).count();
+        assert_eq!(found, 0);
+    }
+
     #[test]
     fn language_tag_matcher() {
         let en = LanguageTag::new_unchecked("en");
@@ -211,6 +291,94 @@ mod test {
         assert!(TermMatcher::matches(&m1, &("hello" * en)));
         assert!(!TermMatcher::matches(&m1, &("hello" * enus)));
         assert!(!TermMatcher::matches(&m1, &("hello" * fr)));
+        assert_eq!(m1.language_tag(), Some(en));
+        assert_eq!(m1.matcher_ref().language_tag(), Some(en));
+        assert_eq!([T1].language_tag(), None);
+    }
+
+    #[test_case("*", "de-CH", true)]
+    #[test_case("de", "de", true)]
+    #[test_case("de", "DE-ch", true)]
+    #[test_case("de-ch", "de-CH-1996", true)]
+    #[test_case("de-CH", "de", false)]
+    #[test_case("de-CH", "de-Latn-CH", false)]
+    #[test_case("de", "deu", false)]
+    #[test_case("en-US", "en-u", false)]
+    fn language_range_basic(range: &str, tag: &str, expected: bool) {
+        let m = LanguageRangeMatcher::basic(range).unwrap();
+        assert!(!m.is_extended());
+        assert_eq!(m.matches_tag(tag), expected);
+        let literal = "hello" * LanguageTag::new_unchecked(tag);
+        assert_eq!(m.matches(&literal), expected);
+        assert!(!m.matches("hello"));
+        assert!(!m.matches(&T1));
+    }
+
+    // examples from RFC 4647 §3.3.2
+    #[test_case("de-*-DE", "de-DE", true)]
+    #[test_case("de-*-DE", "de-de", true; "lowercase tag")]
+    #[test_case("de-*-DE", "de-Latn-DE", true)]
+    #[test_case("de-*-DE", "de-Latf-DE", true)]
+    #[test_case("de-*-DE", "de-DE-x-goethe", true)]
+    #[test_case("de-*-DE", "de-Latn-DE-1996", true)]
+    #[test_case("de-*-DE", "de-Deva-DE", true)]
+    #[test_case("de-*-DE", "de", false)]
+    #[test_case("de-*-DE", "de-x-DE", false)]
+    #[test_case("de-*-DE", "de-Deva", false)]
+    #[test_case("de-DE", "de-Latn-DE", true)]
+    #[test_case("de-*", "de-CH", true)]
+    #[test_case("de-*", "de", true)]
+    #[test_case("de-*", "fr-CH", false)]
+    #[test_case("*-CH", "fr-CH", true)]
+    #[test_case("*-CH", "fr", false)]
+    #[test_case("*", "fr", true)]
+    fn language_range_extended(range: &str, tag: &str, expected: bool) {
+        let m = LanguageRangeMatcher::extended(range).unwrap();
+        assert!(m.is_extended());
+        assert_eq!(m.matches_tag(tag), expected);
+        let literal = "hello" * LanguageTag::new_unchecked(tag);
+        assert_eq!(m.matches(&literal), expected);
+    }
+
+    #[test_case("", false, false)]
+    #[test_case("*", true, true)]
+    #[test_case("de-*", false, true)]
+    #[test_case("*-CH", false, true)]
+    #[test_case("de-CH-1996", true, true)]
+    #[test_case("1996", false, false)]
+    #[test_case("de--CH", false, false)]
+    #[test_case("abcdefghi", false, false)]
+    #[test_case("de-**", false, false)]
+    fn language_range_validity(range: &str, basic: bool, extended: bool) {
+        assert_eq!(LanguageRangeMatcher::basic(range).is_ok(), basic);
+        assert_eq!(LanguageRangeMatcher::extended(range).is_ok(), extended);
+    }
+
+    fn unit_box() -> BoundingBox {
+        BoundingBox::new(Point { x: 0.0, y: 0.0 }, Point { x: 1.0, y: 1.0 })
+    }
+
+    #[test_case("POINT(0.5 0.5)", true, true)]
+    #[test_case("POINT(1 0)", true, true; "corner")]
+    #[test_case("POINT(1.5 0.5)", false, false)]
+    #[test_case("LINESTRING(0.5 0.5, 1.5 0.5)", false, true)]
+    #[test_case("POLYGON((-1 -1, 2 -1, 2 2, -1 -1))", false, true; "around")]
+    #[test_case("POLYGON((2 2, 3 2, 3 3, 2 2))", false, false; "outside")]
+    #[test_case("LINESTRING EMPTY", false, false)]
+    #[test_case("POINT(0.5)", false, false; "invalid")]
+    fn bounding_box(wkt: &str, within: bool, intersecting: bool) {
+        let t = wkt * geo::wktLiteral;
+        assert_eq!(BoundingBoxMatcher::within(unit_box()).matches(&t), within);
+        assert_eq!(
+            BoundingBoxMatcher::intersecting(unit_box()).matches(&t),
+            intersecting
+        );
+    }
+
+    #[test]
+    fn bounding_box_other_datatype() {
+        let t = "POINT(0.5 0.5)" * xsd::string;
+        assert!(!BoundingBoxMatcher::intersecting(unit_box()).matches(&t));
     }
 
     #[test]
@@ -0,0 +1,74 @@
+use super::*;
+use crate::term::wkt::{BoundingBox, WktLiteral};
+
+/// A [`TermMatcher`] that matches `geo:wktLiteral`s by comparing their
+/// [bounding box](crate::term::wkt::Geometry::bounding_box) with a given [`BoundingBox`].
+///
+/// Two spatial relations are supported:
+/// - [within](BoundingBoxMatcher::within) matches geometries entirely inside the given box
+///   (which is exact, as boxes are convex);
+/// - [intersecting](BoundingBoxMatcher::intersecting) matches geometries whose bounding box
+///   intersects the given box (which is an over-approximation,
+///   suitable as a pre-filter before a more precise test).
+///
+/// Literals that are not valid WKT literals, and empty geometries, are never matched.
+/// The coordinate reference system of literals is not taken into account:
+/// it is assumed to be the same as the one of the given box.
+///
+/// # Example
+/// ```
+/// # use sophia_api::ns::geo;
+/// # use sophia_api::term::matcher::{BoundingBoxMatcher, TermMatcher};
+/// # use sophia_api::term::wkt::{BoundingBox, Point};
+/// let paris = BoundingBox::new(Point { x: 2.22, y: 48.81 }, Point { x: 2.47, y: 48.91 });
+/// let notre_dame = "POINT(2.35 48.85)" * geo::wktLiteral;
+/// let seine = "LINESTRING(2.2 48.8, 2.35 48.85, 2.5 48.9)" * geo::wktLiteral;
+/// assert!(BoundingBoxMatcher::within(paris).matches(&notre_dame));
+/// assert!(!BoundingBoxMatcher::within(paris).matches(&seine));
+/// assert!(BoundingBoxMatcher::intersecting(paris).matches(&seine));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingBoxMatcher {
+    bbox: BoundingBox,
+    within: bool,
+}
+
+impl BoundingBoxMatcher {
+    /// Construct a [`BoundingBoxMatcher`] matching geometries entirely inside `bbox`.
+    pub fn within(bbox: BoundingBox) -> Self {
+        BoundingBoxMatcher { bbox, within: true }
+    }
+
+    /// Construct a [`BoundingBoxMatcher`] matching geometries whose bounding box intersects `bbox`.
+    pub fn intersecting(bbox: BoundingBox) -> Self {
+        BoundingBoxMatcher {
+            bbox,
+            within: false,
+        }
+    }
+
+    /// The bounding box of this matcher.
+    pub fn bounding_box(&self) -> BoundingBox {
+        self.bbox
+    }
+
+    /// Check whether a geometry with the given bounding box is matched by this matcher.
+    pub fn matches_box(&self, other: &BoundingBox) -> bool {
+        if self.within {
+            self.bbox.contains(other)
+        } else {
+            self.bbox.intersects(other)
+        }
+    }
+}
+
+impl TermMatcher for BoundingBoxMatcher {
+    type Term = SimpleTerm<'static>; // not used
+
+    fn matches<T2: Term + ?Sized>(&self, term: &T2) -> bool {
+        term.value::<WktLiteral>()
+            .ok()
+            .and_then(|wkt| wkt.geometry.bounding_box())
+            .is_some_and(|bbox| self.matches_box(&bbox))
+    }
+}
@@ -0,0 +1,162 @@
+use std::borrow::Borrow;
+
+use thiserror::Error;
+
+use super::*;
+
+/// A [`TermMatcher`] that matches all literals whose language tag matches a given language range,
+/// as specified by [RFC 4647](https://www.rfc-editor.org/rfc/rfc4647#section-3.3).
+///
+/// Two filtering schemes are supported:
+/// - [basic filtering](LanguageRangeMatcher::basic), where `de-CH` matches `de-CH` and `de-CH-1996`,
+///   but not `de` nor `de-Latn-CH`, and `*` matches any tag;
+/// - [extended filtering](LanguageRangeMatcher::extended), where wildcards (`*`) match any sequence of subtags,
+///   so that `de-*-CH` matches `de-CH`, `de-Latn-CH` and `de-CH-1996`, and `de-*` matches any German tag.
+///
+/// In both cases, comparison is case-insensitive.
+///
+/// # Example
+/// ```
+/// # use sophia_api::term::{LanguageTag, matcher::{LanguageRangeMatcher, TermMatcher}};
+/// let de_ch = "Grüezi" * LanguageTag::new_unchecked("de-CH");
+/// let de_latn_ch = "Grüezi" * LanguageTag::new_unchecked("de-Latn-CH");
+/// assert!(LanguageRangeMatcher::basic("de").unwrap().matches(&de_ch));
+/// assert!(!LanguageRangeMatcher::basic("de-CH").unwrap().matches(&de_latn_ch));
+/// assert!(LanguageRangeMatcher::extended("de-*-CH").unwrap().matches(&de_latn_ch));
+/// assert!(!LanguageRangeMatcher::extended("fr-*").unwrap().matches(&de_ch));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct LanguageRangeMatcher<T: Borrow<str>> {
+    range: T,
+    extended: bool,
+}
+
+impl<T: Borrow<str>> LanguageRangeMatcher<T> {
+    /// Construct a new [`LanguageRangeMatcher`] performing
+    /// [basic filtering](https://www.rfc-editor.org/rfc/rfc4647#section-3.3.1).
+    ///
+    /// `range` must be a valid basic language range (e.g. `*`, `de` or `de-CH`).
+    pub fn basic(range: T) -> Result<Self, InvalidLanguageRange> {
+        let txt = range.borrow();
+        if txt == "*" || is_valid_range(txt, false) {
+            Ok(LanguageRangeMatcher {
+                range,
+                extended: false,
+            })
+        } else {
+            Err(InvalidLanguageRange(txt.to_string()))
+        }
+    }
+
+    /// Construct a new [`LanguageRangeMatcher`] performing
+    /// [extended filtering](https://www.rfc-editor.org/rfc/rfc4647#section-3.3.2).
+    ///
+    /// `range` must be a valid extended language range (e.g. `de-*`, `*-CH` or `de-*-CH`).
+    pub fn extended(range: T) -> Result<Self, InvalidLanguageRange> {
+        let txt = range.borrow();
+        if is_valid_range(txt, true) {
+            Ok(LanguageRangeMatcher {
+                range,
+                extended: true,
+            })
+        } else {
+            Err(InvalidLanguageRange(txt.to_string()))
+        }
+    }
+
+    /// The language range of this matcher.
+    pub fn as_str(&self) -> &str {
+        self.range.borrow()
+    }
+
+    /// Whether this matcher performs extended filtering (or basic filtering otherwise).
+    pub fn is_extended(&self) -> bool {
+        self.extended
+    }
+
+    /// Destructs this [`LanguageRangeMatcher`]
+    pub fn unwrap(self) -> T {
+        self.range
+    }
+
+    /// Check whether the given language tag matches this language range.
+    pub fn matches_tag(&self, tag: &str) -> bool {
+        if self.extended {
+            extended_filter(self.as_str(), tag)
+        } else {
+            basic_filter(self.as_str(), tag)
+        }
+    }
+}
+
+impl<T: Borrow<str>> TermMatcher for LanguageRangeMatcher<T> {
+    type Term = SimpleTerm<'static>; // not used
+
+    fn matches<T2: Term + ?Sized>(&self, term: &T2) -> bool {
+        match term.language_tag() {
+            Some(tag) => self.matches_tag(tag.as_str()),
+            None => false,
+        }
+    }
+}
+
+/// This error is raised when trying to build a [`LanguageRangeMatcher`] with an invalid language range.
+#[derive(Debug, Error)]
+#[error("The given language range '{0}' does not comply with RFC 4647")]
+pub struct InvalidLanguageRange(pub String);
+
+/// Whether `range` matches the `language-range` rule (or `extended-language-range` if `extended`)
+/// of [RFC 4647 §2](https://www.rfc-editor.org/rfc/rfc4647#section-2),
+/// except for the single wildcard `*` of basic ranges.
+fn is_valid_range(range: &str, extended: bool) -> bool {
+    range.split('-').enumerate().all(|(i, subtag)| {
+        (extended && subtag == "*")
+            || ((1..=8).contains(&subtag.len())
+                && subtag
+                    .bytes()
+                    .all(|b| b.is_ascii_alphabetic() || (i > 0 && b.is_ascii_digit())))
+    })
+}
+
+/// Basic filtering, as specified by [RFC 4647 §3.3.1](https://www.rfc-editor.org/rfc/rfc4647#section-3.3.1).
+fn basic_filter(range: &str, tag: &str) -> bool {
+    if range == "*" {
+        return true;
+    }
+    match tag.get(..range.len()) {
+        Some(prefix) => {
+            prefix.eq_ignore_ascii_case(range)
+                && (tag.len() == range.len() || tag.as_bytes()[range.len()] == b'-')
+        }
+        None => false,
+    }
+}
+
+/// Extended filtering, as specified by [RFC 4647 §3.3.2](https://www.rfc-editor.org/rfc/rfc4647#section-3.3.2).
+fn extended_filter(range: &str, tag: &str) -> bool {
+    let mut range = range.split('-');
+    let mut tag = tag.split('-');
+    // the first subtags must match, unless the range starts with a wildcard
+    match (range.next(), tag.next()) {
+        (Some("*"), Some(_)) => (),
+        (Some(r), Some(t)) if r.eq_ignore_ascii_case(t) => (),
+        _ => return false,
+    }
+    for r in range {
+        if r == "*" {
+            continue;
+        }
+        loop {
+            match tag.next() {
+                // a matching subtag
+                Some(t) if t.eq_ignore_ascii_case(r) => break,
+                // a singleton can not be skipped
+                None => return false,
+                Some(t) if t.len() == 1 => return false,
+                // any other subtag can be skipped
+                Some(_) => (),
+            }
+        }
+    }
+    true
+}
@@ -35,6 +35,10 @@ impl<T: Borrow<str>> TermMatcher for LanguageTagMatcher<T> {
             None => false,
         }
     }
+
+    fn language_tag(&self) -> Option<LanguageTag<&str>> {
+        Some(self.as_ref())
+    }
 }
 
 impl<T: Borrow<str>> std::ops::Mul<LanguageTag<T>> for Any {
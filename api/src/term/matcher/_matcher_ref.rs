@@ -21,4 +21,8 @@ impl<'a, T: TermMatcher + ?Sized> TermMatcher for MatcherRef<'a, T> {
     fn constant(&self) -> Option<&Self::Term> {
         self.0.constant()
     }
+
+    fn language_tag(&self) -> Option<LanguageTag<&str>> {
+        self.0.language_tag()
+    }
 }
@@ -0,0 +1,73 @@
+use regex::Regex;
+
+use super::*;
+
+/// A [`TermMatcher`] that matches all IRIs matching a given regular expression.
+///
+/// NB: as with [`Regex::is_match`], the regular expression matches if it is found anywhere in the IRI,
+/// unless it is anchored with `^` and/or `$`.
+#[derive(Clone, Debug)]
+pub struct IriRegexMatcher(Regex);
+
+impl IriRegexMatcher {
+    /// Construct a new [`IriRegexMatcher`] from a [`Regex`]
+    pub fn new(regex: Regex) -> Self {
+        Self(regex)
+    }
+
+    /// Destructs this [`IriRegexMatcher`]
+    pub fn unwrap(self) -> Regex {
+        self.0
+    }
+
+    /// Borrow the inner [`Regex`]
+    pub fn as_regex(&self) -> &Regex {
+        &self.0
+    }
+}
+
+impl TermMatcher for IriRegexMatcher {
+    type Term = SimpleTerm<'static>; // not used
+
+    fn matches<T2: Term + ?Sized>(&self, term: &T2) -> bool {
+        match term.iri() {
+            Some(iri) => self.0.is_match(iri.as_str()),
+            None => false,
+        }
+    }
+}
+
+/// A [`TermMatcher`] that matches all literals whose lexical form matches a given regular expression.
+///
+/// NB: as with [`Regex::is_match`], the regular expression matches if it is found anywhere in the lexical form,
+/// unless it is anchored with `^` and/or `$`.
+#[derive(Clone, Debug)]
+pub struct LexicalRegexMatcher(Regex);
+
+impl LexicalRegexMatcher {
+    /// Construct a new [`LexicalRegexMatcher`] from a [`Regex`]
+    pub fn new(regex: Regex) -> Self {
+        Self(regex)
+    }
+
+    /// Destructs this [`LexicalRegexMatcher`]
+    pub fn unwrap(self) -> Regex {
+        self.0
+    }
+
+    /// Borrow the inner [`Regex`]
+    pub fn as_regex(&self) -> &Regex {
+        &self.0
+    }
+}
+
+impl TermMatcher for LexicalRegexMatcher {
+    type Term = SimpleTerm<'static>; // not used
+
+    fn matches<T2: Term + ?Sized>(&self, term: &T2) -> bool {
+        match term.lexical_form() {
+            Some(lex) => self.0.is_match(&lex),
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,188 @@
+/// Build a tuple of matchers from a concise pattern syntax,
+/// to be passed to [`Graph::triples_matching`](crate::graph::Graph::triples_matching)
+/// or [`Dataset::quads_matching`](crate::dataset::Dataset::quads_matching).
+///
+/// The macro accepts three (subject, predicate, object) or four (plus graph name) comma-separated items,
+/// each of which can be:
+/// - `?name` or `_`, matching any term (the name is only meant for readability);
+/// - `"lex"@tag`, matching a language-tagged string;
+/// - `"lex"^^datatype`, matching a typed literal;
+/// - `[t1, t2, ...]`, matching any of the listed terms (or graph names);
+/// - `{ matcher }`, where `matcher` is any [`TermMatcher`](crate::term::matcher::TermMatcher)
+///   (or [`GraphNameMatcher`](crate::term::matcher::GraphNameMatcher) in the fourth position);
+/// - any other expression, evaluating to a [`Term`](crate::term::Term) that must be matched exactly.
+///
+/// NB: in the fourth position, a term matches the corresponding named graph
+/// (use `{[None]}` or similar to match the default graph).
+///
+/// # Example
+/// ```
+/// # use sophia_api::graph::Graph;
+/// # use sophia_api::ns::{rdf, rdfs, xsd};
+/// # use sophia_api::term::{IriRef, LanguageTag, SimpleTerm, Term, TermKind};
+/// # use sophia_api::tmatch;
+/// let alice = IriRef::new_unchecked("http://example.org/alice");
+/// let graph: Vec<[SimpleTerm; 3]> = vec![
+///     [alice.into_term(), rdfs::label.into_term(), "Alice" * LanguageTag::new_unchecked("en")],
+///     [alice.into_term(), rdf::value.into_term(), "42" * xsd::integer],
+/// ];
+///
+/// let (s, p, o) = tmatch!(?s, rdfs::label, "Alice"@en);
+/// assert_eq!(graph.triples_matching(s, p, o).count(), 1);
+///
+/// let (s, p, o) = tmatch!(alice, [rdf::value, rdfs::comment], "42"^^xsd::integer);
+/// assert_eq!(graph.triples_matching(s, p, o).count(), 1);
+///
+/// let (s, p, o) = tmatch!(_, ?p, {TermKind::Literal});
+/// assert_eq!(graph.triples_matching(s, p, o).count(), 2);
+/// ```
+#[macro_export]
+macro_rules! tmatch {
+    // each item is parsed into a token group: (any), (term ...), (terms ...) or (raw ...)
+    (@parse [$($acc:tt)*] ? $var:ident , $($rest:tt)*) => {
+        $crate::tmatch!(@parse [$($acc)* (any)] $($rest)*)
+    };
+    (@parse [$($acc:tt)*] _ , $($rest:tt)*) => {
+        $crate::tmatch!(@parse [$($acc)* (any)] $($rest)*)
+    };
+    (@parse [$($acc:tt)*] $lex:literal @ $tag:ident $(- $sub:tt)* , $($rest:tt)*) => {
+        $crate::tmatch!(@parse [$($acc)* (term
+            $lex * $crate::term::LanguageTag::new_unchecked(
+                concat!(stringify!($tag) $(, "-", stringify!($sub))*)
+            )
+        )] $($rest)*)
+    };
+    (@parse [$($acc:tt)*] $lex:literal ^ ^ $dt:path , $($rest:tt)*) => {
+        $crate::tmatch!(@parse [$($acc)* (term $lex * $dt)] $($rest)*)
+    };
+    (@parse [$($acc:tt)*] { $matcher:expr } , $($rest:tt)*) => {
+        $crate::tmatch!(@parse [$($acc)* (raw $matcher)] $($rest)*)
+    };
+    (@parse [$($acc:tt)*] [ $($terms:tt)* ] , $($rest:tt)*) => {
+        $crate::tmatch!(@parse [$($acc)* (terms $($terms)*)] $($rest)*)
+    };
+    (@parse [$($acc:tt)*] $term:expr , $($rest:tt)*) => {
+        $crate::tmatch!(@parse [$($acc)* (term $term)] $($rest)*)
+    };
+    // allow a trailing comma
+    (@parse [$($acc:tt)*] ,) => {
+        $crate::tmatch!(@parse [$($acc)*])
+    };
+    (@parse [$s:tt $p:tt $o:tt]) => {
+        (
+            $crate::tmatch!(@term $s),
+            $crate::tmatch!(@term $p),
+            $crate::tmatch!(@term $o),
+        )
+    };
+    (@parse [$s:tt $p:tt $o:tt $g:tt]) => {
+        (
+            $crate::tmatch!(@term $s),
+            $crate::tmatch!(@term $p),
+            $crate::tmatch!(@term $o),
+            $crate::tmatch!(@graph $g),
+        )
+    };
+    (@term (any)) => { $crate::term::matcher::Any };
+    (@term (term $term:expr)) => { [$term] };
+    (@term (terms $($terms:tt)*)) => { [$($terms)*] };
+    (@term (raw $matcher:expr)) => { $matcher };
+    (@graph (any)) => { $crate::term::matcher::Any };
+    (@graph (term $term:expr)) => { $crate::term::matcher::TermMatcher::gn([$term]) };
+    (@graph (terms $($terms:tt)*)) => { [$($terms)*] };
+    (@graph (raw $matcher:expr)) => { $matcher };
+    ($($pattern:tt)*) => {
+        $crate::tmatch!(@parse [] $($pattern)* ,)
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use crate::graph::Graph;
+    use crate::ns::{rdf, rdfs, xsd};
+    use crate::term::matcher::{Any, Not};
+    use crate::term::{LanguageTag, SimpleTerm, Term, TermKind};
+
+    type MyGraph = Vec<[SimpleTerm<'static>; 3]>;
+
+    fn make_graph() -> MyGraph {
+        let en = LanguageTag::new_unchecked("en");
+        let en_gb = LanguageTag::new_unchecked("en-GB");
+        vec![
+            [rdf::Alt.into_term(), rdfs::label.into_term(), "alt" * en],
+            [
+                rdf::Alt.into_term(),
+                rdfs::label.into_term(),
+                "alternative" * en_gb,
+            ],
+            [rdf::Bag.into_term(), rdfs::label.into_term(), "bag" * en],
+            [
+                rdf::Bag.into_term(),
+                rdf::value.into_term(),
+                "42" * xsd::integer,
+            ],
+            [
+                rdf::Seq.into_term(),
+                rdf::type_.into_term(),
+                rdfs::Class.into_term(),
+            ],
+        ]
+    }
+
+    fn count<S, P, O>(g: &MyGraph, (s, p, o): (S, P, O)) -> usize
+    where
+        S: crate::term::matcher::TermMatcher,
+        P: crate::term::matcher::TermMatcher,
+        O: crate::term::matcher::TermMatcher,
+    {
+        g.triples_matching(s, p, o).count()
+    }
+
+    #[test]
+    fn variables() {
+        let g = make_graph();
+        assert_eq!(count(&g, tmatch!(?s, ?p, ?o)), 5);
+        assert_eq!(count(&g, tmatch!(_, _, _,)), 5);
+        assert_eq!(count(&g, tmatch!(rdf::Alt, ?p, _)), 2);
+    }
+
+    #[test]
+    fn literals() {
+        let g = make_graph();
+        assert_eq!(count(&g, tmatch!(?s, rdfs::label, "alt"@en)), 1);
+        assert_eq!(count(&g, tmatch!(?s, rdfs::label, "alternative"@en-GB)), 1);
+        assert_eq!(count(&g, tmatch!(?s, rdfs::label, "alternative"@en)), 0);
+        assert_eq!(count(&g, tmatch!(?s, ?p, "42"^^xsd::integer)), 1);
+        assert_eq!(count(&g, tmatch!(?s, ?p, "42"^^xsd::string)), 0);
+        assert_eq!(count(&g, tmatch!(?s, ?p, "42")), 0);
+    }
+
+    #[test]
+    fn lists_and_matchers() {
+        let g = make_graph();
+        assert_eq!(count(&g, tmatch!([rdf::Alt, rdf::Seq], _, _)), 3);
+        assert_eq!(count(&g, tmatch!(_, _, { TermKind::Iri })), 1);
+        assert_eq!(count(&g, tmatch!(_, _, { Any * xsd::integer })), 1);
+        let p = rdf::type_;
+        assert_eq!(count(&g, tmatch!(_, p, { Not(TermKind::Literal) })), 1);
+    }
+
+    #[test]
+    fn graph_names() {
+        use crate::dataset::Dataset;
+        use crate::quad::Spog;
+        use std::collections::BTreeSet;
+
+        let d: BTreeSet<Spog<SimpleTerm<'static>>> = make_graph()
+            .into_iter()
+            .enumerate()
+            .map(|(i, t)| (t, (i % 2 == 0).then(|| rdf::Alt.into_term())))
+            .collect();
+        let (s, p, o, g) = tmatch!(_, _, _, rdf::Alt);
+        assert_eq!(d.quads_matching(s, p, o, g).count(), 3);
+        let (s, p, o, g) = tmatch!(_, _, _, { [None as Option<SimpleTerm>] });
+        assert_eq!(d.quads_matching(s, p, o, g).count(), 2);
+        let (s, p, o, g) = tmatch!(?s, rdfs::label, _, ?g);
+        assert_eq!(d.quads_matching(s, p, o, g).count(), 3);
+    }
+}
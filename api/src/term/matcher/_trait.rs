@@ -17,6 +17,16 @@ pub trait TermMatcher {
         None
     }
 
+    /// Return `None`, unless this matcher only matches literals with a given language tag,
+    /// in which case this method may return that tag.
+    ///
+    /// This method is provided for optimization purposes
+    /// (e.g. by graphs maintaining an index of language-tagged literals),
+    /// so implementing it is optional.
+    fn language_tag(&self) -> Option<LanguageTag<&str>> {
+        None
+    }
+
     /// Converts this [`TermMatcher`] into a [`GraphNameMatcher`]
     ///
     /// If you only want to borrow this matcher as a [`GraphNameMatcher`],
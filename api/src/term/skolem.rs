@@ -0,0 +1,193 @@
+//! I provide support for [skolemization],
+//! i.e. the replacement of blank nodes by globally unique IRIs (so-called skolem IRIs),
+//! as well as the reverse operation.
+//!
+//! This is typically required before publishing data,
+//! as blank nodes can not be referred to from outside the graph or dataset containing them.
+//! See also [`Graph::skolemize`](crate::graph::Graph::skolemize)
+//! and [`Dataset::skolemize`](crate::dataset::Dataset::skolemize).
+//!
+//! # Example
+//! ```
+//! # use sophia_api::graph::Graph;
+//! # use sophia_api::ns::rdf;
+//! # use sophia_api::source::TripleSource;
+//! # use sophia_api::term::{BnodeId, SimpleTerm, Term};
+//! # use sophia_iri::Iri;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let graph: Vec<[SimpleTerm; 3]> = vec![[BnodeId::new_unchecked("b1").into_term(), rdf::type_.into_term(), rdf::Bag.into_term()]];
+//! let base = Iri::new("https://example.org/")?;
+//!
+//! let skolemized: Vec<[SimpleTerm; 3]> = graph.skolemize(&base).collect_triples()?;
+//! assert_eq!(
+//!     skolemized[0][0].iri().unwrap().as_str(),
+//!     "https://example.org/.well-known/genid/b1",
+//! );
+//!
+//! let deskolemized: Vec<[SimpleTerm; 3]> = skolemized.deskolemize(&base).collect_triples()?;
+//! assert!(Term::eq(&deskolemized[0][0], BnodeId::new_unchecked("b1")));
+//! # Ok(()) }
+//! ```
+//!
+//! [skolemization]: https://www.w3.org/TR/rdf11-concepts/#section-skolemization
+use super::{BnodeId, IriRef, SimpleTerm, Term, TermKind};
+use mownstr::MownStr;
+use sophia_iri::Iri;
+use std::borrow::Borrow;
+
+/// Maps blank nodes to skolem IRIs of the form `{base}/.well-known/genid/{bnode_id}`, and back.
+///
+/// The mapping is stateless: the blank node identifier is embedded in the skolem IRI,
+/// so [`deskolemize`](Skolemizer::deskolemize) restores the original blank node labels.
+#[derive(Clone, Debug)]
+pub struct Skolemizer {
+    prefix: Box<str>,
+}
+
+impl Skolemizer {
+    /// Build a new [`Skolemizer`] minting skolem IRIs under `base`
+    /// (typically the root IRI of the publisher's domain, e.g. `https://example.org/`).
+    pub fn new<T: Borrow<str>>(base: &Iri<T>) -> Self {
+        let base = base.as_str().trim_end_matches('/');
+        Skolemizer {
+            prefix: format!("{base}/.well-known/genid/").into(),
+        }
+    }
+
+    /// The prefix of all skolem IRIs minted by this [`Skolemizer`]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Whether `t` is a skolem IRI minted by this [`Skolemizer`].
+    pub fn is_skolem_iri<T: Term>(&self, t: T) -> bool {
+        self.bnode_id_of(t).is_some()
+    }
+
+    /// Copy `t`, replacing blank nodes (possibly inside quoted triples) by skolem IRIs.
+    pub fn skolemize<T: Term>(&self, t: T) -> SimpleTerm<'static> {
+        match t.kind() {
+            TermKind::BlankNode => {
+                let iri = format!("{}{}", self.prefix, t.bnode_id().unwrap().as_str());
+                SimpleTerm::Iri(IriRef::new_unchecked(iri.into()))
+            }
+            TermKind::Triple => {
+                SimpleTerm::Triple(Box::new(t.triple().unwrap().map(|t| self.skolemize(t))))
+            }
+            _ => t.into_term(),
+        }
+    }
+
+    /// Copy `t`, replacing the skolem IRIs minted by this [`Skolemizer`]
+    /// (possibly inside quoted triples) by the corresponding blank nodes.
+    pub fn deskolemize<T: Term>(&self, t: T) -> SimpleTerm<'static> {
+        match t.kind() {
+            TermKind::Iri => match self.bnode_id_of(t.borrow_term()) {
+                Some(bnid) => SimpleTerm::BlankNode(bnid),
+                None => t.into_term(),
+            },
+            TermKind::Triple => {
+                SimpleTerm::Triple(Box::new(t.triple().unwrap().map(|t| self.deskolemize(t))))
+            }
+            _ => t.into_term(),
+        }
+    }
+
+    /// The blank node identifier encoded in `t`, if it is a skolem IRI minted by this [`Skolemizer`].
+    fn bnode_id_of<T: Term>(&self, t: T) -> Option<BnodeId<MownStr<'static>>> {
+        let iri = t.iri()?;
+        let id = iri.as_str().strip_prefix(&*self.prefix)?;
+        BnodeId::new(MownStr::from(id.to_string())).ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dataset::Dataset;
+    use crate::graph::Graph;
+    use crate::ns::rdf;
+    use crate::quad::Spog;
+    use crate::source::{QuadSource, TripleSource};
+    use std::collections::BTreeSet;
+
+    fn base() -> Iri<&'static str> {
+        Iri::new_unchecked("http://example.org")
+    }
+
+    #[test]
+    fn terms() {
+        let sk = Skolemizer::new(&base());
+        assert_eq!(sk.prefix(), "http://example.org/.well-known/genid/");
+        assert_eq!(
+            sk.prefix(),
+            Skolemizer::new(&Iri::new_unchecked("http://example.org/")).prefix()
+        );
+
+        let b1 = BnodeId::new_unchecked("b1");
+        let skolem = sk.skolemize(b1);
+        assert!(Term::eq(
+            &skolem,
+            IriRef::new_unchecked("http://example.org/.well-known/genid/b1")
+        ));
+        assert!(sk.is_skolem_iri(&skolem));
+        assert!(!sk.is_skolem_iri(rdf::type_));
+        assert!(!sk.is_skolem_iri(b1));
+        assert!(Term::eq(&sk.deskolemize(&skolem), b1));
+
+        // other terms are left unchanged
+        assert!(Term::eq(&sk.skolemize(rdf::type_), rdf::type_));
+        assert!(Term::eq(&sk.skolemize("b1"), "b1"));
+        assert!(Term::eq(&sk.deskolemize(rdf::type_), rdf::type_));
+        // IRIs with a suffix that is not a valid blank node identifier are left unchanged
+        let not_bnode = IriRef::new_unchecked("http://example.org/.well-known/genid/a/b");
+        assert!(Term::eq(&sk.deskolemize(not_bnode), not_bnode));
+    }
+
+    #[test]
+    fn quoted_triples() {
+        let sk = Skolemizer::new(&base());
+        let b1 = BnodeId::new_unchecked("b1");
+        let quoted = SimpleTerm::Triple(Box::new([
+            b1.into_term(),
+            rdf::type_.into_term(),
+            rdf::Bag.into_term(),
+        ]));
+        let skolem = sk.skolemize(&quoted);
+        assert!(sk.is_skolem_iri(skolem.triple().unwrap()[0]));
+        assert!(Term::eq(&sk.deskolemize(&skolem), &quoted));
+    }
+
+    #[test]
+    fn graph() -> Result<(), Box<dyn std::error::Error>> {
+        let b1 = BnodeId::new_unchecked("b1");
+        let b2 = BnodeId::new_unchecked("b2");
+        let g: Vec<[SimpleTerm; 3]> = vec![
+            [b1.into_term(), rdf::value.into_term(), b2.into_term()],
+            [b2.into_term(), rdf::type_.into_term(), rdf::Bag.into_term()],
+        ];
+        let skolemized: Vec<[SimpleTerm; 3]> = g.skolemize(&base()).collect_triples()?;
+        assert_eq!(skolemized.blank_nodes().count(), 0);
+        // the same blank node is consistently replaced by the same IRI
+        assert!(Term::eq(&skolemized[0][2], &skolemized[1][0]));
+        let deskolemized: Vec<[SimpleTerm; 3]> =
+            skolemized.deskolemize(&base()).collect_triples()?;
+        assert_eq!(deskolemized, g);
+        Ok(())
+    }
+
+    #[test]
+    fn dataset() -> Result<(), Box<dyn std::error::Error>> {
+        let b1 = BnodeId::new_unchecked("b1");
+        let d: BTreeSet<Spog<SimpleTerm>> = BTreeSet::from([(
+            [b1.into_term(), rdf::value.into_term(), b1.into_term()],
+            Some(b1.into_term()),
+        )]);
+        let skolemized: BTreeSet<Spog<SimpleTerm>> = d.skolemize(&base()).collect_quads()?;
+        assert_eq!(skolemized.blank_nodes().count(), 0);
+        let deskolemized: BTreeSet<Spog<SimpleTerm>> =
+            skolemized.deskolemize(&base()).collect_quads()?;
+        assert_eq!(deskolemized, d);
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+//! I define [`UnicodePolicy`],
+//! controlling how the lexical forms of literals are normalized and checked.
+//!
+//! Without normalization,
+//! two literals that are displayed identically (e.g. `"é"` written with a single code point,
+//! or as `e` followed by a combining acute accent)
+//! are nonetheless different terms.
+//! Applying the same [`UnicodePolicy`] to all the data prevents this.
+//!
+//! NB: parsers do not apply any policy themselves;
+//! a policy is applied to their output by wrapping the [source](crate::source) they return,
+//! e.g. `parser.parse_str(txt).normalize_triples(policy)`.
+//!
+//! See also
+//! [`TripleSource::normalize_triples`](crate::source::TripleSource::normalize_triples),
+//! [`QuadSource::normalize_quads`](crate::source::QuadSource::normalize_quads)
+//! and [`UnicodeGraph`](crate::graph::adapter::UnicodeGraph).
+use super::{SimpleTerm, Term};
+use crate::quad::{Quad, Spog};
+use crate::triple::Triple;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::error::Error;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+lazy_static! {
+    static ref UNASSIGNED: Regex = Regex::new(r"\p{Cn}").unwrap();
+}
+
+/// A policy controlling how the lexical forms of literals are normalized and checked.
+///
+/// IRIs, blank nodes and variables are never affected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnicodePolicy {
+    nfc: bool,
+    reject_unassigned: bool,
+}
+
+impl UnicodePolicy {
+    /// Should lexical forms be normalized to [Unicode Normalization Form C](https://unicode.org/reports/tr15/).
+    ///
+    /// Default: `true`
+    pub fn nfc(&self) -> bool {
+        self.nfc
+    }
+
+    /// Should lexical forms containing unassigned code points be rejected.
+    ///
+    /// Default: `false`
+    pub fn reject_unassigned(&self) -> bool {
+        self.reject_unassigned
+    }
+
+    /// Build a default policy (see individual methods for default values)
+    pub fn new() -> Self {
+        UnicodePolicy {
+            nfc: true,
+            reject_unassigned: false,
+        }
+    }
+
+    /// Transform a policy with the given value for [`nfc`](UnicodePolicy::nfc)
+    pub fn with_nfc(mut self, b: bool) -> Self {
+        self.nfc = b;
+        self
+    }
+
+    /// Transform a policy with the given value for [`reject_unassigned`](UnicodePolicy::reject_unassigned)
+    pub fn with_reject_unassigned(mut self, b: bool) -> Self {
+        self.reject_unassigned = b;
+        self
+    }
+
+    /// Apply this policy to the given string.
+    ///
+    /// The string is only copied if it needs to be changed.
+    pub fn normalize_str<'a>(&self, txt: &'a str) -> Result<Cow<'a, str>, UnicodeError> {
+        let txt = if self.nfc && !is_nfc(txt) {
+            Cow::Owned(txt.nfc().collect())
+        } else {
+            Cow::Borrowed(txt)
+        };
+        if self.reject_unassigned {
+            if let Some(m) = UNASSIGNED.find(&txt) {
+                return Err(UnicodeError {
+                    code_point: m.as_str().chars().next().unwrap(),
+                    position: m.start(),
+                });
+            }
+        }
+        Ok(txt)
+    }
+
+    /// Apply this policy to the given term
+    /// (and to the terms of the quoted triple it contains, if any).
+    pub fn normalize_term<T: Term>(&self, term: T) -> Result<SimpleTerm<'static>, UnicodeError> {
+        let mut term = term.into_term();
+        self.normalize_in_place(&mut term)?;
+        Ok(term)
+    }
+
+    /// Apply this policy to all the terms of the given triple.
+    pub fn normalize_triple<T: Triple>(
+        &self,
+        triple: T,
+    ) -> Result<[SimpleTerm<'static>; 3], UnicodeError> {
+        let [s, p, o] = triple.to_spo();
+        Ok([
+            self.normalize_term(s)?,
+            self.normalize_term(p)?,
+            self.normalize_term(o)?,
+        ])
+    }
+
+    /// Apply this policy to all the terms of the given quad.
+    pub fn normalize_quad<Q: Quad>(
+        &self,
+        quad: Q,
+    ) -> Result<Spog<SimpleTerm<'static>>, UnicodeError> {
+        let ([s, p, o], g) = quad.to_spog();
+        Ok((
+            [
+                self.normalize_term(s)?,
+                self.normalize_term(p)?,
+                self.normalize_term(o)?,
+            ],
+            g.map(|g| self.normalize_term(g)).transpose()?,
+        ))
+    }
+
+    fn normalize_in_place(&self, term: &mut SimpleTerm<'static>) -> Result<(), UnicodeError> {
+        match term {
+            SimpleTerm::LiteralDatatype(lex, _) | SimpleTerm::LiteralLanguage(lex, _) => {
+                let normalized = match self.normalize_str(lex)? {
+                    Cow::Owned(txt) => Some(txt),
+                    Cow::Borrowed(_) => None,
+                };
+                if let Some(txt) = normalized {
+                    *lex = txt.into();
+                }
+            }
+            SimpleTerm::Triple(spo) => {
+                for t in spo.iter_mut() {
+                    self.normalize_in_place(t)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl Default for UnicodePolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The error raised when a [`UnicodePolicy`] rejects a lexical form.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("Unassigned code point U+{:04X} at byte {position}", u32::from(*.code_point))]
+pub struct UnicodeError {
+    /// The rejected code point
+    pub code_point: char,
+    /// The position (in bytes) of the rejected code point in the (normalized) lexical form
+    pub position: usize,
+}
+
+/// An error raised by a source or a graph enforcing a [`UnicodePolicy`].
+#[derive(Debug, thiserror::Error)]
+pub enum UnicodePolicyError<E: Error> {
+    /// The error was raised by the underlying source or graph
+    #[error(transparent)]
+    Inner(E),
+    /// The policy rejected a lexical form
+    #[error(transparent)]
+    Unicode(#[from] UnicodeError),
+}
+
+// allows UnicodeGraph to support remove_matching and retain_matching
+// when the underlying graph is infallible
+impl<E: Error> From<Infallible> for UnicodePolicyError<E> {
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ns::xsd;
+
+    const COMPOSED: &str = "caf\u{e9}";
+    const DECOMPOSED: &str = "cafe\u{301}";
+
+    #[test]
+    fn normalize_str() {
+        let policy = UnicodePolicy::new();
+        assert!(matches!(
+            policy.normalize_str(COMPOSED),
+            Ok(Cow::Borrowed(_))
+        ));
+        assert_eq!(policy.normalize_str(DECOMPOSED).unwrap(), COMPOSED);
+        let policy = policy.with_nfc(false);
+        assert_eq!(policy.normalize_str(DECOMPOSED).unwrap(), DECOMPOSED);
+    }
+
+    #[test]
+    fn reject_unassigned() {
+        let policy = UnicodePolicy::new().with_reject_unassigned(true);
+        assert!(policy.normalize_str(DECOMPOSED).is_ok());
+        // U+0378 is unassigned
+        let err = policy.normalize_str("ab\u{378}").unwrap_err();
+        assert_eq!(err.code_point, '\u{378}');
+        assert_eq!(err.position, 2);
+        assert_eq!(err.to_string(), "Unassigned code point U+0378 at byte 2");
+        assert!(UnicodePolicy::new().normalize_str("ab\u{378}").is_ok());
+    }
+
+    #[test]
+    fn normalize_term() {
+        let policy = UnicodePolicy::new();
+        let lit = policy.normalize_term(DECOMPOSED * xsd::string).unwrap();
+        assert_eq!(&*lit.lexical_form().unwrap(), COMPOSED);
+        assert!(Term::eq(&lit, COMPOSED));
+        // IRIs are not affected
+        let iri = SimpleTerm::Iri(crate::term::IriRef::new_unchecked(
+            format!("tag:{DECOMPOSED}").into(),
+        ));
+        assert!(Term::eq(&policy.normalize_term(&iri).unwrap(), &iri));
+        // quoted triples are normalized recursively
+        let quoted =
+            SimpleTerm::Triple(Box::new([iri.clone(), iri.clone(), DECOMPOSED.into_term()]));
+        let quoted = policy.normalize_term(quoted).unwrap();
+        assert!(Term::eq(&quoted.triple().unwrap()[2], COMPOSED));
+    }
+}
@@ -0,0 +1,584 @@
+//! I provide support for the [`geo:wktLiteral`](crate::ns::geo::wktLiteral) datatype
+//! of [GeoSPARQL], for simple 2D geometries (points, line strings and polygons).
+//!
+//! * [`WktLiteral`] implements [`LiteralValue`],
+//!   so WKT literals can be parsed with [`Term::value`];
+//! * [`SimpleTerm`] implements [`From`] for [`WktLiteral`] and [`Geometry`],
+//!   building `geo:wktLiteral` literals;
+//! * [`Geometry::bounding_box`] computes the [`BoundingBox`] of a geometry,
+//!   which can be used to filter WKT literals with a
+//!   [`BoundingBoxMatcher`](super::matcher::BoundingBoxMatcher).
+//!
+//! Other geometry types (multi-geometries, collections...)
+//! and coordinates with a Z or M dimension are not supported.
+//!
+//! # Example
+//! ```
+//! # use sophia_api::ns::geo;
+//! # use sophia_api::term::{SimpleTerm, Term};
+//! # use sophia_api::term::wkt::{Geometry, Point, WktLiteral};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let lit = "<http://www.opengis.net/def/crs/OGC/1.3/CRS84> Point(2.35 48.85)" * geo::wktLiteral;
+//! let wkt: WktLiteral = lit.value()?;
+//! assert_eq!(wkt.geometry, Geometry::Point(Point { x: 2.35, y: 48.85 }));
+//! assert!(wkt.is_crs84());
+//!
+//! let line = Geometry::LineString(vec![Point { x: 0.0, y: 0.0 }, Point { x: 1.5, y: 2.0 }]);
+//! let lit = SimpleTerm::from(line);
+//! assert_eq!(lit.lexical_form().unwrap(), "LINESTRING(0 0, 1.5 2)");
+//! # Ok(()) }
+//! ```
+//!
+//! [GeoSPARQL]: https://docs.ogc.org/is/22-047r1/22-047r1.html
+use super::literal_value::{LiteralValue, LiteralValueError};
+use super::{SimpleTerm, Term};
+use crate::ns::geo;
+use sophia_iri::Iri;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// The IRI of the default coordinate reference system of WKT literals
+/// (WGS 84 longitude-latitude).
+pub const CRS84: Iri<&str> =
+    Iri::new_unchecked_const("http://www.opengis.net/def/crs/OGC/1.3/CRS84");
+
+/// A point with two coordinates.
+///
+/// In the default [CRS84] coordinate reference system,
+/// `x` is the longitude and `y` the latitude.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    /// The first coordinate
+    pub x: f64,
+    /// The second coordinate
+    pub y: f64,
+}
+
+/// A simple geometry.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Geometry {
+    /// A single point
+    Point(Point),
+    /// A sequence of (at least two) points, joined by straight lines,
+    /// or an empty line string
+    LineString(Vec<Point>),
+    /// A sequence of closed rings, the first one being the exterior boundary
+    /// and the others (if any) the boundaries of holes,
+    /// or an empty polygon
+    Polygon(Vec<Vec<Point>>),
+}
+
+impl Geometry {
+    /// Whether this geometry is empty (e.g. `LINESTRING EMPTY`).
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Geometry::Point(_) => false,
+            Geometry::LineString(points) => points.is_empty(),
+            Geometry::Polygon(rings) => rings.is_empty(),
+        }
+    }
+
+    /// Iterate over all the points of this geometry.
+    pub fn points(&self) -> impl Iterator<Item = &Point> + '_ {
+        let (single, rings) = match self {
+            Geometry::Point(p) => (std::slice::from_ref(p), &[][..]),
+            Geometry::LineString(points) => (&points[..], &[][..]),
+            Geometry::Polygon(rings) => (&[][..], &rings[..]),
+        };
+        single.iter().chain(rings.iter().flatten())
+    }
+
+    /// The smallest [`BoundingBox`] containing this geometry,
+    /// or `None` if it is empty.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let mut points = self.points();
+        let first = *points.next()?;
+        Some(points.fold(BoundingBox::new(first, first), |bbox, p| {
+            BoundingBox::new(
+                Point {
+                    x: bbox.min.x.min(p.x),
+                    y: bbox.min.y.min(p.y),
+                },
+                Point {
+                    x: bbox.max.x.max(p.x),
+                    y: bbox.max.y.max(p.y),
+                },
+            )
+        }))
+    }
+}
+
+/// Serialize this geometry in WKT (with upper-case keywords and no superfluous whitespace).
+impl fmt::Display for Geometry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Geometry::Point(p) => write!(f, "POINT({} {})", p.x, p.y),
+            Geometry::LineString(points) if points.is_empty() => write!(f, "LINESTRING EMPTY"),
+            Geometry::LineString(points) => {
+                write!(f, "LINESTRING")?;
+                write_points(f, points)
+            }
+            Geometry::Polygon(rings) if rings.is_empty() => write!(f, "POLYGON EMPTY"),
+            Geometry::Polygon(rings) => {
+                write!(f, "POLYGON(")?;
+                for (i, ring) in rings.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write_points(f, ring)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+fn write_points(f: &mut fmt::Formatter<'_>, points: &[Point]) -> fmt::Result {
+    write!(f, "(")?;
+    for (i, p) in points.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{} {}", p.x, p.y)?;
+    }
+    write!(f, ")")
+}
+
+/// Parse a WKT geometry (keywords are case-insensitive).
+impl FromStr for Geometry {
+    type Err = WktError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut scanner = Scanner { txt: s, pos: 0 };
+        scanner.skip_ws();
+        let geometry = scanner.geometry()?;
+        scanner.skip_ws();
+        if scanner.pos < s.len() {
+            return Err(scanner.error("unexpected trailing characters"));
+        }
+        Ok(geometry)
+    }
+}
+
+/// The value of a `geo:wktLiteral`:
+/// a [`Geometry`] and an optional coordinate reference system.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WktLiteral {
+    /// The IRI of the coordinate reference system, if explicitly given
+    /// (otherwise, [CRS84] is assumed)
+    pub crs: Option<Iri<String>>,
+    /// The geometry
+    pub geometry: Geometry,
+}
+
+impl WktLiteral {
+    /// Whether the coordinate reference system of this literal is [CRS84]
+    /// (either explicitly or by default).
+    pub fn is_crs84(&self) -> bool {
+        match &self.crs {
+            None => true,
+            Some(crs) => crs.as_str() == CRS84.as_str(),
+        }
+    }
+}
+
+impl From<Geometry> for WktLiteral {
+    fn from(geometry: Geometry) -> Self {
+        WktLiteral {
+            crs: None,
+            geometry,
+        }
+    }
+}
+
+impl fmt::Display for WktLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(crs) = &self.crs {
+            write!(f, "<{}> ", crs.as_str())?;
+        }
+        self.geometry.fmt(f)
+    }
+}
+
+/// Parse the lexical form of a `geo:wktLiteral`.
+impl FromStr for WktLiteral {
+    type Err = WktError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut scanner = Scanner { txt: s, pos: 0 };
+        scanner.skip_ws();
+        let mut crs = None;
+        if scanner.rest().starts_with('<') {
+            scanner.pos += 1;
+            let start = scanner.pos;
+            let iri = scanner.take_while(|c| c != '>' && !c.is_whitespace());
+            let iri = Iri::new(iri.to_string()).map_err(|_| WktError {
+                offset: start,
+                message: "invalid CRS IRI",
+            })?;
+            if !scanner.rest().starts_with('>') {
+                return Err(scanner.error("expected '>'"));
+            }
+            scanner.pos += 1;
+            scanner.skip_ws();
+            crs = Some(iri);
+        }
+        let geometry = scanner.rest().parse::<Geometry>().map_err(|err| WktError {
+            offset: err.offset + scanner.pos,
+            ..err
+        })?;
+        Ok(WktLiteral { crs, geometry })
+    }
+}
+
+/// Extract values from literals whose datatype is `geo:wktLiteral`.
+impl LiteralValue for WktLiteral {
+    fn from_lexical(lexical_form: &str, datatype: &str) -> Result<Self, LiteralValueError> {
+        if datatype != geo::wktLiteral.iri().unwrap().as_str() {
+            return Err(LiteralValueError::UnsupportedDatatype(datatype.into()));
+        }
+        lexical_form
+            .parse()
+            .map_err(|_| LiteralValueError::invalid(lexical_form, datatype))
+    }
+}
+
+/// Build a `geo:wktLiteral` literal.
+impl From<WktLiteral> for SimpleTerm<'static> {
+    fn from(value: WktLiteral) -> Self {
+        (value.to_string().as_str() * geo::wktLiteral).into_term()
+    }
+}
+
+/// Build a `geo:wktLiteral` literal, without an explicit coordinate reference system.
+impl From<Geometry> for SimpleTerm<'static> {
+    fn from(value: Geometry) -> Self {
+        WktLiteral::from(value).into()
+    }
+}
+
+/// An axis-aligned rectangle, defined by its lower-left and upper-right corners.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingBox {
+    /// The corner with the lowest coordinates
+    pub min: Point,
+    /// The corner with the highest coordinates
+    pub max: Point,
+}
+
+impl BoundingBox {
+    /// Build the bounding box whose opposite corners are `p1` and `p2`.
+    pub fn new(p1: Point, p2: Point) -> Self {
+        BoundingBox {
+            min: Point {
+                x: p1.x.min(p2.x),
+                y: p1.y.min(p2.y),
+            },
+            max: Point {
+                x: p1.x.max(p2.x),
+                y: p1.y.max(p2.y),
+            },
+        }
+    }
+
+    /// Whether `p` is inside this bounding box (or on its boundary).
+    pub fn contains_point(&self, p: &Point) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    /// Whether `other` is entirely inside this bounding box.
+    pub fn contains(&self, other: &BoundingBox) -> bool {
+        self.contains_point(&other.min) && self.contains_point(&other.max)
+    }
+
+    /// Whether this bounding box and `other` have at least one point in common.
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+}
+
+/// Error raised when parsing an invalid (or unsupported) WKT geometry.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[error("Invalid WKT at offset {offset}: {message}")]
+pub struct WktError {
+    /// The offset (in bytes) of the error in the parsed text
+    pub offset: usize,
+    /// A description of the error
+    pub message: &'static str,
+}
+
+/// The geometry types supported by [`Scanner`].
+enum Kind {
+    Point,
+    LineString,
+    Polygon,
+}
+
+struct Scanner<'a> {
+    txt: &'a str,
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn rest(&self) -> &'a str {
+        &self.txt[self.pos..]
+    }
+
+    fn skip_ws(&mut self) {
+        self.take_while(|c| matches!(c, ' ' | '\t' | '\r' | '\n'));
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, predicate: F) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), WktError> {
+        self.skip_ws();
+        if self.rest().starts_with(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(match c {
+                '(' => "expected '('",
+                ')' => "expected ')'",
+                _ => "unexpected character",
+            }))
+        }
+    }
+
+    fn error(&self, message: &'static str) -> WktError {
+        WktError {
+            offset: self.pos,
+            message,
+        }
+    }
+
+    fn geometry(&mut self) -> Result<Geometry, WktError> {
+        let start = self.pos;
+        let keyword = self.take_while(|c| c.is_ascii_alphabetic());
+        let keyword_error = |message| WktError {
+            offset: start,
+            message,
+        };
+        let kind = match keyword.to_ascii_lowercase().as_str() {
+            "point" => Kind::Point,
+            "linestring" => Kind::LineString,
+            "polygon" => Kind::Polygon,
+            "" => return Err(keyword_error("expected a geometry type")),
+            _ => return Err(keyword_error("unsupported geometry type")),
+        };
+        self.skip_ws();
+        let word_start = self.pos;
+        let word = self.take_while(|c| c.is_ascii_alphabetic());
+        if word.eq_ignore_ascii_case("empty") {
+            return match kind {
+                Kind::Point => Err(keyword_error("empty points are not supported")),
+                Kind::LineString => Ok(Geometry::LineString(vec![])),
+                Kind::Polygon => Ok(Geometry::Polygon(vec![])),
+            };
+        } else if !word.is_empty() {
+            return Err(WktError {
+                offset: word_start,
+                message: "Z and M coordinates are not supported",
+            });
+        }
+        match kind {
+            Kind::Point => {
+                self.expect('(')?;
+                let p = self.point()?;
+                self.expect(')')?;
+                Ok(Geometry::Point(p))
+            }
+            Kind::LineString => {
+                let points = self.points()?;
+                if points.len() < 2 {
+                    return Err(keyword_error("a line string needs at least 2 points"));
+                }
+                Ok(Geometry::LineString(points))
+            }
+            Kind::Polygon => {
+                self.expect('(')?;
+                let mut rings = vec![];
+                loop {
+                    self.skip_ws();
+                    let ring_start = self.pos;
+                    let ring = self.points()?;
+                    if ring.len() < 4 || ring.first() != ring.last() {
+                        return Err(WktError {
+                            offset: ring_start,
+                            message: "a polygon ring must be closed and have at least 4 points",
+                        });
+                    }
+                    rings.push(ring);
+                    self.skip_ws();
+                    if !self.rest().starts_with(',') {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                self.expect(')')?;
+                Ok(Geometry::Polygon(rings))
+            }
+        }
+    }
+
+    /// Parse a parenthesized, comma-separated list of points.
+    fn points(&mut self) -> Result<Vec<Point>, WktError> {
+        self.expect('(')?;
+        let mut points = vec![self.point()?];
+        loop {
+            self.skip_ws();
+            if !self.rest().starts_with(',') {
+                break;
+            }
+            self.pos += 1;
+            points.push(self.point()?);
+        }
+        self.expect(')')?;
+        Ok(points)
+    }
+
+    fn point(&mut self) -> Result<Point, WktError> {
+        let x = self.number()?;
+        let y = self.number()?;
+        self.skip_ws();
+        if self
+            .rest()
+            .starts_with(|c: char| c.is_ascii_digit() || "+-.".contains(c))
+        {
+            return Err(self.error("Z and M coordinates are not supported"));
+        }
+        Ok(Point { x, y })
+    }
+
+    fn number(&mut self) -> Result<f64, WktError> {
+        self.skip_ws();
+        let start = self.pos;
+        let txt = self.take_while(|c| c.is_ascii_digit() || "+-.eE".contains(c));
+        match txt.parse() {
+            Ok(n) if !txt.is_empty() => Ok(n),
+            _ => Err(WktError {
+                offset: start,
+                message: "expected a number",
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    fn p(x: f64, y: f64) -> Point {
+        Point { x, y }
+    }
+
+    #[test_case("POINT(1 2)", Geometry::Point(p(1.0, 2.0)))]
+    #[test_case(" point ( -1.5e1  +.5 ) ", Geometry::Point(p(-15.0, 0.5)); "whitespace and case")]
+    #[test_case("LineString(0 0,1 1, 2 0)", Geometry::LineString(vec![p(0.0, 0.0), p(1.0, 1.0), p(2.0, 0.0)]))]
+    #[test_case("LINESTRING EMPTY", Geometry::LineString(vec![]))]
+    #[test_case(
+        "POLYGON((0 0, 4 0, 4 4, 0 0), (1 1, 2 1, 2 2, 1 1))",
+        Geometry::Polygon(vec![
+            vec![p(0.0, 0.0), p(4.0, 0.0), p(4.0, 4.0), p(0.0, 0.0)],
+            vec![p(1.0, 1.0), p(2.0, 1.0), p(2.0, 2.0), p(1.0, 1.0)],
+        ])
+    )]
+    #[test_case("polygon empty", Geometry::Polygon(vec![]))]
+    fn parse_ok(wkt: &str, expected: Geometry) {
+        assert_eq!(wkt.parse::<Geometry>(), Ok(expected));
+    }
+
+    #[test_case("", 0, "expected a geometry type")]
+    #[test_case("CIRCLE(0 0)", 0, "unsupported geometry type")]
+    #[test_case("POINT EMPTY", 0, "empty points are not supported")]
+    #[test_case("POINT Z (1 2 3)", 6, "Z and M coordinates are not supported")]
+    #[test_case("POINT(1 2 3)", 10, "Z and M coordinates are not supported")]
+    #[test_case("POINT(1)", 7, "expected a number")]
+    #[test_case("POINT(1 inf)", 8, "expected a number")]
+    #[test_case("POINT(1 2", 9, "expected ')'")]
+    #[test_case("POINT(1 2) x", 11, "unexpected trailing characters")]
+    #[test_case("LINESTRING(1 2)", 0, "a line string needs at least 2 points")]
+    #[test_case(
+        "POLYGON((0 0, 1 0, 1 1))",
+        8,
+        "a polygon ring must be closed and have at least 4 points"
+    )]
+    #[test_case("POLYGON((0 0, 1 0, 0 0))", 8, "a polygon ring must be closed and have at least 4 points"; "too short")]
+    fn parse_err(wkt: &str, offset: usize, message: &str) {
+        let err = wkt.parse::<Geometry>().unwrap_err();
+        assert_eq!((err.offset, err.message), (offset, message));
+    }
+
+    #[test_case("POINT(1 2)")]
+    #[test_case("POINT(-0.5 100000)")]
+    #[test_case("LINESTRING(0 0, 1.25 -3)")]
+    #[test_case("LINESTRING EMPTY")]
+    #[test_case("POLYGON((0 0, 4 0, 4 4, 0 0), (1 1, 2 1, 2 2, 1 1))")]
+    #[test_case("POLYGON EMPTY")]
+    fn roundtrip(wkt: &str) {
+        assert_eq!(wkt.parse::<Geometry>().unwrap().to_string(), wkt);
+    }
+
+    #[test]
+    fn literal_with_crs() {
+        let epsg = "http://www.opengis.net/def/crs/EPSG/0/4326";
+        let lex = format!(" <{epsg}>\tPOINT(48.85 2.35)");
+        let wkt: WktLiteral = (lex.as_str() * geo::wktLiteral).value().unwrap();
+        assert_eq!(wkt.crs.as_ref().map(|crs| crs.as_str()), Some(epsg));
+        assert!(!wkt.is_crs84());
+        assert_eq!(wkt.geometry, Geometry::Point(p(48.85, 2.35)));
+        let lit = SimpleTerm::from(wkt);
+        assert_eq!(
+            &*lit.lexical_form().unwrap(),
+            format!("<{epsg}> POINT(48.85 2.35)")
+        );
+    }
+
+    #[test_case("<foo> POINT(1 2)", 1, "invalid CRS IRI")]
+    #[test_case("<http://example.org/crs POINT(1 2)", 23, "expected '>'")]
+    #[test_case("<http://example.org/crs> POINT(1 2", 34, "expected ')'")]
+    fn literal_err(lex: &str, offset: usize, message: &str) {
+        let err = lex.parse::<WktLiteral>().unwrap_err();
+        assert_eq!((err.offset, err.message), (offset, message));
+    }
+
+    #[test]
+    fn literal_value_errors() {
+        assert!(matches!(
+            ("POINT(1 2)" * crate::ns::xsd::string).value::<WktLiteral>(),
+            Err(LiteralValueError::UnsupportedDatatype(_))
+        ));
+        assert!(matches!(
+            ("POINT(1)" * geo::wktLiteral).value::<WktLiteral>(),
+            Err(LiteralValueError::InvalidLexicalForm { .. })
+        ));
+    }
+
+    #[test]
+    fn bounding_box() {
+        let g: Geometry = "POLYGON((0 0, 4 -1, 3 5, 0 0))".parse().unwrap();
+        let bbox = g.bounding_box().unwrap();
+        assert_eq!(bbox, BoundingBox::new(p(4.0, 5.0), p(0.0, -1.0)));
+        assert!(bbox.contains_point(&p(4.0, 0.0)));
+        assert!(!bbox.contains_point(&p(4.5, 0.0)));
+        assert!(bbox.contains(&BoundingBox::new(p(1.0, 1.0), p(2.0, 2.0))));
+        assert!(!bbox.contains(&BoundingBox::new(p(1.0, 1.0), p(5.0, 2.0))));
+        assert!(bbox.intersects(&BoundingBox::new(p(1.0, 1.0), p(5.0, 2.0))));
+        assert!(bbox.intersects(&BoundingBox::new(p(4.0, 5.0), p(6.0, 6.0))));
+        assert!(!bbox.intersects(&BoundingBox::new(p(4.1, 5.0), p(6.0, 6.0))));
+        assert_eq!(Geometry::LineString(vec![]).bounding_box(), None);
+        assert_eq!(
+            Geometry::Point(p(1.0, 2.0)).bounding_box(),
+            Some(BoundingBox::new(p(1.0, 2.0), p(1.0, 2.0)))
+        );
+    }
+}
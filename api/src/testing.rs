@@ -0,0 +1,196 @@
+//! I provide [`proptest`] strategies and [`Arbitrary`] implementations
+//! for property-based testing of code using this crate.
+//!
+//! This module requires the `testing` feature.
+//!
+//! Generated terms only contain *strict* RDF terms
+//! (absolute IRIs, blank nodes and literals),
+//! and generated [triples](triple()) only have them in the positions allowed by strict RDF.
+//! IRIs, lexical forms and identifiers are kept short,
+//! but include non-ASCII characters and characters that need escaping in most syntaxes.
+//!
+//! # Example
+//! ```
+//! use proptest::prelude::*;
+//! use sophia_api::term::{SimpleTerm, Term};
+//! use sophia_api::testing::graph;
+//!
+//! proptest! {
+//!     // in a test module, these functions would be annotated with #[test]
+//!     fn terms_are_equal_to_themselves(t: SimpleTerm<'static>) {
+//!         prop_assert!(Term::eq(&t, &t));
+//!     }
+//!
+//!     fn subjects_are_not_literals(g in graph()) {
+//!         for [s, _, _] in &g {
+//!             prop_assert!(!s.is_literal());
+//!         }
+//!     }
+//! }
+//! # terms_are_equal_to_themselves();
+//! # subjects_are_not_literals();
+//! ```
+use crate::term::{BnodeId, IriRef, LanguageTag, SimpleTerm};
+use mownstr::MownStr;
+use proptest::collection::{vec, SizeRange};
+use proptest::prelude::*;
+use proptest::sample::select;
+
+/// A strategy generating absolute IRIs.
+pub fn iri() -> impl Strategy<Value = IriRef<MownStr<'static>>> + Clone {
+    r"(https?://[a-z]{1,8}\.(org|com)/|urn:ex:|tag:ex,2024:)([a-zA-Z0-9_~é-]{1,6}/){0,2}[a-zA-Z0-9_~é.-]{0,6}(#[a-zA-Z0-9_]{0,6})?"
+        .prop_map(|txt| IriRef::new_unchecked(MownStr::from(txt)))
+}
+
+/// A strategy generating blank node identifiers.
+pub fn bnode_id() -> impl Strategy<Value = BnodeId<MownStr<'static>>> + Clone {
+    "[a-zA-Z][a-zA-Z0-9_]{0,7}".prop_map(|txt| BnodeId::new_unchecked(MownStr::from(txt)))
+}
+
+/// A strategy generating language tags.
+pub fn language_tag() -> impl Strategy<Value = LanguageTag<MownStr<'static>>> + Clone {
+    "[a-z]{2,3}(-[A-Z]{2})?".prop_map(|txt| LanguageTag::new_unchecked(MownStr::from(txt)))
+}
+
+/// A strategy generating literals:
+/// simple and language-tagged strings, integers, booleans,
+/// and strings with an arbitrary datatype.
+pub fn literal() -> impl Strategy<Value = SimpleTerm<'static>> + Clone {
+    prop_oneof![
+        lexical_form().prop_map(SimpleTerm::from),
+        (lexical_form(), language_tag())
+            .prop_map(|(lex, tag)| SimpleTerm::LiteralLanguage(lex.into(), tag)),
+        any::<i64>().prop_map(SimpleTerm::from),
+        any::<bool>().prop_map(SimpleTerm::from),
+        (lexical_form(), iri()).prop_map(|(lex, dt)| SimpleTerm::LiteralDatatype(lex.into(), dt)),
+    ]
+}
+
+/// A strategy generating short lexical forms,
+/// including characters that need escaping (quotes, backslashes, line breaks...).
+fn lexical_form() -> impl Strategy<Value = String> + Clone {
+    "(\\PC|[\t\n\r]){0,12}"
+}
+
+/// A strategy generating strict RDF terms (IRIs, blank nodes or literals).
+pub fn term() -> impl Strategy<Value = SimpleTerm<'static>> + Clone {
+    prop_oneof![
+        iri().prop_map(SimpleTerm::Iri),
+        bnode_id().prop_map(SimpleTerm::BlankNode),
+        literal(),
+    ]
+}
+
+/// A strategy generating terms allowed in the subject position
+/// (IRIs or blank nodes).
+pub fn subject() -> impl Strategy<Value = SimpleTerm<'static>> + Clone {
+    prop_oneof![
+        iri().prop_map(SimpleTerm::Iri),
+        bnode_id().prop_map(SimpleTerm::BlankNode),
+    ]
+}
+
+/// A strategy generating strict RDF triples.
+pub fn triple() -> impl Strategy<Value = [SimpleTerm<'static>; 3]> + Clone {
+    (subject(), iri().prop_map(SimpleTerm::Iri), term()).prop_map(|(s, p, o)| [s, p, o])
+}
+
+/// A strategy generating small graphs (up to 16 triples).
+///
+/// See [`graph_with_size`].
+pub fn graph() -> impl Strategy<Value = Vec<[SimpleTerm<'static>; 3]>> {
+    graph_with_size(0..=16)
+}
+
+/// A strategy generating graphs whose number of triples is in `size`.
+///
+/// Triples are built from a small pool of terms,
+/// so that the same nodes (including blank nodes) appear in several triples.
+/// Duplicate triples are removed,
+/// so the actual size of the generated graphs may be lower than requested.
+pub fn graph_with_size(
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = Vec<[SimpleTerm<'static>; 3]>> {
+    let size = size.into();
+    (vec(subject(), 1..=4), vec(iri(), 1..=3), vec(term(), 1..=4)).prop_flat_map(
+        move |(subjects, predicates, objects)| {
+            let objects = objects.into_iter().chain(subjects.iter().cloned());
+            let triple = (
+                select(subjects.clone()),
+                select(predicates).prop_map(SimpleTerm::Iri),
+                select(objects.collect::<Vec<_>>()),
+            )
+                .prop_map(|(s, p, o)| [s, p, o]);
+            vec(triple, size.clone()).prop_map(|mut triples| {
+                triples.sort();
+                triples.dedup();
+                triples
+            })
+        },
+    )
+}
+
+impl Arbitrary for SimpleTerm<'static> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Generate strict RDF terms (see [`term`]).
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        term().boxed()
+    }
+}
+
+impl Arbitrary for BnodeId<MownStr<'static>> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        bnode_id().boxed()
+    }
+}
+
+impl Arbitrary for LanguageTag<MownStr<'static>> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        language_tag().boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::term::Term;
+    use sophia_iri::Iri;
+
+    proptest! {
+        #[test]
+        fn iris_are_valid(i in iri()) {
+            prop_assert!(Iri::new(i.as_str()).is_ok(), "{}", i.as_str());
+        }
+
+        #[test]
+        fn bnode_ids_are_valid(id: BnodeId<MownStr<'static>>) {
+            prop_assert!(BnodeId::new(id.as_str()).is_ok(), "{}", id.as_str());
+        }
+
+        #[test]
+        fn language_tags_are_valid(tag: LanguageTag<MownStr<'static>>) {
+            prop_assert!(LanguageTag::new(tag.as_str()).is_ok(), "{}", tag.as_str());
+        }
+
+        #[test]
+        fn triples_are_strict([s, p, o] in triple()) {
+            prop_assert!(s.is_iri() || s.is_blank_node());
+            prop_assert!(p.is_iri());
+            prop_assert!(!o.is_triple() && !o.is_variable());
+        }
+
+        #[test]
+        fn graphs_have_no_duplicate(g in graph_with_size(0..32)) {
+            prop_assert!(g.len() < 32);
+            prop_assert!(g.windows(2).all(|w| w[0] < w[1]));
+        }
+    }
+}
@@ -241,9 +241,9 @@ mod test_triple {
     use crate::term::SimpleTerm;
     use sophia_iri::IriRef;
 
-    const S: IriRef<&str> = IriRef::new_unchecked_const("tag:s");
-    const P: IriRef<&str> = IriRef::new_unchecked_const("tag:o");
-    const O: IriRef<&str> = IriRef::new_unchecked_const("tag:p");
+    const S: IriRef<&str> = IriRef::new_const("tag:s");
+    const P: IriRef<&str> = IriRef::new_const("tag:o");
+    const O: IriRef<&str> = IriRef::new_const("tag:p");
 
     #[test]
     fn triple_matched_by() {
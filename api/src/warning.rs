@@ -0,0 +1,146 @@
+//! A channel for recoverable issues encountered by parsers and serializers.
+//!
+//! Some issues are not worth aborting a whole pipeline,
+//! but still tell something about the quality of the data:
+//! a relative IRI resolved against a default base,
+//! a deprecated or non-standard syntax,
+//! some information lost by a serializer...
+//! Parsers and serializers report them as [`Warning`]s to a [`WarningSink`],
+//! distinct from the errors that make them fail.
+//!
+//! Any `FnMut(Warning)` closure is a [`WarningSink`],
+//! and so is a `Vec<Warning>` (which collects all warnings).
+//! [`IgnoreWarnings`] is the default sink for components that support warnings.
+//!
+//! # Example
+//! ```
+//! # use sophia_api::warning::{Warning, WarningKind, WarningSink};
+//! let mut collected: Vec<Warning> = vec![];
+//! collected.warn(Warning::new(WarningKind::RelativeIri, "<foo> written as is"));
+//!
+//! let mut count = 0;
+//! let mut counter = |_: Warning| count += 1;
+//! counter.warn(Warning::new(WarningKind::Lossy, "comment dropped"));
+//!
+//! assert_eq!(collected[0].kind, WarningKind::RelativeIri);
+//! assert_eq!(count, 1);
+//! ```
+use crate::parser::encoding::EncodingWarning;
+use std::fmt;
+
+/// A recoverable issue encountered by a parser or a serializer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warning {
+    /// The kind of issue
+    pub kind: WarningKind,
+    /// A human-readable description of the issue
+    pub message: String,
+}
+
+impl Warning {
+    /// Build a new [`Warning`].
+    pub fn new<M: Into<String>>(kind: WarningKind, message: M) -> Self {
+        Warning {
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.kind, self.message)
+    }
+}
+
+impl From<EncodingWarning> for Warning {
+    fn from(value: EncodingWarning) -> Self {
+        let message = match value {
+            EncodingWarning::BomSkipped => "byte-order mark skipped".to_string(),
+            EncodingWarning::Latin1Byte { offset, byte } => {
+                format!("byte {byte:#04X} at offset {offset} transcoded from Latin-1")
+            }
+        };
+        Warning::new(WarningKind::Encoding, message)
+    }
+}
+
+/// The different kinds of [`Warning`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WarningKind {
+    /// A relative IRI reference was encountered where an absolute IRI is expected,
+    /// and was resolved against a default base IRI, or written as is.
+    RelativeIri,
+    /// A deprecated syntax was accepted.
+    DeprecatedSyntax,
+    /// Something outside the standard syntax (e.g. a variable) was accepted or produced.
+    NonStandard,
+    /// Some information could not be represented, and was lost or replaced by a fallback.
+    Lossy,
+    /// The encoding of the input was fixed (see [`EncodingWarning`]).
+    Encoding,
+}
+
+/// A receiver of [`Warning`]s.
+pub trait WarningSink {
+    /// Receive a [`Warning`].
+    fn warn(&mut self, warning: Warning);
+}
+
+impl<F: FnMut(Warning)> WarningSink for F {
+    fn warn(&mut self, warning: Warning) {
+        self(warning)
+    }
+}
+
+impl WarningSink for Vec<Warning> {
+    fn warn(&mut self, warning: Warning) {
+        self.push(warning)
+    }
+}
+
+impl WarningSink for &mut Vec<Warning> {
+    fn warn(&mut self, warning: Warning) {
+        self.push(warning)
+    }
+}
+
+/// A [`WarningSink`] discarding all warnings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IgnoreWarnings;
+
+impl WarningSink for IgnoreWarnings {
+    fn warn(&mut self, _: Warning) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_encoding_warning() {
+        let w = Warning::from(EncodingWarning::Latin1Byte {
+            offset: 11,
+            byte: 0xE9,
+        });
+        assert_eq!(w.kind, WarningKind::Encoding);
+        assert_eq!(
+            w.to_string(),
+            "Encoding: byte 0xE9 at offset 11 transcoded from Latin-1"
+        );
+    }
+
+    fn emit<S: WarningSink>(mut sink: S, kind: WarningKind) {
+        sink.warn(Warning::new(kind, "test"));
+    }
+
+    #[test]
+    fn sinks() {
+        let mut collected = vec![];
+        emit(&mut collected, WarningKind::NonStandard);
+        emit(|w: Warning| collected.push(w), WarningKind::Lossy);
+        emit(IgnoreWarnings, WarningKind::DeprecatedSyntax);
+        let kinds: Vec<_> = collected.iter().map(|w| w.kind).collect();
+        assert_eq!(kinds, [WarningKind::NonStandard, WarningKind::Lossy]);
+    }
+}
@@ -0,0 +1,271 @@
+//! Experimental replicated graph, based on an [OR-Set] CRDT.
+//!
+//! Each replica of an [`OrSetGraph`] can be modified independently,
+//! without any coordination with the other replicas.
+//! Replicas converge to the same set of triples once they have [merged](OrSetGraph::merge)
+//! each other's state, whatever the order in which merges happen.
+//!
+//! When a triple is concurrently inserted in one replica and removed from another,
+//! the insertion wins (it is the semantics of an Observed-Remove Set).
+//!
+//! [OR-Set]: https://en.wikipedia.org/wiki/Conflict-free_replicated_data_type#OR-Set_(Observed-Remove_Set)
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::Infallible;
+
+use sophia_api::graph::{GResult, MgResult, SetGraph};
+use sophia_api::prelude::*;
+use sophia_api::term::SimpleTerm;
+
+/// The identifier of a replica of an [`OrSetGraph`].
+///
+/// Every replica must have a distinct identifier.
+pub type ReplicaId = u64;
+
+/// A tag uniquely identifying an insertion in an [`OrSetGraph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tag {
+    /// The replica where the insertion happened
+    pub replica: ReplicaId,
+    /// The sequence number of the insertion in that replica
+    pub counter: u64,
+}
+
+type OwnedTriple = [SimpleTerm<'static>; 3];
+
+/// A replicated graph, where each triple is an element of an OR-Set.
+///
+/// Each insertion of a triple is identified by a unique [`Tag`];
+/// removing a triple turns all the tags observed for that triple into tombstones.
+/// A triple is in the graph as long as one of its tags is not a tombstone.
+///
+/// NB: tombstones are never discarded,
+/// so the state of the graph grows with the number of removals.
+#[derive(Clone, Debug)]
+pub struct OrSetGraph {
+    replica: ReplicaId,
+    counter: u64,
+    adds: BTreeMap<OwnedTriple, BTreeSet<Tag>>,
+    tombstones: BTreeSet<Tag>,
+}
+
+impl OrSetGraph {
+    /// Construct an empty replica with the given identifier.
+    pub fn new(replica: ReplicaId) -> Self {
+        Self {
+            replica,
+            counter: 0,
+            adds: BTreeMap::new(),
+            tombstones: BTreeSet::new(),
+        }
+    }
+
+    /// The identifier of this replica.
+    pub fn replica(&self) -> ReplicaId {
+        self.replica
+    }
+
+    /// The number of triples in this graph.
+    pub fn len(&self) -> usize {
+        self.adds.len()
+    }
+
+    /// Whether this graph contains no triple.
+    pub fn is_empty(&self) -> bool {
+        self.adds.is_empty()
+    }
+
+    /// The live tags of the given triple (empty if the triple is not in this graph).
+    pub fn tags<TS, TP, TO>(&self, s: TS, p: TP, o: TO) -> impl Iterator<Item = Tag> + '_
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        self.adds
+            .get(&[s.into_term(), p.into_term(), o.into_term()])
+            .into_iter()
+            .flat_map(|tags| tags.iter().copied())
+    }
+
+    /// Merge the state of `other` into this replica.
+    ///
+    /// Merging is commutative, associative and idempotent.
+    ///
+    /// Return `true` iff the set of triples of this graph was changed.
+    pub fn merge(&mut self, other: &OrSetGraph) -> bool {
+        let before = self.adds.len();
+        let mut changed = false;
+        self.tombstones.extend(other.tombstones.iter().copied());
+        self.adds.retain(|_, tags| {
+            tags.retain(|tag| !other.tombstones.contains(tag));
+            !tags.is_empty()
+        });
+        for (triple, tags) in &other.adds {
+            let mut live = tags
+                .iter()
+                .filter(|tag| !self.tombstones.contains(tag))
+                .copied()
+                .peekable();
+            if live.peek().is_some() {
+                let entry = self.adds.entry(triple.clone()).or_default();
+                changed |= entry.is_empty();
+                entry.extend(live);
+            }
+        }
+        // tags created by this replica in a previous incarnation must not be reused
+        self.counter = self
+            .adds
+            .values()
+            .flatten()
+            .chain(self.tombstones.iter())
+            .filter(|tag| tag.replica == self.replica)
+            .map(|tag| tag.counter)
+            .fold(self.counter, u64::max);
+        changed || self.adds.len() != before
+    }
+}
+
+impl Graph for OrSetGraph {
+    type Triple<'x> = [&'x SimpleTerm<'static>; 3] where Self: 'x;
+    type Error = Infallible;
+
+    fn triples(&self) -> impl Iterator<Item = GResult<Self, Self::Triple<'_>>> + '_ {
+        self.adds.keys().map(|t| Ok(t.each_ref()))
+    }
+}
+
+impl MutableGraph for OrSetGraph {
+    type MutationError = Infallible;
+
+    fn insert<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> MgResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let triple = [s.into_term(), p.into_term(), o.into_term()];
+        if self.adds.contains_key(&triple) {
+            return Ok(false);
+        }
+        self.counter += 1;
+        let tag = Tag {
+            replica: self.replica,
+            counter: self.counter,
+        };
+        self.adds.insert(triple, BTreeSet::from([tag]));
+        Ok(true)
+    }
+
+    fn remove<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> MgResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        match self
+            .adds
+            .remove(&[s.into_term(), p.into_term(), o.into_term()])
+        {
+            Some(tags) => {
+                self.tombstones.extend(tags);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+impl SetGraph for OrSetGraph {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::ns::rdf;
+
+    #[test]
+    fn insert_remove() -> Result<(), Box<dyn std::error::Error>> {
+        let mut g = OrSetGraph::new(1);
+        assert!(g.insert(rdf::Alt, rdf::type_, rdf::Bag)?);
+        assert!(!g.insert(rdf::Alt, rdf::type_, rdf::Bag)?);
+        assert!(g.insert(rdf::Seq, rdf::value, "foo")?);
+        assert_eq!(g.len(), 2);
+        assert!(g.contains(rdf::Seq, rdf::value, "foo")?);
+        assert!(g.remove(rdf::Seq, rdf::value, "foo")?);
+        assert!(!g.remove(rdf::Seq, rdf::value, "foo")?);
+        assert_eq!(g.triples().count(), 1);
+        assert!(!g.contains(rdf::Seq, rdf::value, "foo")?);
+        Ok(())
+    }
+
+    #[test]
+    fn concurrent_insert_wins() -> Result<(), Box<dyn std::error::Error>> {
+        let mut g1 = OrSetGraph::new(1);
+        g1.insert(rdf::Alt, rdf::type_, rdf::Bag)?;
+        let mut g2 = OrSetGraph::new(2);
+        assert!(g2.merge(&g1));
+
+        // concurrently: g1 removes the triple, g2 removes then re-inserts it
+        g1.remove(rdf::Alt, rdf::type_, rdf::Bag)?;
+        g2.remove(rdf::Alt, rdf::type_, rdf::Bag)?;
+        g2.insert(rdf::Alt, rdf::type_, rdf::Bag)?;
+
+        let mut m12 = g1.clone();
+        m12.merge(&g2);
+        let mut m21 = g2.clone();
+        m21.merge(&g1);
+        for g in [&m12, &m21] {
+            assert_eq!(g.len(), 1);
+            assert_eq!(
+                g.tags(rdf::Alt, rdf::type_, rdf::Bag).collect::<Vec<_>>(),
+                vec![Tag {
+                    replica: 2,
+                    counter: 1
+                }]
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn merge_converges() -> Result<(), Box<dyn std::error::Error>> {
+        let mut g1 = OrSetGraph::new(1);
+        let mut g2 = OrSetGraph::new(2);
+        g1.insert(rdf::Alt, rdf::type_, rdf::Bag)?;
+        g1.insert(rdf::Seq, rdf::value, "foo")?;
+        g2.insert(rdf::Seq, rdf::value, "foo")?;
+        g2.insert(rdf::Seq, rdf::value, "bar")?;
+        g2.merge(&g1);
+        g2.remove(rdf::Seq, rdf::value, "foo")?;
+
+        assert!(g1.merge(&g2));
+        assert!(!g1.merge(&g2)); // idempotent
+        assert!(!g2.merge(&g1));
+        for g in [&g1, &g2] {
+            assert_eq!(g.len(), 2);
+            assert!(g.contains(rdf::Alt, rdf::type_, rdf::Bag)?);
+            assert!(g.contains(rdf::Seq, rdf::value, "bar")?);
+            assert!(!g.contains(rdf::Seq, rdf::value, "foo")?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn merge_bumps_counter() -> Result<(), Box<dyn std::error::Error>> {
+        let mut g1 = OrSetGraph::new(1);
+        g1.insert(rdf::Alt, rdf::type_, rdf::Bag)?;
+        g1.insert(rdf::Seq, rdf::value, "foo")?;
+        // a fresh incarnation of replica 1, recovering its state from elsewhere
+        let mut g1bis = OrSetGraph::new(1);
+        g1bis.merge(&g1);
+        g1bis.insert(rdf::Seq, rdf::value, "bar")?;
+        assert_eq!(
+            g1bis
+                .tags(rdf::Seq, rdf::value, "bar")
+                .next()
+                .unwrap()
+                .counter,
+            3
+        );
+        Ok(())
+    }
+}
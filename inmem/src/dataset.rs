@@ -9,6 +9,8 @@ use sophia_api::term::GraphName;
 
 use crate::index::*;
 
+mod _digest;
+use _digest::*;
 mod _iter;
 use _iter::*;
 
@@ -18,6 +20,7 @@ use _iter::*;
 pub struct GenericLightDataset<TI: TermIndex> {
     terms: TI,
     quads: BTreeSet<[TI::Index; 4]>,
+    digests: Option<GraphDigests<TI>>,
 }
 
 impl<TI: GraphNameIndex + Default> GenericLightDataset<TI> {
@@ -26,8 +29,65 @@ impl<TI: GraphNameIndex + Default> GenericLightDataset<TI> {
         Self {
             terms: TI::default(),
             quads: BTreeSet::new(),
+            digests: None,
         }
     }
+
+    /// Rebuild the term index of this dataset,
+    /// dropping the terms that are not used anymore by any quad
+    /// (terms are never dropped when quads are [removed](MutableDataset::remove)).
+    ///
+    /// Return the number of terms in the term index after compaction
+    /// (not counting the default graph).
+    pub fn compact(&mut self) -> Result<usize, TI::Error> {
+        let mut compactor = Compactor::new(&self.terms);
+        let quads = self
+            .quads
+            .iter()
+            .map(|q| compactor.remap_gspo(q))
+            .collect::<Result<_, _>>()?;
+        let (terms, len) = compactor.finish();
+        self.terms = terms;
+        self.quads = quads;
+        if self.digests.is_some() {
+            self.digests = Some(compute_digests(&self.terms, self.quads.iter()));
+        }
+        Ok(len)
+    }
+}
+
+impl<TI: GraphNameIndex> GenericLightDataset<TI> {
+    /// Start maintaining a digest of each graph of this dataset
+    /// (see [`graph_digest`](Self::graph_digest)).
+    ///
+    /// This makes insertions and removals slightly slower.
+    pub fn enable_graph_digests(&mut self) {
+        if self.digests.is_none() {
+            self.digests = Some(compute_digests(&self.terms, self.quads.iter()));
+        }
+    }
+
+    /// Stop maintaining a digest of each graph of this dataset.
+    pub fn disable_graph_digests(&mut self) {
+        self.digests = None;
+    }
+
+    /// Return a digest of the content of the given graph,
+    /// or `None` if graph digests are not [enabled](Self::enable_graph_digests).
+    ///
+    /// The digest of a graph changes whenever a triple is inserted into or removed from it,
+    /// so clients can detect changes without enumerating its quads.
+    /// Graphs containing the same triples have the same digest
+    /// (in particular, the digest of an empty graph is 0).
+    pub fn graph_digest<T: Term>(&self, name: GraphName<T>) -> Option<u64> {
+        let digests = self.digests.as_ref()?;
+        Some(
+            self.terms
+                .get_graph_name_index(name)
+                .and_then(|gi| digests.get(&gi).copied())
+                .unwrap_or(0),
+        )
+    }
 }
 
 impl<TI: GraphNameIndex> Dataset for GenericLightDataset<TI> {
@@ -146,7 +206,12 @@ impl<TI: GraphNameIndex> MutableDataset for GenericLightDataset<TI> {
             None => self.terms.get_default_graph_index(),
             Some(gn) => self.terms.ensure_index(gn)?,
         };
-        Ok(self.quads.insert([ig, is, ip, io]))
+        let q = [ig, is, ip, io];
+        let inserted = self.quads.insert(q);
+        if let (true, Some(digests)) = (inserted, self.digests.as_mut()) {
+            update_digest(digests, &self.terms, &q, true);
+        }
+        Ok(inserted)
     }
 
     fn remove<TS, TP, TO, TG>(
@@ -174,7 +239,12 @@ impl<TI: GraphNameIndex> MutableDataset for GenericLightDataset<TI> {
         let Some(ig) = self.terms.get_graph_name_index(g) else {
             return Ok(false);
         };
-        Ok(self.quads.remove(&[ig, is, ip, io]))
+        let q = [ig, is, ip, io];
+        let removed = self.quads.remove(&q);
+        if let (true, Some(digests)) = (removed, self.digests.as_mut()) {
+            update_digest(digests, &self.terms, &q, false);
+        }
+        Ok(removed)
     }
 }
 
@@ -203,6 +273,7 @@ pub struct GenericFastDataset<TI: GraphNameIndex> {
     spog: BTreeSet<[TI::Index; 4]>,
     posg: BTreeSet<[TI::Index; 4]>,
     ospg: BTreeSet<[TI::Index; 4]>,
+    digests: Option<GraphDigests<TI>>,
 }
 
 impl<TI: GraphNameIndex + Default> GenericFastDataset<TI> {
@@ -216,8 +287,67 @@ impl<TI: GraphNameIndex + Default> GenericFastDataset<TI> {
             spog: BTreeSet::new(),
             posg: BTreeSet::new(),
             ospg: BTreeSet::new(),
+            digests: None,
         }
     }
+
+    /// Rebuild the term index of this dataset,
+    /// dropping the terms that are not used anymore by any quad
+    /// (terms are never dropped when quads are [removed](MutableDataset::remove)).
+    ///
+    /// Return the number of terms in the term index after compaction
+    /// (not counting the default graph).
+    pub fn compact(&mut self) -> Result<usize, TI::Error> {
+        let mut compactor = Compactor::new(&self.terms);
+        let gspo: BTreeSet<_> = self
+            .gspo
+            .iter()
+            .map(|q| compactor.remap_gspo(q))
+            .collect::<Result<_, _>>()?;
+        let (terms, len) = compactor.finish();
+        self.terms = terms;
+        self.gpos = gspo.iter().map(|&[g, s, p, o]| [g, p, o, s]).collect();
+        self.gosp = gspo.iter().map(|&[g, s, p, o]| [g, o, s, p]).collect();
+        self.spog = gspo.iter().map(|&[g, s, p, o]| [s, p, o, g]).collect();
+        self.posg = gspo.iter().map(|&[g, s, p, o]| [p, o, s, g]).collect();
+        self.ospg = gspo.iter().map(|&[g, s, p, o]| [o, s, p, g]).collect();
+        self.gspo = gspo;
+        if self.digests.is_some() {
+            self.digests = Some(compute_digests(&self.terms, self.gspo.iter()));
+        }
+        Ok(len)
+    }
+}
+
+impl<TI: GraphNameIndex> GenericFastDataset<TI> {
+    /// Start maintaining a digest of each graph of this dataset
+    /// (see [`graph_digest`](Self::graph_digest)).
+    ///
+    /// This makes insertions and removals slightly slower.
+    pub fn enable_graph_digests(&mut self) {
+        if self.digests.is_none() {
+            self.digests = Some(compute_digests(&self.terms, self.gspo.iter()));
+        }
+    }
+
+    /// Stop maintaining a digest of each graph of this dataset.
+    pub fn disable_graph_digests(&mut self) {
+        self.digests = None;
+    }
+
+    /// Return a digest of the content of the given graph,
+    /// or `None` if graph digests are not [enabled](Self::enable_graph_digests).
+    ///
+    /// See [`GenericLightDataset::graph_digest`].
+    pub fn graph_digest<T: Term>(&self, name: GraphName<T>) -> Option<u64> {
+        let digests = self.digests.as_ref()?;
+        Some(
+            self.terms
+                .get_graph_name_index(name)
+                .and_then(|gi| digests.get(&gi).copied())
+                .unwrap_or(0),
+        )
+    }
 }
 
 impl<TI: GraphNameIndex> Dataset for GenericFastDataset<TI> {
@@ -483,6 +613,9 @@ impl<TI: GraphNameIndex> MutableDataset for GenericFastDataset<TI> {
             debug_assert!(i);
             let i = self.ospg.insert([io, is, ip, ig]);
             debug_assert!(i);
+            if let Some(digests) = self.digests.as_mut() {
+                update_digest(digests, &self.terms, &[ig, is, ip, io], true);
+            }
             Ok(true)
         } else {
             Ok(false)
@@ -525,6 +658,9 @@ impl<TI: GraphNameIndex> MutableDataset for GenericFastDataset<TI> {
             debug_assert!(i);
             let i = self.ospg.remove(&[io, is, ip, ig]);
             debug_assert!(i);
+            if let Some(digests) = self.digests.as_mut() {
+                update_digest(digests, &self.terms, &[ig, is, ip, io], false);
+            }
             Ok(true)
         } else {
             Ok(false)
@@ -559,10 +695,85 @@ pub type FastDataset = GenericFastDataset<SimpleTermIndex<u32>>;
 #[cfg(test)]
 mod test {
     use super::{FastDataset, LightDataset};
+    use sophia_api::dataset::{Dataset, MutableDataset};
+    use sophia_api::ns::rdf;
+    use sophia_api::term::SimpleTerm;
 
     sophia_api::test_dataset_impl!(light_dataset, LightDataset);
     sophia_api::test_dataset_impl!(fast_dataset, FastDataset);
 
+    macro_rules! test_compact {
+        ($name: ident, $dataset: ty) => {
+            #[test]
+            fn $name() -> Result<(), Box<dyn std::error::Error>> {
+                let mut d = <$dataset>::new();
+                let g = Some(rdf::Bag);
+                d.insert(rdf::Alt, rdf::type_, rdf::Bag, g)?;
+                d.insert(rdf::Seq, rdf::type_, rdf::List, None as Option<SimpleTerm>)?;
+                d.insert(rdf::Seq, rdf::value, "foo", g)?;
+                assert_eq!(d.compact()?, 7);
+                d.remove(rdf::Seq, rdf::type_, rdf::List, None as Option<SimpleTerm>)?;
+                d.remove(rdf::Seq, rdf::value, "foo", g)?;
+                assert_eq!(d.compact()?, 3);
+                assert_eq!(d.quads().count(), 1);
+                assert!(d.contains(rdf::Alt, rdf::type_, rdf::Bag, g)?);
+                assert_eq!(
+                    d.quads_matching([rdf::Alt], [rdf::type_], [rdf::Bag], [g])
+                        .count(),
+                    1
+                );
+                assert_eq!(d.graph_names().count(), 1);
+                d.insert(rdf::Seq, rdf::type_, rdf::List, None as Option<SimpleTerm>)?;
+                assert_eq!(d.quads().count(), 2);
+                Ok(())
+            }
+        };
+    }
+    test_compact!(light_compact, LightDataset);
+    test_compact!(fast_compact, FastDataset);
+
+    macro_rules! test_graph_digest {
+        ($name: ident, $dataset: ty) => {
+            #[test]
+            fn $name() -> Result<(), Box<dyn std::error::Error>> {
+                let mut d = <$dataset>::new();
+                let g = Some(rdf::Bag);
+                let dg = None as Option<&SimpleTerm>;
+                d.insert(rdf::Alt, rdf::type_, rdf::Bag, g)?;
+                assert_eq!(d.graph_digest(g), None);
+                d.enable_graph_digests();
+                let d1 = d.graph_digest(g).unwrap();
+                assert_ne!(d1, 0);
+                assert_eq!(d.graph_digest(dg), Some(0));
+                assert_eq!(d.graph_digest(Some(rdf::Seq)), Some(0));
+
+                d.insert(rdf::Seq, rdf::value, "foo", g)?;
+                let d2 = d.graph_digest(g).unwrap();
+                assert_ne!(d2, d1);
+                d.insert(rdf::Seq, rdf::value, "foo", g)?; // no change
+                assert_eq!(d.graph_digest(g), Some(d2));
+                d.insert(rdf::Seq, rdf::value, "foo", dg)?;
+                assert_eq!(d.graph_digest(g), Some(d2));
+                assert_eq!(d.graph_digest(dg), Some(d2.wrapping_sub(d1)));
+
+                d.remove(rdf::Alt, rdf::type_, rdf::Bag, g)?;
+                assert_eq!(d.graph_digest(g), d.graph_digest(dg));
+                d.remove(rdf::Seq, rdf::value, "foo", g)?;
+                assert_eq!(d.graph_digest(g), Some(0));
+
+                // digests survive compaction
+                d.compact()?;
+                assert_eq!(d.graph_digest(dg), Some(d2.wrapping_sub(d1)));
+
+                d.disable_graph_digests();
+                assert_eq!(d.graph_digest(dg), None);
+                Ok(())
+            }
+        };
+    }
+    test_graph_digest!(light_graph_digest, LightDataset);
+    test_graph_digest!(fast_graph_digest, FastDataset);
+
     #[test]
     fn new_available() {
         // ::new() is only available if the underlying TermIndex implements Default,
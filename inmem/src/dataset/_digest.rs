@@ -0,0 +1,51 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::Hasher;
+
+use sophia_api::term::Term;
+
+use crate::index::{GraphNameIndex, TermIndex};
+
+/// Rolling digests of the graphs of a dataset, indexed by graph name index.
+///
+/// The digest of a graph is the (wrapping) sum of the hashes of its triples,
+/// so it can be updated incrementally, and does not depend on the insertion order.
+/// Empty graphs are not stored (their digest is 0).
+pub type GraphDigests<TI> = BTreeMap<<TI as TermIndex>::Index, u64>;
+
+/// Compute the digests of all graphs from the given GSPO quads.
+pub fn compute_digests<'a, TI, I>(terms: &TI, gspo: I) -> GraphDigests<TI>
+where
+    TI: GraphNameIndex,
+    I: Iterator<Item = &'a [TI::Index; 4]>,
+    TI::Index: 'a,
+{
+    let mut digests = GraphDigests::<TI>::new();
+    for q in gspo {
+        update_digest(&mut digests, terms, q, true);
+    }
+    digests
+}
+
+/// Update `digests` after the GSPO quad `q` was inserted or removed.
+pub fn update_digest<TI: GraphNameIndex>(
+    digests: &mut GraphDigests<TI>,
+    terms: &TI,
+    [gi, ti @ ..]: &[TI::Index; 4],
+    inserted: bool,
+) {
+    let mut hasher = DefaultHasher::new();
+    for i in ti {
+        Term::hash(&terms.get_term(*i), &mut hasher);
+    }
+    let h = hasher.finish();
+    let digest = digests.entry(*gi).or_default();
+    if inserted {
+        *digest = digest.wrapping_add(h);
+    } else {
+        *digest = digest.wrapping_sub(h);
+    }
+    if *digest == 0 {
+        digests.remove(gi);
+    }
+}
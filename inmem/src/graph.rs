@@ -1,22 +1,37 @@
 //! In-memory implementations of [`Graph`]
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::iter::{empty, once};
 
 use sophia_api::graph::{CollectibleGraph, GResult, SetGraph};
+use sophia_api::ns::rdf;
 use sophia_api::prelude::*;
 
 use crate::index::*;
 
 mod _iter;
+#[cfg(feature = "serde")]
+mod _serde;
+#[cfg(feature = "analytics")]
+pub mod analytics;
+pub mod annotated;
+pub mod btree;
+pub mod columnar;
+pub mod multi_index;
+pub mod symbol;
 pub(crate) use _iter::TermData;
 use _iter::*;
 
 /// A graph with a single triple index (SPO).
 /// Fast to load but slow to query, with a relatively low memory footprint.
+///
+/// It can also maintain an optional index of `rdf:type` triples
+/// (see [`with_type_index`](GenericLightGraph::with_type_index)).
 #[derive(Clone, Debug, Default)]
 pub struct GenericLightGraph<TI: TermIndex> {
     terms: TI,
     triples: BTreeSet<[TI::Index; 3]>,
+    /// If enabled, the (class, instance) pairs of all `rdf:type` triples
+    types: Option<BTreeSet<[TI::Index; 2]>>,
 }
 
 impl<TI: TermIndex + Default> GenericLightGraph<TI> {
@@ -25,6 +40,120 @@ impl<TI: TermIndex + Default> GenericLightGraph<TI> {
         Self {
             terms: TI::default(),
             triples: BTreeSet::new(),
+            types: None,
+        }
+    }
+
+    /// Rebuild the term index of this graph,
+    /// dropping the terms that are not used anymore by any triple
+    /// (terms are never dropped when triples are [removed](MutableGraph::remove)).
+    ///
+    /// Return the number of terms in the term index after compaction.
+    pub fn compact(&mut self) -> Result<usize, TI::Error> {
+        let mut compactor = Compactor::new(&self.terms);
+        let triples = self
+            .triples
+            .iter()
+            .map(|t| compactor.remap_triple(t))
+            .collect::<Result<_, _>>()?;
+        let (terms, len) = compactor.finish();
+        self.terms = terms;
+        self.triples = triples;
+        if self.types.is_some() {
+            self.types = Some(self.collect_types());
+        }
+        Ok(len)
+    }
+}
+
+impl<TI: TermIndex> GenericLightGraph<TI> {
+    /// Enable the `rdf:type` index of this graph.
+    ///
+    /// With this index, [`instances_of`](GenericLightGraph::instances_of)
+    /// does not need to scan the whole graph.
+    /// The price is a slightly higher memory footprint, and slightly slower insertions and removals.
+    pub fn with_type_index(mut self) -> Self {
+        if self.types.is_none() {
+            self.types = Some(self.collect_types());
+        }
+        self
+    }
+
+    /// Whether the `rdf:type` index of this graph is enabled
+    /// (see [`with_type_index`](GenericLightGraph::with_type_index)).
+    pub fn has_type_index(&self) -> bool {
+        self.types.is_some()
+    }
+
+    /// Iterate over the instances of `class`,
+    /// i.e. the subjects of all triples `(?, rdf:type, class)`.
+    pub fn instances_of<T: Term>(
+        &self,
+        class: T,
+    ) -> Box<dyn Iterator<Item = <TI::Term as Term>::BorrowTerm<'_>> + '_> {
+        let (Some(ti), Some(ci)) = (
+            self.terms.get_index(rdf::type_),
+            self.terms.get_index(class),
+        ) else {
+            return Box::new(empty());
+        };
+        match &self.types {
+            Some(types) => Box::new(
+                types
+                    .range([ci, TI::Index::ZERO]..=[ci, TI::Index::MAX])
+                    .map(|[_, i]| self.terms.get_term(*i)),
+            ),
+            None => Box::new(
+                self.triples
+                    .iter()
+                    .filter(move |[_, p, o]| *p == ti && *o == ci)
+                    .map(|[s, _, _]| self.terms.get_term(*s)),
+            ),
+        }
+    }
+
+    /// Iterate over the classes of `node`,
+    /// i.e. the objects of all triples `(node, rdf:type, ?)`.
+    pub fn classes_of<T: Term>(
+        &self,
+        node: T,
+    ) -> impl Iterator<Item = <TI::Term as Term>::BorrowTerm<'_>> + '_ {
+        let key = self
+            .terms
+            .get_index(node)
+            .zip(self.terms.get_index(rdf::type_));
+        key.into_iter()
+            .flat_map(|(ni, ti)| {
+                self.triples
+                    .range([ni, ti, TI::Index::ZERO]..=[ni, ti, TI::Index::MAX])
+            })
+            .map(|t| self.terms.get_term(t[2]))
+    }
+
+    /// Collect the (class, instance) pairs of all `rdf:type` triples.
+    fn collect_types(&self) -> BTreeSet<[TI::Index; 2]> {
+        let Some(ti) = self.terms.get_index(rdf::type_) else {
+            return BTreeSet::new();
+        };
+        self.triples
+            .iter()
+            .filter(|t| t[1] == ti)
+            .map(|&[s, _, o]| [o, s])
+            .collect()
+    }
+
+    /// Update the `rdf:type` index (if any) for the insertion (or removal) of `t`.
+    fn update_types(&mut self, [s, p, o]: [TI::Index; 3], insert: bool) {
+        let Some(types) = &mut self.types else {
+            return;
+        };
+        if self.terms.get_index(rdf::type_) != Some(p) {
+            return;
+        }
+        if insert {
+            types.insert([o, s]);
+        } else {
+            types.remove(&[o, s]);
         }
     }
 }
@@ -105,7 +234,12 @@ impl<TI: TermIndex> MutableGraph for GenericLightGraph<TI> {
         let is = self.terms.ensure_index(s)?;
         let ip = self.terms.ensure_index(p)?;
         let io = self.terms.ensure_index(o)?;
-        Ok(self.triples.insert([is, ip, io]))
+        if self.triples.insert([is, ip, io]) {
+            self.update_types([is, ip, io], true);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
     }
 
     fn remove<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> sophia_api::graph::MgResult<Self, bool>
@@ -123,7 +257,12 @@ impl<TI: TermIndex> MutableGraph for GenericLightGraph<TI> {
         let Some(io) = self.terms.get_index(o) else {
             return Ok(false);
         };
-        Ok(self.triples.remove(&[is, ip, io]))
+        if self.triples.remove(&[is, ip, io]) {
+            self.update_types([is, ip, io], false);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
     }
 }
 
@@ -143,14 +282,29 @@ impl<TI: TermIndex> SetGraph for GenericLightGraph<TI> {}
 
 /// A heavily indexed graph.
 /// Fast to query but slow to load, with a relatively high memory footprint.
+///
+/// Besides its indexes, it maintains [statistics](GenericFastGraph::term_statistics) about its terms,
+/// which are used by [`triples_matching`](Graph::triples_matching)
+/// to decide whether an index scan or a full scan is more efficient.
+///
+/// It can also maintain an optional index of language-tagged literals
+/// (see [`with_language_index`](GenericFastGraph::with_language_index)).
 #[derive(Clone, Debug, Default)]
 pub struct GenericFastGraph<TI: TermIndex> {
     terms: TI,
     spo: BTreeSet<[TI::Index; 3]>,
     pos: BTreeSet<[TI::Index; 3]>,
     osp: BTreeSet<[TI::Index; 3]>,
+    /// For each term, the number of triples using it as subject, predicate and object
+    stats: BTreeMap<TI::Index, [usize; 3]>,
+    /// If enabled, for each (lowercased) language tag, the language-tagged literals used as objects
+    langs: Option<BTreeMap<Box<str>, BTreeSet<TI::Index>>>,
 }
 
+/// A pattern whose only bound term occurs in more than `1/FULL_SCAN_RATIO` of the triples
+/// is served by a full scan rather than an index scan.
+const FULL_SCAN_RATIO: usize = 2;
+
 impl<TI: TermIndex + Default> GenericFastGraph<TI> {
     /// Construct an empty graph
     pub fn new() -> Self {
@@ -159,7 +313,179 @@ impl<TI: TermIndex + Default> GenericFastGraph<TI> {
             spo: BTreeSet::new(),
             pos: BTreeSet::new(),
             osp: BTreeSet::new(),
+            stats: BTreeMap::new(),
+            langs: None,
+        }
+    }
+
+    /// Rebuild the term index of this graph,
+    /// dropping the terms that are not used anymore by any triple
+    /// (terms are never dropped when triples are [removed](MutableGraph::remove)).
+    ///
+    /// Return the number of terms in the term index after compaction.
+    pub fn compact(&mut self) -> Result<usize, TI::Error> {
+        let mut compactor = Compactor::new(&self.terms);
+        let spo: BTreeSet<_> = self
+            .spo
+            .iter()
+            .map(|t| compactor.remap_triple(t))
+            .collect::<Result<_, _>>()?;
+        let (terms, len) = compactor.finish();
+        self.terms = terms;
+        self.pos = spo.iter().map(|&[s, p, o]| [p, o, s]).collect();
+        self.osp = spo.iter().map(|&[s, p, o]| [o, s, p]).collect();
+        self.stats.clear();
+        if let Some(langs) = &mut self.langs {
+            langs.clear();
         }
+        for t in &spo {
+            self.count(*t);
+        }
+        self.spo = spo;
+        Ok(len)
+    }
+}
+
+impl<TI: TermIndex> GenericFastGraph<TI> {
+    /// The number of triples having `t` as their subject, predicate and object, respectively.
+    pub fn term_statistics<T: Term>(&self, t: T) -> [usize; 3] {
+        self.terms
+            .get_index(t)
+            .and_then(|i| self.stats.get(&i))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Enable the language index of this graph.
+    ///
+    /// With this index, [`triples_matching`](Graph::triples_matching) with an object matcher
+    /// that only matches a given language tag (such as [`LanguageTagMatcher`](sophia_api::term::matcher::LanguageTagMatcher))
+    /// and no constant subject or object
+    /// only visits the triples whose object has that language tag,
+    /// instead of scanning the whole graph (or all the triples with the given predicate).
+    /// The price is a slightly higher memory footprint, and slightly slower insertions and removals.
+    pub fn with_language_index(mut self) -> Self {
+        if self.langs.is_none() {
+            self.langs = Some(BTreeMap::new());
+            let objects: Vec<_> = self
+                .stats
+                .iter()
+                .filter(|(_, counts)| counts[2] > 0)
+                .map(|(i, _)| *i)
+                .collect();
+            for o in objects {
+                self.index_language(o);
+            }
+        }
+        self
+    }
+
+    /// Iterate over the instances of `class`,
+    /// i.e. the subjects of all triples `(?, rdf:type, class)`.
+    ///
+    /// This is served by the POS index, so it never requires a full scan.
+    pub fn instances_of<T: Term>(
+        &self,
+        class: T,
+    ) -> impl Iterator<Item = <TI::Term as Term>::BorrowTerm<'_>> + '_ {
+        let key = self
+            .terms
+            .get_index(rdf::type_)
+            .zip(self.terms.get_index(class));
+        key.into_iter()
+            .flat_map(|(ti, ci)| {
+                self.pos
+                    .range([ti, ci, TI::Index::ZERO]..=[ti, ci, TI::Index::MAX])
+            })
+            .map(|t| self.terms.get_term(t[2]))
+    }
+
+    /// Iterate over the classes of `node`,
+    /// i.e. the objects of all triples `(node, rdf:type, ?)`.
+    ///
+    /// This is served by the SPO index, so it never requires a full scan.
+    pub fn classes_of<T: Term>(
+        &self,
+        node: T,
+    ) -> impl Iterator<Item = <TI::Term as Term>::BorrowTerm<'_>> + '_ {
+        let key = self
+            .terms
+            .get_index(node)
+            .zip(self.terms.get_index(rdf::type_));
+        key.into_iter()
+            .flat_map(|(ni, ti)| {
+                self.spo
+                    .range([ni, ti, TI::Index::ZERO]..=[ni, ti, TI::Index::MAX])
+            })
+            .map(|t| self.terms.get_term(t[2]))
+    }
+
+    /// Whether the language index of this graph is enabled
+    /// (see [`with_language_index`](GenericFastGraph::with_language_index)).
+    pub fn has_language_index(&self) -> bool {
+        self.langs.is_some()
+    }
+
+    /// Update statistics (and the language index, if any) for the insertion of `t`.
+    fn count(&mut self, t: [TI::Index; 3]) {
+        for (pos, i) in t.into_iter().enumerate() {
+            self.stats.entry(i).or_default()[pos] += 1;
+        }
+        self.index_language(t[2]);
+    }
+
+    /// Update statistics (and the language index, if any) for the removal of `t`.
+    fn uncount(&mut self, t: [TI::Index; 3]) {
+        for (pos, i) in t.into_iter().enumerate() {
+            if let Some(counts) = self.stats.get_mut(&i) {
+                counts[pos] -= 1;
+                if *counts == [0; 3] {
+                    self.stats.remove(&i);
+                }
+            }
+        }
+        if self.stats.get(&t[2]).map(|counts| counts[2]).unwrap_or(0) == 0 {
+            self.unindex_language(t[2]);
+        }
+    }
+
+    /// Add `o` to the language index, if enabled and if `o` is a language-tagged literal.
+    fn index_language(&mut self, o: TI::Index) {
+        let Some(langs) = &mut self.langs else {
+            return;
+        };
+        if let Some(tag) = self.terms.get_term(o).language_tag() {
+            let key = tag.as_str().to_ascii_lowercase().into_boxed_str();
+            langs.entry(key).or_default().insert(o);
+        }
+    }
+
+    /// Remove `o` from the language index, if enabled and if `o` is a language-tagged literal.
+    fn unindex_language(&mut self, o: TI::Index) {
+        let Some(langs) = &mut self.langs else {
+            return;
+        };
+        if let Some(tag) = self.terms.get_term(o).language_tag() {
+            let key = tag.as_str().to_ascii_lowercase();
+            if let Some(objects) = langs.get_mut(key.as_str()) {
+                objects.remove(&o);
+                if objects.is_empty() {
+                    langs.remove(key.as_str());
+                }
+            }
+        }
+    }
+
+    /// Whether a pattern whose only bound term is `i` (in position `pos`)
+    /// is more efficiently served by a full scan than by an index scan.
+    ///
+    /// When `i` occurs in most triples (e.g. `rdf:type` as a predicate),
+    /// the index scan hardly reduces the number of visited triples,
+    /// and evaluates the subject (resp. predicate) matcher on each of them,
+    /// while the full scan evaluates it only when the subject (resp. predicate) changes.
+    fn prefers_full_scan(&self, i: TI::Index, pos: usize) -> bool {
+        let count = self.stats.get(&i).map(|counts| counts[pos]).unwrap_or(0);
+        count > self.spo.len() / FULL_SCAN_RATIO
     }
 }
 
@@ -200,6 +526,23 @@ impl<TI: TermIndex> Graph for GenericFastGraph<TI> {
             Some(None) => return Box::new(empty()),
             Some(Some(i)) => Some(i),
         };
+        if let (None, None, Some(langs), Some(tag)) = (si, oi, &self.langs, om.language_tag()) {
+            let Some(objects) = langs.get(tag.as_str().to_ascii_lowercase().as_str()) else {
+                return Box::new(empty());
+            };
+            return Box::new(
+                objects
+                    .iter()
+                    .flat_map(move |&oi| {
+                        let r = [oi, TI::Index::ZERO, TI::Index::ZERO]
+                            ..=[oi, TI::Index::MAX, TI::Index::MAX];
+                        self.osp.range(r)
+                    })
+                    .map(move |&[o, s, p]| [s, p, o].map(|i| self.terms.get_term(i)))
+                    .filter(move |[s, p, _]| sm.matches(s) && pm.matches(p))
+                    .map(Ok),
+            );
+        }
         match (si, pi, oi) {
             (Some(si), Some(pi), Some(oi)) => {
                 let ti = [si, pi, oi];
@@ -237,6 +580,9 @@ impl<TI: TermIndex> Graph for GenericFastGraph<TI> {
                 )
             }
             (None, Some(pi), None) => {
+                if self.prefers_full_scan(pi, 1) {
+                    return SpoMatchingIterator::boxed(&self.terms, self.spo.iter(), sm, pm, om);
+                }
                 let r =
                     [pi, TI::Index::ZERO, TI::Index::ZERO]..=[pi, TI::Index::MAX, TI::Index::MAX];
                 BcMatchingIterator::boxed(&self.terms, self.pos.range(r), om, sm, |[p, o, s]| {
@@ -255,6 +601,9 @@ impl<TI: TermIndex> Graph for GenericFastGraph<TI> {
                 )
             }
             (None, None, Some(oi)) => {
+                if self.prefers_full_scan(oi, 2) {
+                    return SpoMatchingIterator::boxed(&self.terms, self.spo.iter(), sm, pm, om);
+                }
                 let r =
                     [oi, TI::Index::ZERO, TI::Index::ZERO]..=[oi, TI::Index::MAX, TI::Index::MAX];
                 BcMatchingIterator::boxed(&self.terms, self.osp.range(r), sm, pm, |[o, s, p]| {
@@ -285,6 +634,7 @@ impl<TI: TermIndex> MutableGraph for GenericFastGraph<TI> {
             debug_assert!(i);
             let i = self.osp.insert([io, is, ip]);
             debug_assert!(i);
+            self.count([is, ip, io]);
             Ok(true)
         } else {
             Ok(false)
@@ -311,6 +661,7 @@ impl<TI: TermIndex> MutableGraph for GenericFastGraph<TI> {
             debug_assert!(i);
             let i = self.osp.remove(&[io, is, ip]);
             debug_assert!(i);
+            self.uncount([is, ip, io]);
             Ok(true)
         } else {
             Ok(false)
@@ -345,10 +696,132 @@ pub type FastGraph = GenericFastGraph<SimpleTermIndex<u32>>;
 #[cfg(test)]
 mod test {
     use super::{FastGraph, LightGraph};
+    use sophia_api::graph::{Graph, MutableGraph};
+    use sophia_api::ns::rdf;
+    use sophia_api::term::matcher::Any;
 
     sophia_api::test_graph_impl!(light_graph, LightGraph);
     sophia_api::test_graph_impl!(fast_graph, FastGraph);
 
+    macro_rules! test_compact {
+        ($name: ident, $graph: ty) => {
+            #[test]
+            fn $name() -> Result<(), Box<dyn std::error::Error>> {
+                let mut g = <$graph>::new();
+                g.insert(rdf::Alt, rdf::type_, rdf::Bag)?;
+                g.insert(rdf::Seq, rdf::value, "foo")?;
+                assert_eq!(g.compact()?, 6);
+                g.remove(rdf::Seq, rdf::value, "foo")?;
+                assert_eq!(g.compact()?, 3);
+                assert_eq!(g.triples().count(), 1);
+                assert!(g.contains(rdf::Alt, rdf::type_, rdf::Bag)?);
+                assert_eq!(
+                    g.triples_matching([rdf::Alt], [rdf::type_], [rdf::Bag])
+                        .count(),
+                    1
+                );
+                g.insert(rdf::Seq, rdf::value, "foo")?;
+                assert_eq!(g.triples().count(), 2);
+                Ok(())
+            }
+        };
+    }
+    test_compact!(light_compact, LightGraph);
+    test_compact!(fast_compact, FastGraph);
+
+    #[test]
+    fn fast_statistics() -> Result<(), Box<dyn std::error::Error>> {
+        let mut g = FastGraph::new();
+        g.insert(rdf::Alt, rdf::type_, rdf::Bag)?;
+        g.insert(rdf::Seq, rdf::type_, rdf::Bag)?;
+        g.insert(rdf::Bag, rdf::value, rdf::Alt)?;
+        assert_eq!(g.term_statistics(rdf::type_), [0, 2, 0]);
+        assert_eq!(g.term_statistics(rdf::Bag), [1, 0, 2]);
+        assert_eq!(g.term_statistics(rdf::nil), [0, 0, 0]);
+        // rdf:type and rdf:Bag occur in most triples, so these use a full scan
+        assert_eq!(g.triples_matching(Any, [rdf::type_], Any).count(), 2);
+        assert_eq!(g.triples_matching(Any, Any, [rdf::Bag]).count(), 2);
+        // while these use an index scan
+        assert_eq!(g.triples_matching(Any, [rdf::value], Any).count(), 1);
+        assert_eq!(g.triples_matching(Any, Any, [rdf::Alt]).count(), 1);
+
+        g.remove(rdf::Seq, rdf::type_, rdf::Bag)?;
+        assert_eq!(g.term_statistics(rdf::type_), [0, 1, 0]);
+        g.remove(rdf::Bag, rdf::value, rdf::Alt)?;
+        g.compact()?;
+        assert_eq!(g.term_statistics(rdf::Alt), [1, 0, 0]);
+        assert_eq!(g.term_statistics(rdf::value), [0, 0, 0]);
+        Ok(())
+    }
+
+    macro_rules! test_types {
+        ($name: ident, $graph: ty, $enable: expr) => {
+            #[test]
+            fn $name() -> Result<(), Box<dyn std::error::Error>> {
+                use sophia_api::ns::{rdfs, NsTerm};
+
+                let instances = |g: &$graph, c: NsTerm| g.instances_of(c).count();
+                let classes = |g: &$graph, n: NsTerm| g.classes_of(n).count();
+                let mut g = <$graph>::new();
+                g.insert(rdf::Alt, rdf::type_, rdfs::Class)?;
+                g.insert(rdf::Alt, rdf::type_, rdfs::Container)?;
+                g = $enable(g);
+                g.insert(rdf::Bag, rdf::type_, rdfs::Class)?;
+                g.insert(rdf::Bag, rdfs::subClassOf, rdfs::Container)?;
+
+                assert_eq!(instances(&g, rdfs::Class), 2);
+                assert_eq!(instances(&g, rdfs::Container), 1);
+                assert_eq!(instances(&g, rdfs::Datatype), 0);
+                assert_eq!(classes(&g, rdf::Alt), 2);
+                assert_eq!(classes(&g, rdf::Bag), 1);
+                assert_eq!(classes(&g, rdf::Seq), 0);
+
+                g.remove(rdf::Alt, rdf::type_, rdfs::Class)?;
+                assert_eq!(instances(&g, rdfs::Class), 1);
+                g.compact()?;
+                assert_eq!(instances(&g, rdfs::Class), 1);
+                assert_eq!(instances(&g, rdfs::Container), 1);
+                assert_eq!(classes(&g, rdf::Alt), 1);
+                Ok(())
+            }
+        };
+    }
+    test_types!(light_types, LightGraph, |g| g);
+    test_types!(light_type_index, LightGraph, LightGraph::with_type_index);
+    test_types!(fast_types, FastGraph, |g| g);
+
+    #[test]
+    fn fast_language_index() -> Result<(), Box<dyn std::error::Error>> {
+        use sophia_api::term::LanguageTag;
+
+        let [en, en_us, fr] = ["en", "en-US", "fr"].map(LanguageTag::new_unchecked);
+        let mut g = FastGraph::new();
+        g.insert(rdf::Alt, rdf::value, "alt" * en)?;
+        assert!(!g.has_language_index());
+        let mut g = g.with_language_index();
+        assert!(g.has_language_index());
+        g.insert(rdf::Alt, rdf::value, "alternative" * en)?;
+        g.insert(rdf::Bag, rdf::value, "bag" * en_us)?;
+        g.insert(rdf::Bag, rdf::value, "sac" * fr)?;
+        g.insert(rdf::Bag, rdf::type_, rdf::Alt)?;
+
+        assert_eq!(g.triples_matching(Any, Any, Any * en).count(), 2);
+        // language tags are case insensitive
+        let en_us2 = LanguageTag::new_unchecked("EN-us");
+        assert_eq!(g.triples_matching(Any, Any, Any * en_us2).count(), 1);
+        assert_eq!(g.triples_matching([rdf::Bag], Any, Any * fr).count(), 1);
+        assert_eq!(g.triples_matching(Any, [rdf::type_], Any * fr).count(), 0);
+        let de = LanguageTag::new_unchecked("de");
+        assert_eq!(g.triples_matching(Any, Any, Any * de).count(), 0);
+
+        g.remove(rdf::Bag, rdf::value, "sac" * fr)?;
+        assert_eq!(g.triples_matching(Any, Any, Any * fr).count(), 0);
+        g.compact()?;
+        assert_eq!(g.triples_matching(Any, Any, Any * en).count(), 2);
+        assert_eq!(g.triples_matching(Any, Any, Any * en_us).count(), 1);
+        Ok(())
+    }
+
     #[test]
     fn new_available() {
         // ::new() is only available if the underlying TermIndex implements Default,
@@ -0,0 +1,79 @@
+//! Serialization of in-memory graphs, as sequences of triples of [`SimpleTerm`]s.
+use serde::de::Error;
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sophia_api::term::SimpleTerm;
+
+use super::*;
+
+macro_rules! impl_serde {
+    ($graph: ident, $triples: ident) => {
+        impl<TI: TermIndex> Serialize for $graph<TI> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let mut seq = serializer.serialize_seq(Some(self.$triples.len()))?;
+                for ti in &self.$triples {
+                    let [s, p, o] = ti.map(|i| self.terms.get_term(i));
+                    seq.serialize_element(&[s.as_simple(), p.as_simple(), o.as_simple()])?;
+                }
+                seq.end()
+            }
+        }
+
+        impl<'de, TI: TermIndex + Default> Deserialize<'de> for $graph<TI> {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let triples = Vec::<[SimpleTerm<'static>; 3]>::deserialize(deserializer)?;
+                let mut g = Self::new();
+                for [s, p, o] in triples {
+                    g.insert(s, p, o).map_err(D::Error::custom)?;
+                }
+                Ok(g)
+            }
+        }
+    };
+}
+
+impl_serde!(GenericLightGraph, triples);
+impl_serde!(GenericFastGraph, spo);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::ns::{rdf, xsd};
+    use sophia_api::term::BnodeId;
+
+    macro_rules! test_round_trip {
+        ($name: ident, $graph: ty) => {
+            #[test]
+            fn $name() -> Result<(), Box<dyn std::error::Error>> {
+                let b1 = BnodeId::new_unchecked("b1");
+                let mut g = <$graph>::new();
+                g.insert(b1, rdf::type_, rdf::Bag)?;
+                g.insert(b1, rdf::value, 42)?;
+                g.insert(rdf::Bag, rdf::value, "hello")?;
+
+                let json = serde_json::to_string(&g)?;
+                let g2: $graph = serde_json::from_str(&json)?;
+                assert_eq!(g2.triples().count(), 3);
+                assert!(g2.contains(b1, rdf::type_, rdf::Bag)?);
+                assert!(g2.contains(b1, rdf::value, 42)?);
+                assert!(g2.contains(rdf::Bag, rdf::value, "hello")?);
+                assert!(!g2.contains(rdf::Bag, rdf::value, xsd::string)?);
+                Ok(())
+            }
+        };
+    }
+    test_round_trip!(light_round_trip, LightGraph);
+    test_round_trip!(fast_round_trip, FastGraph);
+
+    #[test]
+    fn invalid_term() {
+        let json = r#"[[{"iri": "not an IRI"}, {"iri": "tag:p"}, {"iri": "tag:o"}]]"#;
+        assert!(serde_json::from_str::<FastGraph>(json).is_err());
+    }
+}
@@ -0,0 +1,269 @@
+//! Graph analytics computed directly on the term indices of the in-memory graphs:
+//! [PageRank](GenericFastGraph::pagerank)
+//! and [degree centrality](GenericFastGraph::degree_centrality).
+//!
+//! The nodes considered by these algorithms are the subjects and objects of the triples,
+//! each triple being an edge from its subject to its object.
+//! Triples whose object is a literal are ignored.
+//!
+//! This module is only available with the `analytics` feature.
+use super::*;
+use std::collections::BTreeMap;
+
+/// A ranking of the terms of a graph, by decreasing score.
+pub type Ranking<'a, TI> = Vec<(<<TI as TermIndex>::Term as Term>::BorrowTerm<'a>, f64)>;
+
+/// [PageRank](GenericFastGraph::pagerank) configuration.
+#[derive(Clone, Debug)]
+pub struct PageRankConfig {
+    damping: f64,
+    max_iterations: usize,
+    tolerance: f64,
+}
+
+impl PageRankConfig {
+    /// The probability, at each step, to follow an edge rather than jump to a random node.
+    ///
+    /// Default: 0.85
+    pub fn damping(&self) -> f64 {
+        self.damping
+    }
+
+    /// The maximum number of iterations.
+    ///
+    /// Default: 100
+    pub fn max_iterations(&self) -> usize {
+        self.max_iterations
+    }
+
+    /// The computation stops as soon as the sum of the score variations
+    /// in an iteration is below this value.
+    ///
+    /// Default: 1e-6
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    /// Build a default config (see individual methods for default values)
+    pub fn new() -> Self {
+        PageRankConfig {
+            damping: 0.85,
+            max_iterations: 100,
+            tolerance: 1e-6,
+        }
+    }
+
+    /// Transform a config with the given value for [`damping`](PageRankConfig::damping)
+    pub fn with_damping(mut self, damping: f64) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    /// Transform a config with the given value for [`max_iterations`](PageRankConfig::max_iterations)
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Transform a config with the given value for [`tolerance`](PageRankConfig::tolerance)
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+impl Default for PageRankConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<TI: TermIndex> GenericLightGraph<TI> {
+    /// Compute the PageRank of the nodes of this graph
+    /// (see the [module documentation](self)).
+    ///
+    /// The scores sum to 1 (unless the graph is empty).
+    pub fn pagerank(&self, config: &PageRankConfig) -> Ranking<'_, TI> {
+        ranking(
+            &self.terms,
+            pagerank(Edges::new(&self.terms, &self.triples), config),
+        )
+    }
+
+    /// Compute the degree centrality of the nodes of this graph
+    /// (see the [module documentation](self)),
+    /// i.e. the number of edges incident to each node,
+    /// divided by the number of other nodes.
+    pub fn degree_centrality(&self) -> Ranking<'_, TI> {
+        ranking(
+            &self.terms,
+            degree_centrality(Edges::new(&self.terms, &self.triples)),
+        )
+    }
+}
+
+impl<TI: TermIndex> GenericFastGraph<TI> {
+    /// Compute the PageRank of the nodes of this graph
+    /// (see the [module documentation](self)).
+    ///
+    /// The scores sum to 1 (unless the graph is empty).
+    pub fn pagerank(&self, config: &PageRankConfig) -> Ranking<'_, TI> {
+        ranking(
+            &self.terms,
+            pagerank(Edges::new(&self.terms, &self.spo), config),
+        )
+    }
+
+    /// Compute the degree centrality of the nodes of this graph
+    /// (see the [module documentation](self)),
+    /// i.e. the number of edges incident to each node,
+    /// divided by the number of other nodes.
+    pub fn degree_centrality(&self) -> Ranking<'_, TI> {
+        ranking(
+            &self.terms,
+            degree_centrality(Edges::new(&self.terms, &self.spo)),
+        )
+    }
+}
+
+/// The edges of a graph, between densely numbered nodes.
+struct Edges<I> {
+    nodes: Vec<I>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl<I: Index> Edges<I> {
+    fn new<'a, TI>(terms: &TI, triples: impl IntoIterator<Item = &'a [I; 3]>) -> Self
+    where
+        TI: TermIndex<Index = I>,
+        I: 'a,
+    {
+        let mut numbers = BTreeMap::new();
+        let mut nodes = vec![];
+        let mut number = |i: I| {
+            *numbers.entry(i).or_insert_with(|| {
+                nodes.push(i);
+                nodes.len() - 1
+            })
+        };
+        let mut edges = vec![];
+        for [s, _, o] in triples {
+            if terms.get_term(*o).kind() == TermKind::Literal {
+                continue;
+            }
+            edges.push((number(*s), number(*o)));
+        }
+        Edges { nodes, edges }
+    }
+}
+
+fn pagerank<I>(edges: Edges<I>, config: &PageRankConfig) -> Vec<(I, f64)> {
+    let n = edges.nodes.len();
+    if n == 0 {
+        return vec![];
+    }
+    let mut out_degree = vec![0_usize; n];
+    for (s, _) in &edges.edges {
+        out_degree[*s] += 1;
+    }
+    let d = config.damping;
+    let mut scores = vec![1.0 / n as f64; n];
+    for _ in 0..config.max_iterations {
+        let dangling: f64 = (0..n)
+            .filter(|i| out_degree[*i] == 0)
+            .map(|i| scores[i])
+            .sum();
+        let mut next = vec![(1.0 - d + d * dangling) / n as f64; n];
+        for (s, o) in &edges.edges {
+            next[*o] += d * scores[*s] / out_degree[*s] as f64;
+        }
+        let delta: f64 = scores.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+        scores = next;
+        if delta < config.tolerance {
+            break;
+        }
+    }
+    edges.nodes.into_iter().zip(scores).collect()
+}
+
+fn degree_centrality<I>(edges: Edges<I>) -> Vec<(I, f64)> {
+    let n = edges.nodes.len();
+    let mut degree = vec![0_usize; n];
+    for (s, o) in &edges.edges {
+        degree[*s] += 1;
+        degree[*o] += 1;
+    }
+    let others = n.saturating_sub(1).max(1) as f64;
+    edges
+        .nodes
+        .into_iter()
+        .zip(degree)
+        .map(|(i, d)| (i, d as f64 / others))
+        .collect()
+}
+
+/// Sort the scores by decreasing value, and replace indices by terms.
+fn ranking<TI: TermIndex>(terms: &TI, mut scores: Vec<(TI::Index, f64)>) -> Ranking<'_, TI> {
+    scores.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    scores
+        .into_iter()
+        .map(|(i, score)| (terms.get_term(i), score))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::{FastGraph, LightGraph};
+    use sophia_api::ns::rdf;
+
+    fn names<T: Term>(ranking: &[(T, f64)]) -> Vec<String> {
+        ranking
+            .iter()
+            .map(|(t, _)| t.iri().unwrap().as_str()[4..].to_string())
+            .collect()
+    }
+
+    fn fill<G: MutableGraph>(g: &mut G) {
+        for (s, p, o) in [
+            ("tag:a", "tag:p", "tag:c"),
+            ("tag:b", "tag:p", "tag:c"),
+            ("tag:d", "tag:p", "tag:c"),
+            ("tag:c", "tag:p", "tag:a"),
+            ("tag:d", "tag:q", "tag:b"),
+        ] {
+            let [s, p, o] = [s, p, o].map(IriRef::new_unchecked);
+            g.insert(s, p, o).unwrap();
+        }
+        g.insert(rdf::Alt, rdf::value, "literals are ignored")
+            .unwrap();
+    }
+
+    macro_rules! test_analytics {
+        ($pagerank: ident, $degree: ident, $graph: ty) => {
+            #[test]
+            fn $pagerank() {
+                let mut g = <$graph>::new();
+                fill(&mut g);
+                let ranking = g.pagerank(&PageRankConfig::new());
+                assert_eq!(names(&ranking[..3]), ["c", "a", "b"]);
+                assert_eq!(ranking.len(), 4);
+                let total: f64 = ranking.iter().map(|(_, score)| score).sum();
+                assert!((total - 1.0).abs() < 1e-6);
+                assert!(<$graph>::new().pagerank(&PageRankConfig::new()).is_empty());
+            }
+
+            #[test]
+            fn $degree() {
+                let mut g = <$graph>::new();
+                fill(&mut g);
+                let ranking = g.degree_centrality();
+                assert_eq!(names(&ranking), ["c", "a", "b", "d"]);
+                assert_eq!(ranking[0].1, 4.0 / 3.0);
+                assert_eq!(ranking[3].1, 2.0 / 3.0);
+            }
+        };
+    }
+    test_analytics!(light_pagerank, light_degree, LightGraph);
+    test_analytics!(fast_pagerank, fast_degree, FastGraph);
+}
@@ -0,0 +1,361 @@
+//! A side-table associating user payloads (weights, timestamps, access tags...) with triples.
+//!
+//! [`IndexedGraph`]s, such as [`GenericLightGraph`] and [`GenericFastGraph`],
+//! identify each of their triples by the [indices](TermIndex::Index) of its terms.
+//! [`AnnotatedGraph`] wraps such a graph and uses these indices as keys for the payloads,
+//! which avoids keeping a parallel map keyed by cloned triples.
+//! Payloads are dropped whenever the corresponding triple is removed from the graph.
+//!
+//! # Example
+//! ```
+//! # use sophia_api::graph::{Graph, MutableGraph};
+//! # use sophia_api::ns::rdf;
+//! # use sophia_inmem::graph::FastGraph;
+//! # use sophia_inmem::graph::annotated::AnnotatedGraph;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut g = AnnotatedGraph::new(FastGraph::new());
+//! g.insert_annotated(rdf::Alt, rdf::type_, rdf::Bag, 0.5)?;
+//! g.insert(rdf::Seq, rdf::type_, rdf::Bag)?;
+//! assert_eq!(g.annotation(rdf::Alt, rdf::type_, rdf::Bag), Some(&0.5));
+//! assert_eq!(g.annotation(rdf::Seq, rdf::type_, rdf::Bag), None);
+//!
+//! g.remove(rdf::Alt, rdf::type_, rdf::Bag)?;
+//! assert_eq!(g.annotation(rdf::Alt, rdf::type_, rdf::Bag), None);
+//! # Ok(()) }
+//! ```
+use super::*;
+use sophia_api::graph::MgResult;
+
+/// A [`Graph`] identifying each of its triples by the indices of its terms.
+///
+/// These indices are stable as long as the graph is not compacted
+/// (see e.g. [`GenericFastGraph::compact`]).
+pub trait IndexedGraph: Graph {
+    /// The type of the term indices of this graph
+    type Index: Index;
+
+    /// The indices of the given triple, if it belongs to this graph.
+    fn triple_index<TS, TP, TO>(&self, s: TS, p: TP, o: TO) -> Option<[Self::Index; 3]>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term;
+
+    /// The triple identified by the given indices.
+    ///
+    /// # Panics
+    /// This method may panic if `index` was not obtained from this graph.
+    fn triple_at(&self, index: [Self::Index; 3]) -> Self::Triple<'_>;
+}
+
+impl<TI: TermIndex> IndexedGraph for GenericLightGraph<TI> {
+    type Index = TI::Index;
+
+    fn triple_index<TS, TP, TO>(&self, s: TS, p: TP, o: TO) -> Option<[Self::Index; 3]>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let index = [
+            self.terms.get_index(s)?,
+            self.terms.get_index(p)?,
+            self.terms.get_index(o)?,
+        ];
+        self.triples.contains(&index).then_some(index)
+    }
+
+    fn triple_at(&self, index: [Self::Index; 3]) -> Self::Triple<'_> {
+        index.map(|i| self.terms.get_term(i))
+    }
+}
+
+impl<TI: TermIndex> IndexedGraph for GenericFastGraph<TI> {
+    type Index = TI::Index;
+
+    fn triple_index<TS, TP, TO>(&self, s: TS, p: TP, o: TO) -> Option<[Self::Index; 3]>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let index = [
+            self.terms.get_index(s)?,
+            self.terms.get_index(p)?,
+            self.terms.get_index(o)?,
+        ];
+        self.spo.contains(&index).then_some(index)
+    }
+
+    fn triple_at(&self, index: [Self::Index; 3]) -> Self::Triple<'_> {
+        index.map(|i| self.terms.get_term(i))
+    }
+}
+
+impl<TI: TermIndex> IndexedGraph for multi_index::GenericMultiIndexGraph<TI> {
+    type Index = TI::Index;
+
+    fn triple_index<TS, TP, TO>(&self, s: TS, p: TP, o: TO) -> Option<[Self::Index; 3]>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let index = [
+            self.terms.get_index(s)?,
+            self.terms.get_index(p)?,
+            self.terms.get_index(o)?,
+        ];
+        self.contains_index(index).then_some(index)
+    }
+
+    fn triple_at(&self, index: [Self::Index; 3]) -> Self::Triple<'_> {
+        index.map(|i| self.terms.get_term(i))
+    }
+}
+
+/// An [`IndexedGraph`] with a payload of type `A` attached to some of its triples.
+///
+/// The underlying graph can only be accessed immutably,
+/// which guarantees that its indices remain stable.
+#[derive(Clone, Debug, Default)]
+pub struct AnnotatedGraph<G: IndexedGraph, A> {
+    graph: G,
+    annotations: BTreeMap<[G::Index; 3], A>,
+}
+
+impl<G: IndexedGraph, A> AnnotatedGraph<G, A> {
+    /// Wrap `graph`, with no annotation on its triples.
+    pub fn new(graph: G) -> Self {
+        Self {
+            graph,
+            annotations: BTreeMap::new(),
+        }
+    }
+
+    /// The underlying graph
+    pub fn graph(&self) -> &G {
+        &self.graph
+    }
+
+    /// Unwrap the underlying graph, dropping all annotations
+    pub fn into_graph(self) -> G {
+        self.graph
+    }
+
+    /// The annotation of the given triple, if any.
+    pub fn annotation<TS, TP, TO>(&self, s: TS, p: TP, o: TO) -> Option<&A>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        self.annotation_at(self.graph.triple_index(s, p, o)?)
+    }
+
+    /// A mutable reference to the annotation of the given triple, if any.
+    pub fn annotation_mut<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> Option<&mut A>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let index = self.graph.triple_index(s, p, o)?;
+        self.annotations.get_mut(&index)
+    }
+
+    /// The annotation of the triple identified by `index`
+    /// (as returned by [`IndexedGraph::triple_index`]), if any.
+    pub fn annotation_at(&self, index: [G::Index; 3]) -> Option<&A> {
+        self.annotations.get(&index)
+    }
+
+    /// Annotate the given triple, which must already belong to the graph.
+    ///
+    /// Return the previous annotation of the triple, if any,
+    /// or give `annotation` back if the triple does not belong to the graph.
+    pub fn annotate<TS, TP, TO>(
+        &mut self,
+        s: TS,
+        p: TP,
+        o: TO,
+        annotation: A,
+    ) -> Result<Option<A>, A>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        match self.graph.triple_index(s, p, o) {
+            Some(index) => Ok(self.annotations.insert(index, annotation)),
+            None => Err(annotation),
+        }
+    }
+
+    /// Remove the annotation of the given triple (but not the triple itself),
+    /// and return it, if any.
+    pub fn remove_annotation<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> Option<A>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let index = self.graph.triple_index(s, p, o)?;
+        self.annotations.remove(&index)
+    }
+
+    /// Iterate over all annotated triples, with their annotation.
+    pub fn annotated_triples(&self) -> impl Iterator<Item = (G::Triple<'_>, &A)> + '_ {
+        self.annotations
+            .iter()
+            .map(|(index, a)| (self.graph.triple_at(*index), a))
+    }
+}
+
+impl<G: IndexedGraph + MutableGraph, A> AnnotatedGraph<G, A> {
+    /// Insert the given triple in the graph (if not already there), and annotate it.
+    ///
+    /// Return the previous annotation of the triple, if any.
+    pub fn insert_annotated<TS, TP, TO>(
+        &mut self,
+        s: TS,
+        p: TP,
+        o: TO,
+        annotation: A,
+    ) -> MgResult<G, Option<A>>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        self.graph
+            .insert(s.borrow_term(), p.borrow_term(), o.borrow_term())?;
+        let index = self
+            .graph
+            .triple_index(s, p, o)
+            .expect("triple was just inserted");
+        Ok(self.annotations.insert(index, annotation))
+    }
+}
+
+impl<G: IndexedGraph, A> Graph for AnnotatedGraph<G, A> {
+    type Triple<'x> = G::Triple<'x> where Self: 'x;
+    type Error = G::Error;
+
+    fn triples(&self) -> impl Iterator<Item = GResult<Self, Self::Triple<'_>>> + '_ {
+        self.graph.triples()
+    }
+
+    fn triples_matching<'s, S, P, O>(
+        &'s self,
+        sm: S,
+        pm: P,
+        om: O,
+    ) -> impl Iterator<Item = GResult<Self, Self::Triple<'s>>> + 's
+    where
+        S: sophia_api::term::matcher::TermMatcher + 's,
+        P: sophia_api::term::matcher::TermMatcher + 's,
+        O: sophia_api::term::matcher::TermMatcher + 's,
+    {
+        self.graph.triples_matching(sm, pm, om)
+    }
+
+    fn contains<TS, TP, TO>(&self, s: TS, p: TP, o: TO) -> GResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        self.graph.contains(s, p, o)
+    }
+}
+
+/// NB: [`MutableGraph::insert`] leaves the annotation of the triple (if any) unchanged,
+/// while [`MutableGraph::remove`] drops it.
+impl<G: IndexedGraph + MutableGraph, A> MutableGraph for AnnotatedGraph<G, A> {
+    type MutationError = G::MutationError;
+
+    fn insert<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> MgResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        self.graph.insert(s, p, o)
+    }
+
+    fn remove<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> MgResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let Some(index) =
+            self.graph
+                .triple_index(s.borrow_term(), p.borrow_term(), o.borrow_term())
+        else {
+            return Ok(false);
+        };
+        let removed = self.graph.remove(s, p, o)?;
+        if removed {
+            self.annotations.remove(&index);
+        }
+        Ok(removed)
+    }
+}
+
+impl<G: IndexedGraph + SetGraph, A> SetGraph for AnnotatedGraph<G, A> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::ns::rdf;
+
+    macro_rules! test_annotated {
+        ($name: ident, $graph: ty) => {
+            #[test]
+            fn $name() -> Result<(), Box<dyn std::error::Error>> {
+                let mut g = AnnotatedGraph::new(<$graph>::new());
+                assert_eq!(g.insert_annotated(rdf::Alt, rdf::type_, rdf::Bag, 1)?, None);
+                assert_eq!(
+                    g.insert_annotated(rdf::Alt, rdf::type_, rdf::Bag, 2)?,
+                    Some(1)
+                );
+                g.insert(rdf::Seq, rdf::type_, rdf::Bag)?;
+                assert_eq!(g.triples().count(), 2);
+                assert_eq!(g.annotation(rdf::Alt, rdf::type_, rdf::Bag), Some(&2));
+                assert_eq!(g.annotation(rdf::Seq, rdf::type_, rdf::Bag), None);
+
+                assert_eq!(g.annotate(rdf::Seq, rdf::type_, rdf::Bag, 3), Ok(None));
+                assert_eq!(g.annotate(rdf::nil, rdf::type_, rdf::Bag, 4), Err(4));
+                *g.annotation_mut(rdf::Seq, rdf::type_, rdf::Bag).unwrap() += 10;
+                let index = g
+                    .graph()
+                    .triple_index(rdf::Seq, rdf::type_, rdf::Bag)
+                    .unwrap();
+                assert_eq!(g.annotation_at(index), Some(&13));
+                assert!(Term::eq(&g.graph().triple_at(index)[0], rdf::Seq));
+                assert_eq!(g.annotated_triples().count(), 2);
+
+                // inserting again does not change the annotation
+                assert!(!g.insert(rdf::Seq, rdf::type_, rdf::Bag)?);
+                assert_eq!(g.annotation(rdf::Seq, rdf::type_, rdf::Bag), Some(&13));
+
+                // removing the triple drops the annotation
+                assert!(g.remove(rdf::Seq, rdf::type_, rdf::Bag)?);
+                assert!(!g.remove(rdf::Seq, rdf::type_, rdf::Bag)?);
+                assert_eq!(g.annotation_at(index), None);
+                g.insert(rdf::Seq, rdf::type_, rdf::Bag)?;
+                assert_eq!(g.annotation(rdf::Seq, rdf::type_, rdf::Bag), None);
+
+                assert_eq!(g.remove_annotation(rdf::Alt, rdf::type_, rdf::Bag), Some(2));
+                assert!(g.contains(rdf::Alt, rdf::type_, rdf::Bag)?);
+                assert_eq!(g.annotated_triples().count(), 0);
+                Ok(())
+            }
+        };
+    }
+    test_annotated!(light_annotated, LightGraph);
+    test_annotated!(fast_annotated, FastGraph);
+    test_annotated!(multi_index_annotated, multi_index::MultiIndexGraph);
+}
@@ -0,0 +1,325 @@
+//! An in-memory graph supporting range scans over the IRIs of its subjects.
+//!
+//! [`GenericLightGraph`] and [`GenericFastGraph`] store their triples in B-trees of term indices,
+//! so their triples are sorted, but term indices are assigned in insertion order:
+//! triples whose subjects share a common namespace are therefore not contiguous.
+//! [`GenericBTreeGraph`] wraps a [`GenericLightGraph`] with an additional B-tree
+//! mapping the IRIs of its subjects to their index,
+//! in lexicographic order.
+//! This allows to retrieve efficiently all the triples
+//! whose subject IRI [starts with a given prefix](GenericBTreeGraph::triples_with_subject_prefix)
+//! (e.g. a namespace),
+//! or lies [within a given range](GenericBTreeGraph::triples_with_subject_in).
+//!
+//! # Example
+//! ```
+//! # use sophia_api::graph::MutableGraph;
+//! # use sophia_api::ns::rdfs;
+//! # use sophia_api::term::{IriRef, Term};
+//! # use sophia_inmem::graph::btree::BTreeGraph;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut g = BTreeGraph::new();
+//! for iri in ["http://example.org/b", "http://example.com/c", "http://example.org/a"] {
+//!     g.insert(IriRef::new_unchecked(iri), rdfs::label, "some label")?;
+//! }
+//! let subjects: Vec<_> = g
+//!     .triples_with_subject_prefix("http://example.org/")
+//!     .map(|t| Ok(t?[0].iri().unwrap().as_str().to_string()))
+//!     .collect::<Result<_, Box<dyn std::error::Error>>>()?;
+//! assert_eq!(subjects, ["http://example.org/a", "http://example.org/b"]);
+//! # Ok(()) }
+//! ```
+use std::ops::{Bound, RangeBounds};
+
+use super::*;
+use sophia_api::graph::GTerm;
+
+/// A graph whose triples can be scanned by ranges of subject IRIs.
+///
+/// Only IRI subjects are indexed by IRI;
+/// triples whose subject is a blank node, a literal or a quoted triple
+/// are never returned by the range scans.
+#[derive(Clone, Debug, Default)]
+pub struct GenericBTreeGraph<TI: TermIndex> {
+    graph: GenericLightGraph<TI>,
+    /// The IRIs of all the subjects of the graph, with their index
+    subjects: BTreeMap<Box<str>, TI::Index>,
+}
+
+impl<TI: TermIndex + Default> GenericBTreeGraph<TI> {
+    /// Construct an empty graph
+    pub fn new() -> Self {
+        Self {
+            graph: GenericLightGraph::new(),
+            subjects: BTreeMap::new(),
+        }
+    }
+
+    /// Rebuild the term index of this graph,
+    /// dropping the terms that are not used anymore by any triple
+    /// (terms are never dropped when triples are [removed](MutableGraph::remove)).
+    ///
+    /// Return the number of terms in the term index after compaction.
+    pub fn compact(&mut self) -> Result<usize, TI::Error> {
+        let len = self.graph.compact()?;
+        let subjects = std::mem::take(&mut self.subjects);
+        self.subjects = subjects
+            .into_keys()
+            .map(|iri| {
+                let si = self.graph.terms.get_index(IriRef::new_unchecked(&*iri));
+                (iri, si.unwrap())
+            })
+            .collect();
+        Ok(len)
+    }
+}
+
+impl<TI: TermIndex> GenericBTreeGraph<TI> {
+    /// The underlying [`GenericLightGraph`].
+    pub fn graph(&self) -> &GenericLightGraph<TI> {
+        &self.graph
+    }
+
+    /// Iterate over the IRIs of the subjects of this graph that lie in `range`,
+    /// in lexicographic order.
+    ///
+    /// # Panics
+    /// Panics if the start of `range` is greater than its end,
+    /// or if they are equal and both excluded.
+    pub fn subjects_in<'a, R>(&self, range: R) -> impl Iterator<Item = GTerm<'_, Self>> + '_
+    where
+        R: RangeBounds<&'a str>,
+    {
+        self.subjects_in_bounds(bounds(&range))
+    }
+
+    /// Iterate over the triples of this graph whose subject IRI lies in `range`,
+    /// sorted by subject IRI.
+    ///
+    /// # Panics
+    /// Panics if the start of `range` is greater than its end,
+    /// or if they are equal and both excluded.
+    pub fn triples_with_subject_in<'a, R>(
+        &self,
+        range: R,
+    ) -> impl Iterator<Item = GResult<Self, <Self as Graph>::Triple<'_>>> + '_
+    where
+        R: RangeBounds<&'a str>,
+    {
+        self.triples_with_subject_in_bounds(bounds(&range))
+    }
+
+    /// Iterate over the triples of this graph whose subject IRI starts with `prefix`,
+    /// sorted by subject IRI.
+    pub fn triples_with_subject_prefix<'s>(
+        &'s self,
+        prefix: &'s str,
+    ) -> impl Iterator<Item = GResult<Self, <Self as Graph>::Triple<'s>>> + 's {
+        self.subjects
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .take_while(move |(iri, _)| iri.starts_with(prefix))
+            .flat_map(|(_, si)| self.triples_with_subject_index(*si))
+    }
+
+    // NB: the following methods are not generic over the type of range,
+    // so that the iterators they return do not depend on it
+
+    fn subjects_in_bounds(
+        &self,
+        bounds: (Bound<&str>, Bound<&str>),
+    ) -> impl Iterator<Item = GTerm<'_, Self>> + '_ {
+        self.subjects
+            .range::<str, _>(bounds)
+            .map(|(_, si)| self.graph.terms.get_term(*si))
+    }
+
+    fn triples_with_subject_in_bounds(
+        &self,
+        bounds: (Bound<&str>, Bound<&str>),
+    ) -> impl Iterator<Item = GResult<Self, <Self as Graph>::Triple<'_>>> + '_ {
+        self.subjects
+            .range::<str, _>(bounds)
+            .flat_map(|(_, si)| self.triples_with_subject_index(*si))
+    }
+
+    fn triples_with_subject_index(
+        &self,
+        si: TI::Index,
+    ) -> impl Iterator<Item = GResult<Self, <Self as Graph>::Triple<'_>>> + '_ {
+        let r = [si, TI::Index::ZERO, TI::Index::ZERO]..=[si, TI::Index::MAX, TI::Index::MAX];
+        self.graph
+            .triples
+            .range(r)
+            .map(|ti| Ok(ti.map(|i| self.graph.terms.get_term(i))))
+    }
+}
+
+impl<TI: TermIndex> Graph for GenericBTreeGraph<TI> {
+    type Triple<'x> = [<TI::Term as Term>::BorrowTerm<'x>; 3] where Self: 'x;
+    type Error = TI::Error;
+
+    fn triples(&self) -> impl Iterator<Item = GResult<Self, Self::Triple<'_>>> + '_ {
+        self.graph.triples()
+    }
+
+    #[allow(refining_impl_trait)]
+    fn triples_matching<'s, S, P, O>(
+        &'s self,
+        sm: S,
+        pm: P,
+        om: O,
+    ) -> Box<dyn Iterator<Item = GResult<Self, Self::Triple<'s>>> + 's>
+    where
+        S: sophia_api::term::matcher::TermMatcher + 's,
+        P: sophia_api::term::matcher::TermMatcher + 's,
+        O: sophia_api::term::matcher::TermMatcher + 's,
+    {
+        self.graph.triples_matching(sm, pm, om)
+    }
+}
+
+impl<TI: TermIndex> MutableGraph for GenericBTreeGraph<TI> {
+    type MutationError = TI::Error;
+
+    fn insert<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> sophia_api::graph::MgResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        if !self.graph.insert(s.borrow_term(), p, o)? {
+            return Ok(false);
+        }
+        if let Some(iri) = s.iri() {
+            if !self.subjects.contains_key(iri.as_str()) {
+                let si = self.graph.terms.get_index(s.borrow_term()).unwrap();
+                self.subjects.insert(iri.as_str().into(), si);
+            }
+        }
+        Ok(true)
+    }
+
+    fn remove<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> sophia_api::graph::MgResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        if !self.graph.remove(s.borrow_term(), p, o)? {
+            return Ok(false);
+        }
+        if let Some(iri) = s.iri() {
+            let si = self.subjects[iri.as_str()];
+            if self.triples_with_subject_index(si).next().is_none() {
+                self.subjects.remove(iri.as_str());
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<TI: TermIndex + Default> CollectibleGraph for GenericBTreeGraph<TI> {
+    fn from_triple_source<TS: TripleSource>(
+        mut triples: TS,
+    ) -> sophia_api::source::StreamResult<Self, TS::Error, Self::Error> {
+        let mut g = Self::new();
+        triples.try_for_each_triple(|t| g.insert_triple(t).map(|_| ()))?;
+        Ok(g)
+    }
+}
+
+impl<TI: TermIndex> SetGraph for GenericBTreeGraph<TI> {}
+
+fn bounds<'a, R: RangeBounds<&'a str>>(range: &R) -> (Bound<&'a str>, Bound<&'a str>) {
+    (range.start_bound().cloned(), range.end_bound().cloned())
+}
+
+/// Default configuration of [`GenericBTreeGraph`].
+pub type BTreeGraph = GenericBTreeGraph<SimpleTermIndex<u32>>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::ns::rdf;
+    use sophia_api::term::{BnodeId, SimpleTerm};
+
+    sophia_api::test_graph_impl!(btree_graph, BTreeGraph);
+
+    const EX: &str = "http://example.org/";
+
+    fn subjects<'a>(
+        triples: impl Iterator<Item = GResult<BTreeGraph, [&'a SimpleTerm<'static>; 3]>>,
+    ) -> Vec<String> {
+        triples
+            .map(|t| t.unwrap()[0].iri().unwrap().as_str()[EX.len()..].to_string())
+            .collect()
+    }
+
+    fn make_graph() -> Result<BTreeGraph, Box<dyn std::error::Error>> {
+        let mut g = BTreeGraph::new();
+        for name in ["b", "ab", "a", "c", "b/x", "a"] {
+            let s = IriRef::new_unchecked(format!("{EX}{name}"));
+            g.insert(&s, rdf::type_, rdf::Bag)?;
+            g.insert(&s, rdf::value, name)?;
+        }
+        g.insert(rdf::nil, rdf::type_, rdf::List)?;
+        g.insert(BnodeId::new_unchecked("b"), rdf::type_, rdf::Bag)?;
+        Ok(g)
+    }
+
+    #[test]
+    fn prefix() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph()?;
+        assert_eq!(
+            subjects(g.triples_with_subject_prefix(EX)),
+            ["a", "a", "ab", "ab", "b", "b", "b/x", "b/x", "c", "c"]
+        );
+        assert_eq!(
+            subjects(g.triples_with_subject_prefix("http://example.org/a")),
+            ["a", "a", "ab", "ab"]
+        );
+        assert_eq!(
+            g.triples_with_subject_prefix("http://example.org/d")
+                .count(),
+            0
+        );
+        assert_eq!(g.triples_with_subject_prefix("").count(), 11);
+        Ok(())
+    }
+
+    #[test]
+    fn range() -> Result<(), Box<dyn std::error::Error>> {
+        let g = make_graph()?;
+        let b = format!("{EX}b");
+        let c = format!("{EX}c");
+        assert_eq!(
+            subjects(g.triples_with_subject_in(b.as_str()..c.as_str())),
+            ["b", "b", "b/x", "b/x"]
+        );
+        assert_eq!(
+            subjects(g.triples_with_subject_in(b.as_str()..=c.as_str())),
+            ["b", "b", "b/x", "b/x", "c", "c"]
+        );
+        assert_eq!(
+            subjects(g.triples_with_subject_in(..b.as_str())),
+            ["a", "a", "ab", "ab"]
+        );
+        assert_eq!(g.subjects_in(..).count(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn remove_and_compact() -> Result<(), Box<dyn std::error::Error>> {
+        let mut g = make_graph()?;
+        let a = IriRef::new_unchecked(format!("{EX}a"));
+        g.remove(&a, rdf::type_, rdf::Bag)?;
+        assert_eq!(g.subjects_in(..).count(), 6);
+        g.remove(&a, rdf::value, "a")?;
+        assert_eq!(g.subjects_in(..).count(), 5);
+        assert_eq!(subjects(g.triples_with_subject_prefix(EX)).len(), 8);
+        g.compact()?;
+        assert_eq!(subjects(g.triples_with_subject_prefix(EX)).len(), 8);
+        assert!(g.subjects_in(..).all(|s| !Term::eq(&s, &a)));
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+//! A read-only, column-oriented in-memory graph, optimized for full scans and aggregations.
+//!
+//! Unlike [`GenericLightGraph`] and [`GenericFastGraph`],
+//! which store their triples in B-trees,
+//! [`GenericColumnarGraph`] stores them as three parallel columns of term indices,
+//! sorted by predicate, subject and object.
+//! The predicate column is run-length encoded,
+//! which makes aggregations by predicate (see [`GenericColumnarGraph::count_by_predicate`])
+//! almost free.
+//!
+//! The columns are directly exposed
+//! (see [`subject_column`](GenericColumnarGraph::subject_column),
+//! [`predicate_runs`](GenericColumnarGraph::predicate_runs)
+//! and [`object_column`](GenericColumnarGraph::object_column)),
+//! together with the [term index](GenericColumnarGraph::term_index) acting as their dictionary,
+//! so that they can be exported without copy to columnar formats such as [Apache Arrow]
+//! (as dictionary-encoded and run-end-encoded arrays).
+//!
+//! # Example
+//! ```
+//! # use sophia_api::graph::{CollectibleGraph, Graph};
+//! # use sophia_api::ns::rdf;
+//! # use sophia_api::term::Term;
+//! # use sophia_inmem::graph::columnar::ColumnarGraph;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let triples = vec![
+//!     [rdf::Alt, rdf::type_, rdf::Property],
+//!     [rdf::Bag, rdf::type_, rdf::Property],
+//!     [rdf::Alt, rdf::value, rdf::nil],
+//! ];
+//! let g = ColumnarGraph::from_triple_source(triples.triples())?;
+//! assert_eq!(g.len(), 3);
+//! let counts: Vec<_> = g.count_by_predicate().collect();
+//! assert_eq!(counts.len(), 2);
+//! assert!(counts.iter().any(|(p, n)| rdf::type_ == *p && *n == 2));
+//! # Ok(()) }
+//! ```
+//!
+//! [Apache Arrow]: https://arrow.apache.org/
+use std::ops::Range;
+
+use super::*;
+
+/// A read-only graph storing its triples as parallel columns of term indices.
+///
+/// It does not implement [`MutableGraph`]:
+/// it is built at once from a triple source (see [`CollectibleGraph`]),
+/// duplicate triples being removed.
+#[derive(Clone, Debug, Default)]
+pub struct GenericColumnarGraph<TI: TermIndex> {
+    terms: TI,
+    /// Runs of identical predicates, as pairs (predicate, end of the run in the other columns)
+    predicates: Vec<(TI::Index, usize)>,
+    subjects: Vec<TI::Index>,
+    objects: Vec<TI::Index>,
+}
+
+impl<TI: TermIndex + Default> GenericColumnarGraph<TI> {
+    /// Construct an empty graph
+    pub fn new() -> Self {
+        Self {
+            terms: TI::default(),
+            predicates: vec![],
+            subjects: vec![],
+            objects: vec![],
+        }
+    }
+}
+
+impl<TI: TermIndex> GenericColumnarGraph<TI> {
+    /// Build a graph from a term index and triples of indices from that term index
+    /// (in any order, possibly with duplicates).
+    fn from_index_triples(terms: TI, mut triples: Vec<[TI::Index; 3]>) -> Self {
+        triples.sort_unstable_by_key(|&[s, p, o]| [p, s, o]);
+        triples.dedup();
+        let mut predicates: Vec<(TI::Index, usize)> = vec![];
+        for (i, t) in triples.iter().enumerate() {
+            match predicates.last_mut() {
+                Some((p, end)) if *p == t[1] => *end = i + 1,
+                _ => predicates.push((t[1], i + 1)),
+            }
+        }
+        Self {
+            terms,
+            predicates,
+            subjects: triples.iter().map(|t| t[0]).collect(),
+            objects: triples.iter().map(|t| t[2]).collect(),
+        }
+    }
+
+    /// The number of triples in this graph.
+    pub fn len(&self) -> usize {
+        self.subjects.len()
+    }
+
+    /// Whether this graph is empty.
+    pub fn is_empty(&self) -> bool {
+        self.subjects.is_empty()
+    }
+
+    /// The term index used as a dictionary by the columns of this graph.
+    pub fn term_index(&self) -> &TI {
+        &self.terms
+    }
+
+    /// The column of subjects, sorted by predicate, then subject, then object.
+    pub fn subject_column(&self) -> &[TI::Index] {
+        &self.subjects
+    }
+
+    /// The column of objects, sorted by predicate, then subject, then object.
+    pub fn object_column(&self) -> &[TI::Index] {
+        &self.objects
+    }
+
+    /// The run-length encoded column of predicates,
+    /// as pairs (predicate, end of the run in the other columns),
+    /// sorted by predicate.
+    pub fn predicate_runs(&self) -> &[(TI::Index, usize)] {
+        &self.predicates
+    }
+
+    /// Iter over the distinct predicates of this graph, with their number of triples.
+    pub fn count_by_predicate(
+        &self,
+    ) -> impl Iterator<Item = (<TI::Term as Term>::BorrowTerm<'_>, usize)> + '_ {
+        self.runs()
+            .map(move |(p, r)| (self.terms.get_term(p), r.len()))
+    }
+
+    /// Iter over the runs of the predicate column, with their range in the other columns.
+    fn runs(&self) -> impl Iterator<Item = (TI::Index, Range<usize>)> + '_ {
+        let mut start = 0;
+        self.predicates.iter().map(move |&(p, end)| {
+            let r = start..end;
+            start = end;
+            (p, r)
+        })
+    }
+
+    /// The range of predicate `p` in the other columns, if any.
+    fn run(&self, p: TI::Index) -> Option<Range<usize>> {
+        let k = self.predicates.partition_point(|(q, _)| *q < p);
+        let (q, end) = *self.predicates.get(k)?;
+        let start = if k == 0 { 0 } else { self.predicates[k - 1].1 };
+        (q == p).then_some(start..end)
+    }
+}
+
+impl<TI: TermIndex> Graph for GenericColumnarGraph<TI> {
+    type Triple<'x> = [<TI::Term as Term>::BorrowTerm<'x>; 3] where Self: 'x;
+    type Error = TI::Error;
+
+    fn triples(&self) -> impl Iterator<Item = GResult<Self, Self::Triple<'_>>> + '_ {
+        self.runs().flat_map(move |(p, r)| {
+            r.map(move |i| {
+                Ok([self.subjects[i], p, self.objects[i]].map(|i| self.terms.get_term(i)))
+            })
+        })
+    }
+
+    #[allow(refining_impl_trait)]
+    fn triples_matching<'s, S, P, O>(
+        &'s self,
+        sm: S,
+        pm: P,
+        om: O,
+    ) -> Box<dyn Iterator<Item = GResult<Self, Self::Triple<'s>>> + 's>
+    where
+        S: sophia_api::term::matcher::TermMatcher + 's,
+        P: sophia_api::term::matcher::TermMatcher + 's,
+        O: sophia_api::term::matcher::TermMatcher + 's,
+    {
+        let si = match sm.constant().map(|t| self.terms.get_index(t.borrow_term())) {
+            None => None,
+            Some(None) => return Box::new(empty()),
+            Some(Some(i)) => Some(i),
+        };
+        let runs: Vec<_> = match pm.constant().map(|t| self.terms.get_index(t.borrow_term())) {
+            None => self
+                .runs()
+                .filter(|(p, _)| pm.matches(&self.terms.get_term(*p)))
+                .collect(),
+            Some(None) => return Box::new(empty()),
+            Some(Some(pi)) => self.run(pi).map(|r| (pi, r)).into_iter().collect(),
+        };
+        Box::new(
+            runs.into_iter()
+                .flat_map(move |(p, r)| {
+                    let r = match si {
+                        None => r,
+                        Some(si) => {
+                            // subjects are sorted inside each run of predicates
+                            let col = &self.subjects[r.clone()];
+                            r.start + col.partition_point(|s| *s < si)
+                                ..r.start + col.partition_point(|s| *s <= si)
+                        }
+                    };
+                    r.map(move |i| (p, i))
+                })
+                .filter_map(move |(p, i)| {
+                    let [s, o] =
+                        [self.subjects[i], self.objects[i]].map(|i| self.terms.get_term(i));
+                    (sm.matches(&s) && om.matches(&o)).then(|| Ok([s, self.terms.get_term(p), o]))
+                }),
+        )
+    }
+}
+
+impl<TI: TermIndex + Default> CollectibleGraph for GenericColumnarGraph<TI> {
+    fn from_triple_source<TS: TripleSource>(
+        mut triples: TS,
+    ) -> sophia_api::source::StreamResult<Self, TS::Error, Self::Error> {
+        let mut terms = TI::default();
+        let mut spo = vec![];
+        triples.try_for_each_triple(|t| -> Result<(), TI::Error> {
+            spo.push([
+                terms.ensure_index(t.s())?,
+                terms.ensure_index(t.p())?,
+                terms.ensure_index(t.o())?,
+            ]);
+            Ok(())
+        })?;
+        Ok(Self::from_index_triples(terms, spo))
+    }
+}
+
+impl<TI: TermIndex> SetGraph for GenericColumnarGraph<TI> {}
+
+/// A read-only graph storing its triples as parallel columns of term indices.
+///
+/// Default configuration of [`GenericColumnarGraph`].
+pub type ColumnarGraph = GenericColumnarGraph<SimpleTermIndex<u32>>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::ns::rdf;
+    use sophia_api::term::matcher::Any;
+
+    sophia_api::test_immutable_graph_impl!(columnar_graph, ColumnarGraph);
+
+    #[test]
+    fn columns() -> Result<(), Box<dyn std::error::Error>> {
+        let triples = vec![
+            [rdf::Seq, rdf::value, rdf::nil],
+            [rdf::Alt, rdf::type_, rdf::Property],
+            [rdf::Bag, rdf::type_, rdf::Property],
+            [rdf::Alt, rdf::type_, rdf::Property],
+            [rdf::Alt, rdf::value, rdf::nil],
+        ];
+        let g = ColumnarGraph::from_triple_source(triples.triples())?;
+        assert_eq!(g.len(), 4);
+        let runs = g.predicate_runs();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[1].1, 4);
+        assert_eq!(g.subject_column().len(), 4);
+        assert_eq!(g.object_column().len(), 4);
+
+        let type_run = if rdf::type_ == g.term_index().get_term(runs[0].0) {
+            0..runs[0].1
+        } else {
+            runs[0].1..4
+        };
+        assert_eq!(type_run.len(), 2);
+        let subjects = &g.subject_column()[type_run];
+        assert!(subjects[0] < subjects[1]);
+
+        assert_eq!(g.triples_matching([rdf::Alt], Any, Any).count(), 2);
+        assert_eq!(g.triples_matching(Any, [rdf::value], Any).count(), 2);
+        assert_eq!(g.triples_matching([rdf::Alt], [rdf::value], Any).count(), 1);
+        assert_eq!(g.triples_matching(Any, Any, [rdf::Property]).count(), 2);
+        assert_eq!(g.triples_matching([rdf::List], Any, Any).count(), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,575 @@
+//! An in-memory graph whose triple indexes are configurable.
+//!
+//! [`GenericLightGraph`] maintains a single index (SPO),
+//! and [`GenericFastGraph`] three of them (SPO, POS, OSP).
+//! [`GenericMultiIndexGraph`] lets users choose which [permutations](Permutation)
+//! of the triples are indexed, with an [`IndexLayout`]:
+//! write-heavy workloads may prefer a single index,
+//! while analytical workloads may want all six.
+//!
+//! Each index is a B-tree of term indices, ordered according to its permutation.
+//! [`triples_matching`](Graph::triples_matching) uses the index whose order
+//! has the longest prefix of constant terms in the pattern,
+//! and falls back to a scan of the primary index (the first of the layout).
+//!
+//! # Example
+//! ```
+//! # use sophia_api::graph::{Graph, MutableGraph};
+//! # use sophia_api::ns::rdf;
+//! # use sophia_api::term::matcher::Any;
+//! # use sophia_inmem::graph::multi_index::{IndexLayout, MultiIndexGraph, Permutation};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // a graph optimized for retrieving the subjects and objects of a given predicate
+//! let layout = IndexLayout::new(Permutation::Pso);
+//! let mut g = MultiIndexGraph::new().with_layout(layout);
+//! g.insert(rdf::Alt, rdf::type_, rdf::Bag)?;
+//! g.insert(rdf::Seq, rdf::value, rdf::Bag)?;
+//! assert_eq!(g.triples_matching(Any, [rdf::type_], Any).count(), 1);
+//! // the layout can be changed at any time, rebuilding the indexes
+//! let g = g.with_layout(IndexLayout::full());
+//! assert_eq!(g.layout().permutations().len(), 6);
+//! # Ok(()) }
+//! ```
+use std::collections::BTreeSet;
+
+use super::symbol::{SymbolGraph, TermSymbol};
+use super::*;
+use sophia_api::graph::GTerm;
+
+/// A permutation of the positions of a triple (subject, predicate, object),
+/// defining the order of the keys of an index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Permutation {
+    /// Subject, predicate, object
+    Spo,
+    /// Subject, object, predicate
+    Sop,
+    /// Predicate, subject, object
+    Pso,
+    /// Predicate, object, subject
+    Pos,
+    /// Object, subject, predicate
+    Osp,
+    /// Object, predicate, subject
+    Ops,
+}
+
+impl Permutation {
+    /// All the permutations.
+    pub const ALL: [Permutation; 6] = [
+        Permutation::Spo,
+        Permutation::Sop,
+        Permutation::Pso,
+        Permutation::Pos,
+        Permutation::Osp,
+        Permutation::Ops,
+    ];
+
+    /// The positions in a triple (0 for subject, 1 for predicate, 2 for object)
+    /// in the order defined by this permutation.
+    pub fn order(self) -> [usize; 3] {
+        match self {
+            Permutation::Spo => [0, 1, 2],
+            Permutation::Sop => [0, 2, 1],
+            Permutation::Pso => [1, 0, 2],
+            Permutation::Pos => [1, 2, 0],
+            Permutation::Osp => [2, 0, 1],
+            Permutation::Ops => [2, 1, 0],
+        }
+    }
+
+    /// Reorder `spo` according to this permutation.
+    fn key<I: Copy>(self, spo: [I; 3]) -> [I; 3] {
+        self.order().map(|pos| spo[pos])
+    }
+
+    /// Reorder `key` (ordered according to this permutation) in the subject-predicate-object order.
+    fn spo<I: Copy>(self, key: [I; 3]) -> [I; 3] {
+        let mut spo = key;
+        for (k, pos) in self.order().into_iter().enumerate() {
+            spo[pos] = key[k];
+        }
+        spo
+    }
+
+    /// The number of leading positions of this permutation that are `bound`.
+    fn bound_prefix(self, bound: [bool; 3]) -> usize {
+        self.order().iter().take_while(|pos| bound[**pos]).count()
+    }
+}
+
+/// The set of [permutations](Permutation) indexed by a [`GenericMultiIndexGraph`].
+///
+/// The first permutation is the *primary* one,
+/// whose index is used to iterate over all triples;
+/// the others are only used to serve [`triples_matching`](Graph::triples_matching).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IndexLayout {
+    permutations: Vec<Permutation>,
+}
+
+impl IndexLayout {
+    /// Build a layout with a single index, ordered according to `primary`.
+    pub fn new(primary: Permutation) -> Self {
+        IndexLayout {
+            permutations: vec![primary],
+        }
+    }
+
+    /// Transform a layout to also maintain an index ordered according to `permutation`
+    /// (if not already present).
+    pub fn with(mut self, permutation: Permutation) -> Self {
+        if !self.contains(permutation) {
+            self.permutations.push(permutation);
+        }
+        self
+    }
+
+    /// A single SPO index, like [`GenericLightGraph`].
+    pub fn light() -> Self {
+        Self::new(Permutation::Spo)
+    }
+
+    /// The SPO, POS and OSP indexes, like [`GenericFastGraph`].
+    /// This is the default layout.
+    pub fn fast() -> Self {
+        Self::new(Permutation::Spo)
+            .with(Permutation::Pos)
+            .with(Permutation::Osp)
+    }
+
+    /// All six possible indexes.
+    pub fn full() -> Self {
+        Permutation::ALL
+            .into_iter()
+            .fold(Self::light(), |layout, p| layout.with(p))
+    }
+
+    /// The permutations of this layout, the primary one being first.
+    pub fn permutations(&self) -> &[Permutation] {
+        &self.permutations
+    }
+
+    /// The primary permutation of this layout.
+    pub fn primary(&self) -> Permutation {
+        self.permutations[0]
+    }
+
+    /// Whether this layout maintains an index ordered according to `permutation`.
+    pub fn contains(&self, permutation: Permutation) -> bool {
+        self.permutations.contains(&permutation)
+    }
+}
+
+impl Default for IndexLayout {
+    fn default() -> Self {
+        Self::fast()
+    }
+}
+
+/// A graph maintaining a configurable set of triple indexes.
+///
+/// Every insertion or removal updates all the indexes of its [layout](Self::layout),
+/// so each additional index trades write speed and memory for faster lookups.
+#[derive(Clone, Debug)]
+pub struct GenericMultiIndexGraph<TI: TermIndex> {
+    pub(super) terms: TI,
+    layout: IndexLayout,
+    /// One index per permutation of the layout, in the same order
+    indexes: Vec<BTreeSet<[TI::Index; 3]>>,
+}
+
+impl<TI: TermIndex + Default> GenericMultiIndexGraph<TI> {
+    /// Construct an empty graph, with the [default layout](IndexLayout::fast).
+    pub fn new() -> Self {
+        let layout = IndexLayout::default();
+        Self {
+            terms: TI::default(),
+            indexes: vec![BTreeSet::new(); layout.permutations().len()],
+            layout,
+        }
+    }
+
+    /// Rebuild the term index of this graph,
+    /// dropping the terms that are not used anymore by any triple
+    /// (terms are never dropped when triples are [removed](MutableGraph::remove)).
+    ///
+    /// Return the number of terms in the term index after compaction.
+    pub fn compact(&mut self) -> Result<usize, TI::Error> {
+        let mut compactor = Compactor::new(&self.terms);
+        let spo = self
+            .spo_iter()
+            .map(|t| compactor.remap_triple(&t))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (terms, len) = compactor.finish();
+        self.terms = terms;
+        self.rebuild(spo);
+        Ok(len)
+    }
+}
+
+impl<TI: TermIndex + Default> Default for GenericMultiIndexGraph<TI> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<TI: TermIndex> GenericMultiIndexGraph<TI> {
+    /// Transform this graph to maintain the indexes of `layout`,
+    /// rebuilding them if necessary.
+    pub fn with_layout(mut self, layout: IndexLayout) -> Self {
+        if layout != self.layout {
+            let spo: Vec<_> = self.spo_iter().collect();
+            self.layout = layout;
+            self.rebuild(spo);
+        }
+        self
+    }
+
+    /// The layout of the indexes of this graph.
+    pub fn layout(&self) -> &IndexLayout {
+        &self.layout
+    }
+
+    /// The number of triples in this graph.
+    pub fn len(&self) -> usize {
+        self.indexes[0].len()
+    }
+
+    /// Whether this graph is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether this graph contains the triple with the given term indices.
+    pub(super) fn contains_index(&self, spo: [TI::Index; 3]) -> bool {
+        self.indexes[0].contains(&self.layout.primary().key(spo))
+    }
+
+    /// Iterate over all the triples of this graph, in the order of the primary index.
+    fn spo_iter(&self) -> impl Iterator<Item = [TI::Index; 3]> + '_ {
+        let primary = self.layout.primary();
+        self.indexes[0].iter().map(move |key| primary.spo(*key))
+    }
+
+    /// Replace all indexes with indexes of the `spo` triples, according to the current layout.
+    fn rebuild(&mut self, spo: Vec<[TI::Index; 3]>) {
+        self.indexes = self
+            .layout
+            .permutations()
+            .iter()
+            .map(|p| spo.iter().map(|t| p.key(*t)).collect())
+            .collect();
+    }
+
+    /// The position in the layout of the index serving best a pattern with the given `bound` positions,
+    /// and the number of leading bound positions in that index.
+    fn best_index(&self, bound: [bool; 3]) -> (usize, usize) {
+        let mut best = (0, self.layout.primary().bound_prefix(bound));
+        for (i, p) in self.layout.permutations().iter().enumerate().skip(1) {
+            let prefix = p.bound_prefix(bound);
+            if prefix > best.1 {
+                best = (i, prefix);
+            }
+        }
+        best
+    }
+
+    /// Iterate over the triples (in the subject-predicate-object order)
+    /// whose bound positions are equal to the given indices,
+    /// using the index in position `i` of the layout,
+    /// which must have `prefix` leading bound positions.
+    fn scan(
+        &self,
+        spo: [Option<TI::Index>; 3],
+        (i, prefix): (usize, usize),
+    ) -> impl Iterator<Item = [TI::Index; 3]> + '_ {
+        let permutation = self.layout.permutations()[i];
+        let key = permutation.key(spo);
+        let mut min = [TI::Index::ZERO; 3];
+        let mut max = [TI::Index::MAX; 3];
+        for (k, bound) in key.iter().take(prefix).enumerate() {
+            min[k] = bound.unwrap();
+            max[k] = bound.unwrap();
+        }
+        self.indexes[i]
+            .range(min..=max)
+            .map(move |key| permutation.spo(*key))
+            .filter(move |t| (0..3).all(|pos| spo[pos].is_none() || spo[pos] == Some(t[pos])))
+    }
+}
+
+impl<TI: TermIndex> Graph for GenericMultiIndexGraph<TI> {
+    type Triple<'x> = [<TI::Term as Term>::BorrowTerm<'x>; 3] where Self: 'x;
+    type Error = TI::Error;
+
+    fn triples(&self) -> impl Iterator<Item = GResult<Self, Self::Triple<'_>>> + '_ {
+        self.spo_iter()
+            .map(|ti| Ok(ti.map(|i| self.terms.get_term(i))))
+    }
+
+    #[allow(refining_impl_trait)]
+    fn triples_matching<'s, S, P, O>(
+        &'s self,
+        sm: S,
+        pm: P,
+        om: O,
+    ) -> Box<dyn Iterator<Item = GResult<Self, Self::Triple<'s>>> + 's>
+    where
+        S: sophia_api::term::matcher::TermMatcher + 's,
+        P: sophia_api::term::matcher::TermMatcher + 's,
+        O: sophia_api::term::matcher::TermMatcher + 's,
+    {
+        let si = match sm.constant().map(|t| self.terms.get_index(t.borrow_term())) {
+            None => None,
+            Some(None) => return Box::new(empty()),
+            Some(Some(i)) => Some(i),
+        };
+        let pi = match pm.constant().map(|t| self.terms.get_index(t.borrow_term())) {
+            None => None,
+            Some(None) => return Box::new(empty()),
+            Some(Some(i)) => Some(i),
+        };
+        let oi = match om.constant().map(|t| self.terms.get_index(t.borrow_term())) {
+            None => None,
+            Some(None) => return Box::new(empty()),
+            Some(Some(i)) => Some(i),
+        };
+        let spo = [si, pi, oi];
+        let best = self.best_index(spo.map(|i| i.is_some()));
+        Box::new(
+            self.scan(spo, best)
+                .map(|t| t.map(|i| self.terms.get_term(i)))
+                .filter(move |[s, p, o]| {
+                    (si.is_some() || sm.matches(s))
+                        && (pi.is_some() || pm.matches(p))
+                        && (oi.is_some() || om.matches(o))
+                })
+                .map(Ok),
+        )
+    }
+}
+
+impl<TI: TermIndex> MutableGraph for GenericMultiIndexGraph<TI> {
+    type MutationError = TI::Error;
+
+    fn insert<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> sophia_api::graph::MgResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let is = self.terms.ensure_index(s)?;
+        let ip = self.terms.ensure_index(p)?;
+        let io = self.terms.ensure_index(o)?;
+        let spo = [is, ip, io];
+        let permutations = self.layout.permutations();
+        if self.indexes[0].insert(permutations[0].key(spo)) {
+            for (index, p) in self.indexes.iter_mut().zip(permutations).skip(1) {
+                let i = index.insert(p.key(spo));
+                debug_assert!(i);
+            }
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn remove<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> sophia_api::graph::MgResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let Some(is) = self.terms.get_index(s) else {
+            return Ok(false);
+        };
+        let Some(ip) = self.terms.get_index(p) else {
+            return Ok(false);
+        };
+        let Some(io) = self.terms.get_index(o) else {
+            return Ok(false);
+        };
+        let spo = [is, ip, io];
+        let permutations = self.layout.permutations();
+        if self.indexes[0].remove(&permutations[0].key(spo)) {
+            for (index, p) in self.indexes.iter_mut().zip(permutations).skip(1) {
+                let i = index.remove(&p.key(spo));
+                debug_assert!(i);
+            }
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+impl<TI: TermIndex + Default> CollectibleGraph for GenericMultiIndexGraph<TI> {
+    fn from_triple_source<TS: TripleSource>(
+        mut triples: TS,
+    ) -> sophia_api::source::StreamResult<Self, TS::Error, Self::Error> {
+        let mut g = Self::new();
+        triples.try_for_each_triple(|t| g.insert_triple(t).map(|_| ()))?;
+        Ok(g)
+    }
+}
+
+impl<TI: TermIndex> SetGraph for GenericMultiIndexGraph<TI> {}
+
+impl<TI: TermIndex> SymbolGraph for GenericMultiIndexGraph<TI> {
+    type Index = TI::Index;
+
+    fn symbol<T: Term>(&self, t: T) -> Option<TermSymbol<Self::Index>> {
+        self.terms.get_index(t).map(TermSymbol)
+    }
+
+    fn resolve(&self, symbol: TermSymbol<Self::Index>) -> GTerm<'_, Self> {
+        self.terms.get_term(symbol.0)
+    }
+
+    fn symbol_triples(&self) -> impl Iterator<Item = [TermSymbol<Self::Index>; 3]> + '_ {
+        self.spo_iter().map(|t| t.map(TermSymbol))
+    }
+
+    fn symbol_triples_with_subject(
+        &self,
+        s: TermSymbol<Self::Index>,
+    ) -> impl Iterator<Item = [TermSymbol<Self::Index>; 3]> + '_ {
+        let spo = [Some(s.0), None, None];
+        self.scan(spo, self.best_index([true, false, false]))
+            .map(|t| t.map(TermSymbol))
+    }
+
+    fn symbol_triples_with_object(
+        &self,
+        o: TermSymbol<Self::Index>,
+    ) -> impl Iterator<Item = [TermSymbol<Self::Index>; 3]> + '_ {
+        let spo = [None, None, Some(o.0)];
+        self.scan(spo, self.best_index([false, false, true]))
+            .map(|t| t.map(TermSymbol))
+    }
+}
+
+/// Default configuration of [`GenericMultiIndexGraph`].
+pub type MultiIndexGraph = GenericMultiIndexGraph<SimpleTermIndex<u32>>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::ns::rdf;
+    use sophia_api::source::StreamResult;
+    use sophia_api::term::matcher::Any;
+
+    fn collect_with<TS: TripleSource>(
+        mut triples: TS,
+        layout: IndexLayout,
+    ) -> StreamResult<MultiIndexGraph, TS::Error, TermIndexFullError> {
+        let mut g = MultiIndexGraph::new().with_layout(layout);
+        triples.try_for_each_triple(|t| g.insert_triple(t).map(|_| ()))?;
+        Ok(g)
+    }
+
+    fn collect_light<TS: TripleSource>(
+        triples: TS,
+    ) -> StreamResult<MultiIndexGraph, TS::Error, TermIndexFullError> {
+        collect_with(triples, IndexLayout::light())
+    }
+
+    fn collect_ops<TS: TripleSource>(
+        triples: TS,
+    ) -> StreamResult<MultiIndexGraph, TS::Error, TermIndexFullError> {
+        collect_with(triples, IndexLayout::new(Permutation::Ops))
+    }
+
+    fn collect_full<TS: TripleSource>(
+        triples: TS,
+    ) -> StreamResult<MultiIndexGraph, TS::Error, TermIndexFullError> {
+        collect_with(triples, IndexLayout::full())
+    }
+
+    sophia_api::test_graph_impl!(fast_layout, MultiIndexGraph);
+    sophia_api::test_graph_impl!(light_layout, MultiIndexGraph, true, true, collect_light);
+    sophia_api::test_graph_impl!(ops_layout, MultiIndexGraph, true, true, collect_ops);
+    sophia_api::test_graph_impl!(full_layout, MultiIndexGraph, true, true, collect_full);
+
+    #[test]
+    fn permutations() {
+        for p in Permutation::ALL {
+            let key = p.key(['s', 'p', 'o']);
+            assert_eq!(p.spo(key), ['s', 'p', 'o'], "{p:?}");
+            assert_eq!(format!("{p:?}").to_lowercase(), String::from_iter(key));
+        }
+        assert_eq!(Permutation::Pos.bound_prefix([true, true, false]), 1);
+        assert_eq!(Permutation::Pos.bound_prefix([false, true, true]), 2);
+        assert_eq!(Permutation::Pos.bound_prefix([true, false, true]), 0);
+    }
+
+    #[test]
+    fn layouts() {
+        assert_eq!(IndexLayout::light().permutations(), [Permutation::Spo]);
+        assert_eq!(
+            IndexLayout::default().permutations(),
+            [Permutation::Spo, Permutation::Pos, Permutation::Osp]
+        );
+        assert_eq!(IndexLayout::full().permutations(), Permutation::ALL);
+        let layout = IndexLayout::new(Permutation::Pso)
+            .with(Permutation::Osp)
+            .with(Permutation::Pso);
+        assert_eq!(layout.primary(), Permutation::Pso);
+        assert_eq!(layout.permutations(), [Permutation::Pso, Permutation::Osp]);
+        assert!(!layout.contains(Permutation::Spo));
+    }
+
+    #[test]
+    fn best_index() {
+        let g = MultiIndexGraph::new()
+            .with_layout(IndexLayout::new(Permutation::Pso).with(Permutation::Osp));
+        assert_eq!(g.best_index([false, false, false]), (0, 0));
+        assert_eq!(g.best_index([false, true, false]), (0, 1));
+        assert_eq!(g.best_index([true, true, false]), (0, 2));
+        assert_eq!(g.best_index([false, false, true]), (1, 1));
+        assert_eq!(g.best_index([true, false, true]), (1, 2));
+        assert_eq!(g.best_index([true, false, false]), (0, 0));
+    }
+
+    #[test]
+    fn change_layout() -> Result<(), Box<dyn std::error::Error>> {
+        let mut g = MultiIndexGraph::new();
+        g.insert(rdf::Alt, rdf::type_, rdf::Bag)?;
+        g.insert(rdf::Seq, rdf::type_, rdf::Bag)?;
+        g.insert(rdf::Bag, rdf::value, rdf::Alt)?;
+        for layout in [
+            IndexLayout::light(),
+            IndexLayout::new(Permutation::Ops).with(Permutation::Sop),
+            IndexLayout::full(),
+        ] {
+            g = g.with_layout(layout.clone());
+            assert_eq!(g.layout(), &layout);
+            assert_eq!(g.len(), 3);
+            assert_eq!(g.triples_matching(Any, [rdf::type_], Any).count(), 2);
+            assert_eq!(g.triples_matching(Any, Any, [rdf::Alt]).count(), 1);
+            assert!(g.contains(rdf::Bag, rdf::value, rdf::Alt)?);
+        }
+        g.remove(rdf::Bag, rdf::value, rdf::Alt)?;
+        assert_eq!(g.compact()?, 4);
+        assert_eq!(g.len(), 2);
+        assert_eq!(g.layout(), &IndexLayout::full());
+        assert_eq!(g.triples_matching(Any, Any, [rdf::Bag]).count(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn symbols() -> Result<(), Box<dyn std::error::Error>> {
+        let mut g = MultiIndexGraph::new().with_layout(IndexLayout::new(Permutation::Pos));
+        g.insert(rdf::Alt, rdf::type_, rdf::Bag)?;
+        g.insert(rdf::Seq, rdf::type_, rdf::Bag)?;
+        g.insert(rdf::Bag, rdf::value, rdf::Alt)?;
+        let bag = g.symbol(rdf::Bag).unwrap();
+        assert_eq!(g.symbol_triples_with_subject(bag).count(), 1);
+        assert_eq!(g.symbol_triples_with_object(bag).count(), 2);
+        assert!(g
+            .symbol_triples_with_object(bag)
+            .all(|[_, _, o]| Term::eq(&g.resolve(o), rdf::Bag)));
+        Ok(())
+    }
+}
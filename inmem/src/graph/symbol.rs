@@ -0,0 +1,206 @@
+//! Stable handles on the terms of the in-memory graphs.
+//!
+//! The in-memory graphs store their triples as arrays of term indices.
+//! A [`TermSymbol`] exposes such an index as an opaque, `Copy`, handle,
+//! which can be [resolved](SymbolGraph::resolve) back to the term.
+//! Algorithms that repeatedly revisit the same nodes (traversals, fixpoints...)
+//! can hold symbols instead of cloning terms,
+//! and use them to iterate over triples without looking up terms in the term index.
+//!
+//! A symbol is only meaningful for the graph that produced it.
+//! It remains valid as long as the graph is alive, even when triples are inserted or removed,
+//! but it is invalidated when the graph is [compacted](GenericLightGraph::compact).
+//!
+//! # Example
+//! ```
+//! # use sophia_api::graph::MutableGraph;
+//! # use sophia_api::ns::rdfs;
+//! # use sophia_api::term::{IriRef, Term};
+//! # use sophia_inmem::graph::FastGraph;
+//! # use sophia_inmem::graph::symbol::SymbolGraph;
+//! # use std::collections::BTreeSet;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let [a, b, c] = ["tag:a", "tag:b", "tag:c"].map(IriRef::new_unchecked);
+//! let mut g = FastGraph::new();
+//! g.insert(c, rdfs::subClassOf, b)?;
+//! g.insert(b, rdfs::subClassOf, a)?;
+//!
+//! // compute all the superclasses of tag:c
+//! let sub_class_of = g.symbol(rdfs::subClassOf).unwrap();
+//! let mut to_visit = vec![g.symbol(c).unwrap()];
+//! let mut visited = BTreeSet::new();
+//! while let Some(s) = to_visit.pop() {
+//!     for [_, p, o] in g.symbol_triples_with_subject(s) {
+//!         if p == sub_class_of && visited.insert(o) {
+//!             to_visit.push(o);
+//!         }
+//!     }
+//! }
+//! let superclasses: BTreeSet<_> = visited
+//!     .into_iter()
+//!     .map(|sym| g.resolve(sym).iri().unwrap().as_str().to_string())
+//!     .collect();
+//! assert_eq!(superclasses, BTreeSet::from(["tag:a".to_string(), "tag:b".to_string()]));
+//! # Ok(()) }
+//! ```
+use super::*;
+use sophia_api::graph::GTerm;
+
+/// An opaque handle on a term of a [`SymbolGraph`].
+///
+/// Two symbols from the same graph are equal if and only if they designate the same term,
+/// so they can be compared and hashed in place of the terms,
+/// until the graph is compacted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TermSymbol<I: Index>(pub(crate) I);
+
+/// A [`Graph`] whose terms can be designated by [`TermSymbol`]s.
+///
+/// The `symbol_*` methods iterate over triples as arrays of symbols,
+/// without looking up or cloning any term.
+pub trait SymbolGraph: Graph {
+    /// The type of index wrapped by the symbols of this graph
+    type Index: Index;
+
+    /// Get the symbol of term `t`, if this graph contains it.
+    ///
+    /// NB: a term may have a symbol while not being used in any triple,
+    /// as terms are only dropped from a graph when it is compacted.
+    fn symbol<T: Term>(&self, t: T) -> Option<TermSymbol<Self::Index>>;
+
+    /// Get the term designated by `symbol`.
+    ///
+    /// # Precondition
+    /// `symbol` must have been produced by this graph, and not been invalidated,
+    /// otherwise this method may panic or return an arbitrary term.
+    fn resolve(&self, symbol: TermSymbol<Self::Index>) -> GTerm<'_, Self>;
+
+    /// Iterate over all the triples of this graph, as arrays of symbols.
+    fn symbol_triples(&self) -> impl Iterator<Item = [TermSymbol<Self::Index>; 3]> + '_;
+
+    /// Iterate over the triples of this graph having `s` as their subject, as arrays of symbols.
+    fn symbol_triples_with_subject(
+        &self,
+        s: TermSymbol<Self::Index>,
+    ) -> impl Iterator<Item = [TermSymbol<Self::Index>; 3]> + '_ {
+        self.symbol_triples().filter(move |t| t[0] == s)
+    }
+
+    /// Iterate over the triples of this graph having `o` as their object, as arrays of symbols.
+    fn symbol_triples_with_object(
+        &self,
+        o: TermSymbol<Self::Index>,
+    ) -> impl Iterator<Item = [TermSymbol<Self::Index>; 3]> + '_ {
+        self.symbol_triples().filter(move |t| t[2] == o)
+    }
+}
+
+fn symbols<I: Index>(t: &[I; 3]) -> [TermSymbol<I>; 3] {
+    t.map(TermSymbol)
+}
+
+impl<TI: TermIndex> SymbolGraph for GenericLightGraph<TI> {
+    type Index = TI::Index;
+
+    fn symbol<T: Term>(&self, t: T) -> Option<TermSymbol<Self::Index>> {
+        self.terms.get_index(t).map(TermSymbol)
+    }
+
+    fn resolve(&self, symbol: TermSymbol<Self::Index>) -> GTerm<'_, Self> {
+        self.terms.get_term(symbol.0)
+    }
+
+    fn symbol_triples(&self) -> impl Iterator<Item = [TermSymbol<Self::Index>; 3]> + '_ {
+        self.triples.iter().map(symbols)
+    }
+
+    fn symbol_triples_with_subject(
+        &self,
+        s: TermSymbol<Self::Index>,
+    ) -> impl Iterator<Item = [TermSymbol<Self::Index>; 3]> + '_ {
+        let (z, m) = (TI::Index::ZERO, TI::Index::MAX);
+        self.triples.range([s.0, z, z]..=[s.0, m, m]).map(symbols)
+    }
+}
+
+impl<TI: TermIndex> SymbolGraph for GenericFastGraph<TI> {
+    type Index = TI::Index;
+
+    fn symbol<T: Term>(&self, t: T) -> Option<TermSymbol<Self::Index>> {
+        self.terms.get_index(t).map(TermSymbol)
+    }
+
+    fn resolve(&self, symbol: TermSymbol<Self::Index>) -> GTerm<'_, Self> {
+        self.terms.get_term(symbol.0)
+    }
+
+    fn symbol_triples(&self) -> impl Iterator<Item = [TermSymbol<Self::Index>; 3]> + '_ {
+        self.spo.iter().map(symbols)
+    }
+
+    fn symbol_triples_with_subject(
+        &self,
+        s: TermSymbol<Self::Index>,
+    ) -> impl Iterator<Item = [TermSymbol<Self::Index>; 3]> + '_ {
+        let (z, m) = (TI::Index::ZERO, TI::Index::MAX);
+        self.spo.range([s.0, z, z]..=[s.0, m, m]).map(symbols)
+    }
+
+    fn symbol_triples_with_object(
+        &self,
+        o: TermSymbol<Self::Index>,
+    ) -> impl Iterator<Item = [TermSymbol<Self::Index>; 3]> + '_ {
+        let (z, m) = (TI::Index::ZERO, TI::Index::MAX);
+        self.osp
+            .range([o.0, z, z]..=[o.0, m, m])
+            .map(|&[o, s, p]| symbols(&[s, p, o]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::ns::rdf;
+
+    fn check<G: SymbolGraph + MutableGraph + Default>() -> Result<(), Box<dyn std::error::Error>> {
+        let mut g = G::default();
+        g.insert(rdf::Alt, rdf::type_, rdf::Bag)?;
+        g.insert(rdf::Seq, rdf::type_, rdf::Bag)?;
+        g.insert(rdf::Bag, rdf::value, rdf::Alt)?;
+
+        let alt = g.symbol(rdf::Alt).unwrap();
+        let bag = g.symbol(rdf::Bag).unwrap();
+        assert_eq!(g.symbol(rdf::nil), None);
+        assert!(Term::eq(&g.resolve(alt), rdf::Alt));
+        assert!(Term::eq(&g.resolve(bag), rdf::Bag));
+        assert_eq!(g.symbol_triples().count(), 3);
+        for t in g.symbol_triples() {
+            let [s, p, o] = t.map(|sym| g.resolve(sym));
+            assert!(g.contains(s, p, o)?);
+        }
+        let from_alt: Vec<_> = g.symbol_triples_with_subject(alt).collect();
+        assert_eq!(from_alt.len(), 1);
+        assert_eq!(from_alt[0][2], bag);
+        assert_eq!(g.symbol_triples_with_object(bag).count(), 2);
+        assert!(g.symbol_triples_with_object(bag).all(|t| t[2] == bag));
+        assert_eq!(g.symbol_triples_with_subject(bag).count(), 1);
+
+        // symbols survive insertions and removals
+        g.insert(rdf::nil, rdf::type_, rdf::List)?;
+        g.remove(rdf::Alt, rdf::type_, rdf::Bag)?;
+        assert_eq!(g.symbol(rdf::Alt), Some(alt));
+        assert!(Term::eq(&g.resolve(bag), rdf::Bag));
+        assert_eq!(g.symbol_triples_with_subject(alt).count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn light_graph() -> Result<(), Box<dyn std::error::Error>> {
+        check::<LightGraph>()
+    }
+
+    #[test]
+    fn fast_graph() -> Result<(), Box<dyn std::error::Error>> {
+        check::<FastGraph>()
+    }
+}
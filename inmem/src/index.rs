@@ -2,7 +2,7 @@
 use sophia_api::term::{FromTerm, GraphName, SimpleTerm, Term};
 
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
 
 /// Abstraction of the short numeric indices representing [terms](Term) in a [`TermIndex`].
@@ -116,10 +116,16 @@ pub trait GraphNameIndex: TermIndex {
 //
 
 /// A generic implementation of [`TermIndex`].
+///
+/// It can optionally maintain a secondary lookup of IRIs
+/// (see [`enable_iri_lookup`](SimpleTermIndex::enable_iri_lookup)),
+/// for approximate searches such as the ones needed by interactive tools.
 #[derive(Clone, Debug, Default)]
 pub struct SimpleTermIndex<I: Index> {
     t2i: HashMap<SimpleTerm<'static>, I>,
     i2t: Vec<SimpleTerm<'static>>,
+    /// Lower-cased IRIs, mapped to the indices of the corresponding IRIs
+    iri_lookup: Option<BTreeMap<String, Vec<I>>>,
 }
 
 impl<I: Index> SimpleTermIndex<I> {
@@ -128,6 +134,86 @@ impl<I: Index> SimpleTermIndex<I> {
         SimpleTermIndex {
             t2i: HashMap::new(),
             i2t: vec![],
+            iri_lookup: None,
+        }
+    }
+
+    /// Build and maintain a secondary lookup of the IRIs in this index,
+    /// speeding up [`find_iris_ignore_case`](SimpleTermIndex::find_iris_ignore_case)
+    /// and [`find_iris_by_prefix`](SimpleTermIndex::find_iris_by_prefix)
+    /// (which otherwise scan all the terms).
+    pub fn enable_iri_lookup(&mut self) {
+        if self.iri_lookup.is_some() {
+            return;
+        }
+        let mut lookup = BTreeMap::<String, Vec<I>>::new();
+        for (i, t) in self.i2t.iter().enumerate() {
+            if let SimpleTerm::Iri(iri) = t {
+                lookup
+                    .entry(iri.as_str().to_lowercase())
+                    .or_default()
+                    .push(I::from_usize(i));
+            }
+        }
+        self.iri_lookup = Some(lookup);
+    }
+
+    /// Drop the secondary lookup of IRIs, if any.
+    pub fn disable_iri_lookup(&mut self) {
+        self.iri_lookup = None;
+    }
+
+    /// Whether the secondary lookup of IRIs is enabled.
+    pub fn has_iri_lookup(&self) -> bool {
+        self.iri_lookup.is_some()
+    }
+
+    /// The indices of the IRIs equal to `iri`, ignoring case, in increasing order.
+    pub fn find_iris_ignore_case(&self, iri: &str) -> Vec<I> {
+        let key = iri.to_lowercase();
+        match &self.iri_lookup {
+            Some(lookup) => lookup.get(&key).cloned().unwrap_or_default(),
+            None => self.scan_iris(|txt| txt.to_lowercase() == key),
+        }
+    }
+
+    /// The indices of the IRIs starting with `prefix`
+    /// (ignoring case or not), in increasing order.
+    pub fn find_iris_by_prefix(&self, prefix: &str, ignore_case: bool) -> Vec<I> {
+        let Some(lookup) = &self.iri_lookup else {
+            return if ignore_case {
+                let prefix = prefix.to_lowercase();
+                self.scan_iris(|txt| txt.to_lowercase().starts_with(&prefix))
+            } else {
+                self.scan_iris(|txt| txt.starts_with(prefix))
+            };
+        };
+        let key = prefix.to_lowercase();
+        let mut found: Vec<_> = lookup
+            .range(key.clone()..)
+            .take_while(|(iri, _)| iri.starts_with(&key))
+            .flat_map(|(_, indices)| indices.iter().copied())
+            .filter(|i| ignore_case || self.iri_str(*i).starts_with(prefix))
+            .collect();
+        found.sort();
+        found
+    }
+
+    fn scan_iris<F: Fn(&str) -> bool>(&self, predicate: F) -> Vec<I> {
+        self.i2t
+            .iter()
+            .enumerate()
+            .filter_map(|(i, t)| match t {
+                SimpleTerm::Iri(iri) if predicate(iri.as_str()) => Some(I::from_usize(i)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn iri_str(&self, i: I) -> &str {
+        match &self.i2t[i.into_usize()] {
+            SimpleTerm::Iri(iri) => iri.as_str(),
+            _ => unreachable!("the IRI lookup only contains IRIs"),
         }
     }
 
@@ -164,6 +250,12 @@ impl<I: Index> TermIndex for SimpleTermIndex<I> {
                 // because t2 borrows data from the key in self.t2i,
                 // which will live as long as self, and will not be moved (Box<str>).
                 let t2: SimpleTerm<'static> = unsafe { std::mem::transmute(t2) };
+                if let (Some(lookup), SimpleTerm::Iri(iri)) = (&mut self.iri_lookup, &t2) {
+                    lookup
+                        .entry(iri.as_str().to_lowercase())
+                        .or_default()
+                        .push(i);
+                }
                 self.i2t.push(t2);
                 e.insert(i);
                 Ok(i)
@@ -184,6 +276,57 @@ impl<I: Index> GraphNameIndex for SimpleTermIndex<I> {
     }
 }
 
+/// Rebuilds a [`TermIndex`] containing only the terms that are still in use.
+///
+/// This is used by the `compact` methods of graphs and datasets.
+pub(crate) struct Compactor<'a, TI: TermIndex> {
+    old: &'a TI,
+    new: TI,
+    map: BTreeMap<TI::Index, TI::Index>,
+}
+
+impl<'a, TI: TermIndex + Default> Compactor<'a, TI> {
+    pub fn new(old: &'a TI) -> Self {
+        Compactor {
+            old,
+            new: TI::default(),
+            map: BTreeMap::new(),
+        }
+    }
+
+    /// Map an index of the old term index to the corresponding index in the new one.
+    pub fn remap(&mut self, i: TI::Index) -> Result<TI::Index, TI::Error> {
+        if let Some(j) = self.map.get(&i) {
+            return Ok(*j);
+        }
+        let j = self.new.ensure_index(self.old.get_term(i))?;
+        self.map.insert(i, j);
+        Ok(j)
+    }
+
+    pub fn remap_triple(&mut self, t: &[TI::Index; 3]) -> Result<[TI::Index; 3], TI::Error> {
+        Ok([self.remap(t[0])?, self.remap(t[1])?, self.remap(t[2])?])
+    }
+
+    /// Return the new term index, and the number of terms it contains.
+    pub fn finish(self) -> (TI, usize) {
+        let len = self.map.len();
+        (self.new, len)
+    }
+}
+
+impl<'a, TI: GraphNameIndex + Default> Compactor<'a, TI> {
+    /// Remap a quad in GSPO order.
+    pub fn remap_gspo(&mut self, q: &[TI::Index; 4]) -> Result<[TI::Index; 4], TI::Error> {
+        let g = if q[0] == self.old.get_default_graph_index() {
+            self.new.get_default_graph_index()
+        } else {
+            self.remap(q[0])?
+        };
+        Ok([g, self.remap(q[1])?, self.remap(q[2])?, self.remap(q[3])?])
+    }
+}
+
 /// An error type to indicate that a [`SimpleTermIndex`] is full
 #[derive(thiserror::Error, Copy, Clone, Debug)]
 #[error("This TermIndex can not contain more terms")]
@@ -275,6 +418,55 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn iri_lookup() -> Result<(), Box<dyn std::error::Error>> {
+        let ex = Namespace::new_unchecked("https://example.com/ns/");
+        let mut sti = SimpleTermIndex::<u32>::new();
+        sti.ensure_index(ex.get("Person")?)?;
+        sti.ensure_index(ex.get("person")?)?;
+        sti.ensure_index("https://example.com/ns/Person")?;
+        sti.ensure_index(ex.get("Place")?)?;
+        sti.ensure_index(ex.get("name")?)?;
+        for enabled in [false, true] {
+            assert_eq!(sti.has_iri_lookup(), enabled);
+            assert_eq!(
+                sti.find_iris_ignore_case("HTTPS://EXAMPLE.COM/NS/PERSON"),
+                vec![0, 1]
+            );
+            assert_eq!(
+                sti.find_iris_ignore_case("https://example.com/ns/P"),
+                Vec::<u32>::new()
+            );
+            assert_eq!(
+                sti.find_iris_by_prefix("https://example.com/ns/P", false),
+                vec![0, 3]
+            );
+            assert_eq!(
+                sti.find_iris_by_prefix("https://example.com/ns/P", true),
+                vec![0, 1, 3]
+            );
+            assert_eq!(
+                sti.find_iris_by_prefix("https://example.com/ns/", false),
+                vec![0, 1, 3, 4]
+            );
+            assert_eq!(sti.find_iris_by_prefix("tag:", true), Vec::<u32>::new());
+            sti.enable_iri_lookup();
+        }
+        // the lookup is maintained when new terms are added
+        sti.ensure_index(ex.get("PLACE")?)?;
+        assert_eq!(
+            sti.find_iris_ignore_case("https://example.com/ns/place"),
+            vec![3, 5]
+        );
+        sti.disable_iri_lookup();
+        assert!(!sti.has_iri_lookup());
+        assert_eq!(
+            sti.find_iris_ignore_case("https://example.com/ns/place"),
+            vec![3, 5]
+        );
+        Ok(())
+    }
+
     #[cfg(feature = "all_tests")]
     #[test]
     fn big_simple_term_index() {
@@ -8,6 +8,9 @@
 //! [Linked Data]: http://linkeddata.org/
 #![deny(missing_docs)]
 
+pub mod crdt;
 pub mod dataset;
 pub mod graph;
 pub mod index;
+#[cfg(feature = "sled")]
+pub mod persistent;
@@ -0,0 +1,1154 @@
+//! Persistent graphs and datasets, stored in a [sled] database.
+//!
+//! Unlike the other graphs and datasets of this crate,
+//! [`SledGraph`] and [`SledDataset`] do not keep their content in memory:
+//! every mutation is written to a set of sled trees (a *keyspace*),
+//! so that their content survives process restarts.
+//!
+//! Like [`GenericFastGraph`](crate::graph::GenericFastGraph),
+//! they store each distinct term once, in a dictionary mapping it to an integer id,
+//! and maintain several indexes of id tuples,
+//! so that [`triples_matching`](Graph::triples_matching)
+//! and [`quads_matching`](Dataset::quads_matching) can use a prefix scan
+//! whenever some of the terms are constant.
+//! The indexes of a triple or quad are always updated in a single transaction.
+//!
+//! Several graphs and datasets can be stored in the same database,
+//! provided that they are given distinct names.
+//!
+//! # Maintenance
+//! Removed triples and quads are deleted from the indexes immediately
+//! (sled reclaims the space of its own log segments in the background),
+//! but the terms they used are kept in the dictionary.
+//! [`SledGraph::vacuum`] and [`SledDataset::vacuum`] remove unused terms from the dictionary,
+//! and return up-to-date [statistics](SledStats).
+//! They run online: the graph or dataset can be read and modified concurrently
+//! (through clones sharing the same trees),
+//! and a [`Throttle`] limits their impact on concurrent users.
+//!
+//! # Warm-up
+//! After the database is opened, the first queries have to load index pages from disk.
+//! To avoid the corresponding latency spikes,
+//! [`SledGraph::warm_up`] and [`SledDataset::warm_up`] load a whole graph or dataset
+//! in the page cache of sled (provided that it is large enough,
+//! see [`sled::Config::cache_capacity`]),
+//! while [`SledGraph::prefetch`] and [`SledDataset::prefetch`] only load the pages
+//! that a given [`triples_matching`](Graph::triples_matching)
+//! or [`quads_matching`](Dataset::quads_matching) would read.
+//!
+//! # Example
+//! ```
+//! # use sophia_api::graph::{Graph, MutableGraph};
+//! # use sophia_api::ns::rdf;
+//! # use sophia_inmem::persistent::SledGraph;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let dir = std::env::temp_dir().join(format!("sophia-sled-doc-{}", std::process::id()));
+//! let db = sled::open(&dir)?;
+//! {
+//!     let mut g = SledGraph::open(&db, "my-graph")?;
+//!     g.insert(rdf::Alt, rdf::type_, rdf::Bag)?;
+//!     g.flush()?;
+//! }
+//! // later, possibly in another process (after opening the database at `dir` again)
+//! let g = SledGraph::open(&db, "my-graph")?;
+//! assert!(g.contains(rdf::Alt, rdf::type_, rdf::Bag)?);
+//! # drop((g, db));
+//! # std::fs::remove_dir_all(&dir)?;
+//! # Ok(()) }
+//! ```
+//!
+//! [sled]: https://docs.rs/sled
+use std::iter::{empty, once};
+use std::thread::sleep;
+use std::time::Duration;
+
+use sled::transaction::{
+    ConflictableTransactionError, ConflictableTransactionResult, TransactionError,
+    TransactionalTree,
+};
+use sled::{Db, IVec, Transactional, Tree};
+use sophia_api::dataset::{DResult, MdResult, SetDataset};
+use sophia_api::graph::{GResult, MgResult, SetGraph};
+use sophia_api::prelude::*;
+use sophia_api::quad::Gspo;
+use sophia_api::term::matcher::{GraphNameMatcher, TermMatcher};
+use sophia_api::term::{BnodeId, GraphName, LanguageTag, SimpleTerm, VarName};
+use sophia_api::MownStr;
+use thiserror::Error;
+
+/// The error type of [`SledGraph`] and [`SledDataset`].
+#[derive(Debug, Error)]
+pub enum SledError {
+    /// An error raised by the underlying database
+    #[error("{0}")]
+    Sled(#[from] sled::Error),
+    /// The database contains data that can not be decoded
+    #[error("corrupted data in keyspace: {0}")]
+    Corrupted(&'static str),
+}
+
+/// Limits the impact of [`SledGraph::vacuum`] and [`SledDataset::vacuum`]
+/// on concurrent users of the database.
+///
+/// The dictionary is processed in batches, each in a single transaction,
+/// with a pause between consecutive batches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Throttle {
+    batch_size: usize,
+    pause: Duration,
+}
+
+impl Throttle {
+    /// Build a new default [`Throttle`] (batches of 1000 terms, no pause).
+    pub fn new() -> Self {
+        Throttle {
+            batch_size: 1000,
+            pause: Duration::ZERO,
+        }
+    }
+
+    /// The number of terms processed in each transaction (defaults to 1000).
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// The pause between two batches (defaults to zero).
+    pub fn pause(&self) -> Duration {
+        self.pause
+    }
+
+    /// Change the number of terms processed in each transaction
+    /// (a batch size of 0 is treated as 1).
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Change the pause between two batches.
+    pub fn with_pause(mut self, pause: Duration) -> Self {
+        self.pause = pause;
+        self
+    }
+}
+
+impl Default for Throttle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Statistics about a [`SledGraph`] or a [`SledDataset`],
+/// returned by their `vacuum` method.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SledStats {
+    /// The number of triples or quads
+    pub len: usize,
+    /// The number of terms in the dictionary
+    pub terms: usize,
+    /// The number of unused terms that were removed from the dictionary
+    pub collected_terms: usize,
+    /// The size of the whole database on disk, in bytes
+    pub size_on_disk: u64,
+}
+
+/// A graph persisted in a [sled] database.
+///
+/// The graph opened with a given `name` uses the sled trees whose name starts with `{name}/`:
+/// `spo`, `pos` and `osp` for its triples,
+/// `term_to_id`, `id_to_term` and `refs` for its dictionary.
+///
+/// [sled]: https://docs.rs/sled
+#[derive(Clone, Debug)]
+pub struct SledGraph {
+    store: Store<3>,
+}
+
+impl SledGraph {
+    /// Open the graph named `name` in `db`, creating it if it does not exist.
+    pub fn open(db: &Db, name: &str) -> Result<Self, SledError> {
+        let indexes = [("spo", [0, 1, 2]), ("pos", [1, 2, 0]), ("osp", [2, 0, 1])];
+        Ok(Self {
+            store: Store::open(db, name, &indexes)?,
+        })
+    }
+
+    /// The number of triples in this graph.
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    /// Whether this graph is empty.
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    /// Synchronously write all pending changes of the underlying database to disk,
+    /// and return the number of bytes written.
+    ///
+    /// Otherwise, changes are periodically written to disk in the background
+    /// (see [`sled::Config::flush_every_ms`]).
+    pub fn flush(&self) -> Result<usize, SledError> {
+        Ok(self.store.dict.db.flush()?)
+    }
+
+    /// Remove from the dictionary the terms that are not used by any triple anymore,
+    /// and return up-to-date statistics about this graph.
+    ///
+    /// This graph can be used concurrently (see the [module documentation](self#maintenance)).
+    pub fn vacuum(&self, throttle: Throttle) -> Result<SledStats, SledError> {
+        self.store.vacuum(throttle)
+    }
+
+    /// Read the whole graph (indexes and dictionary), so that it is loaded in the cache of the database,
+    /// and return the number of entries read (see the [module documentation](self#warm-up)).
+    pub fn warm_up(&self) -> Result<usize, SledError> {
+        self.store.warm_up()
+    }
+
+    /// Read the index entries and the terms that [`triples_matching`](Graph::triples_matching)
+    /// would read with the same matchers, so that they are loaded in the cache of the database,
+    /// and return the number of triples read (see the [module documentation](self#warm-up)).
+    ///
+    /// Only the [constant](TermMatcher::constant) matchers are taken into account.
+    pub fn prefetch<S, P, O>(&self, sm: S, pm: P, om: O) -> Result<usize, SledError>
+    where
+        S: TermMatcher,
+        P: TermMatcher,
+        O: TermMatcher,
+    {
+        match self.bound(&sm, &pm, &om)? {
+            Some(bound) => self.store.prefetch(bound),
+            None => Ok(0),
+        }
+    }
+
+    /// The ids of the constant matchers (see [`Store::bound`]).
+    fn bound<S, P, O>(&self, sm: &S, pm: &P, om: &O) -> Result<Option<[Option<u64>; 3]>, SledError>
+    where
+        S: TermMatcher,
+        P: TermMatcher,
+        O: TermMatcher,
+    {
+        let dict = &self.store.dict;
+        Store::bound([
+            dict.constant_id(sm.constant()),
+            dict.constant_id(pm.constant()),
+            dict.constant_id(om.constant()),
+        ])
+    }
+
+    fn triple(&self, [s, p, o]: [u64; 3]) -> Result<[SimpleTerm<'static>; 3], SledError> {
+        let dict = &self.store.dict;
+        Ok([dict.get_term(s)?, dict.get_term(p)?, dict.get_term(o)?])
+    }
+}
+
+impl Graph for SledGraph {
+    type Triple<'x> = [SimpleTerm<'static>; 3] where Self: 'x;
+    type Error = SledError;
+
+    fn triples(&self) -> impl Iterator<Item = GResult<Self, Self::Triple<'_>>> + '_ {
+        self.store.scan([None; 3]).map(|ids| self.triple(ids?))
+    }
+
+    #[allow(refining_impl_trait)]
+    fn triples_matching<'s, S, P, O>(
+        &'s self,
+        sm: S,
+        pm: P,
+        om: O,
+    ) -> Box<dyn Iterator<Item = GResult<Self, Self::Triple<'s>>> + 's>
+    where
+        S: TermMatcher + 's,
+        P: TermMatcher + 's,
+        O: TermMatcher + 's,
+    {
+        let bound = match self.bound(&sm, &pm, &om) {
+            Err(err) => return Box::new(once(Err(err))),
+            Ok(None) => return Box::new(empty()),
+            Ok(Some(bound)) => bound,
+        };
+        Box::new(
+            self.store
+                .scan(bound)
+                .map(|ids| self.triple(ids?))
+                .filter(move |res| match res {
+                    Ok([s, p, o]) => sm.matches(s) && pm.matches(p) && om.matches(o),
+                    Err(_) => true,
+                }),
+        )
+    }
+}
+
+impl MutableGraph for SledGraph {
+    type MutationError = SledError;
+
+    fn insert<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> MgResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let dict = &self.store.dict;
+        self.store.insert(|| {
+            Ok([
+                dict.ensure_id(s.borrow_term())?,
+                dict.ensure_id(p.borrow_term())?,
+                dict.ensure_id(o.borrow_term())?,
+            ])
+        })
+    }
+
+    fn remove<TS, TP, TO>(&mut self, s: TS, p: TP, o: TO) -> MgResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+    {
+        let dict = &self.store.dict;
+        let (Some(si), Some(pi), Some(oi)) = (dict.get_id(s)?, dict.get_id(p)?, dict.get_id(o)?)
+        else {
+            return Ok(false);
+        };
+        self.store.remove([si, pi, oi])
+    }
+}
+
+impl SetGraph for SledGraph {}
+
+/// A dataset persisted in a [sled] database.
+///
+/// The dataset opened with a given `name` uses the sled trees whose name starts with `{name}/`:
+/// `gspo`, `spog`, `posg` and `ospg` for its quads,
+/// `term_to_id`, `id_to_term` and `refs` for its dictionary.
+///
+/// [sled]: https://docs.rs/sled
+#[derive(Clone, Debug)]
+pub struct SledDataset {
+    /// Quads are stored as [s, p, o, g], the default graph having id 0
+    store: Store<4>,
+}
+
+impl SledDataset {
+    /// Open the dataset named `name` in `db`, creating it if it does not exist.
+    pub fn open(db: &Db, name: &str) -> Result<Self, SledError> {
+        let indexes = [
+            ("gspo", [3, 0, 1, 2]),
+            ("spog", [0, 1, 2, 3]),
+            ("posg", [1, 2, 0, 3]),
+            ("ospg", [2, 0, 1, 3]),
+        ];
+        Ok(Self {
+            store: Store::open(db, name, &indexes)?,
+        })
+    }
+
+    /// The number of quads in this dataset.
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    /// Whether this dataset is empty.
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+
+    /// Synchronously write all pending changes of the underlying database to disk,
+    /// and return the number of bytes written.
+    ///
+    /// Otherwise, changes are periodically written to disk in the background
+    /// (see [`sled::Config::flush_every_ms`]).
+    pub fn flush(&self) -> Result<usize, SledError> {
+        Ok(self.store.dict.db.flush()?)
+    }
+
+    /// Remove from the dictionary the terms that are not used by any quad anymore,
+    /// and return up-to-date statistics about this dataset.
+    ///
+    /// This dataset can be used concurrently (see the [module documentation](self#maintenance)).
+    pub fn vacuum(&self, throttle: Throttle) -> Result<SledStats, SledError> {
+        self.store.vacuum(throttle)
+    }
+
+    /// Read the whole dataset (indexes and dictionary), so that it is loaded in the cache of the database,
+    /// and return the number of entries read (see the [module documentation](self#warm-up)).
+    pub fn warm_up(&self) -> Result<usize, SledError> {
+        self.store.warm_up()
+    }
+
+    /// Read the index entries and the terms that [`quads_matching`](Dataset::quads_matching)
+    /// would read with the same matchers, so that they are loaded in the cache of the database,
+    /// and return the number of quads read (see the [module documentation](self#warm-up)).
+    ///
+    /// Only the constant matchers are taken into account.
+    pub fn prefetch<S, P, O, G>(&self, sm: S, pm: P, om: O, gm: G) -> Result<usize, SledError>
+    where
+        S: TermMatcher,
+        P: TermMatcher,
+        O: TermMatcher,
+        G: GraphNameMatcher,
+    {
+        match self.bound(&sm, &pm, &om, &gm)? {
+            Some(bound) => self.store.prefetch(bound),
+            None => Ok(0),
+        }
+    }
+
+    /// The ids of the constant matchers (see [`Store::bound`]).
+    fn bound<S, P, O, G>(
+        &self,
+        sm: &S,
+        pm: &P,
+        om: &O,
+        gm: &G,
+    ) -> Result<Option<[Option<u64>; 4]>, SledError>
+    where
+        S: TermMatcher,
+        P: TermMatcher,
+        O: TermMatcher,
+        G: GraphNameMatcher,
+    {
+        let dict = &self.store.dict;
+        let gi = match gm.constant() {
+            None => Ok(None),
+            Some(None) => Ok(Some(Some(0))),
+            Some(Some(g)) => dict.constant_id(Some(g)),
+        };
+        Store::bound([
+            dict.constant_id(sm.constant()),
+            dict.constant_id(pm.constant()),
+            dict.constant_id(om.constant()),
+            gi,
+        ])
+    }
+
+    fn quad(&self, [s, p, o, g]: [u64; 4]) -> Result<Gspo<SimpleTerm<'static>>, SledError> {
+        let dict = &self.store.dict;
+        let g = match g {
+            0 => None,
+            g => Some(dict.get_term(g)?),
+        };
+        Ok((g, [dict.get_term(s)?, dict.get_term(p)?, dict.get_term(o)?]))
+    }
+}
+
+impl Dataset for SledDataset {
+    type Quad<'x> = Gspo<SimpleTerm<'static>> where Self: 'x;
+    type Error = SledError;
+
+    fn quads(&self) -> impl Iterator<Item = DResult<Self, Self::Quad<'_>>> + '_ {
+        self.store.scan([None; 4]).map(|ids| self.quad(ids?))
+    }
+
+    #[allow(refining_impl_trait)]
+    fn quads_matching<'s, S, P, O, G>(
+        &'s self,
+        sm: S,
+        pm: P,
+        om: O,
+        gm: G,
+    ) -> Box<dyn Iterator<Item = DResult<Self, Self::Quad<'s>>> + 's>
+    where
+        S: TermMatcher + 's,
+        P: TermMatcher + 's,
+        O: TermMatcher + 's,
+        G: GraphNameMatcher + 's,
+    {
+        let bound = match self.bound(&sm, &pm, &om, &gm) {
+            Err(err) => return Box::new(once(Err(err))),
+            Ok(None) => return Box::new(empty()),
+            Ok(Some(bound)) => bound,
+        };
+        Box::new(
+            self.store
+                .scan(bound)
+                .map(|ids| self.quad(ids?))
+                .filter(move |res| match res {
+                    Ok((g, [s, p, o])) => {
+                        sm.matches(s) && pm.matches(p) && om.matches(o) && gm.matches(g.as_ref())
+                    }
+                    Err(_) => true,
+                }),
+        )
+    }
+}
+
+impl MutableDataset for SledDataset {
+    type MutationError = SledError;
+
+    fn insert<TS, TP, TO, TG>(
+        &mut self,
+        s: TS,
+        p: TP,
+        o: TO,
+        g: GraphName<TG>,
+    ) -> MdResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        let dict = &self.store.dict;
+        self.store.insert(|| {
+            let gi = match &g {
+                None => 0,
+                Some(g) => dict.ensure_id(g.borrow_term())?,
+            };
+            Ok([
+                dict.ensure_id(s.borrow_term())?,
+                dict.ensure_id(p.borrow_term())?,
+                dict.ensure_id(o.borrow_term())?,
+                gi,
+            ])
+        })
+    }
+
+    fn remove<TS, TP, TO, TG>(
+        &mut self,
+        s: TS,
+        p: TP,
+        o: TO,
+        g: GraphName<TG>,
+    ) -> MdResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        let dict = &self.store.dict;
+        let gi = match g {
+            None => Some(0),
+            Some(g) => dict.get_id(g)?,
+        };
+        let (Some(si), Some(pi), Some(oi), Some(gi)) =
+            (dict.get_id(s)?, dict.get_id(p)?, dict.get_id(o)?, gi)
+        else {
+            return Ok(false);
+        };
+        self.store.remove([si, pi, oi, gi])
+    }
+}
+
+impl SetDataset for SledDataset {}
+
+//
+
+const EMPTY: &[u8] = &[];
+
+/// The trees storing the content of a graph (N=3) or a dataset (N=4).
+#[derive(Clone, Debug)]
+struct Store<const N: usize> {
+    dict: Dictionary,
+    /// The indexes of tuples of ids; the first one is the primary index
+    trees: Vec<Tree>,
+    /// For each index, the positions in the tuples, in the order of the keys
+    orders: Vec<[usize; N]>,
+}
+
+impl<const N: usize> Store<N> {
+    fn open(db: &Db, name: &str, indexes: &[(&str, [usize; N])]) -> Result<Self, SledError> {
+        Ok(Self {
+            dict: Dictionary::open(db, name)?,
+            trees: indexes
+                .iter()
+                .map(|(suffix, _)| db.open_tree(format!("{name}/{suffix}")))
+                .collect::<Result<_, _>>()?,
+            orders: indexes.iter().map(|(_, order)| *order).collect(),
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.trees[0].len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.trees[0].is_empty()
+    }
+
+    /// Insert the tuple of ids returned by `ids`.
+    ///
+    /// If one of the terms is collected by a concurrent [vacuum](Store::vacuum)
+    /// between the moment its id is retrieved and the insertion,
+    /// `ids` is called again.
+    fn insert(&self, ids: impl Fn() -> Result<[u64; N], SledError>) -> Result<bool, SledError> {
+        loop {
+            let ids = ids()?;
+            let keys = self.keys(ids);
+            let inserted = self.transaction(|trees, refs, id_to_term| {
+                if trees[0].insert(&keys[0][..], EMPTY)?.is_some() {
+                    return Ok(false);
+                }
+                for (tree, key) in trees.iter().zip(&keys).skip(1) {
+                    tree.insert(&key[..], EMPTY)?;
+                }
+                for id in ids.into_iter().filter(|id| *id != 0) {
+                    if id_to_term.get(id.to_be_bytes())?.is_none() {
+                        return Err(ConflictableTransactionError::Abort(Abort::Collected));
+                    }
+                    update_refs(refs, id, |n| n + 1)?;
+                }
+                Ok(true)
+            });
+            match inserted {
+                Err(Abort::Collected) => continue,
+                Err(Abort::Error(err)) => return Err(err),
+                Ok(inserted) => return Ok(inserted),
+            }
+        }
+    }
+
+    fn remove(&self, ids: [u64; N]) -> Result<bool, SledError> {
+        let keys = self.keys(ids);
+        self.transaction(|trees, refs, _| {
+            if trees[0].remove(&keys[0][..])?.is_none() {
+                return Ok(false);
+            }
+            for (tree, key) in trees.iter().zip(&keys).skip(1) {
+                tree.remove(&key[..])?;
+            }
+            for id in ids.into_iter().filter(|id| *id != 0) {
+                update_refs(refs, id, |n| n.saturating_sub(1))?;
+            }
+            Ok(true)
+        })
+        .map_err(Abort::into_error)
+    }
+
+    /// Remove unused terms from the dictionary, batch by batch.
+    fn vacuum(&self, throttle: Throttle) -> Result<SledStats, SledError> {
+        let dict = &self.dict;
+        let mut stats = SledStats::default();
+        let mut start = 0_u64.to_be_bytes();
+        loop {
+            let batch = dict
+                .id_to_term
+                .range(start..)
+                .keys()
+                .take(throttle.batch_size)
+                .collect::<Result<Vec<_>, _>>()?;
+            let Some(last) = batch.last() else {
+                break;
+            };
+            start = (decode_id(last)? + 1).to_be_bytes();
+            let collected = [&dict.refs, &dict.id_to_term, &dict.term_to_id]
+                .as_slice()
+                .transaction(|trees| {
+                    let mut collected = 0;
+                    for id in &batch {
+                        if trees[0].get(id)?.is_some() {
+                            continue;
+                        }
+                        if let Some(key) = trees[1].remove(id)? {
+                            trees[2].remove(key)?;
+                            collected += 1;
+                        }
+                    }
+                    Ok(collected)
+                })
+                .map_err(|err: TransactionError<()>| match err {
+                    TransactionError::Abort(()) => unreachable!("transactions are never aborted"),
+                    TransactionError::Storage(err) => SledError::from(err),
+                })?;
+            stats.terms += batch.len() - collected;
+            stats.collected_terms += collected;
+            if batch.len() < throttle.batch_size {
+                break;
+            }
+            sleep(throttle.pause);
+        }
+        stats.len = self.len();
+        stats.size_on_disk = dict.db.size_on_disk()?;
+        Ok(stats)
+    }
+
+    /// Read all the entries of the indexes and of the dictionary, and return how many.
+    fn warm_up(&self) -> Result<usize, SledError> {
+        let dict = &self.dict;
+        let mut count = 0;
+        for tree in self
+            .trees
+            .iter()
+            .chain([&dict.term_to_id, &dict.id_to_term, &dict.refs])
+        {
+            for entry in tree.iter() {
+                entry?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Read the tuples that [`Store::scan`] would yield for `bound`, and their terms,
+    /// and return the number of tuples.
+    fn prefetch(&self, bound: [Option<u64>; N]) -> Result<usize, SledError> {
+        let mut count = 0;
+        for ids in self.scan(bound) {
+            for id in ids?.into_iter().filter(|id| *id != 0) {
+                self.dict.id_to_term.get(id.to_be_bytes())?;
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Combine the ids of the constant matchers (as returned by [`Dictionary::constant_id`])
+    /// into the `bound` argument of [`Store::scan`],
+    /// or return `None` if one of the constant terms is not in the dictionary (so nothing matches).
+    fn bound(
+        ids: [Result<Option<Option<u64>>, SledError>; N],
+    ) -> Result<Option<[Option<u64>; N]>, SledError> {
+        let mut bound = [None; N];
+        for (b, id) in bound.iter_mut().zip(ids) {
+            match id? {
+                Some(None) => return Ok(None),
+                id => *b = id.flatten(),
+            }
+        }
+        Ok(Some(bound))
+    }
+
+    /// Iterate over the tuples whose positions are equal to the `bound` ids,
+    /// using a prefix scan on the index with the longest prefix of bound positions.
+    fn scan(
+        &self,
+        bound: [Option<u64>; N],
+    ) -> impl Iterator<Item = Result<[u64; N], SledError>> + '_ {
+        let (i, prefix_len) = self
+            .orders
+            .iter()
+            .map(|order| {
+                order
+                    .iter()
+                    .take_while(|pos| bound[**pos].is_some())
+                    .count()
+            })
+            .enumerate()
+            .fold(
+                (0, 0),
+                |best, (i, len)| if len > best.1 { (i, len) } else { best },
+            );
+        let order = self.orders[i];
+        let prefix: Vec<u8> = order[..prefix_len]
+            .iter()
+            .flat_map(|pos| bound[*pos].unwrap().to_be_bytes())
+            .collect();
+        self.trees[i]
+            .scan_prefix(prefix)
+            .map(move |res| {
+                let (key, _) = res?;
+                let mut ids = [0; N];
+                for (k, pos) in order.iter().enumerate() {
+                    ids[*pos] = decode_id(key.get(k * 8..(k + 1) * 8).unwrap_or_default())?;
+                }
+                Ok(ids)
+            })
+            .filter(move |res| match res {
+                Ok(ids) => (0..N).all(|pos| bound[pos].is_none() || bound[pos] == Some(ids[pos])),
+                Err(_) => true,
+            })
+    }
+
+    /// The keys of `ids` in each index.
+    fn keys(&self, ids: [u64; N]) -> Vec<Vec<u8>> {
+        self.orders
+            .iter()
+            .map(|order| {
+                order
+                    .iter()
+                    .flat_map(|pos| ids[*pos].to_be_bytes())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Run `f` in a transaction over all the indexes,
+    /// the reference counts and the id-to-term tree of the dictionary.
+    fn transaction<A>(
+        &self,
+        f: impl Fn(
+            &[TransactionalTree],
+            &TransactionalTree,
+            &TransactionalTree,
+        ) -> ConflictableTransactionResult<A, Abort>,
+    ) -> Result<A, Abort> {
+        let mut trees: Vec<&Tree> = self.trees.iter().collect();
+        trees.push(&self.dict.refs);
+        trees.push(&self.dict.id_to_term);
+        let n = self.trees.len();
+        trees
+            .as_slice()
+            .transaction(|trees| f(&trees[..n], &trees[n], &trees[n + 1]))
+            .map_err(|err| match err {
+                TransactionError::Abort(abort) => abort,
+                TransactionError::Storage(err) => Abort::Error(err.into()),
+            })
+    }
+}
+
+/// The reasons for aborting a transaction of [`Store`].
+enum Abort {
+    /// A term was collected by a concurrent vacuum
+    Collected,
+    /// Any other error
+    Error(SledError),
+}
+
+impl Abort {
+    fn into_error(self) -> SledError {
+        match self {
+            Abort::Collected => unreachable!("only insertions check for collected terms"),
+            Abort::Error(err) => err,
+        }
+    }
+}
+
+/// Update the number of tuples using the term with the given `id`.
+fn update_refs(
+    refs: &TransactionalTree,
+    id: u64,
+    f: impl Fn(u64) -> u64,
+) -> ConflictableTransactionResult<(), Abort> {
+    let key = id.to_be_bytes();
+    let old = match refs.get(key)? {
+        None => 0,
+        Some(n) => {
+            decode_id(&n).map_err(|err| ConflictableTransactionError::Abort(Abort::Error(err)))?
+        }
+    };
+    match f(old) {
+        0 => refs.remove(&key[..])?,
+        new => refs.insert(&key[..], &new.to_be_bytes()[..])?,
+    };
+    Ok(())
+}
+
+/// The trees mapping terms to ids, and back,
+/// and counting the number of uses of each id.
+///
+/// Ids start at 1, so that 0 can be used for the default graph.
+/// Terms are only removed from the dictionary by [`Store::vacuum`].
+#[derive(Clone, Debug)]
+struct Dictionary {
+    db: Db,
+    term_to_id: Tree,
+    id_to_term: Tree,
+    /// The number of occurrences of each id in the tuples (absent if 0)
+    refs: Tree,
+}
+
+impl Dictionary {
+    fn open(db: &Db, name: &str) -> Result<Self, SledError> {
+        Ok(Self {
+            db: db.clone(),
+            term_to_id: db.open_tree(format!("{name}/term_to_id"))?,
+            id_to_term: db.open_tree(format!("{name}/id_to_term"))?,
+            refs: db.open_tree(format!("{name}/refs"))?,
+        })
+    }
+
+    fn get_id<T: Term>(&self, t: T) -> Result<Option<u64>, SledError> {
+        self.term_to_id
+            .get(encode_term(t))?
+            .map(|id| decode_id(&id))
+            .transpose()
+    }
+
+    fn ensure_id<T: Term>(&self, t: T) -> Result<u64, SledError> {
+        let key = encode_term(t);
+        if let Some(id) = self.term_to_id.get(&key)? {
+            return decode_id(&id);
+        }
+        let new_id = (self.db.generate_id()? + 1).to_be_bytes();
+        let id = (&self.term_to_id, &self.id_to_term)
+            .transaction(|(term_to_id, id_to_term)| {
+                // the term may have been added concurrently
+                if let Some(id) = term_to_id.get(&key)? {
+                    return Ok(id);
+                }
+                id_to_term.insert(&new_id[..], &key[..])?;
+                term_to_id.insert(&key[..], &new_id[..])?;
+                Ok(IVec::from(&new_id[..]))
+            })
+            .map_err(|err: TransactionError<()>| match err {
+                TransactionError::Abort(()) => unreachable!("transactions are never aborted"),
+                TransactionError::Storage(err) => SledError::from(err),
+            })?;
+        decode_id(&id)
+    }
+
+    fn get_term(&self, id: u64) -> Result<SimpleTerm<'static>, SledError> {
+        let bytes = self
+            .id_to_term
+            .get(id.to_be_bytes())?
+            .ok_or(SledError::Corrupted("unknown term id"))?;
+        let mut reader = Reader(&bytes);
+        let term = reader.term()?;
+        if reader.0.is_empty() {
+            Ok(term)
+        } else {
+            Err(SledError::Corrupted("trailing bytes after term"))
+        }
+    }
+
+    /// The id of the constant term of a matcher, if any
+    /// (`Some(None)` if that term is not in the dictionary).
+    fn constant_id<T: Term + ?Sized>(
+        &self,
+        constant: Option<&T>,
+    ) -> Result<Option<Option<u64>>, SledError> {
+        constant.map(|t| self.get_id(t.borrow_term())).transpose()
+    }
+}
+
+fn decode_id(bytes: &[u8]) -> Result<u64, SledError> {
+    bytes
+        .try_into()
+        .map(u64::from_be_bytes)
+        .map_err(|_| SledError::Corrupted("invalid id"))
+}
+
+/// Encode a term as a tag byte followed by its components,
+/// strings being prefixed with their length.
+fn encode_term<T: Term>(t: T) -> Vec<u8> {
+    let mut buf = vec![];
+    write_term(&mut buf, t);
+    buf
+}
+
+fn write_term<T: Term>(buf: &mut Vec<u8>, t: T) {
+    match t.kind() {
+        TermKind::Iri => write_strs(buf, b'i', &[t.iri().unwrap().as_str()]),
+        TermKind::BlankNode => write_strs(buf, b'b', &[t.bnode_id().unwrap().as_str()]),
+        TermKind::Literal => {
+            let lex = t.lexical_form().unwrap();
+            match t.language_tag() {
+                Some(tag) => write_strs(buf, b'@', &[&lex, tag.as_str()]),
+                None => write_strs(buf, b'l', &[&lex, t.datatype().unwrap().as_str()]),
+            }
+        }
+        TermKind::Triple => {
+            buf.push(b't');
+            for c in t.triple().unwrap() {
+                write_term(buf, c);
+            }
+        }
+        TermKind::Variable => write_strs(buf, b'v', &[t.variable().unwrap().as_str()]),
+    }
+}
+
+fn write_strs(buf: &mut Vec<u8>, tag: u8, strs: &[&str]) {
+    buf.push(tag);
+    for s in strs {
+        buf.extend_from_slice(&(s.len() as u64).to_be_bytes());
+        buf.extend_from_slice(s.as_bytes());
+    }
+}
+
+/// Decode terms encoded by [`encode_term`].
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], SledError> {
+        if self.0.len() < len {
+            return Err(SledError::Corrupted("truncated term"));
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn str(&mut self) -> Result<MownStr<'static>, SledError> {
+        let len = decode_id(self.take(8)?)? as usize;
+        let txt = std::str::from_utf8(self.take(len)?)
+            .map_err(|_| SledError::Corrupted("invalid UTF-8 in term"))?;
+        Ok(MownStr::from(txt.to_string()))
+    }
+
+    fn term(&mut self) -> Result<SimpleTerm<'static>, SledError> {
+        let term = match self.take(1)?[0] {
+            b'i' => SimpleTerm::Iri(IriRef::new_unchecked(self.str()?)),
+            b'b' => SimpleTerm::BlankNode(BnodeId::new_unchecked(self.str()?)),
+            b'l' => {
+                let lex = self.str()?;
+                SimpleTerm::LiteralDatatype(lex, IriRef::new_unchecked(self.str()?))
+            }
+            b'@' => {
+                let lex = self.str()?;
+                SimpleTerm::LiteralLanguage(lex, LanguageTag::new_unchecked(self.str()?))
+            }
+            b't' => SimpleTerm::Triple(Box::new([self.term()?, self.term()?, self.term()?])),
+            b'v' => SimpleTerm::Variable(VarName::new_unchecked(self.str()?)),
+            _ => return Err(SledError::Corrupted("unknown term tag")),
+        };
+        Ok(term)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::ns::rdf;
+    use sophia_api::source::{StreamError::SinkError, StreamResult};
+
+    fn temporary_db() -> Result<Db, SledError> {
+        Ok(sled::Config::new().temporary(true).open()?)
+    }
+
+    fn collect_graph<TS: TripleSource>(
+        mut triples: TS,
+    ) -> StreamResult<SledGraph, TS::Error, SledError> {
+        let db = temporary_db().map_err(SinkError)?;
+        let mut g = SledGraph::open(&db, "test").map_err(SinkError)?;
+        triples.try_for_each_triple(|t| g.insert_triple(t).map(|_| ()))?;
+        Ok(g)
+    }
+
+    fn collect_dataset<QS: QuadSource>(
+        mut quads: QS,
+    ) -> StreamResult<SledDataset, QS::Error, SledError> {
+        let db = temporary_db().map_err(SinkError)?;
+        let mut d = SledDataset::open(&db, "test").map_err(SinkError)?;
+        quads.try_for_each_quad(|q| d.insert_quad(q).map(|_| ()))?;
+        Ok(d)
+    }
+
+    sophia_api::test_graph_impl!(sled_graph, SledGraph, true, true, collect_graph);
+    sophia_api::test_dataset_impl!(sled_dataset, SledDataset, true, true, collect_dataset);
+
+    #[test]
+    fn term_encoding() -> Result<(), SledError> {
+        let db = temporary_db()?;
+        let dict = Dictionary::open(&db, "test")?;
+        let terms: [SimpleTerm; 6] = [
+            rdf::type_.into_term(),
+            BnodeId::new_unchecked("b1").into_term(),
+            "chat".into_term(),
+            ("chat" * LanguageTag::new_unchecked("fr")).into_term(),
+            VarName::new_unchecked("x").into_term(),
+            SimpleTerm::from_triple([rdf::Alt, rdf::type_, rdf::Bag]),
+        ];
+        for t in &terms {
+            let id = dict.ensure_id(t)?;
+            assert_eq!(dict.ensure_id(t)?, id);
+            assert_eq!(dict.get_id(t)?, Some(id));
+            assert!(Term::eq(&dict.get_term(id)?, t));
+        }
+        assert_eq!(dict.get_id(rdf::Bag)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn persistence() -> Result<(), Box<dyn std::error::Error>> {
+        let db = temporary_db()?;
+        let mut g = SledGraph::open(&db, "g")?;
+        g.insert(rdf::Alt, rdf::type_, rdf::Bag)?;
+        let mut d = SledDataset::open(&db, "d")?;
+        d.insert(rdf::Alt, rdf::type_, rdf::Bag, Some(rdf::Seq))?;
+        d.insert(
+            rdf::Alt,
+            rdf::type_,
+            rdf::Seq,
+            None as GraphName<SimpleTerm>,
+        )?;
+        drop((g, d));
+
+        // reopening a graph or a dataset retrieves its content
+        let g = SledGraph::open(&db, "g")?;
+        assert_eq!(g.len(), 1);
+        assert!(g.contains(rdf::Alt, rdf::type_, rdf::Bag)?);
+        let d = SledDataset::open(&db, "d")?;
+        assert_eq!(d.len(), 2);
+        assert!(d.contains(rdf::Alt, rdf::type_, rdf::Bag, Some(rdf::Seq))?);
+        assert_eq!(
+            d.quads_matching(Any, Any, Any, [None as GraphName<SimpleTerm>])
+                .count(),
+            1
+        );
+        // graphs and datasets with different names are independent
+        assert!(SledGraph::open(&db, "d")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn vacuum() -> Result<(), Box<dyn std::error::Error>> {
+        let db = temporary_db()?;
+        let mut g = SledGraph::open(&db, "g")?;
+        g.insert(rdf::Alt, rdf::type_, rdf::Bag)?;
+        g.insert(rdf::Seq, rdf::type_, rdf::Seq)?;
+        g.insert(rdf::Seq, rdf::value, "foo")?;
+        g.remove(rdf::Seq, rdf::value, "foo")?;
+        // a term can be used several times in the same triple
+        g.remove(rdf::Seq, rdf::type_, rdf::Seq)?;
+        // an unused term, e.g. left by an interrupted insertion
+        g.store.dict.ensure_id(rdf::List)?;
+
+        let throttle = Throttle::new()
+            .with_batch_size(2)
+            .with_pause(Duration::from_millis(1));
+        let stats = g.vacuum(throttle)?;
+        assert_eq!(stats.len, 1);
+        assert_eq!(stats.terms, 3);
+        assert_eq!(stats.collected_terms, 4);
+        assert!(stats.size_on_disk > 0);
+        let dict = &g.store.dict;
+        assert_eq!(dict.get_id(rdf::Seq)?, None);
+        assert_eq!(dict.get_id(rdf::List)?, None);
+        assert!(g.contains(rdf::Alt, rdf::type_, rdf::Bag)?);
+
+        // collected terms can be used again
+        g.insert(rdf::Seq, rdf::value, "foo")?;
+        assert!(g.contains(rdf::Seq, rdf::value, "foo")?);
+        assert_eq!(g.vacuum(Throttle::new())?.collected_terms, 0);
+
+        let mut d = SledDataset::open(&db, "d")?;
+        d.insert(rdf::Alt, rdf::type_, rdf::Bag, Some(rdf::Seq))?;
+        d.insert(
+            rdf::Alt,
+            rdf::type_,
+            rdf::Bag,
+            None as GraphName<SimpleTerm>,
+        )?;
+        d.remove(rdf::Alt, rdf::type_, rdf::Bag, Some(rdf::Seq))?;
+        let stats = d.vacuum(Throttle::new())?;
+        assert_eq!((stats.len, stats.terms, stats.collected_terms), (1, 3, 1));
+        assert_eq!(
+            d.quads_matching(Any, Any, Any, [None as GraphName<SimpleTerm>])
+                .count(),
+            1
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn warm_up() -> Result<(), Box<dyn std::error::Error>> {
+        let db = temporary_db()?;
+        let mut g = SledGraph::open(&db, "g")?;
+        g.insert(rdf::Alt, rdf::type_, rdf::Bag)?;
+        g.insert(rdf::Seq, rdf::type_, rdf::Bag)?;
+        // 2 triples in 3 indexes, 4 terms in 2 trees, 4 reference counts
+        assert_eq!(g.warm_up()?, 18);
+        assert_eq!(g.prefetch(Any, [rdf::type_], Any)?, 2);
+        assert_eq!(g.prefetch([rdf::Alt], Any, Any)?, 1);
+        assert_eq!(g.prefetch([rdf::List], Any, Any)?, 0);
+
+        let mut d = SledDataset::open(&db, "d")?;
+        d.insert(rdf::Alt, rdf::type_, rdf::Bag, Some(rdf::Seq))?;
+        d.insert(
+            rdf::Alt,
+            rdf::type_,
+            rdf::Bag,
+            None as GraphName<SimpleTerm>,
+        )?;
+        // 2 quads in 4 indexes, 4 terms in 2 trees, 4 reference counts
+        assert_eq!(d.warm_up()?, 20);
+        assert_eq!(d.prefetch(Any, Any, Any, [Some(rdf::Seq)])?, 1);
+        assert_eq!(
+            d.prefetch(Any, Any, Any, [None as GraphName<SimpleTerm>])?,
+            1
+        );
+        assert_eq!(d.prefetch([rdf::Alt], Any, Any, Any)?, 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,256 @@
+//! I provide [`IriBuilder`], for building IRIs from their components.
+use super::_percent::{is_sub_delim, push_encoded};
+use super::{percent_encode, InvalidIri, Iri, IriComponent, IriPart};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// A builder for [`Iri`]s, percent-encoding their components as needed.
+///
+/// Every component (except the scheme and the authority) is provided as plain text,
+/// and characters that are not allowed in that component are percent-encoded
+/// (including `%` itself), using [`percent_encode`].
+///
+/// # Example
+/// ```
+/// # use sophia_iri::IriBuilder;
+/// let iri = IriBuilder::new("http")
+///     .with_authority("example.org")
+///     .with_segments(["docs", "a/b c"])
+///     .with_query_pair("q", "rust & rdf")
+///     .with_fragment("part 1")
+///     .build()
+///     .unwrap();
+/// assert_eq!(iri.as_str(), "http://example.org/docs/a%2Fb%20c?q=rust%20%26%20rdf#part%201");
+/// ```
+#[derive(Clone, Debug)]
+pub struct IriBuilder {
+    scheme: String,
+    authority: Option<String>,
+    segments: Vec<String>,
+    query: Option<String>,
+    fragment: Option<String>,
+}
+
+impl IriBuilder {
+    /// Start building an IRI with the given scheme.
+    pub fn new(scheme: &str) -> Self {
+        IriBuilder {
+            scheme: scheme.to_string(),
+            authority: None,
+            segments: vec![],
+            query: None,
+            fragment: None,
+        }
+    }
+
+    /// Set the authority (e.g. `example.org`, `user@example.org:8080`) of the IRI.
+    ///
+    /// Characters that are never allowed in an authority are percent-encoded.
+    pub fn with_authority(mut self, authority: &str) -> Self {
+        let mut encoded = String::with_capacity(authority.len());
+        push_encoded(&mut encoded, authority, |c| {
+            is_sub_delim(c) || matches!(c, ':' | '@' | '[' | ']')
+        });
+        self.authority = Some(encoded);
+        self
+    }
+
+    /// Append a segment to the path of the IRI.
+    ///
+    /// Each segment is preceded by a `/`,
+    /// except for the first one if the IRI has no authority
+    /// (as in `urn:isbn:0451450523` or `mailto:alice@example.org`).
+    /// An empty segment can be used to add a trailing `/`.
+    pub fn with_segment(mut self, segment: &str) -> Self {
+        self.segments
+            .push(percent_encode(segment, IriComponent::Segment).into_owned());
+        self
+    }
+
+    /// Append several segments to the path of the IRI (see [`IriBuilder::with_segment`]).
+    pub fn with_segments<'a, I>(self, segments: I) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        segments
+            .into_iter()
+            .fold(self, |builder, segment| builder.with_segment(segment))
+    }
+
+    /// Append a `key=value` pair to the query of the IRI,
+    /// separated from the previous one (if any) by a `&`.
+    pub fn with_query_pair(mut self, key: &str, value: &str) -> Self {
+        let query = self.query.get_or_insert_with(String::new);
+        if !query.is_empty() {
+            query.push('&');
+        }
+        query.push_str(&percent_encode(key, IriComponent::QueryPart));
+        query.push('=');
+        query.push_str(&percent_encode(value, IriComponent::QueryPart));
+        self
+    }
+
+    /// Set the fragment identifier of the IRI.
+    pub fn with_fragment(mut self, fragment: &str) -> Self {
+        self.fragment = Some(percent_encode(fragment, IriComponent::Fragment).into_owned());
+        self
+    }
+
+    /// Build the IRI.
+    ///
+    /// This fails if the result is not a valid IRI,
+    /// which can only be caused by an invalid scheme or authority (including a non-numeric port),
+    /// or by an IRI without authority whose path would start with `//`
+    /// (and would therefore be mistaken for an authority).
+    pub fn build(&self) -> Result<Iri<String>, InvalidIri> {
+        let mut iri = self.scheme.clone();
+        iri.push(':');
+        let path = self.segments.join("/");
+        if let Some(authority) = &self.authority {
+            iri.push_str("//");
+            if let Some(offset) = invalid_port(authority) {
+                let offset = iri.len() + offset;
+                iri.push_str(authority);
+                return Err(InvalidIri {
+                    iri,
+                    offset,
+                    part: IriPart::Authority,
+                });
+            }
+            iri.push_str(authority);
+            if !self.segments.is_empty() {
+                iri.push('/');
+            }
+        } else if path.starts_with("//") {
+            let offset = iri.len();
+            iri.push_str(&path);
+            return Err(InvalidIri {
+                iri,
+                offset,
+                part: IriPart::Path,
+            });
+        }
+        iri.push_str(&path);
+        if let Some(query) = &self.query {
+            iri.push('?');
+            iri.push_str(query);
+        }
+        if let Some(fragment) = &self.fragment {
+            iri.push('#');
+            iri.push_str(fragment);
+        }
+        Iri::new(iri)
+    }
+}
+
+/// The offset of the first non-digit character in the port of `authority`, if any.
+fn invalid_port(authority: &str) -> Option<usize> {
+    let host_start = authority.rfind('@').map_or(0, |i| i + 1);
+    let host_end = match authority[host_start..].rfind(']') {
+        Some(i) => host_start + i + 1,
+        None => host_start,
+    };
+    let colon = host_end + authority[host_end..].find(':')?;
+    authority[colon + 1..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map(|i| colon + 1 + i)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn full() {
+        let iri = IriBuilder::new("https")
+            .with_authority("user@Example.org:8080")
+            .with_segments(["a b", "c/d", "é", "100%"])
+            .with_segment("")
+            .with_query_pair("x=1", "a&b+c")
+            .with_query_pair("y", "?/:@!")
+            .with_fragment("frag #1/2")
+            .build()
+            .unwrap();
+        assert_eq!(
+            iri.as_str(),
+            "https://user@Example.org:8080/a%20b/c%2Fd/é/100%25/?x%3D1=a%26b%2Bc&y=?/:@!#frag%20%231/2"
+        );
+    }
+
+    #[test]
+    fn no_authority() {
+        let iri = IriBuilder::new("urn")
+            .with_segment("isbn:0451450523")
+            .build()
+            .unwrap();
+        assert_eq!(iri.as_str(), "urn:isbn:0451450523");
+
+        let iri = IriBuilder::new("tag")
+            .with_segments(["a", "b"])
+            .build()
+            .unwrap();
+        assert_eq!(iri.as_str(), "tag:a/b");
+
+        let iri = IriBuilder::new("tag")
+            .with_segments(["", "a"])
+            .build()
+            .unwrap();
+        assert_eq!(iri.as_str(), "tag:/a");
+        assert!(IriBuilder::new("tag")
+            .with_segments(["", "", "a"])
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn empty_authority() {
+        let iri = IriBuilder::new("file")
+            .with_authority("")
+            .with_segments(["tmp", "x.ttl"])
+            .build()
+            .unwrap();
+        assert_eq!(iri.as_str(), "file:///tmp/x.ttl");
+    }
+
+    #[test]
+    fn components() {
+        let iri = IriBuilder::new("http")
+            .with_authority("ex.org")
+            .with_segment("a")
+            .with_query_pair("k", "v")
+            .with_fragment("f")
+            .build()
+            .unwrap();
+        assert_eq!(iri.scheme(), "http");
+        assert_eq!(iri.authority(), Some("ex.org"));
+        assert_eq!(iri.path(), "/a");
+        assert_eq!(iri.query(), Some("k=v"));
+        assert_eq!(iri.fragment(), Some("f"));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(IriBuilder::new("").build().is_err());
+        assert!(IriBuilder::new("1http").build().is_err());
+        assert!(IriBuilder::new("a b").build().is_err());
+        let err = IriBuilder::new("http")
+            .with_authority("ex.org:port")
+            .build()
+            .unwrap_err();
+        assert_eq!(err.offset, 14);
+        assert_eq!(err.part, IriPart::Authority);
+        assert!(IriBuilder::new("http")
+            .with_authority("[::1]:8080x")
+            .build()
+            .is_err());
+        assert!(IriBuilder::new("http")
+            .with_authority("user:pw@[::1]:8080")
+            .build()
+            .is_ok());
+        assert!(IriBuilder::new("http")
+            .with_authority("ex.org:")
+            .build()
+            .is_ok());
+    }
+}
@@ -0,0 +1,76 @@
+//! I provide the splitting of (valid) IRI references into their components.
+
+/// The components of an IRI reference, as defined by
+/// [RFC 3986 §3](https://www.rfc-editor.org/rfc/rfc3986#section-3).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Components<'a> {
+    pub scheme: Option<&'a str>,
+    pub authority: Option<&'a str>,
+    pub path: &'a str,
+    pub query: Option<&'a str>,
+    pub fragment: Option<&'a str>,
+}
+
+/// Split `iri` into its components.
+///
+/// # Precondition
+/// `iri` must be a valid IRI reference, otherwise the result is unspecified.
+pub(crate) fn split(iri: &str) -> Components<'_> {
+    let (rest, fragment) = match iri.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (iri, None),
+    };
+    let (rest, query) = match rest.split_once('?') {
+        Some((rest, query)) => (rest, Some(query)),
+        None => (rest, None),
+    };
+    let (scheme, rest) = match rest.find([':', '/']) {
+        Some(i) if i > 0 && rest.as_bytes()[i] == b':' => (Some(&rest[..i]), &rest[i + 1..]),
+        _ => (None, rest),
+    };
+    let (authority, path) = match rest.strip_prefix("//") {
+        Some(rest) => {
+            let i = rest.find('/').unwrap_or(rest.len());
+            (Some(&rest[..i]), &rest[i..])
+        }
+        None => (None, rest),
+    };
+    Components {
+        scheme,
+        authority,
+        path,
+        query,
+        fragment,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::{POSITIVE_IRIS, RELATIVE_IRIS};
+
+    #[test]
+    fn split_positive() {
+        for (txt, (_, scheme, authority, path, query, fragment)) in POSITIVE_IRIS {
+            let c = split(txt);
+            assert_eq!(c.scheme, *scheme, "{txt}");
+            assert_eq!(c.authority, *authority, "{txt}");
+            assert_eq!(c.path, *path, "{txt}");
+            assert_eq!(c.query, *query, "{txt}");
+            assert_eq!(c.fragment, *fragment, "{txt}");
+        }
+    }
+
+    #[test]
+    fn split_relative() {
+        for (txt, _) in RELATIVE_IRIS {
+            let c = split(txt);
+            // some entries (e.g. "g:h") are actually absolute
+            let expected = txt
+                .split_once(':')
+                .map(|(scheme, _)| scheme)
+                .filter(|scheme| !scheme.contains(['/', '?', '#']));
+            assert_eq!(c.scheme, expected, "{txt}");
+        }
+    }
+}
@@ -0,0 +1,512 @@
+//! I provide `const` functions validating IRIs,
+//! used by [`Iri::new_const`](crate::Iri::new_const) and [`IriRef::new_const`](crate::IriRef::new_const)
+//! to check static IRIs at compile time.
+//!
+//! They implement the same grammar (RFC 3987) as [`is_valid_iri_ref`](crate::is_valid_iri_ref),
+//! with a hand-written parser, as regular expressions can not be used in `const` contexts.
+//! The same parser is used to locate errors in [`InvalidIri`](crate::InvalidIri).
+use crate::IriPart;
+
+/// Whether `txt` is a valid IRI reference,
+/// or, if `absolute` is true, a valid absolute IRI (with an optional fragment).
+pub(crate) const fn is_valid(txt: &str, absolute: bool) -> bool {
+    matches!(check(txt, absolute), Ok(()))
+}
+
+/// Check whether `txt` is a valid IRI reference,
+/// or, if `absolute` is true, a valid absolute IRI (with an optional fragment).
+///
+/// If not, return the byte offset of the first character that could not be accepted,
+/// and the part of the IRI where it was encountered.
+pub(crate) const fn check(txt: &str, absolute: bool) -> Result<(), (usize, IriPart)> {
+    let b = txt.as_bytes();
+    let mut i = scheme_end(b);
+    if i == 0 {
+        if absolute {
+            return Err((invalid_scheme_char(b), IriPart::Scheme));
+        }
+        // ipath-noscheme: the first segment can not contain ':'
+        let mut j = 0;
+        while j < b.len() && !matches!(b[j], b'/' | b'?' | b'#') {
+            if b[j] == b':' {
+                return Err((invalid_scheme_char(b), IriPart::Scheme));
+            }
+            j += 1;
+        }
+    }
+    if i + 1 < b.len() && b[i] == b'/' && b[i + 1] == b'/' {
+        i = match authority_end(b, i + 2) {
+            Ok(end) => end,
+            Err(offset) => {
+                // like the regular expressions of `_regex`,
+                // accept what is not a valid authority as an absolute path starting with an empty segment
+                let end = scan(b, i, b":@/", false);
+                if end < b.len() && !matches!(b[end], b'?' | b'#') {
+                    return Err((offset, IriPart::Authority));
+                }
+                end
+            }
+        };
+    }
+    i = scan(b, i, b":@/", false);
+    let mut part = IriPart::Path;
+    if i < b.len() && b[i] == b'?' {
+        i = scan(b, i + 1, b":@/?", true);
+        part = IriPart::Query;
+    }
+    if i < b.len() && b[i] == b'#' {
+        i = scan(b, i + 1, b":@/?", false);
+        part = IriPart::Fragment;
+    }
+    if i == b.len() {
+        Ok(())
+    } else {
+        Err((i, part))
+    }
+}
+
+/// Replacements for the regex-based functions of `_regex`,
+/// when the `std` feature is disabled.
+#[cfg(not(feature = "std"))]
+pub(crate) mod no_regex {
+    use super::{is_valid, scheme_end};
+    use alloc::string::String;
+
+    /// Check whether `ns` and `suffix` concatenate into a valid (absolute or relative) IRI reference.
+    ///
+    /// # Performances
+    /// In the current implementation, a new String is allocated if `suffix`
+    /// is not `None`.
+    /// Future implementations may be smarter about this.
+    #[inline]
+    pub fn is_valid_suffixed_iri_ref(ns: &str, suffix: Option<&str>) -> bool {
+        match suffix {
+            None => is_valid_iri_ref(ns),
+            Some(suffix) => {
+                let mut buffer = String::with_capacity(ns.len() + suffix.len());
+                buffer.push_str(ns);
+                buffer.push_str(suffix);
+                is_valid_iri_ref(&buffer)
+            }
+        }
+    }
+
+    /// Check whether `txt` is a valid (absolute or relative) IRI reference.
+    #[inline]
+    pub fn is_valid_iri_ref(txt: &str) -> bool {
+        is_valid(txt, false)
+    }
+
+    /// Check whether `txt` is an absolute IRI reference.
+    #[inline]
+    pub fn is_absolute_iri_ref(txt: &str) -> bool {
+        is_valid(txt, true)
+    }
+
+    /// Check whether `txt` is a relative IRI reference.
+    #[inline]
+    pub fn is_relative_iri_ref(txt: &str) -> bool {
+        scheme_end(txt.as_bytes()) == 0 && is_valid(txt, false)
+    }
+}
+
+/// Return the position following the ':' of the scheme, or 0 if there is no scheme.
+pub(crate) const fn scheme_end(b: &[u8]) -> usize {
+    if b.is_empty() || !b[0].is_ascii_alphabetic() {
+        return 0;
+    }
+    let mut i = 1;
+    while i < b.len() && (b[i].is_ascii_alphanumeric() || matches!(b[i], b'+' | b'-' | b'.')) {
+        i += 1;
+    }
+    if i < b.len() && b[i] == b':' {
+        i + 1
+    } else {
+        0
+    }
+}
+
+/// Return the position of the first character that can not be part of a scheme.
+const fn invalid_scheme_char(b: &[u8]) -> usize {
+    let mut i = 0;
+    while i < b.len()
+        && (b[i].is_ascii_alphabetic()
+            || (i > 0 && (b[i].is_ascii_digit() || matches!(b[i], b'+' | b'-' | b'.'))))
+    {
+        i += 1;
+    }
+    i
+}
+
+/// Return the end of the authority starting at `start`,
+/// or the position of the first invalid character in it.
+pub(crate) const fn authority_end(b: &[u8], start: usize) -> Result<usize, usize> {
+    let mut i = scan(b, start, b":", false);
+    if i < b.len() && b[i] == b'@' {
+        i += 1;
+    } else {
+        i = start;
+    }
+    if i < b.len() && b[i] == b'[' {
+        let mut j = i + 1;
+        while j < b.len() && b[j] != b']' {
+            j += 1;
+        }
+        if j == b.len() || !(is_ipv6(b, i + 1, j) || is_ipvfuture(b, i + 1, j)) {
+            return Err(i);
+        }
+        i = j + 1;
+    } else {
+        i = scan(b, i, b"", false);
+    }
+    if i < b.len() && b[i] == b':' {
+        i += 1;
+        while i < b.len() && b[i].is_ascii_digit() {
+            i += 1;
+        }
+    }
+    if i == b.len() || matches!(b[i], b'/' | b'?' | b'#') {
+        Ok(i)
+    } else {
+        Err(i)
+    }
+}
+
+/// Return the position of the first character, from `i`,
+/// that is neither unreserved, a sub-delimiter, part of a percent-encoded octet,
+/// in `extra`, nor (if `private` is true) a private-use character.
+const fn scan(b: &[u8], mut i: usize, extra: &[u8], private: bool) -> usize {
+    while i < b.len() {
+        let c = b[i];
+        if c == b'%' {
+            if i + 2 < b.len() && b[i + 1].is_ascii_hexdigit() && b[i + 2].is_ascii_hexdigit() {
+                i += 3;
+                continue;
+            }
+            return i;
+        }
+        if c < 0x80 {
+            if c.is_ascii_alphanumeric()
+                || matches!(c, b'-' | b'.' | b'_' | b'~')
+                || is_sub_delim(c)
+                || contains(extra, c)
+            {
+                i += 1;
+                continue;
+            }
+            return i;
+        }
+        let (cp, len) = decode(b, i);
+        if is_ucschar(cp) || (private && is_iprivate(cp)) {
+            i += len;
+        } else {
+            return i;
+        }
+    }
+    i
+}
+
+/// Whether `b[start..end]` is an IPv6 address.
+///
+/// This mirrors, alternative by alternative, the `ipv6address` part of the regular expressions
+/// used by [`is_valid_iri_ref`](crate::is_valid_iri_ref), so that both always agree.
+const fn is_ipv6(b: &[u8], start: usize, end: usize) -> bool {
+    // (h16 ":"){6} ls32
+    if let Some(i) = h16_colons(b, start, end, 6) {
+        if is_ipv6_tail(b, i, end, LS32) {
+            return true;
+        }
+    }
+    // h16? "::" (h16 ":"){4} ls32
+    if let Some(i) = h16(b, start, end) {
+        if after_double_colon(b, i, end, 4, LS32) {
+            return true;
+        }
+    }
+    // "::" (h16 ":"){5} ls32
+    after_double_colon(b, start, end, 5, LS32)
+        || after_double_colon(b, start, end, 4, LS32)
+        // ((h16 ":"){0,max} ":" h16)? "::" (h16 ":"){n} tail
+        || with_prefix(b, start, end, 1, 3, LS32)
+        || with_prefix(b, start, end, 2, 2, LS32)
+        || with_prefix(b, start, end, 3, 1, LS32)
+        || with_prefix(b, start, end, 4, 0, LS32)
+        || with_prefix(b, start, end, 5, 0, H16)
+        || with_prefix(b, start, end, 6, 0, NOTHING)
+}
+
+/// The possible tails of an IPv6 address, after the last `h16 ":"` group
+const LS32: u8 = 0;
+const H16: u8 = 1;
+const NOTHING: u8 = 2;
+
+/// Whether `b[start..end]` matches `((h16 ":"){0,max} ":" h16)? "::" (h16 ":"){n} tail`.
+const fn with_prefix(b: &[u8], start: usize, end: usize, max: usize, n: usize, tail: u8) -> bool {
+    if after_double_colon(b, start, end, n, tail) {
+        return true;
+    }
+    let mut k = 0;
+    while k <= max {
+        let Some(i) = h16_colons(b, start, end, k) else {
+            return false;
+        };
+        if i < end && b[i] == b':' {
+            if let Some(j) = h16(b, i + 1, end) {
+                if after_double_colon(b, j, end, n, tail) {
+                    return true;
+                }
+            }
+        }
+        k += 1;
+    }
+    false
+}
+
+/// Whether `b[start..end]` matches `"::" (h16 ":"){n} tail`.
+const fn after_double_colon(b: &[u8], start: usize, end: usize, n: usize, tail: u8) -> bool {
+    if start + 2 > end || b[start] != b':' || b[start + 1] != b':' {
+        return false;
+    }
+    match h16_colons(b, start + 2, end, n) {
+        Some(i) => is_ipv6_tail(b, i, end, tail),
+        None => false,
+    }
+}
+
+/// Whether `b[start..end]` matches `tail`.
+const fn is_ipv6_tail(b: &[u8], start: usize, end: usize, tail: u8) -> bool {
+    match tail {
+        LS32 => {
+            if let Some(i) = h16_colons(b, start, end, 1) {
+                if let Some(j) = h16(b, i, end) {
+                    if j == end {
+                        return true;
+                    }
+                }
+            }
+            is_ipv4(b, start, end)
+        }
+        H16 => matches!(h16(b, start, end), Some(i) if i == end),
+        _ => start == end,
+    }
+}
+
+/// Return the end of `(h16 ":"){n}` starting at `start`, if any.
+const fn h16_colons(b: &[u8], start: usize, end: usize, n: usize) -> Option<usize> {
+    let mut i = start;
+    let mut k = 0;
+    while k < n {
+        match h16(b, i, end) {
+            Some(j) if j < end && b[j] == b':' => i = j + 1,
+            _ => return None,
+        }
+        k += 1;
+    }
+    Some(i)
+}
+
+/// Return the end of the group of 1 to 4 hexadecimal digits starting at `start`, if any.
+const fn h16(b: &[u8], start: usize, end: usize) -> Option<usize> {
+    let mut i = start;
+    while i < end && i - start < 4 && b[i].is_ascii_hexdigit() {
+        i += 1;
+    }
+    if i == start {
+        None
+    } else {
+        Some(i)
+    }
+}
+
+const fn is_ipv4(b: &[u8], start: usize, end: usize) -> bool {
+    let mut i = start;
+    let mut octets = 0;
+    while octets < 4 {
+        if octets > 0 {
+            if i >= end || b[i] != b'.' {
+                return false;
+            }
+            i += 1;
+        }
+        let octet = i;
+        let mut value = 0_u32;
+        while i < end && b[i].is_ascii_digit() && i - octet < 3 {
+            value = value * 10 + (b[i] - b'0') as u32;
+            i += 1;
+        }
+        if i == octet || value > 255 || (i - octet > 1 && b[octet] == b'0') {
+            return false;
+        }
+        octets += 1;
+    }
+    i == end
+}
+
+const fn is_ipvfuture(b: &[u8], start: usize, end: usize) -> bool {
+    if start == end || !matches!(b[start], b'v' | b'V') {
+        return false;
+    }
+    let mut i = start + 1;
+    while i < end && b[i].is_ascii_hexdigit() {
+        i += 1;
+    }
+    if i == start + 1 || i == end || b[i] != b'.' || i + 1 == end {
+        return false;
+    }
+    i += 1;
+    while i < end {
+        let c = b[i];
+        if !(c.is_ascii_alphanumeric()
+            || matches!(c, b'-' | b'.' | b'_' | b'~' | b':')
+            || is_sub_delim(c))
+        {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn is_sub_delim(c: u8) -> bool {
+    matches!(
+        c,
+        b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'='
+    )
+}
+
+const fn contains(bytes: &[u8], c: u8) -> bool {
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == c {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Decode the (non-ASCII) UTF-8 character starting at `i`,
+/// and return its code point and its length in bytes.
+const fn decode(b: &[u8], i: usize) -> (u32, usize) {
+    let c = b[i] as u32;
+    if c < 0xE0 {
+        (((c & 0x1F) << 6) | cont(b, i + 1), 2)
+    } else if c < 0xF0 {
+        (
+            ((c & 0x0F) << 12) | (cont(b, i + 1) << 6) | cont(b, i + 2),
+            3,
+        )
+    } else {
+        let cp =
+            ((c & 0x07) << 18) | (cont(b, i + 1) << 12) | (cont(b, i + 2) << 6) | cont(b, i + 3);
+        (cp, 4)
+    }
+}
+
+/// The payload of the UTF-8 continuation byte at `i`.
+const fn cont(b: &[u8], i: usize) -> u32 {
+    (b[i] & 0x3F) as u32
+}
+
+const fn is_ucschar(c: u32) -> bool {
+    matches!(c,
+        0xA0..=0xD7FF
+        | 0xF900..=0xFDCF
+        | 0xFDF0..=0xFFEF
+        | 0x10000..=0x1FFFD
+        | 0x20000..=0x2FFFD
+        | 0x30000..=0x3FFFD
+        | 0x40000..=0x4FFFD
+        | 0x50000..=0x5FFFD
+        | 0x60000..=0x6FFFD
+        | 0x70000..=0x7FFFD
+        | 0x80000..=0x8FFFD
+        | 0x90000..=0x9FFFD
+        | 0xA0000..=0xAFFFD
+        | 0xB0000..=0xBFFFD
+        | 0xC0000..=0xCFFFD
+        | 0xD0000..=0xDFFFD
+        | 0xE1000..=0xEFFFD
+    )
+}
+
+const fn is_iprivate(c: u32) -> bool {
+    matches!(c, 0xE000..=0xF8FF | 0xF0000..=0xFFFFD | 0x100000..=0x10FFFD)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::*;
+    use crate::{is_absolute_iri_ref, is_valid_iri_ref};
+
+    #[test]
+    fn same_as_regex() {
+        let extra = [
+            "http://[::1]/",
+            "http://[1:2:3:4:5:6:7:8]/",
+            "http://[1:2:3:4:5:6:7::]/",
+            "http://[1::2]/",
+            "http://[:1::]/",
+            "http://[1:2::1.2.3.4]/",
+            "http://[::ffff:192.168.0.1]/",
+            "http://[1::2::3]/",
+            "http://[1:2:3:4:5:6:7:8:9]/",
+            "http://[12345::]/",
+            "http://[::256.0.0.1]/",
+            "http://[::01.0.0.1]/",
+            "http://[v7.abc:d]/",
+            "http://[v7.]/",
+            "http://[]/",
+            "http://[::1/",
+            "http://a@b@c/",
+            "http://a:b:c/",
+            "http://a:80x/",
+            "http://a:80x?q",
+            "http://a:80x[/",
+            "a:b?\u{E000}#c",
+            "a:b#\u{E000}",
+            "a:b#c#d",
+            "a:%4",
+            "1a:b",
+            "a:b:c",
+            "../a:b",
+            "//host",
+            "",
+        ];
+        let all = POSITIVE_IRIS
+            .iter()
+            .map(|t| t.0)
+            .chain(NEGATIVE_IRIS.iter().copied())
+            .chain(RELATIVE_IRIS.iter().map(|t| t.0))
+            .chain(extra);
+        for txt in all {
+            assert_eq!(is_valid(txt, false), is_valid_iri_ref(txt), "{txt:?}");
+            assert_eq!(is_valid(txt, true), is_absolute_iri_ref(txt), "{txt:?}");
+        }
+    }
+
+    #[test]
+    fn locate_errors() {
+        for (txt, absolute, expected) in [
+            ("http://example.org/a b", true, Err((20, IriPart::Path))),
+            ("http://example.org/a?b c", true, Err((22, IriPart::Query))),
+            (
+                "http://example.org/a#b#c",
+                true,
+                Err((22, IriPart::Fragment)),
+            ),
+            ("http://exa mple.org/", true, Err((10, IriPart::Authority))),
+            ("http://[::1/", true, Err((7, IriPart::Authority))),
+            ("my_scheme:a", true, Err((2, IriPart::Scheme))),
+            ("my_scheme:a", false, Err((2, IriPart::Scheme))),
+            ("/a/b", true, Err((0, IriPart::Scheme))),
+            ("/a/b", false, Ok(())),
+            ("a:%4", true, Err((2, IriPart::Path))),
+        ] {
+            assert_eq!(check(txt, absolute), expected, "{txt:?}");
+        }
+    }
+
+    const _CHECKED_AT_COMPILE_TIME: () = assert!(is_valid("http://example.org/é#", true));
+}
@@ -0,0 +1,278 @@
+//! I provide the [`resolve_const`](crate::resolve_const) macro,
+//! resolving IRI references against a base IRI at compile time,
+//! and the `const` functions it relies on.
+//!
+//! Unlike [`BaseIri::resolve`](crate::resolve::BaseIri::resolve),
+//! only the simple cases of [RFC 3986 §5.2](https://www.rfc-editor.org/rfc/rfc3986#section-5.2)
+//! are supported: the resolution must not involve any dot segment (`.` or `..`).
+use crate::_const::{authority_end, is_valid, scheme_end};
+
+#[macro_export]
+/// Resolve an IRI reference against a base IRI at compile time,
+/// producing an [`Iri<&'static str>`](crate::Iri).
+///
+/// Both arguments must be constant `&str` expressions
+/// (e.g. string literals, or the name of a `const`).
+/// The base must be an absolute IRI,
+/// and the IRI reference must not contain any dot segment (`.` or `..`),
+/// nor require removing dot segments from the path of the base;
+/// otherwise, compilation fails.
+///
+/// This is useful for vocabulary crates, to derive the IRIs of their terms from a common base,
+/// without any runtime initialization.
+///
+/// # Example
+/// ```
+/// # use sophia_iri::{resolve_const, Iri};
+/// const BASE: &str = "http://example.org/vocab/v1";
+/// const PERSON: Iri<&str> = resolve_const!(BASE, "#Person");
+/// const KNOWS: Iri<&str> = resolve_const!(BASE, "terms/knows");
+/// const HOME: Iri<&str> = resolve_const!(BASE, "/");
+/// assert_eq!(PERSON.as_str(), "http://example.org/vocab/v1#Person");
+/// assert_eq!(KNOWS.as_str(), "http://example.org/vocab/terms/knows");
+/// assert_eq!(HOME.as_str(), "http://example.org/");
+/// ```
+///
+/// Dot segments are rejected by the compiler:
+/// ```compile_fail
+/// # use sophia_iri::{resolve_const, Iri};
+/// const PARENT: Iri<&str> = resolve_const!("http://example.org/vocab/v1", "../v2");
+/// ```
+macro_rules! resolve_const {
+    ($base: expr, $rel: expr) => {{
+        // item names are not hygienic, so they must not collide with names used in $base or $rel
+        const __RESOLVE_CONST_BASE: &str = $base;
+        const __RESOLVE_CONST_REL: &str = $rel;
+        const __RESOLVE_CONST_LEN: usize =
+            $crate::__resolve_const_len(__RESOLVE_CONST_BASE, __RESOLVE_CONST_REL);
+        const __RESOLVE_CONST_BYTES: [u8; __RESOLVE_CONST_LEN] =
+            $crate::__resolve_const(__RESOLVE_CONST_BASE, __RESOLVE_CONST_REL);
+        const __RESOLVE_CONST_IRI: $crate::Iri<&'static str> =
+            $crate::Iri::new_const(match core::str::from_utf8(&__RESOLVE_CONST_BYTES) {
+                Ok(txt) => txt,
+                Err(_) => panic!("invalid UTF-8"),
+            });
+        __RESOLVE_CONST_IRI
+    }};
+}
+
+/// The length of `rel` resolved against `base`.
+///
+/// Used by the [`resolve_const`](crate::resolve_const) macro.
+#[doc(hidden)]
+pub const fn __resolve_const_len(base: &str, rel: &str) -> usize {
+    let (keep, slash) = plan(base, rel);
+    keep + slash as usize + rel.len()
+}
+
+/// The bytes of `rel` resolved against `base`.
+///
+/// Used by the [`resolve_const`](crate::resolve_const) macro,
+/// `N` being given by [`__resolve_const_len`].
+#[doc(hidden)]
+pub const fn __resolve_const<const N: usize>(base: &str, rel: &str) -> [u8; N] {
+    let (keep, slash) = plan(base, rel);
+    let (b, r) = (base.as_bytes(), rel.as_bytes());
+    let mut out = [0; N];
+    let mut i = 0;
+    while i < keep {
+        out[i] = b[i];
+        i += 1;
+    }
+    if slash {
+        out[i] = b'/';
+        i += 1;
+    }
+    let mut j = 0;
+    while j < r.len() {
+        out[i + j] = r[j];
+        j += 1;
+    }
+    out
+}
+
+/// Determine how `rel` is resolved against `base`,
+/// as the length of the prefix of `base` to keep,
+/// and whether a `/` must be inserted between that prefix and `rel`.
+///
+/// Panic if `base` is not an absolute IRI, if `rel` is not an IRI reference,
+/// or if the resolution would involve removing dot segments.
+const fn plan(base: &str, rel: &str) -> (usize, bool) {
+    assert!(is_valid(base, true), "invalid base IRI");
+    assert!(is_valid(rel, false), "invalid IRI reference");
+    let (b, r) = (base.as_bytes(), rel.as_bytes());
+    let scheme = scheme_end(b);
+    let has_authority = starts_with(b, scheme, b"//");
+    let authority = if has_authority {
+        match authority_end(b, scheme + 2) {
+            Ok(end) => end,
+            Err(_) => panic!("invalid base IRI"),
+        }
+    } else {
+        scheme
+    };
+    let path_end = find(b, authority, b"?#");
+    let query_end = find(b, path_end, b"#");
+
+    if r.is_empty() || r[0] == b'#' {
+        return (query_end, false);
+    }
+    if r[0] == b'?' {
+        return (path_end, false);
+    }
+    let rel_path_end = find(r, 0, b"?#");
+    assert!(
+        !has_dot_segment(r, scheme_end(r), rel_path_end),
+        "dot segments are not supported"
+    );
+    if scheme_end(r) > 0 {
+        (0, false)
+    } else if starts_with(r, 0, b"//") {
+        (scheme, false)
+    } else if r[0] == b'/' {
+        (authority, false)
+    } else if has_authority && path_end == authority {
+        (authority, true)
+    } else {
+        // keep the path of the base up to its last '/' (if any)
+        let mut keep = path_end;
+        while keep > authority && b[keep - 1] != b'/' {
+            keep -= 1;
+        }
+        assert!(
+            !has_dot_segment(b, authority, keep),
+            "dot segments are not supported"
+        );
+        (keep, false)
+    }
+}
+
+/// Whether `b[i..]` starts with `prefix`.
+const fn starts_with(b: &[u8], i: usize, prefix: &[u8]) -> bool {
+    if b.len() < i + prefix.len() {
+        return false;
+    }
+    let mut j = 0;
+    while j < prefix.len() {
+        if b[i + j] != prefix[j] {
+            return false;
+        }
+        j += 1;
+    }
+    true
+}
+
+/// The position of the first byte, from `i`, that is in `stop` (or the length of `b`).
+const fn find(b: &[u8], mut i: usize, stop: &[u8]) -> usize {
+    while i < b.len() {
+        let mut j = 0;
+        while j < stop.len() {
+            if b[i] == stop[j] {
+                return i;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    i
+}
+
+/// Whether any of the `/`-separated segments of `b[start..end]` is `.` or `..`.
+const fn has_dot_segment(b: &[u8], start: usize, end: usize) -> bool {
+    let mut i = start;
+    while i < end {
+        let mut j = i;
+        while j < end && b[j] != b'/' {
+            j += 1;
+        }
+        if (j == i + 1 && b[i] == b'.') || (j == i + 2 && b[i] == b'.' && b[i + 1] == b'.') {
+            return true;
+        }
+        i = j + 1;
+    }
+    false
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+    use crate::resolve::BaseIri;
+    use crate::Iri;
+
+    #[test]
+    fn same_as_resolve() {
+        for base in [
+            "http://a/b/c/d;p?q#f",
+            "http://a",
+            "http://a?q",
+            "http://a/b/",
+            "urn:isbn:123",
+            "tag:ex.org,2024:a/b",
+            "file:///home/user/",
+        ] {
+            for rel in [
+                "",
+                "#s",
+                "?y",
+                "?y#s",
+                "g",
+                "g/",
+                "g/h?y#s",
+                "/g",
+                "//g",
+                "//g/h",
+                "g;x=1",
+                "...",
+                "g.",
+                ".g",
+                "g:h",
+                "http://other/x",
+            ] {
+                let len = __resolve_const_len(base, rel);
+                let bytes = __resolve_const::<64>(base, rel);
+                let got = std::str::from_utf8(&bytes[..len]).unwrap();
+                let expected = BaseIri::new(base).unwrap().resolve(rel).unwrap();
+                assert_eq!(got, expected.as_str(), "<{base}> + <{rel}>");
+            }
+        }
+    }
+
+    #[test]
+    fn dot_segments() {
+        for (base, rel) in [
+            ("http://a/b/c", "."),
+            ("http://a/b/c", "./g"),
+            ("http://a/b/c", "../g"),
+            ("http://a/b/c", "g/.."),
+            ("http://a/b/c", "/./g"),
+            ("http://a/b/c", "x:./g"),
+            ("http://a/../c", "g"),
+        ] {
+            let res = std::panic::catch_unwind(|| plan(base, rel));
+            assert!(res.is_err(), "<{base}> + <{rel}>");
+        }
+        // dot segments in the base do not matter when its path is kept unchanged
+        assert_eq!(plan("http://a/../c", "#f"), (13, false));
+    }
+
+    #[test]
+    fn invalid() {
+        for (base, rel) in [
+            ("not an IRI", "g"),
+            ("/relative", "g"),
+            ("http://a/", "a b"),
+        ] {
+            assert!(std::panic::catch_unwind(|| plan(base, rel)).is_err());
+        }
+    }
+
+    #[test]
+    fn resolve_const_macro() {
+        const BASE: &str = "http://example.org/ns/v1";
+        const ID: Iri<&str> = resolve_const!(BASE, "#id");
+        const PERSON: Iri<&str> = resolve_const!(BASE, "Person");
+        assert_eq!(ID.as_str(), "http://example.org/ns/v1#id");
+        assert_eq!(PERSON.as_str(), "http://example.org/ns/Person");
+        assert_eq!(resolve_const!("http://a", "b").as_str(), "http://a/b");
+    }
+}
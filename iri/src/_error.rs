@@ -1,13 +1,125 @@
 //! Error and result type for IRI resolution.
-
-use thiserror::Error;
+//!
+//! NB: these types implement [`std::error::Error`] only if the `std` feature is enabled.
+use super::_const::check;
+use alloc::string::String;
+use core::fmt;
 
 /// Type alias for `Result` with default error `TermError`.
 ///
 /// Can be used like `std::result::Result` as well.
-pub type Result<T, E = InvalidIri> = std::result::Result<T, E>;
+pub type Result<T, E = InvalidIri> = core::result::Result<T, E>;
 
 /// This error is raised when trying to parse an invalid IRI.
-#[derive(Debug, Error)]
-#[error("The given IRI '{0}' is not valid according to RFC3987")]
-pub struct InvalidIri(pub String);
+///
+/// Besides the invalid IRI, it indicates where the error was detected,
+/// which helps spotting the problem in long IRIs.
+#[derive(Debug)]
+pub struct InvalidIri {
+    /// The invalid IRI
+    pub iri: String,
+    /// The byte offset, in `iri`, of the first character that could not be accepted
+    /// (equal to the length of `iri` if it ends prematurely)
+    pub offset: usize,
+    /// The part of `iri` in which the error was detected
+    pub part: IriPart,
+}
+
+impl InvalidIri {
+    /// Build an error for `iri`, which is not a valid absolute IRI (if `absolute` is true)
+    /// or IRI reference (if `absolute` is false), locating the error.
+    pub(crate) fn locate(iri: String, absolute: bool) -> Self {
+        let (offset, part) = match check(&iri, absolute) {
+            Err(location) => location,
+            // should not happen, but the error must be located somewhere
+            Ok(()) => (iri.len(), IriPart::Path),
+        };
+        InvalidIri { iri, offset, part }
+    }
+}
+
+impl fmt::Display for InvalidIri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The given IRI '{}' is not valid according to RFC3987: ",
+            self.iri
+        )?;
+        match self
+            .iri
+            .get(self.offset..)
+            .and_then(|txt| txt.chars().next())
+        {
+            Some(c) => write!(
+                f,
+                "unexpected {:?} in {} at byte {}",
+                c, self.part, self.offset
+            ),
+            None => write!(f, "unexpected end of IRI in {}", self.part),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidIri {}
+
+/// The parts of an IRI, as used by [`InvalidIri`] to locate errors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IriPart {
+    /// The scheme, preceding the first `:`
+    Scheme,
+    /// The authority, following `//`
+    Authority,
+    /// The path
+    Path,
+    /// The query, following `?`
+    Query,
+    /// The fragment identifier, following `#`
+    Fragment,
+}
+
+impl fmt::Display for IriPart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            IriPart::Scheme => "scheme",
+            IriPart::Authority => "authority",
+            IriPart::Path => "path",
+            IriPart::Query => "query",
+            IriPart::Fragment => "fragment",
+        })
+    }
+}
+
+/// This error is raised when trying to percent-decode a malformed string.
+#[derive(Debug)]
+pub struct InvalidPercentEncoding(pub String);
+
+impl fmt::Display for InvalidPercentEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The given text '{}' is not a valid percent-encoding of UTF-8 text",
+            self.0
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidPercentEncoding {}
+
+/// This error is raised when trying to parse an invalid IRI template.
+#[derive(Debug)]
+pub struct InvalidIriTemplate(pub String);
+
+impl fmt::Display for InvalidIriTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The given IRI template '{}' is not valid according to RFC6570",
+            self.0
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidIriTemplate {}
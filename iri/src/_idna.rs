@@ -0,0 +1,314 @@
+//! I provide the mapping of IRIs to URIs and back,
+//! as described in [RFC 3987 §3](https://www.rfc-editor.org/rfc/rfc3987#section-3),
+//! with internationalized domain names converted to/from
+//! [Punycode](https://www.rfc-editor.org/rfc/rfc3492).
+use super::_components::split;
+use super::_normalize::{decode_percent_encoding, is_unreserved};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// Map the given IRI to a URI:
+/// the labels of the host containing non-ASCII characters are lowercased and converted to Punycode,
+/// and all other non-ASCII characters are percent-encoded.
+///
+/// # Precondition
+/// `iri` must be a valid IRI reference, otherwise the result is unspecified.
+pub(crate) fn to_uri(iri: &str) -> String {
+    if iri.is_ascii() {
+        return iri.to_string();
+    }
+    map_host(
+        iri,
+        |host, out| {
+            for (i, label) in host.split('.').enumerate() {
+                if i > 0 {
+                    out.push('.');
+                }
+                if label.is_ascii() {
+                    out.push_str(label);
+                    continue;
+                }
+                let label = label.to_lowercase();
+                match punycode_encode(&label.chars().collect::<Vec<_>>()) {
+                    Some(encoded) => {
+                        out.push_str(ACE_PREFIX);
+                        out.push_str(&encoded);
+                    }
+                    None => push_percent_encoded(out, &label),
+                }
+            }
+        },
+        true,
+    )
+}
+
+/// Map the given URI (or IRI) to an IRI:
+/// Punycode labels of the host are decoded,
+/// and percent-encoded non-ASCII characters are decoded wherever they are allowed.
+///
+/// NB: as a side effect, the remaining percent-encoded octets use uppercase hexadecimal digits.
+///
+/// # Precondition
+/// `uri` must be a valid IRI reference, otherwise the result is unspecified.
+pub(crate) fn to_iri(uri: &str) -> String {
+    let decoded = decode_percent_encoding(uri, |c| !c.is_ascii() && is_unreserved(c));
+    map_host(
+        &decoded,
+        |host, out| {
+            for (i, label) in host.split('.').enumerate() {
+                if i > 0 {
+                    out.push('.');
+                }
+                let decoded = label
+                    .get(..ACE_PREFIX.len())
+                    .filter(|prefix| prefix.eq_ignore_ascii_case(ACE_PREFIX))
+                    .and_then(|_| punycode_decode(&label[ACE_PREFIX.len()..]))
+                    .filter(|decoded| decoded.chars().all(is_unreserved));
+                out.push_str(decoded.as_deref().unwrap_or(label));
+            }
+        },
+        false,
+    )
+}
+
+/// Rebuild `iri`, processing its host with `f`,
+/// and percent-encoding non-ASCII characters in its other components if `encode` is true.
+fn map_host<F: Fn(&str, &mut String)>(iri: &str, f: F, encode: bool) -> String {
+    let push = |out: &mut String, txt: &str| {
+        if encode {
+            push_percent_encoded(out, txt);
+        } else {
+            out.push_str(txt);
+        }
+    };
+    let c = split(iri);
+    let mut out = String::with_capacity(iri.len() * 2);
+    if let Some(scheme) = c.scheme {
+        out.push_str(scheme);
+        out.push(':');
+    }
+    if let Some(authority) = c.authority {
+        out.push_str("//");
+        let (userinfo, host_port) = match authority.rfind('@') {
+            Some(i) => authority.split_at(i + 1),
+            None => ("", authority),
+        };
+        let (host, port) = match host_port.rfind(':') {
+            Some(i) if !host_port.ends_with(']') => host_port.split_at(i),
+            _ => (host_port, ""),
+        };
+        push(&mut out, userinfo);
+        f(host, &mut out);
+        out.push_str(port);
+    }
+    push(&mut out, c.path);
+    if let Some(query) = c.query {
+        out.push('?');
+        push(&mut out, query);
+    }
+    if let Some(fragment) = c.fragment {
+        out.push('#');
+        push(&mut out, fragment);
+    }
+    out
+}
+
+/// Push `txt` into `out`, percent-encoding its non-ASCII characters.
+fn push_percent_encoded(out: &mut String, txt: &str) {
+    for c in txt.chars() {
+        if c.is_ascii() {
+            out.push(c);
+        } else {
+            let mut buf = [0; 4];
+            for b in c.encode_utf8(&mut buf).bytes() {
+                write!(out, "%{b:02X}").unwrap();
+            }
+        }
+    }
+}
+
+const ACE_PREFIX: &str = "xn--";
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+
+/// Punycode encoding, as per [RFC 3492 §6.3](https://www.rfc-editor.org/rfc/rfc3492#section-6.3).
+///
+/// Return `None` on overflow.
+fn punycode_encode(input: &[char]) -> Option<String> {
+    let mut output: String = input.iter().filter(|c| c.is_ascii()).collect();
+    let basic = output.len() as u32;
+    let mut handled = basic;
+    if basic > 0 {
+        output.push('-');
+    }
+    let mut n = INITIAL_N;
+    let mut delta = 0_u32;
+    let mut bias = INITIAL_BIAS;
+    while (handled as usize) < input.len() {
+        let m = input.iter().map(|c| *c as u32).filter(|c| *c >= n).min()?;
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+        for c in input.iter().map(|c| *c as u32) {
+            if c < n {
+                delta = delta.checked_add(1)?;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = threshold(k, bias);
+                    if q < t {
+                        break;
+                    }
+                    output.push(digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta = delta.checked_add(1)?;
+        n = n.checked_add(1)?;
+    }
+    Some(output)
+}
+
+/// Punycode decoding, as per [RFC 3492 §6.2](https://www.rfc-editor.org/rfc/rfc3492#section-6.2).
+///
+/// Return `None` if `input` is not valid Punycode.
+fn punycode_decode(input: &str) -> Option<String> {
+    let (basic, extended) = match input.rfind('-') {
+        Some(i) => (&input[..i], &input[i + 1..]),
+        None => ("", input),
+    };
+    if !basic.is_ascii() {
+        return None;
+    }
+    let mut output: Vec<char> = basic.chars().collect();
+    let mut n = INITIAL_N;
+    let mut i = 0_u32;
+    let mut bias = INITIAL_BIAS;
+    let mut digits = extended.bytes().peekable();
+    while digits.peek().is_some() {
+        let old_i = i;
+        let mut w = 1_u32;
+        let mut k = BASE;
+        loop {
+            let d = digit_value(digits.next()?)?;
+            i = i.checked_add(d.checked_mul(w)?)?;
+            let t = threshold(k, bias);
+            if d < t {
+                break;
+            }
+            w = w.checked_mul(BASE - t)?;
+            k += BASE;
+        }
+        let len = output.len() as u32 + 1;
+        bias = adapt(i - old_i, len, old_i == 0);
+        n = n.checked_add(i / len)?;
+        i %= len;
+        output.insert(i as usize, char::from_u32(n)?);
+        i += 1;
+    }
+    Some(output.into_iter().collect())
+}
+
+fn threshold(k: u32, bias: u32) -> u32 {
+    if k <= bias {
+        T_MIN
+    } else if k >= bias + T_MAX {
+        T_MAX
+    } else {
+        k - bias
+    }
+}
+
+fn adapt(delta: u32, num_points: u32, first_time: bool) -> u32 {
+    let mut delta = if first_time { delta / DAMP } else { delta / 2 };
+    delta += delta / num_points;
+    let mut k = 0;
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+    k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+}
+
+fn digit(d: u32) -> char {
+    match d {
+        0..=25 => (b'a' + d as u8) as char,
+        _ => (b'0' + (d - 26) as u8) as char,
+    }
+}
+
+fn digit_value(b: u8) -> Option<u32> {
+    match b {
+        b'a'..=b'z' => Some((b - b'a') as u32),
+        b'A'..=b'Z' => Some((b - b'A') as u32),
+        b'0'..=b'9' => Some((b - b'0') as u32 + 26),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PUNYCODE: &[(&str, &str)] = &[
+        ("bücher", "bcher-kva"),
+        ("mañana", "maana-pta"),
+        ("ü", "tda"),
+        ("例え", "r8jz45g"),
+        ("テスト", "zckzah"),
+    ];
+
+    #[test]
+    fn punycode() {
+        for (unicode, ascii) in PUNYCODE {
+            let chars: Vec<char> = unicode.chars().collect();
+            assert_eq!(punycode_encode(&chars).as_deref(), Some(*ascii));
+            assert_eq!(punycode_decode(ascii).as_deref(), Some(*unicode));
+        }
+        assert_eq!(punycode_decode("a-b-!"), None);
+        assert_eq!(punycode_decode("99999999999"), None);
+    }
+
+    const MAPPINGS: &[(&str, &str)] = &[
+        ("http://example.org/a", "http://example.org/a"),
+        ("http://例え.テスト/", "http://xn--r8jz45g.xn--zckzah/"),
+        (
+            "http://ü@Bücher.example:8080/é?q=ü#ü",
+            "http://%C3%BC@xn--bcher-kva.example:8080/%C3%A9?q=%C3%BC#%C3%BC",
+        ),
+        ("tag:été", "tag:%C3%A9t%C3%A9"),
+        ("http://[::1]/é", "http://[::1]/%C3%A9"),
+    ];
+
+    #[test]
+    fn iri_to_uri() {
+        for (iri, uri) in MAPPINGS {
+            assert_eq!(&to_uri(iri), uri, "{iri}");
+        }
+    }
+
+    #[test]
+    fn uri_to_iri() {
+        for (iri, uri) in MAPPINGS {
+            assert_eq!(to_iri(uri), iri.replace("Bücher", "bücher"), "{uri}");
+        }
+        assert_eq!(
+            to_iri("http://XN--bcher-kva.org/%2f"),
+            "http://bücher.org/%2F"
+        );
+        assert_eq!(to_iri("http://xn--a-b-!.org/"), "http://xn--a-b-!.org/");
+    }
+}
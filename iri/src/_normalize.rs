@@ -0,0 +1,465 @@
+//! I provide the syntax-based and scheme-based normalization of IRIs,
+//! as described in [RFC 3987 §5.3.2](https://www.rfc-editor.org/rfc/rfc3987#section-5.3.2)
+//! and [§5.3.3](https://www.rfc-editor.org/rfc/rfc3987#section-5.3.3).
+use super::_components::split;
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec;
+use core::fmt::Write;
+
+/// Normalize the given IRI:
+/// - percent-encoded octets use uppercase hexadecimal digits,
+///   and those encoding unreserved characters are decoded;
+/// - the scheme and host are lowercased;
+/// - dot-segments are removed from the path.
+///
+/// # Precondition
+/// `iri` must be a valid absolute IRI, otherwise the result is unspecified.
+pub(crate) fn normalize(iri: &str) -> String {
+    let iri = decode_percent_encoding(iri, is_unreserved);
+    let c = split(&iri);
+    let mut out = String::with_capacity(iri.len());
+    if let Some(scheme) = c.scheme {
+        out.push_str(&scheme.to_ascii_lowercase());
+        out.push(':');
+    }
+    let path = remove_dot_segments(c.path);
+    match c.authority {
+        Some(authority) => {
+            out.push_str("//");
+            let (userinfo, host) = match authority.rfind('@') {
+                Some(i) => authority.split_at(i + 1),
+                None => ("", authority),
+            };
+            out.push_str(userinfo);
+            push_lowercase(&mut out, host);
+        }
+        None if path.starts_with("//") => {
+            // prevent the first segment from being interpreted as an authority
+            out.push_str("/.");
+        }
+        None => (),
+    }
+    out.push_str(&path);
+    if let Some(query) = c.query {
+        out.push('?');
+        out.push_str(query);
+    }
+    if let Some(fragment) = c.fragment {
+        out.push('#');
+        out.push_str(fragment);
+    }
+    out
+}
+
+/// Normalize the given IRI as [`normalize`] does, then apply the following scheme-based rules
+/// to IRIs with an authority and a scheme among `http`, `https`, `ws`, `wss` and `ftp`:
+/// - an empty or default port is removed;
+/// - an empty path is replaced by `/`.
+///
+/// # Precondition
+/// `iri` must be a valid absolute IRI, otherwise the result is unspecified.
+pub(crate) fn normalize_scheme_based(iri: &str) -> String {
+    let norm = normalize(iri);
+    let c = split(&norm);
+    let (Some(scheme), Some(authority)) = (c.scheme, c.authority) else {
+        return norm;
+    };
+    let default_port = match scheme {
+        "http" | "ws" => "80",
+        "https" | "wss" => "443",
+        "ftp" => "21",
+        _ => return norm,
+    };
+    let mut out = String::with_capacity(norm.len() + 1);
+    out.push_str(scheme);
+    out.push_str("://");
+    // the port is whatever follows the last ':' not enclosed in an IP literal
+    let authority = match authority.rfind(':') {
+        Some(i) if !authority[i..].contains(']') => match &authority[i + 1..] {
+            "" => &authority[..i],
+            port if port == default_port => &authority[..i],
+            _ => authority,
+        },
+        _ => authority,
+    };
+    out.push_str(authority);
+    out.push_str(if c.path.is_empty() { "/" } else { c.path });
+    if let Some(query) = c.query {
+        out.push('?');
+        out.push_str(query);
+    }
+    if let Some(fragment) = c.fragment {
+        out.push('#');
+        out.push_str(fragment);
+    }
+    out
+}
+
+/// Push `txt` lowercased into `out`, preserving percent-encoded octets.
+fn push_lowercase(out: &mut String, txt: &str) {
+    let mut chars = txt.chars();
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            out.push(c);
+            out.extend(chars.by_ref().take(2));
+        } else {
+            out.extend(c.to_lowercase());
+        }
+    }
+}
+
+/// Uppercase the hexadecimal digits of percent-encoded octets,
+/// and decode those encoding characters satisfying `decode`
+/// (which must only accept characters allowed anywhere in an IRI).
+pub(crate) fn decode_percent_encoding<F>(iri: &str, decode: F) -> Cow<'_, str>
+where
+    F: Fn(char) -> bool,
+{
+    if !iri.contains('%') {
+        return Cow::Borrowed(iri);
+    }
+    let mut out = String::with_capacity(iri.len());
+    let mut octets = vec![];
+    let mut rest = iri;
+    while let Some(i) = rest.find('%') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        octets.clear();
+        while rest.len() >= 3 && rest.as_bytes()[0] == b'%' {
+            octets.push(u8::from_str_radix(&rest[1..3], 16).unwrap());
+            rest = &rest[3..];
+        }
+        push_octets(&mut out, &octets, &decode);
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+/// Push a sequence of (decoded) octets into `out`,
+/// as plain characters if they satisfy `decode`, percent-encoded otherwise.
+fn push_octets<F: Fn(char) -> bool>(out: &mut String, octets: &[u8], decode: &F) {
+    let mut i = 0;
+    while i < octets.len() {
+        let len = match octets[i] {
+            0x00..=0x7F => 1,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 0,
+        };
+        let c = octets
+            .get(i..i + len)
+            .filter(|_| len > 0)
+            .and_then(|bytes| core::str::from_utf8(bytes).ok())
+            .and_then(|s| s.chars().next());
+        match c {
+            Some(c) if decode(c) => {
+                out.push(c);
+                i += len;
+            }
+            _ => {
+                write!(out, "%{:02X}", octets[i]).unwrap();
+                i += 1;
+            }
+        }
+    }
+}
+
+/// Compare two IRI references after case normalization and percent-encoding normalization,
+/// as per [RFC 3987 §5.3.2.1](https://www.rfc-editor.org/rfc/rfc3987#section-5.3.2.1)
+/// and [§5.3.2.3](https://www.rfc-editor.org/rfc/rfc3987#section-5.3.2.3):
+/// - the scheme and host are compared case-insensitively,
+///   as well as the hexadecimal digits of percent-encoded octets;
+/// - percent-encoded unreserved characters are equivalent to the characters themselves.
+///
+/// Contrarily to [`Iri::eq_syntax_based`](crate::Iri::eq_syntax_based),
+/// dot-segments are *not* removed,
+/// but both IRIs are compared on the fly, without allocating their normalized form.
+/// This makes this function suitable for hot code paths, such as deduplication or term matching.
+///
+/// # Precondition
+/// `a` and `b` must be valid IRI references, otherwise the result is unspecified.
+///
+/// # Example
+/// ```
+/// # use sophia_iri::iri_eq_normalized;
+/// assert!(iri_eq_normalized("HTTP://Example.ORG/%7euser", "http://example.org/~user"));
+/// assert!(iri_eq_normalized("http://ex.org/%2f", "http://ex.org/%2F"));
+/// assert!(!iri_eq_normalized("http://ex.org/A", "http://ex.org/a"));
+/// assert!(!iri_eq_normalized("http://ex.org/%2F", "http://ex.org//"));
+/// ```
+pub fn iri_eq_normalized(a: &str, b: &str) -> bool {
+    a == b || NormalizedChars::new(a).eq(NormalizedChars::new(b))
+}
+
+/// A unit of a normalized IRI, as yielded by [`NormalizedChars`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Unit {
+    /// A plain character
+    Char(char),
+    /// A percent-encoded octet
+    Octet(u8),
+}
+
+/// Iterate over the [units](Unit) of the normalized form of an IRI reference
+/// (without removing dot-segments).
+struct NormalizedChars<'a> {
+    iri: &'a str,
+    pos: usize,
+    /// The end of the scheme, and the start and end of the host, which are case-insensitive
+    scheme_end: usize,
+    host: (usize, usize),
+    /// The remaining characters of the lowercase form of the last character
+    lower: Option<core::char::ToLowercase>,
+}
+
+impl<'a> NormalizedChars<'a> {
+    fn new(iri: &'a str) -> Self {
+        let c = split(iri);
+        let scheme_end = c.scheme.map_or(0, |scheme| scheme.len() + 1);
+        let host = match c.authority {
+            Some(authority) => {
+                let start = scheme_end + 2;
+                let host_start = authority.rfind('@').map_or(0, |i| i + 1);
+                (start + host_start, start + authority.len())
+            }
+            None => (0, 0),
+        };
+        NormalizedChars {
+            iri,
+            pos: 0,
+            scheme_end,
+            host,
+            lower: None,
+        }
+    }
+
+    /// The octet percent-encoded at `pos`, if any.
+    fn octet_at(&self, pos: usize) -> Option<u8> {
+        let hex = self.iri.get(pos..pos + 3)?.strip_prefix('%')?;
+        if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        u8::from_str_radix(hex, 16).ok()
+    }
+
+    /// Decode the percent-encoded UTF-8 character starting at `self.pos`
+    /// (whose first octet is `first`), if any, with its length in octets.
+    fn decode(&self, first: u8) -> Option<(char, usize)> {
+        let len = match first {
+            0x00..=0x7F => 1,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => return None,
+        };
+        let mut octets = [first, 0, 0, 0];
+        for (i, octet) in octets.iter_mut().enumerate().take(len).skip(1) {
+            *octet = self.octet_at(self.pos + 3 * i)?;
+        }
+        let c = core::str::from_utf8(&octets[..len]).ok()?.chars().next()?;
+        Some((c, len))
+    }
+}
+
+impl Iterator for NormalizedChars<'_> {
+    type Item = Unit;
+
+    fn next(&mut self) -> Option<Unit> {
+        if let Some(c) = self.lower.as_mut().and_then(Iterator::next) {
+            return Some(Unit::Char(c));
+        }
+        let start = self.pos;
+        let c = match self.octet_at(start) {
+            Some(octet) => match self.decode(octet) {
+                Some((c, len)) if is_unreserved(c) => {
+                    self.pos += 3 * len;
+                    c
+                }
+                _ => {
+                    self.pos += 3;
+                    return Some(Unit::Octet(octet));
+                }
+            },
+            None => {
+                let c = self.iri[start..].chars().next()?;
+                self.pos += c.len_utf8();
+                c
+            }
+        };
+        if start < self.scheme_end || (self.host.0 <= start && start < self.host.1) {
+            let mut lower = c.to_lowercase();
+            let c = lower.next();
+            self.lower = Some(lower);
+            c.map(Unit::Char)
+        } else {
+            self.lower = None;
+            Some(Unit::Char(c))
+        }
+    }
+}
+
+/// Whether `c` matches the `iunreserved` rule of RFC 3987.
+pub(crate) fn is_unreserved(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+        || matches!(c, '-' | '.' | '_' | '~')
+        || matches!(c,
+            '\u{A0}'..='\u{D7FF}'
+            | '\u{F900}'..='\u{FDCF}'
+            | '\u{FDF0}'..='\u{FFEF}'
+            | '\u{10000}'..='\u{1FFFD}'
+            | '\u{20000}'..='\u{2FFFD}'
+            | '\u{30000}'..='\u{3FFFD}'
+            | '\u{40000}'..='\u{4FFFD}'
+            | '\u{50000}'..='\u{5FFFD}'
+            | '\u{60000}'..='\u{6FFFD}'
+            | '\u{70000}'..='\u{7FFFD}'
+            | '\u{80000}'..='\u{8FFFD}'
+            | '\u{90000}'..='\u{9FFFD}'
+            | '\u{A0000}'..='\u{AFFFD}'
+            | '\u{B0000}'..='\u{BFFFD}'
+            | '\u{C0000}'..='\u{CFFFD}'
+            | '\u{D0000}'..='\u{DFFFD}'
+            | '\u{E1000}'..='\u{EFFFD}'
+        )
+}
+
+/// Implements [RFC 3986 §5.2.4](https://www.rfc-editor.org/rfc/rfc3986#section-5.2.4).
+fn remove_dot_segments(path: &str) -> Cow<'_, str> {
+    if !path.split('/').any(|s| s == "." || s == "..") {
+        return Cow::Borrowed(path);
+    }
+    let mut out = String::with_capacity(path.len());
+    let mut input = path;
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix("../") {
+            input = rest;
+        } else if let Some(rest) = input.strip_prefix("./") {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") {
+            input = &input[3..];
+            pop_segment(&mut out);
+        } else if input == "/.." {
+            input = "/";
+            pop_segment(&mut out);
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            let start = usize::from(input.starts_with('/'));
+            let end = input[start..].find('/').map_or(input.len(), |i| i + start);
+            out.push_str(&input[..end]);
+            input = &input[end..];
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Remove the last segment (and its preceding '/', if any) from `out`.
+fn pop_segment(out: &mut String) {
+    match out.rfind('/') {
+        Some(i) => out.truncate(i),
+        None => out.clear(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CASES: &[(&str, &str)] = &[
+        ("http://example.org/a/b", "http://example.org/a/b"),
+        ("HTTP://Example.ORG/A/B", "http://example.org/A/B"),
+        (
+            "http://User@Example.ORG:8080/",
+            "http://User@example.org:8080/",
+        ),
+        ("http://%c3%a9x%2a.org/%2f", "http://éx%2A.org/%2F"),
+        (
+            "http://ex.org/%7euser/%41%2F%3f",
+            "http://ex.org/~user/A%2F%3F",
+        ),
+        ("http://ex.org/caf%C3%A9", "http://ex.org/café"),
+        ("http://ex.org/%FF%C3", "http://ex.org/%FF%C3"),
+        ("http://ex.org/%EE%80%80", "http://ex.org/%EE%80%80"),
+        ("http://ex.org/a/./b/../c", "http://ex.org/a/c"),
+        ("http://ex.org/a/b/..", "http://ex.org/a/"),
+        ("http://ex.org/../../a", "http://ex.org/a"),
+        ("http://ex.org/a/%2E%2E/b", "http://ex.org/b"),
+        ("tag:a/../b", "tag:/b"),
+        ("tag:/.//a", "tag:/.//a"),
+        (
+            "http://ex.org/a/../b?Q=/./#F/..",
+            "http://ex.org/b?Q=/./#F/..",
+        ),
+    ];
+
+    #[test]
+    fn normalize_iris() {
+        for (txt, expected) in CASES {
+            assert_eq!(&normalize(txt), expected, "{txt}");
+        }
+    }
+
+    const SCHEME_CASES: &[(&str, &str)] = &[
+        ("http://ex.org", "http://ex.org/"),
+        ("HTTP://Ex.org:80", "http://ex.org/"),
+        ("http://ex.org:/a?q", "http://ex.org/a?q"),
+        ("http://ex.org:8080#f", "http://ex.org:8080/#f"),
+        ("https://ex.org:443/a", "https://ex.org/a"),
+        ("https://ex.org:80/a", "https://ex.org:80/a"),
+        ("http://[::80]/a", "http://[::80]/a"),
+        ("http://[::1]:80/a", "http://[::1]/a"),
+        ("wss://ex.org:443", "wss://ex.org/"),
+        ("ftp://user@ex.org:21/a/../b", "ftp://user@ex.org/b"),
+        ("foo://ex.org:80", "foo://ex.org:80"),
+        ("tag:ex.org:80", "tag:ex.org:80"),
+    ];
+
+    #[test]
+    fn eq_normalized() {
+        // dot-segments are not removed by iri_eq_normalized
+        let cases = CASES
+            .iter()
+            .filter(|(txt, _)| !txt.contains("/.") && !txt.contains("%2E"));
+        for (txt, expected) in cases {
+            assert!(iri_eq_normalized(txt, expected), "{txt}");
+            assert!(iri_eq_normalized(expected, txt), "{txt}");
+        }
+        for (a, b) in [
+            ("http://ex.org/%41", "http://ex.org/A"),
+            ("http://ex.org/%c3%A9", "http://ex.org/%C3%a9"),
+            ("http://ÉX.org/", "http://éx.org/"),
+            ("http://%C3%89x.org/", "http://éx.org/"),
+            ("HTTP://ex.org", "http://ex.org"),
+            ("//EX.org/a", "//ex.org/a"),
+            ("a/b?%41#%7E", "a/b?A#~"),
+        ] {
+            assert!(iri_eq_normalized(a, b), "{a} {b}");
+        }
+        for (a, b) in [
+            ("http://ex.org/a", "http://ex.org/A"),
+            ("http://User@ex.org/", "http://user@ex.org/"),
+            ("http://ex.org/%2F", "http://ex.org//"),
+            ("http://ex.org/%FF", "http://ex.org/%FE"),
+            ("http://ex.org/a", "http://ex.org/a/"),
+            ("http://ex.org/a/../b", "http://ex.org/b"),
+            ("http://ex.org?Q", "http://ex.org?q"),
+            ("tag:A", "tag:a"),
+        ] {
+            assert!(!iri_eq_normalized(a, b), "{a} {b}");
+            assert!(!iri_eq_normalized(b, a), "{a} {b}");
+        }
+    }
+
+    #[test]
+    fn normalize_scheme_based_iris() {
+        for (txt, expected) in SCHEME_CASES {
+            assert_eq!(&normalize_scheme_based(txt), expected, "{txt}");
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! I provide [`percent_encode`] and [`percent_decode`],
+//! for safely building IRI components from arbitrary text, and back.
+use super::_normalize::is_unreserved;
+use super::InvalidPercentEncoding;
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// The components of an IRI that [`percent_encode`] can produce.
+///
+/// Each of them determines which characters are kept as is
+/// (besides unreserved characters, including non-ASCII letters, which are always kept).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IriComponent {
+    /// A single segment of a path: `/`, `?` and `#` are encoded.
+    Segment,
+    /// A whole path: `/` is kept, `?` and `#` are encoded.
+    Path,
+    /// A whole query: `/`, `?`, `&` and `=` are kept, `#` is encoded.
+    Query,
+    /// A key or a value in a `key=value` pair of a query:
+    /// `&`, `=`, `+` and `#` are encoded.
+    QueryPart,
+    /// A fragment identifier: `/`, `?` are kept, `#` is encoded.
+    Fragment,
+}
+
+impl IriComponent {
+    /// Whether `c` is allowed as is in this component
+    /// (not considering unreserved characters).
+    fn allows(self, c: char) -> bool {
+        match self {
+            IriComponent::Segment => is_sub_delim(c) || matches!(c, ':' | '@'),
+            IriComponent::Path => is_sub_delim(c) || matches!(c, ':' | '@' | '/'),
+            IriComponent::Query | IriComponent::Fragment => {
+                is_sub_delim(c) || matches!(c, ':' | '@' | '/' | '?')
+            }
+            IriComponent::QueryPart => {
+                (is_sub_delim(c) && !matches!(c, '&' | '=' | '+'))
+                    || matches!(c, ':' | '@' | '/' | '?')
+            }
+        }
+    }
+}
+
+/// Percent-encode `txt` so that it can be used as the given `component` of an IRI.
+///
+/// Every character that is neither unreserved nor allowed in `component`
+/// is percent-encoded (including `%` itself),
+/// so that [`percent_decode`] always gives back the original text.
+///
+/// # Example
+/// ```
+/// # use sophia_iri::{percent_encode, IriComponent};
+/// assert_eq!(percent_encode("a/b c", IriComponent::Segment), "a%2Fb%20c");
+/// assert_eq!(percent_encode("a/b c", IriComponent::Path), "a/b%20c");
+/// assert_eq!(percent_encode("café", IriComponent::Segment), "café");
+/// ```
+pub fn percent_encode(txt: &str, component: IriComponent) -> Cow<'_, str> {
+    let allowed = |c| component.allows(c);
+    if txt.chars().all(|c| is_unreserved(c) || allowed(c)) {
+        return Cow::Borrowed(txt);
+    }
+    let mut out = String::with_capacity(txt.len() * 3 / 2);
+    push_encoded(&mut out, txt, allowed);
+    Cow::Owned(out)
+}
+
+/// Decode all the percent-encoded octets of `txt`.
+///
+/// This fails if a `%` is not followed by two hexadecimal digits,
+/// or if the decoded octets are not valid UTF-8.
+///
+/// # Example
+/// ```
+/// # use sophia_iri::percent_decode;
+/// assert_eq!(percent_decode("a%2Fb%20caf%C3%A9").unwrap(), "a/b café");
+/// assert!(percent_decode("100%").is_err());
+/// assert!(percent_decode("%FF").is_err());
+/// ```
+pub fn percent_decode(txt: &str) -> Result<Cow<'_, str>, InvalidPercentEncoding> {
+    if !txt.contains('%') {
+        return Ok(Cow::Borrowed(txt));
+    }
+    let err = || InvalidPercentEncoding(txt.to_string());
+    let mut bytes = Vec::with_capacity(txt.len());
+    let mut rest = txt.as_bytes();
+    while let Some((b, tail)) = rest.split_first() {
+        if *b == b'%' {
+            let hex = tail
+                .get(..2)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .ok_or_else(err)?;
+            bytes.push(u8::from_str_radix(core::str::from_utf8(hex).unwrap(), 16).unwrap());
+            rest = &tail[2..];
+        } else {
+            bytes.push(*b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).map(Cow::Owned).map_err(|_| err())
+}
+
+/// Push `txt` into `out`, percent-encoding every character
+/// that is neither unreserved nor `allowed`.
+pub(crate) fn push_encoded<F: Fn(char) -> bool>(out: &mut String, txt: &str, allowed: F) {
+    for c in txt.chars() {
+        if is_unreserved(c) || allowed(c) {
+            out.push(c);
+        } else {
+            let mut buf = [0; 4];
+            for b in c.encode_utf8(&mut buf).bytes() {
+                write!(out, "%{b:02X}").unwrap();
+            }
+        }
+    }
+}
+
+/// Whether `c` matches the `sub-delims` rule of RFC 3986.
+pub(crate) fn is_sub_delim(c: char) -> bool {
+    matches!(
+        c,
+        '!' | '$' | '&' | '\'' | '(' | ')' | '*' | '+' | ',' | ';' | '='
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Iri;
+    use alloc::format;
+
+    const COMPONENTS: &[IriComponent] = &[
+        IriComponent::Segment,
+        IriComponent::Path,
+        IriComponent::Query,
+        IriComponent::QueryPart,
+        IriComponent::Fragment,
+    ];
+
+    const TEXTS: &[&str] = &[
+        "",
+        "abc",
+        "a b",
+        "a/b?c#d",
+        "k=v&k2=v+2",
+        "100%",
+        "%41",
+        "café ü 例え",
+        "\u{E000}",
+        "<\"{|}\\^`>",
+        "\t\n\u{7F}",
+    ];
+
+    #[test]
+    fn encode() {
+        use IriComponent::*;
+        let cases = [
+            ("a/b?c#d", Segment, "a%2Fb%3Fc%23d"),
+            ("a/b?c#d", Path, "a/b%3Fc%23d"),
+            ("a/b?c#d", Query, "a/b?c%23d"),
+            ("a/b?c#d", QueryPart, "a/b?c%23d"),
+            ("a/b?c#d", Fragment, "a/b?c%23d"),
+            ("k=v&k2=v+2", Query, "k=v&k2=v+2"),
+            ("k=v&k2=v+2", QueryPart, "k%3Dv%26k2%3Dv%2B2"),
+            ("100%", Segment, "100%25"),
+            ("\u{E000}", Segment, "%EE%80%80"),
+        ];
+        for (txt, component, expected) in cases {
+            assert_eq!(
+                percent_encode(txt, component),
+                expected,
+                "{txt} {component:?}"
+            );
+        }
+        assert!(matches!(
+            percent_encode("café", Segment),
+            Cow::Borrowed("café")
+        ));
+    }
+
+    #[test]
+    fn roundtrip() {
+        for component in COMPONENTS {
+            for txt in TEXTS {
+                let encoded = percent_encode(txt, *component);
+                assert!(
+                    Iri::new(format!("tag:x/{encoded}")).is_ok(),
+                    "{txt} {component:?}"
+                );
+                assert_eq!(&percent_decode(&encoded).unwrap(), txt);
+            }
+        }
+    }
+
+    #[test]
+    fn decode_errors() {
+        for txt in ["%", "%4", "a%4", "%zz", "%+1", "%C3", "%C3%28", "%é"] {
+            assert!(percent_decode(txt).is_err(), "{txt}");
+        }
+    }
+}
@@ -12,7 +12,7 @@ impl<'a, T: Borrow<str> + Deserialize<'a>> Deserialize<'a> for Iri<T> {
     {
         let inner: T = T::deserialize(deserializer)?;
         Iri::new(inner)
-            .map_err(|err| D::Error::invalid_value(Unexpected::Str(&err.0), &"valid IRI"))
+            .map_err(|err| D::Error::invalid_value(Unexpected::Str(&err.iri), &"valid IRI"))
     }
 }
 
@@ -31,8 +31,9 @@ impl<'a, T: Borrow<str> + Deserialize<'a>> Deserialize<'a> for IriRef<T> {
         D: serde::Deserializer<'a>,
     {
         let inner: T = T::deserialize(deserializer)?;
-        IriRef::new(inner)
-            .map_err(|err| D::Error::invalid_value(Unexpected::Str(&err.0), &"valid IRI reference"))
+        IriRef::new(inner).map_err(|err| {
+            D::Error::invalid_value(Unexpected::Str(&err.iri), &"valid IRI reference")
+        })
     }
 }
 
@@ -0,0 +1,593 @@
+//! I provide [`IriTemplate`], implementing [RFC 6570](https://www.rfc-editor.org/rfc/rfc6570) URI templates
+//! (up to level 4).
+use super::_percent::is_sub_delim;
+use super::{InvalidIri, InvalidIriTemplate, Iri};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::hash::Hash;
+
+/// A parsed [URI template](https://www.rfc-editor.org/rfc/rfc6570),
+/// that can be [expanded](IriTemplate::expand) into IRIs.
+///
+/// All levels of the RFC are supported,
+/// including prefix (`{var:3}`) and explode (`{list*}`) modifiers.
+///
+/// # Example
+/// ```
+/// # use sophia_iri::{IriTemplate, TemplateValue};
+/// # use std::collections::HashMap;
+/// let template = IriTemplate::new("http://example.org/search{/path*}{?q,page}").unwrap();
+/// let vars = HashMap::from([
+///     ("path", TemplateValue::list(["books", "sci-fi"])),
+///     ("q", "dune & co".into()),
+/// ]);
+/// let iri = template.expand(&vars).unwrap();
+/// assert_eq!(iri.as_str(), "http://example.org/search/books/sci-fi?q=dune%20%26%20co");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IriTemplate {
+    template: String,
+    parts: Vec<Part>,
+}
+
+/// The value of a variable in an [`IriTemplate`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TemplateValue {
+    /// A simple string value
+    String(String),
+    /// A list of values
+    List(Vec<String>),
+    /// An ordered list of `(key, value)` pairs
+    Map(Vec<(String, String)>),
+}
+
+impl TemplateValue {
+    /// Build a [`TemplateValue::List`].
+    pub fn list<I, T>(items: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        TemplateValue::List(items.into_iter().map(Into::into).collect())
+    }
+
+    /// Build a [`TemplateValue::Map`].
+    pub fn map<I, K, V>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        TemplateValue::Map(
+            pairs
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+
+    /// Whether this value is considered as undefined (empty list or map).
+    fn is_undefined(&self) -> bool {
+        match self {
+            TemplateValue::String(_) => false,
+            TemplateValue::List(items) => items.is_empty(),
+            TemplateValue::Map(pairs) => pairs.is_empty(),
+        }
+    }
+}
+
+impl From<&str> for TemplateValue {
+    fn from(value: &str) -> Self {
+        TemplateValue::String(value.to_string())
+    }
+}
+
+impl From<String> for TemplateValue {
+    fn from(value: String) -> Self {
+        TemplateValue::String(value)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Expression(Operator, Vec<VarSpec>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct VarSpec {
+    name: String,
+    prefix: Option<usize>,
+    explode: bool,
+}
+
+/// The operators of RFC 6570 §3.2, with their expansion parameters (Appendix A).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operator {
+    Simple,
+    Reserved,
+    Fragment,
+    Label,
+    PathSegment,
+    PathParameter,
+    Query,
+    QueryContinuation,
+}
+
+impl Operator {
+    fn parse(c: char) -> Option<Self> {
+        Some(match c {
+            '+' => Operator::Reserved,
+            '#' => Operator::Fragment,
+            '.' => Operator::Label,
+            '/' => Operator::PathSegment,
+            ';' => Operator::PathParameter,
+            '?' => Operator::Query,
+            '&' => Operator::QueryContinuation,
+            _ => return None,
+        })
+    }
+
+    fn first(self) -> &'static str {
+        match self {
+            Operator::Simple | Operator::Reserved => "",
+            Operator::Fragment => "#",
+            Operator::Label => ".",
+            Operator::PathSegment => "/",
+            Operator::PathParameter => ";",
+            Operator::Query => "?",
+            Operator::QueryContinuation => "&",
+        }
+    }
+
+    fn sep(self) -> &'static str {
+        match self {
+            Operator::Simple | Operator::Reserved | Operator::Fragment => ",",
+            Operator::Label => ".",
+            Operator::PathSegment => "/",
+            Operator::PathParameter => ";",
+            Operator::Query | Operator::QueryContinuation => "&",
+        }
+    }
+
+    fn named(self) -> bool {
+        matches!(
+            self,
+            Operator::PathParameter | Operator::Query | Operator::QueryContinuation
+        )
+    }
+
+    fn if_empty(self) -> &'static str {
+        match self {
+            Operator::Query | Operator::QueryContinuation => "=",
+            _ => "",
+        }
+    }
+
+    fn allow_reserved(self) -> bool {
+        matches!(self, Operator::Reserved | Operator::Fragment)
+    }
+}
+
+impl IriTemplate {
+    /// Parse the given URI template.
+    pub fn new(template: &str) -> Result<Self, InvalidIriTemplate> {
+        let err = || InvalidIriTemplate(template.to_string());
+        let mut parts = vec![];
+        let mut rest = template;
+        while !rest.is_empty() {
+            if let Some(expr) = rest.strip_prefix('{') {
+                let end = expr.find('}').ok_or_else(err)?;
+                parts.push(parse_expression(&expr[..end]).ok_or_else(err)?);
+                rest = &expr[end + 1..];
+            } else {
+                let end = rest.find('{').unwrap_or(rest.len());
+                let literal = parse_literal(&rest[..end]).ok_or_else(err)?;
+                parts.push(Part::Literal(literal));
+                rest = &rest[end..];
+            }
+        }
+        Ok(IriTemplate {
+            template: template.to_string(),
+            parts,
+        })
+    }
+
+    /// The text of this template.
+    pub fn as_str(&self) -> &str {
+        &self.template
+    }
+
+    /// The names of the variables used in this template, in order of first appearance.
+    pub fn variables(&self) -> Vec<&str> {
+        let mut ret: Vec<&str> = vec![];
+        for part in &self.parts {
+            if let Part::Expression(_, vars) = part {
+                for var in vars {
+                    if !ret.contains(&var.name.as_str()) {
+                        ret.push(&var.name);
+                    }
+                }
+            }
+        }
+        ret
+    }
+
+    /// Expand this template with the given variables,
+    /// missing variables being considered as undefined.
+    ///
+    /// This fails if the result is not a valid (absolute) IRI.
+    pub fn expand<K>(&self, vars: &HashMap<K, TemplateValue>) -> Result<Iri<String>, InvalidIri>
+    where
+        K: Borrow<str> + Hash + Eq,
+    {
+        Iri::new(self.expand_raw(vars))
+    }
+
+    fn expand_raw<K>(&self, vars: &HashMap<K, TemplateValue>) -> String
+    where
+        K: Borrow<str> + Hash + Eq,
+    {
+        let mut out = String::with_capacity(self.template.len() * 2);
+        for part in &self.parts {
+            match part {
+                Part::Literal(txt) => out.push_str(txt),
+                Part::Expression(op, specs) => expand_expression(&mut out, *op, specs, vars),
+            }
+        }
+        out
+    }
+}
+
+impl std::str::FromStr for IriTemplate {
+    type Err = InvalidIriTemplate;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        IriTemplate::new(s)
+    }
+}
+
+/// Parse a literal part of a template, percent-encoding characters not allowed in URIs.
+fn parse_literal(txt: &str) -> Option<String> {
+    let mut out = String::with_capacity(txt.len());
+    let mut chars = txt.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '%' {
+            if !is_pct_encoded(&txt[i..]) {
+                return None;
+            }
+            out.push_str(&txt[i..i + 3]);
+            chars.nth(1);
+        } else if c.is_control()
+            || matches!(
+                c,
+                ' ' | '"' | '\'' | '<' | '>' | '\\' | '^' | '`' | '{' | '|' | '}'
+            )
+        {
+            return None;
+        } else {
+            push_encoded(&mut out, &c.to_string(), true);
+        }
+    }
+    Some(out)
+}
+
+/// Parse the content of an expression (between braces).
+fn parse_expression(txt: &str) -> Option<Part> {
+    // all operators are ASCII characters
+    let (op, list) = match txt.chars().next().and_then(Operator::parse) {
+        Some(op) => (op, &txt[1..]),
+        None => (Operator::Simple, txt),
+    };
+    let specs = list
+        .split(',')
+        .map(parse_varspec)
+        .collect::<Option<Vec<_>>>()?;
+    Some(Part::Expression(op, specs))
+}
+
+/// Parse a variable name with its optional modifier.
+fn parse_varspec(txt: &str) -> Option<VarSpec> {
+    let (name, prefix, explode) = if let Some(name) = txt.strip_suffix('*') {
+        (name, None, true)
+    } else if let Some((name, len)) = txt.split_once(':') {
+        if len.is_empty() || len.len() > 4 || !len.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        (name, Some(len.parse().ok().filter(|len| *len > 0)?), false)
+    } else {
+        (txt, None, false)
+    };
+    let valid_name = !name.is_empty()
+        && name.split('.').all(|part| {
+            let mut rest = part;
+            while let Some(c) = rest.chars().next() {
+                if c == '%' && is_pct_encoded(rest) {
+                    rest = &rest[3..];
+                } else if c.is_ascii_alphanumeric() || c == '_' {
+                    rest = &rest[1..];
+                } else {
+                    return false;
+                }
+            }
+            !part.is_empty()
+        });
+    valid_name.then(|| VarSpec {
+        name: name.to_string(),
+        prefix,
+        explode,
+    })
+}
+
+/// Expand an expression into `out`, as per RFC 6570 Appendix A.
+fn expand_expression<K>(
+    out: &mut String,
+    op: Operator,
+    specs: &[VarSpec],
+    vars: &HashMap<K, TemplateValue>,
+) where
+    K: Borrow<str> + Hash + Eq,
+{
+    let reserved = op.allow_reserved();
+    let mut first = true;
+    for spec in specs {
+        let Some(value) = vars.get(spec.name.as_str()).filter(|v| !v.is_undefined()) else {
+            continue;
+        };
+        out.push_str(if first { op.first() } else { op.sep() });
+        first = false;
+        match value {
+            TemplateValue::String(value) => {
+                if op.named() {
+                    push_name_value(out, &spec.name, value.is_empty(), op);
+                }
+                let value = match spec.prefix {
+                    Some(len) => value
+                        .char_indices()
+                        .nth(len)
+                        .map_or(&value[..], |(i, _)| &value[..i]),
+                    None => value,
+                };
+                push_encoded(out, value, reserved);
+            }
+            TemplateValue::List(items) if spec.explode => {
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(op.sep());
+                    }
+                    if op.named() {
+                        push_name_value(out, &spec.name, item.is_empty(), op);
+                    }
+                    push_encoded(out, item, reserved);
+                }
+            }
+            TemplateValue::Map(pairs) if spec.explode => {
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(op.sep());
+                    }
+                    push_encoded(out, key, reserved);
+                    out.push_str(if op.named() && value.is_empty() {
+                        op.if_empty()
+                    } else {
+                        "="
+                    });
+                    push_encoded(out, value, reserved);
+                }
+            }
+            TemplateValue::List(items) => {
+                if op.named() {
+                    push_name_value(out, &spec.name, false, op);
+                }
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    push_encoded(out, item, reserved);
+                }
+            }
+            TemplateValue::Map(pairs) => {
+                if op.named() {
+                    push_name_value(out, &spec.name, false, op);
+                }
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    push_encoded(out, key, reserved);
+                    out.push(',');
+                    push_encoded(out, value, reserved);
+                }
+            }
+        }
+    }
+}
+
+/// Push the name of a variable followed by `=`, or by [`Operator::if_empty`] if `empty`.
+fn push_name_value(out: &mut String, name: &str, empty: bool, op: Operator) {
+    out.push_str(name);
+    out.push_str(if empty { op.if_empty() } else { "=" });
+}
+
+/// Push `txt` into `out`, percent-encoding every character that is not unreserved
+/// (as defined by RFC 3986),
+/// or, if `reserved` is true, that is neither unreserved, reserved, nor part of a percent-encoded octet.
+fn push_encoded(out: &mut String, txt: &str, reserved: bool) {
+    for (i, c) in txt.char_indices() {
+        let keep = c.is_ascii_alphanumeric()
+            || matches!(c, '-' | '.' | '_' | '~')
+            || (reserved
+                && (is_sub_delim(c)
+                    || matches!(c, ':' | '/' | '?' | '#' | '[' | ']' | '@')
+                    || (c == '%' && is_pct_encoded(&txt[i..]))));
+        if keep {
+            out.push(c);
+        } else {
+            let mut buf = [0; 4];
+            for b in c.encode_utf8(&mut buf).bytes() {
+                write!(out, "%{b:02X}").unwrap();
+            }
+        }
+    }
+}
+
+/// Whether `txt` starts with a percent-encoded octet.
+fn is_pct_encoded(txt: &str) -> bool {
+    let b = txt.as_bytes();
+    b.len() >= 3 && b[0] == b'%' && b[1].is_ascii_hexdigit() && b[2].is_ascii_hexdigit()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn vars() -> HashMap<&'static str, TemplateValue> {
+        HashMap::from([
+            ("var", "value".into()),
+            ("hello", "Hello World!".into()),
+            ("path", "/foo/bar".into()),
+            ("empty", "".into()),
+            ("x", "1024".into()),
+            ("y", "768".into()),
+            ("list", TemplateValue::list(["red", "green", "blue"])),
+            (
+                "keys",
+                TemplateValue::map([("semi", ";"), ("dot", "."), ("comma", ",")]),
+            ),
+            ("empty_keys", TemplateValue::map::<_, &str, &str>([])),
+        ])
+    }
+
+    /// Examples from RFC 6570 §1.2 and §3.2
+    const CASES: &[(&str, &str)] = &[
+        // level 1
+        ("{var}", "value"),
+        ("{hello}", "Hello%20World%21"),
+        ("{undef}", ""),
+        ("{empty_keys}", ""),
+        // level 2
+        ("{+var}", "value"),
+        ("{+hello}", "Hello%20World!"),
+        ("{+path}/here", "/foo/bar/here"),
+        ("here?ref={+path}", "here?ref=/foo/bar"),
+        ("X{#var}", "X#value"),
+        ("X{#hello}", "X#Hello%20World!"),
+        // level 3
+        ("map?{x,y}", "map?1024,768"),
+        ("{x,hello,y}", "1024,Hello%20World%21,768"),
+        ("{+x,hello,y}", "1024,Hello%20World!,768"),
+        ("{+path,x}/here", "/foo/bar,1024/here"),
+        ("{#x,hello,y}", "#1024,Hello%20World!,768"),
+        ("{#path,x}/here", "#/foo/bar,1024/here"),
+        ("X{.var}", "X.value"),
+        ("X{.x,y}", "X.1024.768"),
+        ("{/var}", "/value"),
+        ("{/var,x}/here", "/value/1024/here"),
+        ("{;x,y}", ";x=1024;y=768"),
+        ("{;x,y,empty}", ";x=1024;y=768;empty"),
+        ("{?x,y}", "?x=1024&y=768"),
+        ("{?x,y,empty}", "?x=1024&y=768&empty="),
+        ("?fixed=yes{&x}", "?fixed=yes&x=1024"),
+        ("{&x,y,empty}", "&x=1024&y=768&empty="),
+        ("{x,undef,y}", "1024,768"),
+        // level 4
+        ("{var:3}", "val"),
+        ("{var:30}", "value"),
+        ("{list}", "red,green,blue"),
+        ("{list*}", "red,green,blue"),
+        ("{keys}", "semi,%3B,dot,.,comma,%2C"),
+        ("{keys*}", "semi=%3B,dot=.,comma=%2C"),
+        ("{+path:6}/here", "/foo/b/here"),
+        ("{+list}", "red,green,blue"),
+        ("{+list*}", "red,green,blue"),
+        ("{+keys}", "semi,;,dot,.,comma,,"),
+        ("{+keys*}", "semi=;,dot=.,comma=,"),
+        ("{#path:6}/here", "#/foo/b/here"),
+        ("{#list}", "#red,green,blue"),
+        ("{#list*}", "#red,green,blue"),
+        ("{#keys}", "#semi,;,dot,.,comma,,"),
+        ("{#keys*}", "#semi=;,dot=.,comma=,"),
+        ("X{.var:3}", "X.val"),
+        ("X{.list}", "X.red,green,blue"),
+        ("X{.list*}", "X.red.green.blue"),
+        ("X{.keys}", "X.semi,%3B,dot,.,comma,%2C"),
+        ("X{.keys*}", "X.semi=%3B.dot=..comma=%2C"),
+        ("{/var:1,var}", "/v/value"),
+        ("{/list}", "/red,green,blue"),
+        ("{/list*}", "/red/green/blue"),
+        ("{/list*,path:4}", "/red/green/blue/%2Ffoo"),
+        ("{/keys}", "/semi,%3B,dot,.,comma,%2C"),
+        ("{/keys*}", "/semi=%3B/dot=./comma=%2C"),
+        ("{;hello:5}", ";hello=Hello"),
+        ("{;list}", ";list=red,green,blue"),
+        ("{;list*}", ";list=red;list=green;list=blue"),
+        ("{;keys}", ";keys=semi,%3B,dot,.,comma,%2C"),
+        ("{;keys*}", ";semi=%3B;dot=.;comma=%2C"),
+        ("{?var:3}", "?var=val"),
+        ("{?list}", "?list=red,green,blue"),
+        ("{?list*}", "?list=red&list=green&list=blue"),
+        ("{?keys}", "?keys=semi,%3B,dot,.,comma,%2C"),
+        ("{?keys*}", "?semi=%3B&dot=.&comma=%2C"),
+        ("{&var:3}", "&var=val"),
+        ("{&list}", "&list=red,green,blue"),
+        ("{&list*}", "&list=red&list=green&list=blue"),
+        ("{&keys}", "&keys=semi,%3B,dot,.,comma,%2C"),
+        ("{&keys*}", "&semi=%3B&dot=.&comma=%2C"),
+        // literals
+        ("caf\u{e9}/%41{var}", "caf%C3%A9/%41value"),
+    ];
+
+    #[test]
+    fn expansion() {
+        let vars = vars();
+        for (template, expected) in CASES {
+            let t = IriTemplate::new(template).unwrap();
+            assert_eq!(&t.expand_raw(&vars), expected, "{template}");
+        }
+    }
+
+    #[test]
+    fn expand_to_iri() {
+        let t: IriTemplate = "http://example.org/{var}{?list*}".parse().unwrap();
+        assert_eq!(t.variables(), ["var", "list"]);
+        let iri = t.expand(&vars()).unwrap();
+        assert_eq!(
+            iri.as_str(),
+            "http://example.org/value?list=red&list=green&list=blue"
+        );
+        let no_vars = HashMap::<String, TemplateValue>::new();
+        assert_eq!(t.expand(&no_vars).unwrap().as_str(), "http://example.org/");
+        // not an absolute IRI
+        assert!(IriTemplate::new("{var}").unwrap().expand(&vars()).is_err());
+    }
+
+    #[test]
+    fn invalid() {
+        for template in [
+            "{",
+            "{var",
+            "}{",
+            "{}",
+            "{var,}",
+            "{!var}",
+            "{=var}",
+            "{var:}",
+            "{var:0}",
+            "{var:10000}",
+            "{var:3*}",
+            "{va r}",
+            "{.}",
+            "{a..b}",
+            "a b",
+            "a%2",
+            "a|b",
+            "a\"b",
+        ] {
+            assert!(IriTemplate::new(template).is_err(), "{template}");
+        }
+        for template in ["", "{a.b}", "{a%2Fb}", "{_0}", "{+a:9999}"] {
+            assert!(IriTemplate::new(template).is_ok(), "{template}");
+        }
+    }
+}
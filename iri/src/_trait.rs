@@ -1,6 +1,6 @@
 //! IRI related traits.
 use super::{Iri, IriRef};
-use std::borrow::Borrow;
+use core::borrow::Borrow;
 
 /// Marker trait guaranteeing that the underlying `str` is a valid IRI
 /// (i.e. absolute or relative, with an optional fragment identifier)
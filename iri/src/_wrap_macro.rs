@@ -14,7 +14,10 @@
 ///     MyWrapper<T: SomeTrait> :
 ///
 ///     // NB: the trait bound is required, as well as the trailing colon (':').
-///     // The derive clause, however, is optional (it will default to Clone, Copy, Debug).
+///     // The attributes of the wrapper type, however, are optional
+///     // (they default to a derive clause for Clone, Copy, Debug).
+///     // When provided, they replace that default, and may be any outer attribute
+///     // (e.g. other derives, or `#[repr(transparent)]`).
 ///
 ///     // You can include members in the impl of the wrapper type.
 ///     // At the very least, you must define a `new` constructor as the first member,
@@ -53,16 +56,121 @@
 /// }
 /// ```
 ///
+/// In the specific case of `borrowing str`,
+/// the `new` constructor must have the signature `fn new(inner: T) -> Result<Self, E>`,
+/// and the wrapper also implements `TryFrom<&str>` (for `Foo<&str>`),
+/// `TryFrom<String>` and [`FromStr`](core::str::FromStr) (for `Foo<String>`),
+/// all delegating to `new`.
+/// It also gets a `try_map` method, a checked counterpart of `map_unchecked`,
+/// which applies a function to the wrapped value and passes the result to `new`.
+/// Attributes can also be given to this form, but they do not replace its default derive clause;
+/// they are added to `#[derive(Clone, Copy)]` (and `Debug` is implemented by the macro):
+/// ```
+/// # #[macro_use] extern crate sophia_iri;
+/// wrap! { #[repr(transparent)] Foo borrowing str :
+///     /// As before
+///     pub fn new(inner: T) -> Result<Self, String> {
+///         if inner.borrow().contains("foo") {
+///             Ok(Self(inner))
+///         } else {
+///             Err(format!("{:?} is not a valid Foo", inner.borrow()))
+///         }
+///     }
+/// }
+/// let foo: Foo<String> = "a foo".parse().unwrap();
+/// assert_eq!(&foo, "a foo");
+/// assert!(Foo::<&str>::try_from("a bar").is_err());
+/// assert_eq!(&foo.clone().try_map(|txt| txt.to_uppercase() + " foo").unwrap(), "A FOO foo");
+/// assert!(foo.try_map(|txt| txt.replace("foo", "bar")).is_err());
+/// ```
+///
+/// Both forms can be prefixed with the `serde` keyword,
+/// in order to also implement [`Serialize`](https://docs.rs/serde/latest/serde/trait.Serialize.html)
+/// (transparently, i.e. as the wrapped value)
+/// and [`Deserialize`](https://docs.rs/serde/latest/serde/trait.Deserialize.html)
+/// (checking the wrapped value with the `new` constructor) for the wrapper.
+/// This requires the `serde` feature of this crate
+/// (but not a direct dependency to `serde` in the crate using the macro):
+#[cfg_attr(feature = "serde", doc = "```")]
+#[cfg_attr(not(feature = "serde"), doc = "```ignore")]
+/// # #[macro_use] extern crate sophia_iri;
+/// wrap! { serde Foo borrowing str :
+///     /// As before
+///     pub fn new(inner: T) -> Result<Self, String> {
+///         if inner.borrow().contains("foo") {
+///             Ok(Self(inner))
+///         } else {
+///             Err(format!("{:?} is not a valid Foo", inner.borrow()))
+///         }
+///     }
+/// }
+/// ```
+///
 /// Two [examples](crate::wrap_macro_examples) are available,
 /// illustrating the members and trait implementation generated by this macro.
 ///
 /// NB: the documentation of the wrapper will point to the documentation of the `new` method.
 macro_rules! wrap {
 
-    // general wrapper definition
-    (#[derive($($derive:ident),*)] $wid:ident<$tid:ident: $bound:path>: $new:item $($item:item)*) => {
-        #[derive($($derive),*)]
+    // wrapper definitions with the "serde" keyword
+    (serde $(#[$meta:meta])+ $wid:ident<$tid:ident: $bound:path>: $new:item $($item:item)*) => {
+        $crate::wrap!( $(#[$meta])+ $wid<$tid: $bound>: $new $($item)* );
+        $crate::wrap!( @serde $wid<$tid: $bound> );
+    };
+    (serde $wid:ident<$tid:ident: $bound:path>: $new:item $($item:item)*) => {
+        $crate::wrap!( $wid<$tid: $bound>: $new $($item)* );
+        $crate::wrap!( @serde $wid<$tid: $bound> );
+    };
+    (serde $(#[$meta:meta])* $wid:ident borrowing str: $(#[$attr:meta])* $vis:vis fn new($arg:ident: T) -> Result<Self, $err:ty> $body:block $($item:item)*) => {
+        $crate::wrap!( $(#[$meta])* $wid borrowing str: $(#[$attr])* $vis fn new($arg: T) -> Result<Self, $err> $body $($item)* );
+        $crate::wrap!( @serde $wid<T: core::borrow::Borrow<str>> );
+    };
+    (serde $(#[$meta:meta])+ $wid:ident borrowing $bid:ty: $new:item $($item:item)*) => {
+        $crate::wrap!( $(#[$meta])+ $wid borrowing $bid: $new $($item)* );
+        $crate::wrap!( @serde $wid<T: core::borrow::Borrow<$bid>> );
+    };
+    (serde $wid:ident borrowing $bid:ty: $new:item $($item:item)*) => {
+        $crate::wrap!( $wid borrowing $bid: $new $($item)* );
+        $crate::wrap!( @serde $wid<T: core::borrow::Borrow<$bid>> );
+    };
+
+    // serde implementations for a wrapper defined with the "serde" keyword
+    (@serde $wid:ident<$tid:ident: $bound:path>) => {
+        impl<$tid> $crate::__serde::Serialize for $wid<$tid>
+        where
+            $tid: $bound + $crate::__serde::Serialize,
+        {
+            fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::__serde::Serializer,
+            {
+                self.0.serialize(serializer)
+            }
+        }
+
+        impl<'de, $tid> $crate::__serde::Deserialize<'de> for $wid<$tid>
+        where
+            $tid: $bound + $crate::__serde::Deserialize<'de>,
+        {
+            fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+            where
+                D: $crate::__serde::Deserializer<'de>,
+            {
+                let inner = $tid::deserialize(deserializer)?;
+                $wid::new(inner).map_err(|err| {
+                    <D::Error as $crate::__serde::de::Error>::custom(core::format_args!(
+                        "invalid {}: {:?}",
+                        stringify!($wid),
+                        err
+                    ))
+                })
+            }
+        }
+    };
 
+    // general wrapper definition
+    ($(#[$meta:meta])+ $wid:ident<$tid:ident: $bound:path>: $new:item $($item:item)*) => {
+        $(#[$meta])+
         #[doc = concat!(
             "See [`",
             stringify!($wid),
@@ -116,65 +224,65 @@ macro_rules! wrap {
             }
         }
 
-        impl<T: $bound> std::ops::Deref for $wid<T> {
+        impl<T: $bound> core::ops::Deref for $wid<T> {
             type Target = T;
             fn deref(&self) -> &T {
                 &self.0
             }
         }
 
-        impl<T: $bound> std::convert::AsRef<T> for $wid<T> {
+        impl<T: $bound> core::convert::AsRef<T> for $wid<T> {
             fn as_ref(&self) -> &T {
                 &self.0
             }
         }
 
-        impl<T: $bound> std::borrow::Borrow<T> for $wid<T> {
+        impl<T: $bound> core::borrow::Borrow<T> for $wid<T> {
             fn borrow(&self) -> &T {
                 &self.0
             }
         }
 
-        impl<T, U> std::cmp::PartialEq<$wid<T>> for $wid<U>
+        impl<T, U> core::cmp::PartialEq<$wid<T>> for $wid<U>
         where
             T: $bound,
-            U: $bound + std::cmp::PartialEq<T>,
+            U: $bound + core::cmp::PartialEq<T>,
         {
             fn eq(&self, rhs: &$wid<T>) -> bool {
                 self.0 == rhs.0
             }
         }
 
-        impl<T> std::cmp::Eq for $wid<T>
+        impl<T> core::cmp::Eq for $wid<T>
         where
-            T: $bound + std::cmp::Eq,
+            T: $bound + core::cmp::Eq,
         {}
 
-        impl<T, U> std::cmp::PartialOrd<$wid<T>> for $wid<U>
+        impl<T, U> core::cmp::PartialOrd<$wid<T>> for $wid<U>
         where
             T: $bound,
-            U: $bound + std::cmp::PartialOrd<T>,
+            U: $bound + core::cmp::PartialOrd<T>,
         {
-            fn partial_cmp(&self, rhs: &$wid<T>) -> std::option::Option<std::cmp::Ordering> {
-                std::cmp::PartialOrd::partial_cmp(&self.0, &rhs.0)
+            fn partial_cmp(&self, rhs: &$wid<T>) -> core::option::Option<core::cmp::Ordering> {
+                core::cmp::PartialOrd::partial_cmp(&self.0, &rhs.0)
             }
         }
 
-        impl<T> std::cmp::Ord for $wid<T>
+        impl<T> core::cmp::Ord for $wid<T>
         where
-            T: $bound + std::cmp::Eq + std::cmp::Ord,
+            T: $bound + core::cmp::Eq + core::cmp::Ord,
         {
-            fn cmp(&self, rhs: &$wid<T>) -> std::cmp::Ordering {
-                std::cmp::Ord::cmp(&self.0, &rhs.0)
+            fn cmp(&self, rhs: &$wid<T>) -> core::cmp::Ordering {
+                core::cmp::Ord::cmp(&self.0, &rhs.0)
             }
         }
 
 
-        impl<T> std::hash::Hash for $wid<T>
+        impl<T> core::hash::Hash for $wid<T>
         where
-            T: $bound + std::hash::Hash,
+            T: $bound + core::hash::Hash,
         {
-            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
                 self.0.hash(state);
             }
         }
@@ -187,12 +295,15 @@ macro_rules! wrap {
     };
 
     // very specific wrapper definition for Borrow<str>, relying on the less specific "borrowing" definition below
-    ($wid:ident borrowing str: $new:item $($item:item)*) => {
+    ($(#[$meta:meta])* $wid:ident borrowing str: $(#[$attr:meta])* $vis:vis fn new($arg:ident: T) -> Result<Self, $err:ty> $body:block $($item:item)*) => {
         $crate::wrap!{
+            @borrowing
             #[derive(Clone, Copy)]
+            $(#[$meta])*
             $wid borrowing str:
 
-            $new
+            $(#[$attr])*
+            $vis fn new($arg: T) -> Result<Self, $err> $body
 
             $($item)*
 
@@ -200,23 +311,74 @@ macro_rules! wrap {
             pub fn as_str(&self) -> &str {
                 self.0.borrow()
             }
+
+            #[doc = concat!(
+                "Map a `",
+                stringify!($wid),
+                "<T>` to a `",
+                stringify!($wid),
+                "<U>` by applying a function to the wrapped value, ",
+                "and checking the result with [`",
+                stringify!($wid),
+                "::new`].",
+            )]
+            #[allow(dead_code)]
+            $vis fn try_map<F, U>(self, f: F) -> core::result::Result<$wid<U>, $err>
+            where
+                F: FnOnce(T) -> U,
+                U: core::borrow::Borrow<str>,
+            {
+                $wid::new(f(self.0))
+            }
         }
 
-        impl<T: std::borrow::Borrow<str>> std::fmt::Debug for $wid<T> {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        impl<T: core::borrow::Borrow<str>> core::fmt::Debug for $wid<T> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::result::Result<(), core::fmt::Error> {
                 write!(f, "{}({:?})", stringify!($wid), self.0.borrow())
             }
         }
+
+        impl<'a> core::convert::TryFrom<&'a str> for $wid<&'a str> {
+            type Error = $err;
+            fn try_from(value: &'a str) -> core::result::Result<Self, Self::Error> {
+                $wid::new(value)
+            }
+        }
+
+        impl core::convert::TryFrom<$crate::__String> for $wid<$crate::__String> {
+            type Error = $err;
+            fn try_from(value: $crate::__String) -> core::result::Result<Self, Self::Error> {
+                $wid::new(value)
+            }
+        }
+
+        impl core::str::FromStr for $wid<$crate::__String> {
+            type Err = $err;
+            fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
+                $wid::new($crate::__String::from(s))
+            }
+        }
     };
 
     // specific wrapper definition with the "borrowing" keyword ;
     // shortcut for Borrow<T>, and adds more trait implementation
-    (#[derive($($derive:ident),*)] $wid:ident borrowing $bid:ty: $new:item $($item:item)*) => {
-        $crate::wrap!( #[derive($($derive),*)] $wid<T: std::borrow::Borrow<$bid>>: $new $($item)* );
+    ($(#[$meta:meta])+ $wid:ident borrowing $bid:ty: $new:item $($item:item)*) => {
+        $crate::wrap!( @borrowing $(#[$meta])+ $wid borrowing $bid: $new $($item)* );
+    };
+
+    // same as the specific "borrowing" wrapper declaration above, with implicit derive clause
+    ($wid:ident borrowing $bid:ty: $new:item $($item:item)*) => {
+        $crate::wrap!( #[derive(Clone, Copy, Debug)] $wid borrowing $bid: $new $($item)* );
+    };
+
+    // implementation of the "borrowing" wrapper declarations above
+    // (also used by the "borrowing str" declaration, which would otherwise match its own expansion)
+    (@borrowing $(#[$meta:meta])+ $wid:ident borrowing $bid:ty: $new:item $($item:item)*) => {
+        $crate::wrap!( $(#[$meta])+ $wid<T: core::borrow::Borrow<$bid>>: $new $($item)* );
 
         impl<T> $wid<T>
         where
-            T: std::borrow::Borrow<$bid>,
+            T: core::borrow::Borrow<$bid>,
         {
             #[doc = concat!(
                 "Convert from `&",
@@ -248,63 +410,58 @@ macro_rules! wrap {
             }
         }
 
-        impl<T: std::borrow::Borrow<$bid>> std::convert::AsRef<$bid> for $wid<T> {
+        impl<T: core::borrow::Borrow<$bid>> core::convert::AsRef<$bid> for $wid<T> {
             fn as_ref(&self) -> &$bid {
                 &self.0.borrow()
             }
         }
 
-        impl<T: std::borrow::Borrow<$bid>> std::borrow::Borrow<$bid> for $wid<T> {
+        impl<T: core::borrow::Borrow<$bid>> core::borrow::Borrow<$bid> for $wid<T> {
             fn borrow(&self) -> &$bid {
                 &self.0.borrow()
             }
         }
 
-        impl<T> std::cmp::PartialEq<$bid> for $wid<T>
+        impl<T> core::cmp::PartialEq<$bid> for $wid<T>
         where
-            T: std::borrow::Borrow<$bid>,
-            $bid: std::cmp::PartialEq,
+            T: core::borrow::Borrow<$bid>,
+            $bid: core::cmp::PartialEq,
         {
                 fn eq(&self, other: &$bid) -> bool {
                     self.0.borrow() == other
                 }
         }
 
-        impl<T> std::cmp::PartialEq<$wid<T>> for $bid
+        impl<T> core::cmp::PartialEq<$wid<T>> for $bid
         where
-            T: std::borrow::Borrow<$bid>,
-            $bid: std::cmp::PartialEq,
+            T: core::borrow::Borrow<$bid>,
+            $bid: core::cmp::PartialEq,
         {
                 fn eq(&self, other: &$wid<T>) -> bool {
                     self == other.0.borrow()
                 }
         }
 
-        impl<T> std::cmp::PartialOrd<$bid> for $wid<T>
+        impl<T> core::cmp::PartialOrd<$bid> for $wid<T>
         where
-            T: std::borrow::Borrow<$bid>,
-            $bid: std::cmp::PartialOrd,
+            T: core::borrow::Borrow<$bid>,
+            $bid: core::cmp::PartialOrd,
         {
-            fn partial_cmp(&self, other: &$bid) -> std::option::Option<std::cmp::Ordering> {
+            fn partial_cmp(&self, other: &$bid) -> core::option::Option<core::cmp::Ordering> {
                 self.0.borrow().partial_cmp(other)
             }
         }
 
-        impl<T> std::cmp::PartialOrd<$wid<T>> for $bid
+        impl<T> core::cmp::PartialOrd<$wid<T>> for $bid
         where
-            T: std::borrow::Borrow<$bid>,
-            $bid: std::cmp::PartialOrd,
+            T: core::borrow::Borrow<$bid>,
+            $bid: core::cmp::PartialOrd,
         {
-            fn partial_cmp(&self, other: &$wid<T>) -> std::option::Option<std::cmp::Ordering> {
+            fn partial_cmp(&self, other: &$wid<T>) -> core::option::Option<core::cmp::Ordering> {
                 self.partial_cmp(other.0.borrow())
             }
         }
     };
-
-    // same as the specific "borrowing" wrapper declaration above, with implicit derive clause
-    ($wid:ident borrowing $bid:ty: $new:item $($item:item)*) => {
-        $crate::wrap!( #[derive(Clone, Copy, Debug)] $wid borrowing $bid: $new $($item)* );
-    };
 }
 
 #[cfg(test)]
@@ -367,14 +524,62 @@ pub mod test_simple_wrap {
     // only check that this compiles
     #[allow(dead_code)]
     fn borrow() {
-        use std::borrow::Borrow;
+        use core::borrow::Borrow;
         let even = Even(42);
         let _: &isize = even.borrow();
     }
 }
 
+#[cfg(test)]
+pub mod test_wrap_attributes {
+    use super::test_simple_wrap::Number;
+    use alloc::format;
+
+    wrap! {
+        #[derive(Clone, Debug, Default)]
+        #[repr(transparent)]
+        Even<T: Number>:
+        pub fn new(inner: T) -> Result<Self, ()> {
+            if inner.even() {
+                Ok(Even(inner))
+            } else {
+                Err(())
+            }
+        }
+    }
+
+    wrap! {
+        #[repr(transparent)]
+        Foo borrowing str:
+        pub fn new(inner: T) -> Result<Self, ()> {
+            if inner.borrow().contains("foo") {
+                Ok(Foo(inner))
+            } else {
+                Err(())
+            }
+        }
+    }
+
+    #[test]
+    fn derive() {
+        let even: Even<i32> = Even::default();
+        assert_eq!(*even, 0);
+        assert!(Even::new(*even).is_ok());
+    }
+
+    #[test]
+    fn borrowing_str() {
+        let foo = Foo::new("foo").unwrap();
+        assert_eq!(foo.as_str(), "foo");
+        assert_eq!(format!("{:?}", foo), "Foo(\"foo\")");
+    }
+}
+
 #[cfg(test)]
 pub mod test_wrap_borrowing {
+    use alloc::format;
+    use alloc::string::{String, ToString};
+
     wrap! { Foo borrowing str :
         /// The constructor of Foo
         pub fn new(inner: T) -> Result<Self, ()> {
@@ -412,6 +617,29 @@ pub mod test_wrap_borrowing {
         assert_eq!(f1a.as_str(), f1b.as_str());
     }
 
+    #[test]
+    fn try_from() {
+        assert!(Foo::<&str>::try_from("this foo is good").is_ok());
+        assert!(Foo::<&str>::try_from("this bar is bad").is_err());
+        assert!(Foo::<String>::try_from("this foo is good".to_string()).is_ok());
+        assert!(Foo::<String>::try_from("this bar is bad".to_string()).is_err());
+    }
+
+    #[test]
+    fn from_str() {
+        let foo: Foo<String> = "this foo is good".parse().unwrap();
+        assert_eq!(&foo, "this foo is good");
+        assert!("this bar is bad".parse::<Foo<String>>().is_err());
+    }
+
+    #[test]
+    fn try_map() {
+        let foo = Foo::new("foo").unwrap();
+        let foo2: Foo<String> = foo.try_map(|txt| format!("{txt}2")).unwrap();
+        assert_eq!(&foo2, "foo2");
+        assert!(foo.try_map(|txt| &txt[1..]).is_err());
+    }
+
     // only check that this compiles
     #[allow(dead_code)]
     fn new_unchecked() {
@@ -444,7 +672,7 @@ pub mod test_wrap_borrowing {
     // only check that this compiles
     #[allow(dead_code)]
     fn borrow() {
-        use std::borrow::Borrow;
+        use core::borrow::Borrow;
         let foo = Foo("this foo is good".to_string());
         let _: &String = foo.borrow();
         let _: &str = foo.borrow();
@@ -457,3 +685,63 @@ pub mod test_wrap_borrowing {
         let _: Foo<&str> = foo.as_ref();
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+pub mod test_wrap_serde {
+    pub trait Number {
+        fn even(&self) -> bool;
+    }
+    impl Number for i32 {
+        fn even(&self) -> bool {
+            *self % 2 == 0
+        }
+    }
+
+    wrap! { serde Even<T: Number>:
+        pub fn new(inner: T) -> Result<Self, ()> {
+            if inner.even() {
+                Ok(Even(inner))
+            } else {
+                Err(())
+            }
+        }
+    }
+
+    wrap! { serde Foo borrowing str :
+        /// The constructor of Foo
+        pub fn new(inner: T) -> Result<Self, ()> {
+            if inner.borrow().contains("foo") {
+                Ok(Foo(inner))
+            } else {
+                Err(())
+            }
+        }
+    }
+
+    #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    struct MyTable {
+        even: Even<i32>,
+        foo: Foo<String>,
+    }
+
+    #[test]
+    fn roundtrip() {
+        let data = MyTable {
+            even: Even::new(42).unwrap(),
+            foo: Foo::new("foo1".to_string()).unwrap(),
+        };
+        let toml_str = toml::to_string(&data).unwrap();
+        assert_eq!(toml_str, "even = 42\nfoo = \"foo1\"\n");
+        let data2 = toml::from_str::<MyTable>(&toml_str).unwrap();
+        assert_eq!(data2.even, data.even);
+        assert_eq!(data2.foo, data.foo);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = toml::from_str::<MyTable>("even = 43\nfoo = \"foo1\"").unwrap_err();
+        assert!(err.to_string().contains("invalid Even"));
+        let err = toml::from_str::<MyTable>("even = 42\nfoo = \"bar\"").unwrap_err();
+        assert!(err.to_string().contains("invalid Foo"));
+    }
+}
@@ -1,9 +1,16 @@
 //! I provide generic wrappers around `Borrow<str>` types,
 //! guaranteeing that their underlying string is a valid IRI or IRI reference.
+use super::_components::split;
+use super::_const::is_valid;
+use super::_idna;
+use super::_normalize::{normalize, normalize_scheme_based};
+#[cfg(feature = "std")]
 use super::resolve::{BaseIri, BaseIriRef};
 use super::{InvalidIri, IsIri, IsIriRef, *};
-use std::borrow::Borrow;
-use std::fmt::Display;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt::Display;
 
 wrap! { Iri borrowing str :
     /// This wrapper guarantees that the underlying `str`
@@ -13,7 +20,7 @@ wrap! { Iri borrowing str :
         if is_absolute_iri_ref(iri.borrow()) {
             Ok(Iri(iri))
         } else {
-            Err(InvalidIri(iri.borrow().to_string()))
+            Err(InvalidIri::locate(iri.borrow().to_string(), true))
         }
     }
 
@@ -22,31 +29,167 @@ wrap! { Iri borrowing str :
     /// NB: when resolving multiple IRI references against the same base,
     /// it is preferable to first turn it into a [`BaseIri`],
     /// with the [`Iri::as_base`] or [`Iri::to_base`] methods.
+    #[cfg(feature = "std")]
     pub fn resolve<U: IsIriRef>(&self, rel: U) -> Iri<String> {
         self.as_base().resolve(rel)
     }
 
     /// Borrow this IRI as a [`BaseIri`]
     /// providing more efficient and flexible resolution methods than [`Iri::resolve`].
+    #[cfg(feature = "std")]
     pub fn as_base(&self) -> BaseIri<&str> {
         BaseIri::new(self.0.borrow()).unwrap()
     }
 
     /// Turn this IRI into a [`BaseIri`]
     /// providing more efficient and flexible resolution methods than [`Iri::resolve`].
+    #[cfg(feature = "std")]
     pub fn to_base(self) -> BaseIri<T>
     where
-        T: std::ops::Deref<Target = str>,
+        T: core::ops::Deref<Target = str>,
     {
         BaseIri::new(self.0).unwrap()
     }
+
+    /// Return a normalized copy of this IRI,
+    /// as per the syntax-based normalization of
+    /// [RFC 3987 §5.3.2](https://www.rfc-editor.org/rfc/rfc3987#section-5.3.2):
+    /// - the scheme and host are lowercased,
+    ///   as well as the hexadecimal digits of percent-encoded octets;
+    /// - percent-encoded unreserved characters are decoded;
+    /// - dot-segments (`.` and `..`) are removed from the path.
+    ///
+    /// Two IRIs with the same normalized form identify the same resource,
+    /// even if they are not equal.
+    pub fn normalized(&self) -> Iri<String> {
+        Iri(normalize(self.0.borrow()))
+    }
+
+    /// Simple string comparison, as per
+    /// [RFC 3987 §5.3.1](https://www.rfc-editor.org/rfc/rfc3987#section-5.3.1):
+    /// true if both IRIs are identical character by character.
+    ///
+    /// This is the cheapest comparison, but also the one with the most false negatives.
+    pub fn eq_simple<U: Borrow<str>>(&self, other: &Iri<U>) -> bool {
+        self.0.borrow() == other.0.borrow()
+    }
+
+    /// Syntax-based comparison, as per
+    /// [RFC 3987 §5.3.2](https://www.rfc-editor.org/rfc/rfc3987#section-5.3.2):
+    /// true if both IRIs have the same [normalized](Iri::normalized) form.
+    pub fn eq_syntax_based<U: Borrow<str>>(&self, other: &Iri<U>) -> bool {
+        self.eq_simple(other) || normalize(self.0.borrow()) == normalize(other.0.borrow())
+    }
+
+    /// Scheme-based comparison, as per
+    /// [RFC 3987 §5.3.3](https://www.rfc-editor.org/rfc/rfc3987#section-5.3.3):
+    /// as [`Iri::eq_syntax_based`], but additionally,
+    /// for IRIs with an authority and a scheme among `http`, `https`, `ws`, `wss` and `ftp`,
+    /// - an empty or default port (e.g. `:80` for `http`) is ignored, and
+    /// - an empty path is considered equivalent to `/`.
+    pub fn eq_scheme_based<U: Borrow<str>>(&self, other: &Iri<U>) -> bool {
+        self.eq_simple(other)
+            || normalize_scheme_based(self.0.borrow()) == normalize_scheme_based(other.0.borrow())
+    }
+
+    /// Map this IRI to a URI, as per
+    /// [RFC 3987 §3.1](https://www.rfc-editor.org/rfc/rfc3987#section-3.1),
+    /// e.g. to hand it to a client that only accepts URIs:
+    /// - the labels of the host containing non-ASCII characters
+    ///   are lowercased and converted to [Punycode](https://www.rfc-editor.org/rfc/rfc3492)
+    ///   (no other IDNA mapping is applied);
+    /// - all other non-ASCII characters are percent-encoded.
+    pub fn to_uri(&self) -> Iri<String> {
+        Iri(_idna::to_uri(self.0.borrow()))
+    }
+
+    /// Map this IRI (typically a URI) to an IRI, as per
+    /// [RFC 3987 §3.2](https://www.rfc-editor.org/rfc/rfc3987#section-3.2),
+    /// i.e. the reverse of [`Iri::to_uri`]:
+    /// - the Punycode labels of the host are decoded;
+    /// - percent-encoded non-ASCII characters are decoded, wherever they are allowed.
+    ///
+    /// NB: the remaining percent-encoded octets use uppercase hexadecimal digits.
+    pub fn to_unicode(&self) -> Iri<String> {
+        Iri(_idna::to_iri(self.0.borrow()))
+    }
+
+    /// The scheme of this IRI (without the trailing `:`).
+    pub fn scheme(&self) -> &str {
+        split(self.0.borrow()).scheme.unwrap()
+    }
+
+    /// The authority of this IRI (without the leading `//`), if any.
+    pub fn authority(&self) -> Option<&str> {
+        split(self.0.borrow()).authority
+    }
+
+    /// The path of this IRI (possibly empty).
+    pub fn path(&self) -> &str {
+        split(self.0.borrow()).path
+    }
+
+    /// The query of this IRI (without the leading `?`), if any.
+    pub fn query(&self) -> Option<&str> {
+        split(self.0.borrow()).query
+    }
+
+    /// The fragment identifier of this IRI (without the leading `#`), if any.
+    pub fn fragment(&self) -> Option<&str> {
+        split(self.0.borrow()).fragment
+    }
+}
+
+impl Iri<&'static str> {
+    /// Construct an `Iri<&'static str>` from a string literal,
+    /// checking at compile time that it is a valid IRI
+    /// when used in a `const` or `static` declaration
+    /// (or panicking at run time otherwise).
+    ///
+    /// # Example
+    /// ```
+    /// # use sophia_iri::Iri;
+    /// const EX: Iri<&str> = Iri::new_const("http://example.org/");
+    /// ```
+    ///
+    /// Invalid IRIs are rejected by the compiler:
+    /// ```compile_fail
+    /// # use sophia_iri::Iri;
+    /// const EX: Iri<&str> = Iri::new_const("not an IRI");
+    /// ```
+    pub const fn new_const(iri: &'static str) -> Self {
+        assert!(is_valid(iri, true), "invalid IRI");
+        Iri(iri)
+    }
+}
+
+impl<'a> Iri<&'a str> {
+    /// Validate many IRIs at once, as [`Iri::new`] would.
+    ///
+    /// This is meant for bulk-loading scenarios, where IRI validation can take a significant time.
+    /// IRIs consisting only of ASCII characters (the vast majority in practice)
+    /// are detected with a vectorized scan ([`str::is_ascii`]),
+    /// and validated by a hand-written parser, faster than the regular expression used by [`Iri::new`].
+    ///
+    /// # Example
+    /// ```
+    /// # use sophia_iri::Iri;
+    /// let results = Iri::validate_many(&["http://example.org/", "not an IRI"]);
+    /// assert!(results[0].is_ok());
+    /// assert!(results[1].is_err());
+    /// ```
+    pub fn validate_many(iris: &[&'a str]) -> Vec<Result<Self, InvalidIri>> {
+        iris.iter()
+            .map(|iri| validate_fast(iri, true).map(|()| Iri(*iri)))
+            .collect()
+    }
 }
 
 impl<T: Borrow<str>> IsIriRef for Iri<T> {}
 impl<T: Borrow<str>> IsIri for Iri<T> {}
 
 impl<T: Borrow<str>> Display for Iri<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.0.borrow())
     }
 }
@@ -61,7 +204,7 @@ wrap! { IriRef borrowing str :
         if is_valid_iri_ref(iri.borrow()) {
             Ok(IriRef(iri))
         } else {
-            Err(InvalidIri(iri.borrow().to_string()))
+            Err(InvalidIri::locate(iri.borrow().to_string(), false))
         }
     }
 
@@ -70,40 +213,122 @@ wrap! { IriRef borrowing str :
     /// NB: when resolving multiple IRI references against the same base,
     /// it is preferable to first turn it into a [`BaseIriRef`],
     /// with the [`IriRef::as_base`] or [`IriRef::to_base`] methods.
+    #[cfg(feature = "std")]
     pub fn resolve<U: IsIriRef>(&self, rel: U) -> IriRef<String> {
         self.as_base().resolve(rel)
     }
 
     /// Borrow this IRI as a [`BaseIriRef`]
     /// providing more efficient and flexible resolution methods than [`IriRef::resolve`].
+    #[cfg(feature = "std")]
     pub fn as_base(&self) -> BaseIriRef<&str> {
         BaseIriRef::new(self.0.borrow()).unwrap()
     }
 
     /// Turn this IRI into a [`BaseIriRef`]
     /// providing more efficient and flexible resolution methods than [`IriRef::resolve`].
+    #[cfg(feature = "std")]
     pub fn to_base(self) -> BaseIriRef<T>
     where
-        T: std::ops::Deref<Target = str>,
+        T: core::ops::Deref<Target = str>,
     {
         BaseIriRef::new(self.0).unwrap()
     }
+
+    /// The scheme of this IRI reference (without the trailing `:`),
+    /// if it is absolute.
+    pub fn scheme(&self) -> Option<&str> {
+        split(self.0.borrow()).scheme
+    }
+
+    /// The authority of this IRI reference (without the leading `//`), if any.
+    pub fn authority(&self) -> Option<&str> {
+        split(self.0.borrow()).authority
+    }
+
+    /// The path of this IRI reference (possibly empty).
+    pub fn path(&self) -> &str {
+        split(self.0.borrow()).path
+    }
+
+    /// The query of this IRI reference (without the leading `?`), if any.
+    pub fn query(&self) -> Option<&str> {
+        split(self.0.borrow()).query
+    }
+
+    /// The fragment identifier of this IRI reference (without the leading `#`), if any.
+    pub fn fragment(&self) -> Option<&str> {
+        split(self.0.borrow()).fragment
+    }
+}
+
+impl IriRef<&'static str> {
+    /// Construct an `IriRef<&'static str>` from a string literal,
+    /// checking at compile time that it is a valid IRI reference
+    /// when used in a `const` or `static` declaration
+    /// (or panicking at run time otherwise).
+    ///
+    /// # Example
+    /// ```
+    /// # use sophia_iri::IriRef;
+    /// const EX: IriRef<&str> = IriRef::new_const("../foo#bar");
+    /// ```
+    ///
+    /// Invalid IRI references are rejected by the compiler:
+    /// ```compile_fail
+    /// # use sophia_iri::IriRef;
+    /// const EX: IriRef<&str> = IriRef::new_const("a b");
+    /// ```
+    pub const fn new_const(iri: &'static str) -> Self {
+        assert!(is_valid(iri, false), "invalid IRI reference");
+        IriRef(iri)
+    }
+}
+
+impl<'a> IriRef<&'a str> {
+    /// Validate many IRI references at once, as [`IriRef::new`] would.
+    ///
+    /// See [`Iri::validate_many`].
+    pub fn validate_many(iris: &[&'a str]) -> Vec<Result<Self, InvalidIri>> {
+        iris.iter()
+            .map(|iri| validate_fast(iri, false).map(|()| IriRef(*iri)))
+            .collect()
+    }
 }
 
 impl<T: Borrow<str>> IsIriRef for IriRef<T> {}
 
 impl<T: Borrow<str>> Display for IriRef<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.0.borrow())
     }
 }
 
 //
 
+/// Check whether `txt` is a valid absolute IRI (if `absolute` is true) or IRI reference,
+/// using the hand-written parser of [`is_valid`] for ASCII-only strings,
+/// and the regular expressions otherwise.
+fn validate_fast(txt: &str, absolute: bool) -> Result<(), InvalidIri> {
+    let valid = if txt.is_ascii() {
+        is_valid(txt, absolute)
+    } else if absolute {
+        is_absolute_iri_ref(txt)
+    } else {
+        is_valid_iri_ref(txt)
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(InvalidIri::locate(txt.to_string(), absolute))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::test::*;
+    use alloc::boxed::Box;
 
     #[test]
     fn iri() {
@@ -115,6 +340,28 @@ mod test {
         }
     }
 
+    #[test]
+    fn iri_validate_many() {
+        let txts: Vec<&str> = POSITIVE_IRIS
+            .iter()
+            .map(|(txt, _)| *txt)
+            .chain(NEGATIVE_IRIS.iter().copied())
+            .chain(RELATIVE_IRIS.iter().map(|(txt, _)| *txt))
+            .collect();
+        for (txt, res) in txts.iter().zip(Iri::validate_many(&txts)) {
+            match Iri::new(*txt) {
+                Ok(iri) => assert_eq!(res.unwrap(), iri),
+                Err(err) => assert_eq!(res.unwrap_err().to_string(), err.to_string()),
+            }
+        }
+        for (txt, res) in txts.iter().zip(IriRef::validate_many(&txts)) {
+            match IriRef::new(*txt) {
+                Ok(iri) => assert_eq!(res.unwrap(), iri),
+                Err(err) => assert_eq!(res.unwrap_err().to_string(), err.to_string()),
+            }
+        }
+    }
+
     #[test]
     fn iri_box() {
         for (txt, (abs, ..)) in POSITIVE_IRIS {
@@ -125,6 +372,98 @@ mod test {
         }
     }
 
+    #[test]
+    fn iri_normalized() {
+        for (txt, (abs, ..)) in POSITIVE_IRIS {
+            if !*abs {
+                continue;
+            }
+            let norm = Iri::new(*txt).unwrap().normalized();
+            assert!(Iri::new(norm.as_str()).is_ok(), "{txt} -> {norm}");
+            assert_eq!(norm.normalized(), norm, "{txt}");
+        }
+        let iri = Iri::new("HTTP://Example.COM/a/./b/../c/%7euser%2fx").unwrap();
+        assert_eq!(
+            iri.normalized().as_str(),
+            "http://example.com/a/c/~user%2Fx"
+        );
+    }
+
+    #[test]
+    fn iri_comparison_ladder() {
+        let cases = [
+            ("http://ex.org/a", "http://ex.org/a", [true, true, true]),
+            ("http://ex.org/a", "HTTP://EX.org/./a", [false, true, true]),
+            (
+                "http://ex.org/%7Ea",
+                "http://ex.org/~a",
+                [false, true, true],
+            ),
+            ("http://ex.org", "http://ex.org:80/", [false, false, true]),
+            (
+                "https://ex.org:443/a",
+                "https://ex.org/a",
+                [false, false, true],
+            ),
+            ("http://ex.org/a", "http://ex.org/A", [false, false, false]),
+            (
+                "http://ex.org:8080/",
+                "http://ex.org/",
+                [false, false, false],
+            ),
+            ("foo://ex.org:80", "foo://ex.org", [false, false, false]),
+        ];
+        for (txt1, txt2, expected) in cases {
+            let (iri1, iri2) = (Iri::new(txt1).unwrap(), Iri::new(txt2).unwrap());
+            for (a, b) in [(iri1, iri2), (iri2, iri1)] {
+                let got = [
+                    a.eq_simple(&b),
+                    a.eq_syntax_based(&b),
+                    a.eq_scheme_based(&b),
+                ];
+                assert_eq!(got, expected, "{a} vs {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn iri_components() {
+        for (txt, (abs, scheme, authority, path, query, fragment)) in POSITIVE_IRIS {
+            if !*abs {
+                continue;
+            }
+            let iri = Iri::new(*txt).unwrap();
+            assert_eq!(Some(iri.scheme()), *scheme, "{txt}");
+            assert_eq!(iri.authority(), *authority, "{txt}");
+            assert_eq!(iri.path(), *path, "{txt}");
+            assert_eq!(iri.query(), *query, "{txt}");
+            assert_eq!(iri.fragment(), *fragment, "{txt}");
+        }
+    }
+
+    #[test]
+    fn iri_to_uri_and_back() {
+        let iri = Iri::new("http://Bücher.example/été?q=ü#ü").unwrap();
+        let uri = iri.to_uri();
+        assert_eq!(
+            uri.as_str(),
+            "http://xn--bcher-kva.example/%C3%A9t%C3%A9?q=%C3%BC#%C3%BC"
+        );
+        assert_eq!(uri.to_unicode().as_str(), "http://bücher.example/été?q=ü#ü");
+        for (txt, (abs, ..)) in POSITIVE_IRIS {
+            if !*abs {
+                continue;
+            }
+            let uri = Iri::new(*txt).unwrap().to_uri();
+            assert!(uri.as_str().is_ascii(), "{txt}");
+            assert!(Iri::new(uri.as_str()).is_ok(), "{txt} -> {uri}");
+            assert!(
+                Iri::new(uri.to_unicode().as_str()).is_ok(),
+                "{txt} -> {uri}"
+            );
+        }
+    }
+
     #[test]
     fn iri_ref() {
         for (txt, _) in POSITIVE_IRIS {
@@ -138,6 +477,39 @@ mod test {
         }
     }
 
+    #[test]
+    fn invalid_iri_location() {
+        let err = Iri::new("http://example.org/a b").unwrap_err();
+        assert_eq!(err.offset, 20);
+        assert_eq!(err.part, IriPart::Path);
+        assert_eq!(
+            err.to_string(),
+            "The given IRI 'http://example.org/a b' is not valid according to RFC3987: \
+             unexpected ' ' in path at byte 20"
+        );
+        let err = IriRef::new("a?b#c#").unwrap_err();
+        assert_eq!(err.offset, 5);
+        assert_eq!(err.part, IriPart::Fragment);
+        let err = Iri::new("http").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The given IRI 'http' is not valid according to RFC3987: \
+             unexpected end of IRI in scheme"
+        );
+    }
+
+    #[test]
+    fn iri_ref_components() {
+        for (txt, (_, scheme, authority, path, query, fragment)) in POSITIVE_IRIS {
+            let iri = IriRef::new(*txt).unwrap();
+            assert_eq!(iri.scheme(), *scheme, "{txt}");
+            assert_eq!(iri.authority(), *authority, "{txt}");
+            assert_eq!(iri.path(), *path, "{txt}");
+            assert_eq!(iri.query(), *query, "{txt}");
+            assert_eq!(iri.fragment(), *fragment, "{txt}");
+        }
+    }
+
     #[test]
     fn iri_ref_box() {
         for (txt, _) in POSITIVE_IRIS {
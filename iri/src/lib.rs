@@ -7,6 +7,14 @@
 //!
 //! # Feature gates
 //!
+//! - **std** (enabled by default) is required for
+//!   [resolving](mod@resolve) IRI references, [IRI templates](IriTemplate)
+//!   and the [`serde`](https://serde.rs/) integration.
+//!   Without it, this crate is `no_std` (but still requires `alloc`),
+//!   and IRIs are validated by a hand-written parser rather than regular expressions.
+//!   Simple cases of resolution are also available at compile time
+//!   (hence without `std`) via the [`resolve_const`] macro.
+//!
 //! - **test_data** exposes the [`test`](`mod@test`) module,
 //!   which contains arrays of good and bad IRIs,
 //!   useful for testing purposes, possibly in other crates.
@@ -19,20 +27,49 @@
 //! [Linked Data]: http://linkeddata.org/
 
 #![deny(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 mod _wrap_macro;
 
+mod _builder;
+pub use _builder::*;
+mod _components;
+mod _const;
+mod _const_resolve;
+#[doc(hidden)]
+pub use _const_resolve::{__resolve_const, __resolve_const_len};
 mod _error;
 pub use _error::*;
+mod _idna;
+mod _normalize;
+pub use _normalize::iri_eq_normalized;
+mod _percent;
+pub use _percent::*;
+#[cfg(feature = "std")]
 mod _regex;
+#[cfg(not(feature = "std"))]
+pub use self::_const::no_regex::*;
+#[cfg(feature = "std")]
 pub use self::_regex::*;
+#[cfg(feature = "std")]
+mod _template;
+#[cfg(feature = "std")]
+pub use _template::*;
 mod _trait;
 pub use self::_trait::*;
 mod _wrapper;
 pub use _wrapper::*;
 #[cfg(feature = "serde")]
 mod _serde;
+#[doc(hidden)]
+pub use alloc::string::String as __String;
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde as __serde;
 
+#[cfg(feature = "std")]
 pub mod resolve;
 
 #[cfg(feature = "examples")]
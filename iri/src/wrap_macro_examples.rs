@@ -37,6 +37,7 @@
 #![allow(dead_code)]
 
 use crate::wrap;
+use alloc::string::String;
 
 wrap! { SimpleWrapper<T: PartialEq<isize>> :
     /// SimpleWrapper wraps any type comparable to `isize`,
@@ -105,7 +105,7 @@ impl<LF> JsonLdParser<LF> {
         let base = self
             .options()
             .base()
-            .unwrap_or(Iri::new_unchecked_const("x-string://"))
+            .unwrap_or(Iri::new_const("x-string://"))
             .map_unchecked(Arc::from);
         let json_res = Value::parse_str(txt, |span| Location::new(base.clone(), span));
         let json = match json_res {
@@ -0,0 +1,261 @@
+//! I define [`LazyFileDataset`], a dataset whose named graphs are stored in separate files,
+//! and parsed only when first accessed.
+//!
+//! This is useful for applications dealing with many graphs,
+//! only a few of which are actually used at any given time:
+//! they do not pay the cost of parsing all the graphs upfront,
+//! and the number of graphs kept in memory is bounded
+//! (the least recently used graphs are dropped first).
+//!
+//! # Example
+//! ```
+//! # use sophia_api::term::SimpleTerm;
+//! # use sophia_iri::Iri;
+//! # use sophia_resource::LazyFileDataset;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! type MyGraph = Vec<[SimpleTerm<'static>; 3]>;
+//!
+//! // each file test/<name>.ttl contains the graph named http://example.org/<name>
+//! let ns = Iri::new_unchecked("http://example.org/");
+//! let dataset = LazyFileDataset::<MyGraph>::from_directory(ns, "test", 16)?;
+//! assert!(!dataset.is_loaded(Iri::new_unchecked("http://example.org/file1")));
+//!
+//! let g = dataset.graph(Iri::new_unchecked("http://example.org/file1"))?;
+//! assert!(dataset.is_loaded(Iri::new_unchecked("http://example.org/file1")));
+//! assert_eq!(g.len(), 20);
+//! # Ok(()) }
+//! ```
+use crate::loader::{CatalogError, CatalogLoader, Loader, LoaderError};
+use sophia_api::graph::CollectibleGraph;
+use sophia_api::MownStr;
+use sophia_iri::Iri;
+use std::borrow::Borrow;
+use std::collections::VecDeque;
+use std::fs::read_dir;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+type IriBuf = Iri<MownStr<'static>>;
+
+/// A dataset whose named graphs are parsed from files on demand.
+///
+/// Graphs are parsed on their first access through [`LazyFileDataset::graph`],
+/// and kept in a cache of bounded [capacity](LazyFileDataset::capacity),
+/// from which the least recently used graphs are evicted.
+/// The syntax of each file is guessed from its extension.
+#[derive(Debug)]
+pub struct LazyFileDataset<G> {
+    names: Vec<IriBuf>,
+    files: CatalogLoader,
+    capacity: usize,
+    /// The materialized graphs, the most recently used last
+    cache: Mutex<VecDeque<(IriBuf, Arc<G>)>>,
+}
+
+impl<G> LazyFileDataset<G> {
+    /// Build an empty [`LazyFileDataset`],
+    /// keeping at most `capacity` graphs in memory.
+    pub fn new(capacity: usize) -> Self {
+        LazyFileDataset {
+            names: vec![],
+            files: CatalogLoader::new(),
+            capacity,
+            cache: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Build a [`LazyFileDataset`] from all the files in directory `dir`
+    /// (not recursively) whose syntax can be guessed from their extension,
+    /// keeping at most `capacity` graphs in memory.
+    ///
+    /// The name of the graph stored in each file is `ns`
+    /// followed by the name of the file without its extension.
+    pub fn from_directory<T, P>(ns: Iri<T>, dir: P, capacity: usize) -> Result<Self, CatalogError>
+    where
+        T: Borrow<str>,
+        P: AsRef<Path>,
+    {
+        let dir = dir.as_ref();
+        let io_error = |e| CatalogError::IoError(dir.into(), e);
+        let mut dataset = Self::new(capacity);
+        for entry in read_dir(dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            if !path.is_file() {
+                continue;
+            }
+            let (Some(stem), Some(ext)) = (path.file_stem(), path.extension()) else {
+                continue;
+            };
+            let (Some(stem), Some(ext)) = (stem.to_str(), ext.to_str()) else {
+                continue;
+            };
+            if !is_supported(ext) {
+                continue;
+            }
+            let name = format!("{}{stem}", ns.as_str());
+            let name = Iri::new(name.into()).map_err(|e| CatalogError::InvalidIri(e.iri))?;
+            dataset.add_graph(name, path);
+        }
+        Ok(dataset)
+    }
+
+    /// Add to this dataset a graph named `name`, stored in the file at `path`.
+    ///
+    /// # Precondition
+    /// `name` must contain no fragment identifier.
+    pub fn add_graph(&mut self, name: IriBuf, path: PathBuf) {
+        debug_assert!(name.as_str().find('#').is_none());
+        self.files.add_uri(name.clone(), path);
+        self.names.push(name);
+    }
+
+    /// The maximum number of graphs kept in memory.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Iter over the names of the graphs of this dataset, in the order in which they were added.
+    pub fn graph_names(&self) -> impl Iterator<Item = Iri<&str>> + '_ {
+        self.names.iter().map(|n| n.as_ref())
+    }
+
+    /// Whether this dataset contains a graph named `name`.
+    pub fn contains_graph<T: Borrow<str>>(&self, name: Iri<T>) -> bool {
+        self.names.iter().any(|n| n.as_str() == name.as_str())
+    }
+
+    /// Whether the graph named `name` is currently in memory.
+    pub fn is_loaded<T: Borrow<str>>(&self, name: Iri<T>) -> bool {
+        self.cache
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|(n, _)| n.as_str() == name.as_str())
+    }
+
+    /// Drop all the graphs currently in memory.
+    pub fn unload_all(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+}
+
+impl<G: CollectibleGraph> LazyFileDataset<G> {
+    /// Get the graph named `name`, parsing its file if it is not in memory.
+    ///
+    /// If the cache is full, the least recently used graph is evicted from it
+    /// (but remains usable by whoever still holds it).
+    pub fn graph<T: Borrow<str>>(&self, name: Iri<T>) -> Result<Arc<G>, LoaderError> {
+        let name = name.as_ref();
+        if let Some(g) = self.cached(name) {
+            return Ok(g);
+        }
+        if !self.contains_graph(name) {
+            return Err(LoaderError::NotFound(
+                name.map_unchecked(|t| t.to_owned().into()),
+            ));
+        }
+        // NB: the graph is parsed without holding the lock,
+        // so that other graphs can be accessed in the meantime
+        let g: Arc<G> = Arc::new(self.files.get_graph(name)?);
+        if self.capacity > 0 {
+            let mut cache = self.cache.lock().unwrap();
+            if let Some((_, g)) = cache.iter().find(|(n, _)| n.as_str() == name.as_str()) {
+                // loaded concurrently by another thread
+                return Ok(g.clone());
+            }
+            if cache.len() >= self.capacity {
+                cache.pop_front();
+            }
+            cache.push_back((name.map_unchecked(|t| t.to_owned().into()), g.clone()));
+        }
+        Ok(g)
+    }
+
+    /// Get the graph named `name` from the cache, if present,
+    /// marking it as the most recently used.
+    fn cached(&self, name: Iri<&str>) -> Option<Arc<G>> {
+        let mut cache = self.cache.lock().unwrap();
+        let i = cache
+            .iter()
+            .position(|(n, _)| n.as_str() == name.as_str())?;
+        let entry = cache.remove(i).unwrap();
+        let g = entry.1.clone();
+        cache.push_back(entry);
+        Some(g)
+    }
+}
+
+/// Whether files with extension `ext` can be parsed by [`LazyFileDataset`].
+fn is_supported(ext: &str) -> bool {
+    match ext {
+        "ttl" | "nt" => true,
+        "jsonld" => cfg!(feature = "jsonld"),
+        "rdf" => cfg!(feature = "xml"),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::*;
+
+    const F2X: Iri<&str> = Iri::new_const("http://example.org/file2");
+
+    fn make_dataset(capacity: usize) -> LazyFileDataset<MyGraph> {
+        LazyFileDataset::from_directory(NS, "test", capacity).unwrap()
+    }
+
+    #[test]
+    fn from_directory() {
+        let ds = make_dataset(4);
+        assert!(ds.contains_graph(F1X));
+        assert!(ds.contains_graph(F2X));
+        assert!(ds.contains_graph(F3X));
+        assert!(!ds.contains_graph(F1));
+        assert!(!ds.contains_graph(Iri::new_unchecked("http://example.org/catalog")));
+        assert!(ds.graph_names().all(|n| !ds.is_loaded(n)));
+    }
+
+    #[test]
+    fn lazy_loading() -> TestResult {
+        let ds = make_dataset(4);
+        let g1 = ds.graph(F1X)?;
+        assert_eq!(g1.len(), F1_LEN);
+        assert!(ds.is_loaded(F1X));
+        assert!(!ds.is_loaded(F3X));
+        assert!(Arc::ptr_eq(&g1, &ds.graph(F1X)?));
+        assert!(matches!(ds.graph(FAIL), Err(LoaderError::NotFound(_))));
+        ds.unload_all();
+        assert!(!ds.is_loaded(F1X));
+        Ok(())
+    }
+
+    #[test]
+    fn least_recently_used_is_evicted() -> TestResult {
+        let ds = make_dataset(2);
+        ds.graph(F1X)?;
+        ds.graph(F2X)?;
+        ds.graph(F1X)?;
+        let g3 = ds.graph(F3X)?;
+        assert_eq!(g3.len(), F3_LEN);
+        assert!(ds.is_loaded(F1X));
+        assert!(!ds.is_loaded(F2X));
+        assert!(ds.is_loaded(F3X));
+
+        let ds = make_dataset(0);
+        assert_eq!(ds.graph(F2X)?.len(), F2_LEN);
+        assert!(!ds.is_loaded(F2X));
+        Ok(())
+    }
+
+    #[test]
+    fn add_graph() -> TestResult {
+        let mut ds = LazyFileDataset::<MyGraph>::new(1);
+        let name = Iri::new_unchecked(MownStr::from("http://example.org/other"));
+        ds.add_graph(name.clone(), "test/file2.ttl".into());
+        assert_eq!(ds.graph_names().collect::<Vec<_>>(), vec![name.as_ref()]);
+        assert_eq!(ds.graph(name)?.len(), F2_LEN);
+        Ok(())
+    }
+}
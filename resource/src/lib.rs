@@ -8,10 +8,15 @@
 //! [Linked Data]: http://linkeddata.org/
 #![deny(missing_docs)]
 
+pub mod lazy;
 pub mod loader;
+pub mod ontology;
 pub mod resource;
+pub mod shacl;
 
-pub use loader::{Loader, LoaderError, LocalLoader, NoLoader};
+pub use lazy::LazyFileDataset;
+pub use loader::{CatalogLoader, Loader, LoaderError, LocalLoader, NoLoader};
+pub use ontology::ImportsResolver;
 pub use resource::{Resource, ResourceError, TypedResource};
 
 #[cfg(test)]
@@ -24,39 +29,38 @@ mod test {
     };
     use sophia_iri::Iri;
 
-    pub const NS: Iri<&str> = Iri::new_unchecked_const("http://example.org/");
-    pub const F1: Iri<&str> = Iri::new_unchecked_const("http://example.org/file1.ttl");
-    pub const F1R1: Iri<&str> = Iri::new_unchecked_const("http://example.org/file1.ttl#res1");
-    pub const F1R2: Iri<&str> = Iri::new_unchecked_const("http://example.org/file1.ttl#res2");
-    pub const F1R3: Iri<&str> = Iri::new_unchecked_const("http://example.org/file1.ttl#res3");
-    pub const F2: Iri<&str> = Iri::new_unchecked_const("http://example.org/file2.ttl");
-    pub const F2R1: Iri<&str> = Iri::new_unchecked_const("http://example.org/file2.ttl#res1");
-    pub const F2R2: Iri<&str> = Iri::new_unchecked_const("http://example.org/file2.ttl#res2");
-    pub const FAIL: Iri<&str> = Iri::new_unchecked_const("http://example.org/not_there");
-    pub const F3: Iri<&str> = Iri::new_unchecked_const("http://example.org/file3.nt");
+    pub const NS: Iri<&str> = Iri::new_const("http://example.org/");
+    pub const F1: Iri<&str> = Iri::new_const("http://example.org/file1.ttl");
+    pub const F1R1: Iri<&str> = Iri::new_const("http://example.org/file1.ttl#res1");
+    pub const F1R2: Iri<&str> = Iri::new_const("http://example.org/file1.ttl#res2");
+    pub const F1R3: Iri<&str> = Iri::new_const("http://example.org/file1.ttl#res3");
+    pub const F2: Iri<&str> = Iri::new_const("http://example.org/file2.ttl");
+    pub const F2R1: Iri<&str> = Iri::new_const("http://example.org/file2.ttl#res1");
+    pub const F2R2: Iri<&str> = Iri::new_const("http://example.org/file2.ttl#res2");
+    pub const FAIL: Iri<&str> = Iri::new_const("http://example.org/not_there");
+    pub const F3: Iri<&str> = Iri::new_const("http://example.org/file3.nt");
     #[cfg(feature = "jsonld")]
-    pub const F4: Iri<&str> = Iri::new_unchecked_const("http://example.org/file4.jsonld");
+    pub const F4: Iri<&str> = Iri::new_const("http://example.org/file4.jsonld");
     #[cfg(feature = "xml")]
-    pub const F5: Iri<&str> = Iri::new_unchecked_const("http://example.org/file5.rdf");
-    pub const SUBDIR: Iri<&str> = Iri::new_unchecked_const("http://example.org/subdir");
+    pub const F5: Iri<&str> = Iri::new_const("http://example.org/file5.rdf");
+    pub const SUBDIR: Iri<&str> = Iri::new_const("http://example.org/subdir");
     // test with no extension (conneg emulation)
-    pub const F1X: Iri<&str> = Iri::new_unchecked_const("http://example.org/file1");
-    pub const F1XR1: Iri<&str> = Iri::new_unchecked_const("http://example.org/file1#res1");
-    pub const F3X: Iri<&str> = Iri::new_unchecked_const("http://example.org/file3");
+    pub const F1X: Iri<&str> = Iri::new_const("http://example.org/file1");
+    pub const F1XR1: Iri<&str> = Iri::new_const("http://example.org/file1#res1");
+    pub const F3X: Iri<&str> = Iri::new_const("http://example.org/file3");
     #[cfg(feature = "jsonld")]
-    pub const F4X: Iri<&str> = Iri::new_unchecked_const("http://example.org/file4");
+    pub const F4X: Iri<&str> = Iri::new_const("http://example.org/file4");
     #[cfg(feature = "xml")]
-    pub const F5X: Iri<&str> = Iri::new_unchecked_const("http://example.org/file5");
+    pub const F5X: Iri<&str> = Iri::new_const("http://example.org/file5");
 
-    pub const EX_ID: Iri<&str> = Iri::new_unchecked_const("http://example.org/ns#id");
-    pub const EX_LIST: Iri<&str> = Iri::new_unchecked_const("http://example.org/ns#list");
-    pub const EX_FOREIGN1: Iri<&str> = Iri::new_unchecked_const("http://example.org/ns#foreign1");
-    pub const EX_FOREIGN2: Iri<&str> = Iri::new_unchecked_const("http://example.org/ns#foreign2");
-    pub const EX_NEXT: Iri<&str> = Iri::new_unchecked_const("http://example.org/ns#next");
-    pub const EX_RELATED: Iri<&str> = Iri::new_unchecked_const("http://example.org/ns#related");
-    pub const EX_UNREACHABLE: Iri<&str> =
-        Iri::new_unchecked_const("http://example.org/ns#unreachable");
-    pub const EX_UNUSED: Iri<&str> = Iri::new_unchecked_const("http://example.org/ns#unused");
+    pub const EX_ID: Iri<&str> = Iri::new_const("http://example.org/ns#id");
+    pub const EX_LIST: Iri<&str> = Iri::new_const("http://example.org/ns#list");
+    pub const EX_FOREIGN1: Iri<&str> = Iri::new_const("http://example.org/ns#foreign1");
+    pub const EX_FOREIGN2: Iri<&str> = Iri::new_const("http://example.org/ns#foreign2");
+    pub const EX_NEXT: Iri<&str> = Iri::new_const("http://example.org/ns#next");
+    pub const EX_RELATED: Iri<&str> = Iri::new_const("http://example.org/ns#related");
+    pub const EX_UNREACHABLE: Iri<&str> = Iri::new_const("http://example.org/ns#unreachable");
+    pub const EX_UNUSED: Iri<&str> = Iri::new_const("http://example.org/ns#unused");
 
     /// Number of triples in F1
     pub const F1_LEN: usize = 20;
@@ -1,4 +1,6 @@
 //! This create defies the [`Loader`] trait and some implementations.
+mod _catalog;
+pub use _catalog::*;
 mod _error;
 pub use _error::*;
 mod _local;
@@ -0,0 +1,381 @@
+use super::{util::*, *};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use sophia_iri::Iri;
+use std::borrow::Borrow;
+use std::fs::{read, read_to_string};
+use std::io::{self, ErrorKind as IoErrorKind};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// A resource loader resolving IRIs to local files, based on a catalog.
+///
+/// The catalog contains two kinds of entries:
+/// * `uri` entries, mapping a single IRI to a file;
+/// * `rewrite` entries, mapping all the IRIs starting with a given prefix to a directory.
+///
+/// `uri` entries take precedence over `rewrite` entries,
+/// and the longest matching prefix is used among `rewrite` entries.
+///
+/// A catalog can be populated programmatically,
+/// or read from a [simple mapping file](CatalogLoader::from_mapping_file)
+/// or from an [OASIS XML catalog](CatalogLoader::from_xml_catalog).
+/// It is typically used to resolve `owl:imports` without any network access
+/// (see [`ImportsResolver`](crate::ImportsResolver)).
+#[derive(Clone, Debug, Default)]
+pub struct CatalogLoader {
+    uris: Vec<(IriBuf, PathBuf)>,
+    rewrites: Vec<(IriBuf, PathBuf)>,
+}
+
+impl CatalogLoader {
+    /// Build an empty catalog
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read a simple mapping file.
+    ///
+    /// Each non-empty line of the file contains an IRI and a path, separated by whitespaces.
+    /// If the IRI ends with a slash (`/`), the line is a `rewrite` entry,
+    /// otherwise it is a `uri` entry.
+    /// An IRI ending with a hash (`#`) is a namespace whose terms are all defined in one document,
+    /// so it is mapped as a `uri` entry, without its trailing `#`
+    /// (see [`CatalogLoader::add_rewrite`]).
+    /// Relative paths are interpreted relative to the directory containing the mapping file.
+    /// Lines starting with `#` are ignored.
+    ///
+    /// ```text
+    /// # ontologies used in our tests
+    /// http://example.org/onto.ttl   ./onto.ttl
+    /// http://example.org/vocab/     /opt/vocabs/
+    /// ```
+    pub fn from_mapping_file<P: AsRef<Path>>(path: P) -> Result<Self, CatalogError> {
+        let path = path.as_ref();
+        let txt = read_to_string(path).map_err(|e| CatalogError::IoError(path.into(), e))?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let mut catalog = Self::new();
+        for (lineno, line) in txt.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let syntax_error =
+                |msg: &str| CatalogError::SyntaxError(path.into(), lineno + 1, msg.into());
+            let mut parts = line.split_whitespace();
+            let iri = parts.next().unwrap();
+            let Some(target) = parts.next() else {
+                return Err(syntax_error("missing path"));
+            };
+            if parts.next().is_some() {
+                return Err(syntax_error("unexpected data after path"));
+            }
+            let iri = check_iri(iri)?;
+            let target = dir.join(target);
+            if iri.ends_with(['/', '#']) {
+                catalog.add_rewrite(iri, target);
+            } else {
+                catalog.add_uri(iri, target);
+            }
+        }
+        Ok(catalog)
+    }
+
+    /// Read an [OASIS XML catalog](https://www.oasis-open.org/committees/download.php/14809/xml-catalogs.html).
+    ///
+    /// Only the `uri`, `rewriteURI` and `nextCatalog` elements are supported
+    /// (which is enough to read, for example, the `catalog-v001.xml` files produced by Protégé);
+    /// other elements are ignored.
+    /// Relative paths are interpreted relative to the directory containing the catalog,
+    /// or to the `xml:base` in scope.
+    /// Catalogs referenced by `nextCatalog` are read as well,
+    /// and their entries are consulted after those of the referencing catalog.
+    pub fn from_xml_catalog<P: AsRef<Path>>(path: P) -> Result<Self, CatalogError> {
+        let mut catalog = Self::new();
+        catalog.read_xml_catalog(path.as_ref(), &mut vec![])?;
+        Ok(catalog)
+    }
+
+    fn read_xml_catalog(
+        &mut self,
+        path: &Path,
+        visited: &mut Vec<PathBuf>,
+    ) -> Result<(), CatalogError> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.into());
+        if visited.contains(&canonical) {
+            return Ok(());
+        }
+        visited.push(canonical);
+        let xml = read_to_string(path).map_err(|e| CatalogError::IoError(path.into(), e))?;
+        let syntax_error = |pos: usize, msg: String| {
+            let lineno = xml[..pos].matches('\n').count() + 1;
+            CatalogError::SyntaxError(path.into(), lineno, msg)
+        };
+        let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        // the base directory of each open element
+        let mut bases: Vec<PathBuf> = vec![];
+        let mut next_catalogs = vec![];
+        let mut reader = Reader::from_str(&xml);
+        reader.check_end_names(true);
+        loop {
+            let pos = reader.buffer_position();
+            let (elt, empty) = match reader
+                .read_event()
+                .map_err(|e| syntax_error(pos, e.to_string()))?
+            {
+                Event::Start(elt) => (elt, false),
+                Event::Empty(elt) => (elt, true),
+                Event::End(_) => {
+                    bases.pop();
+                    continue;
+                }
+                Event::Eof => break,
+                _ => continue,
+            };
+            let mut attributes = vec![];
+            for attr in elt.attributes() {
+                let attr = attr.map_err(|e| syntax_error(pos, e.to_string()))?;
+                let value = attr
+                    .unescape_value()
+                    .map_err(|e| syntax_error(pos, e.to_string()))?;
+                attributes.push((attr.key.as_ref().to_vec(), value.into_owned()));
+            }
+            let find = |name: &str| {
+                attributes
+                    .iter()
+                    .find(|(n, _)| n == name.as_bytes())
+                    .map(|(_, v)| v.as_str())
+            };
+            let elt_name = String::from_utf8_lossy(elt.local_name().as_ref()).into_owned();
+            let get = |name: &str| {
+                find(name).ok_or_else(|| {
+                    syntax_error(pos, format!("missing attribute {name} in {elt_name}"))
+                })
+            };
+            let parent_base = bases.last().unwrap_or(&dir);
+            let base = match find("xml:base") {
+                Some(xml_base) => base_dir(parent_base, xml_base),
+                None => parent_base.clone(),
+            };
+            match elt_name.as_str() {
+                "uri" => self.add_uri(check_iri(get("name")?)?, local_path(&base, get("uri")?)),
+                "rewriteURI" => self.add_rewrite(
+                    check_iri(get("uriStartString")?)?,
+                    local_path(&base, get("rewritePrefix")?),
+                ),
+                "nextCatalog" => next_catalogs.push(local_path(&base, get("catalog")?)),
+                _ => (),
+            }
+            if !empty {
+                bases.push(base);
+            }
+        }
+        for next in next_catalogs {
+            self.read_xml_catalog(&next, visited)?;
+        }
+        Ok(())
+    }
+
+    /// Map the IRI `iri` to the file `path`.
+    pub fn add_uri(&mut self, iri: IriBuf, path: PathBuf) {
+        self.uris.push((iri, path));
+    }
+
+    /// Map all the IRIs starting with `prefix` to the directory `path`.
+    ///
+    /// NB: IRIs are [resolved](CatalogLoader::resolve) without their fragment identifier,
+    /// so if `prefix` ends with a hash (`#`),
+    /// it is equivalent to a `uri` entry mapping `prefix` without its `#` to the *file* `path`.
+    /// It is then stored as such.
+    pub fn add_rewrite(&mut self, prefix: IriBuf, path: PathBuf) {
+        match prefix.as_str().strip_suffix('#') {
+            Some(iri) => self.add_uri(iri_buf(iri), path),
+            None => self.rewrites.push((prefix, path)),
+        }
+    }
+
+    /// Wrap this loader into an `Arc<Loader>`.
+    pub fn arced(self) -> Arc<Self> {
+        Arc::new(self)
+    }
+
+    /// Return the local path of the given IRI (ignoring its fragment identifier), if any.
+    pub fn resolve(&self, iri: &str) -> Option<PathBuf> {
+        let iri = iri.split('#').next().unwrap();
+        if let Some((_, path)) = self.uris.iter().find(|(i, _)| i.as_str() == iri) {
+            return Some(path.clone());
+        }
+        self.rewrites
+            .iter()
+            .filter(|(prefix, _)| iri.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .and_then(|(prefix, path)| {
+                let rest = Path::new(&iri[prefix.len()..]);
+                // the rest of the IRI must not escape the mapped directory
+                rest.components()
+                    .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+                    .then(|| path.join(rest))
+            })
+    }
+}
+
+impl Loader for CatalogLoader {
+    fn get<T: Borrow<str>>(&self, iri: Iri<T>) -> Result<(Vec<u8>, String), LoaderError> {
+        let iri = iri.as_str().split('#').next().unwrap();
+        let Some(path) = self.resolve(iri) else {
+            return Err(LoaderError::UnsupportedIri(
+                iri_buf(iri),
+                "no matching catalog entry".into(),
+            ));
+        };
+        match read(&path) {
+            Ok(data) => Ok((data, ctype(&path.to_string_lossy()))),
+            Err(e) if e.kind() == IoErrorKind::NotFound => Err(LoaderError::NotFound(iri_buf(iri))),
+            Err(e) => Err(LoaderError::IoError(iri_buf(iri), e)),
+        }
+    }
+}
+
+fn check_iri(iri: &str) -> Result<IriBuf, CatalogError> {
+    Iri::new(iri.to_string().into()).map_err(|_| CatalogError::InvalidIri(iri.into()))
+}
+
+/// Interpret `reference` (a path or a `file:` URI) relative to the directory `base`.
+fn local_path(base: &Path, reference: &str) -> PathBuf {
+    let reference = reference
+        .strip_prefix("file://")
+        .or_else(|| reference.strip_prefix("file:"))
+        .unwrap_or(reference);
+    if reference.is_empty() {
+        base.into()
+    } else {
+        base.join(reference)
+    }
+}
+
+/// The directory against which references are resolved, given an `xml:base` attribute.
+///
+/// As with IRI resolution, the last segment of `xml_base` is ignored
+/// unless it ends with a slash.
+fn base_dir(base: &Path, xml_base: &str) -> PathBuf {
+    let path = local_path(base, xml_base);
+    if xml_base.is_empty() || xml_base.ends_with('/') {
+        path
+    } else {
+        path.parent().map(Path::to_path_buf).unwrap_or(path)
+    }
+}
+
+//
+
+/// An error raised while reading a [`CatalogLoader`]
+#[derive(Debug, thiserror::Error)]
+pub enum CatalogError {
+    /// The catalog file could not be read
+    #[error("IO error when reading {0}: {1}")]
+    IoError(PathBuf, io::Error),
+    /// The catalog file is not well formed
+    #[error("Syntax error in {0} line {1}: {2}")]
+    SyntaxError(PathBuf, usize, String),
+    /// The catalog contains an invalid IRI
+    #[error("Invalid IRI in catalog: {0}")]
+    InvalidIri(String),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::*;
+
+    const ONTO1: Iri<&str> = Iri::new_const("http://example.org/onto/first");
+    const ONTO2: Iri<&str> = Iri::new_const("http://example.org/onto/second");
+
+    #[test]
+    fn empty_fails() {
+        let ldr = CatalogLoader::new();
+        assert!(matches!(ldr.get(F1), Err(LoaderError::UnsupportedIri(..)),));
+    }
+
+    #[test]
+    fn add_uri() -> TestResult {
+        let mut ldr = CatalogLoader::new();
+        ldr.add_uri(iri_buf(ONTO1.as_str()), "test/file1.ttl".into());
+        assert_eq!(
+            ldr.get(ONTO1)?,
+            (read("test/file1.ttl")?, "text/turtle".into())
+        );
+        assert!(ldr.get(ONTO2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn rewrite_longest_prefix() -> TestResult {
+        let mut ldr = CatalogLoader::new();
+        ldr.add_rewrite(iri_buf("http://example.org/"), "somewhere/else".into());
+        ldr.add_rewrite(iri_buf("http://example.org/ns/"), "test".into());
+        assert_eq!(
+            ldr.resolve("http://example.org/ns/file3.nt#foo"),
+            Some(PathBuf::from("test/file3.nt")),
+        );
+        assert_eq!(
+            ldr.resolve("http://example.org/file3.nt"),
+            Some(PathBuf::from("somewhere/else/file3.nt")),
+        );
+        assert_eq!(ldr.resolve("http://example.org/ns/../secret"), None);
+        assert_eq!(ldr.resolve("http://example.org/ns//etc/passwd"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn mapping_file() -> TestResult {
+        let ldr = CatalogLoader::from_mapping_file("test/catalog.txt")?;
+        assert_eq!(
+            ldr.get(ONTO1)?,
+            (read("test/file1.ttl")?, "text/turtle".into())
+        );
+        assert_eq!(
+            ldr.get(ONTO2)?,
+            (read("test/file3.nt")?, "application/n-triples".into())
+        );
+        let g: MyGraph = ldr.get_graph(ONTO1)?;
+        assert_eq!(g.len(), F1_LEN);
+        // namespace ending with '#'
+        assert_eq!(
+            ldr.resolve("http://example.org/ns#Person"),
+            Some(PathBuf::from("test/file1.ttl")),
+        );
+        assert_eq!(
+            ldr.get(Iri::new_unchecked("http://example.org/ns#Person"))?,
+            (read("test/file1.ttl")?, "text/turtle".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn xml_catalog() -> TestResult {
+        let ldr = CatalogLoader::from_xml_catalog("test/catalog-v001.xml")?;
+        assert_eq!(
+            ldr.get(ONTO1)?,
+            (read("test/file1.ttl")?, "text/turtle".into())
+        );
+        assert_eq!(
+            ldr.get(ONTO2)?,
+            (read("test/file3.nt")?, "application/n-triples".into())
+        );
+        assert!(matches!(ldr.get(FAIL), Err(LoaderError::NotFound(..))));
+        // entries of the next catalog come after those of the main catalog
+        assert_eq!(
+            ldr.resolve("http://example.org/onto/third"),
+            Some(PathBuf::from("test/subdir/../file2.ttl")),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn mapping_file_error() {
+        assert!(matches!(
+            CatalogLoader::from_mapping_file("test/not_there.txt"),
+            Err(CatalogError::IoError(..)),
+        ));
+    }
+}
@@ -56,20 +56,6 @@ impl LocalLoader {
             Ok((iri, path))
         }
     }
-
-    fn ctype(&self, iri: &str) -> String {
-        if iri.ends_with(".ttl") {
-            "text/turtle".into()
-        } else if iri.ends_with(".nt") {
-            "application/n-triples".into()
-        } else if cfg!(feature = "jsonld") && iri.ends_with(".jsonld") {
-            "application/ld+json".into()
-        } else if cfg!(feature = "xml") && iri.ends_with(".rdf") {
-            "application/rdf+xml".into()
-        } else {
-            "application/octet-stream".into()
-        }
-    }
 }
 
 impl Loader for LocalLoader {
@@ -81,7 +67,7 @@ impl Loader for LocalLoader {
                 let subpath = Path::new(&iri[ns.len()..]);
                 let resource_path: PathBuf = path.join(subpath);
                 return match read(resource_path) {
-                    Ok(data) => Ok((data, self.ctype(iri))),
+                    Ok(data) => Ok((data, ctype(iri))),
                     Err(e) if e.kind() == IoErrorKind::NotFound => {
                         // emulate conneg if there is no extension
                         let no_ext = iri.as_bytes()[iri.rfind(['.', '/']).unwrap_or(0)] != b'.';
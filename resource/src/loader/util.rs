@@ -6,3 +6,18 @@ pub type IriBuf = Iri<MownStr<'static>>;
 pub fn iri_buf(iri: &str) -> IriBuf {
     IriBuf::new_unchecked(iri.to_owned().into())
 }
+
+/// Guess the content-type of a resource from the extension of its IRI or path.
+pub fn ctype(iri: &str) -> String {
+    if iri.ends_with(".ttl") {
+        "text/turtle".into()
+    } else if iri.ends_with(".nt") {
+        "application/n-triples".into()
+    } else if cfg!(feature = "jsonld") && iri.ends_with(".jsonld") {
+        "application/ld+json".into()
+    } else if cfg!(feature = "xml") && iri.ends_with(".rdf") {
+        "application/rdf+xml".into()
+    } else {
+        "application/octet-stream".into()
+    }
+}
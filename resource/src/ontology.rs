@@ -0,0 +1,11 @@
+//! This module provides utilities for handling RDFS and OWL ontologies.
+
+mod _diff;
+pub use _diff::*;
+mod _imports;
+pub use _imports::*;
+mod _schema;
+pub use _schema::*;
+
+#[cfg(test)]
+mod test;
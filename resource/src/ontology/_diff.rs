@@ -0,0 +1,309 @@
+use sophia_api::graph::Graph;
+use sophia_api::ns::{owl, rdf, rdfs};
+use sophia_api::source::{StreamError::*, StreamResult};
+use sophia_api::term::{BnodeId, SimpleTerm, Term};
+use sophia_api::triple::Triple;
+use sophia_api::MownStr;
+use sophia_isomorphism::isomorphic_graphs;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The [Changeset](http://purl.org/vocab/changeset/schema#) vocabulary,
+/// used by [`OntologyDiff::change_description`].
+pub mod cs {
+    use sophia_api::namespace;
+    namespace!(
+        "http://purl.org/vocab/changeset/schema#",
+        ChangeSet,
+        addition,
+        removal,
+        subjectOfChange
+    );
+}
+
+/// A triple made of owned [`SimpleTerm`]s.
+pub type OwnedTriple = [SimpleTerm<'static>; 3];
+
+/// The result of [`diff`]:
+/// an ontology-aware description of the differences between two ontology graphs.
+///
+/// Entities (classes and properties) are identified by their IRI,
+/// and detected through their `rdf:type`.
+///
+/// Triples containing blank nodes are grouped into structures
+/// (e.g. OWL restrictions or RDF lists) connected through their blank nodes,
+/// and those structures are compared up to isomorphism,
+/// so that two loads of the same file are not reported as different.
+#[derive(Clone, Debug, Default)]
+pub struct OntologyDiff {
+    /// Classes (`owl:Class` or `rdfs:Class`) that were added, removed or modified
+    pub classes: EntityDiff,
+    /// Properties (`rdf:Property` or any kind of OWL property) that were added, removed or modified
+    pub properties: EntityDiff,
+    /// Axioms (triples) present in the new graph but not in the old one
+    /// (with the blank nodes of the new graph)
+    pub added_axioms: Vec<OwnedTriple>,
+    /// Axioms (triples) present in the old graph but not in the new one
+    /// (with the blank nodes of the old graph)
+    pub removed_axioms: Vec<OwnedTriple>,
+}
+
+/// The differences for one category of entities in an [`OntologyDiff`].
+#[derive(Clone, Debug, Default)]
+pub struct EntityDiff {
+    /// Entities declared in the new graph but not in the old one
+    pub added: Vec<SimpleTerm<'static>>,
+    /// Entities declared in the old graph but not in the new one
+    pub removed: Vec<SimpleTerm<'static>>,
+    /// Entities declared in both graphs, but whose description has changed
+    pub modified: Vec<SimpleTerm<'static>>,
+}
+
+impl EntityDiff {
+    /// Whether this diff is empty
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+impl OntologyDiff {
+    /// Whether the two compared graphs were identical
+    pub fn is_empty(&self) -> bool {
+        self.added_axioms.is_empty() && self.removed_axioms.is_empty()
+    }
+
+    /// Describe this diff in RDF,
+    /// using the [Changeset](http://purl.org/vocab/changeset/schema#) vocabulary.
+    ///
+    /// The change set is a blank node labelled `cs_changeset`,
+    /// whose `cs:subjectOfChange` is `subject` (typically the ontology IRI),
+    /// and every added or removed axiom is described by a reified `rdf:Statement`
+    /// (labelled `cs_a{i}` and `cs_r{i}`, respectively).
+    ///
+    /// NB: the blank nodes of removed axioms (resp. added axioms) are prefixed with `old_`
+    /// (resp. `new_`), so that blank nodes from the two versions can not be confused
+    /// with each other, nor with the blank nodes of the change set.
+    pub fn change_description<T: Term>(&self, subject: T) -> Vec<OwnedTriple> {
+        let changeset = bnode("cs_changeset".into());
+        let mut ret = vec![
+            [
+                changeset.clone(),
+                rdf::type_.into_term(),
+                cs::ChangeSet.into_term(),
+            ],
+            [
+                changeset.clone(),
+                cs::subjectOfChange.into_term(),
+                subject.into_term(),
+            ],
+        ];
+        let changes = [
+            (&self.added_axioms, cs::addition, "a", "new_"),
+            (&self.removed_axioms, cs::removal, "r", "old_"),
+        ];
+        for (axioms, predicate, st_prefix, prefix) in changes {
+            for (i, t) in axioms.iter().enumerate() {
+                let [s, p, o] = t.clone().map(|term| relabel(term, prefix));
+                let st = bnode(format!("cs_{st_prefix}{i}"));
+                ret.push([changeset.clone(), predicate.into_term(), st.clone()]);
+                ret.push([
+                    st.clone(),
+                    rdf::type_.into_term(),
+                    rdf::Statement.into_term(),
+                ]);
+                ret.push([st.clone(), rdf::subject.into_term(), s]);
+                ret.push([st.clone(), rdf::predicate.into_term(), p]);
+                ret.push([st, rdf::object.into_term(), o]);
+            }
+        }
+        ret
+    }
+}
+
+/// Compute the [`OntologyDiff`] between an `old` and a `new` version of an ontology graph.
+///
+/// # Error
+/// If an error occurs while traversing `old`,
+/// a [`SourceError`](sophia_api::source::StreamError::SourceError`) is returned.
+///
+/// If an error occurs while traversing `new`,
+/// a [`SinkError`](sophia_api::source::StreamError::SinkError`) is returned.
+pub fn diff<G1, G2>(old: &G1, new: &G2) -> StreamResult<OntologyDiff, G1::Error, G2::Error>
+where
+    G1: Graph,
+    G2: Graph,
+{
+    let old = collect(old).map_err(SourceError)?;
+    let new = collect(new).map_err(SinkError)?;
+    let (old_ground, old_structures) = split(&old);
+    let (new_ground, new_structures) = split(&new);
+    let mut added_axioms: Vec<_> = new_ground.difference(&old_ground).cloned().collect();
+    let mut removed_axioms: Vec<_> = old_ground.difference(&new_ground).cloned().collect();
+    let (removed_structures, added_structures) = unmatched(old_structures, new_structures);
+    added_axioms.extend(added_structures);
+    removed_axioms.extend(removed_structures);
+    let classes = entity_diff(&old, &new, &added_axioms, &removed_axioms, is_class);
+    let properties = entity_diff(&old, &new, &added_axioms, &removed_axioms, is_property);
+    Ok(OntologyDiff {
+        classes,
+        properties,
+        added_axioms,
+        removed_axioms,
+    })
+}
+
+fn collect<G: Graph>(g: &G) -> Result<BTreeSet<OwnedTriple>, G::Error> {
+    g.triples()
+        .map(|res| res.map(|t| t.spo().map(Term::into_term)))
+        .collect()
+}
+
+/// Split `g` into its ground triples,
+/// and the structures of triples connected through blank nodes.
+fn split(g: &BTreeSet<OwnedTriple>) -> (BTreeSet<OwnedTriple>, Vec<Vec<OwnedTriple>>) {
+    let mut ground = BTreeSet::new();
+    let mut by_bnode: BTreeMap<&str, Vec<&OwnedTriple>> = BTreeMap::new();
+    for t in g {
+        let mut bnodes = bnodes(t).peekable();
+        if bnodes.peek().is_none() {
+            ground.insert(t.clone());
+        }
+        for b in bnodes {
+            by_bnode.entry(b).or_default().push(t);
+        }
+    }
+    let mut visited = BTreeSet::new();
+    let mut structures = vec![];
+    for &start in by_bnode.keys() {
+        if !visited.insert(start) {
+            continue;
+        }
+        let mut triples = BTreeSet::new();
+        let mut todo = vec![start];
+        while let Some(b) = todo.pop() {
+            for &t in &by_bnode[b] {
+                if triples.insert(t) {
+                    todo.extend(bnodes(t).filter(|b| visited.insert(*b)));
+                }
+            }
+        }
+        structures.push(triples.into_iter().cloned().collect());
+    }
+    (ground, structures)
+}
+
+fn bnodes(t: &OwnedTriple) -> impl Iterator<Item = &str> {
+    t.iter().filter_map(|term| match term {
+        SimpleTerm::BlankNode(b) => Some(b.as_str()),
+        _ => None,
+    })
+}
+
+/// Pair the `old` structures with isomorphic `new` structures,
+/// and return the triples of the unpaired ones (from `old`, then from `new`).
+fn unmatched(
+    old: Vec<Vec<OwnedTriple>>,
+    new: Vec<Vec<OwnedTriple>>,
+) -> (Vec<OwnedTriple>, Vec<OwnedTriple>) {
+    // isomorphic structures have the same signature,
+    // so only structures with the same signature need to be checked for isomorphism
+    let mut candidates: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for s in new {
+        candidates.entry(signature(&s)).or_default().push(s);
+    }
+    let mut removed = vec![];
+    for s in old {
+        let same_signature = candidates.entry(signature(&s)).or_default();
+        match same_signature
+            .iter()
+            .position(|c| matches!(isomorphic_graphs(&s, c), Ok(true)))
+        {
+            Some(i) => {
+                same_signature.swap_remove(i);
+            }
+            None => removed.extend(s),
+        }
+    }
+    let added = candidates.into_values().flatten().flatten().collect();
+    (removed, added)
+}
+
+/// The sorted triples of `structure`, with all blank nodes replaced by the same one.
+fn signature(structure: &[OwnedTriple]) -> Vec<OwnedTriple> {
+    let mut ret: Vec<_> = structure
+        .iter()
+        .map(|t| {
+            t.clone().map(|term| match term {
+                SimpleTerm::BlankNode(_) => bnode("b".into()),
+                _ => term,
+            })
+        })
+        .collect();
+    ret.sort();
+    ret
+}
+
+fn entity_diff(
+    old: &BTreeSet<OwnedTriple>,
+    new: &BTreeSet<OwnedTriple>,
+    added_axioms: &[OwnedTriple],
+    removed_axioms: &[OwnedTriple],
+    kind: fn(&SimpleTerm) -> bool,
+) -> EntityDiff {
+    let old_entities = entities(old, kind);
+    let new_entities = entities(new, kind);
+    let changed: BTreeSet<_> = added_axioms
+        .iter()
+        .chain(removed_axioms)
+        .map(|t| &t[0])
+        .collect();
+    EntityDiff {
+        added: new_entities.difference(&old_entities).cloned().collect(),
+        removed: old_entities.difference(&new_entities).cloned().collect(),
+        modified: old_entities
+            .intersection(&new_entities)
+            .filter(|e| changed.contains(e))
+            .cloned()
+            .collect(),
+    }
+}
+
+fn entities(
+    g: &BTreeSet<OwnedTriple>,
+    kind: fn(&SimpleTerm) -> bool,
+) -> BTreeSet<SimpleTerm<'static>> {
+    g.iter()
+        .filter(|[s, p, o]| s.is_iri() && rdf::type_ == p && kind(o))
+        .map(|t| t[0].clone())
+        .collect()
+}
+
+pub(super) fn is_class(t: &SimpleTerm) -> bool {
+    owl::Class == t || rdfs::Class == t
+}
+
+pub(super) fn is_property(t: &SimpleTerm) -> bool {
+    rdf::Property == t
+        || owl::ObjectProperty == t
+        || owl::DatatypeProperty == t
+        || owl::AnnotationProperty == t
+        || owl::AsymmetricProperty == t
+        || owl::FunctionalProperty == t
+        || owl::InverseFunctionalProperty == t
+        || owl::SymmetricProperty == t
+        || owl::TransitiveProperty == t
+        || owl::IrreflexiveProperty == t
+        || owl::ReflexiveProperty == t
+}
+
+/// Prefix all blank node labels in `term` with `prefix`
+fn relabel(term: SimpleTerm<'static>, prefix: &str) -> SimpleTerm<'static> {
+    match term {
+        SimpleTerm::BlankNode(bnid) => bnode(format!("{prefix}{}", bnid.as_str())),
+        SimpleTerm::Triple(spo) => SimpleTerm::Triple(Box::new((*spo).map(|t| relabel(t, prefix)))),
+        term => term,
+    }
+}
+
+fn bnode(label: String) -> SimpleTerm<'static> {
+    SimpleTerm::BlankNode(BnodeId::new_unchecked(MownStr::from(label)))
+}
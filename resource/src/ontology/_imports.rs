@@ -0,0 +1,143 @@
+use super::OwnedTriple;
+use crate::{Loader, LoaderError};
+use sophia_api::ns::owl;
+use sophia_api::term::{BnodeId, SimpleTerm, Term};
+use sophia_iri::Iri;
+use std::borrow::Borrow;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+/// Resolves the `owl:imports` closure of ontologies, using a [`Loader`].
+///
+/// The mapping from ontology IRIs to actual files (or any other location)
+/// is the responsibility of the underlying loader
+/// (see for example [`LocalLoader`](crate::LocalLoader)).
+///
+/// Every loaded document is cached,
+/// so that resolving several closures sharing some imports only loads them once.
+#[derive(Debug)]
+pub struct ImportsResolver<L> {
+    loader: Arc<L>,
+    cache: HashMap<String, Arc<Vec<OwnedTriple>>>,
+}
+
+/// The result of [`ImportsResolver::resolve`].
+#[derive(Clone, Debug, Default)]
+pub struct ImportsClosure {
+    /// The merged graph of all the ontologies in the closure.
+    ///
+    /// NB: blank nodes are relabelled in each ontology,
+    /// to prevent accidental collisions between the documents.
+    pub graph: Vec<OwnedTriple>,
+    /// The ontologies of the closure, in the order in which they were visited
+    /// (the first one being the one that was requested)
+    pub ontologies: Vec<Iri<String>>,
+    /// The `owl:imports` links (importing, imported) that close a cycle
+    pub cycles: Vec<(Iri<String>, Iri<String>)>,
+}
+
+impl<L: Loader> ImportsResolver<L> {
+    /// Constructor
+    pub fn new(loader: Arc<L>) -> Self {
+        ImportsResolver {
+            loader,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// The loader used by this resolver
+    pub fn loader(&self) -> &Arc<L> {
+        &self.loader
+    }
+
+    /// Load the ontology identified by `iri`,
+    /// and (recursively) all the ontologies it imports.
+    ///
+    /// Cycles in the `owl:imports` graph are not an error:
+    /// every ontology is loaded only once,
+    /// and the links closing a cycle are reported in [`ImportsClosure::cycles`].
+    pub fn resolve<T: Borrow<str>>(&mut self, iri: Iri<T>) -> Result<ImportsClosure, LoaderError> {
+        let mut closure = ImportsClosure::default();
+        let mut triples = BTreeSet::new();
+        let mut stack = vec![];
+        self.visit(
+            doc_iri(iri.as_str()),
+            &mut stack,
+            &mut closure,
+            &mut triples,
+        )?;
+        closure.graph = triples.into_iter().collect();
+        Ok(closure)
+    }
+
+    /// Remove all cached documents.
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+    }
+
+    fn visit(
+        &mut self,
+        iri: &str,
+        stack: &mut Vec<String>,
+        closure: &mut ImportsClosure,
+        triples: &mut BTreeSet<OwnedTriple>,
+    ) -> Result<(), LoaderError> {
+        let doc = self.load(iri)?;
+        let index = closure.ontologies.len();
+        closure.ontologies.push(Iri::new_unchecked(iri.to_string()));
+        triples.extend(
+            doc.iter()
+                .map(|t| t.clone().map(|term| relabel(term, index))),
+        );
+        let mut imported = vec![];
+        for [_, p, o] in doc.iter() {
+            if owl::imports == p {
+                if let Some(oiri) = o.iri() {
+                    let oiri = doc_iri(oiri.as_str()).to_string();
+                    if !imported.contains(&oiri) {
+                        imported.push(oiri);
+                    }
+                }
+            }
+        }
+        stack.push(iri.to_string());
+        for other in imported {
+            if stack.contains(&other) {
+                closure.cycles.push((
+                    Iri::new_unchecked(iri.to_string()),
+                    Iri::new_unchecked(other),
+                ));
+            } else if !closure.ontologies.iter().any(|o| o.as_str() == other) {
+                self.visit(&other, stack, closure, triples)?;
+            }
+        }
+        stack.pop();
+        Ok(())
+    }
+
+    fn load(&mut self, iri: &str) -> Result<Arc<Vec<OwnedTriple>>, LoaderError> {
+        if let Some(doc) = self.cache.get(iri) {
+            return Ok(doc.clone());
+        }
+        let doc: Arc<Vec<OwnedTriple>> = Arc::new(self.loader.get_graph(Iri::new_unchecked(iri))?);
+        self.cache.insert(iri.to_string(), doc.clone());
+        Ok(doc)
+    }
+}
+
+/// Strip the fragment identifier (if any) of `iri`
+fn doc_iri(iri: &str) -> &str {
+    iri.split('#').next().unwrap()
+}
+
+/// Prefix all blank node labels in `term` with the index of the document it comes from
+fn relabel(term: SimpleTerm<'static>, index: usize) -> SimpleTerm<'static> {
+    match term {
+        SimpleTerm::BlankNode(bnid) => {
+            let label = format!("o{index}_{}", bnid.as_str());
+            SimpleTerm::BlankNode(BnodeId::new_unchecked(label.into()))
+        }
+        SimpleTerm::Triple(spo) => SimpleTerm::Triple(Box::new((*spo).map(|t| relabel(t, index)))),
+        term => term,
+    }
+}
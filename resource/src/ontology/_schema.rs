@@ -0,0 +1,181 @@
+use super::_diff::{is_class, is_property, OwnedTriple};
+use sophia_api::graph::Graph;
+use sophia_api::ns::{rdf, rdfs};
+use sophia_api::term::{SimpleTerm, Term};
+use sophia_api::triple::Triple;
+use std::collections::{BTreeMap, BTreeSet};
+
+type TermSet = BTreeSet<SimpleTerm<'static>>;
+type TermMap = BTreeMap<SimpleTerm<'static>, TermSet>;
+
+/// A schema-aware index of ontologies, suited for term completion
+/// (e.g. in form builders or editors).
+///
+/// It answers the questions
+/// "which properties are applicable to instances of class C"
+/// (see [`properties_for_class`](SchemaIndex::properties_for_class)) and
+/// "which classes can be values of property P"
+/// (see [`classes_for_property`](SchemaIndex::classes_for_property)),
+/// based on `rdfs:domain` and `rdfs:range` declarations,
+/// taking into account `rdfs:subClassOf` and `rdfs:subPropertyOf` (transitively).
+///
+/// Classes (resp. properties) are IRIs or blank nodes declared as such through their `rdf:type`,
+/// or used as such in any of the axioms above.
+///
+/// NB: OWL class expressions (e.g. `owl:unionOf`) are not interpreted.
+#[derive(Clone, Debug, Default)]
+pub struct SchemaIndex {
+    classes: TermSet,
+    properties: TermSet,
+    super_classes: TermMap,
+    super_properties: TermMap,
+    domains: TermMap,
+    ranges: TermMap,
+}
+
+impl SchemaIndex {
+    /// Build an index from the given ontology graph.
+    pub fn new<G: Graph>(g: &G) -> Result<Self, G::Error> {
+        let mut index = Self::default();
+        index.add_graph(g)?;
+        Ok(index)
+    }
+
+    /// Add the schema information contained in `g` to this index.
+    ///
+    /// This can be used to index several ontologies together.
+    pub fn add_graph<G: Graph>(&mut self, g: &G) -> Result<(), G::Error> {
+        for t in g.triples() {
+            let [s, p, o]: OwnedTriple = t?.spo().map(Term::into_term);
+            if s.is_literal() || o.is_literal() {
+                continue;
+            }
+            if rdf::type_ == p {
+                if is_class(&o) {
+                    self.classes.insert(s);
+                } else if is_property(&o) {
+                    self.properties.insert(s);
+                }
+            } else if rdfs::subClassOf == p {
+                self.classes.insert(s.clone());
+                self.classes.insert(o.clone());
+                self.super_classes.entry(s).or_default().insert(o);
+            } else if rdfs::subPropertyOf == p {
+                self.properties.insert(s.clone());
+                self.properties.insert(o.clone());
+                self.super_properties.entry(s).or_default().insert(o);
+            } else if rdfs::domain == p {
+                self.properties.insert(s.clone());
+                self.classes.insert(o.clone());
+                self.domains.entry(s).or_default().insert(o);
+            } else if rdfs::range == p {
+                self.properties.insert(s.clone());
+                self.classes.insert(o.clone());
+                self.ranges.entry(s).or_default().insert(o);
+            }
+        }
+        Ok(())
+    }
+
+    /// All the classes known to this index.
+    pub fn classes(&self) -> impl Iterator<Item = &SimpleTerm<'static>> + '_ {
+        self.classes.iter()
+    }
+
+    /// All the properties known to this index.
+    pub fn properties(&self) -> impl Iterator<Item = &SimpleTerm<'static>> + '_ {
+        self.properties.iter()
+    }
+
+    /// The super-classes of `class`, including itself (empty if `class` is unknown).
+    pub fn super_classes<T: Term>(&self, class: T) -> BTreeSet<&SimpleTerm<'static>> {
+        closure(&self.super_classes, &self.classes, class.into_term())
+    }
+
+    /// The super-properties of `property`, including itself (empty if `property` is unknown).
+    pub fn super_properties<T: Term>(&self, property: T) -> BTreeSet<&SimpleTerm<'static>> {
+        closure(
+            &self.super_properties,
+            &self.properties,
+            property.into_term(),
+        )
+    }
+
+    /// The domains of `property`, including those inherited from its super-properties.
+    pub fn domains<T: Term>(&self, property: T) -> BTreeSet<&SimpleTerm<'static>> {
+        self.inherited(&self.domains, property.into_term())
+    }
+
+    /// The ranges of `property`, including those inherited from its super-properties.
+    pub fn ranges<T: Term>(&self, property: T) -> BTreeSet<&SimpleTerm<'static>> {
+        self.inherited(&self.ranges, property.into_term())
+    }
+
+    /// The properties applicable to instances of `class`,
+    /// i.e. the properties having at least one domain,
+    /// all of them being super-classes of `class` (or `class` itself).
+    ///
+    /// Properties without any domain are applicable to anything,
+    /// and are therefore not included.
+    pub fn properties_for_class<T: Term>(&self, class: T) -> Vec<&SimpleTerm<'static>> {
+        let supers = self.super_classes(class);
+        self.properties
+            .iter()
+            .filter(|p| {
+                let domains = self.domains(*p);
+                !domains.is_empty() && domains.is_subset(&supers)
+            })
+            .collect()
+    }
+
+    /// The classes whose instances can be values of `property`,
+    /// i.e. the classes which are sub-classes of all the ranges of `property`
+    /// (or the ranges themselves).
+    ///
+    /// If `property` has no range, the result is empty.
+    pub fn classes_for_property<T: Term>(&self, property: T) -> Vec<&SimpleTerm<'static>> {
+        let ranges = self.ranges(property);
+        if ranges.is_empty() {
+            return vec![];
+        }
+        self.classes
+            .iter()
+            .filter(|c| ranges.is_subset(&self.super_classes(*c)))
+            .collect()
+    }
+
+    fn inherited<'a>(
+        &'a self,
+        map: &'a TermMap,
+        property: SimpleTerm<'static>,
+    ) -> BTreeSet<&'a SimpleTerm<'static>> {
+        closure(&self.super_properties, &self.properties, property)
+            .into_iter()
+            .filter_map(|p| map.get(p))
+            .flatten()
+            .collect()
+    }
+}
+
+/// The reflexive transitive closure of `start` in `map`.
+///
+/// NB: if `start` is not in `known`, the result is empty.
+fn closure<'a>(
+    map: &'a TermMap,
+    known: &'a TermSet,
+    start: SimpleTerm<'static>,
+) -> BTreeSet<&'a SimpleTerm<'static>> {
+    let mut result = BTreeSet::new();
+    let Some(first) = known.get(&start) else {
+        return result;
+    };
+    let mut todo = vec![first];
+    while let Some(t) = todo.pop() {
+        if result.insert(t) {
+            if let Some(parents) = map.get(t) {
+                todo.extend(parents.iter());
+            }
+        }
+    }
+    result
+}
@@ -0,0 +1,360 @@
+//! Tests for the [`ontology`](super) module.
+use super::*;
+use crate::test::*;
+use sophia_api::ns::{rdf, NsTerm};
+use sophia_api::prelude::*;
+use sophia_api::term::SimpleTerm;
+use sophia_iri::Iri;
+use sophia_turtle::parser::turtle;
+use std::collections::BTreeSet;
+
+const OLD: &str = r#"
+    PREFIX : <http://example.org/ns#>
+    PREFIX owl: <http://www.w3.org/2002/07/owl#>
+    PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+
+    :Person a owl:Class; rdfs:label "Person".
+    :Animal a owl:Class.
+    :name a owl:DatatypeProperty; rdfs:domain :Person.
+"#;
+
+const NEW: &str = r#"
+    PREFIX : <http://example.org/ns#>
+    PREFIX owl: <http://www.w3.org/2002/07/owl#>
+    PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+
+    :Person a owl:Class; rdfs:label "Human"; rdfs:subClassOf :Agent.
+    :Agent a owl:Class.
+    :name a owl:DatatypeProperty; rdfs:domain :Person.
+"#;
+
+const EX_PERSON: Iri<&str> = Iri::new_const("http://example.org/ns#Person");
+const EX_ANIMAL: Iri<&str> = Iri::new_const("http://example.org/ns#Animal");
+const EX_AGENT: Iri<&str> = Iri::new_const("http://example.org/ns#Agent");
+const EX_ONTO: Iri<&str> = Iri::new_const("http://example.org/ns");
+
+fn parse(ttl: &str) -> MyGraph {
+    turtle::parse_str(ttl).collect_triples().unwrap()
+}
+
+#[test]
+fn diff_identical() -> TestResult {
+    let g = parse(OLD);
+    let d = diff(&g, &g)?;
+    assert!(d.is_empty());
+    assert!(d.classes.is_empty());
+    assert!(d.properties.is_empty());
+    assert_eq!(d.change_description(EX_ONTO).len(), 2);
+    Ok(())
+}
+
+#[test]
+fn diff_classes() -> TestResult {
+    let d = diff(&parse(OLD), &parse(NEW))?;
+    assert_eq!(d.classes.added, vec![EX_AGENT.into_term::<SimpleTerm>()]);
+    assert_eq!(d.classes.removed, vec![EX_ANIMAL.into_term::<SimpleTerm>()]);
+    assert_eq!(
+        d.classes.modified,
+        vec![EX_PERSON.into_term::<SimpleTerm>()]
+    );
+    assert!(d.properties.is_empty());
+    assert_eq!(d.added_axioms.len(), 3);
+    assert_eq!(d.removed_axioms.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn change_description() -> TestResult {
+    let d = diff(&parse(OLD), &parse(NEW))?;
+    let desc = d.change_description(EX_ONTO);
+    assert_eq!(desc.len(), 2 + 5 * (3 + 2));
+    assert_eq!(
+        desc.triples_matching(Any, [cs::subjectOfChange], [EX_ONTO])
+            .count(),
+        1
+    );
+    assert_eq!(
+        desc.triples_matching(Any, [cs::addition], Any).count(),
+        d.added_axioms.len()
+    );
+    assert_eq!(
+        desc.triples_matching(Any, [cs::removal], Any).count(),
+        d.removed_axioms.len()
+    );
+    Ok(())
+}
+
+const RESTRICTIONS: &str = r#"
+    PREFIX : <http://example.org/ns#>
+    PREFIX owl: <http://www.w3.org/2002/07/owl#>
+    PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+
+    :Person a owl:Class;
+        rdfs:subClassOf [ a owl:Restriction; owl:onProperty :name; owl:someValuesFrom :Name ].
+    :Agent a owl:Class;
+        owl:unionOf (:Person :Organization).
+    :name a owl:DatatypeProperty, owl:AsymmetricProperty.
+"#;
+
+#[test]
+fn diff_bnodes_up_to_isomorphism() -> TestResult {
+    let old = parse(RESTRICTIONS);
+    let relabelled = parse(&RESTRICTIONS.replace('[', "_:r. _:r").replace(']', ""));
+    let d = diff(&old, &relabelled)?;
+    assert!(d.is_empty());
+
+    let changed = parse(&RESTRICTIONS.replace(":Organization", ":Group"));
+    let d = diff(&old, &changed)?;
+    assert!(d.classes.added.is_empty());
+    assert!(d.classes.removed.is_empty());
+    assert_eq!(d.classes.modified, vec![EX_AGENT.into_term::<SimpleTerm>()]);
+    // the whole list is reported as changed
+    assert_eq!(d.added_axioms.len(), 5);
+    assert_eq!(d.removed_axioms.len(), 5);
+    Ok(())
+}
+
+#[test]
+fn change_description_keeps_bnodes_apart() -> TestResult {
+    let old = parse(RESTRICTIONS);
+    let new = parse(&RESTRICTIONS.replace(":Organization", ":Group"));
+    let d = diff(&old, &new)?;
+    let labels = |axioms: &[OwnedTriple]| -> BTreeSet<SimpleTerm<'static>> {
+        axioms
+            .iter()
+            .flatten()
+            .filter(|t| t.is_blank_node())
+            .cloned()
+            .collect()
+    };
+    // both versions were labelled the same way by the parser
+    assert!(!labels(&d.added_axioms).is_disjoint(&labels(&d.removed_axioms)));
+
+    let desc = d.change_description(EX_ONTO);
+    let statements = |change: NsTerm| -> BTreeSet<SimpleTerm<'static>> {
+        desc.iter()
+            .filter(|[_, p, _]| change == p)
+            .map(|[_, _, o]| o.clone())
+            .collect()
+    };
+    let (additions, removals) = (statements(cs::addition), statements(cs::removal));
+    let mut added = BTreeSet::new();
+    let mut removed = BTreeSet::new();
+    for [s, p, o] in &desc {
+        if o.is_blank_node() && (rdf::subject == p || rdf::object == p) {
+            if additions.contains(s) {
+                added.insert(o.clone());
+            } else if removals.contains(s) {
+                removed.insert(o.clone());
+            }
+        }
+    }
+    assert!(!added.is_empty());
+    assert!(!removed.is_empty());
+    assert!(added.is_disjoint(&removed));
+    assert!(added.is_disjoint(&additions) && added.is_disjoint(&removals));
+    assert!(removed.is_disjoint(&additions) && removed.is_disjoint(&removals));
+    let changeset = &desc[0][0];
+    assert!(!added.contains(changeset) && !removed.contains(changeset));
+    Ok(())
+}
+
+#[test]
+fn diff_property_characteristics() -> TestResult {
+    let old = parse(RESTRICTIONS);
+    let new = parse(&RESTRICTIONS.replace("owl:DatatypeProperty, ", ""));
+    let d = diff(&new, &old)?;
+    assert!(d.properties.added.is_empty());
+    assert_eq!(d.properties.modified.len(), 1);
+    Ok(())
+}
+
+const ONTO1: Iri<&str> = Iri::new_const("http://example.org/ontology/onto1.ttl");
+const ONTO2: Iri<&str> = Iri::new_const("http://example.org/ontology/onto2.ttl");
+const ONTO3: Iri<&str> = Iri::new_const("http://example.org/ontology/onto3.ttl");
+
+#[test]
+fn imports_closure() -> TestResult {
+    let mut resolver = ImportsResolver::new(make_loader().arced());
+    let closure = resolver.resolve(ONTO1)?;
+    assert_eq!(
+        closure
+            .ontologies
+            .iter()
+            .map(|iri| iri.as_str())
+            .collect::<Vec<_>>(),
+        vec![ONTO1.as_str(), ONTO2.as_str(), ONTO3.as_str()],
+    );
+    assert_eq!(closure.cycles.len(), 1);
+    assert_eq!(closure.cycles[0].0.as_str(), ONTO2.as_str());
+    assert_eq!(closure.cycles[0].1.as_str(), ONTO1.as_str());
+    // 6 triples in onto1 and onto2, 2 in onto3
+    assert_eq!(closure.graph.len(), 6 + 6 + 2);
+    // blank nodes from different ontologies are kept distinct
+    let bnodes: BTreeSet<_> = closure.graph.blank_nodes().collect::<Result<_, _>>()?;
+    assert_eq!(bnodes.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn imports_closure_from_imported() -> TestResult {
+    let mut resolver = ImportsResolver::new(make_loader().arced());
+    let closure = resolver.resolve(ONTO3)?;
+    assert_eq!(closure.ontologies.len(), 1);
+    assert!(closure.cycles.is_empty());
+    assert_eq!(closure.graph.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn imports_closure_not_found() {
+    let mut resolver = ImportsResolver::new(make_loader().arced());
+    assert!(resolver.resolve(FAIL).is_err());
+}
+
+const SCHEMA: &str = r#"
+    PREFIX : <http://example.org/ns#>
+    PREFIX owl: <http://www.w3.org/2002/07/owl#>
+    PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+
+    :Agent a owl:Class.
+    :Person rdfs:subClassOf :Agent.
+    :Student rdfs:subClassOf :Person.
+    :Organization rdfs:subClassOf :Agent.
+    :Document a owl:Class.
+
+    :name rdfs:domain :Agent.
+    :birthDate rdfs:domain :Person.
+    :memberOf rdfs:domain :Person; rdfs:range :Organization.
+    :studiesAt rdfs:subPropertyOf :memberOf; rdfs:domain :Student.
+    :knows rdfs:domain :Person, :Organization; rdfs:range :Agent.
+    :comment a owl:AnnotationProperty.
+"#;
+
+fn ex(suffix: &str) -> SimpleTerm<'static> {
+    SimpleTerm::Iri(IriRef::new_unchecked(
+        format!("http://example.org/ns#{suffix}").into(),
+    ))
+}
+
+fn exs(suffixes: &[&str]) -> Vec<SimpleTerm<'static>> {
+    suffixes.iter().map(|s| ex(s)).collect()
+}
+
+#[test]
+fn schema_index_entities() -> TestResult {
+    let g: MyGraph = turtle::parse_str(SCHEMA).collect_triples()?;
+    let index = SchemaIndex::new(&g)?;
+    assert_eq!(
+        index.classes().cloned().collect::<Vec<_>>(),
+        exs(&["Agent", "Document", "Organization", "Person", "Student"]),
+    );
+    assert_eq!(
+        index.properties().cloned().collect::<Vec<_>>(),
+        exs(&[
+            "birthDate",
+            "comment",
+            "knows",
+            "memberOf",
+            "name",
+            "studiesAt"
+        ]),
+    );
+    assert_eq!(
+        index
+            .super_classes(ex("Student"))
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>(),
+        exs(&["Agent", "Person", "Student"]),
+    );
+    assert!(index.super_classes(ex("Unknown")).is_empty());
+    assert_eq!(
+        index
+            .domains(ex("studiesAt"))
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>(),
+        exs(&["Person", "Student"]),
+    );
+    assert_eq!(
+        index
+            .ranges(ex("studiesAt"))
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>(),
+        exs(&["Organization"]),
+    );
+    Ok(())
+}
+
+#[test]
+fn schema_index_properties_for_class() -> TestResult {
+    let g: MyGraph = turtle::parse_str(SCHEMA).collect_triples()?;
+    let index = SchemaIndex::new(&g)?;
+    let props = |c| {
+        index
+            .properties_for_class(ex(c))
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(props("Agent"), exs(&["name"]));
+    assert_eq!(props("Person"), exs(&["birthDate", "memberOf", "name"]));
+    assert_eq!(
+        props("Student"),
+        exs(&["birthDate", "memberOf", "name", "studiesAt"]),
+    );
+    assert_eq!(props("Organization"), exs(&["name"]));
+    assert!(props("Document").is_empty());
+    assert!(props("Unknown").is_empty());
+    Ok(())
+}
+
+#[test]
+fn schema_index_classes_for_property() -> TestResult {
+    let g: MyGraph = turtle::parse_str(SCHEMA).collect_triples()?;
+    let index = SchemaIndex::new(&g)?;
+    let classes = |p| {
+        index
+            .classes_for_property(ex(p))
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(classes("memberOf"), exs(&["Organization"]));
+    assert_eq!(classes("studiesAt"), exs(&["Organization"]));
+    assert_eq!(
+        classes("knows"),
+        exs(&["Agent", "Organization", "Person", "Student"]),
+    );
+    assert!(classes("name").is_empty());
+    assert!(classes("unknown").is_empty());
+    Ok(())
+}
+
+#[test]
+fn schema_index_several_graphs() -> TestResult {
+    let g1: MyGraph = turtle::parse_str(SCHEMA).collect_triples()?;
+    let g2: MyGraph = turtle::parse_str(
+        r#"
+        PREFIX : <http://example.org/ns#>
+        PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+        :Report rdfs:subClassOf :Document, :Agent.
+        :title rdfs:domain :Document.
+        "#,
+    )
+    .collect_triples()?;
+    let mut index = SchemaIndex::new(&g1)?;
+    assert!(index.properties_for_class(ex("Report")).is_empty());
+    index.add_graph(&g2)?;
+    assert_eq!(
+        index
+            .properties_for_class(ex("Report"))
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>(),
+        exs(&["name", "title"]),
+    );
+    Ok(())
+}
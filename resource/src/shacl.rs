@@ -0,0 +1,46 @@
+//! This module provides utilities for using [SHACL] shapes.
+//!
+//! [SHACL]: https://www.w3.org/TR/shacl/
+
+mod _form;
+pub use _form::*;
+
+/// The standard `sh:` namespace (restricted to the terms used by this module).
+pub mod sh {
+    use sophia_api::namespace;
+    namespace!(
+        "http://www.w3.org/ns/shacl#",
+        // classes
+        NodeShape,
+        PropertyShape,
+        // node kinds
+        BlankNode,
+        BlankNodeOrIRI,
+        BlankNodeOrLiteral,
+        IRI,
+        IRIOrLiteral,
+        Literal,
+        // properties
+        class,
+        datatype,
+        defaultValue,
+        description,
+        flags,
+        maxCount,
+        maxLength,
+        minCount,
+        minLength,
+        name,
+        nodeKind,
+        order,
+        path,
+        pattern,
+        property,
+        targetClass;
+        // 'in' is a Rust keyword, so we use 'in_' instead
+        in_, "in"
+    );
+}
+
+#[cfg(test)]
+mod test;
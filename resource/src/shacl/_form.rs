@@ -0,0 +1,512 @@
+use super::sh;
+use crate::ontology::OwnedTriple;
+use lazy_static::lazy_static;
+use regex::{Regex, RegexBuilder};
+use sophia_api::graph::Graph;
+use sophia_api::ns::{rdf, rdfs, xsd, NsTerm};
+use sophia_api::term::matcher::Any;
+use sophia_api::term::{SimpleTerm, Term, TryFromTerm};
+use sophia_api::triple::Triple;
+use sophia_api::MownStr;
+use sophia_iri::{Iri, IriRef};
+use std::collections::BTreeSet;
+use std::error::Error;
+
+/// A structured description of a form, generated from a SHACL node shape
+/// (see [`FormDescription::from_shape`]).
+///
+/// It is meant to be rendered by a UI layer,
+/// and the values submitted through that UI can be turned back into triples
+/// with [`FormDescription::validate`].
+#[derive(Clone, Debug)]
+pub struct FormDescription {
+    /// The node shape this form was generated from
+    pub shape: SimpleTerm<'static>,
+    /// The `rdfs:label` of the shape, if any
+    pub label: Option<String>,
+    /// The `sh:targetClass` of the shape, if any
+    pub target_class: Option<Iri<String>>,
+    /// The fields of the form, one per property shape,
+    /// sorted by `sh:order` (fields without any order come last)
+    pub fields: Vec<FormField>,
+}
+
+/// A field of a [`FormDescription`], generated from a SHACL property shape.
+#[derive(Clone, Debug)]
+pub struct FormField {
+    /// The `sh:path` of the property shape, which also identifies the field
+    /// (only predicate paths are supported)
+    pub path: Iri<String>,
+    /// The `sh:name` of the property shape, if any
+    pub name: Option<String>,
+    /// The `sh:description` of the property shape, if any
+    pub description: Option<String>,
+    /// The kind of values expected in this field
+    pub kind: FieldKind,
+    /// The `sh:datatype` of the property shape, if any
+    pub datatype: Option<Iri<String>>,
+    /// The `sh:class` of the property shape, if any
+    pub class: Option<Iri<String>>,
+    /// The `sh:minCount` of the property shape (0 if absent)
+    pub min_count: usize,
+    /// The `sh:maxCount` of the property shape, if any
+    pub max_count: Option<usize>,
+    /// The values listed by `sh:in` (empty if absent)
+    pub allowed_values: Vec<SimpleTerm<'static>>,
+    /// The `sh:defaultValue` of the property shape, if any
+    pub default_value: Option<SimpleTerm<'static>>,
+    /// The `sh:pattern` of the property shape (taking `sh:flags` into account), if any
+    pub pattern: Option<Regex>,
+    /// The `sh:minLength` of the property shape, if any
+    pub min_length: Option<usize>,
+    /// The `sh:maxLength` of the property shape, if any
+    pub max_length: Option<usize>,
+    /// The `sh:order` of the property shape, if any
+    pub order: Option<f64>,
+}
+
+/// The kind of values expected in a [`FormField`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldKind {
+    /// Values are IRIs
+    /// (when `sh:nodeKind` allows IRIs but not literals,
+    /// or when `sh:class` is specified, or when all the `sh:in` values are IRIs)
+    Iri,
+    /// Values are literals (in all other cases)
+    Literal,
+}
+
+/// An error raised by [`FormDescription::from_shape`]
+#[derive(Debug, thiserror::Error)]
+pub enum FormError<E: Error + Send + Sync + 'static> {
+    /// The underlying graph raised an error
+    #[error("Graph error: {0}")]
+    GraphError(#[source] E),
+    /// The given property shape has a path which is not a predicate IRI
+    #[error("Unsupported path in property shape {0:?} (only predicate paths are supported)")]
+    UnsupportedPath(SimpleTerm<'static>),
+    /// The given constraint has an invalid value in the given property shape
+    #[error("Invalid value for {1} in property shape {0:?}")]
+    InvalidConstraint(SimpleTerm<'static>, &'static str),
+}
+
+/// A problem detected by [`FormDescription::validate`] in the submitted values.
+#[derive(Clone, Debug, thiserror::Error)]
+#[error("{path}: {violation}")]
+pub struct FieldError {
+    /// The path (IRI) identifying the field
+    pub path: String,
+    /// The detected problem
+    pub violation: FieldViolation,
+}
+
+/// The kind of problem reported by a [`FieldError`].
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum FieldViolation {
+    /// No field has this path in the form
+    #[error("unknown field")]
+    UnknownField,
+    /// Fewer values than `sh:minCount` were submitted
+    #[error("expected at least {0} value(s), got {1}")]
+    MinCount(usize, usize),
+    /// More values than `sh:maxCount` were submitted
+    #[error("expected at most {0} value(s), got {1}")]
+    MaxCount(usize, usize),
+    /// The value is not a valid (absolute) IRI
+    #[error("invalid IRI {0:?}")]
+    InvalidIri(String),
+    /// The value is not a valid lexical form for the datatype of the field
+    #[error("invalid value {0:?} for datatype <{1}>")]
+    InvalidLiteral(String, String),
+    /// The value is not one of the `sh:in` values
+    #[error("value {0:?} is not allowed")]
+    NotAllowed(String),
+    /// The value does not match the `sh:pattern` of the field
+    #[error("value {0:?} does not match {1:?}")]
+    PatternMismatch(String, String),
+    /// The value is shorter than the `sh:minLength` of the field
+    #[error("value {0:?} is shorter than {1} characters")]
+    TooShort(String, usize),
+    /// The value is longer than the `sh:maxLength` of the field
+    #[error("value {0:?} is longer than {1} characters")]
+    TooLong(String, usize),
+}
+
+impl FormDescription {
+    /// Generate a form description from the node shape `shape` described in `g`.
+    ///
+    /// Every `sh:property` of the shape becomes a [`FormField`].
+    /// Constraints that can not be represented in a form
+    /// (e.g. `sh:node`, `sh:or`, value ranges...) are ignored.
+    pub fn from_shape<G: Graph, T: Term>(g: &G, shape: T) -> Result<Self, FormError<G::Error>> {
+        let shape: SimpleTerm<'static> = shape.into_term();
+        let label = value(g, &shape, rdfs::label)?.and_then(|t| lexical_form(&t));
+        let target_class = value(g, &shape, sh::targetClass)?
+            .map(|t| {
+                iri(&t).ok_or(FormError::InvalidConstraint(
+                    shape.clone(),
+                    "sh:targetClass",
+                ))
+            })
+            .transpose()?;
+        let mut fields = objects(g, &shape, sh::property)?
+            .into_iter()
+            .map(|p| FormField::from_property_shape(g, p))
+            .collect::<Result<Vec<_>, _>>()?;
+        fields.sort_by(|f1, f2| match (f1.order, f2.order) {
+            (Some(o1), Some(o2)) => o1.total_cmp(&o2),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        Ok(FormDescription {
+            shape,
+            label,
+            target_class,
+            fields,
+        })
+    }
+
+    /// The field identified by the given path, if any.
+    pub fn field(&self, path: &str) -> Option<&FormField> {
+        self.fields.iter().find(|f| f.path.as_str() == path)
+    }
+
+    /// Validate the submitted `values`, and convert them into triples describing `focus`.
+    ///
+    /// `values` are (path, value) pairs,
+    /// where path identifies a [field](FormDescription::fields) by its [path](FormField::path).
+    /// Empty values are ignored, as they usually denote unfilled inputs.
+    ///
+    /// If the form has a [target class](FormDescription::target_class),
+    /// a triple stating that `focus` has this `rdf:type` is also produced.
+    ///
+    /// If any value is invalid, or if any field has a wrong number of values,
+    /// all the detected problems are returned instead.
+    pub fn validate<'a, T, I>(
+        &self,
+        focus: T,
+        values: I,
+    ) -> Result<Vec<OwnedTriple>, Vec<FieldError>>
+    where
+        T: Term,
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        let focus: SimpleTerm<'static> = focus.into_term();
+        let mut triples = vec![];
+        let mut errors = vec![];
+        let mut counts = vec![0; self.fields.len()];
+        if let Some(class) = &self.target_class {
+            triples.push([
+                focus.clone(),
+                rdf::type_.into_term(),
+                class.as_ref().into_term(),
+            ]);
+        }
+        for (path, val) in values {
+            if val.is_empty() {
+                continue;
+            }
+            let Some(i) = self.fields.iter().position(|f| f.path.as_str() == path) else {
+                errors.push(FieldError {
+                    path: path.to_string(),
+                    violation: FieldViolation::UnknownField,
+                });
+                continue;
+            };
+            let field = &self.fields[i];
+            match field.convert(val) {
+                Ok(o) => {
+                    let triple = [focus.clone(), field.path.as_ref().into_term(), o];
+                    if !<[_]>::contains(&triples, &triple) {
+                        counts[i] += 1;
+                        triples.push(triple);
+                    }
+                }
+                Err(violation) => errors.push(FieldError {
+                    path: path.to_string(),
+                    violation,
+                }),
+            }
+        }
+        for (field, count) in self.fields.iter().zip(counts) {
+            let violation = match field.max_count {
+                _ if count < field.min_count => FieldViolation::MinCount(field.min_count, count),
+                Some(max) if count > max => FieldViolation::MaxCount(max, count),
+                _ => continue,
+            };
+            errors.push(FieldError {
+                path: field.path.as_str().to_string(),
+                violation,
+            });
+        }
+        if errors.is_empty() {
+            Ok(triples)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl FormField {
+    fn from_property_shape<G: Graph>(
+        g: &G,
+        shape: SimpleTerm<'static>,
+    ) -> Result<Self, FormError<G::Error>> {
+        let invalid = |constraint| FormError::InvalidConstraint(shape.clone(), constraint);
+        let path = value(g, &shape, sh::path)?
+            .as_ref()
+            .and_then(iri)
+            .ok_or_else(|| FormError::UnsupportedPath(shape.clone()))?;
+        let string = |p: NsTerm<'static>, constraint| -> Result<_, FormError<G::Error>> {
+            value(g, &shape, p)?
+                .map(|t| lexical_form(&t).ok_or(invalid(constraint)))
+                .transpose()
+        };
+        let iri_value = |p: NsTerm<'static>, constraint| -> Result<_, FormError<G::Error>> {
+            value(g, &shape, p)?
+                .map(|t| iri(&t).ok_or(invalid(constraint)))
+                .transpose()
+        };
+        let count = |p: NsTerm<'static>, constraint| -> Result<_, FormError<G::Error>> {
+            value(g, &shape, p)?
+                .map(|t| usize::try_from_term(t).map_err(|_| invalid(constraint)))
+                .transpose()
+        };
+        let name = string(sh::name, "sh:name")?;
+        let description = string(sh::description, "sh:description")?;
+        let datatype = iri_value(sh::datatype, "sh:datatype")?;
+        let class = iri_value(sh::class, "sh:class")?;
+        let min_count = count(sh::minCount, "sh:minCount")?.unwrap_or(0);
+        let max_count = count(sh::maxCount, "sh:maxCount")?;
+        let min_length = count(sh::minLength, "sh:minLength")?;
+        let max_length = count(sh::maxLength, "sh:maxLength")?;
+        let allowed_values = match value(g, &shape, sh::in_)? {
+            Some(head) => list(g, head).ok_or(invalid("sh:in"))??,
+            None => vec![],
+        };
+        let default_value = value(g, &shape, sh::defaultValue)?;
+        let pattern = string(sh::pattern, "sh:pattern")?
+            .map(|pattern| -> Result<_, FormError<G::Error>> {
+                let flags = string(sh::flags, "sh:flags")?.unwrap_or_default();
+                RegexBuilder::new(&pattern)
+                    .case_insensitive(flags.contains('i'))
+                    .multi_line(flags.contains('m'))
+                    .dot_matches_new_line(flags.contains('s'))
+                    .ignore_whitespace(flags.contains('x'))
+                    .build()
+                    .map_err(|_| invalid("sh:pattern"))
+            })
+            .transpose()?;
+        let order = value(g, &shape, sh::order)?
+            .map(|t| {
+                f64::try_from_term(&t)
+                    .ok()
+                    .or_else(|| isize::try_from_term(&t).ok().map(|i| i as f64))
+                    .ok_or(invalid("sh:order"))
+            })
+            .transpose()?;
+        let node_kind = value(g, &shape, sh::nodeKind)?;
+        let kind = match node_kind {
+            Some(k) if sh::IRI == k || sh::BlankNode == k || sh::BlankNodeOrIRI == k => {
+                FieldKind::Iri
+            }
+            Some(k) if sh::Literal == k || sh::BlankNodeOrLiteral == k => FieldKind::Literal,
+            _ if datatype.is_some() => FieldKind::Literal,
+            _ if class.is_some() => FieldKind::Iri,
+            _ if !allowed_values.is_empty() && allowed_values.iter().all(Term::is_iri) => {
+                FieldKind::Iri
+            }
+            _ => FieldKind::Literal,
+        };
+        Ok(FormField {
+            path,
+            name,
+            description,
+            kind,
+            datatype,
+            class,
+            min_count,
+            max_count,
+            allowed_values,
+            default_value,
+            pattern,
+            min_length,
+            max_length,
+            order,
+        })
+    }
+
+    /// Convert a submitted value into a term, checking the constraints of this field.
+    ///
+    /// If the field has [allowed values](FormField::allowed_values),
+    /// `value` is expected to be the IRI or lexical form of one of them.
+    pub fn convert(&self, value: &str) -> Result<SimpleTerm<'static>, FieldViolation> {
+        if !self.allowed_values.is_empty() {
+            return self
+                .allowed_values
+                .iter()
+                .find(|t| match t.iri() {
+                    Some(iri) => iri.as_str() == value,
+                    None => t.lexical_form().is_some_and(|lex| &*lex == value),
+                })
+                .cloned()
+                .ok_or_else(|| FieldViolation::NotAllowed(value.to_string()));
+        }
+        let length = value.chars().count();
+        if let Some(min) = self.min_length.filter(|min| length < *min) {
+            return Err(FieldViolation::TooShort(value.to_string(), min));
+        }
+        if let Some(max) = self.max_length.filter(|max| length > *max) {
+            return Err(FieldViolation::TooLong(value.to_string(), max));
+        }
+        if let Some(pattern) = self.pattern.as_ref().filter(|p| !p.is_match(value)) {
+            return Err(FieldViolation::PatternMismatch(
+                value.to_string(),
+                pattern.as_str().to_string(),
+            ));
+        }
+        match self.kind {
+            FieldKind::Iri => Iri::new(value.to_string())
+                .map(|iri| iri.into_term())
+                .map_err(|_| FieldViolation::InvalidIri(value.to_string())),
+            FieldKind::Literal => {
+                let datatype = self
+                    .datatype
+                    .as_ref()
+                    .map(|dt| dt.as_str())
+                    .unwrap_or(XSD_STRING);
+                if !is_valid_lexical(value, datatype) {
+                    return Err(FieldViolation::InvalidLiteral(
+                        value.to_string(),
+                        datatype.to_string(),
+                    ));
+                }
+                Ok(SimpleTerm::LiteralDatatype(
+                    MownStr::from(value.to_string()),
+                    IriRef::new_unchecked(MownStr::from(datatype.to_string())),
+                ))
+            }
+        }
+    }
+}
+
+const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+
+fn objects<G: Graph>(
+    g: &G,
+    s: &SimpleTerm<'static>,
+    p: NsTerm<'static>,
+) -> Result<Vec<SimpleTerm<'static>>, FormError<G::Error>> {
+    g.triples_matching([s], [p], Any)
+        .map(|res| res.map(|t| t.to_o().into_term()))
+        .collect::<Result<_, _>>()
+        .map_err(FormError::GraphError)
+}
+
+fn value<G: Graph>(
+    g: &G,
+    s: &SimpleTerm<'static>,
+    p: NsTerm<'static>,
+) -> Result<Option<SimpleTerm<'static>>, FormError<G::Error>> {
+    g.triples_matching([s], [p], Any)
+        .next()
+        .transpose()
+        .map(|o| o.map(|t| t.to_o().into_term()))
+        .map_err(FormError::GraphError)
+}
+
+/// Read the RDF list starting at `head`,
+/// or return `None` if it is not well-formed.
+fn list<G: Graph>(
+    g: &G,
+    mut head: SimpleTerm<'static>,
+) -> Option<Result<Vec<SimpleTerm<'static>>, FormError<G::Error>>> {
+    let mut items = vec![];
+    let mut visited = BTreeSet::new();
+    while rdf::nil != head {
+        if !visited.insert(head.clone()) {
+            return None; // cycle
+        }
+        match (value(g, &head, rdf::first), value(g, &head, rdf::rest)) {
+            (Ok(Some(first)), Ok(Some(rest))) => {
+                items.push(first);
+                head = rest;
+            }
+            (Err(err), _) | (_, Err(err)) => return Some(Err(err)),
+            _ => return None,
+        }
+    }
+    Some(Ok(items))
+}
+
+fn lexical_form(t: &SimpleTerm) -> Option<String> {
+    t.lexical_form().map(|lex| lex.to_string())
+}
+
+fn iri(t: &SimpleTerm) -> Option<Iri<String>> {
+    t.iri()
+        .and_then(|iri| Iri::new(iri.as_str().to_string()).ok())
+}
+
+lazy_static! {
+    static ref INTEGER: Regex = Regex::new(r"^[+-]?[0-9]+$").unwrap();
+    static ref DECIMAL: Regex = Regex::new(r"^[+-]?([0-9]+(\.[0-9]*)?|\.[0-9]+)$").unwrap();
+    static ref DOUBLE: Regex =
+        Regex::new(r"^([+-]?([0-9]+(\.[0-9]*)?|\.[0-9]+)([eE][+-]?[0-9]+)?|[+-]?INF|NaN)$")
+            .unwrap();
+    static ref DATE: Regex =
+        Regex::new(r"^-?[0-9]{4,}-[0-9]{2}-[0-9]{2}(Z|[+-][0-9]{2}:[0-9]{2})?$").unwrap();
+    static ref DATE_TIME: Regex = Regex::new(
+        r"^-?[0-9]{4,}-[0-9]{2}-[0-9]{2}T[0-9]{2}:[0-9]{2}:[0-9]{2}(\.[0-9]+)?(Z|[+-][0-9]{2}:[0-9]{2})?$"
+    )
+    .unwrap();
+}
+
+/// Check `value` against the lexical space of the most common XSD datatypes.
+///
+/// Other datatypes accept any value, except for `rdf:langString`,
+/// as forms provide no way to specify a language tag.
+fn is_valid_lexical(value: &str, datatype: &str) -> bool {
+    if rdf::langString == IriRef::new_unchecked(datatype) {
+        return false;
+    }
+    let Some(local) = datatype.strip_prefix(xsd::PREFIX.as_str()) else {
+        return true;
+    };
+    let bounds = match local {
+        "boolean" => return matches!(value, "true" | "false" | "1" | "0"),
+        "decimal" => return DECIMAL.is_match(value),
+        "double" | "float" => return DOUBLE.is_match(value),
+        "date" => return DATE.is_match(value),
+        "dateTime" => return DATE_TIME.is_match(value),
+        "integer" => (None, None),
+        "nonNegativeInteger" => (Some(0), None),
+        "positiveInteger" => (Some(1), None),
+        "nonPositiveInteger" => (None, Some(0)),
+        "negativeInteger" => (None, Some(-1)),
+        "long" => (Some(i64::MIN.into()), Some(i64::MAX.into())),
+        "int" => (Some(i32::MIN.into()), Some(i32::MAX.into())),
+        "short" => (Some(i16::MIN.into()), Some(i16::MAX.into())),
+        "byte" => (Some(i8::MIN.into()), Some(i8::MAX.into())),
+        "unsignedLong" => (Some(0), Some(u64::MAX.into())),
+        "unsignedInt" => (Some(0), Some(u32::MAX.into())),
+        "unsignedShort" => (Some(0), Some(u16::MAX.into())),
+        "unsignedByte" => (Some(0), Some(u8::MAX.into())),
+        _ => return true,
+    };
+    if !INTEGER.is_match(value) {
+        return false;
+    }
+    match value.parse::<i128>() {
+        Ok(i) => bounds.0.is_none_or(|min| i >= min) && bounds.1.is_none_or(|max| i <= max),
+        // out of the i128 range, so only unbounded sides are acceptable
+        Err(_) => {
+            if value.starts_with('-') {
+                bounds.0.is_none()
+            } else {
+                bounds.1.is_none()
+            }
+        }
+    }
+}
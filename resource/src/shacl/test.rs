@@ -0,0 +1,254 @@
+//! Tests for the [`shacl`](super) module.
+use super::*;
+use crate::test::*;
+use sophia_api::ns::rdf;
+use sophia_api::prelude::*;
+use sophia_api::term::SimpleTerm;
+use sophia_api::MownStr;
+use sophia_turtle::parser::turtle;
+
+const SHAPES: &str = r#"
+    PREFIX : <http://example.org/ns#>
+    PREFIX rdfs: <http://www.w3.org/2000/01/rdf-schema#>
+    PREFIX sh: <http://www.w3.org/ns/shacl#>
+    PREFIX xsd: <http://www.w3.org/2001/XMLSchema#>
+
+    :PersonShape a sh:NodeShape;
+        rdfs:label "Person";
+        sh:targetClass :Person;
+        sh:property [
+            sh:path :age;
+            sh:name "age";
+            sh:datatype xsd:nonNegativeInteger;
+            sh:maxCount 1;
+            sh:order 2;
+        ], [
+            sh:path :name;
+            sh:name "name";
+            sh:description "The full name of the person";
+            sh:datatype xsd:string;
+            sh:minCount 1;
+            sh:maxCount 1;
+            sh:minLength 2;
+            sh:maxLength 20;
+            sh:order 1;
+        ], [
+            sh:path :knows;
+            sh:class :Person;
+        ], [
+            sh:path :status;
+            sh:in (:Active :Retired);
+            sh:defaultValue :Active;
+            sh:order 3;
+        ], [
+            sh:path :email;
+            sh:pattern "^[a-z.]+@example\\.org$";
+            sh:flags "i";
+            sh:order 4;
+        ].
+
+    :BadShape sh:property [ sh:path [ sh:inversePath :knows ] ].
+"#;
+
+fn ex(suffix: &str) -> SimpleTerm<'static> {
+    SimpleTerm::Iri(IriRef::new_unchecked(
+        format!("http://example.org/ns#{suffix}").into(),
+    ))
+}
+
+fn ex_str(suffix: &str) -> String {
+    format!("http://example.org/ns#{suffix}")
+}
+
+fn make_form() -> Result<FormDescription, Box<dyn std::error::Error>> {
+    let g: MyGraph = turtle::parse_str(SHAPES).collect_triples()?;
+    Ok(FormDescription::from_shape(&g, ex("PersonShape"))?)
+}
+
+#[test]
+fn form_description() -> TestResult {
+    let form = make_form()?;
+    assert_eq!(form.label.as_deref(), Some("Person"));
+    assert_eq!(
+        form.target_class.as_ref().map(|c| c.as_str()),
+        Some(ex_str("Person").as_str())
+    );
+    assert_eq!(
+        form.fields
+            .iter()
+            .map(|f| f.path.as_str())
+            .collect::<Vec<_>>(),
+        ["name", "age", "status", "email", "knows"].map(ex_str),
+    );
+
+    let name = &form.fields[0];
+    assert_eq!(name.name.as_deref(), Some("name"));
+    assert_eq!(
+        name.description.as_deref(),
+        Some("The full name of the person")
+    );
+    assert_eq!(name.kind, FieldKind::Literal);
+    assert_eq!((name.min_count, name.max_count), (1, Some(1)));
+    assert_eq!((name.min_length, name.max_length), (Some(2), Some(20)));
+
+    let age = &form.fields[1];
+    assert_eq!(
+        age.datatype.as_ref().map(|dt| dt.as_str()),
+        Some("http://www.w3.org/2001/XMLSchema#nonNegativeInteger")
+    );
+    assert_eq!((age.min_count, age.max_count), (0, Some(1)));
+
+    let status = &form.fields[2];
+    assert_eq!(status.kind, FieldKind::Iri);
+    assert_eq!(status.allowed_values, vec![ex("Active"), ex("Retired")]);
+    assert_eq!(status.default_value, Some(ex("Active")));
+
+    let knows = form.field(&ex_str("knows")).unwrap();
+    assert_eq!(knows.kind, FieldKind::Iri);
+    assert_eq!(
+        knows.class.as_ref().map(|c| c.as_str()),
+        Some(ex_str("Person").as_str())
+    );
+    assert_eq!(knows.order, None);
+    Ok(())
+}
+
+#[test]
+fn form_unsupported_path() -> TestResult {
+    let g: MyGraph = turtle::parse_str(SHAPES).collect_triples()?;
+    assert!(matches!(
+        FormDescription::from_shape(&g, ex("BadShape")),
+        Err(FormError::UnsupportedPath(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn form_validate_ok() -> TestResult {
+    let form = make_form()?;
+    let (name, age, knows, status, email) = (
+        ex_str("name"),
+        ex_str("age"),
+        ex_str("knows"),
+        ex_str("status"),
+        ex_str("email"),
+    );
+    let (bob, retired) = (ex_str("bob"), ex_str("Retired"));
+    let triples = form
+        .validate(
+            ex("alice"),
+            [
+                (name.as_str(), "Alice"),
+                (age.as_str(), "42"),
+                (knows.as_str(), bob.as_str()),
+                (knows.as_str(), ""),
+                (status.as_str(), retired.as_str()),
+                (email.as_str(), "Alice@Example.org"),
+            ],
+        )
+        .map_err(|errs| format!("{errs:?}"))?;
+    let xsd_string: IriRef<MownStr<'static>> =
+        IriRef::new_unchecked("http://www.w3.org/2001/XMLSchema#string".into());
+    let xsd_nni: IriRef<MownStr<'static>> =
+        IriRef::new_unchecked("http://www.w3.org/2001/XMLSchema#nonNegativeInteger".into());
+    assert_eq!(
+        triples,
+        vec![
+            [ex("alice"), rdf::type_.into_term(), ex("Person")],
+            [
+                ex("alice"),
+                ex("name"),
+                SimpleTerm::LiteralDatatype("Alice".into(), xsd_string.clone()),
+            ],
+            [
+                ex("alice"),
+                ex("age"),
+                SimpleTerm::LiteralDatatype("42".into(), xsd_nni),
+            ],
+            [ex("alice"), ex("knows"), ex("bob")],
+            [ex("alice"), ex("status"), ex("Retired")],
+            [
+                ex("alice"),
+                ex("email"),
+                SimpleTerm::LiteralDatatype("Alice@Example.org".into(), xsd_string),
+            ],
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn form_validate_errors() -> TestResult {
+    let form = make_form()?;
+    let (age, knows, status, email) = (
+        ex_str("age"),
+        ex_str("knows"),
+        ex_str("status"),
+        ex_str("email"),
+    );
+    let errors = form
+        .validate(
+            ex("alice"),
+            [
+                (age.as_str(), "-1"),
+                (knows.as_str(), "not an IRI"),
+                (status.as_str(), "Dead"),
+                (email.as_str(), "alice@example.com"),
+                ("http://example.org/ns#unknown", "x"),
+            ],
+        )
+        .unwrap_err();
+    assert_eq!(
+        errors
+            .into_iter()
+            .map(|e| (e.path, e.violation))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                age.clone(),
+                FieldViolation::InvalidLiteral(
+                    "-1".into(),
+                    "http://www.w3.org/2001/XMLSchema#nonNegativeInteger".into()
+                )
+            ),
+            (knows, FieldViolation::InvalidIri("not an IRI".into())),
+            (status, FieldViolation::NotAllowed("Dead".into())),
+            (
+                email,
+                FieldViolation::PatternMismatch(
+                    "alice@example.com".into(),
+                    "^[a-z.]+@example\\.org$".into()
+                )
+            ),
+            (
+                "http://example.org/ns#unknown".into(),
+                FieldViolation::UnknownField
+            ),
+            (ex_str("name"), FieldViolation::MinCount(1, 0)),
+        ]
+    );
+
+    let name = ex_str("name");
+    let errors = form
+        .validate(
+            ex("alice"),
+            [
+                (name.as_str(), "A"),
+                (age.as_str(), "1"),
+                (age.as_str(), "2"),
+            ],
+        )
+        .unwrap_err();
+    assert_eq!(
+        errors
+            .into_iter()
+            .map(|e| (e.path, e.violation))
+            .collect::<Vec<_>>(),
+        vec![
+            (name.clone(), FieldViolation::TooShort("A".into(), 2)),
+            (name, FieldViolation::MinCount(1, 0)),
+            (age, FieldViolation::MaxCount(1, 2)),
+        ]
+    );
+    Ok(())
+}
@@ -128,7 +128,7 @@ fn datatype(l: Literal) -> IriRef<MownStr> {
 
 fn language_tag(l: Literal) -> Option<LanguageTag<MownStr>> {
     if let Literal::LanguageTaggedString { language, .. } = l {
-        debug_assert!(LanguageTag::new(language).is_ok());
+        debug_assert!(LanguageTag::new_lenient(language).is_ok());
         Some(LanguageTag::new_unchecked(language.into()))
     } else {
         None
@@ -11,6 +11,7 @@
 //! * [`isomorphism`]
 //! * [`jsonld`] (with the `jsonld` feature enabled)
 //! * [`resource`]
+//! * [`store`]
 //! * [`turtle`]
 //! * [`term`]
 //! * [`xml`] (with the `xml` feature enabled)
@@ -40,6 +41,8 @@ pub use sophia_jsonld as jsonld;
 #[doc(inline)]
 pub use sophia_resource as resource;
 #[doc(inline)]
+pub use sophia_store as store;
+#[doc(inline)]
 pub use sophia_term as term;
 #[doc(inline)]
 pub use sophia_turtle as turtle;
@@ -0,0 +1,284 @@
+//! A file sink for serializers, replacing its target atomically.
+//!
+//! An [`AtomicFile`] writes into a temporary file, in the same directory as its target,
+//! and only renames it to the target path when [committed](AtomicFile::commit).
+//! So if the serialization fails, or if the process is interrupted (e.g. with Ctrl-C),
+//! the target file is never left truncated:
+//! it either keeps its previous content, or gets the complete new content.
+//!
+//! It can be used with any serializer, by passing it a `&mut AtomicFile`.
+//!
+//! # Example
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use sophia_api::serializer::TripleSerializer;
+//! use sophia_store::atomic::AtomicFile;
+//! use sophia_turtle::serializer::nt::NtSerializer;
+//!
+//! let graph = vec![["tag:s", "tag:p", "tag:o"].map(sophia_api::term::IriRef::new_unchecked)];
+//! let path = std::env::temp_dir().join(format!("sophia_atomic_doc_{}.nt", std::process::id()));
+//! let mut file = AtomicFile::create(&path)?;
+//! NtSerializer::new(&mut file).serialize_graph(&graph)?;
+//! file.commit()?;
+//! assert_eq!(std::fs::read_to_string(&path)?, "<tag:s> <tag:p> <tag:o>.\n");
+//! # std::fs::remove_file(&path)?;
+//! # Ok(()) }
+//! ```
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Used to make temporary file names unique within a process.
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// [`AtomicFile`] configuration.
+#[derive(Clone, Debug)]
+pub struct AtomicFileConfig {
+    fsync: bool,
+    fsync_dir: bool,
+}
+
+impl AtomicFileConfig {
+    /// Should the temporary file be synced to disk before being renamed.
+    ///
+    /// If true (default), the new content is guaranteed to be durable
+    /// once [`AtomicFile::commit`] returns.
+    /// Otherwise, a system crash shortly after the commit may leave an empty or truncated file.
+    pub fn fsync(&self) -> bool {
+        self.fsync
+    }
+
+    /// Should the parent directory be synced to disk after the rename.
+    ///
+    /// If true (default), the rename itself is guaranteed to be durable
+    /// once [`AtomicFile::commit`] returns.
+    /// This is only supported on Unix platforms, and ignored on others.
+    pub fn fsync_dir(&self) -> bool {
+        self.fsync_dir
+    }
+
+    /// Build a default config (see individual methods for default values)
+    pub fn new() -> Self {
+        AtomicFileConfig {
+            fsync: true,
+            fsync_dir: true,
+        }
+    }
+
+    /// Transform a config with the given value for [`fsync`](AtomicFileConfig::fsync)
+    pub fn with_fsync(mut self, b: bool) -> Self {
+        self.fsync = b;
+        self
+    }
+
+    /// Transform a config with the given value for [`fsync_dir`](AtomicFileConfig::fsync_dir)
+    pub fn with_fsync_dir(mut self, b: bool) -> Self {
+        self.fsync_dir = b;
+        self
+    }
+}
+
+impl Default for AtomicFileConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A buffered file writer, atomically replacing its target path on [commit](AtomicFile::commit).
+///
+/// If it is dropped without being committed, the temporary file is removed,
+/// and the target path is left untouched.
+#[derive(Debug)]
+pub struct AtomicFile {
+    path: PathBuf,
+    temp_path: PathBuf,
+    file: Option<BufWriter<File>>,
+    config: AtomicFileConfig,
+}
+
+impl AtomicFile {
+    /// Prepare to write into `path`, with the default config.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::create_with_config(path, AtomicFileConfig::default())
+    }
+
+    /// Prepare to write into `path`, with a custom config.
+    pub fn create_with_config<P: AsRef<Path>>(
+        path: P,
+        config: AtomicFileConfig,
+    ) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let Some(name) = path.file_name() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("not a file path: {}", path.display()),
+            ));
+        };
+        let temp_name = format!(
+            ".{}.{}-{}.tmp",
+            name.to_string_lossy(),
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed),
+        );
+        let temp_path = path.with_file_name(temp_name);
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp_path)?;
+        Ok(AtomicFile {
+            path,
+            temp_path,
+            file: Some(BufWriter::new(file)),
+            config,
+        })
+    }
+
+    /// The path that will be replaced on [commit](AtomicFile::commit).
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The path of the temporary file being written.
+    pub fn temp_path(&self) -> &Path {
+        &self.temp_path
+    }
+
+    /// The config of this file.
+    pub fn config(&self) -> &AtomicFileConfig {
+        &self.config
+    }
+
+    /// Flush the written content, and atomically replace the target path with it.
+    ///
+    /// If this fails, the temporary file is removed and the target path is left untouched.
+    pub fn commit(mut self) -> io::Result<()> {
+        let file = self.file.take().expect("only taken by commit or drop");
+        let res = self.finish(file);
+        if res.is_err() {
+            let _ = std::fs::remove_file(&self.temp_path);
+        }
+        res
+    }
+
+    fn finish(&self, file: BufWriter<File>) -> io::Result<()> {
+        let file = file.into_inner().map_err(|err| err.into_error())?;
+        if self.config.fsync {
+            file.sync_all()?;
+        }
+        drop(file);
+        std::fs::rename(&self.temp_path, &self.path)?;
+        if cfg!(unix) && self.config.fsync_dir {
+            let dir = match self.path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    }
+
+    fn writer(&mut self) -> &mut BufWriter<File> {
+        self.file.as_mut().expect("only taken by commit or drop")
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer().write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.writer().write_all(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer().flush()
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        if let Some(file) = self.file.take() {
+            // not committed: discard the content without flushing it
+            let (file, _) = file.into_parts();
+            drop(file);
+            let _ = std::fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::serializer::TripleSerializer;
+    use sophia_api::source::TripleSource;
+    use sophia_api::term::SimpleTerm;
+    use sophia_turtle::serializer::nt::NtSerializer;
+
+    fn tmp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("sophia_atomic_{name}_{}.nt", std::process::id()))
+    }
+
+    #[test]
+    fn commit() -> Result<(), Box<dyn std::error::Error>> {
+        let path = tmp_path("commit");
+        std::fs::write(&path, "old")?;
+        let mut file = AtomicFile::create_with_config(
+            &path,
+            AtomicFileConfig::new()
+                .with_fsync(false)
+                .with_fsync_dir(false),
+        )?;
+        let temp_path = file.temp_path().to_path_buf();
+        assert_eq!(temp_path.parent(), path.parent());
+        let graph: Vec<[SimpleTerm<'static>; 3]> =
+            sophia_turtle::parser::nt::parse_str("<tag:s> <tag:p> \"o\" .").collect_triples()?;
+        NtSerializer::new(&mut file).serialize_graph(&graph)?;
+        file.flush()?;
+        assert_eq!(std::fs::read_to_string(&path)?, "old");
+        file.commit()?;
+        assert_eq!(std::fs::read_to_string(&path)?, "<tag:s> <tag:p> \"o\".\n");
+        assert!(!temp_path.exists());
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn drop_without_commit() -> Result<(), Box<dyn std::error::Error>> {
+        let path = tmp_path("drop");
+        std::fs::write(&path, "old")?;
+        let mut file = AtomicFile::create(&path)?;
+        let temp_path = file.temp_path().to_path_buf();
+        file.write_all(b"partial content")?;
+        file.flush()?;
+        assert!(temp_path.exists());
+        drop(file);
+        assert!(!temp_path.exists());
+        assert_eq!(std::fs::read_to_string(&path)?, "old");
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn unique_temp_paths() -> Result<(), Box<dyn std::error::Error>> {
+        let path = tmp_path("unique");
+        let f1 = AtomicFile::create(&path)?;
+        let f2 = AtomicFile::create(&path)?;
+        assert_ne!(f1.temp_path(), f2.temp_path());
+        f1.commit()?;
+        f2.commit()?; // last commit wins
+        assert_eq!(std::fs::read_to_string(&path)?, "");
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_path() {
+        assert!(AtomicFile::create("/").is_err());
+    }
+}
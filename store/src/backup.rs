@@ -0,0 +1,319 @@
+//! Backup and restore of datasets, as consistent snapshots.
+//!
+//! A snapshot is a directory containing
+//! * [`DATA_FILE`]: the [canonical N-Quads](sophia_c14n::rdfc10) serialization of the dataset,
+//! * [`PREFIXES_FILE`]: a prefix map, as SPARQL-style `PREFIX` directives,
+//! * [`METADATA_FILE`]: some [metadata](BackupInfo) about the snapshot.
+//!
+//! The metadata file is written last, and removed first when a new snapshot is written
+//! in the same directory, so an interrupted backup is never mistaken for a complete one.
+//! As the data is canonical, snapshots of isomorphic datasets are byte-for-byte identical,
+//! whatever the implementation of the dataset they were produced from.
+//!
+//! # Example
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use sophia_api::quad::Spog;
+//! use sophia_api::source::QuadSource;
+//! use sophia_api::term::SimpleTerm;
+//! use sophia_store::backup::{backup, restore, Restored};
+//! use std::collections::BTreeSet;
+//!
+//! type MyDataset = BTreeSet<Spog<SimpleTerm<'static>>>;
+//! let dataset: MyDataset =
+//!     sophia_turtle::parser::nq::parse_str("<tag:a> <tag:p> _:b .\n_:b <tag:p> \"c\" <tag:g> .\n")
+//!         .collect_quads()?;
+//! let dir = std::env::temp_dir().join(format!("sophia_backup_doc_{}", std::process::id()));
+//! let info = backup(&dataset, &[], &dir)?;
+//! assert_eq!(info.quads, 2);
+//!
+//! let restored: Restored<MyDataset> = restore(&dir)?;
+//! assert!(sophia_isomorphism::isomorphic_datasets(&dataset, &restored.dataset)?);
+//! # std::fs::remove_dir_all(&dir)?;
+//! # Ok(()) }
+//! ```
+use rio_turtle::TurtleError;
+use sophia_api::dataset::{CollectibleDataset, SetDataset};
+use sophia_api::parser::QuadParser;
+use sophia_api::prefix::{OrderedPrefixMap, PrefixMapError, PrefixMapPair};
+use sophia_api::source::{SinkError, SourceError};
+use sophia_c14n::C14nError;
+use sophia_turtle::parser::nq::NQuadsParser;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// The name of the file containing the canonical N-Quads, in a snapshot directory.
+pub const DATA_FILE: &str = "dataset.nq";
+/// The name of the file containing the prefix map, in a snapshot directory.
+pub const PREFIXES_FILE: &str = "prefixes.ttl";
+/// The name of the file containing the metadata, in a snapshot directory.
+pub const METADATA_FILE: &str = "metadata.txt";
+
+/// The version of the snapshot format produced by [`backup`].
+const FORMAT: &str = "sophia-backup-1";
+
+/// Metadata about a snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackupInfo {
+    /// The number of quads in the snapshot
+    pub quads: usize,
+    /// When the snapshot was created
+    pub created: SystemTime,
+}
+
+/// The result of [`restore`].
+#[derive(Clone, Debug)]
+pub struct Restored<D> {
+    /// The restored dataset
+    pub dataset: D,
+    /// The restored prefix map
+    pub prefixes: Vec<PrefixMapPair>,
+    /// The metadata of the snapshot
+    pub info: BackupInfo,
+}
+
+/// An error raised by [`backup`] or [`restore`].
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    /// An IO error was encountered while reading or writing the snapshot
+    #[error("IO error: {0}")]
+    IoError(#[from] io::Error),
+    /// One of the files of the snapshot is not valid
+    #[error("Invalid {0} at line {1}: {2}")]
+    InvalidFile(&'static str, usize, String),
+    /// The data file of the snapshot could not be parsed
+    #[error("Can not parse {DATA_FILE}: {0}")]
+    ParseError(TurtleError),
+    /// The content of the snapshot does not match its metadata
+    #[error("Inconsistent snapshot: {0}")]
+    Inconsistent(String),
+    /// An error was raised by the dataset being backed up or restored,
+    /// or during its canonicalization
+    #[error("Dataset error: {0}")]
+    DatasetError(Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+/// Write a snapshot of `dataset`, with the given `prefixes`, in directory `path`
+/// (created if necessary).
+///
+/// Any previous snapshot in that directory is overwritten.
+pub fn backup<D, P>(
+    dataset: &D,
+    prefixes: &[PrefixMapPair],
+    path: P,
+) -> Result<BackupInfo, BackupError>
+where
+    D: SetDataset,
+    P: AsRef<Path>,
+{
+    let dir = path.as_ref();
+    std::fs::create_dir_all(dir)?;
+    match std::fs::remove_file(dir.join(METADATA_FILE)) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+        _ => (),
+    }
+
+    let mut data = LineCounter(BufWriter::new(File::create(dir.join(DATA_FILE))?), 0);
+    sophia_c14n::rdfc10::normalize(dataset, &mut data).map_err(|err| match err {
+        C14nError::Io(err) => BackupError::IoError(err),
+        err => BackupError::DatasetError(Box::new(err)),
+    })?;
+    data.flush()?;
+
+    let mut w = BufWriter::new(File::create(dir.join(PREFIXES_FILE))?);
+    for (prefix, ns) in prefixes {
+        writeln!(w, "PREFIX {}: <{}>", prefix.as_str(), ns.as_str())?;
+    }
+    w.flush()?;
+
+    let info = BackupInfo {
+        quads: data.1,
+        created: SystemTime::now(),
+    };
+    let created = info
+        .created
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let mut w = BufWriter::new(File::create(dir.join(METADATA_FILE))?);
+    writeln!(w, "format {FORMAT}")?;
+    writeln!(w, "quads {}", info.quads)?;
+    writeln!(w, "created {created}")?;
+    w.flush()?;
+    Ok(info)
+}
+
+/// Read the snapshot in directory `path`.
+///
+/// An error is raised if the snapshot is incomplete,
+/// or if the number of quads in the restored dataset does not match the metadata.
+pub fn restore<D, P>(path: P) -> Result<Restored<D>, BackupError>
+where
+    D: CollectibleDataset,
+    P: AsRef<Path>,
+{
+    let dir = path.as_ref();
+    let info = read_metadata(dir)?;
+    let prefixes = read_prefixes(dir)?;
+    let quads = NQuadsParser {}.parse(BufReader::new(File::open(dir.join(DATA_FILE))?));
+    let dataset = D::from_quad_source(quads).map_err(|err| match err {
+        SourceError(err) => BackupError::ParseError(err),
+        SinkError(err) => BackupError::DatasetError(Box::new(err)),
+    })?;
+    let len = dataset.quads().count();
+    if len != info.quads {
+        return Err(BackupError::Inconsistent(format!(
+            "expected {} quads, found {len}",
+            info.quads
+        )));
+    }
+    Ok(Restored {
+        dataset,
+        prefixes,
+        info,
+    })
+}
+
+fn read_metadata(dir: &Path) -> Result<BackupInfo, BackupError> {
+    let txt = std::fs::read_to_string(dir.join(METADATA_FILE))?;
+    let mut quads = None;
+    let mut created = None;
+    for (i, line) in txt.lines().enumerate() {
+        let invalid = |msg: &str| BackupError::InvalidFile(METADATA_FILE, i + 1, msg.to_string());
+        let Some((key, value)) = line.split_once(' ') else {
+            return Err(invalid("expected a key and a value"));
+        };
+        match key {
+            "format" if value == FORMAT => (),
+            "format" => return Err(invalid("unsupported format")),
+            "quads" => quads = Some(value.parse().map_err(|_| invalid("invalid number"))?),
+            "created" => {
+                let secs = value.parse().map_err(|_| invalid("invalid timestamp"))?;
+                created = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+            }
+            _ => (), // ignore unknown keys, for forward compatibility
+        }
+    }
+    match (quads, created) {
+        (Some(quads), Some(created)) => Ok(BackupInfo { quads, created }),
+        _ => Err(BackupError::InvalidFile(
+            METADATA_FILE,
+            0,
+            "missing 'quads' or 'created'".to_string(),
+        )),
+    }
+}
+
+fn read_prefixes(dir: &Path) -> Result<Vec<PrefixMapPair>, BackupError> {
+    let txt = std::fs::read_to_string(dir.join(PREFIXES_FILE))?;
+    match txt.parse::<OrderedPrefixMap>() {
+        Ok(map) => Ok(map.into()),
+        Err(PrefixMapError::Syntax { line, message, .. }) => {
+            Err(BackupError::InvalidFile(PREFIXES_FILE, line, message))
+        }
+        Err(err) => Err(BackupError::InvalidFile(PREFIXES_FILE, 0, err.to_string())),
+    }
+}
+
+/// A writer counting the lines it writes.
+struct LineCounter<W>(W, usize);
+
+impl<W: Write> Write for LineCounter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.0.write(buf)?;
+        self.1 += buf[..n].iter().filter(|b| **b == b'\n').count();
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::prefix::Prefix;
+    use sophia_api::quad::Spog;
+    use sophia_api::source::QuadSource;
+    use sophia_api::term::SimpleTerm;
+    use sophia_iri::Iri;
+    use std::collections::BTreeSet;
+
+    type MyDataset = BTreeSet<Spog<SimpleTerm<'static>>>;
+
+    const NQ: &str = r#"<tag:s> <tag:p> _:x .
+_:x <tag:p> _:y <tag:g> .
+_:y <tag:p> "foo"@en .
+"#;
+
+    fn tmp_dir(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("sophia_backup_{name}_{}", std::process::id()))
+    }
+
+    fn dataset(nq: &str) -> MyDataset {
+        sophia_turtle::parser::nq::parse_str(nq)
+            .collect_quads()
+            .unwrap()
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tmp_dir("round_trip");
+        let d = dataset(NQ);
+        let prefixes = vec![(
+            Prefix::new_unchecked("ex".into()),
+            Iri::new_unchecked("http://example.org/".into()),
+        )];
+        let info = backup(&d, &prefixes, &dir)?;
+        assert_eq!(info.quads, 3);
+
+        let restored: Restored<MyDataset> = restore(&dir)?;
+        assert!(sophia_isomorphism::isomorphic_datasets(
+            &d,
+            &restored.dataset
+        )?);
+        assert_eq!(restored.prefixes, prefixes);
+        assert_eq!(restored.info.quads, 3);
+
+        // snapshots are canonical
+        let data1 = std::fs::read(dir.join(DATA_FILE))?;
+        let renamed = dataset(&NQ.replace("_:x", "_:z"));
+        backup(&renamed, &prefixes, &dir)?;
+        assert_eq!(std::fs::read(dir.join(DATA_FILE))?, data1);
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn inconsistent() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tmp_dir("inconsistent");
+        backup(&dataset(NQ), &[], &dir)?;
+        std::fs::write(dir.join(DATA_FILE), "<tag:s> <tag:p> <tag:o> .\n")?;
+        assert!(matches!(
+            restore::<MyDataset, _>(&dir),
+            Err(BackupError::Inconsistent(_))
+        ));
+
+        std::fs::write(dir.join(PREFIXES_FILE), "PREFIX ex <tag:>\n")?;
+        assert!(matches!(
+            restore::<MyDataset, _>(&dir),
+            Err(BackupError::InvalidFile(PREFIXES_FILE, 1, _))
+        ));
+
+        std::fs::remove_file(dir.join(METADATA_FILE))?;
+        assert!(matches!(
+            restore::<MyDataset, _>(&dir),
+            Err(BackupError::IoError(_))
+        ));
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,620 @@
+//! Load many files at once into a dataset, selecting them with a glob pattern.
+//!
+//! Files are parsed concurrently (see [`LoadConfig::threads`]),
+//! and their syntax is guessed from their extension:
+//! `.nt` (N-Triples), `.nq` (N-Quads), `.ttl` (Turtle) or `.trig` (TriG).
+//! Their `file:` IRI is used as the base IRI.
+//!
+//! Depending on [`LoadConfig::graph_naming`],
+//! triples are loaded in the default graph, or in a graph named after the file they come from.
+//! In both cases, quads that N-Quads or TriG files put in a named graph are kept in that graph.
+//!
+//! A file that can not be read or parsed does not abort the whole process;
+//! the [`LoadSummary`] returned at the end reports the outcome for each file.
+//!
+//! # Example
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use sophia_api::quad::Spog;
+//! use sophia_api::term::SimpleTerm;
+//! use sophia_store::batch::{load_files, GraphNaming, LoadConfig};
+//! use std::collections::BTreeSet;
+//!
+//! let dir = std::env::temp_dir().join(format!("sophia_batch_doc_{}", std::process::id()));
+//! std::fs::create_dir_all(dir.join("sub"))?;
+//! std::fs::write(dir.join("a.ttl"), "<tag:a> <tag:p> <tag:b>.")?;
+//! std::fs::write(dir.join("sub/b.nt"), "<tag:b> <tag:p> <tag:c>.")?;
+//!
+//! let mut dataset = BTreeSet::<Spog<SimpleTerm<'static>>>::new();
+//! let config = LoadConfig::new().with_graph_naming(GraphNaming::FileIri);
+//! let pattern = format!("{}/**/*.{{ttl,nt}}", dir.display());
+//! let summary = load_files(&pattern, &mut dataset, &config)?;
+//! assert_eq!(summary.files.len(), 2);
+//! assert_eq!(summary.quads(), 2);
+//! assert!(summary.is_success());
+//! # std::fs::remove_dir_all(&dir)?;
+//! # Ok(()) }
+//! ```
+use rio_turtle::TurtleError;
+use sophia_api::dataset::MutableDataset;
+use sophia_api::parser::{QuadParser, TripleParser};
+use sophia_api::quad::{Quad, Spog};
+use sophia_api::source::{QuadSource, TripleSource};
+use sophia_api::term::{SimpleTerm, Term};
+use sophia_api::triple::Triple;
+use sophia_iri::Iri;
+use sophia_turtle::parser::{
+    nq::NQuadsParser, nt::NTriplesParser, trig::TriGParser, turtle::TurtleParser,
+};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+/// How triples are assigned to graphs by [`load_files`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GraphNaming {
+    /// Triples go to the default graph
+    /// (quads already in a named graph, in N-Quads or TriG files, are kept in that graph)
+    DefaultGraph,
+    /// Triples go to a graph named after the `file:` IRI of their file
+    /// (quads already in a named graph, in N-Quads or TriG files, are kept in that graph)
+    FileIri,
+}
+
+/// [`load_files`] configuration.
+#[derive(Clone, Debug)]
+pub struct LoadConfig {
+    graph_naming: GraphNaming,
+    threads: usize,
+}
+
+impl LoadConfig {
+    /// How triples are assigned to graphs.
+    ///
+    /// Default is [`GraphNaming::DefaultGraph`].
+    pub fn graph_naming(&self) -> GraphNaming {
+        self.graph_naming
+    }
+
+    /// The maximum number of files parsed concurrently.
+    ///
+    /// Default is the available parallelism of the machine.
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Build a default config (see individual methods for default values)
+    pub fn new() -> Self {
+        LoadConfig {
+            graph_naming: GraphNaming::DefaultGraph,
+            threads: std::thread::available_parallelism().map_or(1, usize::from),
+        }
+    }
+
+    /// Transform a config with the given value for [`graph_naming`](LoadConfig::graph_naming)
+    pub fn with_graph_naming(mut self, graph_naming: GraphNaming) -> Self {
+        self.graph_naming = graph_naming;
+        self
+    }
+
+    /// Transform a config with the given value for [`threads`](LoadConfig::threads)
+    /// (at least one thread is always used).
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+}
+
+impl Default for LoadConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An error preventing [`load_files`] from completing.
+#[derive(Debug, thiserror::Error)]
+pub enum BatchError {
+    /// An IO error was encountered while looking for files
+    #[error("IO error: {0}")]
+    IoError(#[from] io::Error),
+    /// An error was raised by the dataset being loaded
+    #[error("Dataset error: {0}")]
+    DatasetError(Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+/// An error preventing a single file from being loaded by [`load_files`].
+#[derive(Debug, thiserror::Error)]
+pub enum FileError {
+    /// The file could not be read
+    #[error("IO error: {0}")]
+    IoError(#[from] io::Error),
+    /// The file could not be parsed
+    #[error("Parse error: {0}")]
+    ParseError(TurtleError),
+    /// The syntax of the file could not be guessed from its extension
+    #[error("Unsupported file extension")]
+    UnsupportedFormat,
+}
+
+/// The outcome of loading one file, in a [`LoadSummary`].
+#[derive(Debug)]
+pub struct FileReport {
+    /// The path of the file
+    pub path: PathBuf,
+    /// The number of quads inserted in the dataset from this file,
+    /// or the reason why it could not be loaded
+    pub result: Result<usize, FileError>,
+}
+
+/// The outcome of [`load_files`].
+#[derive(Debug, Default)]
+pub struct LoadSummary {
+    /// One report per matched file, in the order of their path
+    pub files: Vec<FileReport>,
+}
+
+impl LoadSummary {
+    /// The total number of quads inserted in the dataset.
+    pub fn quads(&self) -> usize {
+        self.files
+            .iter()
+            .filter_map(|f| f.result.as_ref().ok())
+            .sum()
+    }
+
+    /// The reports of the files that could not be loaded.
+    pub fn failures(&self) -> impl Iterator<Item = &FileReport> + '_ {
+        self.files.iter().filter(|f| f.result.is_err())
+    }
+
+    /// Whether all files were successfully loaded.
+    pub fn is_success(&self) -> bool {
+        self.failures().next().is_none()
+    }
+}
+
+impl fmt::Display for LoadSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let failures = self.failures().count();
+        write!(
+            f,
+            "{} files, {} quads loaded, {} failures",
+            self.files.len(),
+            self.quads(),
+            failures,
+        )?;
+        for report in self.failures() {
+            let err = report.result.as_ref().unwrap_err();
+            write!(f, "\n  {}: {}", report.path.display(), err)?;
+        }
+        Ok(())
+    }
+}
+
+/// Load into `dataset` all the files matching `pattern` (see [`find_files`]).
+///
+/// Files that can not be read or parsed are reported in the returned [`LoadSummary`];
+/// an error is only returned if the files can not be listed,
+/// or if `dataset` fails to insert a quad.
+pub fn load_files<D>(
+    pattern: &str,
+    dataset: &mut D,
+    config: &LoadConfig,
+) -> Result<LoadSummary, BatchError>
+where
+    D: MutableDataset,
+{
+    load_files_with_progress(pattern, dataset, config, |_| ())
+}
+
+/// Same as [`load_files`],
+/// but calls `progress` each time a file has been loaded (or failed to load).
+///
+/// Files are reported in the order in which they are loaded,
+/// which may differ from the order of the final [`LoadSummary`].
+pub fn load_files_with_progress<D, F>(
+    pattern: &str,
+    dataset: &mut D,
+    config: &LoadConfig,
+    mut progress: F,
+) -> Result<LoadSummary, BatchError>
+where
+    D: MutableDataset,
+    F: FnMut(&FileReport),
+{
+    let paths = find_files(pattern)?;
+    let next = AtomicUsize::new(0);
+    let naming = config.graph_naming;
+    let mut reports: Vec<Option<FileReport>> = paths.iter().map(|_| None).collect();
+    std::thread::scope(|scope| -> Result<(), BatchError> {
+        let (tx, rx) = mpsc::sync_channel(config.threads);
+        for _ in 0..config.threads.min(paths.len()) {
+            let tx = tx.clone();
+            let (next, paths) = (&next, &paths);
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= paths.len() || tx.send((i, parse_file(&paths[i], naming))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+        for (i, parsed) in rx {
+            let result = match parsed {
+                Ok(quads) => Ok(dataset
+                    .insert_all(quads.into_iter().map(Ok::<_, std::convert::Infallible>))
+                    .map_err(|err| BatchError::DatasetError(Box::new(err.unwrap_sink_error())))?),
+                Err(err) => Err(err),
+            };
+            let report = FileReport {
+                path: paths[i].clone(),
+                result,
+            };
+            progress(&report);
+            reports[i] = Some(report);
+        }
+        Ok(())
+    })?;
+    Ok(LoadSummary {
+        files: reports.into_iter().flatten().collect(),
+    })
+}
+
+/// Find the files matching `pattern`, sorted by path.
+///
+/// In the pattern, `/` is the path separator, and
+/// * `*` matches any sequence of characters in a file or directory name,
+/// * `?` matches any single character in a file or directory name,
+/// * `{a,b}` matches either `a` or `b` in a file or directory name,
+/// * `**` (as a whole path component) matches any number of nested directories.
+///
+/// As usual, wildcards do not match the leading `.` of hidden files.
+/// If `pattern` is the path of a directory, it is interpreted as `pattern/**/*`.
+pub fn find_files(pattern: &str) -> io::Result<Vec<PathBuf>> {
+    let mut pattern = pattern.to_string();
+    if Path::new(&pattern).is_dir() {
+        pattern = format!("{}/**/*", pattern.trim_end_matches('/'));
+    }
+    let (root, rest) = match pattern.strip_prefix('/') {
+        Some(rest) => (PathBuf::from("/"), rest),
+        None => (PathBuf::new(), &pattern[..]),
+    };
+    let components: Vec<&str> = rest.split('/').filter(|c| !c.is_empty()).collect();
+    let mut found = BTreeSet::new();
+    walk(&root, &components, &mut found)?;
+    Ok(found.into_iter().collect())
+}
+
+fn walk(dir: &Path, components: &[&str], found: &mut BTreeSet<PathBuf>) -> io::Result<()> {
+    let Some((first, rest)) = components.split_first() else {
+        return Ok(());
+    };
+    let read_dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    if *first == "**" {
+        walk(dir, rest, found)?;
+        for entry in std::fs::read_dir(read_dir)? {
+            let entry = entry?;
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if !hidden && entry.file_type()?.is_dir() {
+                walk(&dir.join(entry.file_name()), components, found)?;
+            }
+        }
+    } else if !first.contains(['*', '?', '{']) {
+        let path = dir.join(first);
+        if rest.is_empty() {
+            if path.is_file() {
+                found.insert(path);
+            }
+        } else if path.is_dir() {
+            walk(&path, rest, found)?;
+        }
+    } else {
+        for entry in std::fs::read_dir(read_dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            if !wildcard_match(first, &name.to_string_lossy()) {
+                continue;
+            }
+            let path = dir.join(&name);
+            if rest.is_empty() {
+                if path.is_file() {
+                    found.insert(path);
+                }
+            } else if path.is_dir() {
+                walk(&path, rest, found)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether `name` matches `pattern` (a single path component, see [`find_files`]).
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    if name.starts_with('.') && !pattern.starts_with('.') {
+        return false;
+    }
+    expand_braces(pattern).iter().any(|p| {
+        match_chars(
+            &p.chars().collect::<Vec<_>>(),
+            &name.chars().collect::<Vec<_>>(),
+        )
+    })
+}
+
+/// Expand the (non-nested) `{a,b}` alternatives of `pattern`.
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let Some(start) = pattern.find('{') else {
+        return vec![pattern.to_string()];
+    };
+    let Some(len) = pattern[start..].find('}') else {
+        return vec![pattern.to_string()];
+    };
+    let (prefix, alternatives, suffix) = (
+        &pattern[..start],
+        &pattern[start + 1..start + len],
+        &pattern[start + len + 1..],
+    );
+    alternatives
+        .split(',')
+        .flat_map(|alt| expand_braces(&format!("{prefix}{alt}{suffix}")))
+        .collect()
+}
+
+fn match_chars(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some('*'), _) => {
+            match_chars(&pattern[1..], name)
+                || (!name.is_empty() && match_chars(pattern, &name[1..]))
+        }
+        (Some('?'), Some(_)) => match_chars(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => match_chars(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+/// The `file:` IRI of the given path.
+fn file_iri(path: &Path) -> io::Result<String> {
+    let path = path.canonicalize()?;
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut iri = String::from("file://");
+    if !path.starts_with('/') {
+        iri.push('/');
+    }
+    for b in path.bytes() {
+        if b.is_ascii_alphanumeric() || b"-._~/!$&'()*+,;=:@".contains(&b) {
+            iri.push(b as char);
+        } else {
+            iri.push_str(&format!("%{b:02X}"));
+        }
+    }
+    Ok(iri)
+}
+
+fn parse_file(
+    path: &Path,
+    naming: GraphNaming,
+) -> Result<Vec<Spog<SimpleTerm<'static>>>, FileError> {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+    if !matches!(extension.as_deref(), Some("nt" | "nq" | "ttl" | "trig")) {
+        return Err(FileError::UnsupportedFormat);
+    }
+    let iri = file_iri(path)?;
+    let graph: Option<SimpleTerm<'static>> = match naming {
+        GraphNaming::DefaultGraph => None,
+        GraphNaming::FileIri => Some(Iri::new_unchecked(iri.as_str()).into_term()),
+    };
+    let base = Some(Iri::new_unchecked(iri));
+    let read = BufReader::new(File::open(path)?);
+    let mut quads = vec![];
+    let mut push_triple = |t: [SimpleTerm<'static>; 3]| quads.push((t, graph.clone()));
+    match extension.as_deref() {
+        Some("nt") => NTriplesParser {}
+            .parse(read)
+            .for_each_triple(|t| push_triple(t.spo().map(Term::into_term))),
+        Some("ttl") => TurtleParser { base }
+            .parse(read)
+            .for_each_triple(|t| push_triple(t.spo().map(Term::into_term))),
+        _ => {
+            let mut push_quad = |q: Spog<SimpleTerm<'static>>| {
+                let (spo, g) = q;
+                quads.push((spo, g.or_else(|| graph.clone())));
+            };
+            if extension.as_deref() == Some("nq") {
+                NQuadsParser {}.parse(read).for_each_quad(|q| {
+                    let (spo, g) = q.spog();
+                    push_quad((spo.map(Term::into_term), g.map(Term::into_term)))
+                })
+            } else {
+                TriGParser { base }.parse(read).for_each_quad(|q| {
+                    let (spo, g) = q.spog();
+                    push_quad((spo.map(Term::into_term), g.map(Term::into_term)))
+                })
+            }
+        }
+    }
+    .map_err(FileError::ParseError)?;
+    Ok(quads)
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::dataset::Dataset;
+    use sophia_api::term::IriRef;
+
+    type MyDataset = BTreeSet<Spog<SimpleTerm<'static>>>;
+
+    fn tmp_dir(name: &str) -> io::Result<PathBuf> {
+        let dir = std::env::temp_dir().join(format!("sophia_batch_{name}_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub/subsub"))?;
+        std::fs::create_dir_all(dir.join(".hidden"))?;
+        std::fs::write(dir.join("a.ttl"), "<tag:a> <tag:p> <b>.")?;
+        std::fs::write(
+            dir.join("b.nt"),
+            "<tag:b> <tag:p> <tag:c>.\n<tag:b> <tag:p> <tag:d>.",
+        )?;
+        std::fs::write(dir.join("notes.txt"), "not RDF")?;
+        std::fs::write(
+            dir.join("sub/c.nq"),
+            "<tag:c> <tag:p> <tag:d> <tag:g>.\n<tag:c> <tag:p> <tag:e>.",
+        )?;
+        std::fs::write(
+            dir.join("sub/subsub/d.trig"),
+            "<tag:g> { <tag:d> <tag:p> <tag:e> }",
+        )?;
+        std::fs::write(dir.join("sub/subsub/bad.ttl"), "<tag:d> <tag:p> .")?;
+        std::fs::write(dir.join(".hidden/e.ttl"), "<tag:e> <tag:p> <tag:f>.")?;
+        Ok(dir)
+    }
+
+    fn names(paths: Vec<PathBuf>, dir: &Path) -> Vec<String> {
+        paths
+            .iter()
+            .map(|p| {
+                p.strip_prefix(dir)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(wildcard_match("*.ttl", "a.ttl"));
+        assert!(wildcard_match("*", "a.ttl"));
+        assert!(!wildcard_match("*", ".a.ttl"));
+        assert!(wildcard_match(".*", ".a.ttl"));
+        assert!(wildcard_match("?.t*l", "a.ttl"));
+        assert!(!wildcard_match("?.ttl", "ab.ttl"));
+        assert!(wildcard_match("*.{ttl,nt}", "a.nt"));
+        assert!(!wildcard_match("*.{ttl,nt}", "a.nq"));
+        assert!(wildcard_match("a*b*c", "aXXbYYc"));
+        assert!(!wildcard_match("a*b*c", "aXXbYY"));
+    }
+
+    #[test]
+    fn find() -> io::Result<()> {
+        let dir = tmp_dir("find")?;
+        let d = dir.to_string_lossy().replace('\\', "/");
+        assert_eq!(
+            names(find_files(&format!("{d}/*.ttl"))?, &dir),
+            vec!["a.ttl"]
+        );
+        assert_eq!(
+            names(find_files(&format!("{d}/**/*.ttl"))?, &dir),
+            vec!["a.ttl", "sub/subsub/bad.ttl"]
+        );
+        assert_eq!(
+            names(find_files(&format!("{d}/*/*.{{nq,trig}}"))?, &dir),
+            vec!["sub/c.nq"]
+        );
+        assert_eq!(names(find_files(&format!("{d}/b.nt"))?, &dir), vec!["b.nt"]);
+        assert_eq!(find_files(&format!("{d}/missing/*.nt"))?.len(), 0);
+        assert_eq!(
+            names(find_files(&d)?, &dir),
+            vec![
+                "a.ttl",
+                "b.nt",
+                "notes.txt",
+                "sub/c.nq",
+                "sub/subsub/bad.ttl",
+                "sub/subsub/d.trig"
+            ]
+        );
+        std::fs::remove_dir_all(&dir)
+    }
+
+    #[test]
+    fn load_default_graph() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tmp_dir("default")?;
+        let d = dir.to_string_lossy().replace('\\', "/");
+        let mut dataset = MyDataset::new();
+        let mut progress = 0;
+        let summary = load_files_with_progress(
+            &format!("{d}/**/*"),
+            &mut dataset,
+            &LoadConfig::new().with_threads(2),
+            |_| progress += 1,
+        )?;
+        assert_eq!(progress, 6);
+        assert_eq!(summary.files.len(), 6);
+        assert_eq!(summary.quads(), 6);
+        assert_eq!(dataset.len(), 6);
+        assert_eq!(
+            names(summary.failures().map(|r| r.path.clone()).collect(), &dir),
+            vec!["notes.txt", "sub/subsub/bad.ttl"]
+        );
+        assert!(matches!(
+            summary.files[2].result,
+            Err(FileError::UnsupportedFormat)
+        ));
+        assert!(matches!(
+            summary.files[4].result,
+            Err(FileError::ParseError(_))
+        ));
+        assert!(summary
+            .to_string()
+            .starts_with("6 files, 6 quads loaded, 2 failures\n"));
+        // quads in a named graph are kept in that graph
+        let graph_names: BTreeSet<_> = dataset.iter().map(|q| q.1.clone()).collect();
+        assert_eq!(
+            graph_names,
+            BTreeSet::from([None, Some(Iri::new_unchecked("tag:g").into_term())])
+        );
+
+        // relative IRIs are resolved against the file IRI
+        let b = Iri::new(format!("{}/b", file_iri(&dir)?))?;
+        assert!(Dataset::contains(
+            &dataset,
+            IriRef::new_unchecked("tag:a"),
+            IriRef::new_unchecked("tag:p"),
+            b,
+            None as Option<SimpleTerm>,
+        )?);
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn load_file_graphs() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tmp_dir("file_graphs")?;
+        let d = dir.to_string_lossy().replace('\\', "/");
+        let mut dataset = MyDataset::new();
+        let config = LoadConfig::new().with_graph_naming(GraphNaming::FileIri);
+        let summary = load_files(&format!("{d}/**/*.{{nt,nq,trig}}"), &mut dataset, &config)?;
+        assert!(summary.is_success());
+        assert_eq!(summary.quads(), 5);
+        let graph_names: BTreeSet<_> = dataset.iter().map(|q| q.1.clone()).collect();
+        let file_graph = |name: &str| -> io::Result<Option<SimpleTerm<'static>>> {
+            Ok(Some(
+                Iri::new_unchecked(file_iri(&dir.join(name))?).into_term(),
+            ))
+        };
+        assert_eq!(
+            graph_names,
+            BTreeSet::from([
+                file_graph("b.nt")?,
+                file_graph("sub/c.nq")?,
+                Some(Iri::new_unchecked("tag:g").into_term()),
+            ])
+        );
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
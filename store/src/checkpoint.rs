@@ -0,0 +1,315 @@
+//! Resumable parsing of large [N-Quads] (or [N-Triples]) files.
+//!
+//! Loading a huge N-Quads file can take hours;
+//! if the process is interrupted, restarting from scratch is costly.
+//! [`CheckpointingQuads`] parses a document line by line,
+//! keeping track of the byte offset of the last statement boundary
+//! before which all statements have been yielded.
+//! This [`Checkpoint`] can be saved together with the data loaded so far,
+//! and later used to [resume](CheckpointingQuads::resume) parsing where it stopped.
+//!
+//! # Example
+//! ```
+//! use sophia_api::term::SimpleTerm;
+//! use sophia_api::quad::Spog;
+//! use sophia_store::checkpoint::{Checkpoint, CheckpointingQuads};
+//! use std::io::Cursor;
+//!
+//! let nq = "<tag:s> <tag:p> <tag:o1> .\n<tag:s> <tag:p> <tag:o2> <tag:g> .\n<tag:s> <tag:p> <tag:o3> .\n";
+//! let mut loaded: Vec<Spog<SimpleTerm>> = vec![];
+//!
+//! // load the first two quads, then get "interrupted"
+//! let mut quads = CheckpointingQuads::new(Cursor::new(nq));
+//! let mut saved = Checkpoint::START;
+//! while let Some(res) = quads.next() {
+//!     loaded.push(res?);
+//!     saved = quads.checkpoint(); // to be stored alongside the loaded data
+//!     if loaded.len() == 2 {
+//!         break; // simulate an interruption
+//!     }
+//! }
+//!
+//! // resume from the saved checkpoint
+//! let quads = CheckpointingQuads::resume(Cursor::new(nq), saved)?;
+//! for res in quads {
+//!     loaded.push(res?);
+//! }
+//! assert_eq!(loaded.len(), 3);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [N-Triples]: https://www.w3.org/TR/n-triples/
+//! [N-Quads]: https://www.w3.org/TR/n-quads/
+use sophia_api::quad::Spog;
+use sophia_api::term::{FromTerm, SimpleTerm};
+use sophia_turtle::parser::borrowing::{BorrowingParseError, BorrowingQuads};
+use std::io::{self, BufRead, Seek, SeekFrom};
+
+type ParsedQuad = Result<Spog<SimpleTerm<'static>>, BorrowingParseError>;
+
+/// A position in an N-Quads document, from which parsing can be resumed
+/// (see [`CheckpointingQuads`]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Checkpoint {
+    /// The offset, in bytes, of the first line whose statements have not all been yielded
+    pub offset: u64,
+    /// The (1-based) number of that line, used to report errors
+    pub line: usize,
+}
+
+impl Checkpoint {
+    /// The beginning of a document.
+    pub const START: Checkpoint = Checkpoint { offset: 0, line: 1 };
+}
+
+/// An iterator over the quads of an N-Quads document read from a [`BufRead`],
+/// keeping track of a [`Checkpoint`] from which parsing can be resumed.
+/// It is also a [`QuadSource`](sophia_api::source::QuadSource).
+///
+/// As N-Triples documents are valid N-Quads documents, it can be used to parse them as well.
+///
+/// Each line is parsed independently: after an error, iteration resumes at the next line.
+#[derive(Debug)]
+pub struct CheckpointingQuads<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    pending: std::vec::IntoIter<ParsedQuad>,
+    /// The end of the last line whose statements have all been yielded
+    done: Checkpoint,
+    /// The end of the last line read
+    read: Checkpoint,
+}
+
+impl<R: BufRead> CheckpointingQuads<R> {
+    /// Parse the N-Quads document read from `reader`, from its beginning.
+    pub fn new(reader: R) -> Self {
+        Self::new_at(reader, Checkpoint::START)
+    }
+
+    /// Parse the N-Quads document read from `reader`,
+    /// which is assumed to be already positioned at `checkpoint`.
+    ///
+    /// This is useful for readers that can not [`Seek`] (e.g. a decompressing reader),
+    /// which must then be advanced to `checkpoint.offset` by other means.
+    /// Otherwise, see [`resume`](CheckpointingQuads::resume).
+    pub fn new_at(reader: R, checkpoint: Checkpoint) -> Self {
+        CheckpointingQuads {
+            reader,
+            buffer: vec![],
+            pending: vec![].into_iter(),
+            done: checkpoint,
+            read: checkpoint,
+        }
+    }
+
+    /// Resume parsing the N-Quads document read from `reader` at the given `checkpoint`,
+    /// previously obtained with [`checkpoint`](CheckpointingQuads::checkpoint).
+    pub fn resume(mut reader: R, checkpoint: Checkpoint) -> io::Result<Self>
+    where
+        R: Seek,
+    {
+        reader.seek(SeekFrom::Start(checkpoint.offset))?;
+        Ok(Self::new_at(reader, checkpoint))
+    }
+
+    /// The position from which parsing should be resumed,
+    /// given all the items yielded so far.
+    ///
+    /// NB: in the rare case of a line containing several statements
+    /// (separated by a lone carriage return),
+    /// the checkpoint only moves past that line once all its statements have been yielded.
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.done
+    }
+
+    /// Destructs this [`CheckpointingQuads`], returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: BufRead> Iterator for CheckpointingQuads<R> {
+    type Item = Result<Spog<SimpleTerm<'static>>, CheckpointingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(res) = self.pending.next() {
+                if self.pending.as_slice().is_empty() {
+                    self.done = self.read;
+                }
+                return Some(res.map_err(Into::into));
+            }
+            self.done = self.read;
+            self.buffer.clear();
+            match self.reader.read_until(b'\n', &mut self.buffer) {
+                Err(err) => return Some(Err(err.into())),
+                Ok(0) => return None,
+                Ok(n) => {
+                    self.read.offset += n as u64;
+                    if self.buffer.ends_with(b"\n") {
+                        self.read.line += 1;
+                    }
+                }
+            }
+            let line = self.done.line;
+            self.pending = match std::str::from_utf8(&self.buffer) {
+                Ok(txt) => BorrowingQuads::new(txt)
+                    .map(|res| {
+                        res.map(into_owned)
+                            .map_err(|err| BorrowingParseError { line, ..err })
+                    })
+                    .collect::<Vec<_>>(),
+                Err(err) => vec![Err(BorrowingParseError {
+                    line,
+                    column: err.valid_up_to() + 1,
+                    message: "Invalid UTF-8",
+                })],
+            }
+            .into_iter();
+        }
+    }
+}
+
+/// Copy the text of a quad borrowed from the current line.
+fn into_owned(([s, p, o], g): Spog<SimpleTerm>) -> Spog<SimpleTerm<'static>> {
+    (
+        [s, p, o].map(SimpleTerm::<'static>::from_term),
+        g.map(SimpleTerm::<'static>::from_term),
+    )
+}
+
+/// Error raised by [`CheckpointingQuads`].
+#[derive(Debug, thiserror::Error)]
+pub enum CheckpointingError {
+    /// The document could not be read
+    #[error("IO error: {0}")]
+    IoError(#[from] io::Error),
+    /// A line of the document could not be parsed
+    #[error("Parse error: {0}")]
+    ParseError(#[from] BorrowingParseError),
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::term::{IriRef, Term};
+    use std::io::Cursor;
+
+    const NQ: &str = "<tag:s> <tag:p> <tag:o1> .\n\
+                      # comment\n\
+                      \n\
+                      <tag:s> <tag:p> <tag:o2> <tag:g> .\r\n\
+                      <tag:s> <tag:p> \"é\" .\n\
+                      <tag:s> <tag:p> <tag:o4> .";
+
+    fn objects(quads: &[Spog<SimpleTerm>]) -> Vec<String> {
+        quads
+            .iter()
+            .map(|(spo, _)| match spo[2].iri() {
+                Some(iri) => iri.as_str().to_string(),
+                None => spo[2].lexical_form().unwrap().to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn whole_document() -> Result<(), CheckpointingError> {
+        let mut quads = CheckpointingQuads::new(Cursor::new(NQ));
+        assert_eq!(quads.checkpoint(), Checkpoint::START);
+        let all = quads.by_ref().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(objects(&all), ["tag:o1", "tag:o2", "é", "tag:o4"]);
+        assert!(all[0].1.is_none());
+        assert!(Term::eq(
+            all[1].1.as_ref().unwrap(),
+            IriRef::new_unchecked("tag:g")
+        ));
+        assert_eq!(
+            quads.checkpoint(),
+            Checkpoint {
+                offset: NQ.len() as u64,
+                line: 6
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn checkpoints() {
+        let mut quads = CheckpointingQuads::new(Cursor::new(NQ));
+        let mut offsets = vec![];
+        while let Some(res) = quads.next() {
+            res.unwrap();
+            offsets.push(quads.checkpoint());
+        }
+        let line_start = |i: usize| {
+            NQ.split_inclusive('\n')
+                .take(i)
+                .map(str::len)
+                .sum::<usize>() as u64
+        };
+        assert_eq!(
+            offsets,
+            [
+                Checkpoint {
+                    offset: line_start(1),
+                    line: 2
+                },
+                Checkpoint {
+                    offset: line_start(4),
+                    line: 5
+                },
+                Checkpoint {
+                    offset: line_start(5),
+                    line: 6
+                },
+                Checkpoint {
+                    offset: NQ.len() as u64,
+                    line: 6
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn resume() -> Result<(), CheckpointingError> {
+        for n in 0..=4 {
+            let mut quads = CheckpointingQuads::new(Cursor::new(NQ));
+            let mut loaded = quads.by_ref().take(n).collect::<Result<Vec<_>, _>>()?;
+            let checkpoint = quads.checkpoint();
+            let resumed = CheckpointingQuads::resume(Cursor::new(NQ), checkpoint)?;
+            loaded.extend(resumed.collect::<Result<Vec<_>, _>>()?);
+            assert_eq!(objects(&loaded), ["tag:o1", "tag:o2", "é", "tag:o4"], "{n}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        let nq: &[u8] = b"<tag:s> <tag:p> <tag:o1> .\n\
+                          <tag:s> <tag:p> .\n\
+                          <tag:s> <tag:p> \"\xFF\" .\n\
+                          <tag:s> <tag:p> <tag:o4> .\n";
+        let mut quads = CheckpointingQuads::new(Cursor::new(nq));
+        assert!(quads.next().unwrap().is_ok());
+        let resumed = CheckpointingQuads::resume(Cursor::new(nq), quads.checkpoint()).unwrap();
+        let results: Vec<_> = resumed.collect();
+        assert_eq!(results.len(), 3);
+        match &results[0] {
+            Err(CheckpointingError::ParseError(err)) => assert_eq!(err.line, 2),
+            other => panic!("{other:?}"),
+        }
+        match &results[1] {
+            Err(CheckpointingError::ParseError(err)) => {
+                assert_eq!(err.line, 3);
+                assert_eq!(err.column, 18);
+                assert_eq!(err.message, "Invalid UTF-8");
+            }
+            other => panic!("{other:?}"),
+        }
+        assert!(results[2].is_ok());
+    }
+}
@@ -0,0 +1,19 @@
+//! This crate is part of [Sophia],
+//! an [RDF] and [Linked Data] toolkit in Rust.
+//!
+//! Utilities for storing, replicating and backing up datasets,
+//! based on the N-Quads parser and serializer of [`sophia_turtle`].
+//!
+//! [Sophia]: https://docs.rs/sophia/latest/sophia/
+//! [RDF]: https://www.w3.org/TR/rdf-primer/
+//! [Linked Data]: http://linkeddata.org/
+#![deny(missing_docs)]
+
+pub mod atomic;
+pub mod backup;
+pub mod batch;
+pub mod checkpoint;
+pub mod patch;
+pub mod shard;
+pub mod sync;
+pub mod wal;
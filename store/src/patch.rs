@@ -0,0 +1,500 @@
+//! Change logs in the [RDF Patch] format.
+//!
+//! * [`PatchWriter`] writes RDF Patch rows (additions, deletions and transaction boundaries);
+//! * [`PatchLogDataset`] wraps a [`MutableDataset`],
+//!   and appends every effective mutation of that dataset to a patch log;
+//! * [`replay`] applies a patch log to a dataset,
+//!   possibly stopping after a given number of transactions (point-in-time recovery).
+//!
+//! NB: only the rows produced by [`PatchWriter`] are supported by [`replay`];
+//! in particular, prefix declarations (`PA`, `PD`) are rejected, and headers (`H`) are ignored.
+//!
+//! # Example
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use sophia_api::dataset::MutableDataset;
+//! use sophia_api::quad::Spog;
+//! use sophia_api::term::{IriRef, SimpleTerm};
+//! use sophia_store::patch::{replay, PatchLogDataset};
+//! use std::collections::BTreeSet;
+//!
+//! type MyDataset = BTreeSet<Spog<SimpleTerm<'static>>>;
+//! let (s, p) = (IriRef::new_unchecked("tag:s"), IriRef::new_unchecked("tag:p"));
+//! let mut d = PatchLogDataset::new(MyDataset::new(), Vec::<u8>::new());
+//! d.insert(s, p, "hello", None as Option<SimpleTerm>)?;
+//! d.log_mut().begin()?;
+//! d.insert(s, p, "world", None as Option<SimpleTerm>)?;
+//! d.remove(s, p, "hello", None as Option<SimpleTerm>)?;
+//! d.log_mut().commit()?;
+//! let (original, log) = d.into_inner()?;
+//! assert_eq!(
+//!     std::str::from_utf8(&log)?,
+//!     "A <tag:s> <tag:p> \"hello\" .\nTX .\nA <tag:s> <tag:p> \"world\" .\nD <tag:s> <tag:p> \"hello\" .\nTC .\n",
+//! );
+//!
+//! let mut replica = MyDataset::new();
+//! replay(&log[..], &mut replica, None)?;
+//! assert_eq!(replica, original);
+//! # Ok(()) }
+//! ```
+//!
+//! [RDF Patch]: https://afs.github.io/rdf-patch/
+use sophia_api::dataset::{DResult, Dataset, MdResult, MutableDataset, SetDataset};
+use sophia_api::parser::QuadParser;
+use sophia_api::quad::Spog;
+use sophia_api::source::QuadSource;
+use sophia_api::term::matcher::{GraphNameMatcher, TermMatcher};
+use sophia_api::term::{GraphName, SimpleTerm, Term};
+use sophia_turtle::parser::nq::NQuadsParser;
+use sophia_turtle::serializer::nt::write_term;
+use std::error::Error;
+use std::io::{self, BufRead};
+
+/// An error raised while writing or replaying an RDF Patch log.
+#[derive(Debug, thiserror::Error)]
+pub enum PatchError<E: Error + 'static> {
+    /// An IO error was encountered while reading or writing the log
+    #[error("IO error: {0}")]
+    IoError(#[from] io::Error),
+    /// The log contains an invalid or unsupported row
+    #[error("Syntax error at line {0}: {1}")]
+    SyntaxError(usize, String),
+    /// An error was raised by the underlying dataset
+    #[error("Dataset error: {0}")]
+    DatasetError(E),
+}
+
+/// A writer of [RDF Patch](self) rows.
+///
+/// **Important**:
+/// this writer makes no effort to minimize the number of write operations.
+/// Hence, in most cases, it should be passed a [`BufWriter`](std::io::BufWriter).
+#[derive(Clone, Debug)]
+pub struct PatchWriter<W> {
+    write: W,
+    in_transaction: bool,
+}
+
+impl<W: io::Write> PatchWriter<W> {
+    /// Build a new patch writer writing to `write`.
+    pub fn new(write: W) -> Self {
+        PatchWriter {
+            write,
+            in_transaction: false,
+        }
+    }
+
+    /// Whether a transaction has been [begun](PatchWriter::begin) and not yet ended.
+    pub fn in_transaction(&self) -> bool {
+        self.in_transaction
+    }
+
+    /// Write a transaction start (`TX`).
+    pub fn begin(&mut self) -> io::Result<()> {
+        self.in_transaction = true;
+        self.write.write_all(b"TX .\n")
+    }
+
+    /// Write a transaction commit (`TC`).
+    pub fn commit(&mut self) -> io::Result<()> {
+        self.in_transaction = false;
+        self.write.write_all(b"TC .\n")?;
+        self.write.flush()
+    }
+
+    /// Write a transaction abort (`TA`).
+    ///
+    /// NB: the changes of the aborted transaction will be ignored by [`replay`],
+    /// but this does *not* revert them in any [`PatchLogDataset`] using this writer.
+    pub fn abort(&mut self) -> io::Result<()> {
+        self.in_transaction = false;
+        self.write.write_all(b"TA .\n")?;
+        self.write.flush()
+    }
+
+    /// Write the addition (`A`) of the given quad.
+    pub fn add<TS, TP, TO, TG>(&mut self, s: TS, p: TP, o: TO, g: GraphName<TG>) -> io::Result<()>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        self.write_row(b"A", s, p, o, g)
+    }
+
+    /// Write the deletion (`D`) of the given quad.
+    pub fn delete<TS, TP, TO, TG>(
+        &mut self,
+        s: TS,
+        p: TP,
+        o: TO,
+        g: GraphName<TG>,
+    ) -> io::Result<()>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        self.write_row(b"D", s, p, o, g)
+    }
+
+    /// Borrow the underlying write.
+    pub fn get_ref(&self) -> &W {
+        &self.write
+    }
+
+    /// Flush the underlying write.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.write.flush()
+    }
+
+    /// Flush and return the underlying write.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.write.flush()?;
+        Ok(self.write)
+    }
+
+    fn write_row<TS, TP, TO, TG>(
+        &mut self,
+        op: &[u8],
+        s: TS,
+        p: TP,
+        o: TO,
+        g: GraphName<TG>,
+    ) -> io::Result<()>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        let w = &mut self.write;
+        w.write_all(op)?;
+        w.write_all(b" ")?;
+        write_term(w, s)?;
+        w.write_all(b" ")?;
+        write_term(w, p)?;
+        w.write_all(b" ")?;
+        write_term(w, o)?;
+        if let Some(g) = g {
+            w.write_all(b" ")?;
+            write_term(w, g)?;
+        }
+        w.write_all(b" .\n")?;
+        if !self.in_transaction {
+            w.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// A [`MutableDataset`] wrapper appending every effective mutation to an [RDF Patch](self) log.
+///
+/// Mutations are logged after they have been applied to the underlying dataset,
+/// and only if they actually changed it (according to the boolean returned by
+/// [`insert`](MutableDataset::insert) and [`remove`](MutableDataset::remove)).
+/// If writing to the log fails, an error is returned,
+/// but the underlying dataset has been changed nonetheless.
+///
+/// Changes can be grouped into transactions using [`log_mut`](PatchLogDataset::log_mut).
+#[derive(Clone, Debug)]
+pub struct PatchLogDataset<D, W> {
+    dataset: D,
+    log: PatchWriter<W>,
+}
+
+impl<D, W> PatchLogDataset<D, W>
+where
+    D: MutableDataset,
+    W: io::Write,
+{
+    /// Wrap `dataset`, logging its mutations to `write`.
+    pub fn new(dataset: D, write: W) -> Self {
+        PatchLogDataset {
+            dataset,
+            log: PatchWriter::new(write),
+        }
+    }
+
+    /// Borrow the underlying dataset.
+    pub fn dataset(&self) -> &D {
+        &self.dataset
+    }
+
+    /// Borrow the patch writer, e.g. to start or end transactions.
+    pub fn log_mut(&mut self) -> &mut PatchWriter<W> {
+        &mut self.log
+    }
+
+    /// Return the underlying dataset and the (flushed) underlying write.
+    pub fn into_inner(self) -> io::Result<(D, W)> {
+        Ok((self.dataset, self.log.into_inner()?))
+    }
+}
+
+impl<D, W> Dataset for PatchLogDataset<D, W>
+where
+    D: Dataset,
+{
+    type Quad<'x> = D::Quad<'x> where Self: 'x;
+    type Error = D::Error;
+
+    fn quads(&self) -> impl Iterator<Item = DResult<Self, Self::Quad<'_>>> + '_ {
+        self.dataset.quads()
+    }
+
+    fn quads_matching<'s, S, P, O, G>(
+        &'s self,
+        sm: S,
+        pm: P,
+        om: O,
+        gm: G,
+    ) -> impl Iterator<Item = DResult<Self, Self::Quad<'s>>> + 's
+    where
+        S: TermMatcher + 's,
+        P: TermMatcher + 's,
+        O: TermMatcher + 's,
+        G: GraphNameMatcher + 's,
+    {
+        self.dataset.quads_matching(sm, pm, om, gm)
+    }
+
+    fn contains<TS, TP, TO, TG>(&self, s: TS, p: TP, o: TO, g: GraphName<TG>) -> DResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        self.dataset.contains(s, p, o, g)
+    }
+}
+
+impl<D, W> MutableDataset for PatchLogDataset<D, W>
+where
+    D: MutableDataset,
+    W: io::Write,
+{
+    type MutationError = PatchError<D::MutationError>;
+
+    fn insert<TS, TP, TO, TG>(
+        &mut self,
+        s: TS,
+        p: TP,
+        o: TO,
+        g: GraphName<TG>,
+    ) -> MdResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        let g2 = g.as_ref().map(Term::borrow_term);
+        let changed = self
+            .dataset
+            .insert(s.borrow_term(), p.borrow_term(), o.borrow_term(), g2)
+            .map_err(PatchError::DatasetError)?;
+        if changed {
+            self.log.add(s, p, o, g)?;
+        }
+        Ok(changed)
+    }
+
+    fn remove<TS, TP, TO, TG>(
+        &mut self,
+        s: TS,
+        p: TP,
+        o: TO,
+        g: GraphName<TG>,
+    ) -> MdResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        let g2 = g.as_ref().map(Term::borrow_term);
+        let changed = self
+            .dataset
+            .remove(s.borrow_term(), p.borrow_term(), o.borrow_term(), g2)
+            .map_err(PatchError::DatasetError)?;
+        if changed {
+            self.log.delete(s, p, o, g)?;
+        }
+        Ok(changed)
+    }
+}
+
+impl<D, W> SetDataset for PatchLogDataset<D, W> where D: SetDataset {}
+
+/// Apply the [RDF Patch](self) log read from `read` to `dataset`.
+///
+/// Changes inside a transaction are only applied when the transaction is committed (`TC`);
+/// aborted transactions (`TA`) and unterminated transactions (at the end of the log) are ignored.
+/// Changes outside any transaction are applied immediately.
+///
+/// If `max_transactions` is provided, replay stops after that number of committed transactions
+/// (which allows to restore the state of the dataset at a given point in time).
+///
+/// Return the number of changes (additions and deletions) applied to `dataset`.
+pub fn replay<R, D>(
+    read: R,
+    dataset: &mut D,
+    max_transactions: Option<usize>,
+) -> Result<usize, PatchError<D::MutationError>>
+where
+    R: BufRead,
+    D: MutableDataset,
+{
+    let mut pending: Option<Vec<(bool, Spog<SimpleTerm<'static>>)>> = None;
+    let mut transactions = 0;
+    let mut changes = 0;
+    for (i, line) in read.lines().enumerate() {
+        if max_transactions.is_some_and(|max| transactions >= max) {
+            break;
+        }
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let syntax_error = |msg: String| PatchError::SyntaxError(i + 1, msg);
+        let (op, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        match op {
+            "A" | "D" => {
+                let quads: Vec<Spog<SimpleTerm<'static>>> = NQuadsParser {}
+                    .parse_str(rest)
+                    .collect_quads()
+                    .map_err(|err| syntax_error(err.to_string()))?;
+                let [quad] = <[_; 1]>::try_from(quads)
+                    .map_err(|_| syntax_error("expected exactly one quad".into()))?;
+                match pending.as_mut() {
+                    Some(buffer) => buffer.push((op == "A", quad)),
+                    None => changes += apply(dataset, op == "A", quad)?,
+                }
+            }
+            "TX" => {
+                if pending.is_some() {
+                    return Err(syntax_error("nested transaction".into()));
+                }
+                pending = Some(vec![]);
+            }
+            "TC" => {
+                let Some(buffer) = pending.take() else {
+                    return Err(syntax_error("no transaction to commit".into()));
+                };
+                for (add, quad) in buffer {
+                    changes += apply(dataset, add, quad)?;
+                }
+                transactions += 1;
+            }
+            "TA" => {
+                if pending.take().is_none() {
+                    return Err(syntax_error("no transaction to abort".into()));
+                }
+            }
+            "H" => (),
+            _ => return Err(syntax_error(format!("unsupported row {op:?}"))),
+        }
+    }
+    Ok(changes)
+}
+
+fn apply<D: MutableDataset>(
+    dataset: &mut D,
+    add: bool,
+    quad: Spog<SimpleTerm<'static>>,
+) -> Result<usize, PatchError<D::MutationError>> {
+    let r = if add {
+        dataset.insert_quad(quad)
+    } else {
+        dataset.remove_quad(quad)
+    };
+    r.map_err(PatchError::DatasetError)?;
+    Ok(1)
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::term::IriRef;
+    use std::collections::BTreeSet;
+
+    type MyDataset = BTreeSet<Spog<SimpleTerm<'static>>>;
+
+    const S: IriRef<&str> = IriRef::new_const("tag:s");
+    const P: IriRef<&str> = IriRef::new_const("tag:p");
+    const G: IriRef<&str> = IriRef::new_const("tag:g");
+
+    #[test]
+    fn log_only_effective_changes() -> Result<(), Box<dyn Error>> {
+        let mut d = PatchLogDataset::new(MyDataset::new(), Vec::<u8>::new());
+        assert!(d.insert(S, P, "a", Some(G))?);
+        assert!(!d.insert(S, P, "a", Some(G))?);
+        assert!(!d.remove(S, P, "b", Some(G))?);
+        assert!(d.remove(S, P, "a", Some(G))?);
+        assert_eq!(d.quads().count(), 0);
+        let (_, log) = d.into_inner()?;
+        assert_eq!(
+            String::from_utf8(log)?,
+            "A <tag:s> <tag:p> \"a\" <tag:g> .\nD <tag:s> <tag:p> \"a\" <tag:g> .\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn replay_transactions() -> Result<(), Box<dyn Error>> {
+        let mut d = PatchLogDataset::new(MyDataset::new(), Vec::<u8>::new());
+        let none = None as Option<SimpleTerm>;
+        d.insert(S, P, "1", none.clone())?;
+        d.log_mut().begin()?;
+        d.insert(S, P, "2", none.clone())?;
+        d.log_mut().abort()?;
+        d.log_mut().begin()?;
+        d.insert(S, P, "3", none.clone())?;
+        d.insert(S, P, "4", Some(G))?;
+        d.log_mut().commit()?;
+        d.log_mut().begin()?;
+        d.remove(S, P, "1", none.clone())?;
+        d.log_mut().commit()?;
+        let (_, log) = d.into_inner()?;
+
+        let mut replica = MyDataset::new();
+        assert_eq!(replay(&log[..], &mut replica, None)?, 4);
+        // "2" was aborted
+        assert_eq!(replica.len(), 2);
+        assert!(Dataset::contains(&replica, S, P, "3", none.clone())?);
+        assert!(Dataset::contains(&replica, S, P, "4", Some(G))?);
+
+        // point-in-time: after the first transaction
+        let mut replica = MyDataset::new();
+        assert_eq!(replay(&log[..], &mut replica, Some(1))?, 3);
+        assert_eq!(replica.len(), 3);
+        assert!(Dataset::contains(&replica, S, P, "1", none)?);
+        Ok(())
+    }
+
+    #[test]
+    fn replay_errors() {
+        let mut d = MyDataset::new();
+        for (log, line) in [
+            ("A <tag:s> <tag:p> .\n", 1),
+            ("TX .\nTX .\n", 2),
+            ("TC .\n", 1),
+            ("\nPA \"ex\" <tag:> .\n", 2),
+        ] {
+            match replay(log.as_bytes(), &mut d, None) {
+                Err(PatchError::SyntaxError(l, _)) => assert_eq!(l, line, "{log}"),
+                other => panic!("{log} => {other:?}"),
+            }
+        }
+        assert!(d.is_empty());
+    }
+}
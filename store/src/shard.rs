@@ -0,0 +1,403 @@
+//! Export a (large) dataset as several [N-Quads] shards, and load them back in parallel.
+//!
+//! Quads are dispatched to shards according to the hash of their subject or of their graph name
+//! (see [`ShardKey`]).
+//! A manifest file ([`MANIFEST_FILE`]) is written alongside the shards,
+//! listing them with the number of quads they contain,
+//! so that they can be distributed across several processes or machines.
+//!
+//! # Example
+//! ```
+//! # fn main() -> Result<(), sophia_store::shard::ShardError> {
+//! use sophia_api::quad::Spog;
+//! use sophia_api::source::QuadSource;
+//! use sophia_api::term::SimpleTerm;
+//! use sophia_store::shard::{export_shards, load_shards, Manifest, ShardKey};
+//!
+//! type MyDataset = Vec<Spog<SimpleTerm<'static>>>;
+//! let dataset: MyDataset = sophia_turtle::parser::nq::parse_str(
+//!     "<tag:a> <tag:p> <tag:b> .\n<tag:b> <tag:p> <tag:c> <tag:g> .\n",
+//! )
+//! .collect_quads()
+//! .unwrap();
+//! let dir = std::env::temp_dir().join(format!("sophia_shard_doc_{}", std::process::id()));
+//! let manifest = export_shards(&dataset, &dir, 4, ShardKey::Subject)?;
+//! assert_eq!(manifest.shards.len(), 4);
+//! assert_eq!(manifest, Manifest::read(&dir)?);
+//!
+//! let shards: Vec<MyDataset> = load_shards(&dir)?;
+//! assert_eq!(shards.iter().map(Vec::len).sum::<usize>(), 2);
+//! # std::fs::remove_dir_all(&dir)?;
+//! # Ok(()) }
+//! ```
+//!
+//! [N-Quads]: https://www.w3.org/TR/n-quads/
+use crate::sync::fnv1a;
+use rio_turtle::TurtleError;
+use sophia_api::dataset::{CollectibleDataset, Dataset, MutableDataset};
+use sophia_api::parser::QuadParser;
+use sophia_api::quad::{Quad, Spog};
+use sophia_api::source::{SinkError, SourceError};
+use sophia_api::term::SimpleTerm;
+use sophia_turtle::parser::nq::NQuadsParser;
+use sophia_turtle::serializer::nt::{write_term, write_triple};
+use std::convert::Infallible;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// The name of the manifest file, in the directory containing the shards.
+pub const MANIFEST_FILE: &str = "manifest.txt";
+
+/// The first line of manifest files.
+const MANIFEST_HEADER: &str = "# sophia N-Quads shards";
+
+/// How quads are dispatched to shards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShardKey {
+    /// By hash of the subject: all the quads with the same subject end up in the same shard
+    Subject,
+    /// By hash of the graph name: each graph ends up in a single shard
+    Graph,
+}
+
+/// The description of a set of shards, stored in the [`MANIFEST_FILE`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Manifest {
+    /// How quads were dispatched to shards
+    pub key: ShardKey,
+    /// The shards
+    pub shards: Vec<ShardInfo>,
+}
+
+/// The description of a shard in a [`Manifest`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShardInfo {
+    /// The name of the shard file, relative to the directory of the manifest
+    pub file: String,
+    /// The number of quads in the shard
+    pub quads: usize,
+}
+
+/// An error raised while exporting or loading shards.
+#[derive(Debug, thiserror::Error)]
+pub enum ShardError {
+    /// An IO error was encountered while reading or writing a file
+    #[error("IO error: {0}")]
+    IoError(#[from] io::Error),
+    /// The manifest file is not valid
+    #[error("Invalid manifest at line {0}: {1}")]
+    InvalidManifest(usize, String),
+    /// A shard could not be parsed
+    #[error("Can not parse shard {0:?}: {1}")]
+    ParseError(String, TurtleError),
+    /// An error was raised by the exported dataset or the dataset being loaded
+    #[error("Dataset error: {0}")]
+    DatasetError(Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+impl Manifest {
+    /// The total number of quads in the shards.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|s| s.quads).sum()
+    }
+
+    /// Whether the shards contain no quad at all.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read the manifest in directory `dir`.
+    pub fn read<P: AsRef<Path>>(dir: P) -> Result<Self, ShardError> {
+        let txt = std::fs::read_to_string(dir.as_ref().join(MANIFEST_FILE))?;
+        let mut key = None;
+        let mut shards = vec![];
+        for (i, line) in txt.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |msg: &str| ShardError::InvalidManifest(i + 1, msg.to_string());
+            let (first, second) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| invalid("expected two fields"))?;
+            let second = second.trim_start();
+            if key.is_none() {
+                key = match (first, second) {
+                    ("key", "subject") => Some(ShardKey::Subject),
+                    ("key", "graph") => Some(ShardKey::Graph),
+                    _ => return Err(invalid("expected 'key subject' or 'key graph'")),
+                };
+            } else {
+                let quads = second
+                    .parse()
+                    .map_err(|_| invalid("invalid number of quads"))?;
+                shards.push(ShardInfo {
+                    file: first.to_string(),
+                    quads,
+                });
+            }
+        }
+        let key = key.ok_or_else(|| ShardError::InvalidManifest(0, "missing key".to_string()))?;
+        Ok(Manifest { key, shards })
+    }
+
+    /// Write this manifest in directory `dir`.
+    pub fn write<P: AsRef<Path>>(&self, dir: P) -> Result<(), ShardError> {
+        let mut w = BufWriter::new(File::create(dir.as_ref().join(MANIFEST_FILE))?);
+        writeln!(w, "{MANIFEST_HEADER}")?;
+        let key = match self.key {
+            ShardKey::Subject => "subject",
+            ShardKey::Graph => "graph",
+        };
+        writeln!(w, "key {key}")?;
+        for shard in &self.shards {
+            writeln!(w, "{} {}", shard.file, shard.quads)?;
+        }
+        w.flush()?;
+        Ok(())
+    }
+}
+
+/// The index of the shard (among `n`) that `quad` belongs to, according to `key`.
+///
+/// The key term is hashed with 64-bit FNV-1a, applied to its N-Triples serialization
+/// (the default graph being hashed as an empty string).
+/// This assignment is stable across platforms, Rust versions and releases of this crate,
+/// so that shards can be appended to by any program, on any machine.
+///
+/// # Precondition
+/// `n` must be strictly positive.
+pub fn shard_index<Q: Quad>(quad: &Q, key: ShardKey, n: usize) -> usize {
+    let mut bytes = vec![];
+    match key {
+        ShardKey::Subject => write_term(&mut bytes, quad.s()),
+        ShardKey::Graph => quad.g().map_or(Ok(()), |g| write_term(&mut bytes, g)),
+    }
+    .expect("writing to a Vec can not fail");
+    (fnv1a(0, &bytes) % n as u64) as usize
+}
+
+/// Export `dataset` into `n` N-Quads files in directory `dir` (created if necessary),
+/// together with a [`Manifest`] describing them.
+///
+/// # Precondition
+/// `n` must be strictly positive, otherwise this function will panic.
+pub fn export_shards<D, P>(
+    dataset: &D,
+    dir: P,
+    n: usize,
+    key: ShardKey,
+) -> Result<Manifest, ShardError>
+where
+    D: Dataset,
+    P: AsRef<Path>,
+{
+    assert!(n > 0, "the number of shards must be strictly positive");
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)?;
+    let mut shards: Vec<_> = (0..n)
+        .map(|i| ShardInfo {
+            file: format!("shard-{i:04}.nq"),
+            quads: 0,
+        })
+        .collect();
+    let mut writers = shards
+        .iter()
+        .map(|s| -> io::Result<_> { Ok(BufWriter::new(File::create(dir.join(&s.file))?)) })
+        .collect::<io::Result<Vec<_>>>()?;
+    for quad in dataset.quads() {
+        let quad = quad.map_err(|err| ShardError::DatasetError(Box::new(err)))?;
+        let i = shard_index(&quad, key, n);
+        let w = &mut writers[i];
+        let (tr, gn) = quad.spog();
+        write_triple(w, tr)?;
+        if let Some(g) = gn {
+            w.write_all(b" ")?;
+            write_term(w, g)?;
+        }
+        w.write_all(b" .\n")?;
+        shards[i].quads += 1;
+    }
+    for mut w in writers {
+        w.flush()?;
+    }
+    let manifest = Manifest { key, shards };
+    manifest.write(dir)?;
+    Ok(manifest)
+}
+
+/// Load the shards described by the manifest in directory `dir`,
+/// each in a separate dataset.
+///
+/// Shards are parsed in parallel, one thread per shard.
+pub fn load_shards<D, P>(dir: P) -> Result<Vec<D>, ShardError>
+where
+    D: CollectibleDataset + Send,
+    P: AsRef<Path>,
+{
+    let dir = dir.as_ref();
+    let manifest = Manifest::read(dir)?;
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = manifest
+            .shards
+            .iter()
+            .map(|shard| scope.spawn(move || load_shard::<D>(dir, shard)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("shard loading thread panicked"))
+            .collect()
+    })
+}
+
+/// Load the shards described by the manifest in directory `dir` into `dataset`.
+///
+/// Shards are parsed in parallel (see [`load_shards`]),
+/// then inserted into `dataset`.
+/// The returned value is the one of [`MutableDataset::insert_all`].
+pub fn load_shards_into<D, P>(dir: P, dataset: &mut D) -> Result<usize, ShardError>
+where
+    D: MutableDataset,
+    P: AsRef<Path>,
+{
+    let shards: Vec<Vec<Spog<SimpleTerm<'static>>>> = load_shards(dir)?;
+    let mut count = 0;
+    for shard in shards {
+        count += dataset
+            .insert_all(shard.into_iter().map(Ok::<_, Infallible>))
+            .map_err(|err| ShardError::DatasetError(Box::new(err.unwrap_sink_error())))?;
+    }
+    Ok(count)
+}
+
+fn load_shard<D: CollectibleDataset>(dir: &Path, shard: &ShardInfo) -> Result<D, ShardError> {
+    let file = File::open(dir.join(&shard.file))?;
+    let quads = NQuadsParser {}.parse(BufReader::new(file));
+    D::from_quad_source(quads).map_err(|err| match err {
+        SourceError(err) => ShardError::ParseError(shard.file.clone(), err),
+        SinkError(err) => ShardError::DatasetError(Box::new(err)),
+    })
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::source::QuadSource;
+    use sophia_api::term::{IriRef, Term};
+    use std::collections::BTreeSet;
+
+    type MyDataset = Vec<Spog<SimpleTerm<'static>>>;
+
+    const NQ: &str = r#"<tag:s1> <tag:p> <tag:o1> .
+<tag:s1> <tag:p> "foo"@en <tag:g1> .
+<tag:s2> <tag:p> _:b .
+_:b <tag:p> "42"^^<http://www.w3.org/2001/XMLSchema#integer> <tag:g1> .
+<tag:s3> <tag:p> <tag:o3> <tag:g2> .
+<tag:s1> <tag:q> <tag:o2> <tag:g2> .
+"#;
+
+    fn dataset() -> MyDataset {
+        sophia_turtle::parser::nq::parse_str(NQ)
+            .collect_quads()
+            .unwrap()
+    }
+
+    fn tmp_dir(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("sophia_shard_{name}_{}", std::process::id()))
+    }
+
+    fn sorted(d: MyDataset) -> BTreeSet<Spog<SimpleTerm<'static>>> {
+        d.into_iter().collect()
+    }
+
+    #[test]
+    fn by_subject() -> Result<(), ShardError> {
+        let dir = tmp_dir("subject");
+        let manifest = export_shards(&dataset(), &dir, 3, ShardKey::Subject)?;
+        assert_eq!(manifest.len(), 6);
+        assert_eq!(manifest, Manifest::read(&dir)?);
+
+        let shards: Vec<MyDataset> = load_shards(&dir)?;
+        assert_eq!(shards.len(), 3);
+        for (shard, info) in shards.iter().zip(&manifest.shards) {
+            assert_eq!(shard.len(), info.quads);
+        }
+        // each subject is in exactly one shard
+        for s in ["tag:s1", "tag:s2", "tag:s3"] {
+            let n = shards
+                .iter()
+                .filter(|shard| {
+                    shard
+                        .iter()
+                        .any(|q| q.s().iri().is_some_and(|iri| iri.as_str() == s))
+                })
+                .count();
+            assert_eq!(n, 1);
+        }
+        let merged: MyDataset = shards.into_iter().flatten().collect();
+        assert_eq!(sorted(merged), sorted(dataset()));
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn by_graph() -> Result<(), ShardError> {
+        let dir = tmp_dir("graph");
+        let manifest = export_shards(&dataset(), &dir, 2, ShardKey::Graph)?;
+        assert_eq!(manifest.key, ShardKey::Graph);
+
+        let mut loaded = MyDataset::new();
+        assert_eq!(load_shards_into(&dir, &mut loaded)?, 6);
+        assert_eq!(sorted(loaded), sorted(dataset()));
+
+        let shards: Vec<MyDataset> = load_shards(&dir)?;
+        for g in [None, Some("tag:g1"), Some("tag:g2")] {
+            let n = shards
+                .iter()
+                .filter(|shard| {
+                    shard.iter().any(|q| match (q.g(), g) {
+                        (None, None) => true,
+                        (Some(t), Some(iri)) => t.iri().unwrap().as_str() == iri,
+                        _ => false,
+                    })
+                })
+                .count();
+            assert_eq!(n, 1);
+        }
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn stable_index() {
+        let t = |iri: &str| SimpleTerm::Iri(IriRef::new_unchecked(iri.to_string().into()));
+        let quad = ([t("tag:a"), t("tag:p"), t("tag:o")], Some(t("tag:g")));
+        assert_eq!(shard_index(&quad, ShardKey::Subject, 1000), 834);
+        assert_eq!(shard_index(&quad, ShardKey::Graph, 1000), 812);
+        let quad = (quad.0, None);
+        assert_eq!(shard_index(&quad, ShardKey::Graph, 1000), 37);
+    }
+
+    #[test]
+    fn invalid_manifest() {
+        let dir = tmp_dir("invalid");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(MANIFEST_FILE), "key subject\nshard-0000.nq many\n").unwrap();
+        assert!(matches!(
+            Manifest::read(&dir),
+            Err(ShardError::InvalidManifest(2, _))
+        ));
+        std::fs::write(dir.join(MANIFEST_FILE), "key subject\nshard-0000.nq 1\n").unwrap();
+        std::fs::write(dir.join("shard-0000.nq"), "<tag:s> <tag:p> .\n").unwrap();
+        assert!(matches!(
+            load_shards::<MyDataset, _>(&dir),
+            Err(ShardError::ParseError(..))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,393 @@
+//! Delta-based synchronization of datasets, for limited bandwidth.
+//!
+//! The protocol between a *target* dataset (e.g. an edge cache)
+//! and a *source* dataset (e.g. a central store) goes as follows:
+//! 1. the target computes a compact [`Summary`] of its quads (a [Bloom filter]),
+//!    and sends it ([`Summary::to_bytes`]) to the source;
+//! 2. the source [writes](write_delta) as [N-Quads] the quads that are *not* in the summary,
+//!    and sends them to the target;
+//! 3. the target [applies](apply_delta) them.
+//!
+//! As Bloom filters have false positives,
+//! a quad missing from the target may be wrongly considered as present,
+//! with the probability chosen when building the summary.
+//! Each round uses a different `seed` (i.e. different hash functions),
+//! so repeating the protocol with a new seed divides the probability
+//! of still missing a quad by the same factor.
+//!
+//! Only insertions are propagated: quads removed from the source are not removed from the target.
+//! Blank nodes are compared by their label.
+//!
+//! # Example
+//! ```
+//! # fn main() -> Result<(), sophia_store::sync::SyncError> {
+//! use sophia_api::quad::Spog;
+//! use sophia_api::source::QuadSource;
+//! use sophia_api::term::SimpleTerm;
+//! use sophia_store::sync::{apply_delta, write_delta, Summary};
+//! use std::collections::BTreeSet;
+//!
+//! type MyDataset = BTreeSet<Spog<SimpleTerm<'static>>>;
+//! let central: MyDataset = sophia_turtle::parser::nq::parse_str(
+//!     "<tag:a> <tag:p> <tag:b> .\n<tag:b> <tag:p> <tag:c> <tag:g> .\n",
+//! )
+//! .collect_quads()
+//! .unwrap();
+//! let mut edge: MyDataset = sophia_turtle::parser::nq::parse_str("<tag:a> <tag:p> <tag:b> .\n")
+//!     .collect_quads()
+//!     .unwrap();
+//!
+//! // on the edge
+//! let summary = Summary::new(&edge, 0.01, 0)?.to_bytes();
+//! // on the central store
+//! let mut delta = vec![];
+//! let sent = write_delta(&central, &Summary::from_bytes(&summary)?, &mut delta)?;
+//! assert_eq!(sent, 1);
+//! // back on the edge
+//! apply_delta(&delta[..], &mut edge)?;
+//! assert_eq!(edge, central);
+//! # Ok(()) }
+//! ```
+//!
+//! [Bloom filter]: https://en.wikipedia.org/wiki/Bloom_filter
+//! [N-Quads]: https://www.w3.org/TR/n-quads/
+use rio_turtle::TurtleError;
+use sophia_api::dataset::{Dataset, MutableDataset};
+use sophia_api::parser::QuadParser;
+use sophia_api::quad::Quad;
+use sophia_api::source::StreamError;
+use sophia_turtle::parser::nq::NQuadsParser;
+use sophia_turtle::serializer::nt::{write_term, write_triple};
+use std::io::{self, BufRead, Write};
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Size (in bytes) of the header of the serialized form of a [`Summary`].
+const HEADER_LEN: usize = 8 + 4 + 8;
+
+/// A compact summary of the quads of a dataset, as a Bloom filter.
+///
+/// Membership is based on the N-Quads serialization of quads,
+/// hashed with a stable hash function,
+/// so summaries can be exchanged between different processes and platforms.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Summary {
+    seed: u64,
+    hashes: u32,
+    nbits: u64,
+    bits: Vec<u64>,
+}
+
+/// An error raised during synchronization.
+#[derive(Debug, thiserror::Error)]
+pub enum SyncError {
+    /// An IO error was encountered while writing or reading a delta
+    #[error("IO error: {0}")]
+    IoError(#[from] io::Error),
+    /// A serialized summary is not valid
+    #[error("Invalid summary: {0}")]
+    InvalidSummary(String),
+    /// A delta could not be parsed
+    #[error("Can not parse delta: {0}")]
+    ParseError(TurtleError),
+    /// An error was raised by one of the datasets being synchronized
+    #[error("Dataset error: {0}")]
+    DatasetError(Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+impl Summary {
+    /// Summarize `dataset`, with the given false positive rate
+    /// (i.e. the probability for a quad to be wrongly considered as present).
+    ///
+    /// The `seed` determines the hash functions;
+    /// successive rounds of synchronization should use different seeds.
+    ///
+    /// # Precondition
+    /// `false_positive_rate` must be strictly between 0 and 1, otherwise this function will panic.
+    pub fn new<D: Dataset>(
+        dataset: &D,
+        false_positive_rate: f64,
+        seed: u64,
+    ) -> Result<Self, SyncError> {
+        assert!(
+            false_positive_rate > 0.0 && false_positive_rate < 1.0,
+            "the false positive rate must be strictly between 0 and 1"
+        );
+        let keys = dataset
+            .quads()
+            .map(|q| -> Result<_, SyncError> {
+                Ok(quad_key(
+                    q.map_err(|err| SyncError::DatasetError(Box::new(err)))?,
+                )?)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let ln2 = std::f64::consts::LN_2;
+        let n = keys.len().max(1) as f64;
+        let nbits = (-n * false_positive_rate.ln() / (ln2 * ln2))
+            .ceil()
+            .max(64.0) as u64;
+        let hashes = ((nbits as f64 / n) * ln2).round().clamp(1.0, 32.0) as u32;
+        let mut summary = Summary {
+            seed,
+            hashes,
+            nbits,
+            bits: vec![0; nbits.div_ceil(64) as usize],
+        };
+        for key in &keys {
+            for i in positions(seed, hashes, nbits, key) {
+                summary.bits[(i / 64) as usize] |= 1 << (i % 64);
+            }
+        }
+        Ok(summary)
+    }
+
+    /// The seed of the hash functions of this summary.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The size of this summary, in bits.
+    pub fn nbits(&self) -> u64 {
+        self.nbits
+    }
+
+    /// Whether `quad` is possibly in the summarized dataset.
+    ///
+    /// If `false`, the quad is definitely *not* in the summarized dataset.
+    pub fn maybe_contains<Q: Quad>(&self, quad: Q) -> bool {
+        let key = quad_key(quad).expect("writing to a Vec can not fail");
+        self.contains_key(&key)
+    }
+
+    /// Serialize this summary, to send it to another process.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + 8 * self.bits.len());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.hashes.to_le_bytes());
+        bytes.extend_from_slice(&self.nbits.to_le_bytes());
+        for word in &self.bits {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Deserialize a summary produced by [`Summary::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SyncError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(SyncError::InvalidSummary("truncated header".into()));
+        }
+        let seed = u64::from_le_bytes(bytes[0..8].try_into().unwrap());
+        let hashes = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        let nbits = u64::from_le_bytes(bytes[12..20].try_into().unwrap());
+        let body = &bytes[HEADER_LEN..];
+        if hashes == 0 || nbits == 0 {
+            return Err(SyncError::InvalidSummary("empty filter".into()));
+        }
+        if body.len() as u64 != nbits.div_ceil(64) * 8 {
+            return Err(SyncError::InvalidSummary(format!(
+                "expected {} bits, found {} bytes",
+                nbits,
+                body.len()
+            )));
+        }
+        let bits = body
+            .chunks_exact(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        Ok(Summary {
+            seed,
+            hashes,
+            nbits,
+            bits,
+        })
+    }
+
+    fn contains_key(&self, key: &[u8]) -> bool {
+        self.positions(key)
+            .all(|i| self.bits[(i / 64) as usize] & (1 << (i % 64)) != 0)
+    }
+
+    /// The positions of the bits corresponding to `key`, using double hashing.
+    fn positions(&self, key: &[u8]) -> impl Iterator<Item = u64> {
+        positions(self.seed, self.hashes, self.nbits, key)
+    }
+}
+
+/// The positions of the bits corresponding to `key` in a summary with the given parameters.
+fn positions(seed: u64, hashes: u32, nbits: u64, key: &[u8]) -> impl Iterator<Item = u64> {
+    let h1 = fnv1a(seed, key);
+    let h2 = fnv1a(seed.rotate_left(32) ^ 0x9e37_79b9_7f4a_7c15, key) | 1;
+    (0..hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % nbits)
+}
+
+/// Write as N-Quads into `write` the quads of `dataset` that are not in `summary`.
+///
+/// Return the number of quads written.
+pub fn write_delta<D, W>(dataset: &D, summary: &Summary, mut write: W) -> Result<usize, SyncError>
+where
+    D: Dataset,
+    W: Write,
+{
+    let mut count = 0;
+    for quad in dataset.quads() {
+        let quad = quad.map_err(|err| SyncError::DatasetError(Box::new(err)))?;
+        let key = quad_key(quad)?;
+        if !summary.contains_key(&key) {
+            write.write_all(&key)?;
+            write.write_all(b" .\n")?;
+            count += 1;
+        }
+    }
+    write.flush()?;
+    Ok(count)
+}
+
+/// Insert into `dataset` the quads of a delta produced by [`write_delta`].
+///
+/// The returned value is the one of [`MutableDataset::insert_all`].
+pub fn apply_delta<D, R>(read: R, dataset: &mut D) -> Result<usize, SyncError>
+where
+    D: MutableDataset,
+    R: BufRead,
+{
+    dataset
+        .insert_all(NQuadsParser {}.parse(read))
+        .map_err(|err| match err {
+            StreamError::SourceError(err) => SyncError::ParseError(err),
+            StreamError::SinkError(err) => SyncError::DatasetError(Box::new(err)),
+        })
+}
+
+/// Synchronize `target` with `source` locally,
+/// performing the given number of `rounds` of the protocol,
+/// each with the given false positive rate.
+///
+/// This is mostly useful for testing,
+/// as both datasets would usually live in different processes.
+///
+/// Return the number of quads transferred.
+pub fn sync<S, T>(
+    source: &S,
+    target: &mut T,
+    false_positive_rate: f64,
+    rounds: u64,
+) -> Result<usize, SyncError>
+where
+    S: Dataset,
+    T: MutableDataset,
+{
+    let mut count = 0;
+    for seed in 0..rounds {
+        let summary = Summary::new(&*target, false_positive_rate, seed)?;
+        let mut delta = vec![];
+        count += write_delta(source, &summary, &mut delta)?;
+        apply_delta(&delta[..], target)?;
+    }
+    Ok(count)
+}
+
+/// The N-Quads serialization of `quad`, without the final dot.
+fn quad_key<Q: Quad>(quad: Q) -> io::Result<Vec<u8>> {
+    let mut key = vec![];
+    let (tr, gn) = quad.spog();
+    write_triple(&mut key, tr)?;
+    if let Some(g) = gn {
+        key.push(b' ');
+        write_term(&mut key, g)?;
+    }
+    Ok(key)
+}
+
+/// 64-bit FNV-1a hash of `bytes`, with its offset basis xor-ed with `seed`.
+///
+/// Unlike [`std::collections::hash_map::DefaultHasher`],
+/// it is stable across platforms and Rust versions.
+pub(crate) fn fnv1a(seed: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET ^ seed, |h, b| {
+        (h ^ *b as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::quad::Spog;
+    use sophia_api::source::QuadSource;
+    use sophia_api::term::SimpleTerm;
+    use std::collections::BTreeSet;
+
+    type MyDataset = BTreeSet<Spog<SimpleTerm<'static>>>;
+
+    fn dataset(n: usize, offset: usize) -> MyDataset {
+        let nq: String = (offset..offset + n)
+            .map(|i| format!("<tag:s{i}> <tag:p> \"{i}\" <tag:g{}> .\n", i % 3))
+            .collect();
+        sophia_turtle::parser::nq::parse_str(&nq)
+            .collect_quads()
+            .unwrap()
+    }
+
+    #[test]
+    fn summary() -> Result<(), SyncError> {
+        let d = dataset(100, 0);
+        let summary = Summary::new(&d, 0.01, 42)?;
+        assert_eq!(summary.seed(), 42);
+        assert!(d.quads().all(|q| summary.maybe_contains(q.unwrap())));
+        let other = dataset(1000, 100);
+        let fp = other
+            .quads()
+            .map(|q| summary.maybe_contains(q.unwrap()))
+            .filter(|b| *b)
+            .count();
+        assert!(fp < 50, "too many false positives: {fp}");
+
+        let bytes = summary.to_bytes();
+        assert_eq!(Summary::from_bytes(&bytes)?, summary);
+        assert!(matches!(
+            Summary::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(SyncError::InvalidSummary(_))
+        ));
+        assert!(matches!(
+            Summary::from_bytes(&bytes[..10]),
+            Err(SyncError::InvalidSummary(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn delta() -> Result<(), SyncError> {
+        let source = dataset(200, 0);
+        let mut target = dataset(150, 0);
+        let summary = Summary::new(&target, 0.001, 0)?;
+        let mut delta = vec![];
+        let sent = write_delta(&source, &summary, &mut delta)?;
+        assert!(sent <= 50);
+        assert_eq!(apply_delta(&delta[..], &mut target)?, sent);
+        assert!(target.len() <= 200);
+        Ok(())
+    }
+
+    #[test]
+    fn sync_rounds() -> Result<(), SyncError> {
+        let source = dataset(300, 0);
+        let mut target = dataset(100, 250);
+        // a high false positive rate, compensated by several rounds
+        let sent = sync(&source, &mut target, 0.2, 8)?;
+        assert!(sent <= 250);
+        assert!(source.iter().all(|q| target.contains(q)));
+        assert_eq!(target.len(), 350);
+        Ok(())
+    }
+
+    #[test]
+    fn stable_hash() {
+        // summaries must be portable across processes and platforms
+        assert_eq!(fnv1a(0, b""), FNV_OFFSET);
+        assert_eq!(fnv1a(0, b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
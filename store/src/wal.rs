@@ -0,0 +1,340 @@
+//! Write-ahead logging of dataset mutations.
+//!
+//! [`WalDataset`] wraps a [`MutableDataset`] (typically an in-memory one),
+//! and appends every mutation to a log file *before* applying it to the dataset.
+//! When the log file is [opened](WalDataset::open), its content is first replayed into the dataset,
+//! so that the state of the dataset survives a restart (or a crash) of the application,
+//! without requiring a persistent backend.
+//!
+//! The log is written in the [RDF Patch](crate::patch) format,
+//! so it can also be processed with [`replay`].
+//! As it grows with every mutation, it should be [checkpointed](WalDataset::checkpoint) from time to time.
+//!
+//! # Example
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use sophia_api::dataset::{Dataset, MutableDataset};
+//! use sophia_api::quad::Spog;
+//! use sophia_api::term::{IriRef, SimpleTerm};
+//! use sophia_store::wal::WalDataset;
+//! use std::collections::BTreeSet;
+//!
+//! type MyDataset = BTreeSet<Spog<SimpleTerm<'static>>>;
+//! let path = std::env::temp_dir().join(format!("sophia_wal_doc_{}.rdfp", std::process::id()));
+//! let (s, p) = (IriRef::new_unchecked("tag:s"), IriRef::new_unchecked("tag:p"));
+//!
+//! let mut d = WalDataset::open(&path, MyDataset::new())?;
+//! d.insert(s, p, "hello", None as Option<SimpleTerm>)?;
+//! d.insert(s, p, "world", None as Option<SimpleTerm>)?;
+//! d.remove(s, p, "hello", None as Option<SimpleTerm>)?;
+//! drop(d);
+//!
+//! // later, e.g. after a restart
+//! let d = WalDataset::open(&path, MyDataset::new())?;
+//! assert_eq!(d.quads().count(), 1);
+//! # std::fs::remove_file(&path)?;
+//! # Ok(()) }
+//! ```
+use crate::patch::{replay, PatchError, PatchWriter};
+use sophia_api::dataset::{DResult, Dataset, MdResult, MutableDataset, SetDataset};
+use sophia_api::quad::Quad;
+use sophia_api::term::matcher::{GraphNameMatcher, TermMatcher};
+use sophia_api::term::{GraphName, Term};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+/// A [`MutableDataset`] wrapper logging every mutation to a file before applying it.
+///
+/// Every call to [`insert`](MutableDataset::insert) or [`remove`](MutableDataset::remove)
+/// is logged (whether or not it actually changes the dataset),
+/// and the log is flushed before the mutation is applied.
+/// If the underlying dataset then fails to apply the mutation,
+/// the mutation will nonetheless be attempted again when the log is replayed.
+///
+/// By default, the log is only flushed to the operating system,
+/// which protects against a crash of the application, but not of the whole system;
+/// see [`WalDataset::with_fsync`] for stronger guarantees.
+#[derive(Debug)]
+pub struct WalDataset<D> {
+    dataset: D,
+    path: PathBuf,
+    log: PatchWriter<BufWriter<File>>,
+    fsync: bool,
+}
+
+impl<D: MutableDataset> WalDataset<D> {
+    /// Replay the log file at `path` (if it exists) into `dataset`,
+    /// and wrap `dataset`, logging its subsequent mutations to that file.
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        mut dataset: D,
+    ) -> Result<Self, PatchError<D::MutationError>> {
+        let path = path.as_ref().to_path_buf();
+        match File::open(&path) {
+            Ok(file) => {
+                replay(BufReader::new(file), &mut dataset, None)?;
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+            Err(err) => return Err(err.into()),
+        }
+        let log = open_log(&path)?;
+        Ok(WalDataset {
+            dataset,
+            path,
+            log,
+            fsync: false,
+        })
+    }
+
+    /// Whether every mutation is synchronized to disk before being applied (defaults to `false`).
+    pub fn fsync(&self) -> bool {
+        self.fsync
+    }
+
+    /// Set whether every mutation must be synchronized to disk before being applied
+    /// (see [`File::sync_data`]).
+    ///
+    /// This guarantees that no applied mutation can be lost, even in case of a system crash,
+    /// but makes mutations significantly slower.
+    pub fn with_fsync(mut self, fsync: bool) -> Self {
+        self.fsync = fsync;
+        self
+    }
+
+    /// Borrow the underlying dataset.
+    pub fn dataset(&self) -> &D {
+        &self.dataset
+    }
+
+    /// The path of the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Replace the log file by a log containing only the additions of the quads currently in the dataset,
+    /// so that replaying it is not slower than necessary.
+    ///
+    /// The new log is written to a temporary file, then atomically renamed,
+    /// so an interrupted checkpoint leaves the previous log untouched.
+    ///
+    /// Return the number of quads in the new log.
+    pub fn checkpoint(&mut self) -> io::Result<usize> {
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+        let mut log = PatchWriter::new(BufWriter::new(File::create(&tmp)?));
+        let mut count = 0;
+        log.begin()?;
+        for q in self.dataset.quads() {
+            let q = q.map_err(io::Error::other)?;
+            let ([s, p, o], g) = q.spog();
+            log.add(s, p, o, g)?;
+            count += 1;
+        }
+        log.commit()?;
+        log.into_inner()?.into_inner()?.sync_all()?;
+        std::fs::rename(&tmp, &self.path)?;
+        self.log = open_log(&self.path)?;
+        Ok(count)
+    }
+
+    /// Return the underlying dataset, after flushing the log.
+    pub fn into_inner(self) -> io::Result<D> {
+        self.log.into_inner()?;
+        Ok(self.dataset)
+    }
+
+    fn synchronize(&mut self) -> io::Result<()> {
+        if self.fsync {
+            self.log.get_ref().get_ref().sync_data()?;
+        }
+        Ok(())
+    }
+}
+
+fn open_log(path: &Path) -> io::Result<PatchWriter<BufWriter<File>>> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    Ok(PatchWriter::new(BufWriter::new(file)))
+}
+
+impl<D> Dataset for WalDataset<D>
+where
+    D: Dataset,
+{
+    type Quad<'x> = D::Quad<'x> where Self: 'x;
+    type Error = D::Error;
+
+    fn quads(&self) -> impl Iterator<Item = DResult<Self, Self::Quad<'_>>> + '_ {
+        self.dataset.quads()
+    }
+
+    fn quads_matching<'s, S, P, O, G>(
+        &'s self,
+        sm: S,
+        pm: P,
+        om: O,
+        gm: G,
+    ) -> impl Iterator<Item = DResult<Self, Self::Quad<'s>>> + 's
+    where
+        S: TermMatcher + 's,
+        P: TermMatcher + 's,
+        O: TermMatcher + 's,
+        G: GraphNameMatcher + 's,
+    {
+        self.dataset.quads_matching(sm, pm, om, gm)
+    }
+
+    fn contains<TS, TP, TO, TG>(&self, s: TS, p: TP, o: TO, g: GraphName<TG>) -> DResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        self.dataset.contains(s, p, o, g)
+    }
+}
+
+impl<D> MutableDataset for WalDataset<D>
+where
+    D: MutableDataset,
+{
+    type MutationError = PatchError<D::MutationError>;
+
+    fn insert<TS, TP, TO, TG>(
+        &mut self,
+        s: TS,
+        p: TP,
+        o: TO,
+        g: GraphName<TG>,
+    ) -> MdResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        let g2 = g.as_ref().map(Term::borrow_term);
+        self.log
+            .add(s.borrow_term(), p.borrow_term(), o.borrow_term(), g2)?;
+        self.synchronize()?;
+        self.dataset
+            .insert(s, p, o, g)
+            .map_err(PatchError::DatasetError)
+    }
+
+    fn remove<TS, TP, TO, TG>(
+        &mut self,
+        s: TS,
+        p: TP,
+        o: TO,
+        g: GraphName<TG>,
+    ) -> MdResult<Self, bool>
+    where
+        TS: Term,
+        TP: Term,
+        TO: Term,
+        TG: Term,
+    {
+        let g2 = g.as_ref().map(Term::borrow_term);
+        self.log
+            .delete(s.borrow_term(), p.borrow_term(), o.borrow_term(), g2)?;
+        self.synchronize()?;
+        self.dataset
+            .remove(s, p, o, g)
+            .map_err(PatchError::DatasetError)
+    }
+}
+
+impl<D> SetDataset for WalDataset<D> where D: SetDataset {}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::quad::Spog;
+    use sophia_api::term::{IriRef, SimpleTerm};
+    use std::collections::BTreeSet;
+    use std::error::Error;
+
+    type MyDataset = BTreeSet<Spog<SimpleTerm<'static>>>;
+
+    const S: IriRef<&str> = IriRef::new_const("tag:s");
+    const P: IriRef<&str> = IriRef::new_const("tag:p");
+    const G: IriRef<&str> = IriRef::new_const("tag:g");
+
+    fn tmp_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("sophia_wal_{name}_{}.rdfp", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn log_before_apply() -> Result<(), Box<dyn Error>> {
+        let path = tmp_path("log_before_apply");
+        let mut d = WalDataset::open(&path, MyDataset::new())?.with_fsync(true);
+        assert!(d.fsync());
+        assert!(d.insert(S, P, "a", Some(G))?);
+        assert!(!d.insert(S, P, "a", Some(G))?);
+        assert!(d.remove(S, P, "a", Some(G))?);
+        // the log is flushed after each mutation
+        assert_eq!(
+            std::fs::read_to_string(&path)?,
+            "A <tag:s> <tag:p> \"a\" <tag:g> .\n\
+             A <tag:s> <tag:p> \"a\" <tag:g> .\n\
+             D <tag:s> <tag:p> \"a\" <tag:g> .\n"
+        );
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn replay_on_open() -> Result<(), Box<dyn Error>> {
+        let path = tmp_path("replay_on_open");
+        let none = None as Option<SimpleTerm>;
+        let mut d = WalDataset::open(&path, MyDataset::new())?;
+        d.insert(S, P, "1", none.clone())?;
+        d.insert(S, P, "2", Some(G))?;
+        d.remove(S, P, "1", none.clone())?;
+        let original = d.into_inner()?;
+
+        let mut d = WalDataset::open(&path, MyDataset::new())?;
+        assert_eq!(d.dataset(), &original);
+        d.insert(S, P, "3", none.clone())?;
+        drop(d);
+
+        let d = WalDataset::open(&path, MyDataset::new())?;
+        assert_eq!(d.quads().count(), 2);
+        assert!(d.contains(S, P, "3", none)?);
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn checkpoint() -> Result<(), Box<dyn Error>> {
+        let path = tmp_path("checkpoint");
+        let none = None as Option<SimpleTerm>;
+        let mut d = WalDataset::open(&path, MyDataset::new())?;
+        for i in 0..10 {
+            d.insert(S, P, i.to_string().as_str(), none.clone())?;
+        }
+        for i in 0..8 {
+            d.remove(S, P, i.to_string().as_str(), none.clone())?;
+        }
+        assert_eq!(d.checkpoint()?, 2);
+        assert_eq!(std::fs::read_to_string(&path)?.lines().count(), 4);
+        d.insert(S, P, "10", Some(G))?;
+        let original = d.into_inner()?;
+
+        let d = WalDataset::open(&path, MyDataset::new())?;
+        assert_eq!(d.dataset(), &original);
+        assert_eq!(original.len(), 3);
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
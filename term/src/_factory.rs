@@ -0,0 +1,214 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use sophia_api::term::bnode_id::BnodeIdGenerator;
+use sophia_api::term::{BnodeId, IriRef, LanguageTag, SimpleTerm, Term, VarName};
+
+use crate::{ArcTerm, GenericLiteral};
+
+/// The number of shards used by [`ArcTermFactory::new`].
+const DEFAULT_SHARDS: usize = 16;
+
+/// A thread-safe collection of strings that can be reused for generating [`ArcTerm`]
+/// without reallocating identical strings multiple times,
+/// using the [`copy_term`](ArcTermFactory::copy_term) method.
+///
+/// Contrarily to [`ArcStrStash`](crate::ArcStrStash),
+/// all its methods only require a shared reference,
+/// so a single factory can be used by several threads (e.g. parallel loaders).
+/// Strings are distributed over several shards, each protected by its own lock,
+/// in order to reduce contention.
+#[derive(Debug)]
+pub struct ArcTermFactory {
+    shards: Box<[RwLock<HashSet<Arc<str>>>]>,
+    hasher: RandomState,
+}
+
+impl ArcTermFactory {
+    /// Create a new empty factory
+    pub fn new() -> Self {
+        Self::with_shards(DEFAULT_SHARDS)
+    }
+
+    /// Create a new empty factory, with the given number of shards (at least 1)
+    pub fn with_shards(shards: usize) -> Self {
+        Self {
+            shards: (0..shards.max(1)).map(|_| RwLock::default()).collect(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    fn shard(&self, probe: &str) -> &RwLock<HashSet<Arc<str>>> {
+        let index = self.hasher.hash_one(probe) as usize % self.shards.len();
+        &self.shards[index]
+    }
+
+    /// Retrieve a value from the factory, if present
+    pub fn get(&self, probe: &str) -> Option<Arc<str>> {
+        // a poisoned lock can not leave the set in an inconsistent state
+        self.shard(probe)
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(probe)
+            .cloned()
+    }
+
+    /// Retrieve a value from the factory, inserting it if not present
+    pub fn get_or_insert(&self, probe: &str) -> Arc<str> {
+        if let Some(value) = self.get(probe) {
+            return value;
+        }
+        let mut shard = self
+            .shard(probe)
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        // another thread may have inserted the value in the meantime
+        if let Some(value) = shard.get(probe) {
+            return value.clone();
+        }
+        let value = Arc::<str>::from(probe);
+        shard.insert(value.clone());
+        value
+    }
+
+    /// How many values are stored in this factory
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.read().unwrap_or_else(PoisonError::into_inner).len())
+            .sum()
+    }
+
+    /// Is this factory empty?
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copy any [`Borrow<str>`] into an [`Arc<str>`] backed on this factory.
+    pub fn copy_str<U: Borrow<str>>(&self, txt: U) -> Arc<str> {
+        self.get_or_insert(txt.borrow())
+    }
+
+    /// Copy any [`IriRef`] into an [`IriRef<Arc<str>>`] backed on this factory.
+    pub fn copy_iri<U: Borrow<str>>(&self, iri: IriRef<U>) -> IriRef<Arc<str>> {
+        IriRef::new_unchecked(self.copy_str(iri))
+    }
+
+    /// Copy any [`BnodeId`] into an [`BnodeId<Arc<str>>`] backed on this factory.
+    pub fn copy_bnode_id<U: Borrow<str>>(&self, bnid: BnodeId<U>) -> BnodeId<Arc<str>> {
+        BnodeId::new_unchecked(self.copy_str(bnid))
+    }
+
+    /// Copy any [`LanguageTag`] into an [`LanguageTag<Arc<str>>`] backed on this factory.
+    pub fn copy_language_tag<U: Borrow<str>>(&self, tag: LanguageTag<U>) -> LanguageTag<Arc<str>> {
+        LanguageTag::new_unchecked(self.copy_str(tag))
+    }
+
+    /// Copy any [`VarName`] into an [`VarName<Arc<str>>`] backed on this factory.
+    pub fn copy_var_name<U: Borrow<str>>(&self, vn: VarName<U>) -> VarName<Arc<str>> {
+        VarName::new_unchecked(self.copy_str(vn))
+    }
+
+    /// Copy any [`Term`] into an [`ArcTerm`] backed on this factory.
+    pub fn copy_term<U: Term>(&self, t: U) -> ArcTerm {
+        use SimpleTerm::*;
+        match t.as_simple() {
+            Iri(iri) => ArcTerm::Iri(self.copy_iri(iri)),
+            BlankNode(bnid) => ArcTerm::BlankNode(self.copy_bnode_id(bnid)),
+            LiteralDatatype(lex, dt) => {
+                ArcTerm::Literal(GenericLiteral::Typed(self.copy_str(lex), self.copy_iri(dt)))
+            }
+            LiteralLanguage(lex, tag) => ArcTerm::Literal(GenericLiteral::LanguageString(
+                self.copy_str(lex),
+                self.copy_language_tag(tag),
+            )),
+            Triple(tr) => ArcTerm::Triple(Arc::new(tr.map(|t| self.copy_term(t)))),
+            Variable(vn) => ArcTerm::Variable(self.copy_var_name(vn)),
+        }
+    }
+
+    /// Mint a fresh blank node, whose identifier is provided by `generator`.
+    ///
+    /// NB: fresh identifiers are not stored in this factory,
+    /// as they are not expected to be shared by other terms.
+    pub fn fresh_bnode<G: BnodeIdGenerator>(&self, mut generator: G) -> ArcTerm {
+        ArcTerm::BlankNode(generator.next_bnode_id().map_unchecked(Arc::from))
+    }
+}
+
+impl Default for ArcTermFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::ns::{rdf, xsd};
+
+    #[test]
+    fn copy_term() {
+        let factory = ArcTermFactory::new();
+        assert!(factory.is_empty());
+
+        let t1 = factory.copy_term(xsd::integer);
+        assert!(Term::eq(&t1, xsd::integer));
+        assert_eq!(factory.len(), 1);
+
+        let t2 = factory.copy_term(42);
+        assert!(Term::eq(&t2, 42));
+        assert_eq!(factory.len(), 2); // datatype was already there
+
+        let t3 = factory.copy_term("42");
+        assert!(Term::eq(&t3, "42"));
+        assert_eq!(factory.len(), 3); // lex was already there
+
+        let (ArcTerm::Iri(iri1), ArcTerm::Literal(GenericLiteral::Typed(_, iri2))) = (t1, t2)
+        else {
+            panic!("unexpected term kinds");
+        };
+        assert!(Arc::ptr_eq(&iri1.unwrap(), &iri2.unwrap()));
+    }
+
+    #[test]
+    fn fresh_bnode() {
+        use sophia_api::term::bnode_id::CounterBnodeIdGenerator;
+
+        let factory = ArcTermFactory::new();
+        let mut generator = CounterBnodeIdGenerator::new();
+        let b0 = factory.fresh_bnode(&mut generator);
+        let b1 = factory.fresh_bnode(&mut generator);
+        assert!(Term::eq(&b0, BnodeId::new_unchecked("b0")));
+        assert!(Term::eq(&b1, BnodeId::new_unchecked("b1")));
+        assert!(factory.is_empty());
+    }
+
+    #[test]
+    fn shared_between_threads() {
+        let factory = ArcTermFactory::with_shards(4);
+        let terms: Vec<Vec<ArcTerm>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| {
+                    scope.spawn(|| {
+                        (0..100)
+                            .map(|i| factory.copy_term(i))
+                            .chain([factory.copy_term(rdf::type_)])
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        // 100 lexical forms + xsd:integer + rdf:type
+        assert_eq!(factory.len(), 102);
+        for other in &terms[1..] {
+            for (t1, t2) in terms[0].iter().zip(other) {
+                assert!(Term::eq(t1, t2));
+            }
+        }
+    }
+}
@@ -237,6 +237,40 @@ macro_rules! gen_term {
                 }
             }
 
+            /// Parsed in the N-Triples syntax,
+            /// as the equivalent [`SimpleTerm`](sophia_api::term::SimpleTerm).
+            impl std::str::FromStr for $type_name {
+                type Err = sophia_api::term::TermParseError;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    s.parse::<sophia_api::term::SimpleTerm>()
+                        .map(Term::into_term)
+                }
+            }
+
+            /// Serialized as the equivalent [`SimpleTerm`](sophia_api::term::SimpleTerm).
+            #[cfg(feature = "serde")]
+            impl serde::Serialize for $type_name {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: serde::Serializer,
+                {
+                    serde::Serialize::serialize(&self.as_simple(), serializer)
+                }
+            }
+
+            /// Deserialized as the equivalent [`SimpleTerm`](sophia_api::term::SimpleTerm).
+            #[cfg(feature = "serde")]
+            impl<'de> serde::Deserialize<'de> for $type_name {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    <sophia_api::term::SimpleTerm as serde::Deserialize>::deserialize(deserializer)
+                        .map(Term::into_term)
+                }
+            }
+
             #[cfg(test)]
             mod test {
                 use super::*;
@@ -303,6 +337,32 @@ macro_rules! gen_term {
                     assert_eq!(gt.kind(), TermKind::Variable);
                     assert_eq!(&gt.variable().unwrap(), "x");
                 }
+
+                #[test]
+                fn gen_term_from_str() {
+                    let gt: $type_name = "<< _:b <tag:p> \"chat\"@en >>".parse().unwrap();
+                    assert_consistent_term_impl(&gt);
+                    assert_eq!(gt.kind(), TermKind::Triple);
+                    assert!("<< >>".parse::<$type_name>().is_err());
+                }
+
+                #[cfg(feature = "serde")]
+                #[test]
+                fn gen_term_serde() {
+                    let spo = [rdf::type_, rdf::type_, rdf::Property].map(Term::into_term);
+                    let qt = SimpleTerm::Triple(Box::new(spo));
+                    let terms: Vec<$type_name> = vec![
+                        rdf::type_.into_term(),
+                        42.into_term(),
+                        ("hello" * LanguageTag::new_unchecked("en")).into_term(),
+                        qt.into_term(),
+                    ];
+                    let json = serde_json::to_string(&terms).unwrap();
+                    let simple: Vec<SimpleTerm> = terms.iter().map(Term::as_simple).collect();
+                    assert_eq!(json, serde_json::to_string(&simple).unwrap());
+                    let terms2: Vec<$type_name> = serde_json::from_str(&json).unwrap();
+                    assert_eq!(terms, terms2);
+                }
             }
         }
         pub use $mod_name::$type_name;
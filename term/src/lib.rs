@@ -1,12 +1,17 @@
 //! I define implementations of [`sophia_api::term::Term`]:
 //! * [`ArcTerm`] using [`Arc<str>`](std::sync::Arc) as the underlying text,
 //!   making it cheap to clone and thread-safe;
-//!   see also [`ArcStrStash`].
+//!   see also [`ArcStrStash`] and [`ArcTermFactory`].
 //! * [`RcTerm`] using [`Rc<str>`](std::rc::Rc) as the underlying text,
 //!   making it cheap to clone;
 //!   see also [`RcStrStash`].
+//!
+//! With the `serde` feature enabled, both can be serialized and deserialized
+//! (with the same representation as [`SimpleTerm`](sophia_api::term::SimpleTerm)).
 #![deny(missing_docs)]
 
+mod _factory;
+pub use _factory::*;
 mod _generic;
 pub use _generic::*;
 #[macro_use]
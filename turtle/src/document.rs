@@ -0,0 +1,504 @@
+//! Building blocks for interactive tools (such as language servers) handling Turtle documents.
+//!
+//! [`TurtleDocument`] keeps the text of a document together with its tokens
+//! and the span of each of its statements.
+//! It supports incremental edits,
+//! where only the affected region of the document is re-tokenized,
+//! and statements can be parsed individually.
+//!
+//! # Example
+//! ```
+//! use sophia_turtle::document::{StatementKind, TurtleDocument};
+//!
+//! let mut doc = TurtleDocument::new("PREFIX ex: <http://example.org/>\nex:s ex:p ex:o.\n");
+//! assert_eq!(doc.statements().len(), 2);
+//! assert_eq!(doc.statements()[0].kind, StatementKind::Prefix);
+//!
+//! // replace ex:o by "hello"
+//! let changed = doc.edit(43..47, "\"hello\"");
+//! assert_eq!(changed, 1..2);
+//! let triples = doc.parse_statement(1).unwrap();
+//! assert_eq!(triples.len(), 1);
+//! ```
+use crate::lexer::{Lexer, Token, TokenKind};
+use crate::parser::hints::{repair_hints, RepairHint};
+use crate::parser::turtle::TurtleParser;
+use rio_turtle::TurtleError;
+use sophia_api::parser::TripleParser;
+use sophia_api::source::TripleSource;
+use sophia_api::term::SimpleTerm;
+use std::ops::Range;
+
+/// A Turtle document, designed for interactive edition.
+#[derive(Clone, Debug)]
+pub struct TurtleDocument {
+    text: String,
+    tokens: Vec<(TokenKind, Range<usize>)>,
+    statements: Vec<Statement>,
+}
+
+/// A statement (directive or triples) in a [`TurtleDocument`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Statement {
+    /// The kind of this statement
+    pub kind: StatementKind,
+    /// The span (in bytes) of this statement in the document,
+    /// from the start of its first token to the end of its last token
+    pub span: Range<usize>,
+    /// The indexes of the tokens of this statement (see [`TurtleDocument::tokens`])
+    pub tokens: Range<usize>,
+}
+
+/// The different kinds of [`Statement`]s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StatementKind {
+    /// A prefix declaration (`@prefix` or `PREFIX`)
+    Prefix,
+    /// A base declaration (`@base` or `BASE`)
+    Base,
+    /// A set of triples (or, in TriG, a graph)
+    Triples,
+}
+
+/// A completion item, as returned by [`TurtleDocument::completions`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Completion {
+    /// The kind of this completion
+    pub kind: CompletionKind,
+    /// The text to insert
+    pub label: String,
+}
+
+/// The different kinds of [`Completion`]s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CompletionKind {
+    /// A declared prefix (with its trailing colon)
+    Prefix,
+    /// A prefixed name used elsewhere in the document
+    PrefixedName,
+}
+
+impl TurtleDocument {
+    /// Build a new document with the given text.
+    pub fn new<T: Into<String>>(text: T) -> Self {
+        let text = text.into();
+        let tokens = Lexer::new(&text).map(|t| (t.kind, t.span)).collect();
+        let mut doc = TurtleDocument {
+            text,
+            tokens,
+            statements: vec![],
+        };
+        doc.statements = doc.build_statements();
+        doc
+    }
+
+    /// The text of this document.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The tokens of this document (including whitespace and comments).
+    pub fn tokens(&self) -> impl Iterator<Item = Token<'_>> + '_ {
+        (0..self.tokens.len()).map(|i| self.token(i))
+    }
+
+    /// The `i`-th token of this document.
+    ///
+    /// # Panics
+    /// If `i` is not smaller than the number of tokens.
+    pub fn token(&self, i: usize) -> Token<'_> {
+        let (kind, span) = self.tokens[i].clone();
+        Token {
+            kind,
+            text: &self.text[span.clone()],
+            span,
+        }
+    }
+
+    /// The token containing the given byte `offset`, if any.
+    pub fn token_at(&self, offset: usize) -> Option<Token<'_>> {
+        let i = self.tokens.partition_point(|(_, span)| span.end <= offset);
+        (i < self.tokens.len()).then(|| self.token(i))
+    }
+
+    /// The statements of this document.
+    ///
+    /// NB: if the document ends with an incomplete statement,
+    /// it is included in this list.
+    pub fn statements(&self) -> &[Statement] {
+        &self.statements
+    }
+
+    /// The index of the statement containing the given byte `offset`, if any.
+    pub fn statement_at(&self, offset: usize) -> Option<usize> {
+        let i = self.statements.partition_point(|st| st.span.end <= offset);
+        (i < self.statements.len() && self.statements[i].span.start <= offset).then_some(i)
+    }
+
+    /// Replace the given `range` of the text by `replacement`.
+    ///
+    /// Only the affected region of the document is re-tokenized.
+    /// The returned value is the range of the statements (in the new document)
+    /// that may have changed, and should therefore be re-parsed.
+    ///
+    /// # Panics
+    /// If `range` is not a valid range of the text (with respect to UTF-8 boundaries).
+    pub fn edit(&mut self, range: Range<usize>, replacement: &str) -> Range<usize> {
+        self.text.replace_range(range.clone(), replacement);
+        let new_end = range.start + replacement.len();
+        let shift = |pos: usize| pos + new_end - range.end;
+
+        // re-tokenize from the start of the statement preceding the edit,
+        // as the edit may also change how the end of that statement is tokenized
+        let first = self
+            .statements
+            .iter()
+            .rposition(|st| st.span.start <= range.start)
+            .and_then(|i| i.checked_sub(1))
+            .map(|i| self.statements[i].tokens.start)
+            .unwrap_or(0);
+        let start = self.tokens.get(first).map(|(_, s)| s.start).unwrap_or(0);
+        let old_tokens = self.tokens.split_off(first);
+        let mut old_after = old_tokens
+            .iter()
+            .filter(|(_, span)| span.start >= range.end)
+            .map(|(kind, span)| (*kind, shift(span.start)..shift(span.end)))
+            .peekable();
+        let mut first_diff = None;
+        let mut resync = None;
+        for (j, token) in Lexer::new_at(&self.text, start).enumerate() {
+            let token = (token.kind, token.span);
+            if first_diff.is_none() && old_tokens.get(j) != Some(&token) {
+                first_diff = Some(first + j);
+            }
+            if token.1.start >= new_end {
+                while old_after
+                    .peek()
+                    .is_some_and(|(_, span)| span.start < token.1.start)
+                {
+                    old_after.next();
+                }
+                if old_after.peek() == Some(&token) {
+                    // tokenization is back in sync with the old one
+                    resync = Some(self.tokens.len());
+                    self.tokens.extend(old_after);
+                    break;
+                }
+            }
+            self.tokens.push(token);
+        }
+        let resync = resync.unwrap_or(self.tokens.len());
+        let first_diff = first_diff.unwrap_or(resync);
+
+        self.statements = self.build_statements();
+        let from = self
+            .statements
+            .partition_point(|st| st.tokens.end < first_diff);
+        let to = self
+            .statements
+            .partition_point(|st| st.tokens.start < resync);
+        from..to.max(from)
+    }
+
+    /// Parse the `i`-th statement of this document,
+    /// taking into account the prefix and base declarations preceding it.
+    ///
+    /// Positions in the returned error (if any) are positions in the whole document.
+    ///
+    /// NB: blank nodes are scoped to the statement,
+    /// so the same blank node label in two different statements may be parsed
+    /// as different blank nodes.
+    ///
+    /// # Panics
+    /// If `i` is not smaller than the number of statements.
+    pub fn parse_statement(&self, i: usize) -> Result<Vec<[SimpleTerm<'static>; 3]>, TurtleError> {
+        // blank out everything except the relevant statements,
+        // so that positions are preserved
+        let mut keep = vec![false; self.text.len()];
+        for (j, st) in self.statements.iter().enumerate() {
+            if j == i || (j < i && st.kind != StatementKind::Triples) {
+                keep[st.span.clone()].fill(true);
+            }
+        }
+        let masked: String = self
+            .text
+            .char_indices()
+            .flat_map(|(pos, c)| {
+                let keep_c = keep[pos] || c == '\n';
+                let n = if keep_c { 1 } else { c.len_utf8() };
+                std::iter::repeat_n(if keep_c { c } else { ' ' }, n)
+            })
+            .collect();
+        TurtleParser { base: None }
+            .parse_str(&masked)
+            .collect_triples()
+            .map_err(|err| err.unwrap_source_error())
+    }
+
+    /// The prefixes declared in this document, with their namespace IRI (without angle brackets).
+    pub fn prefixes(&self) -> Vec<(&str, &str)> {
+        self.statements
+            .iter()
+            .filter(|st| st.kind == StatementKind::Prefix)
+            .filter_map(|st| {
+                let mut tokens = st
+                    .tokens
+                    .clone()
+                    .map(|i| self.token(i))
+                    .filter(|t| !t.is_trivia());
+                let _keyword = tokens.next()?;
+                let pname = tokens
+                    .next()
+                    .filter(|t| t.kind == TokenKind::PrefixedName)?;
+                let iri = tokens.next().filter(|t| t.kind == TokenKind::IriRef)?;
+                Some((
+                    pname.text.strip_suffix(':')?,
+                    &iri.text[1..iri.text.len() - 1],
+                ))
+            })
+            .collect()
+    }
+
+    /// The completions available at the given byte `offset`
+    /// (typically, the position of the cursor), sorted and without duplicates.
+    ///
+    /// If the text before `offset` is the beginning of a prefixed name,
+    /// the prefixed names used elsewhere in the document and starting with that text are returned.
+    /// Otherwise, the declared prefixes starting with that text are returned.
+    pub fn completions(&self, offset: usize) -> Vec<Completion> {
+        let current = offset
+            .checked_sub(1)
+            .and_then(|o| self.token_at(o))
+            .filter(|t| matches!(t.kind, TokenKind::PrefixedName | TokenKind::Error));
+        let partial = current
+            .as_ref()
+            .map(|t| &self.text[t.span.start..offset])
+            .unwrap_or("");
+        let mut ret: Vec<_> = if partial.contains(':') {
+            self.tokens()
+                .filter(|t| {
+                    t.kind == TokenKind::PrefixedName
+                        && Some(t) != current.as_ref()
+                        && !t.text.ends_with(':')
+                        && t.text.starts_with(partial)
+                })
+                .map(|t| Completion {
+                    kind: CompletionKind::PrefixedName,
+                    label: t.text.to_string(),
+                })
+                .collect()
+        } else {
+            self.prefixes()
+                .into_iter()
+                .filter(|(prefix, _)| prefix.starts_with(partial))
+                .map(|(prefix, _)| Completion {
+                    kind: CompletionKind::Prefix,
+                    label: format!("{prefix}:"),
+                })
+                .collect()
+        };
+        ret.sort();
+        ret.dedup();
+        ret
+    }
+
+    /// The [`RepairHint`]s for this document (see [`repair_hints`]).
+    pub fn hints(&self) -> Vec<RepairHint> {
+        repair_hints(&self.text)
+    }
+
+    fn build_statements(&self) -> Vec<Statement> {
+        let mut statements = vec![];
+        let mut current: Option<(StatementKind, usize)> = None;
+        let mut depth = 0_usize;
+        let mut last = 0;
+        for i in 0..self.tokens.len() {
+            let token = self.token(i);
+            if token.is_trivia() {
+                continue;
+            }
+            let (kind, first) = *current.get_or_insert((statement_kind(&token), i));
+            last = i;
+            let end = match (token.kind, token.text) {
+                (TokenKind::Punctuation, "[" | "(" | "<<" | "{|" | "{") => {
+                    depth += 1;
+                    false
+                }
+                (TokenKind::Punctuation, "]" | ")" | ">>" | "|}" | "}") => {
+                    depth = depth.saturating_sub(1);
+                    depth == 0 && token.text == "}"
+                }
+                (TokenKind::Punctuation, ".") => depth == 0,
+                // SPARQL-style directives are not terminated by a dot
+                (TokenKind::IriRef, _) => {
+                    kind != StatementKind::Triples && !self.token(first).text.starts_with('@')
+                }
+                _ => false,
+            };
+            if end {
+                statements.push(self.make_statement(kind, first, i));
+                current = None;
+                depth = 0;
+            }
+        }
+        if let Some((kind, first)) = current {
+            statements.push(self.make_statement(kind, first, last));
+        }
+        statements
+    }
+
+    fn make_statement(&self, kind: StatementKind, first: usize, last: usize) -> Statement {
+        Statement {
+            kind,
+            span: self.tokens[first].1.start..self.tokens[last].1.end,
+            tokens: first..last + 1,
+        }
+    }
+}
+
+pub(crate) fn statement_kind(first_token: &Token) -> StatementKind {
+    if first_token.kind != TokenKind::Keyword {
+        StatementKind::Triples
+    } else if first_token.text == "@prefix" || first_token.text.eq_ignore_ascii_case("prefix") {
+        StatementKind::Prefix
+    } else if first_token.text == "@base" || first_token.text.eq_ignore_ascii_case("base") {
+        StatementKind::Base
+    } else {
+        StatementKind::Triples
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rio_api::parser::ParseError;
+
+    const TTL: &str = r#"@prefix ex: <http://example.org/> .
+PREFIX foaf: <http://xmlns.com/foaf/0.1/>
+ex:alice a foaf:Person ;
+    foaf:name "Alice" ;
+    foaf:knows [ foaf:name "Bob" ] .
+
+ex:bob foaf:knows ex:alice
+"#;
+
+    fn kinds(doc: &TurtleDocument) -> Vec<StatementKind> {
+        doc.statements().iter().map(|st| st.kind).collect()
+    }
+
+    #[test]
+    fn statements() {
+        use StatementKind::*;
+        let doc = TurtleDocument::new(TTL);
+        assert_eq!(kinds(&doc), vec![Prefix, Prefix, Triples, Triples]);
+        let texts: Vec<_> = doc
+            .statements()
+            .iter()
+            .map(|st| &TTL[st.span.clone()])
+            .collect();
+        assert_eq!(texts[0], "@prefix ex: <http://example.org/> .");
+        assert_eq!(texts[1], "PREFIX foaf: <http://xmlns.com/foaf/0.1/>");
+        assert!(texts[2].starts_with("ex:alice") && texts[2].ends_with("] ."));
+        assert_eq!(texts[3], "ex:bob foaf:knows ex:alice");
+        assert_eq!(doc.statement_at(TTL.find("Bob").unwrap()), Some(2));
+        assert_eq!(doc.statement_at(0), Some(0));
+        assert_eq!(doc.statement_at(TTL.len()), None);
+    }
+
+    #[test]
+    fn tokens() {
+        let doc = TurtleDocument::new(TTL);
+        let rebuilt: String = doc.tokens().map(|t| t.text).collect();
+        assert_eq!(rebuilt, TTL);
+        let token = doc.token_at(TTL.find("Alice").unwrap()).unwrap();
+        assert_eq!(token.kind, TokenKind::String);
+        assert_eq!(token.text, "\"Alice\"");
+    }
+
+    #[test]
+    fn parse_statement() {
+        let doc = TurtleDocument::new(TTL);
+        assert_eq!(doc.parse_statement(0).unwrap().len(), 0);
+        assert_eq!(doc.parse_statement(2).unwrap().len(), 4);
+        let err = doc.parse_statement(3).unwrap_err();
+        // positions are preserved
+        assert!(err.textual_position().unwrap().line_number() >= 6);
+    }
+
+    #[test]
+    fn edit() {
+        let mut doc = TurtleDocument::new(TTL);
+        let pos = TTL.find("\"Bob\"").unwrap();
+        let changed = doc.edit(pos..pos + 5, "\"Robert\" ; foaf:age 42");
+        assert_eq!(changed, 2..3);
+        assert_eq!(
+            doc.text(),
+            TTL.replace("\"Bob\"", "\"Robert\" ; foaf:age 42")
+        );
+        assert_eq!(doc.parse_statement(2).unwrap().len(), 5);
+        let fresh = TurtleDocument::new(doc.text());
+        assert_eq!(doc.tokens, fresh.tokens);
+        assert_eq!(doc.statements, fresh.statements);
+
+        // adding the missing dot
+        let changed = doc.edit(doc.text().len() - 1..doc.text().len() - 1, " .");
+        assert_eq!(changed, 3..4);
+        assert_eq!(doc.parse_statement(3).unwrap().len(), 1);
+
+        // splitting a statement in two
+        let pos = doc.text().find(" ;\n    foaf:name").unwrap();
+        let changed = doc.edit(pos..pos + 2, " .\nex:alice");
+        assert_eq!(changed, 2..4);
+        let fresh = TurtleDocument::new(doc.text());
+        assert_eq!(doc.tokens, fresh.tokens);
+        assert_eq!(doc.statements, fresh.statements);
+        assert_eq!(kinds(&doc).len(), 5);
+    }
+
+    #[test]
+    fn edit_in_string() {
+        let mut doc = TurtleDocument::new(TTL);
+        // opening a string changes the tokenization of the rest of the line
+        let pos = TTL.find("foaf:Person").unwrap();
+        doc.edit(pos..pos, "\"");
+        let fresh = TurtleDocument::new(doc.text());
+        assert_eq!(doc.tokens, fresh.tokens);
+        assert_eq!(doc.statements, fresh.statements);
+    }
+
+    #[test]
+    fn prefixes() {
+        let doc = TurtleDocument::new(TTL);
+        assert_eq!(
+            doc.prefixes(),
+            vec![
+                ("ex", "http://example.org/"),
+                ("foaf", "http://xmlns.com/foaf/0.1/")
+            ]
+        );
+    }
+
+    #[test]
+    fn completions() {
+        let ttl = format!("{TTL}ex:carol foaf:kn");
+        let doc = TurtleDocument::new(ttl.as_str());
+        let labels = |offset| -> Vec<_> {
+            doc.completions(offset)
+                .into_iter()
+                .map(|c| c.label)
+                .collect()
+        };
+        assert_eq!(labels(ttl.len()), vec!["foaf:knows"]);
+        assert_eq!(
+            labels(ttl.len() - 2),
+            vec!["foaf:Person", "foaf:knows", "foaf:name"]
+        );
+        assert_eq!(labels(ttl.len() - 5), vec!["foaf:"]);
+        assert_eq!(labels(ttl.len() - 6), vec!["foaf:"]);
+        assert_eq!(labels(ttl.len() - 7), vec!["ex:", "foaf:"]);
+    }
+}
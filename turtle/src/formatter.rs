@@ -0,0 +1,464 @@
+//! A formatter for Turtle and TriG documents, in the spirit of `rustfmt`.
+//!
+//! Unlike the [serializers](crate::serializer), which re-emit a graph from scratch,
+//! the formatter works on the [tokens](crate::lexer) of the original document.
+//! Comments, directives (and their order), prefixed names
+//! and the lexical form of literals are therefore preserved;
+//! only the layout and the style of directives are changed,
+//! according to a [`FormatConfig`].
+//!
+//! # Example
+//! ```
+//! use sophia_turtle::formatter::{format_turtle, FormatConfig};
+//!
+//! let ttl = "@prefix ex: <http://example.org/>.\nex:alice ex:knows ex:bob,ex:carol;ex:name \"Alice\" . # me";
+//! let formatted = format_turtle(ttl, &FormatConfig::new()).unwrap();
+//! assert_eq!(
+//!     formatted,
+//!     r#"@prefix ex: <http://example.org/> .
+//!
+//! ex:alice ex:knows ex:bob, ex:carol ;
+//!   ex:name "Alice" . # me
+//! "#
+//! );
+//! ```
+use crate::document::{statement_kind, StatementKind};
+use crate::lexer::{Lexer, Token, TokenKind};
+use crate::parser::{trig::TriGParser, turtle::TurtleParser};
+use rio_turtle::TurtleError;
+use sophia_api::parser::{QuadParser, TripleParser};
+use sophia_api::source::{QuadSource, TripleSource};
+use sophia_iri::Iri;
+use std::io;
+use std::path::Path;
+
+/// Formatter configuration.
+#[derive(Clone, Debug)]
+pub struct FormatConfig {
+    indentation: String,
+    directives: DirectiveStyle,
+    blank_line_after_directives: bool,
+}
+
+/// The style to use for `prefix` and `base` directives.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DirectiveStyle {
+    /// Keep the style of each directive
+    #[default]
+    Keep,
+    /// Use Turtle-style directives (`@prefix`, `@base`)
+    Turtle,
+    /// Use SPARQL-style directives (`PREFIX`, `BASE`)
+    Sparql,
+}
+
+impl FormatConfig {
+    /// Indentation used for the continuation lines of statements,
+    /// and for the content of graphs (in TriG).
+    /// (defaults to `"  "`)
+    pub fn indentation(&self) -> &str {
+        &self.indentation
+    }
+
+    /// The style used for directives.
+    /// (defaults to [`DirectiveStyle::Keep`])
+    pub fn directives(&self) -> DirectiveStyle {
+        self.directives
+    }
+
+    /// Whether an empty line is inserted after the directives at the start of the document
+    /// (or of any group of consecutive directives).
+    /// (defaults to `true`)
+    ///
+    /// Other empty lines of the original document are preserved
+    /// (although several consecutive empty lines are collapsed into one).
+    pub fn blank_line_after_directives(&self) -> bool {
+        self.blank_line_after_directives
+    }
+
+    /// Build a new default [`FormatConfig`].
+    pub fn new() -> Self {
+        FormatConfig {
+            indentation: "  ".to_string(),
+            directives: DirectiveStyle::Keep,
+            blank_line_after_directives: true,
+        }
+    }
+
+    /// Transform a [`FormatConfig`] by setting its [`indentation`][`FormatConfig::indentation`].
+    ///
+    /// # Precondition
+    /// `indentation` must only contain ASCII whitespaces, otherwise this method will panic.
+    pub fn with_indentation<T: ToString>(mut self, indentation: T) -> Self {
+        let indentation = indentation.to_string();
+        assert!(indentation.chars().all(|c| c.is_ascii_whitespace()));
+        self.indentation = indentation;
+        self
+    }
+
+    /// Transform a [`FormatConfig`] by setting its [`directives`][`FormatConfig::directives`] style.
+    pub fn with_directives(mut self, style: DirectiveStyle) -> Self {
+        self.directives = style;
+        self
+    }
+
+    /// Transform a [`FormatConfig`] by setting its
+    /// [`blank_line_after_directives`][`FormatConfig::blank_line_after_directives`] flag.
+    pub fn with_blank_line_after_directives(mut self, b: bool) -> Self {
+        self.blank_line_after_directives = b;
+        self
+    }
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        FormatConfig::new()
+    }
+}
+
+/// Reformat the given Turtle document.
+///
+/// The document is parsed first, and an error is returned if it is not valid.
+pub fn format_turtle(ttl: &str, config: &FormatConfig) -> Result<String, TurtleError> {
+    TurtleParser {
+        base: Some(dummy_base()),
+    }
+    .parse_str(ttl)
+    .for_each_triple(|_| ())?;
+    Ok(format_tokens(ttl, config))
+}
+
+/// Reformat the given TriG document.
+///
+/// The document is parsed first, and an error is returned if it is not valid.
+pub fn format_trig(trig: &str, config: &FormatConfig) -> Result<String, TurtleError> {
+    TriGParser {
+        base: Some(dummy_base()),
+    }
+    .parse_str(trig)
+    .for_each_quad(|_| ())?;
+    Ok(format_tokens(trig, config))
+}
+
+/// Reformat the given file in place.
+///
+/// The file is parsed as TriG if its extension is `.trig`, as Turtle otherwise.
+/// It is only written if its formatted content differs from the original one.
+/// The returned boolean indicates whether the file was changed.
+///
+/// Syntax errors are reported as [`io::Error`]s of kind [`io::ErrorKind::InvalidData`].
+pub fn format_file<P: AsRef<Path>>(path: P, config: &FormatConfig) -> io::Result<bool> {
+    let path = path.as_ref();
+    let original = std::fs::read_to_string(path)?;
+    let formatted = match path.extension() {
+        Some(ext) if ext == "trig" => format_trig(&original, config),
+        _ => format_turtle(&original, config),
+    }
+    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    if formatted == original {
+        return Ok(false);
+    }
+    std::fs::write(path, formatted)?;
+    Ok(true)
+}
+
+/// Relative IRIs are preserved as is by the formatter,
+/// but the parser needs a base IRI to accept them.
+fn dummy_base() -> Iri<String> {
+    Iri::new_unchecked("x-sophia-fmt:/".to_string())
+}
+
+/// The whitespace to insert between two tokens
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Separator {
+    None,
+    Space,
+    Newline,
+}
+
+fn format_tokens(txt: &str, config: &FormatConfig) -> String {
+    let mut out = String::with_capacity(txt.len());
+    // nesting level of graphs (in TriG)
+    let mut graphs = 0;
+    // nesting level of brackets in the current statement
+    let mut depth = 0_usize;
+    // the kind of the current statement (None between statements)
+    let mut current = None;
+    // for directives: whether the Turtle style is used
+    let mut turtle_style = false;
+    // the previous (non-trivia) token
+    let mut previous: Option<Token> = None;
+    // the number of line breaks in the original text since the previous token
+    let mut newlines = 0;
+    let mut after_comment = false;
+    let mut after_directive = false;
+    let mut skip_dot = false;
+
+    for token in Lexer::new(txt) {
+        match token.kind {
+            TokenKind::Whitespace => {
+                newlines += token.text.matches('\n').count();
+                continue;
+            }
+            TokenKind::Comment => {
+                if newlines == 0 && !out.is_empty() {
+                    out.push(' ');
+                } else if !out.is_empty() {
+                    let level = graphs + usize::from(current.is_some());
+                    let blank = current.is_none()
+                        && (newlines > 1
+                            || (after_directive && config.blank_line_after_directives));
+                    push_newline(&mut out, config, level, blank);
+                    after_directive &= current.is_some();
+                }
+                out.push_str(token.text.trim_end());
+                newlines = 0;
+                after_comment = true;
+                continue;
+            }
+            _ => (),
+        }
+        if current.is_none() && std::mem::take(&mut skip_dot) && is_punct(&token, ".") {
+            // the dot terminating a Turtle-style directive, already re-inserted
+            continue;
+        }
+        if current == Some(StatementKind::Triples) && depth == 0 && is_punct(&token, "}") {
+            // last statement of a graph, without a final dot
+            current = None;
+        }
+
+        match current {
+            None => {
+                let kind = statement_kind(&token);
+                if !out.is_empty() {
+                    let blank = newlines > 1
+                        || (after_directive
+                            && kind == StatementKind::Triples
+                            && config.blank_line_after_directives);
+                    if is_punct(&token, "}") {
+                        graphs = graphs.saturating_sub(1);
+                    }
+                    push_newline(&mut out, config, graphs, blank);
+                }
+                after_directive = kind != StatementKind::Triples;
+                if is_punct(&token, "}") {
+                    out.push('}');
+                } else {
+                    current = Some(kind);
+                    if kind == StatementKind::Triples {
+                        push_token(&mut out, &token);
+                    } else {
+                        turtle_style = match config.directives {
+                            DirectiveStyle::Keep => token.text.starts_with('@'),
+                            DirectiveStyle::Turtle => true,
+                            DirectiveStyle::Sparql => false,
+                        };
+                        skip_dot = token.text.starts_with('@');
+                        out.push_str(match (kind, turtle_style) {
+                            (StatementKind::Prefix, true) => "@prefix",
+                            (StatementKind::Prefix, false) => "PREFIX",
+                            (_, true) => "@base",
+                            (_, false) => "BASE",
+                        });
+                    }
+                }
+            }
+            Some(StatementKind::Triples) => {
+                let mut sep = separator(previous.as_ref().unwrap(), &token, depth);
+                if after_comment {
+                    sep = Separator::Newline;
+                }
+                match sep {
+                    Separator::None => (),
+                    Separator::Space => out.push(' '),
+                    Separator::Newline => push_newline(&mut out, config, graphs + 1, false),
+                }
+                push_token(&mut out, &token);
+            }
+            Some(_) => {
+                // directive: the dot, if any, is skipped and re-inserted below
+                if after_comment {
+                    push_newline(&mut out, config, graphs + 1, false);
+                } else {
+                    out.push(' ');
+                }
+                push_token(&mut out, &token);
+            }
+        }
+
+        match (current, token.kind, token.text) {
+            (Some(StatementKind::Triples), TokenKind::Punctuation, "[" | "(" | "<<" | "{|") => {
+                depth += 1;
+            }
+            (Some(StatementKind::Triples), TokenKind::Punctuation, "]" | ")" | ">>" | "|}") => {
+                depth = depth.saturating_sub(1);
+            }
+            (Some(StatementKind::Triples), TokenKind::Punctuation, ".") if depth == 0 => {
+                current = None;
+            }
+            (Some(StatementKind::Triples), TokenKind::Punctuation, "{") if depth == 0 => {
+                graphs += 1;
+                current = None;
+            }
+            (Some(StatementKind::Prefix | StatementKind::Base), TokenKind::IriRef, _) => {
+                if turtle_style {
+                    out.push_str(" .");
+                }
+                current = None;
+            }
+            _ => (),
+        }
+        previous = Some(token);
+        newlines = 0;
+        after_comment = false;
+    }
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+fn separator(previous: &Token, token: &Token, depth: usize) -> Separator {
+    use Separator::*;
+    if is_punct(token, ",") || token.kind == TokenKind::LangTag {
+        None
+    } else if is_punct(token, ";") || is_punct(token, ".") {
+        Space
+    } else if is_punct(token, "^^")
+        || is_punct(previous, "^^")
+        || (is_punct(previous, "[") && is_punct(token, "]"))
+        || (is_punct(previous, "(") && is_punct(token, ")"))
+    {
+        None
+    } else if is_punct(previous, ";") && depth == 0 {
+        Newline
+    } else {
+        Space
+    }
+}
+
+fn is_punct(token: &Token, punct: &str) -> bool {
+    token.kind == TokenKind::Punctuation && token.text == punct
+}
+
+fn push_token(out: &mut String, token: &Token) {
+    if token.kind == TokenKind::Keyword && token.text.eq_ignore_ascii_case("graph") {
+        out.push_str("GRAPH");
+    } else {
+        out.push_str(token.text);
+    }
+}
+
+fn push_newline(out: &mut String, config: &FormatConfig, level: usize, blank: bool) {
+    out.push('\n');
+    if blank {
+        out.push('\n');
+    }
+    for _ in 0..level {
+        out.push_str(&config.indentation);
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TTL: &str = r#"@prefix ex: <http://example.org/>.
+# people
+ex:alice   a ex:Person;ex:knows ex:bob,ex:carol ; # friends
+  ex:name "Alice"@en, "Alicia"^^<http://www.w3.org/2001/XMLSchema#string>.
+
+
+ex:bob ex:knows [ ex:name "Carol" ; ex:age 42 ], [] ; ex:likes ( 1 2 ) , () ."#;
+
+    const FORMATTED: &str = r#"@prefix ex: <http://example.org/> .
+
+# people
+ex:alice a ex:Person ;
+  ex:knows ex:bob, ex:carol ; # friends
+  ex:name "Alice"@en, "Alicia"^^<http://www.w3.org/2001/XMLSchema#string> .
+
+ex:bob ex:knows [ ex:name "Carol" ; ex:age 42 ], [] ;
+  ex:likes ( 1 2 ), () .
+"#;
+
+    #[test]
+    fn turtle() {
+        let config = FormatConfig::new();
+        assert_eq!(format_turtle(TTL, &config).unwrap(), FORMATTED);
+        // formatting is idempotent
+        assert_eq!(format_turtle(FORMATTED, &config).unwrap(), FORMATTED);
+    }
+
+    #[test]
+    fn directives() {
+        let ttl =
+            "PREFIX ex: <http://example.org/>\n@base <http://example.org/base/> .\nex:s <p> ex:o .";
+        let config = FormatConfig::new().with_directives(DirectiveStyle::Sparql);
+        assert_eq!(
+            format_turtle(ttl, &config).unwrap(),
+            "PREFIX ex: <http://example.org/>\nBASE <http://example.org/base/>\n\nex:s <p> ex:o .\n"
+        );
+        let config = FormatConfig::new().with_directives(DirectiveStyle::Turtle);
+        assert_eq!(
+            format_turtle(ttl, &config).unwrap(),
+            "@prefix ex: <http://example.org/> .\n@base <http://example.org/base/> .\n\nex:s <p> ex:o .\n"
+        );
+        let config = FormatConfig::new().with_blank_line_after_directives(false);
+        assert_eq!(
+            format_turtle(ttl, &config).unwrap(),
+            "PREFIX ex: <http://example.org/>\n@base <http://example.org/base/> .\nex:s <p> ex:o .\n"
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let ttl = "# header\n\n\n@prefix : <x:> .\n:s :p [ # inner\n :q :r ] .\n# footer\n";
+        assert_eq!(
+            format_turtle(ttl, &FormatConfig::new().with_indentation("    ")).unwrap(),
+            "# header\n\n@prefix : <x:> .\n\n:s :p [ # inner\n    :q :r ] .\n# footer\n"
+        );
+    }
+
+    #[test]
+    fn trig() {
+        let trig =
+            "PREFIX : <http://example.org/>\n:g1 {:s :p :o} GRAPH :g2 { :s :p :o . :s :q :r }";
+        assert_eq!(
+            format_trig(trig, &FormatConfig::new()).unwrap(),
+            r#"PREFIX : <http://example.org/>
+
+:g1 {
+  :s :p :o
+}
+GRAPH :g2 {
+  :s :p :o .
+  :s :q :r
+}
+"#
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(format_turtle(":s :p :o .", &FormatConfig::new()).is_err());
+        assert!(format_trig("<s> <p> <o> } .", &FormatConfig::new()).is_err());
+    }
+
+    #[test]
+    fn file() -> io::Result<()> {
+        let path = std::env::temp_dir().join(format!("sophia_fmt_{}.ttl", std::process::id()));
+        std::fs::write(&path, TTL)?;
+        assert!(format_file(&path, &FormatConfig::new())?);
+        assert_eq!(std::fs::read_to_string(&path)?, FORMATTED);
+        assert!(!format_file(&path, &FormatConfig::new())?);
+        std::fs::write(&path, "<s> <p> .")?;
+        let err = format_file(&path, &FormatConfig::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path)
+    }
+}
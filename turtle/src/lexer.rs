@@ -0,0 +1,665 @@
+//! A lexer for the Turtle-family of RDF concrete syntaxes.
+//!
+//! This lexer is tolerant: it never fails,
+//! but produces tokens of kind [`TokenKind::Error`] for unrecognized input.
+//! Concatenating the text of all the tokens always gives back the original text.
+//!
+//! It implements the terminal productions of the [Turtle] and [TriG] grammars,
+//! for the benefit of syntax highlighters, formatters or other tools
+//! that would otherwise approximate them with regular expressions.
+//! Its tests check it against the parsers in [`crate::parser`]:
+//! it produces an error token for every lexical error they report,
+//! and no error token for documents they accept.
+//! A text tokenized without error may of course still be rejected by the parsers,
+//! because of a syntax error (e.g. a missing `.`) or an undeclared prefix.
+//!
+//! # Example
+//! ```
+//! use sophia_turtle::lexer::{Lexer, TokenKind};
+//!
+//! let ttl = r#"<#me> a <#Person> ; <#name> "Alice"@en . # me"#;
+//! let mut kinds = Lexer::new(ttl)
+//!     .filter(|t| !t.is_trivia())
+//!     .map(|t| t.kind);
+//! assert_eq!(kinds.next(), Some(TokenKind::IriRef));
+//! assert_eq!(kinds.next(), Some(TokenKind::Keyword));
+//! assert_eq!(kinds.nth(3), Some(TokenKind::String));
+//! assert_eq!(kinds.next(), Some(TokenKind::LangTag));
+//!
+//! // the span of each token can be used to decorate the original text
+//! let comment = Lexer::new(ttl).last().unwrap();
+//! assert_eq!(comment.kind, TokenKind::Comment);
+//! assert_eq!(&ttl[comment.span], "# me");
+//! ```
+//!
+//! [Turtle]: https://www.w3.org/TR/turtle/#sec-grammar
+//! [TriG]: https://www.w3.org/TR/trig/#sec-grammar
+use std::ops::Range;
+
+/// The different kinds of [`Token`]s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// A sequence of whitespace characters (including line breaks)
+    Whitespace,
+    /// A comment, from `#` to the end of the line (excluded)
+    Comment,
+    /// An IRI reference between angle brackets
+    IriRef,
+    /// A prefixed name, or a prefix declared in a directive (e.g. `ex:`)
+    PrefixedName,
+    /// A labelled blank node (e.g. `_:b1`)
+    BlankNodeLabel,
+    /// A short or long string, with its quotes
+    String,
+    /// A language tag, with its leading `@`
+    LangTag,
+    /// An integer, decimal or double literal
+    Number,
+    /// `true` or `false`
+    Boolean,
+    /// `a`, `@prefix`, `@base`, `PREFIX`, `BASE` or `GRAPH`
+    Keyword,
+    /// `.`, `;`, `,`, `[`, `]`, `(`, `)`, `{`, `}`, `<<`, `>>`, `{|`, `|}` or `^^`
+    Punctuation,
+    /// Unrecognized input, including unterminated strings and IRIs
+    Error,
+}
+
+/// A token, as produced by [`Lexer`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// The kind of this token
+    pub kind: TokenKind,
+    /// The text of this token
+    pub text: &'a str,
+    /// The span of this token in the original text (in bytes)
+    pub span: Range<usize>,
+}
+
+impl Token<'_> {
+    /// Whether this token is semantically irrelevant (whitespace or comment)
+    pub fn is_trivia(&self) -> bool {
+        matches!(self.kind, TokenKind::Whitespace | TokenKind::Comment)
+    }
+
+    /// Whether this token is an unterminated string
+    pub fn is_unterminated_string(&self) -> bool {
+        match self.text.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                self.kind == TokenKind::Error && !Lexer::new(self.text).scan_string(quote).1
+            }
+            _ => false,
+        }
+    }
+}
+
+/// An iterator over the [`Token`]s of a Turtle (or N-Triples, N-Quads, TriG) document.
+#[derive(Clone, Debug)]
+pub struct Lexer<'a> {
+    text: &'a str,
+    pos: usize,
+    previous: Option<TokenKind>,
+}
+
+impl<'a> Lexer<'a> {
+    /// Build a lexer for the given `text`.
+    pub fn new(text: &'a str) -> Self {
+        Lexer {
+            text,
+            pos: 0,
+            previous: None,
+        }
+    }
+
+    /// Build a lexer for the given `text`, starting at byte offset `pos`.
+    ///
+    /// This is useful for re-tokenizing only part of a text after it has been edited
+    /// (see for example [`TurtleDocument::edit`](crate::document::TurtleDocument::edit)).
+    ///
+    /// # Precondition
+    /// `pos` must be the start of a token (as produced by a lexer starting at 0).
+    pub fn new_at(text: &'a str, pos: usize) -> Self {
+        Lexer {
+            text,
+            pos,
+            previous: None,
+        }
+    }
+
+    /// The byte offset of the next token to be produced by this lexer.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn peek_at(&self, n: usize) -> Option<char> {
+        self.rest().chars().nth(n)
+    }
+
+    fn scan_while<F: Fn(char) -> bool>(&self, from: usize, f: F) -> usize {
+        self.text[from..]
+            .char_indices()
+            .find(|(_, c)| !f(*c))
+            .map(|(i, _)| from + i)
+            .unwrap_or(self.text.len())
+    }
+
+    /// Scan a name, where `.` is only allowed inside (not at the end).
+    /// If `local` is true, the additional characters allowed in local names
+    /// (`:`, percent-encoding and escape sequences) are also accepted.
+    fn scan_name(&self, from: usize, local: bool) -> usize {
+        let mut end = from;
+        let mut chars = self.text[from..].char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if is_name_char(c) || (local && c == ':') {
+                end = from + i + c.len_utf8();
+            } else if local && (c == '%' || c == '\\') {
+                // escape sequences: consume the next character as well
+                match chars.next() {
+                    Some((j, d)) => end = from + j + d.len_utf8(),
+                    None => break,
+                }
+            } else if c == '.' {
+                match chars.peek() {
+                    Some((_, d)) if is_name_char(*d) || (local && *d == ':') => {
+                        end = from + i + 1;
+                    }
+                    _ => break,
+                }
+            } else {
+                break;
+            }
+        }
+        end
+    }
+
+    fn scan_string(&self, quote: char) -> (usize, bool) {
+        let start = self.pos;
+        let long: String = [quote; 3].iter().collect();
+        if self.rest().starts_with(&long) {
+            let mut chars = self.text[start + 3..].char_indices();
+            while let Some((i, c)) = chars.next() {
+                if c == '\\' {
+                    chars.next();
+                } else if self.text[start + 3 + i..].starts_with(&long) {
+                    return (start + 3 + i + 3, true);
+                }
+            }
+            (self.text.len(), false)
+        } else {
+            let mut chars = self.text[start + 1..].char_indices();
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '\\' => {
+                        chars.next();
+                    }
+                    '\n' | '\r' => return (start + 1 + i, false),
+                    c if c == quote => return (start + 1 + i + 1, true),
+                    _ => (),
+                }
+            }
+            (self.text.len(), false)
+        }
+    }
+
+    fn scan_number(&self) -> Option<usize> {
+        let mut end = self.pos;
+        if self.rest().starts_with(['+', '-']) {
+            end += 1;
+        }
+        let int_end = self.scan_while(end, |c| c.is_ascii_digit());
+        let mut digits = int_end > end;
+        end = int_end;
+        if self.text[end..].starts_with('.') {
+            let frac_end = self.scan_while(end + 1, |c| c.is_ascii_digit());
+            if frac_end > end + 1 {
+                digits = true;
+                end = frac_end;
+            }
+        }
+        if !digits {
+            return None;
+        }
+        if self.text[end..].starts_with(['e', 'E']) {
+            let mut exp = end + 1;
+            if self.text[exp..].starts_with(['+', '-']) {
+                exp += 1;
+            }
+            let exp_end = self.scan_while(exp, |c| c.is_ascii_digit());
+            if exp_end > exp {
+                end = exp_end;
+            }
+        }
+        Some(end)
+    }
+
+    fn next_token(&self) -> (TokenKind, usize) {
+        use TokenKind::*;
+        let start = self.pos;
+        let c = self.peek_at(0).unwrap();
+        let c2 = self.peek_at(1);
+        match c {
+            c if c.is_whitespace() => (Whitespace, self.scan_while(start, char::is_whitespace)),
+            '#' => (Comment, self.scan_while(start, |c| c != '\n' && c != '\r')),
+            '<' if c2 == Some('<') => (Punctuation, start + 2),
+            '<' => {
+                let end = self.scan_while(start + 1, |c| {
+                    !c.is_whitespace()
+                        && !matches!(c, '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`')
+                });
+                if !self.text[end..].starts_with('>') {
+                    (Error, end)
+                } else if valid_escapes(&self.text[start + 1..end], false) {
+                    (IriRef, end + 1)
+                } else {
+                    (Error, end + 1)
+                }
+            }
+            '>' if c2 == Some('>') => (Punctuation, start + 2),
+            '{' if c2 == Some('|') => (Punctuation, start + 2),
+            '|' if c2 == Some('}') => (Punctuation, start + 2),
+            '^' if c2 == Some('^') => (Punctuation, start + 2),
+            '.' | ';' | ',' | '[' | ']' | '(' | ')' | '{' | '}' => match self.scan_number() {
+                Some(end) if c == '.' => (Number, end),
+                _ => (Punctuation, start + 1),
+            },
+            '"' | '\'' => match self.scan_string(c) {
+                (end, true) if valid_escapes(&self.text[start..end], true) => (String, end),
+                (end, _) => (Error, end),
+            },
+            '@' => {
+                let end = self.scan_while(start + 1, |c| c.is_ascii_alphanumeric() || c == '-');
+                let word = &self.text[start + 1..end];
+                if self.previous != Some(String) && (word == "prefix" || word == "base") {
+                    (Keyword, end)
+                } else if is_lang_tag(word) {
+                    (LangTag, end)
+                } else {
+                    (Error, end)
+                }
+            }
+            '_' if c2 == Some(':') => {
+                let end = self.scan_name(start + 2, false);
+                if is_bnode_label(&self.text[start + 2..end]) {
+                    (BlankNodeLabel, end)
+                } else {
+                    (Error, end)
+                }
+            }
+            '+' | '-' | '0'..='9' => match self.scan_number() {
+                Some(end) => (Number, end),
+                None => self.scan_word(),
+            },
+            _ => self.scan_word(),
+        }
+    }
+
+    fn scan_word(&self) -> (TokenKind, usize) {
+        use TokenKind::*;
+        let start = self.pos;
+        let end = self.scan_name(start, false);
+        if self.text[end..].starts_with(':') {
+            let local_end = self.scan_name(end + 1, true);
+            let valid =
+                is_pn_prefix(&self.text[start..end]) && is_pn_local(&self.text[end + 1..local_end]);
+            return (if valid { PrefixedName } else { Error }, local_end);
+        }
+        if end == start {
+            let c = self.peek_at(0).unwrap();
+            return (Error, start + c.len_utf8());
+        }
+        let word = &self.text[start..end];
+        if word == "a"
+            || word.eq_ignore_ascii_case("prefix")
+            || word.eq_ignore_ascii_case("base")
+            || word.eq_ignore_ascii_case("graph")
+        {
+            (Keyword, end)
+        } else if word == "true" || word == "false" {
+            (Boolean, end)
+        } else {
+            (Error, end)
+        }
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.text.len() {
+            return None;
+        }
+        let (kind, end) = self.next_token();
+        let span = self.pos..end;
+        self.pos = end;
+        self.previous = Some(kind);
+        Some(Token {
+            kind,
+            text: &self.text[span.clone()],
+            span,
+        })
+    }
+}
+
+/// Whether `c` may be part of a name.
+///
+/// This is more lenient than the grammar, in order to determine the extent of tokens;
+/// their text is then checked with [`is_pn_prefix`], [`is_pn_local`] or [`is_bnode_label`].
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c as u32 >= 0x80
+}
+
+/// `PN_CHARS_BASE`
+fn is_pn_chars_base(c: char) -> bool {
+    matches!(c,
+        'A'..='Z'
+        | 'a'..='z'
+        | '\u{C0}'..='\u{D6}'
+        | '\u{D8}'..='\u{F6}'
+        | '\u{F8}'..='\u{2FF}'
+        | '\u{370}'..='\u{37D}'
+        | '\u{37F}'..='\u{1FFF}'
+        | '\u{200C}'..='\u{200D}'
+        | '\u{2070}'..='\u{218F}'
+        | '\u{2C00}'..='\u{2FEF}'
+        | '\u{3001}'..='\u{D7FF}'
+        | '\u{F900}'..='\u{FDCF}'
+        | '\u{FDF0}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{EFFFF}'
+    )
+}
+
+/// `PN_CHARS_U`
+fn is_pn_chars_u(c: char) -> bool {
+    is_pn_chars_base(c) || c == '_'
+}
+
+/// `PN_CHARS`
+fn is_pn_chars(c: char) -> bool {
+    is_pn_chars_u(c)
+        || matches!(c,
+            '-' | '0'..='9' | '\u{B7}' | '\u{300}'..='\u{36F}' | '\u{203F}'..='\u{2040}'
+        )
+}
+
+/// Whether `txt` is a `PN_PREFIX` (or is empty),
+/// assuming that it does not end with `.` (see [`Lexer::scan_name`]).
+fn is_pn_prefix(txt: &str) -> bool {
+    let mut chars = txt.chars();
+    chars.next().is_none_or(is_pn_chars_base) && chars.all(|c| is_pn_chars(c) || c == '.')
+}
+
+/// Whether `txt` is the label of a `BLANK_NODE_LABEL` (after `_:`),
+/// assuming that it does not end with `.` (see [`Lexer::scan_name`]).
+fn is_bnode_label(txt: &str) -> bool {
+    let mut chars = txt.chars();
+    chars
+        .next()
+        .is_some_and(|c| is_pn_chars_u(c) || c.is_ascii_digit())
+        && chars.all(|c| is_pn_chars(c) || c == '.')
+}
+
+/// Whether `txt` is a `PN_LOCAL` (or is empty),
+/// assuming that it does not end with `.` (see [`Lexer::scan_name`]).
+fn is_pn_local(txt: &str) -> bool {
+    let mut chars = txt.chars();
+    let mut first = true;
+    while let Some(c) = chars.next() {
+        let ok = match c {
+            '%' => {
+                chars.next().is_some_and(|c| c.is_ascii_hexdigit())
+                    && chars.next().is_some_and(|c| c.is_ascii_hexdigit())
+            }
+            '\\' => chars
+                .next()
+                .is_some_and(|c| "_~.-!$&'()*+,;=/?#@%".contains(c)),
+            ':' => true,
+            '.' | '-' | '\u{B7}' | '\u{300}'..='\u{36F}' | '\u{203F}'..='\u{2040}' => !first,
+            _ => is_pn_chars(c),
+        };
+        if !ok {
+            return false;
+        }
+        first = false;
+    }
+    true
+}
+
+/// Whether `txt` is a `LANGTAG` (without the leading `@`).
+fn is_lang_tag(txt: &str) -> bool {
+    let mut subtags = txt.split('-');
+    subtags
+        .next()
+        .is_some_and(|s| !s.is_empty() && s.bytes().all(|b| b.is_ascii_alphabetic()))
+        && subtags.all(|s| !s.is_empty() && s.bytes().all(|b| b.is_ascii_alphanumeric()))
+}
+
+/// Whether all the escape sequences in `txt` are `UCHAR`s (or `ECHAR`s, if `echar` is true).
+fn valid_escapes(txt: &str, echar: bool) -> bool {
+    let mut rest = txt;
+    while let Some(i) = rest.find('\\') {
+        let esc = &rest[i + 1..];
+        let len = match esc.as_bytes().first() {
+            Some(b'u') => 5,
+            Some(b'U') => 9,
+            Some(b't' | b'b' | b'n' | b'r' | b'f' | b'"' | b'\'' | b'\\') if echar => 1,
+            _ => return false,
+        };
+        let hex = esc.get(1..len);
+        if !hex.is_some_and(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit())) {
+            return false;
+        }
+        rest = &esc[len..];
+    }
+    true
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use TokenKind::*;
+
+    fn kinds(txt: &str) -> Vec<(TokenKind, &str)> {
+        Lexer::new(txt)
+            .filter(|t| t.kind != Whitespace)
+            .map(|t| (t.kind, t.text))
+            .collect()
+    }
+
+    #[test]
+    fn lossless() {
+        let txt = r#"@prefix ex: <http://example.org/> . # comment
+            ex:s a ex:C; ex:p "a\"b"@en, '''x
+            y''', 1.5e3, -2, .5, true, [ ex:q _:b.1 ], (1 2) ; ex:r "x"^^ex:d."#;
+        let rebuilt: std::string::String = Lexer::new(txt).map(|t| t.text).collect();
+        assert_eq!(rebuilt, txt);
+        assert!(Lexer::new(txt).all(|t| t.kind != Error));
+    }
+
+    #[test]
+    fn tokens() {
+        assert_eq!(
+            kinds("PREFIX ex: <tag:> ex:s ex:p ex:o.#c"),
+            vec![
+                (Keyword, "PREFIX"),
+                (PrefixedName, "ex:"),
+                (IriRef, "<tag:>"),
+                (PrefixedName, "ex:s"),
+                (PrefixedName, "ex:p"),
+                (PrefixedName, "ex:o"),
+                (Punctuation, "."),
+                (Comment, "#c"),
+            ]
+        );
+        assert_eq!(
+            kinds("<< _:a :b 42. >> {| :c \"d\"@en-us |}"),
+            vec![
+                (Punctuation, "<<"),
+                (BlankNodeLabel, "_:a"),
+                (PrefixedName, ":b"),
+                (Number, "42"),
+                (Punctuation, "."),
+                (Punctuation, ">>"),
+                (Punctuation, "{|"),
+                (PrefixedName, ":c"),
+                (String, "\"d\""),
+                (LangTag, "@en-us"),
+                (Punctuation, "|}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            kinds(":s :p \"abc\n:t"),
+            vec![
+                (PrefixedName, ":s"),
+                (PrefixedName, ":p"),
+                (Error, "\"abc"),
+                (PrefixedName, ":t"),
+            ]
+        );
+        assert_eq!(kinds("<foo bar>").first(), Some(&(Error, "<foo")));
+        assert!(Lexer::new("'''abc")
+            .next()
+            .unwrap()
+            .is_unterminated_string());
+        assert_eq!(kinds("foo"), vec![(Error, "foo")]);
+    }
+
+    #[test]
+    fn trig() {
+        assert_eq!(
+            kinds("GRAPH <g> { [] :p (1 2.0) } "),
+            vec![
+                (Keyword, "GRAPH"),
+                (IriRef, "<g>"),
+                (Punctuation, "{"),
+                (Punctuation, "["),
+                (Punctuation, "]"),
+                (PrefixedName, ":p"),
+                (Punctuation, "("),
+                (Number, "1"),
+                (Number, "2.0"),
+                (Punctuation, ")"),
+                (Punctuation, "}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn new_at() {
+        let txt = ":s :p \"o\"@en .";
+        let mut lexer = Lexer::new_at(txt, 6);
+        assert_eq!(lexer.position(), 6);
+        let token = lexer.next().unwrap();
+        assert_eq!((token.kind, token.span), (String, 6..9));
+        assert_eq!(lexer.position(), 9);
+        assert_eq!(lexer.next().unwrap().kind, LangTag);
+    }
+
+    /// Documents accepted by the parsers,
+    /// covering the terminal productions of Turtle and TriG
+    /// (mostly adapted from the W3C test suites).
+    const VALID: &[&str] = &[
+        "<http://a.example/s> <http://a.example/p> <http://a.example/o> .",
+        "<s> <p> <o> .",
+        "<http://a.example/\\u0073> <http://a.example/p> <http://a.example/\\U00000073> .",
+        "@prefix : <http://a.example/> . :s :p :o .",
+        "@prefix p: <http://a.example/> . p:s p:p p:o .",
+        "PREFIX : <http://a.example/> BASE <http://b.example/> :s :p <o> .",
+        "prefix p.1: <http://a.example/> p.1:s p.1:p p.1:o .",
+        "@prefix : <http://a.example/> . :s.1 :p.x :o_ .",
+        "@prefix : <http://a.example/> . :s :p :0 , :a.b , :a:b , :a%20b , :a\\~b .",
+        "@prefix : <http://a.example/> . :s :p :é , :a·b , :a\u{203F}b .",
+        "@prefix : <http://a.example/> . :s :p : .",
+        "_:a <p> _:b1 , _:1 , _:a.b , _:a-b , _:é .",
+        "<s> <p> \"\" , \"a\" , 'a' , \"\"\"a\n\"b\"\"\" , '''a\n'b''' .",
+        "<s> <p> \"\\t\\b\\n\\r\\f\\\"\\'\\\\\" , \"\\u00e9\\U0001F600\" .",
+        "<s> <p> \"a\"@en , \"a\"@en-US , \"a\"@de-1996 , \"a\"@x-1 .",
+        "<s> <p> \"a\"^^<http://www.w3.org/2001/XMLSchema#string> .",
+        "@prefix xsd: <http://www.w3.org/2001/XMLSchema#> . <s> <p> \"1\"^^xsd:integer .",
+        "<s> <p> 1 , -1 , +1 , 1.5 , -.5 , .5 , 1e3 , 1.5E-3 , -1e+3 , .5e1 .",
+        "<s> <p> true , false .",
+        "<s> a <C> ; <p> [ <q> <r> ] , ( 1 ( 2 ) [] ) ; .",
+        "[ <p> <o> ] .",
+        "<< <s> <p> <o> >> <q> << _:a <p> \"o\" >> .",
+        "<s> <p> <o> {| <q> <r> |} .",
+        "# comment\n<s> <p> <o> . # comment\r\n",
+        "<g> { <s> <p> <o> } GRAPH <h> { <s> <p> <o> . } { <s> <p> <o> }",
+        "@prefix : <http://a.example/> . :g { :s :p :o } _:g { :s :p :o }",
+    ];
+
+    /// Documents rejected by the parsers because of a lexical error.
+    const INVALID: &[&str] = &[
+        "<s> <p> <o",
+        "<s> <p> <a b> .",
+        "<s> <p> <a\"b> .",
+        "<s> <p> <a\\n> .",
+        "<s> <p> <a\\u00> .",
+        "<s> <p> \"a .",
+        "<s> <p> \"a\nb\" .",
+        "<s> <p> '''a .",
+        "<s> <p> \"a\\x\" .",
+        "<s> <p> \"a\\u00g9\" .",
+        "<s> <p> \"a\"@ .",
+        "<s> <p> \"a\"@1en .",
+        "<s> <p> \"a\"@en- .",
+        "<s> <p> \"a\"@en_US .",
+        "<s> <p> _: .",
+        "<s> <p> _:-a .",
+        "<s> <p> _:.a .",
+        "@prefix -a: <http://a.example/> . -a:s <p> <o> .",
+        "@prefix _a: <http://a.example/> . _a:s <p> <o> .",
+        "@prefix : <http://a.example/> . :s <p> :-a .",
+        "@prefix : <http://a.example/> . :s <p> :a%2 .",
+        "@prefix : <http://a.example/> . :s <p> :a\\b .",
+        "@prefix : <http://a.example/> . :s <p> :a× .",
+        "<s> <p> o .",
+        "<s> <p> 1e .",
+        "<s> <p> True .",
+        "<s> <p> ^ <o> .",
+        "<s> <p> <o> ? .",
+    ];
+
+    fn parses(txt: &str) -> bool {
+        use crate::parser::trig::TriGParser;
+        use sophia_api::parser::QuadParser;
+        use sophia_api::source::QuadSource;
+        let parser = TriGParser {
+            base: Some(sophia_iri::Iri::new_unchecked("http://a.example/".into())),
+        };
+        parser.parse_str(txt).for_each_quad(|_| ()).is_ok()
+    }
+
+    #[test]
+    fn agrees_with_parsers() {
+        let mut failures = vec![];
+        for txt in VALID {
+            if !parses(txt) {
+                failures.push(format!("rejected by the parser: {txt:?}"));
+            }
+            for t in Lexer::new(txt).filter(|t| t.kind == Error) {
+                failures.push(format!("error token {:?} in {txt:?}", t.text));
+            }
+        }
+        for txt in INVALID {
+            if parses(txt) {
+                failures.push(format!("accepted by the parser: {txt:?}"));
+            }
+            if Lexer::new(txt).all(|t| t.kind != Error) {
+                failures.push(format!("no error token in {txt:?}"));
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
@@ -9,5 +9,8 @@
 //! [Linked Data]: http://linkeddata.org/
 #![deny(missing_docs)]
 
+pub mod document;
+pub mod formatter;
+pub mod lexer;
 pub mod parser;
 pub mod serializer;
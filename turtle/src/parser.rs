@@ -1,9 +1,12 @@
 //! Parsers for the Turtle-familt of RDF concrete syntaxes,
 //! based on [`rio_turtle`].
 
+pub mod borrowing;
 pub mod gnq;
 pub mod gtrig;
+pub mod hints;
 pub mod nq;
 pub mod nt;
 pub mod trig;
 pub mod turtle;
+pub mod validate;
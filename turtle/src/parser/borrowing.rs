@@ -0,0 +1,271 @@
+//! Zero-copy parsing of [N-Triples] and [N-Quads] documents held in memory.
+//!
+//! When triples or quads produced by the [N-Triples](super::nt::NTriplesParser)
+//! or [N-Quads](super::nq::NQuadsParser) parsers are stored,
+//! a new string is allocated for each of their terms,
+//! which dominates the time spent loading large documents.
+//!
+//! When the whole document is available as a `&str`
+//! (e.g. read in a `String` or memory-mapped),
+//! the iterators of this module can be used instead.
+//! They produce [`SimpleTerm`]s borrowing their text from the document,
+//! so that only IRIs and literals containing escape sequences
+//! (which need to be unescaped) and quoted triples allocate memory.
+//! They are also [`TripleSource`](sophia_api::source::TripleSource)s
+//! and [`QuadSource`](sophia_api::source::QuadSource)s, respectively.
+//!
+//! Each line is parsed independently: after an error, iteration resumes at the next line.
+//!
+//! # Example
+//! ```
+//! use sophia_api::term::{SimpleTerm, Term};
+//! use sophia_turtle::parser::nt::NTriplesParser;
+//!
+//! let nt = String::from(r#"
+//!     <tag:alice> <tag:name> "Alice" .
+//!     <tag:alice> <tag:knows> _:b .
+//! "#);
+//! let triples: Vec<[SimpleTerm; 3]> = NTriplesParser {}
+//!     .parse_borrowed(&nt)
+//!     .collect::<Result<_, _>>()?;
+//! assert_eq!(triples.len(), 2);
+//! assert_eq!(triples[0][2].lexical_form().unwrap(), "Alice");
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [N-Triples]: https://www.w3.org/TR/n-triples/
+//! [N-Quads]: https://www.w3.org/TR/n-quads/
+use sophia_api::quad::Spog;
+use sophia_api::term::{parse_nt_statement, SimpleTerm};
+
+/// An iterator over the triples of an N-Triples document,
+/// borrowing their text from that document
+/// (see [`NTriplesParser::parse_borrowed`](super::nt::NTriplesParser::parse_borrowed)).
+#[derive(Clone, Debug)]
+pub struct BorrowingTriples<'a>(Statements<'a>);
+
+impl<'a> BorrowingTriples<'a> {
+    /// Iterate over the triples of the N-Triples document `txt`.
+    pub fn new(txt: &'a str) -> Self {
+        BorrowingTriples(Statements::new(txt, false))
+    }
+}
+
+impl<'a> Iterator for BorrowingTriples<'a> {
+    type Item = Result<[SimpleTerm<'a>; 3], BorrowingParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|res| res.map(|(spo, _)| spo))
+    }
+}
+
+/// An iterator over the quads of an N-Quads document,
+/// borrowing their text from that document
+/// (see [`NQuadsParser::parse_borrowed`](super::nq::NQuadsParser::parse_borrowed)).
+#[derive(Clone, Debug)]
+pub struct BorrowingQuads<'a>(Statements<'a>);
+
+impl<'a> BorrowingQuads<'a> {
+    /// Iterate over the quads of the N-Quads document `txt`.
+    pub fn new(txt: &'a str) -> Self {
+        BorrowingQuads(Statements::new(txt, true))
+    }
+}
+
+impl<'a> Iterator for BorrowingQuads<'a> {
+    type Item = Result<Spog<SimpleTerm<'a>>, BorrowingParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+/// Error raised by [`BorrowingTriples`] and [`BorrowingQuads`].
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("Syntax error at line {line}, column {column}: {message}")]
+pub struct BorrowingParseError {
+    /// The (1-based) number of the line where the error occurred
+    pub line: usize,
+    /// The (1-based) position in that line, in bytes, where the error occurred
+    pub column: usize,
+    /// A description of the error
+    pub message: &'static str,
+}
+
+/// The statements of a document, in the same format as quads.
+#[derive(Clone, Debug)]
+struct Statements<'a> {
+    txt: &'a str,
+    pos: usize,
+    line: usize,
+    quads: bool,
+}
+
+impl<'a> Statements<'a> {
+    fn new(txt: &'a str, quads: bool) -> Self {
+        Statements {
+            txt,
+            pos: 0,
+            line: 1,
+            quads,
+        }
+    }
+}
+
+impl<'a> Iterator for Statements<'a> {
+    type Item = Result<Spog<SimpleTerm<'a>>, BorrowingParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.txt.len() {
+            let rest = &self.txt[self.pos..];
+            // a lone CR is also a line terminator in N-Triples,
+            // but only LFs are counted to number lines (as in the validate module)
+            let end = rest.find(['\n', '\r']).unwrap_or(rest.len());
+            let line = self.line;
+            if rest.as_bytes().get(end) == Some(&b'\n') {
+                self.line += 1;
+            }
+            self.pos += (end + 1).min(rest.len());
+            match parse_nt_statement(&rest[..end], self.quads) {
+                Ok(None) => continue,
+                Ok(Some(quad)) => return Some(Ok(quad)),
+                Err(err) => {
+                    // the column is relative to the last line terminator, be it a CR or a LF
+                    return Some(Err(BorrowingParseError {
+                        line,
+                        column: err.position + 1,
+                        message: err.message,
+                    }));
+                }
+            }
+        }
+        None
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sophia_api::ns::{rdf, xsd};
+    use sophia_api::term::{IriRef, Term, TermKind};
+
+    /// Whether the text of `t` borrows from `doc`
+    fn borrows_from(t: &SimpleTerm, doc: &str) -> bool {
+        let range = doc.as_bytes().as_ptr_range();
+        let txt: &str = match t {
+            SimpleTerm::Iri(iri) => iri.as_str(),
+            SimpleTerm::BlankNode(id) => id.as_str(),
+            SimpleTerm::LiteralDatatype(lex, _) | SimpleTerm::LiteralLanguage(lex, _) => lex,
+            _ => return false,
+        };
+        range.contains(&txt.as_ptr())
+    }
+
+    #[test]
+    fn triples() -> Result<(), BorrowingParseError> {
+        let nt = "<tag:s> <tag:p> <tag:o> .\n\
+                  # comment\n\
+                  \n\
+                  _:b1 <tag:p> \"lit\" . # comment\r\n\
+                  _:b1 <tag:p> \"chat\"@en-GB .\r\
+                  _:b.1 <tag:p> \"42\"^^<http://www.w3.org/2001/XMLSchema#integer>.\n\
+                  << <tag:s> <tag:p> _:o >> <tag:p> <tag:o> .";
+        let triples = BorrowingTriples::new(nt).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(triples.len(), 5);
+        assert!(Term::eq(&triples[0][2], IriRef::new_unchecked("tag:o")));
+        assert!(triples[1][0].is_blank_node());
+        assert!(Term::eq(&triples[1][2], "lit"));
+        assert_eq!(triples[2][2].language_tag().unwrap().as_str(), "en-GB");
+        assert_eq!(triples[3][0].bnode_id().unwrap().as_str(), "b.1");
+        assert!(Term::eq(&triples[3][2], "42" * xsd::integer));
+        assert_eq!(triples[4][0].kind(), TermKind::Triple);
+        for t in triples[..4].iter().flatten() {
+            assert!(borrows_from(t, nt), "{t:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn escapes() -> Result<(), BorrowingParseError> {
+        let nt = r#"<tag:\u00E9> <tag:p> "a\tb\\n\"c\" \u00e9\U0001F600 \\\\" ."#;
+        let triples = BorrowingTriples::new(nt).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(triples[0][0].iri().unwrap().as_str(), "tag:é");
+        assert_eq!(
+            triples[0][2].lexical_form().unwrap(),
+            "a\tb\\n\"c\" é😀 \\\\"
+        );
+        assert!(!borrows_from(&triples[0][0], nt));
+        assert!(borrows_from(&triples[0][1], nt));
+        Ok(())
+    }
+
+    #[test]
+    fn quads() -> Result<(), BorrowingParseError> {
+        let nq = "<tag:s> <tag:p> <tag:o> .\n\
+                  <tag:s> <tag:p> <tag:o> <tag:g> .\n\
+                  <tag:s> <tag:p> \"o\"@en _:g .";
+        let quads = BorrowingQuads::new(nq).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(quads.len(), 3);
+        assert!(quads[0].1.is_none());
+        assert!(Term::eq(
+            quads[1].1.as_ref().unwrap(),
+            IriRef::new_unchecked("tag:g")
+        ));
+        assert!(quads[2].1.as_ref().unwrap().is_blank_node());
+        assert!(BorrowingTriples::new("<tag:s> <tag:p> <tag:o> <tag:g> .")
+            .next()
+            .unwrap()
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        let nt = "<tag:s> <tag:p> <tag:o> .\n\
+                  <tag:s> <tag:p> \"unterminated .\n\
+                  <s> <tag:p> <tag:o> .\n\
+                  <tag:s> <tag:p> <tag:o>\n\
+                  <tag:s> <tag:p> \"\\uD800\" .\n\
+                  <tag:s> <tag:p> <tag:o> .";
+        let results: Vec<_> = BorrowingTriples::new(nt).collect();
+        assert_eq!(results.len(), 6);
+        assert!(results[0].is_ok());
+        assert_eq!(
+            results[1].as_ref().unwrap_err(),
+            &BorrowingParseError {
+                line: 2,
+                column: 17,
+                message: "unterminated string"
+            }
+        );
+        assert_eq!(results[2].as_ref().unwrap_err().line, 3);
+        assert_eq!(results[3].as_ref().unwrap_err().column, 24);
+        assert_eq!(results[4].as_ref().unwrap_err().line, 5);
+        assert!(results[5].is_ok());
+    }
+
+    #[test]
+    fn same_as_nt_parser() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::parser::nt;
+        use sophia_api::source::TripleSource;
+        use sophia_isomorphism::isomorphic_graphs;
+
+        let nt = r#"
+            <http://localhost/ex#me> <http://example.org/ns/knows> _:b1.
+            _:b1 <http://www.w3.org/1999/02/22-rdf-syntax-ns#type> <http://example.org/ns/Person>.
+            _:b1 <http://example.org/ns/name> "Alice\u0021"@en.
+            << <http://localhost/ex#me> <http://example.org/ns/knows> _:b1 >> <http://example.org/ns/since> "2002"^^<http://www.w3.org/2001/XMLSchema#integer>.
+        "#;
+        let mut expected = Vec::<[SimpleTerm; 3]>::new();
+        nt::parse_str(nt).add_to_graph(&mut expected)?;
+        let mut got = Vec::<[SimpleTerm; 3]>::new();
+        BorrowingTriples::new(nt).add_to_graph(&mut got)?;
+        assert!(isomorphic_graphs(&expected, &got)?);
+        assert!(Term::eq(&got[1][1], rdf::type_));
+        Ok(())
+    }
+}
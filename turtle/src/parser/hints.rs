@@ -0,0 +1,478 @@
+//! Best-effort repair suggestions for Turtle syntax errors.
+//!
+//! The Turtle parser stops at the first error, with a message intended for humans.
+//! The functions of this module analyze the source text in a tolerant way,
+//! and produce machine-readable [`RepairHint`]s,
+//! so that editors and linters can offer quick-fixes.
+//!
+//! # Example
+//! ```
+//! use sophia_turtle::parser::hints::{repair_hints, RepairHintKind};
+//!
+//! let ttl = "PREFIX foaf: <http://xmlns.com/foaf/0.1/>\n<#me> foaf:name \"Alice\"; fof:age 42\n";
+//! let hints = repair_hints(ttl);
+//! assert_eq!(hints.len(), 2);
+//! assert_eq!(
+//!     hints[0].kind,
+//!     RepairHintKind::UndefinedPrefix {
+//!         prefix: "fof".into(),
+//!         suggestion: Some("foaf".into()),
+//!     }
+//! );
+//! assert_eq!(hints[1].kind, RepairHintKind::MissingDot);
+//! ```
+use crate::lexer::{Lexer, Token, TokenKind};
+use rio_api::parser::ParseError;
+use rio_turtle::TurtleError;
+use std::fmt;
+use std::ops::Range;
+
+/// A machine-readable suggestion for repairing a syntax error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RepairHint {
+    /// The kind of problem detected
+    pub kind: RepairHintKind,
+    /// The span (in bytes) of the source text where the problem was detected
+    pub span: Range<usize>,
+}
+
+/// The different kinds of [`RepairHint`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RepairHintKind {
+    /// A string is not terminated (the span covers the unterminated string)
+    UnterminatedString {
+        /// The delimiter that should close the string
+        delimiter: String,
+    },
+    /// A statement is not terminated by a dot (the span is empty, where the dot should be)
+    MissingDot,
+    /// A prefix is used without having been declared (the span covers the prefix)
+    UndefinedPrefix {
+        /// The undefined prefix (without the colon)
+        prefix: String,
+        /// The closest prefix declared in the document, if any
+        suggestion: Option<String>,
+    },
+}
+
+impl RepairHint {
+    /// The quick-fix for this hint, if any,
+    /// as a span of the source text and its replacement.
+    pub fn fix(&self) -> Option<(Range<usize>, String)> {
+        match &self.kind {
+            RepairHintKind::UnterminatedString { delimiter } => {
+                Some((self.span.end..self.span.end, delimiter.clone()))
+            }
+            RepairHintKind::MissingDot => Some((self.span.clone(), " .".into())),
+            RepairHintKind::UndefinedPrefix { suggestion, .. } => suggestion
+                .as_ref()
+                .map(|suggestion| (self.span.clone(), suggestion.clone())),
+        }
+    }
+
+    /// The (0-based) line number of this hint in `source`.
+    pub fn line(&self, source: &str) -> usize {
+        source[..self.span.start].matches('\n').count()
+    }
+}
+
+impl fmt::Display for RepairHint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            RepairHintKind::UnterminatedString { delimiter } => {
+                write!(f, "unterminated string (missing {delimiter})")
+            }
+            RepairHintKind::MissingDot => write!(f, "missing '.' at the end of the statement"),
+            RepairHintKind::UndefinedPrefix { prefix, suggestion } => {
+                write!(f, "undefined prefix '{prefix}:'")?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, " (did you mean '{suggestion}:'?)")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Analyze the Turtle document `source` and return all the [`RepairHint`]s found in it,
+/// in the order of the source text.
+///
+/// This never fails, and returns an empty vector if no problem was detected.
+/// Note however that the analysis is best-effort:
+/// the absence of hints does not guarantee that `source` is valid Turtle.
+pub fn repair_hints(source: &str) -> Vec<RepairHint> {
+    let tokens: Vec<_> = Lexer::new(source).filter(|t| !t.is_trivia()).collect();
+    let declared: Vec<&str> = tokens
+        .windows(2)
+        .filter(|w| is_prefix_keyword(&w[0]) && w[1].kind == TokenKind::PrefixedName)
+        .map(|w| prefix_of(&w[1]))
+        .collect();
+    let mut hints = vec![];
+    let mut checker = StatementChecker::default();
+    let mut known: Vec<&str> = vec![];
+    let mut previous: Option<&Token> = None;
+    for token in &tokens {
+        if token.is_unterminated_string() {
+            let delimiter = if token.text.starts_with(r#"""""#) || token.text.starts_with("'''") {
+                &token.text[..3]
+            } else {
+                &token.text[..1]
+            };
+            hints.push(RepairHint {
+                kind: RepairHintKind::UnterminatedString {
+                    delimiter: delimiter.into(),
+                },
+                span: token.span.clone(),
+            });
+        }
+        if token.kind == TokenKind::PrefixedName {
+            let prefix = prefix_of(token);
+            if previous.is_some_and(is_prefix_keyword) {
+                known.push(prefix);
+            } else if !known.contains(&prefix) {
+                hints.push(RepairHint {
+                    kind: RepairHintKind::UndefinedPrefix {
+                        prefix: prefix.into(),
+                        suggestion: closest(prefix, &declared).map(Into::into),
+                    },
+                    span: token.span.start..token.span.start + prefix.len(),
+                });
+            }
+        }
+        if let Some(end) = checker.step(token) {
+            hints.push(RepairHint {
+                kind: RepairHintKind::MissingDot,
+                span: end..end,
+            });
+        }
+        previous = Some(token);
+    }
+    if let Some(end) = checker.finish() {
+        hints.push(RepairHint {
+            kind: RepairHintKind::MissingDot,
+            span: end..end,
+        });
+    }
+    hints.sort_by_key(|h| h.span.start);
+    hints
+}
+
+/// Return the [`RepairHint`]s of `source` that are relevant to the given parse `error`,
+/// the most relevant (i.e. the closest to the error) first.
+///
+/// `error` is expected to have been produced while parsing `source`.
+pub fn hints_for_error(source: &str, error: &TurtleError) -> Vec<RepairHint> {
+    let mut hints = repair_hints(source);
+    if let Some(line) = error.textual_position().map(|p| p.line_number() as usize) {
+        hints.retain(|h| h.line(source) <= line);
+        hints.reverse();
+    }
+    hints
+}
+
+fn is_prefix_keyword(token: &Token) -> bool {
+    token.kind == TokenKind::Keyword
+        && (token.text == "@prefix" || token.text.eq_ignore_ascii_case("prefix"))
+}
+
+fn prefix_of<'a>(token: &Token<'a>) -> &'a str {
+    let text = token.text;
+    &text[..text.find(':').unwrap()]
+}
+
+/// Find the closest candidate to `prefix`, if it is close enough.
+fn closest<'a>(prefix: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|c| (levenshtein(prefix, c), *c))
+        .filter(|(d, c)| *d <= 2.max(c.chars().count() / 3))
+        .min()
+        .map(|(_, c)| c)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diag = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diag
+            } else {
+                1 + diag.min(above).min(row[j])
+            };
+            diag = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// A simple state machine, following the structure of Turtle statements,
+/// used to detect missing dots.
+#[derive(Debug, Default)]
+struct StatementChecker {
+    state: State,
+    /// Nesting of `[`, `(`, `<<` and `{|`
+    depth: usize,
+    /// Position right after the last token at depth 0
+    last_end: usize,
+    /// Set after `^^`, to skip the datatype
+    datatype: bool,
+    /// Set when entering a TriG graph or encountering an error; the checker then gives up
+    disabled: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum State {
+    /// At the start of a statement
+    #[default]
+    Start,
+    /// After the subject, or after a `;`
+    Subject,
+    /// After a predicate, or after a `,`
+    Predicate,
+    /// After an object
+    Object,
+    /// Inside a `@prefix` or `@base` directive (the dot is required)
+    Directive,
+    /// Inside a `PREFIX` or `BASE` directive (no dot)
+    SparqlDirective,
+}
+
+impl StatementChecker {
+    /// Process the next (non-trivia) token,
+    /// and return the position of a missing dot, if one was detected.
+    fn step(&mut self, token: &Token) -> Option<usize> {
+        use State::*;
+        if self.disabled || token.kind == TokenKind::Error {
+            // the structure of the document can not be reliably followed anymore
+            self.disabled = true;
+            return None;
+        }
+        if self.datatype {
+            self.datatype = false;
+            self.last_end = token.span.end;
+            return None;
+        }
+        if self.depth > 0 {
+            match token.text {
+                "[" | "(" | "<<" | "{|" if token.kind == TokenKind::Punctuation => self.depth += 1,
+                "]" | ")" | ">>" | "|}" if token.kind == TokenKind::Punctuation => {
+                    self.depth -= 1;
+                    if self.depth == 0 {
+                        self.last_end = token.span.end;
+                        if token.text == "|}" {
+                            // annotation: back to the state after the object
+                            self.state = Object;
+                        } else {
+                            self.advance();
+                        }
+                    }
+                }
+                _ => (),
+            }
+            return None;
+        }
+        let mut missing = None;
+        match (token.kind, token.text) {
+            (TokenKind::Punctuation, "{" | "}") => {
+                self.disabled = true;
+                return None;
+            }
+            (TokenKind::Keyword, text) if text.eq_ignore_ascii_case("graph") => {
+                self.disabled = true;
+                return None;
+            }
+            (TokenKind::Keyword, "@prefix" | "@base") => {
+                if self.state == Object {
+                    missing = Some(self.last_end);
+                }
+                self.state = Directive;
+            }
+            (TokenKind::Keyword, text)
+                if text.eq_ignore_ascii_case("prefix") || text.eq_ignore_ascii_case("base") =>
+            {
+                if self.state == Object {
+                    missing = Some(self.last_end);
+                }
+                self.state = SparqlDirective;
+            }
+            (TokenKind::Punctuation, ".") => self.state = Start,
+            (TokenKind::Punctuation, ";") if self.state == Object || self.state == Subject => {
+                self.state = Subject
+            }
+            (TokenKind::Punctuation, ",") if self.state == Object => self.state = Predicate,
+            (TokenKind::Punctuation, "^^") => self.datatype = true,
+            (TokenKind::LangTag, _) => (),
+            (TokenKind::Punctuation, "[" | "(" | "<<" | "{|") => {
+                if self.state == Object && token.text != "{|" {
+                    missing = Some(self.last_end);
+                    self.state = Start;
+                }
+                self.depth = 1;
+            }
+            _ if self.state == Directive || self.state == SparqlDirective => {
+                if token.kind == TokenKind::IriRef {
+                    if self.state == Directive {
+                        self.state = Object; // expecting a dot
+                    } else {
+                        self.state = Start;
+                    }
+                }
+            }
+            _ => {
+                if self.state == Object {
+                    missing = Some(self.last_end);
+                    self.state = Start;
+                }
+                self.advance();
+            }
+        }
+        self.last_end = token.span.end;
+        missing
+    }
+
+    /// Return the position of a missing dot at the end of the document, if any.
+    fn finish(&self) -> Option<usize> {
+        (!self.disabled && self.depth == 0 && self.state == State::Object).then_some(self.last_end)
+    }
+
+    /// Advance the state after a complete term
+    fn advance(&mut self) {
+        use State::*;
+        self.state = match self.state {
+            Start => Subject,
+            Subject => Predicate,
+            Predicate | Object => Object,
+            other => other,
+        };
+    }
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::turtle;
+    use sophia_api::source::TripleSource;
+    use sophia_api::term::SimpleTerm;
+
+    fn kinds(ttl: &str) -> Vec<RepairHintKind> {
+        repair_hints(ttl).into_iter().map(|h| h.kind).collect()
+    }
+
+    #[test]
+    fn valid_turtle() {
+        let ttl = r#"
+            @prefix : <http://example.org/ns/>.
+            PREFIX xsd: <http://www.w3.org/2001/XMLSchema#>
+            :alice a :Person; :name "Alice"@en, "Alicia"; :age "42"^^xsd:integer;
+                :knows [ :name "Bob" ], _:c;
+                :likes (1 2.5 :x) .
+            [ :p :o ] .
+            [] :p :o .
+            << :a :b :c >> :d :e {| :f :g |} .
+            :s :p """long
+            string""".
+        "#;
+        assert_eq!(kinds(ttl), vec![]);
+    }
+
+    #[test]
+    fn unterminated_string() {
+        let ttl = "PREFIX : <tag:>\n:s :p \"abc ;\n  :q '''x.";
+        let hints = repair_hints(ttl);
+        assert_eq!(hints.len(), 2);
+        assert_eq!(
+            hints[0].kind,
+            RepairHintKind::UnterminatedString {
+                delimiter: "\"".into()
+            }
+        );
+        assert_eq!(hints[0].fix(), Some((28..28, "\"".into())));
+        assert_eq!(
+            hints[1].kind,
+            RepairHintKind::UnterminatedString {
+                delimiter: "'''".into()
+            }
+        );
+    }
+
+    #[test]
+    fn missing_dot() {
+        let ttl =
+            "PREFIX : <tag:>\n:s :p :o\n:t :q :r.\n:u :p [ :q :r ]\n@prefix ex: <tag:ex>\n:v :w :x";
+        let hints = repair_hints(ttl);
+        let positions: Vec<_> = hints.iter().map(|h| h.span.start).collect();
+        assert!(hints.iter().all(|h| h.kind == RepairHintKind::MissingDot));
+        assert_eq!(positions, vec![24, 50, 71, 80]);
+        assert_eq!(hints[0].fix(), Some((24..24, " .".into())));
+    }
+
+    #[test]
+    fn undefined_prefix() {
+        let ttl = "@prefix schema: <http://schema.org/>.\nfoo:s schema:p schem:o, :o.";
+        assert_eq!(
+            kinds(ttl),
+            vec![
+                RepairHintKind::UndefinedPrefix {
+                    prefix: "foo".into(),
+                    suggestion: None,
+                },
+                RepairHintKind::UndefinedPrefix {
+                    prefix: "schem".into(),
+                    suggestion: Some("schema".into()),
+                },
+                RepairHintKind::UndefinedPrefix {
+                    prefix: "".into(),
+                    suggestion: None,
+                },
+            ]
+        );
+        let hints = repair_hints(ttl);
+        assert_eq!(hints[1].fix(), Some((53..58, "schema".into())));
+        assert_eq!(
+            hints[1].to_string(),
+            "undefined prefix 'schem:' (did you mean 'schema:'?)"
+        );
+    }
+
+    #[test]
+    fn prefix_declared_too_late() {
+        let ttl = "ex:s ex:p ex:o.\n@prefix ex: <tag:>.";
+        let hints = repair_hints(ttl);
+        assert_eq!(hints.len(), 3);
+        assert_eq!(
+            hints[0].kind,
+            RepairHintKind::UndefinedPrefix {
+                prefix: "ex".into(),
+                suggestion: Some("ex".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn for_error() {
+        let ttl = "PREFIX : <tag:>\n:s :p :o\n:t :q :r.\n:u :p :v.\n:w :p fo:o.";
+        let err = turtle::parse_str(ttl)
+            .collect_triples::<Vec<[SimpleTerm; 3]>>()
+            .unwrap_err()
+            .unwrap_source_error();
+        let hints = hints_for_error(ttl, &err);
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].kind, RepairHintKind::MissingDot);
+    }
+
+    #[test]
+    fn levenshtein() {
+        assert_eq!(super::levenshtein("", ""), 0);
+        assert_eq!(super::levenshtein("abc", ""), 3);
+        assert_eq!(super::levenshtein("foaf", "fof"), 1);
+        assert_eq!(super::levenshtein("kitten", "sitting"), 3);
+    }
+}
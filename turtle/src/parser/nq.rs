@@ -1,6 +1,7 @@
 //! Adapter for the [N-Quads] parser from [RIO](https://github.com/Tpt/rio/blob/master/turtle/src/nquads.rs)
 //!
 //! [N-Quads]: https://www.w3.org/TR/n-quads/
+use super::borrowing::BorrowingQuads;
 use rio_turtle::NQuadsParser as RioNQParser;
 use sophia_api::parser::QuadParser;
 use sophia_rio::parser::*;
@@ -10,6 +11,15 @@ use std::io::BufRead;
 #[derive(Clone, Debug, Default)]
 pub struct NQuadsParser {}
 
+impl NQuadsParser {
+    /// Parse the N-Quads document `txt`,
+    /// producing quads whose terms borrow their text from `txt`
+    /// (see [`borrowing`](super::borrowing) for more details).
+    pub fn parse_borrowed<'a>(&self, txt: &'a str) -> BorrowingQuads<'a> {
+        BorrowingQuads::new(txt)
+    }
+}
+
 impl<B: BufRead> QuadParser<B> for NQuadsParser {
     type Source = StrictRioQuadSource<RioNQParser<B>>;
     fn parse(&self, data: B) -> Self::Source {
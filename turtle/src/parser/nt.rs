@@ -1,6 +1,7 @@
 //! Adapter for the [N-Triples] parser from [RIO](https://github.com/Tpt/rio/blob/master/turtle/src/ntriples.rs)
 //!
 //! [N-Triples]: https://www.w3.org/TR/n-triples/
+use super::borrowing::BorrowingTriples;
 use rio_turtle::NTriplesParser as RioNTParser;
 use sophia_api::parser::TripleParser;
 use sophia_rio::parser::*;
@@ -10,6 +11,15 @@ use std::io::BufRead;
 #[derive(Clone, Debug, Default)]
 pub struct NTriplesParser {}
 
+impl NTriplesParser {
+    /// Parse the N-Triples document `txt`,
+    /// producing triples whose terms borrow their text from `txt`
+    /// (see [`borrowing`](super::borrowing) for more details).
+    pub fn parse_borrowed<'a>(&self, txt: &'a str) -> BorrowingTriples<'a> {
+        BorrowingTriples::new(txt)
+    }
+}
+
 impl<B: BufRead> TripleParser<B> for NTriplesParser {
     type Source = StrictRioTripleSource<RioNTParser<B>>;
     fn parse(&self, data: B) -> Self::Source {
@@ -1,7 +1,13 @@
 //! Adapter for the TriG parser from [RIO](https://github.com/Tpt/rio/blob/master/turtle/src/turtle.rs)
 
+use crate::document::{StatementKind, TurtleDocument};
+use crate::lexer::TokenKind;
 use rio_turtle::TriGParser as RioTriGParser;
+use rio_turtle::TurtleError;
 use sophia_api::parser::QuadParser;
+use sophia_api::quad::Quad;
+use sophia_api::source::QuadSource;
+use sophia_api::term::{SimpleTerm, Term};
 use sophia_iri::Iri;
 use sophia_rio::parser::*;
 use std::io::BufRead;
@@ -26,6 +32,67 @@ impl<B: BufRead> QuadParser<B> for TriGParser {
     }
 }
 
+impl TriGParser {
+    /// Return the names of the graphs explicitly declared in `txt`
+    /// (with or without the `GRAPH` keyword), in the order of their first declaration.
+    ///
+    /// Contrarily to the quads produced by [`QuadParser::parse`],
+    /// this includes the names of empty graphs (e.g. `GRAPH <tag:g> {}`),
+    /// which can then be kept in a
+    /// [`DeclaredGraphsDataset`](sophia_api::dataset::declared::DeclaredGraphsDataset).
+    /// Graphs named by an anonymous blank node (`[] { ... }`) are ignored.
+    ///
+    /// NB: this method parses the whole of `txt`.
+    pub fn declared_graphs(&self, txt: &str) -> Result<Vec<SimpleTerm<'static>>, TurtleError> {
+        // insert a marker triple at the start of every graph block,
+        // and let the parser resolve graph names
+        let doc = TurtleDocument::new(txt);
+        let mut marked = String::with_capacity(txt.len());
+        let mut copied = 0;
+        for st in doc.statements() {
+            if st.kind != StatementKind::Triples {
+                continue;
+            }
+            let mut tokens = st
+                .tokens
+                .clone()
+                .map(|i| doc.token(i))
+                .filter(|t| !t.is_trivia())
+                .skip_while(|t| {
+                    t.kind == TokenKind::Keyword && t.text.eq_ignore_ascii_case("graph")
+                });
+            let (Some(name), Some(brace)) = (tokens.next(), tokens.next()) else {
+                continue;
+            };
+            if matches!(
+                name.kind,
+                TokenKind::IriRef | TokenKind::PrefixedName | TokenKind::BlankNodeLabel
+            ) && brace.kind == TokenKind::Punctuation
+                && brace.text == "{"
+            {
+                marked.push_str(&txt[copied..brace.span.end]);
+                marked.push_str(&format!(" {0} <{DECLARED}> {0} . ", name.text));
+                copied = brace.span.end;
+            }
+        }
+        marked.push_str(&txt[copied..]);
+
+        let mut names = vec![];
+        self.parse_str(&marked).for_each_quad(|q| {
+            if q.p().iri().is_some_and(|iri| iri.as_str() == DECLARED) {
+                let name: SimpleTerm<'static> = q.to_s().into_term();
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        })?;
+        Ok(names)
+    }
+}
+
+/// The predicate of the marker triples used by [`TriGParser::declared_graphs`].
+const DECLARED: &str = "urn:x-sophia:declared-graph";
+
 sophia_api::def_mod_functions_for_bufread_parser!(TriGParser, QuadParser);
 
 // ---------------------------------------------------------------------------------
@@ -111,4 +178,33 @@ mod test {
         assert_eq!(d.blank_nodes().collect::<HashSet<_>>().len(), 1);
         Ok(())
     }
+
+    #[test]
+    fn declared_graphs() -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let trig = r#"
+            PREFIX : <http://example.org/ns/>
+            BASE <http://localhost/>
+            :s :p :o.
+            { :s :p :o2 }
+            GRAPH :g1 {}
+            <g2> { :s :p :o. }
+            GRAPH _:g3 { }
+            [] { :s :p :o3 }
+            :g1 { :s :p "{" }
+        "#;
+        let p = TriGParser::default();
+        let names = p.declared_graphs(trig)?;
+        assert_eq!(names.len(), 3);
+        assert!(names[0] == Iri::new_unchecked("http://example.org/ns/g1"));
+        assert!(names[1] == Iri::new_unchecked("http://localhost/g2"));
+        assert!(names[2].is_blank_node());
+
+        let mut d = MyDataset::new();
+        p.parse_str(trig).add_to_dataset(&mut d)?;
+        assert_eq!(d.len(), 5);
+        assert!(d
+            .iter()
+            .all(|q| q.1.as_ref().is_none_or(|g| g != &names[2])));
+        Ok(())
+    }
 }
@@ -0,0 +1,179 @@
+//! Fast validation of [N-Triples] and [N-Quads] documents.
+//!
+//! The functions of this module check the syntax of every line of a document,
+//! without stopping at the first error, and without storing any triple or quad.
+//! They are therefore suitable for checking very large dumps before loading them.
+//!
+//! Each line is checked with [`parse_nt_statement`],
+//! which is also used by the [zero-copy parsers](super::borrowing),
+//! so a line is valid exactly when those parsers accept it.
+//!
+//! # Example
+//! ```
+//! use sophia_turtle::parser::validate::validate_ntriples;
+//!
+//! let nt = r#"<tag:s> <tag:p> "valid" .
+//! <tag:s> <tag:p> "unterminated .
+//! ## a comment
+//! <tag:s> "not a predicate" <tag:o> .
+//! "#;
+//! assert_eq!(validate_ntriples(nt.as_bytes())?, vec![2, 4]);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! [N-Triples]: https://www.w3.org/TR/n-triples/
+//! [N-Quads]: https://www.w3.org/TR/n-quads/
+use sophia_api::term::parse_nt_statement;
+use std::io::{self, BufRead};
+
+/// Check the syntax of the N-Triples document `read`,
+/// and return the (1-based) numbers of the lines that are invalid.
+///
+/// RDF-star quoted triples (`<< s p o >>`) are accepted,
+/// as they are by the [N-Triples parser](super::nt::NTriplesParser).
+///
+/// This only fails on I/O errors.
+pub fn validate_ntriples<R: BufRead>(read: R) -> io::Result<Vec<usize>> {
+    validate(read, false)
+}
+
+/// Check the syntax of the N-Quads document `read`,
+/// and return the (1-based) numbers of the lines that are invalid.
+///
+/// RDF-star quoted triples (`<< s p o >>`) are accepted,
+/// as they are by the [N-Quads parser](super::nq::NQuadsParser).
+///
+/// This only fails on I/O errors.
+pub fn validate_nquads<R: BufRead>(read: R) -> io::Result<Vec<usize>> {
+    validate(read, true)
+}
+
+fn validate<R: BufRead>(mut read: R, quads: bool) -> io::Result<Vec<usize>> {
+    let mut invalid = vec![];
+    let mut buf = Vec::with_capacity(1024);
+    let mut line = 0;
+    loop {
+        buf.clear();
+        if read.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        line += 1;
+        if !is_valid_line(&buf, quads) {
+            invalid.push(line);
+        }
+    }
+    Ok(invalid)
+}
+
+/// Check a line (possibly ending with `\n`).
+fn is_valid_line(line: &[u8], quads: bool) -> bool {
+    let Ok(txt) = std::str::from_utf8(line) else {
+        return false;
+    };
+    let txt = txt.strip_suffix('\n').unwrap_or(txt);
+    // a lone CR is also a line terminator in N-Triples
+    txt.split('\r')
+        .all(|stmt| parse_nt_statement(stmt, quads).is_ok())
+}
+
+// ---------------------------------------------------------------------------------
+//                                      tests
+// ---------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const VALID_NT: &[&str] = &[
+        "",
+        "   ",
+        "# comment",
+        "<http://ex.org/s> <http://ex.org/p> <http://ex.org/o> .",
+        "<tag:s><tag:p><tag:o>.",
+        "\t<tag:s> <tag:p> <tag:o> . # comment",
+        "_:b1 <tag:p> _:b.2.",
+        "_:1 <tag:p> _:été .",
+        "<tag:s> <tag:p> \"lit\" .",
+        "<tag:s> <tag:p> \"\" .",
+        "<tag:s> <tag:p> \"a \\\"b\\\" \\n \\u00e9 \\U0001F600\" .",
+        "<tag:s> <tag:p> \"chat\"@fr .",
+        "<tag:s> <tag:p> \"chat\"@en-GB-x-123 .",
+        "<tag:s> <tag:p> \"42\"^^<http://www.w3.org/2001/XMLSchema#integer> .",
+        "<tag:s#\\u00E9> <tag:p> \"é\" .",
+        "<< <tag:s> <tag:p> _:o >> <tag:p> << _:s <tag:p> \"o\" >> .",
+        "<<<tag:s><tag:p><tag:o>>><tag:p><tag:o>.",
+    ];
+
+    const INVALID_NT: &[&str] = &[
+        "<tag:s> <tag:p> <tag:o>",
+        "<tag:s> <tag:p> <tag:o> . .",
+        "<tag:s> <tag:p> <tag:o> . <tag:s> <tag:p> <tag:o> .",
+        "<tag:s> <tag:p> .",
+        "<s> <tag:p> <tag:o> .",
+        "<tag:s> <tag:p> <tag: o> .",
+        "<tag:s> <tag:p> <tag:o{}> .",
+        "<tag:s> <tag:p> <tag:\\n> .",
+        "<tag:s> <tag:p> <tag:\\u00e> .",
+        "\"s\" <tag:p> <tag:o> .",
+        "<tag:s> _:p <tag:o> .",
+        "<tag:s> <tag:p> \"unterminated .",
+        "<tag:s> <tag:p> \"bad \\x escape\" .",
+        "<tag:s> <tag:p> \"lit\"@ .",
+        "<tag:s> <tag:p> \"lit\"@en- .",
+        "<tag:s> <tag:p> \"lit\"@1en .",
+        "<tag:s> <tag:p> \"lit\"^<tag:d> .",
+        "<tag:s> <tag:p> \"lit\"^^\"d\" .",
+        "_: <tag:p> <tag:o> .",
+        "_:-b <tag:p> <tag:o> .",
+        "<< <tag:s> <tag:p> <tag:o> > <tag:p> <tag:o> .",
+        "<< <tag:s> <tag:p> >> <tag:p> <tag:o> .",
+        "<tag:s> <tag:p> <tag:o> <tag:g> .",
+        "PREFIX ex: <tag:>",
+        "ex:s ex:p ex:o .",
+    ];
+
+    #[test]
+    fn lines() {
+        for txt in VALID_NT {
+            assert!(is_valid_line(txt.as_bytes(), false), "{txt:?}");
+            assert!(is_valid_line(txt.as_bytes(), true), "{txt:?}");
+        }
+        for txt in INVALID_NT {
+            assert!(!is_valid_line(txt.as_bytes(), false), "{txt:?}");
+        }
+        assert!(!is_valid_line(b"<tag:s> <tag:p> \"\xFF\" .", false));
+    }
+
+    #[test]
+    fn quads() {
+        for txt in [
+            "<tag:s> <tag:p> <tag:o> <tag:g> .",
+            "<tag:s> <tag:p> <tag:o> _:g .",
+            "<tag:s> <tag:p> \"o\"@en<tag:g>.",
+        ] {
+            assert!(!is_valid_line(txt.as_bytes(), false), "{txt:?}");
+            assert!(is_valid_line(txt.as_bytes(), true), "{txt:?}");
+        }
+        for txt in [
+            "<tag:s> <tag:p> <tag:o> \"g\" .",
+            "<tag:s> <tag:p> <tag:o> << <tag:s> <tag:p> <tag:o> >> .",
+            "<tag:s> <tag:p> <tag:o> <tag:g> <tag:h> .",
+        ] {
+            assert!(!is_valid_line(txt.as_bytes(), true), "{txt:?}");
+        }
+    }
+
+    #[test]
+    fn document() -> io::Result<()> {
+        let nt = "<tag:s> <tag:p> <tag:o> .\r\n\
+                  <tag:s> <tag:p> \"o\"\r<tag:s> <tag:p> <tag:o> .\n\
+                  \n\
+                  <tag:s> <tag:p> <tag:o> .\r<tag:s> <tag:p> <tag:o> .\n\
+                  <tag:s> <tag:p> <tag:o> <tag:g> .\n\
+                  <tag:s> <tag:p> <tag:o> .";
+        assert_eq!(validate_ntriples(nt.as_bytes())?, vec![2, 5]);
+        assert_eq!(validate_nquads(nt.as_bytes())?, vec![2]);
+        assert_eq!(validate_ntriples(&b""[..])?, Vec::<usize>::new());
+        Ok(())
+    }
+}
@@ -69,6 +69,7 @@ struct Prettifier<'a, W> {
     indent: String,
     config: &'a TurtleConfig,
     labelled: BTreeSet<&'a SimpleTerm<'a>>,
+    labels: BTreeMap<&'a SimpleTerm<'a>, String>,
     subject_types: Vec<(
         GraphName<&'a SimpleTerm<'a>>,
         &'a SimpleTerm<'a>,
@@ -92,6 +93,11 @@ impl<'a, W: Write> Prettifier<'a, W> {
         config: &'a TurtleConfig,
     ) -> Self {
         let labelled = build_labelled(dataset);
+        let labels = if config.hash_bnode_labels {
+            build_hash_labels(dataset, &labelled)
+        } else {
+            BTreeMap::new()
+        };
         let mut subject_types = build_subject_types(dataset, &labelled);
         let lists = build_lists(dataset, &mut subject_types);
         let subject_types: Vec<_> = subject_types
@@ -112,6 +118,7 @@ impl<'a, W: Write> Prettifier<'a, W> {
             indent,
             config,
             labelled,
+            labels,
             subject_types,
             lists,
             graph_range,
@@ -170,6 +177,14 @@ impl<'a, W: Write> Prettifier<'a, W> {
     }
 
     fn write_tree(&mut self, root: &'a SimpleTerm<'a>) -> io::Result<()> {
+        let config = self.config;
+        for (_, title) in config.sections.iter().filter(|(s, _)| Term::eq(s, root)) {
+            self.write_newline()?;
+            self.write_comment(title)?;
+        }
+        for (_, comment) in config.comments.iter().filter(|(s, _)| Term::eq(s, root)) {
+            self.write_comment(comment)?;
+        }
         self.write_newline()?;
         self.write_term(root)?;
         self.write_properties(root)?;
@@ -323,7 +338,10 @@ impl<'a, W: Write> Prettifier<'a, W> {
             self.write_newline()?;
             self.write_bytes(b")")?;
         } else if self.labelled.contains(&bn) {
-            write!(self.write, "_:{}", bn.bnode_id().unwrap().as_str())?;
+            match self.labels.get(bn) {
+                Some(label) => write!(self.write, "_:{label}")?,
+                None => write!(self.write, "_:{}", bn.bnode_id().unwrap().as_str())?,
+            }
         } else if let Some(i) = self.find_st_index(bn) {
             let (_, s, st) = self.subject_types[i];
             match st {
@@ -355,9 +373,15 @@ impl<'a, W: Write> Prettifier<'a, W> {
         {
             self.write_bytes(value.as_bytes())?;
         } else {
-            self.write_bytes(b"\"")?;
-            super::nt::quoted_string(&mut self.write, value.as_bytes())?;
-            self.write_bytes(b"\"")?;
+            if self.config.long_strings && is_long_string(&value) {
+                self.write_bytes(b"\"\"\"")?;
+                long_quoted_string(&mut self.write, value.as_bytes())?;
+                self.write_bytes(b"\"\"\"")?;
+            } else {
+                self.write_bytes(b"\"")?;
+                super::nt::quoted_string(&mut self.write, value.as_bytes())?;
+                self.write_bytes(b"\"")?;
+            }
             if let Some(tag) = lit.language_tag() {
                 write!(self.write, "@{}", tag.as_str())?;
             } else if xsd::string != datatype {
@@ -368,6 +392,18 @@ impl<'a, W: Write> Prettifier<'a, W> {
         Ok(())
     }
 
+    /// Write each line of `text` as a comment, each on a new line
+    fn write_comment(&mut self, text: &str) -> io::Result<()> {
+        for line in text.lines() {
+            self.write_newline()?;
+            self.write_bytes(b"#")?;
+            if !line.is_empty() {
+                write!(self.write, " {line}")?;
+            }
+        }
+        Ok(())
+    }
+
     fn write_newline(&mut self) -> io::Result<()> {
         self.write_bytes(b"\n")?;
         self.write.write_all(self.indent.as_bytes())
@@ -411,6 +447,49 @@ impl<'a, W: Write> Prettifier<'a, W> {
     }
 }
 
+/// Minimum number of double quotes for a string to be written as a long string.
+const LONG_STRING_QUOTES: usize = 2;
+
+/// Whether `txt` is more readable with the long string syntax (`"""..."""`),
+/// i.e. if it contains a newline or several double quotes.
+fn is_long_string(txt: &str) -> bool {
+    txt.contains('\n') || txt.matches('"').count() >= LONG_STRING_QUOTES
+}
+
+/// Write `txt` as the content of a long string (`"""..."""`).
+///
+/// Newlines and double quotes are kept as is,
+/// except for double quotes that would otherwise close the string prematurely
+/// (i.e. a third consecutive double quote, or a double quote at the end of `txt`).
+fn long_quoted_string<W: Write>(mut w: W, txt: &[u8]) -> io::Result<()> {
+    let mut quotes = 0;
+    for (pos, chr) in txt.iter().enumerate() {
+        match *chr {
+            b'"' if quotes == 2 || pos + 1 == txt.len() => {
+                w.write_all(b"\\\"")?;
+                quotes = 0;
+            }
+            b'"' => {
+                w.write_all(b"\"")?;
+                quotes += 1;
+            }
+            b'\\' => {
+                w.write_all(b"\\\\")?;
+                quotes = 0;
+            }
+            b'\r' => {
+                w.write_all(b"\\r")?;
+                quotes = 0;
+            }
+            _ => {
+                w.write_all(&txt[pos..pos + 1])?;
+                quotes = 0;
+            }
+        }
+    }
+    Ok(())
+}
+
 /// blank nodes MUST be labelled (as opposed to described with square brackets) if
 /// - they are used in several named graphs, or
 /// - they are used several times as object, or
@@ -504,6 +583,134 @@ fn build_labelled<'a>(d: &'a PrettifiableDataset) -> BTreeSet<&'a SimpleTerm<'a>
         .collect()
 }
 
+/// Insert into `bnodes` the blank nodes of `t` (including those in quoted triples).
+fn collect_bnodes<'a>(t: &'a SimpleTerm<'a>, bnodes: &mut BTreeSet<&'a SimpleTerm<'a>>) {
+    match t {
+        SimpleTerm::BlankNode(_) => {
+            bnodes.insert(t);
+        }
+        SimpleTerm::Triple(spo) => {
+            for t in spo.iter() {
+                collect_bnodes(t, bnodes);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Number of refinement rounds in [`build_hash_labels`].
+const HASH_ROUNDS: usize = 3;
+
+/// Compute a label for each of the `labelled` blank nodes,
+/// based on a hash of its neighbourhood (up to [`HASH_ROUNDS`] hops away),
+/// ignoring the identifiers of all blank nodes.
+///
+/// Blank nodes with the same hash (because their neighbourhoods are similar, or by accident)
+/// are disambiguated with a numeric suffix, in the order of their original identifiers.
+fn build_hash_labels<'a>(
+    d: &'a PrettifiableDataset<'a>,
+    labelled: &BTreeSet<&'a SimpleTerm<'a>>,
+) -> BTreeMap<&'a SimpleTerm<'a>, String> {
+    // index the quads by the blank nodes that they contain
+    let mut incident = BTreeMap::<&'a SimpleTerm<'a>, Vec<&'a Gspo<SimpleTerm<'a>>>>::new();
+    for q in d.iter() {
+        let mut bnodes = BTreeSet::new();
+        for t in q.1.iter().chain(q.0.iter()) {
+            collect_bnodes(t, &mut bnodes);
+        }
+        for b in bnodes {
+            incident.entry(b).or_default().push(q);
+        }
+    }
+    // iteratively refine the hash of each blank node with the hashes of its neighbours
+    let mut hashes = BTreeMap::<&'a SimpleTerm<'a>, u64>::new();
+    for _ in 0..HASH_ROUNDS {
+        hashes = incident
+            .iter()
+            .map(|(b, quads)| {
+                let mut lines: Vec<String> = quads
+                    .iter()
+                    .map(|(g, spo)| {
+                        let mut line = String::new();
+                        for t in spo.iter().chain(g.iter()) {
+                            write_for_hash(t, b, &hashes, &mut line);
+                            line.push(' ');
+                        }
+                        line
+                    })
+                    .collect();
+                lines.sort_unstable();
+                (*b, fnv1a(lines.join("\n").as_bytes()))
+            })
+            .collect();
+    }
+    // build the labels
+    let mut by_label = BTreeMap::<String, Vec<&'a SimpleTerm<'a>>>::new();
+    for b in labelled {
+        let hash = hashes.get(b).copied().unwrap_or_default();
+        by_label
+            .entry(format!("b{:08x}", hash >> 32))
+            .or_default()
+            .push(*b);
+    }
+    let mut labels = BTreeMap::new();
+    for (label, bnodes) in by_label {
+        if bnodes.len() == 1 {
+            labels.insert(bnodes[0], label);
+        } else {
+            for (i, b) in bnodes.into_iter().enumerate() {
+                labels.insert(b, format!("{label}_{}", i + 1));
+            }
+        }
+    }
+    labels
+}
+
+/// Write `t` into `out`, as a part of the neighbourhood of blank node `focus`.
+fn write_for_hash<'a>(
+    t: &'a SimpleTerm<'a>,
+    focus: &SimpleTerm,
+    hashes: &BTreeMap<&'a SimpleTerm<'a>, u64>,
+    out: &mut String,
+) {
+    use std::fmt::Write;
+    match t {
+        SimpleTerm::BlankNode(_) if t == focus => out.push('*'),
+        SimpleTerm::BlankNode(_) => match hashes.get(&t) {
+            Some(hash) => write!(out, "_{hash:x}").unwrap(),
+            None => out.push('_'),
+        },
+        SimpleTerm::Iri(iri) => write!(out, "<{}>", iri.as_str()).unwrap(),
+        SimpleTerm::LiteralDatatype(lex, dt) => {
+            write!(out, "{}:{lex}^^<{}>", lex.len(), dt.as_str()).unwrap()
+        }
+        SimpleTerm::LiteralLanguage(lex, tag) => {
+            write!(out, "{}:{lex}@{}", lex.len(), tag.as_str()).unwrap()
+        }
+        SimpleTerm::Triple(spo) => {
+            out.push_str("<<");
+            for t in spo.iter() {
+                write_for_hash(t, focus, hashes, out);
+                out.push(' ');
+            }
+            out.push_str(">>");
+        }
+        SimpleTerm::Variable(var) => write!(out, "?{}", var.as_str()).unwrap(),
+    }
+}
+
+/// 64-bit FNV-1a hash,
+/// which is stable across platforms and Rust versions
+/// (contrarily to [`std::collections::hash_map::DefaultHasher`]).
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for b in bytes {
+        hash ^= u64::from(*b);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
 struct BnodeProfile<'a> {
     bad: bool,
     named_graphs: BTreeSet<GraphName<&'a SimpleTerm<'a>>>,
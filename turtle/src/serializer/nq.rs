@@ -9,19 +9,25 @@
 //! [`Write`]: https://doc.rust-lang.org/std/io/trait.Write.html
 //! [`BufWriter`]: https://doc.rust-lang.org/std/io/struct.BufWriter.html
 
-use super::nt::{write_term, write_triple};
+use super::nt::{warn_non_standard, write_term, write_triple};
 use sophia_api::quad::Quad;
 use sophia_api::serializer::*;
 use sophia_api::source::{QuadSource, StreamResult};
+use sophia_api::warning::{IgnoreWarnings, WarningSink};
 use std::io;
 
 /// N-Quads serializer configuration.
 pub type NqConfig = super::nt::NtConfig;
 
 /// N-Quads serializer.
-pub struct NqSerializer<W> {
+///
+/// Variables and relative IRI references are not allowed in N-Quads,
+/// but are written as is (as `?name` and `<iri>`, respectively).
+/// They can be reported to a [`WarningSink`] (see [`with_warning_sink`](NqSerializer::with_warning_sink)).
+pub struct NqSerializer<W, S = IgnoreWarnings> {
     config: NqConfig,
     write: W,
+    warnings: S,
 }
 
 impl<W> NqSerializer<W>
@@ -36,18 +42,43 @@ where
 
     /// Build a new N-Quads serializer writing to `write`, with the given config.
     pub fn new_with_config(write: W, config: NqConfig) -> NqSerializer<W> {
-        NqSerializer { config, write }
+        NqSerializer {
+            config,
+            write,
+            warnings: IgnoreWarnings,
+        }
     }
+}
 
+impl<W, S> NqSerializer<W, S>
+where
+    W: io::Write,
+    S: WarningSink,
+{
     /// Borrow this serializer's configuration.
     pub fn config(&self) -> &NqConfig {
         &self.config
     }
+
+    /// Transform this serializer so that it reports [`Warning`](sophia_api::warning::Warning)s to `sink`.
+    pub fn with_warning_sink<S2: WarningSink>(self, sink: S2) -> NqSerializer<W, S2> {
+        NqSerializer {
+            config: self.config,
+            write: self.write,
+            warnings: sink,
+        }
+    }
+
+    /// Borrow this serializer's [`WarningSink`].
+    pub fn warning_sink(&self) -> &S {
+        &self.warnings
+    }
 }
 
-impl<W> QuadSerializer for NqSerializer<W>
+impl<W, S> QuadSerializer for NqSerializer<W, S>
 where
     W: io::Write,
+    S: WarningSink,
 {
     type Error = io::Error;
 
@@ -64,6 +95,9 @@ where
         source
             .try_for_each_quad(|q| {
                 {
+                    for term in [q.s(), q.p(), q.o()].into_iter().chain(q.g()) {
+                        warn_non_standard(term, &mut self.warnings);
+                    }
                     let w = &mut self.write;
                     let (tr, gn) = q.spog();
                     write_triple(w, tr)?;
@@ -95,7 +129,7 @@ impl NqSerializer<Vec<u8>> {
     }
 }
 
-impl Stringifier for NqSerializer<Vec<u8>> {
+impl<S: WarningSink> Stringifier for NqSerializer<Vec<u8>, S> {
     fn as_utf8(&self) -> &[u8] {
         &self.write[..]
     }
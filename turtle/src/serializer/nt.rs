@@ -14,6 +14,7 @@ use sophia_api::serializer::*;
 use sophia_api::source::{StreamResult, TripleSource};
 use sophia_api::term::{Term, TermKind};
 use sophia_api::triple::Triple;
+use sophia_api::warning::{IgnoreWarnings, Warning, WarningKind, WarningSink};
 use std::io;
 
 /// N-Triples serializer configuration.
@@ -31,9 +32,14 @@ impl NtConfig {
 }
 
 /// N-Triples serializer.
-pub struct NtSerializer<W> {
+///
+/// Variables and relative IRI references are not allowed in N-Triples,
+/// but are written as is (as `?name` and `<iri>`, respectively).
+/// They can be reported to a [`WarningSink`] (see [`with_warning_sink`](NtSerializer::with_warning_sink)).
+pub struct NtSerializer<W, S = IgnoreWarnings> {
     config: NtConfig,
     write: W,
+    warnings: S,
 }
 
 impl<W> NtSerializer<W>
@@ -48,18 +54,43 @@ where
 
     /// Build a new N-Triples serializer writing to `write`, with the given config.
     pub fn new_with_config(write: W, config: NtConfig) -> NtSerializer<W> {
-        NtSerializer { config, write }
+        NtSerializer {
+            config,
+            write,
+            warnings: IgnoreWarnings,
+        }
     }
+}
 
+impl<W, S> NtSerializer<W, S>
+where
+    W: io::Write,
+    S: WarningSink,
+{
     /// Borrow this serializer's configuration.
     pub fn config(&self) -> &NtConfig {
         &self.config
     }
+
+    /// Transform this serializer so that it reports [`Warning`]s to `sink`.
+    pub fn with_warning_sink<S2: WarningSink>(self, sink: S2) -> NtSerializer<W, S2> {
+        NtSerializer {
+            config: self.config,
+            write: self.write,
+            warnings: sink,
+        }
+    }
+
+    /// Borrow this serializer's [`WarningSink`].
+    pub fn warning_sink(&self) -> &S {
+        &self.warnings
+    }
 }
 
-impl<W> TripleSerializer for NtSerializer<W>
+impl<W, S> TripleSerializer for NtSerializer<W, S>
 where
     W: io::Write,
+    S: WarningSink,
 {
     type Error = io::Error;
 
@@ -76,6 +107,9 @@ where
         source
             .try_for_each_triple(|t| {
                 {
+                    for term in [t.s(), t.p(), t.o()] {
+                        warn_non_standard(term, &mut self.warnings);
+                    }
                     let w = &mut self.write;
                     write_triple(w, t)?;
                     w.write_all(b".\n")
@@ -99,7 +133,7 @@ impl NtSerializer<Vec<u8>> {
     }
 }
 
-impl Stringifier for NtSerializer<Vec<u8>> {
+impl<S: WarningSink> Stringifier for NtSerializer<Vec<u8>, S> {
     fn as_utf8(&self) -> &[u8] {
         &self.write[..]
     }
@@ -169,6 +203,37 @@ where
     Ok(())
 }
 
+/// Report to `sink` the parts of `t` that are not allowed in N-Triples or N-Quads
+/// (variables and relative IRI references).
+pub(crate) fn warn_non_standard<T, S>(t: T, sink: &mut S)
+where
+    T: Term,
+    S: WarningSink,
+{
+    use TermKind::*;
+    match t.kind() {
+        Iri => {
+            let iri = t.iri().unwrap();
+            if iri.scheme().is_none() {
+                sink.warn(Warning::new(
+                    WarningKind::RelativeIri,
+                    format!("relative IRI reference <{}> written as is", iri.as_str()),
+                ));
+            }
+        }
+        Triple => {
+            for t in t.to_triple().unwrap() {
+                warn_non_standard(t, sink);
+            }
+        }
+        Variable => sink.warn(Warning::new(
+            WarningKind::NonStandard,
+            format!("variable ?{} written as is", t.variable().unwrap().as_str()),
+        )),
+        BlankNode | Literal => (),
+    }
+}
+
 pub(crate) fn quoted_string<W: io::Write>(w: &mut W, txt: &[u8]) -> io::Result<()> {
     let mut cut = txt.len();
     let mut cutchar = b'\0';
@@ -214,7 +279,7 @@ pub(crate) mod test {
     use super::*;
     use sophia_api::graph::MutableGraph;
     use sophia_api::ns::*;
-    use sophia_api::term::{BnodeId, LanguageTag, SimpleTerm, VarName};
+    use sophia_api::term::{BnodeId, IriRef, LanguageTag, SimpleTerm, VarName};
     use sophia_iri::Iri;
 
     #[test]
@@ -271,4 +336,52 @@ _:me <http://example.org/message> "hello\nworld"@en.
         );
         Ok(())
     }
+
+    proptest::proptest! {
+        #[test]
+        fn roundtrip(g in sophia_api::testing::graph()) {
+            let nt = NtSerializer::new_stringifier()
+                .serialize_graph(&g)
+                .unwrap()
+                .to_string();
+            let parsed: Vec<[SimpleTerm; 3]> = crate::parser::nt::parse_str(&nt)
+                .collect_triples()
+                .unwrap();
+            proptest::prop_assert!(
+                sophia_isomorphism::isomorphic_graphs(&g, &parsed).unwrap(),
+                "{}",
+                nt
+            );
+        }
+    }
+
+    #[test]
+    fn warnings() {
+        let abs: SimpleTerm = IriRef::new_unchecked("tag:abs").into_term();
+        let rel: SimpleTerm = IriRef::new_unchecked("rel").into_term();
+        let var: SimpleTerm = VarName::new_unchecked("x").into_term();
+        let quoted = SimpleTerm::Triple(Box::new([abs.clone(), abs.clone(), rel]));
+        let g = vec![[abs.clone(), abs.clone(), abs.clone()], [quoted, abs, var]];
+
+        let mut warnings = vec![];
+        let s = NtSerializer::new_stringifier()
+            .with_warning_sink(&mut warnings)
+            .serialize_graph(&g)
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            &s,
+            "<tag:abs> <tag:abs> <tag:abs>.\n<<<tag:abs> <tag:abs> <rel>>> <tag:abs> ?x.\n"
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                Warning::new(
+                    WarningKind::RelativeIri,
+                    "relative IRI reference <rel> written as is"
+                ),
+                Warning::new(WarningKind::NonStandard, "variable ?x written as is"),
+            ]
+        );
+    }
 }
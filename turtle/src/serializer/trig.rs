@@ -10,10 +10,12 @@
 //! [`BufWriter`]: https://doc.rust-lang.org/std/io/struct.BufWriter.html
 
 use rio_turtle::TriGFormatter;
+use sophia_api::dataset::declared::DeclaredGraphsDataset;
+use sophia_api::dataset::Dataset;
 use sophia_api::quad::Quad;
 use sophia_api::serializer::{QuadSerializer, Stringifier};
 use sophia_api::source::{QuadSource, SinkError, SourceError, StreamResult};
-use sophia_api::term::Term;
+use sophia_api::term::{SimpleTerm, Term};
 use sophia_rio::serializer::rio_format_quads;
 use std::io;
 
@@ -47,6 +49,29 @@ where
     pub fn config(&self) -> &TrigConfig {
         &self.config
     }
+
+    /// Serialize `dataset`, followed by an empty block (`<g> {}`)
+    /// for each of its declared graphs containing no quad,
+    /// so that they are not lost when the output is parsed back
+    /// (see [`TriGParser::declared_graphs`](crate::parser::trig::TriGParser::declared_graphs)).
+    pub fn serialize_declared_dataset<D>(
+        &mut self,
+        dataset: &DeclaredGraphsDataset<D>,
+    ) -> StreamResult<&mut Self, D::Error, io::Error>
+    where
+        D: Dataset,
+    {
+        self.serialize_dataset(dataset)?;
+        for g in dataset.empty_graphs().map_err(SourceError)? {
+            match g {
+                SimpleTerm::Iri(iri) => writeln!(self.write, "<{}> {{}}", iri.as_str()),
+                SimpleTerm::BlankNode(bnid) => writeln!(self.write, "_:{} {{}}", bnid.as_str()),
+                _ => Ok(()), // not a valid graph name
+            }
+            .map_err(SinkError)?;
+        }
+        Ok(self)
+    }
 }
 
 impl<W> QuadSerializer for TrigSerializer<W>
@@ -110,6 +135,7 @@ pub(crate) mod test {
     use std::error::Error;
 
     use super::*;
+    use sophia_api::parser::QuadParser;
     use sophia_api::term::SimpleTerm;
 
     use sophia_api::{dataset::Dataset, quad::Spog};
@@ -214,4 +240,37 @@ pub(crate) mod test {
         }
         Ok(())
     }
+
+    #[test]
+    fn roundtrip_declared_graphs() -> Result<(), Box<dyn Error>> {
+        let trig = r#"
+            PREFIX : <http://example.org/ns/>
+            :s :p :o.
+            GRAPH :g1 { :s :p :o }
+            GRAPH :g2 {}
+            GRAPH _:g3 {}
+        "#;
+        for pretty in [false, true] {
+            let parser = crate::parser::trig::TriGParser::default();
+            let mut d1 = DeclaredGraphsDataset::new(Vec::<Spog<SimpleTerm>>::new());
+            parser.parse_str(trig).add_to_dataset(&mut d1)?;
+            for g in parser.declared_graphs(trig)? {
+                d1.declare_graph(g);
+            }
+            assert_eq!(d1.empty_graphs()?.len(), 2);
+
+            let config = TrigConfig::new().with_pretty(pretty);
+            let out = TrigSerializer::new_stringifier_with_config(config)
+                .serialize_declared_dataset(&d1)?
+                .to_string();
+            println!("{}", &out);
+
+            let names = parser.declared_graphs(&out)?;
+            assert_eq!(names.len(), 3);
+            assert_eq!(names.iter().filter(|g| g.is_blank_node()).count(), 1);
+            let d2: Vec<Spog<SimpleTerm>> = parser.parse_str(&out).collect_quads()?;
+            assert!(isomorphic_datasets(d1.dataset(), &d2)?);
+        }
+        Ok(())
+    }
 }
@@ -27,6 +27,10 @@ pub struct TurtleConfig {
     pub(super) pretty: bool,
     pub(super) prefix_map: Vec<PrefixMapPair>,
     pub(super) indentation: String,
+    pub(super) comments: Vec<(SimpleTerm<'static>, String)>,
+    pub(super) sections: Vec<(SimpleTerm<'static>, String)>,
+    pub(super) hash_bnode_labels: bool,
+    pub(super) long_strings: bool,
 }
 
 impl TurtleConfig {
@@ -59,6 +63,52 @@ impl TurtleConfig {
         &self.indentation
     }
 
+    /// Comments attached to subjects, written as `#` lines just before their description.
+    /// (defaults to none)
+    ///
+    /// NB: only used if [`pretty`][`TurtleConfig::pretty`] is `true`,
+    /// and only for subjects that are described at the top-level
+    /// (i.e. not nested in square brackets or collections).
+    pub fn comments(&self) -> &[(SimpleTerm<'static>, String)] {
+        &self.comments
+    }
+
+    /// Section headers, written as `#` lines (preceded by an empty line)
+    /// before the description of the subject opening the section.
+    /// (defaults to none)
+    ///
+    /// NB: only used if [`pretty`][`TurtleConfig::pretty`] is `true`,
+    /// and only for subjects that are described at the top-level
+    /// (i.e. not nested in square brackets or collections).
+    pub fn sections(&self) -> &[(SimpleTerm<'static>, String)] {
+        &self.sections
+    }
+
+    /// Should blank nodes that require a label be labelled with a short hash
+    /// of their neighbourhood, rather than with their original identifier.
+    /// (defaults to false)
+    ///
+    /// This makes the labels independent of the order in which blank nodes were created,
+    /// so that unrelated changes in a graph do not change the labels of all its blank nodes
+    /// (which matters, e.g., when the output is under version control).
+    ///
+    /// NB: only used if [`pretty`][`TurtleConfig::pretty`] is `true`.
+    pub fn hash_bnode_labels(&self) -> bool {
+        self.hash_bnode_labels
+    }
+
+    /// Should string literals containing newlines or several double quotes
+    /// be written with the long string syntax (`"""..."""`),
+    /// rather than escaping them on a single line.
+    /// (defaults to true)
+    ///
+    /// This greatly improves the readability of, e.g., multi-line descriptions in ontologies.
+    ///
+    /// NB: only used if [`pretty`][`TurtleConfig::pretty`] is `true`.
+    pub fn long_strings(&self) -> bool {
+        self.long_strings
+    }
+
     /// Build a new default [`TurtleConfig`].
     pub fn new() -> Self {
         let pretty = false;
@@ -68,6 +118,10 @@ impl TurtleConfig {
             pretty,
             prefix_map,
             indentation,
+            comments: vec![],
+            sections: vec![],
+            hash_bnode_labels: false,
+            long_strings: true,
         }
     }
 
@@ -100,6 +154,38 @@ impl TurtleConfig {
         self
     }
 
+    /// Transform a [`TurtleConfig`] by attaching a [comment][`TurtleConfig::comments`] to `subject`.
+    ///
+    /// `comment` may span several lines, each of them will be prefixed with `#`.
+    pub fn with_comment<T: Term, C: ToString>(mut self, subject: T, comment: C) -> Self {
+        self.comments
+            .push((subject.into_term(), comment.to_string()));
+        self
+    }
+
+    /// Transform a [`TurtleConfig`] by starting a new [section][`TurtleConfig::sections`]
+    /// with the given `title` before `subject`.
+    ///
+    /// `title` may span several lines, each of them will be prefixed with `#`.
+    pub fn with_section<T: Term, C: ToString>(mut self, subject: T, title: C) -> Self {
+        self.sections.push((subject.into_term(), title.to_string()));
+        self
+    }
+
+    /// Transform a [`TurtleConfig`] by setting the [`hash_bnode_labels`][`TurtleConfig::hash_bnode_labels`] flag.
+    pub fn with_hash_bnode_labels(mut self, b: bool) -> Self {
+        self.hash_bnode_labels = b;
+        self
+    }
+
+    /// Transform a [`TurtleConfig`] by setting the [`long_strings`][`TurtleConfig::long_strings`] flag.
+    ///
+    /// Setting it to `false` forces all string literals to be written on a single line.
+    pub fn with_long_strings(mut self, b: bool) -> Self {
+        self.long_strings = b;
+        self
+    }
+
     /// Return the prefix map that is used when none is provided
     pub fn default_prefix_map() -> Vec<PrefixMapPair> {
         vec![
@@ -266,6 +352,34 @@ pub(crate) mod test {
         Ok(())
     }
 
+    #[test]
+    fn comments_and_sections() -> Result<(), Box<dyn Error>> {
+        let ttl = r#"
+            PREFIX : <http://example.org/ns/>
+            :alice a :Person; :knows [ :name "Bob" ].
+            :Person a :Class.
+        "#;
+        let g: Vec<[SimpleTerm; 3]> = crate::parser::turtle::parse_str(ttl).collect_triples()?;
+        let alice = Iri::new_unchecked("http://example.org/ns/alice");
+        let person = Iri::new_unchecked("http://example.org/ns/Person");
+        let config = TurtleConfig::new()
+            .with_pretty(true)
+            .with_section(person, "Classes")
+            .with_comment(person, "A human being")
+            .with_comment(alice, "first line\nsecond line");
+        let pretty = TurtleSerializer::new_stringifier_with_config(config)
+            .serialize_triples(g.triples())?
+            .to_string();
+        println!("{}", &pretty);
+        assert!(pretty.contains("\n# first line\n# second line\n<http://example.org/ns/alice>"));
+        assert!(pretty.contains("\n\n# Classes\n# A human being\n<http://example.org/ns/Person>"));
+
+        let g2: Vec<[SimpleTerm; 3]> =
+            crate::parser::turtle::parse_str(&pretty).collect_triples()?;
+        assert!(isomorphic_graphs(&g, &g2)?);
+        Ok(())
+    }
+
     #[test]
     fn roundtrip_pretty() -> Result<(), Box<dyn Error>> {
         for ttl in TESTS {
@@ -301,4 +415,75 @@ pub(crate) mod test {
         }
         Ok(())
     }
+
+    #[test]
+    fn hash_bnode_labels() -> Result<(), Box<dyn Error>> {
+        let ttl1 = r#"
+            <tag:a> <tag:p> _:x. <tag:b> <tag:p> _:x. _:x <tag:name> "x".
+            <tag:a> <tag:p> _:y. <tag:b> <tag:p> _:y. _:y <tag:name> "y".
+        "#;
+        let ttl2 = r#"
+            <tag:b> <tag:p> _:n2. <tag:a> <tag:p> _:n2. _:n2 <tag:name> "y".
+            <tag:b> <tag:p> _:n1. <tag:a> <tag:p> _:n1. _:n1 <tag:name> "x".
+            <tag:c> <tag:p> [ <tag:name> "z" ].
+        "#;
+        let config = TurtleConfig::new()
+            .with_pretty(true)
+            .with_hash_bnode_labels(true);
+        let mut labels = vec![];
+        for ttl in [ttl1, ttl2] {
+            let g1: Vec<[SimpleTerm; 3]> =
+                crate::parser::turtle::parse_str(ttl).collect_triples()?;
+            let pretty = TurtleSerializer::new_stringifier_with_config(config.clone())
+                .serialize_triples(g1.triples())?
+                .to_string();
+            println!("{}", &pretty);
+            let mut found: Vec<_> = pretty
+                .split_whitespace()
+                .filter(|w| w.starts_with("_:"))
+                .map(|w| w.trim_end_matches(['.', ';', ',']).to_string())
+                .collect();
+            found.sort();
+            found.dedup();
+            assert_eq!(found.len(), 2);
+            assert!(found.iter().all(|l| l.starts_with("_:b")));
+            labels.push(found);
+
+            let g2: Vec<[SimpleTerm; 3]> =
+                crate::parser::turtle::parse_str(&pretty).collect_triples()?;
+            assert!(isomorphic_graphs(&g1, &g2)?);
+        }
+        assert_eq!(labels[0], labels[1]);
+        Ok(())
+    }
+
+    #[test]
+    fn long_strings() -> Result<(), Box<dyn Error>> {
+        let ttl = r#"
+            <tag:a> <tag:p>
+                "a\nmulti-line\r\nstring",
+                "a \"quoted\" and \"\"\"triple-quoted\"\"\" string"@en,
+                "ends with quotes\"\"",
+                "back\\slash\n",
+                "a \"single\" quote",
+                "plain".
+        "#;
+        let g1: Vec<[SimpleTerm; 3]> = crate::parser::turtle::parse_str(ttl).collect_triples()?;
+        for (long_strings, expected) in [(true, 5), (false, 0)] {
+            let config = TurtleConfig::new()
+                .with_pretty(true)
+                .with_long_strings(long_strings);
+            let pretty = TurtleSerializer::new_stringifier_with_config(config)
+                .serialize_triples(g1.triples())?
+                .to_string();
+            println!("{}", &pretty);
+            assert_eq!(pretty.matches("\"\"\"").count(), 2 * expected);
+            assert_eq!(pretty.contains("a\nmulti-line"), long_strings);
+
+            let g2: Vec<[SimpleTerm; 3]> =
+                crate::parser::turtle::parse_str(&pretty).collect_triples()?;
+            assert!(isomorphic_graphs(&g1, &g2)?);
+        }
+        Ok(())
+    }
 }
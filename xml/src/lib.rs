@@ -2,7 +2,8 @@
 //! an [RDF] and [Linked Data] toolkit in Rust.
 //!
 //! Parser and serializer for the [RDF/XML] concrete syntax,
-//! based on [`rio_xml`].
+//! based on [`rio_xml`],
+//! and support for the canonicalization of [XML literals](xml_literal).
 //!
 //! [Sophia]: https://docs.rs/sophia/latest/sophia/
 //! [RDF]: https://www.w3.org/TR/rdf-primer/
@@ -12,3 +13,4 @@
 
 pub mod parser;
 pub mod serializer;
+pub mod xml_literal;
@@ -0,0 +1,358 @@
+//! Support for the [`rdf:XMLLiteral`](sophia_api::ns::rdf::XMLLiteral) datatype.
+//!
+//! The value of an XML literal is an XML fragment,
+//! and many different lexical forms can represent the same fragment
+//! (e.g. `<a x="1" y="2"/>` and `<a y='2' x='1'></a>`).
+//! This module implements [Exclusive XML Canonicalization] (without comments)
+//! of such fragments, so that they can be [compared](xml_literal_eq)
+//! or [serialized](xml_literal) consistently.
+//!
+//! # Example
+//! ```
+//! # use sophia_api::ns::rdf;
+//! # use sophia_api::term::Term;
+//! # use sophia_xml::xml_literal::{canonicalize, xml_literal_eq};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let lex = r#"<p:a xmlns:p="tag:p" xmlns:q="tag:q" y='2' x="1"/><!-- c -->&#65;"#;
+//! assert_eq!(canonicalize(lex)?, r#"<p:a xmlns:p="tag:p" x="1" y="2"></p:a>A"#);
+//! assert!(xml_literal_eq(
+//!     lex * rdf::XMLLiteral,
+//!     r#"<p:a y="2" x="1" xmlns:p="tag:p"></p:a>A"# * rdf::XMLLiteral,
+//! ));
+//! # Ok(()) }
+//! ```
+//!
+//! [Exclusive XML Canonicalization]: https://www.w3.org/TR/xml-exc-c14n/
+use quick_xml::escape::unescape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use sophia_api::ns::rdf;
+use sophia_api::term::{SimpleTerm, Term};
+use std::collections::BTreeMap;
+
+const XML_NS: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// An error raised when canonicalizing an XML literal.
+#[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
+pub enum XmlLiteralError {
+    /// The lexical form is not a well-formed XML fragment
+    #[error("Ill-formed XML fragment: {0}")]
+    IllFormed(String),
+    /// The lexical form uses a namespace prefix that it does not declare
+    #[error("Undeclared namespace prefix {0:?}")]
+    UndeclaredPrefix(String),
+}
+
+fn ill_formed<E: std::fmt::Display>(err: E) -> XmlLiteralError {
+    XmlLiteralError::IllFormed(err.to_string())
+}
+
+/// Namespace bindings (the default namespace being bound to the empty prefix).
+type Bindings = BTreeMap<String, String>;
+
+/// Canonicalize the XML fragment `lexical_form`,
+/// as per [Exclusive XML Canonicalization] (without comments).
+///
+/// As the fragment is considered out of any context,
+/// all the namespace prefixes it uses must be declared in the fragment itself.
+///
+/// [Exclusive XML Canonicalization]: https://www.w3.org/TR/xml-exc-c14n/
+pub fn canonicalize(lexical_form: &str) -> Result<String, XmlLiteralError> {
+    // XML processors normalize line endings before parsing
+    let txt = lexical_form.replace("\r\n", "\n").replace('\r', "\n");
+    let mut reader = Reader::from_str(&txt);
+    reader.expand_empty_elements(true);
+    reader.check_end_names(true);
+    let mut out = String::with_capacity(txt.len());
+    // for each open element, the bindings in scope in the input and in the output
+    let mut stack: Vec<(Bindings, Bindings)> = vec![];
+    loop {
+        match reader.read_event().map_err(ill_formed)? {
+            Event::Start(e) => {
+                let (input, output) = stack.last().cloned().unwrap_or_default();
+                stack.push(write_start(&e, input, output, &mut out)?);
+            }
+            Event::End(e) => {
+                if stack.pop().is_none() {
+                    return Err(ill_formed("unexpected end tag"));
+                }
+                out.push_str("</");
+                out.push_str(std::str::from_utf8(e.name().as_ref()).map_err(ill_formed)?);
+                out.push('>');
+            }
+            Event::Empty(_) => unreachable!("empty elements are expanded"),
+            Event::Text(e) => escape_text(&e.unescape().map_err(ill_formed)?, &mut out),
+            Event::CData(e) => {
+                escape_text(std::str::from_utf8(&e).map_err(ill_formed)?, &mut out);
+            }
+            Event::PI(e) => {
+                let pi = std::str::from_utf8(&e).map_err(ill_formed)?;
+                let (target, data) = pi.split_once(char::is_whitespace).unwrap_or((pi, ""));
+                out.push_str("<?");
+                out.push_str(target);
+                let data = data.trim_start();
+                if !data.is_empty() {
+                    out.push(' ');
+                    out.push_str(data);
+                }
+                out.push_str("?>");
+            }
+            Event::Comment(_) | Event::Decl(_) => (),
+            Event::DocType(_) => return Err(ill_formed("DOCTYPE is not allowed in fragments")),
+            Event::Eof => break,
+        }
+    }
+    if !stack.is_empty() {
+        return Err(ill_formed("unclosed element"));
+    }
+    Ok(out)
+}
+
+/// Write the canonical start tag of `e`,
+/// given the `input` and `output` bindings of its parent,
+/// and return the `input` and `output` bindings of `e`.
+fn write_start(
+    e: &BytesStart,
+    mut input: Bindings,
+    mut output: Bindings,
+    out: &mut String,
+) -> Result<(Bindings, Bindings), XmlLiteralError> {
+    let name = std::str::from_utf8(e.name().as_ref())
+        .map_err(ill_formed)?
+        .to_string();
+    let mut attributes = vec![];
+    for attr in e.attributes() {
+        let attr = attr.map_err(ill_formed)?;
+        let key = std::str::from_utf8(attr.key.as_ref()).map_err(ill_formed)?;
+        let raw = std::str::from_utf8(&attr.value).map_err(ill_formed)?;
+        // attribute-value normalization, before character references are expanded
+        let value = unescape(&raw.replace(['\t', '\n'], " "))
+            .map_err(ill_formed)?
+            .to_string();
+        if key == "xmlns" {
+            input.insert(String::new(), value);
+        } else if let Some(prefix) = key.strip_prefix("xmlns:") {
+            input.insert(prefix.to_string(), value);
+        } else {
+            attributes.push((key.to_string(), value));
+        }
+    }
+
+    // render the namespaces that are visibly utilized, unless already in scope in the output
+    let mut utilized = vec![prefix(&name).unwrap_or("")];
+    utilized.extend(attributes.iter().filter_map(|(key, _)| prefix(key)));
+    let mut namespaces = vec![];
+    for p in utilized {
+        if p == "xml" {
+            continue;
+        }
+        let ns = match input.get(p) {
+            Some(ns) => ns.as_str(),
+            None if p.is_empty() => "",
+            None => return Err(XmlLiteralError::UndeclaredPrefix(p.into())),
+        };
+        // NB: an absent default namespace is equivalent to an empty one
+        if output.get(p).map(String::as_str).unwrap_or("") != ns {
+            output.insert(p.into(), ns.into());
+            namespaces.push(p.to_string());
+        }
+    }
+    namespaces.sort_unstable();
+
+    // attributes are sorted by namespace URI, then local name
+    let mut sorted = Vec::with_capacity(attributes.len());
+    for (key, value) in attributes {
+        let ns = match prefix(&key) {
+            None => "",
+            Some("xml") => XML_NS,
+            Some(p) => input[p].as_str(),
+        };
+        let local = key.rsplit(':').next().unwrap().to_string();
+        sorted.push((ns.to_string(), local, key, value));
+    }
+    sorted.sort_unstable();
+
+    out.push('<');
+    out.push_str(&name);
+    for p in namespaces {
+        out.push_str(if p.is_empty() { " xmlns" } else { " xmlns:" });
+        out.push_str(&p);
+        out.push_str("=\"");
+        escape_attribute(&output[&p], out);
+        out.push('"');
+    }
+    for (_, _, key, value) in sorted {
+        out.push(' ');
+        out.push_str(&key);
+        out.push_str("=\"");
+        escape_attribute(&value, out);
+        out.push('"');
+    }
+    out.push('>');
+    Ok((input, output))
+}
+
+/// The prefix of a qualified name, if any.
+fn prefix(qname: &str) -> Option<&str> {
+    qname.split_once(':').map(|(p, _)| p)
+}
+
+fn escape_text(txt: &str, out: &mut String) {
+    for c in txt.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\r' => out.push_str("&#xD;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+fn escape_attribute(txt: &str, out: &mut String) {
+    for c in txt.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' => out.push_str("&#x9;"),
+            '\n' => out.push_str("&#xA;"),
+            '\r' => out.push_str("&#xD;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// Build an `rdf:XMLLiteral` whose lexical form is the [canonical](canonicalize) form of `lexical_form`.
+pub fn xml_literal(lexical_form: &str) -> Result<SimpleTerm<'static>, XmlLiteralError> {
+    let canonical = canonicalize(lexical_form)?;
+    Ok((canonical.as_str() * rdf::XMLLiteral).into_term())
+}
+
+/// Compare two terms, considering that two `rdf:XMLLiteral`s are equal
+/// if their lexical forms have the same [canonical](canonicalize) form.
+///
+/// Other terms (including ill-formed XML literals) are compared with [`Term::eq`].
+pub fn xml_literal_eq<T1: Term, T2: Term>(t1: T1, t2: T2) -> bool {
+    match (canonical_form(&t1), canonical_form(&t2)) {
+        (Some(c1), Some(c2)) => c1 == c2,
+        _ => Term::eq(&t1, t2),
+    }
+}
+
+/// The canonical form of `t`, if it is a well-formed `rdf:XMLLiteral`.
+fn canonical_form<T: Term>(t: &T) -> Option<String> {
+    if !Term::eq(&t.datatype()?, rdf::XMLLiteral) {
+        return None;
+    }
+    canonicalize(&t.lexical_form()?).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn syntax_normalization() -> Result<(), XmlLiteralError> {
+        assert_eq!(canonicalize("")?, "");
+        assert_eq!(canonicalize("plain &amp; simple")?, "plain &amp; simple");
+        assert_eq!(canonicalize("<a/>")?, "<a></a>");
+        assert_eq!(
+            canonicalize("<a b='x' a=\"y\" />")?,
+            r#"<a a="y" b="x"></a>"#
+        );
+        assert_eq!(
+            canonicalize("<a>x<!-- comment -->&#x3E;<![CDATA[<&>]]></a>")?,
+            "<a>x&gt;&lt;&amp;&gt;</a>"
+        );
+        assert_eq!(
+            canonicalize("<?pi   some data ?><?pi?>")?,
+            "<?pi some data ?><?pi?>"
+        );
+        assert_eq!(canonicalize("a\r\nb\rc&#13;")?, "a\nb\nc&#xD;");
+        Ok(())
+    }
+
+    #[test]
+    fn attribute_values() -> Result<(), XmlLiteralError> {
+        assert_eq!(
+            canonicalize("<a b='\"&lt;&gt;' c=\"x\ty\nz&#9;&#10;\"/>")?,
+            r#"<a b="&quot;&lt;>" c="x y z&#x9;&#xA;"></a>"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn namespaces() -> Result<(), XmlLiteralError> {
+        // unused namespaces are dropped
+        assert_eq!(
+            canonicalize(r#"<a xmlns:p="tag:p" xmlns="tag:d"><b/></a>"#)?,
+            r#"<a xmlns="tag:d"><b></b></a>"#
+        );
+        // namespaces are declared where they are used
+        assert_eq!(
+            canonicalize(r#"<a xmlns:p="tag:p"><p:b/><c p:x="1"/></a>"#)?,
+            r#"<a><p:b xmlns:p="tag:p"></p:b><c xmlns:p="tag:p" p:x="1"></c></a>"#
+        );
+        // declarations are not repeated
+        assert_eq!(
+            canonicalize(r#"<p:a xmlns:p="tag:p"><p:b xmlns:p="tag:p"/></p:a>"#)?,
+            r#"<p:a xmlns:p="tag:p"><p:b></p:b></p:a>"#
+        );
+        // the default namespace is undeclared when necessary
+        assert_eq!(
+            canonicalize(r#"<a xmlns="tag:d"><b xmlns=""/></a>"#)?,
+            r#"<a xmlns="tag:d"><b xmlns=""></b></a>"#
+        );
+        assert_eq!(canonicalize(r#"<a xmlns=""/>"#)?, "<a></a>");
+        // namespaces are sorted by prefix, attributes by namespace then local name
+        assert_eq!(
+            canonicalize(
+                r#"<q:a xmlns:q="tag:1" xmlns:p="tag:2" p:x="1" q:y="2" z="3" xml:lang="en"/>"#
+            )?,
+            r#"<q:a xmlns:p="tag:2" xmlns:q="tag:1" z="3" xml:lang="en" q:y="2" p:x="1"></q:a>"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        for lex in [
+            "<a>",
+            "</a>",
+            "<a></b>",
+            "<a b='1' b='2'/>",
+            "&foo;",
+            "<!DOCTYPE a><a/>",
+        ] {
+            assert!(
+                matches!(canonicalize(lex), Err(XmlLiteralError::IllFormed(_))),
+                "{lex:?}"
+            );
+        }
+        assert_eq!(
+            canonicalize("<p:a/>"),
+            Err(XmlLiteralError::UndeclaredPrefix("p".into()))
+        );
+    }
+
+    #[test]
+    fn literal_eq() -> Result<(), XmlLiteralError> {
+        let lit = xml_literal("<a  b='1'/>")?;
+        assert_eq!(lit.lexical_form().unwrap(), r#"<a b="1"></a>"#);
+        assert!(Term::eq(&lit.datatype().unwrap(), rdf::XMLLiteral));
+        assert!(xml_literal_eq(&lit, "<a b=\"1\" />" * rdf::XMLLiteral));
+        assert!(!xml_literal_eq(&lit, "<a b=\"2\" />" * rdf::XMLLiteral));
+        // other literals are compared as usual
+        assert!(!xml_literal_eq("<a/>", "<a></a>"));
+        assert!(xml_literal_eq(
+            "<a/>" * rdf::XMLLiteral,
+            "<a/>" * rdf::XMLLiteral
+        ));
+        assert!(!xml_literal_eq(
+            "<a>" * rdf::XMLLiteral,
+            "<a></a>" * rdf::XMLLiteral
+        ));
+        Ok(())
+    }
+}